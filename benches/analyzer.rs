@@ -0,0 +1,180 @@
+//! Analyzer and optimizer benchmarks
+//!
+//! Run with `cargo bench` or `copt devtest bench`. Each benchmark runs on
+//! three prompts built from the test corpus: a single prompt, ~10 KB, and
+//! ~500 KB, so both per-call overhead and scaling on large inputs show up.
+//! The `startup` group runs the binary itself, the way editor integrations
+//! and pre-commit hooks call `copt --offline --analyze`.
+
+use std::hint::black_box;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use copt::{analyzer, optimizer, tui, utils};
+use criterion::{
+    criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+
+/// Corpus prompts, cycled to build the larger inputs
+const CORPUS: &[&str] = &[
+    include_str!("../tests/corpus/agentic/refactor_service.txt"),
+    include_str!("../tests/corpus/agentic/fix_failing_test.txt"),
+    include_str!("../tests/corpus/role_only/python_expert.txt"),
+    include_str!("../tests/corpus/role_only/customer_support.txt"),
+    include_str!("../tests/corpus/rag/research_summary.txt"),
+    include_str!("../tests/corpus/rag/policy_qa.txt"),
+    include_str!("../tests/corpus/frontend/react_dashboard.txt"),
+    include_str!("../tests/corpus/frontend/landing_page.txt"),
+    include_str!("../tests/corpus/already_optimized/sql_helper.txt"),
+    include_str!("../tests/corpus/already_optimized/code_reviewer.txt"),
+];
+
+/// Corpus prompts joined by blank lines until the text reaches `bytes`
+fn sized_prompt(bytes: usize) -> String {
+    let mut prompt = String::with_capacity(bytes + 1024);
+    for text in CORPUS.iter().cycle() {
+        if prompt.len() >= bytes {
+            break;
+        }
+        if !prompt.is_empty() {
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str(text.trim());
+    }
+    prompt
+}
+
+/// The benchmark inputs, labeled by size
+fn prompts() -> Vec<(&'static str, String)> {
+    vec![
+        ("small", CORPUS[0].to_string()),
+        ("10KB", sized_prompt(10 * 1024)),
+        ("500KB", sized_prompt(500 * 1024)),
+    ]
+}
+
+/// Fewer, flat samples for the large input, which takes seconds per call
+fn configure(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    size: &str,
+) {
+    if size == "500KB" {
+        group.sample_size(10);
+        group.sampling_mode(SamplingMode::Flat);
+        group.measurement_time(Duration::from_secs(60));
+    } else {
+        group.sampling_mode(SamplingMode::Auto);
+        group.sample_size(50);
+        group.measurement_time(Duration::from_secs(5));
+    }
+}
+
+fn bench_analyze(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analyze");
+    for (size, prompt) in prompts() {
+        configure(&mut group, size);
+        group.throughput(Throughput::Bytes(prompt.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &prompt, |b, prompt| {
+            b.iter(|| analyzer::analyze(black_box(prompt), None).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_optimize_static(c: &mut Criterion) {
+    let mut group = c.benchmark_group("optimize_static");
+    for (size, prompt) in prompts() {
+        configure(&mut group, size);
+        let issues = analyzer::analyze(&prompt, None).unwrap();
+        group.throughput(Throughput::Bytes(prompt.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &prompt, |b, prompt| {
+            b.iter(|| optimizer::optimize_static(black_box(prompt), &issues).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("diff");
+    for (size, prompt) in prompts() {
+        configure(&mut group, size);
+        let issues = analyzer::analyze(&prompt, None).unwrap();
+        let optimized = optimizer::optimize_static(&prompt, &issues).unwrap();
+        group.throughput(Throughput::Bytes(prompt.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &prompt, |b, prompt| {
+            b.iter(|| {
+                tui::diff::write_diff(&mut std::io::sink(), black_box(prompt), &optimized).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A one-line edit in the middle of the prompt, as an editor would send it
+fn bench_incremental(c: &mut Criterion) {
+    let mut group = c.benchmark_group("incremental");
+    for (size, prompt) in prompts() {
+        let middle = prompt.len() / 2;
+        let at = middle + prompt[middle..].find('\n').unwrap_or(0);
+        let edited = format!(
+            "{}\nCan you also check the tests?{}",
+            &prompt[..at],
+            &prompt[at..]
+        );
+        let analysis = analyzer::incremental::IncrementalAnalysis::new(&prompt, None).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &edited, |b, edited| {
+            b.iter_batched(
+                || analysis.clone(),
+                |mut analysis| analysis.update(black_box(edited)).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_count_tokens(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_tokens");
+    for (size, prompt) in prompts() {
+        group.throughput(Throughput::Bytes(prompt.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &prompt, |b, prompt| {
+            b.iter(|| utils::count_tokens(black_box(prompt)))
+        });
+    }
+    group.finish();
+}
+
+/// A whole `copt --offline --analyze` process: startup, config, analysis,
+/// and the JSON report
+fn bench_startup(c: &mut Criterion) {
+    // Empty config and data directories, so a configured rule pack or the
+    // metrics file of whoever runs the benchmark doesn't skew it
+    let home = std::env::temp_dir().join("copt-bench-startup");
+    let mut group = c.benchmark_group("startup");
+    group.sample_size(30);
+    group.bench_function("offline_analyze", |b| {
+        b.iter(|| {
+            let status = Command::new(env!("CARGO_BIN_EXE_copt"))
+                .args(["--offline", "--analyze", "--format", "json", CORPUS[0]])
+                .env("XDG_CONFIG_HOME", &home)
+                .env("XDG_DATA_HOME", &home)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_analyze,
+    bench_optimize_static,
+    bench_diff,
+    bench_incremental,
+    bench_count_tokens,
+    bench_startup
+);
+criterion_main!(benches);
@@ -0,0 +1,166 @@
+//! Acronym detection for the ALL CAPS emphasis rule (STY002)
+//!
+//! Capitalized domain terms ("SLA", "HIPAA", "KYC") are not emphasis and must
+//! survive the casing fix. A word counts as an acronym when it is built in,
+//! listed in `rules.acronyms`, or learned from the prompt itself: defined
+//! ("Service Level Agreement (SLA)", "KYC means…", "SLA: …") or used as a
+//! noun at least twice outside shouted runs of capitals.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::{LazyLock, OnceLock};
+
+/// Acronyms common enough to always accept
+const BUILTIN: &[&str] = &[
+    "AI", "API", "AWS", "CI", "CD", "CLI", "CPU", "CSS", "CSV", "DNS", "FAQ", "GCP", "GPU", "HTML",
+    "HTTP", "HTTPS", "ID", "IDE", "IP", "JSON", "JWT", "LLM", "PDF", "PR", "REST", "SDK", "SQL",
+    "SSH", "SSO", "TCP", "UI", "URL", "UX", "XML", "YAML",
+];
+
+/// Shouted words that are never acronyms, however often they appear
+const EMPHASIS: &[&str] = &[
+    "ABSOLUTELY",
+    "ALL",
+    "ALWAYS",
+    "ANY",
+    "AVOID",
+    "CAUTION",
+    "CERTAINLY",
+    "CRITICAL",
+    "CRUCIAL",
+    "DEFINITELY",
+    "DO",
+    "DONT",
+    "ENSURE",
+    "ESSENTIAL",
+    "EVER",
+    "IMPORTANT",
+    "MANDATORY",
+    "MUST",
+    "NEVER",
+    "NO",
+    "NOT",
+    "NOTE",
+    "ONLY",
+    "REMEMBER",
+    "REQUIRED",
+    "STOP",
+    "VERY",
+    "WARNING",
+];
+
+/// Acronyms from `rules.acronyms`
+static CONFIGURED: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Add acronyms from config for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(acronyms: Vec<String>) {
+    let _ = CONFIGURED.set(acronyms);
+}
+
+/// All acronyms to preserve in `prompt`: built-in, configured, and learned
+pub fn known_acronyms(prompt: &str) -> HashSet<String> {
+    let mut known: HashSet<String> = BUILTIN.iter().map(|a| a.to_string()).collect();
+    if let Some(configured) = CONFIGURED.get() {
+        known.extend(configured.iter().map(|a| a.trim().to_uppercase()));
+    }
+    known.extend(learn(prompt));
+    known
+}
+
+/// Acronyms the prompt defines or uses as nouns
+fn learn(prompt: &str) -> HashSet<String> {
+    static DEFINED: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+        [
+            // Service Level Agreement (SLA)
+            r"\(([A-Z][A-Z0-9]{1,7})s?\)",
+            // SLA (service level agreement)
+            r"\b([A-Z][A-Z0-9]{1,7})s?\s+\((?:[A-Za-z][a-z]+[\s-]*){2,}\)",
+            // KYC means / stands for / refers to
+            r"\b([A-Z][A-Z0-9]{1,7})s?\s+(?:means|stands for|refers to|is short for)\b",
+            // Glossary entries: "SLA: the response-time commitment"
+            r"(?m)^\s*(?:[-*]\s*)?\**([A-Z][A-Z0-9]{1,7})\**\s*[:=—–]\s+\S",
+        ]
+        .iter()
+        .map(|p| Regex::new(p).unwrap())
+        .collect()
+    });
+    static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9'’]+").unwrap());
+
+    let mut learned = HashSet::new();
+
+    for re in DEFINED.iter() {
+        for caps in re.captures_iter(prompt) {
+            learned.insert(caps[1].to_string());
+        }
+    }
+
+    // Repeated noun use: capitals with ordinary words on both sides, so
+    // "Escalate P1 tickets per the SLA" counts but "DO NOT SHARE" doesn't
+    let is_caps = |w: &str| w.len() >= 2 && w.chars().all(|c| c.is_ascii_uppercase());
+    let mut uses: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for line in prompt.lines() {
+        let words: Vec<&str> = WORD.find_iter(line).map(|m| m.as_str()).collect();
+        for (i, word) in words.iter().enumerate() {
+            let word = word
+                .strip_suffix('s')
+                .filter(|w| is_caps(w))
+                .unwrap_or(word);
+            if !is_caps(word) {
+                continue;
+            }
+            let shouted =
+                |j: Option<usize>| j.and_then(|j| words.get(j)).is_some_and(|w| is_caps(w));
+            if !shouted(i.checked_sub(1)) && !shouted(Some(i + 1)) {
+                *uses.entry(word).or_default() += 1;
+            }
+        }
+    }
+    learned.extend(
+        uses.into_iter()
+            .filter(|(_, count)| *count >= 2)
+            .map(|(word, _)| word.to_string()),
+    );
+
+    learned.retain(|word| !EMPHASIS.contains(&word.as_str()));
+    learned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learn_defined_acronyms() {
+        let prompt = "Follow the Service Level Agreement (SLA).\n\
+            HIPAA (health insurance portability rules) applies.\n\
+            KYC means know-your-customer checks.\n\
+            - AML: anti-money-laundering review";
+        let learned = learn(prompt);
+        for acronym in ["SLA", "HIPAA", "KYC", "AML"] {
+            assert!(learned.contains(acronym), "{} not learned", acronym);
+        }
+    }
+
+    #[test]
+    fn test_learn_repeated_nouns_not_emphasis() {
+        let prompt = "Escalate tickets that breach the SLA.\n\
+            Quote the SLA in every reply.\n\
+            DO NOT SHARE customer data. NEVER guess. NEVER apologize.";
+        let learned = learn(prompt);
+        assert!(learned.contains("SLA"));
+        assert!(!learned.contains("SHARE"));
+        assert!(!learned.contains("NEVER"));
+
+        // A single use is not enough to learn from
+        assert!(!learn("Check the SLA.").contains("SLA"));
+    }
+
+    #[test]
+    fn test_known_acronyms_include_builtin() {
+        let known = known_acronyms("Call the API.");
+        assert!(known.contains("API"));
+        assert!(!known.contains("CALL"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Per-file analysis cache
+//!
+//! `copt lint` over a large tree and pre-commit hooks analyze mostly the same
+//! prompts run after run. Results are stored under the user cache directory,
+//! one entry per key, where the key hashes the prompt's content together
+//! with everything else that changes what [`super::analyze`] reports: the
+//! rule set ([`super::ruleset::hash`]), the `--check` categories, configured
+//! acronyms and dictionary words, and which referenced files exist. An
+//! unchanged prompt is answered from disk; an edited one, or any change to
+//! the rules or config, misses and is analyzed again. The key also covers
+//! the copt executable itself, so a rebuild with changed rule logic never
+//! reuses results from before, even when the version number is the same.
+//!
+//! Entries unused for [`MAX_AGE`] are deleted, and beyond [`MAX_ENTRIES`]
+//! the least recently used go first; pruning runs once per process, the
+//! first time an entry is written.
+//!
+//! The cache is off until [`configure`] is called, so tests and library
+//! callers never touch the user's cache directory. It is best effort: an
+//! entry that can't be read or written is treated as a miss.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Once, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use super::{acronyms, language, references, ruleset, Issue};
+use crate::error::CoptError;
+
+/// Where entries live, set once per process
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+static HITS: AtomicUsize = AtomicUsize::new(0);
+static MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Entries not used for this long are deleted
+pub const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Most entries kept; about 1 KB each
+pub const MAX_ENTRIES: usize = 10_000;
+
+static PRUNED: Once = Once::new();
+
+/// Identifies the running build: the executable's size and modification
+/// time, so every `cargo build` gets fresh entries
+static BUILD: LazyLock<String> = LazyLock::new(|| {
+    std::env::current_exe()
+        .and_then(std::fs::metadata)
+        .map(|meta| {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos());
+            format!("{}:{}", meta.len(), modified)
+        })
+        .unwrap_or_default()
+});
+
+/// Cache analysis results in `dir` for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(dir: PathBuf) {
+    let _ = DIR.set(dir);
+}
+
+/// `analysis/` under the user cache directory
+pub fn default_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.cache_dir().join("analysis"))
+}
+
+/// Lookups answered from the cache and lookups that ran the analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Cache lookups so far in this process
+pub fn stats() -> Stats {
+    Stats {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+    }
+}
+
+/// [`super::analyze`], answered from the cache when `prompt` was analyzed
+/// under the same rules and settings before
+pub fn analyze(prompt: &str, check_categories: Option<&[String]>) -> Result<Vec<Issue>, CoptError> {
+    let Some(dir) = DIR.get() else {
+        return super::analyze(prompt, check_categories);
+    };
+    analyze_in(dir, prompt, check_categories)
+}
+
+fn analyze_in(
+    dir: &Path,
+    prompt: &str,
+    check_categories: Option<&[String]>,
+) -> Result<Vec<Issue>, CoptError> {
+    let entry = dir.join(format!("{}.json", key(prompt, check_categories)));
+
+    if let Some(issues) = read(&entry) {
+        // Recently used entries are the last to be pruned
+        let _ = std::fs::File::options()
+            .write(true)
+            .open(&entry)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        HITS.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!(issues = issues.len(), "analysis cache hit");
+        return Ok(issues);
+    }
+
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    let issues = super::analyze(prompt, check_categories)?;
+    if let Err(e) = write(&entry, &issues) {
+        tracing::debug!("Failed to write analysis cache entry: {}", e);
+    }
+    PRUNED.call_once(|| {
+        let removed = prune(dir, SystemTime::now());
+        tracing::debug!(removed, "analysis cache pruned");
+    });
+    Ok(issues)
+}
+
+/// Hex digest of the prompt and every setting that changes its analysis
+fn key(prompt: &str, check_categories: Option<&[String]>) -> String {
+    let mut hasher = Sha256::new();
+    let mut field = |label: &str, value: &str| {
+        hasher.update(label.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    };
+
+    field("prompt", prompt);
+    field("build", &BUILD);
+    field("ruleset", &ruleset::hash());
+    match check_categories {
+        Some(categories) => categories.iter().for_each(|c| field("check", c)),
+        None => field("check", "*"),
+    }
+    let mut known: Vec<String> = acronyms::known_acronyms(prompt).into_iter().collect();
+    known.sort();
+    for acronym in &known {
+        field("acronym", acronym);
+    }
+    for word in language::dictionary() {
+        field("dictionary", word);
+    }
+    // EXP007 depends on the files around the prompt, not just its text
+    for issue in references::analyze_references(prompt) {
+        field("dangling", &issue.message);
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Delete entries unused for [`MAX_AGE`], then the least recently used
+/// beyond [`MAX_ENTRIES`]; returns how many were deleted
+fn prune(dir: &Path, now: SystemTime) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut kept: Vec<(SystemTime, PathBuf)> = Vec::new();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let Ok(used) = entry.metadata().and_then(|m| m.modified()) else {
+            continue;
+        };
+        let stale = now.duration_since(used).is_ok_and(|age| age > MAX_AGE);
+        if stale && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        } else if !stale {
+            kept.push((used, entry.path()));
+        }
+    }
+
+    if kept.len() > MAX_ENTRIES {
+        kept.sort();
+        let excess = kept.len() - MAX_ENTRIES;
+        for (_, path) in kept.into_iter().take(excess) {
+            if std::fs::remove_file(path).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
+
+fn read(entry: &Path) -> Option<Vec<Issue>> {
+    let content = std::fs::read_to_string(entry).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Write through a temporary file so a concurrent run (two hooks at once)
+/// never reads half an entry
+fn write(entry: &Path, issues: &[Issue]) -> Result<()> {
+    let dir = entry.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    crate::utils::file::write_atomic(entry, &serde_json::to_string(issues)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_prompt_is_answered_from_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let prompt = "Please write some code maybe.";
+
+        let first = analyze_in(dir.path(), prompt, None).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let hits = stats().hits;
+        let second = analyze_in(dir.path(), prompt, None).unwrap();
+        assert!(stats().hits > hits);
+        assert_eq!(
+            first.iter().map(|i| &i.id).collect::<Vec<_>>(),
+            second.iter().map(|i| &i.id).collect::<Vec<_>>()
+        );
+
+        // A different prompt or --check selection is a different entry
+        analyze_in(dir.path(), "Write some code.", None).unwrap();
+        analyze_in(dir.path(), prompt, Some(&["style".to_string()])).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_prune_drops_stale_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        for (name, age_days) in [("fresh.json", 1), ("stale.json", 31)] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "[]").unwrap();
+            let used = now - Duration::from_secs(age_days * 24 * 60 * 60);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(used)
+                .unwrap();
+        }
+
+        assert_eq!(prune(dir.path(), now), 1);
+        assert!(dir.path().join("fresh.json").exists());
+        assert!(!dir.path().join("stale.json").exists());
+    }
+
+    #[test]
+    fn test_unreadable_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let prompt = "Summarize the report.";
+        let entry = dir.path().join(format!("{}.json", key(prompt, None)));
+        std::fs::write(&entry, "not json").unwrap();
+
+        let issues = analyze_in(dir.path(), prompt, None).unwrap();
+        assert_eq!(read(&entry).unwrap().len(), issues.len());
+    }
+}
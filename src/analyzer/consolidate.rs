@@ -0,0 +1,98 @@
+//! Related rules that report one underlying problem
+//!
+//! Some prompts trip several rules for the same thing: "NEVER do this!!!"
+//! is a negative instruction (STY001), shouting (STY002), and overtriggering
+//! language (STY004) at once. When two or more rules of a set fire, the
+//! report and the LLM issue summary show them as one grouped finding with
+//! the individual rules as sub-findings, so one habit doesn't read as three
+//! problems. The flat issue list, fixes, and the quality score are unchanged.
+
+use super::Issue;
+
+/// Rules that describe the same underlying problem
+#[derive(Debug)]
+pub struct RelatedRules {
+    /// Stable key, e.g. `tone` (JSON `group`)
+    pub key: &'static str,
+    /// What the grouped finding is called
+    pub title: &'static str,
+    pub rules: &'static [&'static str],
+}
+
+/// Every set of related rules; a rule belongs to at most one
+pub const RELATED: &[RelatedRules] = &[
+    RelatedRules {
+        key: "tone",
+        title: "Aggressive or negative tone",
+        rules: &["STY001", "STY002", "STY004"],
+    },
+    RelatedRules {
+        key: "output_spec",
+        title: "Output left unspecified",
+        rules: &["FMT001", "EXP006"],
+    },
+    RelatedRules {
+        key: "long_task",
+        title: "Long-running work without state or progress guidance",
+        rules: &["LHT001", "LHT002", "LHT003", "AGT003", "VRB002"],
+    },
+    RelatedRules {
+        key: "design",
+        title: "UI request without design guidance",
+        rules: &["FED001", "FED002", "FED003", "FED004", "FED005"],
+    },
+];
+
+/// The set `rule_id` belongs to, if any
+pub fn related_set(rule_id: &str) -> Option<&'static RelatedRules> {
+    RELATED.iter().find(|set| set.rules.contains(&rule_id))
+}
+
+/// The set `rule_id` is grouped under in `issues`: only when another rule
+/// of its set fired too
+pub fn group_of(rule_id: &str, issues: &[Issue]) -> Option<&'static RelatedRules> {
+    related_set(rule_id).filter(|set| {
+        issues
+            .iter()
+            .any(|i| i.id != rule_id && set.rules.contains(&i.id.as_str()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Severity;
+
+    fn issue(id: &str) -> Issue {
+        Issue {
+            id: id.to_string(),
+            category: "style".to_string(),
+            severity: Severity::Warning,
+            message: String::new(),
+            line: Some(1),
+            confidence: 1.0,
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_only_when_related_rules_co_fire() {
+        let issues = [issue("STY001"), issue("STY001"), issue("EXP003")];
+        assert!(group_of("STY001", &issues).is_none());
+
+        let issues = [issue("STY001"), issue("STY004"), issue("EXP003")];
+        assert_eq!(group_of("STY001", &issues).unwrap().key, "tone");
+        assert_eq!(group_of("STY004", &issues).unwrap().key, "tone");
+        assert!(group_of("EXP003", &issues).is_none());
+    }
+
+    #[test]
+    fn test_rules_belong_to_one_set() {
+        let mut seen = std::collections::HashSet::new();
+        for set in RELATED {
+            for rule in set.rules {
+                assert!(seen.insert(rule), "{} is in two sets", rule);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Prompt corpus helpers
+//!
+//! Loads the categorized prompts under `tests/corpus/` and renders the rule IDs
+//! the analyzer fires for each one. Shared by the snapshot tests and the
+//! `copt devtest corpus` command so both produce identical snapshots.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Default corpus location, relative to the repository root
+pub const DEFAULT_CORPUS_DIR: &str = "tests/corpus";
+
+/// Directory holding the analyzer snapshots, relative to the repository root
+pub const SNAPSHOT_DIR: &str = "src/analyzer/snapshots";
+
+/// Source file that owns the corpus snapshots (insta records it in the header)
+const SNAPSHOT_SOURCE: &str = "src/analyzer/corpus_tests.rs";
+
+/// Module prefix insta uses for snapshot file names
+const SNAPSHOT_PREFIX: &str = "copt__analyzer__corpus_tests";
+
+/// A single prompt from the corpus
+#[derive(Debug, Clone)]
+pub struct CorpusEntry {
+    /// Category directory (e.g. "role_only")
+    pub category: String,
+    /// File stem (e.g. "customer_support")
+    pub name: String,
+    pub path: PathBuf,
+    pub prompt: String,
+}
+
+impl CorpusEntry {
+    /// Snapshot name used by insta (`<category>__<name>`)
+    pub fn snapshot_name(&self) -> String {
+        format!("{}__{}", self.category, self.name)
+    }
+}
+
+/// Load every `<category>/<name>.txt` prompt under `dir`, sorted by path
+pub fn load_corpus(dir: &Path) -> Result<Vec<CorpusEntry>> {
+    let mut entries = Vec::new();
+
+    let categories = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read corpus directory: {}", dir.display()))?;
+
+    for category in categories {
+        let category = category?;
+        if !category.file_type()?.is_dir() {
+            continue;
+        }
+        let category_name = category.file_name().to_string_lossy().to_string();
+
+        for file in std::fs::read_dir(category.path())? {
+            let path = file?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+                continue;
+            }
+            let prompt = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read corpus prompt: {}", path.display()))?;
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            entries.push(CorpusEntry {
+                category: category_name.clone(),
+                name,
+                path,
+                prompt,
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Render the rule IDs that fire for a prompt, one per line
+///
+/// Line-level issues include their line number so a rule moving between
+/// lines shows up in review.
+pub fn fired_rules_report(prompt: &str) -> Result<String> {
+    let issues = super::analyze(prompt, None)?;
+
+    if issues.is_empty() {
+        return Ok("(no issues)".to_string());
+    }
+
+    let mut lines: Vec<String> = issues
+        .iter()
+        .map(|i| match i.line {
+            Some(line) => format!("{} L{}", i.id, line),
+            None => i.id.clone(),
+        })
+        .collect();
+    lines.sort();
+
+    Ok(lines.join("\n"))
+}
+
+/// Path of the insta snapshot file for an entry
+pub fn snapshot_path(snapshot_dir: &Path, entry: &CorpusEntry) -> PathBuf {
+    snapshot_dir.join(format!(
+        "{}__{}.snap",
+        SNAPSHOT_PREFIX,
+        entry.snapshot_name()
+    ))
+}
+
+/// Whether the stored snapshot differs from the analyzer's current output
+pub fn is_snapshot_stale(snapshot_dir: &Path, entry: &CorpusEntry) -> Result<bool> {
+    let report = fired_rules_report(&entry.prompt)?;
+    let previous = std::fs::read_to_string(snapshot_path(snapshot_dir, entry)).unwrap_or_default();
+    Ok(snapshot_body(&previous) != Some(report.as_str()))
+}
+
+/// Write a snapshot file in insta's format for the given entry
+///
+/// Returns true when the snapshot content changed.
+pub fn write_snapshot(snapshot_dir: &Path, entry: &CorpusEntry) -> Result<bool> {
+    if !is_snapshot_stale(snapshot_dir, entry)? {
+        return Ok(false);
+    }
+
+    let report = fired_rules_report(&entry.prompt)?;
+    let path = snapshot_path(snapshot_dir, entry);
+    let contents = format!(
+        "---\nsource: {}\nexpression: report\n---\n{}\n",
+        SNAPSHOT_SOURCE, report
+    );
+
+    std::fs::create_dir_all(snapshot_dir).with_context(|| {
+        format!(
+            "Failed to create snapshot directory: {}",
+            snapshot_dir.display()
+        )
+    })?;
+    std::fs::write(&path, contents)
+        .with_context(|| format!("Failed to write snapshot: {}", path.display()))?;
+
+    Ok(true)
+}
+
+/// Extract the body of an insta snapshot (everything after the header)
+fn snapshot_body(contents: &str) -> Option<&str> {
+    let rest = contents.strip_prefix("---\n")?;
+    let end = rest.find("\n---\n")?;
+    Some(rest[end + 5..].trim_end_matches('\n'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_body() {
+        let snap = "---\nsource: x.rs\nexpression: report\n---\nEXP001 L1\nFED002\n";
+        assert_eq!(snapshot_body(snap), Some("EXP001 L1\nFED002"));
+        assert_eq!(snapshot_body(""), None);
+    }
+
+    #[test]
+    fn test_fired_rules_report_sorted() {
+        let report = fired_rules_report("Create a dashboard").unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        let mut sorted = lines.clone();
+        sorted.sort();
+        assert_eq!(lines, sorted);
+        assert!(report.contains("EXP001 L1"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Snapshot tests for the prompt corpus
+//!
+//! Asserts exactly which rule IDs fire for every prompt in `tests/corpus/`.
+//! Regenerate with `copt devtest corpus` after intentional analyzer changes.
+
+#![cfg(test)]
+
+use std::path::Path;
+
+use insta::assert_snapshot;
+
+use super::corpus::{fired_rules_report, load_corpus, DEFAULT_CORPUS_DIR};
+
+#[test]
+fn corpus_rule_ids() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_CORPUS_DIR);
+    let entries = load_corpus(&dir).unwrap();
+    assert!(!entries.is_empty(), "corpus is empty");
+
+    for entry in entries {
+        let report = fired_rules_report(&entry.prompt).unwrap();
+        assert_snapshot!(entry.snapshot_name(), report);
+    }
+}
+
+#[test]
+fn corpus_covers_all_categories() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_CORPUS_DIR);
+    let entries = load_corpus(&dir).unwrap();
+
+    for category in [
+        "role_only",
+        "agentic",
+        "rag",
+        "frontend",
+        "already_optimized",
+    ] {
+        assert!(
+            entries.iter().any(|e| e.category == category),
+            "missing corpus category: {}",
+            category
+        );
+    }
+}
+
+#[test]
+fn role_only_prompts_flag_exp005_or_exp006() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_CORPUS_DIR);
+    let entries = load_corpus(&dir).unwrap();
+
+    for entry in entries.iter().filter(|e| e.category == "role_only") {
+        let report = fired_rules_report(&entry.prompt).unwrap();
+        assert!(
+            report.contains("EXP005") || report.contains("EXP006"),
+            "{} should be flagged as role-only/open-ended:\n{}",
+            entry.name,
+            report
+        );
+    }
+}
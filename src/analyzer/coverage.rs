@@ -0,0 +1,231 @@
+//! Which rule categories an analysis covered
+//!
+//! The issue list only says what was flagged; an empty list could mean the
+//! prompt is clean or that nothing looked. Coverage lists every category
+//! with what happened to it: flagged, passed, or skipped and why (not
+//! applicable to the prompt type, left out of `--check`, opt-in, or turned
+//! off in the config), so an audit can show positive confirmation.
+
+use serde::Serialize;
+
+use super::{language, rule_pack, Issue, PromptType};
+
+/// What happened to one category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageStatus {
+    /// Checked, and at least one issue remains
+    Flagged,
+    /// Checked, and nothing was found
+    Passed,
+    /// The prompt type doesn't use these rules
+    NotApplicable,
+    /// `--check` named other categories
+    NotSelected,
+    /// Runs only when enabled in the config or named in `--check`
+    OptIn,
+    /// Turned off in the config (`[rules] disabled_categories`)
+    Disabled,
+}
+
+impl CoverageStatus {
+    /// Whether the category's rules ran
+    pub fn evaluated(self) -> bool {
+        matches!(self, Self::Flagged | Self::Passed)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Flagged => "flagged",
+            Self::Passed => "passed",
+            Self::NotApplicable => "skipped: not applicable",
+            Self::NotSelected => "skipped: not in --check",
+            Self::OptIn => "skipped: opt-in",
+            Self::Disabled => "skipped: disabled",
+        }
+    }
+}
+
+/// Coverage of one category
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryCoverage {
+    pub category: String,
+    pub display_name: String,
+    pub status: CoverageStatus,
+    /// Issues reported in the category
+    pub issues: usize,
+    /// Rules of an evaluated category that the config turned off
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disabled_rules: Vec<String>,
+}
+
+/// Rule ID prefix of a built-in category, e.g. `STY` for `style`
+pub fn rule_prefix(category: &str) -> Option<&'static str> {
+    Some(match category {
+        "explicitness" => "EXP",
+        "style" => "STY",
+        "tools" => "TUL",
+        "formatting" => "FMT",
+        "verbosity" => "VRB",
+        "agentic" => "AGT",
+        "long_horizon" => "LHT",
+        "frontend" => "FED",
+        "hygiene" => "HYG",
+        language::CATEGORY => "LNG",
+        _ => return None,
+    })
+}
+
+/// Coverage of every category for an analysis of `prompt` that reported
+/// `issues` (after config filtering)
+pub fn coverage(
+    prompt: &str,
+    check_categories: Option<&[String]>,
+    issues: &[Issue],
+) -> Vec<CategoryCoverage> {
+    let prompt_type = super::classify_prompt(prompt);
+    let selected = super::selected_categories(prompt_type, check_categories);
+
+    let mut categories: Vec<&str> = super::CATEGORIES.to_vec();
+    if rule_pack::active().is_some() {
+        categories.push(rule_pack::CUSTOM_CATEGORY);
+    }
+
+    categories
+        .into_iter()
+        .map(|category| {
+            let count = issues.iter().filter(|i| i.category == category).count();
+            let status = if is_category_disabled(category) {
+                CoverageStatus::Disabled
+            } else if category == rule_pack::CUSTOM_CATEGORY {
+                // Pack rules run for every prompt type
+                match check_categories {
+                    Some(cats) if !cats.iter().any(|c| c == category) => {
+                        CoverageStatus::NotSelected
+                    }
+                    _ => evaluated(count),
+                }
+            } else if selected.contains(&category) {
+                evaluated(count)
+            } else {
+                skipped(category, prompt_type, check_categories)
+            };
+            CategoryCoverage {
+                category: category.to_string(),
+                display_name: super::report::category_display_name(category),
+                status,
+                issues: count,
+                disabled_rules: if status.evaluated() {
+                    disabled_rules(category)
+                } else {
+                    Vec::new()
+                },
+            }
+        })
+        .collect()
+}
+
+fn evaluated(issues: usize) -> CoverageStatus {
+    if issues > 0 {
+        CoverageStatus::Flagged
+    } else {
+        CoverageStatus::Passed
+    }
+}
+
+/// Why a category outside the selection didn't run
+fn skipped(
+    category: &str,
+    prompt_type: PromptType,
+    check_categories: Option<&[String]>,
+) -> CoverageStatus {
+    if check_categories.is_some() {
+        CoverageStatus::NotSelected
+    } else if category == language::CATEGORY {
+        CoverageStatus::OptIn
+    } else if !super::get_applicable_categories(prompt_type).contains(&category) {
+        CoverageStatus::NotApplicable
+    } else {
+        CoverageStatus::NotSelected
+    }
+}
+
+/// Whether the config turned off the whole category, by name or rule prefix
+fn is_category_disabled(category: &str) -> bool {
+    let required = super::REQUIRED_CATEGORIES
+        .get()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if required.contains(&category) {
+        return false;
+    }
+    let (_, categories) = super::disabled();
+    categories.iter().any(|disabled| {
+        disabled == category
+            || rule_prefix(category).is_some_and(|prefix| disabled.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// Rules of `category` turned off one by one in the config
+fn disabled_rules(category: &str) -> Vec<String> {
+    let (rules, _) = super::disabled();
+    rule_prefix(category)
+        .map(|prefix| {
+            rules
+                .iter()
+                .filter(|rule| rule.starts_with(prefix))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of<'a>(coverage: &'a [CategoryCoverage], category: &str) -> &'a CategoryCoverage {
+        coverage.iter().find(|c| c.category == category).unwrap()
+    }
+
+    #[test]
+    fn test_coverage_explains_every_category() {
+        let prompt = "Can you write a short poem about autumn leaves?";
+        let issues = super::super::analyze(prompt, None).unwrap();
+        let coverage = coverage(prompt, None, &issues);
+
+        assert_eq!(coverage.len(), super::super::CATEGORIES.len());
+        let explicitness = status_of(&coverage, "explicitness");
+        assert_eq!(explicitness.status, CoverageStatus::Flagged);
+        assert!(explicitness.issues > 0);
+        assert_eq!(
+            status_of(&coverage, "hygiene").status,
+            CoverageStatus::Passed
+        );
+        assert_eq!(
+            status_of(&coverage, "long_horizon").status,
+            CoverageStatus::NotApplicable
+        );
+        assert_eq!(
+            status_of(&coverage, "language").status,
+            CoverageStatus::OptIn
+        );
+    }
+
+    #[test]
+    fn test_coverage_with_check() {
+        let prompt = "Refactor the parser module and update its tests.";
+        let check = ["style".to_string()];
+        let coverage = coverage(prompt, Some(&check), &[]);
+
+        assert_eq!(status_of(&coverage, "style").status, CoverageStatus::Passed);
+        assert_eq!(
+            status_of(&coverage, "explicitness").status,
+            CoverageStatus::NotSelected
+        );
+        assert!(coverage
+            .iter()
+            .filter(|c| c.category != "style")
+            .all(|c| !c.status.evaluated()));
+    }
+}
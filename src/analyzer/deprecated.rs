@@ -0,0 +1,154 @@
+//! Retired rule IDs
+//!
+//! Rule IDs end up in places copt doesn't control: `rules.disabled` and
+//! `rules.severity_overrides` in team config files, `copt docs` calls in
+//! scripts, and the issues recorded with saved optimizations and metrics.
+//! When a rule is renamed or split, its old ID stays in [`DEPRECATED`] with
+//! its successors, so those keep working: the old ID is read as its
+//! successors, config files get a warning naming them, and
+//! `copt rules list --deprecated` prints the whole mapping.
+//!
+//! Never reuse a retired ID for a new rule.
+
+use serde::Serialize;
+
+/// A rule ID that was renamed or split
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    pub id: &'static str,
+    /// One ID for a rename, several for a split
+    pub successors: &'static [&'static str],
+    /// copt version that retired the ID
+    pub since: &'static str,
+    /// Why, in a few words
+    pub reason: &'static str,
+}
+
+/// Retired rule IDs, oldest first
+pub const DEPRECATED: &[Deprecation] = &[];
+
+/// The retirement of `id`, if it was retired
+pub fn lookup(id: &str) -> Option<&'static Deprecation> {
+    lookup_in(DEPRECATED, id)
+}
+
+fn lookup_in<'a>(table: &'a [Deprecation], id: &str) -> Option<&'a Deprecation> {
+    table.iter().find(|d| d.id.eq_ignore_ascii_case(id.trim()))
+}
+
+/// The current rule IDs `id` stands for: `id` itself unless it was retired
+///
+/// Follows a rename of a rename to the rule that exists today.
+pub fn current_ids(id: &str) -> Vec<String> {
+    current_ids_in(DEPRECATED, id)
+}
+
+fn current_ids_in(table: &[Deprecation], id: &str) -> Vec<String> {
+    let mut current = Vec::new();
+    let mut pending = vec![id.to_string()];
+    // No real chain of renames is this long; a table mistake that loops
+    // stops here instead of never finishing
+    let mut hops = 0;
+    while let Some(id) = pending.pop() {
+        match lookup_in(table, &id) {
+            Some(deprecation) if hops < 100 => {
+                hops += 1;
+                pending.extend(deprecation.successors.iter().rev().map(|s| s.to_string()));
+            }
+            _ => {
+                if !current.contains(&id) {
+                    current.push(id);
+                }
+            }
+        }
+    }
+    current
+}
+
+/// `ids` with every retired ID replaced by its successors
+pub fn migrate(ids: &[String]) -> Vec<String> {
+    migrate_in(DEPRECATED, ids)
+}
+
+fn migrate_in(table: &[Deprecation], ids: &[String]) -> Vec<String> {
+    let mut migrated: Vec<String> = Vec::new();
+    for id in ids {
+        for current in current_ids_in(table, id) {
+            if !migrated.contains(&current) {
+                migrated.push(current);
+            }
+        }
+    }
+    migrated
+}
+
+/// The one rule `id` was renamed to; `None` for current IDs and splits
+pub fn renamed_to(id: &str) -> Option<String> {
+    lookup(id)?;
+    let mut current = current_ids(id);
+    (current.len() == 1).then(|| current.remove(0))
+}
+
+impl Deprecation {
+    /// "EXP010 was renamed to EXP011 in copt 0.3.0"
+    pub fn describe(&self) -> String {
+        let change = match self.successors {
+            [one] => format!("renamed to {}", one),
+            many => format!("split into {}", many.join(", ")),
+        };
+        format!("{} was {} in copt {}", self.id, change, self.since)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &[Deprecation] = &[
+        Deprecation {
+            id: "STY010",
+            successors: &["STY011"],
+            since: "0.3.0",
+            reason: "renamed",
+        },
+        Deprecation {
+            id: "STY011",
+            successors: &["STY012", "STY013"],
+            since: "0.4.0",
+            reason: "split by trigger",
+        },
+    ];
+
+    #[test]
+    fn test_retired_ids_resolve_to_current_rules() {
+        assert_eq!(current_ids_in(TABLE, "EXP001"), ["EXP001"]);
+        assert_eq!(current_ids_in(TABLE, "sty010"), ["STY012", "STY013"]);
+        assert_eq!(
+            migrate_in(TABLE, &["STY011".into(), "STY013".into(), "HYG002".into()]),
+            ["STY012", "STY013", "HYG002"]
+        );
+        assert_eq!(
+            TABLE[0].describe(),
+            "STY010 was renamed to STY011 in copt 0.3.0"
+        );
+        assert_eq!(
+            TABLE[1].describe(),
+            "STY011 was split into STY012, STY013 in copt 0.4.0"
+        );
+    }
+
+    #[test]
+    fn test_builtin_table_is_consistent() {
+        let builtin: Vec<&str> = super::super::docs::builtin_rule_ids().collect();
+        for deprecation in DEPRECATED {
+            assert!(
+                !builtin.contains(&deprecation.id),
+                "{} is retired but still a built-in rule",
+                deprecation.id
+            );
+            for id in current_ids(deprecation.id) {
+                assert!(builtin.contains(&id.as_str()), "{} has no rule", id);
+            }
+        }
+    }
+}
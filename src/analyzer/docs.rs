@@ -0,0 +1,124 @@
+//! Documentation links for each rule
+//!
+//! Most built-in rules come straight from Anthropic's Claude 4 prompt
+//! engineering best practices, so each links to the section that explains
+//! why the rule exists. Hygiene and language rules are copt's own and link
+//! to docs/RULES.md instead. Rule pack rules can supply a `docs_url`.
+
+use super::rule_pack;
+
+/// Anthropic's Claude 4 prompt engineering best practices
+pub const BEST_PRACTICES_URL: &str =
+    "https://platform.claude.com/docs/en/build-with-claude/prompt-engineering/claude-4-best-practices";
+
+/// copt's own rules reference
+pub const RULES_REFERENCE_URL: &str = "https://github.com/praveenc/copt/blob/main/docs/RULES.md";
+
+/// Where a built-in rule's documentation lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Anchor on the best-practices page
+    BestPractices(&'static str),
+    /// Anchor in docs/RULES.md
+    RulesReference(&'static str),
+}
+
+const fn bp(anchor: &'static str) -> Section {
+    Section::BestPractices(anchor)
+}
+
+const fn rr(anchor: &'static str) -> Section {
+    Section::RulesReference(anchor)
+}
+
+/// Documentation sections for built-in rules, by rule ID
+const BUILTIN: &[(&str, Section)] = &[
+    ("AGT001", bp("code-exploration")),
+    ("AGT002", bp("minimizing-hallucinations-in-agentic-coding")),
+    ("AGT003", bp("state-management-best-practices")),
+    ("AGT004", bp("overeagerness")),
+    ("EXP001", bp("be-explicit-with-your-instructions")),
+    ("EXP002", bp("add-context-to-improve-performance")),
+    ("EXP003", bp("tool-usage-patterns")),
+    ("EXP004", bp("be-explicit-with-your-instructions")),
+    ("EXP005", bp("be-explicit-with-your-instructions")),
+    ("EXP006", bp("be-explicit-with-your-instructions")),
+    ("EXP007", rr("exp007--nonexistent-file-reference")),
+    ("FED001", bp("enhance-visual-and-frontend-code-generation")),
+    ("FED002", bp("enhance-visual-and-frontend-code-generation")),
+    ("FED003", bp("enhance-visual-and-frontend-code-generation")),
+    ("FED004", bp("enhance-visual-and-frontend-code-generation")),
+    ("FED005", bp("enhance-visual-and-frontend-code-generation")),
+    ("FMT001", bp("control-the-format-of-responses")),
+    ("FMT002", bp("control-the-format-of-responses")),
+    ("FMT003", bp("control-the-format-of-responses")),
+    ("FMT004", bp("control-the-format-of-responses")),
+    ("FMT005", bp("control-the-format-of-responses")),
+    ("FMT006", rr("fmt006--dead-section")),
+    (
+        "FMT007",
+        rr("fmt007--instruction-the-output-channel-rules-out"),
+    ),
+    ("HYG001", rr("hyg001--mixed-line-endings")),
+    ("HYG002", rr("hyg002--trailing-whitespace")),
+    ("HYG003", rr("hyg003--invisible-unicode-characters")),
+    ("HYG004", rr("hyg004--byte-order-mark")),
+    ("HYG005", rr("hyg005--excessive-blank-lines")),
+    ("LHT001", bp("state-management-best-practices")),
+    ("LHT002", bp("long-horizon-reasoning-and-state-tracking")),
+    ("LHT003", bp("context-awareness-and-multi-window-workflows")),
+    ("LNG001", rr("lng001--possible-misspelling")),
+    ("LNG002", rr("lng002--inconsistent-terminology")),
+    ("STY001", bp("control-the-format-of-responses")),
+    ("STY002", bp("tool-usage-and-triggering")),
+    ("STY003", bp("thinking-sensitivity")),
+    ("STY004", bp("tool-usage-and-triggering")),
+    ("TUL001", bp("tool-usage-patterns")),
+    ("TUL002", bp("optimize-parallel-tool-calling")),
+    ("TUL003", bp("reduce-file-creation-in-agentic-coding")),
+    ("VRB001", bp("communication-style")),
+    ("VRB002", bp("communication-style")),
+];
+
+/// Every built-in rule ID, sorted
+pub fn builtin_rule_ids() -> impl Iterator<Item = &'static str> {
+    BUILTIN.iter().map(|(id, _)| *id)
+}
+
+/// Documentation link for `rule_id`, from the built-in rules or the active rule pack
+pub fn docs_url(rule_id: &str) -> Option<String> {
+    if let Ok(i) = BUILTIN.binary_search_by(|(id, _)| (*id).cmp(rule_id)) {
+        return Some(match BUILTIN[i].1 {
+            Section::BestPractices(anchor) => format!("{}#{}", BEST_PRACTICES_URL, anchor),
+            Section::RulesReference(anchor) => format!("{}#{}", RULES_REFERENCE_URL, anchor),
+        });
+    }
+
+    rule_pack::active()?.docs_url(rule_id).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_are_sorted() {
+        assert!(BUILTIN.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_every_rule_with_an_example_has_docs() {
+        // The example table is the most complete list of built-in rule IDs
+        for id in super::super::examples::builtin_ids() {
+            assert!(docs_url(id).is_some(), "no docs for {}", id);
+        }
+        assert_eq!(
+            docs_url("STY003").as_deref(),
+            Some(
+                "https://platform.claude.com/docs/en/build-with-claude/prompt-engineering/claude-4-best-practices#thinking-sensitivity"
+            )
+        );
+        assert!(docs_url("HYG001").unwrap().starts_with(RULES_REFERENCE_URL));
+        assert!(docs_url("XYZ999").is_none());
+    }
+}
@@ -0,0 +1,252 @@
+//! Before/after examples for each rule
+//!
+//! A suggestion says what to change; an example shows it. Built-in rules
+//! carry a short pair here, condensed from docs/RULES.md so it fits in a
+//! terminal. Rule pack rules can supply their own with an `example` table.
+
+use super::rule_pack;
+
+/// A before/after pair illustrating a rule's fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleExample {
+    pub before: &'static str,
+    pub after: &'static str,
+}
+
+const fn ex(before: &'static str, after: &'static str) -> RuleExample {
+    RuleExample { before, after }
+}
+
+/// Examples for built-in rules, by rule ID
+const BUILTIN: &[(&str, RuleExample)] = &[
+    ("AGT001", ex(
+        "Fix the bug in the user authentication flow",
+        "Fix the bug in the user authentication flow. Read the relevant files before proposing changes.",
+    )),
+    ("AGT002", ex(
+        "Why is the login failing for admin users?",
+        "Investigate why login fails for admin users. Read the auth code before answering; don't speculate about code you haven't opened.",
+    )),
+    ("AGT003", ex(
+        "Implement the full CRUD API for products",
+        "Implement the full CRUD API for products. Track finished endpoints in progress.txt and commit after each one.",
+    )),
+    ("AGT004", ex(
+        "Build a caching system for the API responses",
+        "Build a simple cache for the API responses. Only implement what is needed now; skip extra abstractions and options.",
+    )),
+    ("EXP001", ex(
+        "Create a dashboard",
+        "Create a sales dashboard for regional managers showing weekly revenue, the top 10 products, and a date filter.",
+    )),
+    ("EXP002", ex(
+        "Always use snake_case for variable names",
+        "Always use snake_case for variable names to stay consistent with our Python codebase and PEP 8.",
+    )),
+    ("EXP003", ex(
+        "Can you refactor this function to be more efficient?",
+        "Refactor this function to improve its performance. Make the changes directly.",
+    )),
+    ("EXP004", ex(
+        "Research the best practices for API design",
+        "Research REST API design best practices and summarize 3-5 recommendations each for naming, errors, and pagination.",
+    )),
+    ("EXP005", ex(
+        "You are an experienced travel assistant.",
+        "You are an experienced travel assistant. Answer flight questions with options sorted by departure time and list fees for changes.",
+    )),
+    ("EXP006", ex(
+        "Answer any questions the user might have about the product.",
+        "Answer questions about ProductX features, pricing, and setup. Redirect billing disputes to support@example.com.",
+    )),
+    ("EXP007", ex(
+        "Follow the style guide in docs/style.md.",
+        "Follow the style guide in docs/STYLE.md.",
+    )),
+    ("FED001", ex(
+        "Create a login page",
+        "Create a login page with a distinctive design: unique typography, a bold color palette, and subtle micro-interactions.",
+    )),
+    ("FED002", ex(
+        "Build a data visualization component",
+        "Build a data visualization component with a modern font, a cohesive palette, smooth transitions, and hover tooltips.",
+    )),
+    ("FED003", ex(
+        "Build a React checkout form",
+        "Build a React checkout form that is responsive from mobile to desktop, with semantic elements, ARIA labels, and keyboard navigation.",
+    )),
+    ("FED004", ex(
+        "Create a Vue settings page",
+        "Create a Vue settings page split into SettingsForm, ProfileCard, and NotificationToggles components with typed props.",
+    )),
+    ("FED005", ex(
+        "Build a Svelte search component",
+        "Build a Svelte search component with loading, empty, and error states, plus hover and focus styles.",
+    )),
+    ("FMT001", ex(
+        "Explain how authentication works in this system",
+        "Explain how authentication works in this system, with a heading per component and code blocks for examples.",
+    )),
+    ("FMT002", ex(
+        "Don't use any markdown formatting in your response",
+        "Write your response in flowing prose paragraphs.",
+    )),
+    ("FMT003", ex(
+        "Here are the rules: be concise. Here's the input: [data]",
+        "<rules>Be concise.</rules>\n<input>[data]</input>",
+    )),
+    ("FMT004", ex(
+        "[long prompt with no output instructions]",
+        "<output_format>A summary paragraph, then a table of affected accounts.</output_format>",
+    )),
+    ("FMT005", ex(
+        "<context>[codebase conventions]</context>",
+        "<context>[codebase conventions]</context>\n<instructions>Review the attached diff against these conventions.</instructions>",
+    )),
+    ("FMT006", ex(
+        "<examples>[calls to web_search]</examples>\nweb_search is unavailable here.",
+        "<examples>[answers drawn from the documents]</examples>\nweb_search is unavailable here.",
+    )),
+    ("FMT007", ex(
+        "Respond only with JSON.\nIf unsure, ask the user a clarifying question.",
+        "Respond only with JSON.\nIf unsure, return {\"error\": \"ambiguous\"} with what's missing.",
+    )),
+    ("HYG001", ex(
+        "First line\\r\\nSecond line\\n",
+        "First line\\nSecond line\\n",
+    )),
+    ("HYG002", ex(
+        "Be concise.   ",
+        "Be concise.",
+    )),
+    ("HYG003", ex(
+        "Summarize\\u{200B} the report",
+        "Summarize the report",
+    )),
+    ("HYG004", ex(
+        "\\u{FEFF}You are a support agent.",
+        "You are a support agent.",
+    )),
+    ("HYG005", ex(
+        "Be brief.\\n\\n\\n\\n\\nCite sources.",
+        "Be brief.\\n\\nCite sources.",
+    )),
+    ("LHT001", ex(
+        "Refactor the entire backend to use async/await",
+        "Refactor the backend to async/await. Keep a todo list of files, track progress in progress.txt, and commit incrementally.",
+    )),
+    ("LHT002", ex(
+        "Implement all the missing features in the dashboard",
+        "Implement the missing dashboard features one at a time, finishing and testing each before the next.",
+    )),
+    ("LHT003", ex(
+        "Explore the codebase and document everything you find",
+        "Explore the codebase and document the main components. If context runs low, save a summary of your findings first.",
+    )),
+    ("LNG001", ex(
+        "Seperate the results by catagory.",
+        "Separate the results by category.",
+    )),
+    ("LNG002", ex(
+        "Greet the user by name. Look up the customer's orders.",
+        "Greet the customer by name. Look up the customer's orders.",
+    )),
+    ("STY001", ex(
+        "Don't use markdown in your response",
+        "Write in flowing prose paragraphs",
+    )),
+    ("STY002", ex(
+        "You MUST ALWAYS check for null values!!!",
+        "Check for null values before processing them.",
+    )),
+    ("STY003", ex(
+        "Think about the edge cases before implementing",
+        "Consider the edge cases before implementing",
+    )),
+    ("STY004", ex(
+        "CRITICAL: You MUST use this tool when searching. It's MANDATORY.",
+        "Use this tool when searching for files in the codebase.",
+    )),
+    ("TUL001", ex(
+        "Can you suggest some changes to improve this function?",
+        "Improve this function's performance. Make the changes directly with the edit tool.",
+    )),
+    ("TUL002", ex(
+        "Read the config files in the settings directory",
+        "Read the config files in the settings directory, in parallel when they don't depend on each other.",
+    )),
+    ("TUL003", ex(
+        "Create some test scripts to verify the changes",
+        "Create test scripts to verify the changes, then delete any temporary files you created.",
+    )),
+    ("VRB001", ex(
+        "Refactor the authentication module",
+        "Refactor the authentication module, then briefly summarize what changed and why.",
+    )),
+    ("VRB002", ex(
+        "Update all API endpoints to use the new response format",
+        "Update all API endpoints to the new response format, summarizing the changes after each endpoint.",
+    )),
+];
+
+/// Before/after example for `rule_id`, from the built-in rules or the active rule pack
+pub fn rule_example(rule_id: &str) -> Option<RuleExample> {
+    if let Some(example) = BUILTIN
+        .binary_search_by(|(id, _)| (*id).cmp(rule_id))
+        .ok()
+        .map(|i| BUILTIN[i].1)
+    {
+        return Some(example);
+    }
+
+    let example = rule_pack::active()?.example(rule_id)?;
+    Some(RuleExample {
+        before: &example.before,
+        after: &example.after,
+    })
+}
+
+/// IDs of the built-in rules that have examples
+#[cfg(test)]
+pub(crate) fn builtin_ids() -> impl Iterator<Item = &'static str> {
+    BUILTIN.iter().map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_are_sorted() {
+        assert!(BUILTIN.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_every_builtin_rule_has_an_example() {
+        for category in super::super::CATEGORIES {
+            let prefix = match *category {
+                "explicitness" => "EXP",
+                "style" => "STY",
+                "tools" => "TUL",
+                "formatting" => "FMT",
+                "verbosity" => "VRB",
+                "agentic" => "AGT",
+                "long_horizon" => "LHT",
+                "frontend" => "FED",
+                "hygiene" => "HYG",
+                "language" => "LNG",
+                other => panic!("unknown category {}", other),
+            };
+            assert!(
+                BUILTIN.iter().any(|(id, _)| id.starts_with(prefix)),
+                "no examples for {}",
+                category
+            );
+        }
+        assert_eq!(
+            rule_example("STY003").map(|e| e.after),
+            Some("Consider the edge cases before implementing")
+        );
+        assert!(rule_example("XYZ999").is_none());
+    }
+}
@@ -0,0 +1,359 @@
+//! Incremental re-analysis for editors and watchers
+//!
+//! Re-running every rule on each keystroke is too slow for large prompts.
+//! Most per-line rules read only their own line (a few also need the next
+//! line, or whether the line sits in a code fence), so after an edit only the
+//! changed lines get another pass. Issues from rules that weigh the whole
+//! prompt are carried over from the last full analysis and marked stale until
+//! [`IncrementalAnalysis::refresh`] runs, e.g. once the user stops typing.
+
+use anyhow::Result;
+use std::ops::Range;
+
+use super::{
+    classify_prompt, extract_xml_blocks, language, rule_pack, selected_categories, Issue,
+    PromptType, PRESERVED_TAGS,
+};
+
+/// What a rule reads to decide whether to flag a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleScope {
+    /// The line itself
+    Line,
+    /// The line and the one after it
+    LineAndNext,
+    /// The line and whether it sits inside a code fence
+    Fenced,
+    /// The whole prompt
+    Document,
+}
+
+/// Scope of the rule with `rule_id`; rule pack rules match line by line
+pub fn rule_scope(rule_id: &str) -> RuleScope {
+    match rule_id {
+        "EXP003" | "EXP007" | "STY001" | "TUL001" | "FMT002" => RuleScope::Line,
+        "EXP002" => RuleScope::LineAndNext,
+        "STY003" | "LNG001" => RuleScope::Fenced,
+        _ if rule_pack::active().is_some_and(|pack| pack.has_rule(rule_id)) => RuleScope::Line,
+        _ => RuleScope::Document,
+    }
+}
+
+/// How [`IncrementalAnalysis::update`] handled an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Update {
+    /// The text did not change
+    Unchanged,
+    /// Only these lines (1-based, end exclusive) were re-analyzed
+    Lines(Range<usize>),
+    /// The edit could affect any line, so the whole prompt was re-analyzed
+    Full,
+}
+
+/// Analysis of a prompt that is being edited
+#[derive(Debug, Clone)]
+pub struct IncrementalAnalysis {
+    text: String,
+    check_categories: Option<Vec<String>>,
+    prompt_type: PromptType,
+    line_issues: Vec<Issue>,
+    document_issues: Vec<Issue>,
+    document_stale: bool,
+}
+
+impl IncrementalAnalysis {
+    /// Analyze `prompt` in full
+    pub fn new(prompt: &str, check_categories: Option<&[String]>) -> Result<Self> {
+        let mut analysis = Self {
+            text: String::new(),
+            check_categories: check_categories.map(<[String]>::to_vec),
+            prompt_type: PromptType::General,
+            line_issues: Vec::new(),
+            document_issues: Vec::new(),
+            document_stale: false,
+        };
+        analysis.reanalyze(prompt)?;
+        Ok(analysis)
+    }
+
+    /// Current issues, ordered by line
+    pub fn issues(&self) -> Vec<Issue> {
+        let mut issues: Vec<Issue> = self
+            .line_issues
+            .iter()
+            .chain(&self.document_issues)
+            .cloned()
+            .collect();
+        issues.sort_by_key(|issue| issue.line);
+        issues
+    }
+
+    /// Whether whole-prompt issues predate the latest edit
+    pub fn is_document_stale(&self) -> bool {
+        self.document_stale
+    }
+
+    /// Re-run the full analysis, bringing whole-prompt issues up to date
+    pub fn refresh(&mut self) -> Result<()> {
+        let text = std::mem::take(&mut self.text);
+        self.reanalyze(&text)
+    }
+
+    /// Bring the analysis up to date with the edited `prompt`
+    pub fn update(&mut self, prompt: &str) -> Result<Update> {
+        if prompt == self.text {
+            return Ok(Update::Unchanged);
+        }
+
+        // The prompt type picks the categories that run
+        let prompt_type = classify_prompt(prompt);
+        if prompt_type != self.prompt_type {
+            self.reanalyze(prompt)?;
+            return Ok(Update::Full);
+        }
+
+        let old: Vec<&str> = self.text.lines().collect();
+        let new: Vec<&str> = prompt.lines().collect();
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+
+        // Fences and preserved blocks change how every later line is read
+        let changed = old[prefix..old_end].iter().chain(&new[prefix..new_end]);
+        if changed.into_iter().any(|line| changes_context(line)) {
+            self.reanalyze(prompt)?;
+            return Ok(Update::Full);
+        }
+
+        // EXP002 on the line before the edit reads the first edited line, and
+        // the line after the edit is read (not re-analyzed) for the same rule
+        let start = prefix.saturating_sub(1);
+        let context_end = (new_end + 1).min(new.len());
+
+        let (cleaned, _) = extract_xml_blocks(prompt);
+        let cleaned: Vec<&str> = cleaned.lines().collect();
+        let in_fence = cleaned[..start.min(cleaned.len())]
+            .iter()
+            .filter(|line| is_fence(line))
+            .count()
+            % 2
+            == 1;
+
+        // Reopen the fence so fence-aware rules see the window as the full
+        // analysis would
+        let mut window = String::new();
+        if in_fence {
+            window.push_str("```\n");
+        }
+        for idx in start..context_end {
+            window.push_str(cleaned.get(idx).copied().unwrap_or_default());
+            window.push('\n');
+        }
+
+        let fresh: Vec<Issue> = analyze_window(
+            &window,
+            prompt_type,
+            &selected_categories(prompt_type, self.check_categories.as_deref()),
+            self.check_categories.as_deref(),
+        )
+        .into_iter()
+        .filter_map(|mut issue| {
+            let line = (issue.line? + start).checked_sub(usize::from(in_fence))?;
+            issue.line = Some(line);
+            (line > start && line <= new_end).then_some(issue)
+        })
+        .collect();
+
+        let shift = |issue: &mut Issue| {
+            if let Some(line) = issue.line.as_mut() {
+                if *line > old_end {
+                    *line = *line + new_end - old_end;
+                }
+            }
+        };
+        self.line_issues.retain(|issue| {
+            issue
+                .line
+                .is_none_or(|line| line <= start || line > old_end)
+        });
+        self.line_issues.iter_mut().for_each(shift);
+        self.line_issues.extend(fresh);
+        self.document_issues.iter_mut().for_each(shift);
+
+        self.text = prompt.to_string();
+        self.document_stale = true;
+        Ok(Update::Lines(start + 1..new_end + 1))
+    }
+
+    fn reanalyze(&mut self, prompt: &str) -> Result<()> {
+        let issues = super::analyze(prompt, self.check_categories.as_deref())?;
+        (self.line_issues, self.document_issues) = issues
+            .into_iter()
+            .partition(|issue| rule_scope(&issue.id) != RuleScope::Document);
+        self.prompt_type = classify_prompt(prompt);
+        self.text = prompt.to_string();
+        self.document_stale = false;
+        Ok(())
+    }
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Whether editing `line` can change how other lines are analyzed
+fn changes_context(line: &str) -> bool {
+    is_fence(line)
+        || PRESERVED_TAGS.iter().any(|tag| {
+            line.contains(&format!("<{}>", tag)) || line.contains(&format!("</{}>", tag))
+        })
+}
+
+/// Issues from line-scoped rules in `window`, a slice of a prompt already
+/// classified as `prompt_type`
+fn analyze_window(
+    window: &str,
+    prompt_type: PromptType,
+    categories: &[&str],
+    check_categories: Option<&[String]>,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for category in categories {
+        match *category {
+            "explicitness" => issues.extend(super::analyze_explicitness(window, prompt_type)),
+            "style" => issues.extend(super::analyze_style(window)),
+            "tools" => issues.extend(super::analyze_tools(window)),
+            "formatting" => issues.extend(super::analyze_formatting(window)),
+            language::CATEGORY => issues.extend(language::analyze_language(window)),
+            _ => {} // No line-scoped rules
+        }
+    }
+    if let Some(pack) = rule_pack::active() {
+        issues.extend(pack.analyze(window, check_categories));
+    }
+    issues.retain(|issue| rule_scope(&issue.id) != RuleScope::Document);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROMPT: &str = "You are a coding assistant for the parser crate.
+Can you refactor the tokenizer?
+Never use unwrap.
+because it panics on bad input.
+
+```
+think about this in code
+```
+Think about edge cases first.
+<example>
+Could you suggest some changes?
+</example>
+Don't use markdown formatting.
+Always add tests.
+";
+
+    fn check() -> Vec<String> {
+        ["explicitness", "style", "tools", "formatting", "hygiene"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    /// Issues as comparable tuples, optionally only the line-scoped ones
+    fn summary(issues: &[Issue], line_scoped_only: bool) -> Vec<(Option<usize>, String, String)> {
+        let mut summary: Vec<_> = issues
+            .iter()
+            .filter(|i| !line_scoped_only || rule_scope(&i.id) != RuleScope::Document)
+            .map(|i| (i.line, i.id.clone(), i.message.clone()))
+            .collect();
+        summary.sort();
+        summary
+    }
+
+    fn assert_matches_full(analysis: &IncrementalAnalysis, prompt: &str, line_scoped_only: bool) {
+        let full = crate::analyzer::analyze(prompt, Some(&check())).unwrap();
+        assert_eq!(
+            summary(&analysis.issues(), line_scoped_only),
+            summary(&full, line_scoped_only),
+            "incremental result differs for:\n{}",
+            prompt
+        );
+    }
+
+    #[test]
+    fn test_rule_scopes() {
+        assert_eq!(rule_scope("EXP003"), RuleScope::Line);
+        assert_eq!(rule_scope("EXP002"), RuleScope::LineAndNext);
+        assert_eq!(rule_scope("STY003"), RuleScope::Fenced);
+        assert_eq!(rule_scope("STY002"), RuleScope::Document);
+        assert_eq!(rule_scope("FMT004"), RuleScope::Document);
+    }
+
+    #[test]
+    fn test_edits_match_full_analysis() {
+        let check = check();
+        let mut analysis = IncrementalAnalysis::new(PROMPT, Some(&check)).unwrap();
+        assert_matches_full(&analysis, PROMPT, false);
+        assert_eq!(analysis.update(PROMPT).unwrap(), Update::Unchanged);
+
+        // Rewording a line only re-analyzes it and its neighbor above
+        let edited = PROMPT.replace("Can you refactor", "Refactor");
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Lines(1..3));
+        assert!(analysis.is_document_stale());
+        assert_matches_full(&analysis, &edited, true);
+
+        // Inserting a line takes the context away from the prohibition above
+        // it and shifts every later issue down
+        let edited = edited.replace(
+            "Never use unwrap.\n",
+            "Never use unwrap.\nCould you explain why?\n",
+        );
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Lines(3..5));
+        assert_matches_full(&analysis, &edited, true);
+
+        // Lines inside a code fence or a preserved block stay unflagged
+        let edited = edited.replace("think about this in code", "think about it again");
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Lines(7..9));
+        assert_matches_full(&analysis, &edited, true);
+        let edited = edited.replace(
+            "Could you suggest some changes?",
+            "Can you propose changes?",
+        );
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Lines(11..13));
+        assert_matches_full(&analysis, &edited, true);
+
+        // Deleting lines shifts later issues up
+        let edited = edited.replace("Think about edge cases first.\n", "");
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Lines(9..10));
+        assert_matches_full(&analysis, &edited, true);
+
+        // Whole-prompt issues catch up on refresh
+        analysis.refresh().unwrap();
+        assert!(!analysis.is_document_stale());
+        assert_matches_full(&analysis, &edited, false);
+    }
+
+    #[test]
+    fn test_structural_edits_reanalyze_everything() {
+        let check = check();
+        let mut analysis = IncrementalAnalysis::new(PROMPT, Some(&check)).unwrap();
+
+        // Removing a fence changes how every line after it is read
+        let edited = PROMPT.replacen("```\n", "", 1);
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Full);
+        assert!(!analysis.is_document_stale());
+        assert_matches_full(&analysis, &edited, false);
+
+        let edited = edited.replace("</example>", "");
+        assert_eq!(analysis.update(&edited).unwrap(), Update::Full);
+        assert_matches_full(&analysis, &edited, false);
+    }
+}
@@ -0,0 +1,372 @@
+//! Language rules: spelling and terminology consistency (LNG001-002)
+//!
+//! An optional category: it runs with `--check language`, or on every
+//! analysis when `language.enabled = true`. This is not a dictionary spell
+//! check: LNG001 only flags words on a built-in list of common misspellings,
+//! so product names, identifiers, and jargon are never flagged; words listed
+//! in `language.dictionary` are never flagged either.
+
+use regex::Regex;
+use std::sync::{LazyLock, OnceLock};
+
+use super::{Issue, Severity};
+
+/// Category name for language rules
+pub const CATEGORY: &str = "language";
+
+/// Language settings for this process
+#[derive(Debug, Default)]
+struct Settings {
+    enabled: bool,
+    dictionary: Vec<String>,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Apply `language.*` config for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(enabled: bool, dictionary: Vec<String>) {
+    let _ = SETTINGS.set(Settings {
+        enabled,
+        dictionary,
+    });
+}
+
+/// Whether language rules run without an explicit `--check`
+pub fn enabled() -> bool {
+    SETTINGS.get().is_some_and(|s| s.enabled)
+}
+
+/// Words from `language.dictionary`
+pub fn dictionary() -> &'static [String] {
+    SETTINGS.get().map_or(&[], |s| s.dictionary.as_slice())
+}
+
+fn in_user_dictionary(word: &str) -> bool {
+    SETTINGS
+        .get()
+        .is_some_and(|s| s.dictionary.iter().any(|w| w.eq_ignore_ascii_case(word)))
+}
+
+/// Known misspellings and their corrections (sorted); any word not listed
+/// here passes LNG001
+const COMMON_MISSPELLINGS: &[(&str, &str)] = &[
+    ("accesible", "accessible"),
+    ("accomodate", "accommodate"),
+    ("accross", "across"),
+    ("acess", "access"),
+    ("acheive", "achieve"),
+    ("adn", "and"),
+    ("adress", "address"),
+    ("agressive", "aggressive"),
+    ("ambigious", "ambiguous"),
+    ("apparant", "apparent"),
+    ("appearence", "appearance"),
+    ("approriate", "appropriate"),
+    ("apropriate", "appropriate"),
+    ("arguement", "argument"),
+    ("assistent", "assistant"),
+    ("basicly", "basically"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("belive", "believe"),
+    ("calender", "calendar"),
+    ("catagory", "category"),
+    ("changable", "changeable"),
+    ("collegue", "colleague"),
+    ("comming", "coming"),
+    ("commited", "committed"),
+    ("comparision", "comparison"),
+    ("completly", "completely"),
+    ("concensus", "consensus"),
+    ("consise", "concise"),
+    ("consistant", "consistent"),
+    ("continous", "continuous"),
+    ("correspondance", "correspondence"),
+    ("critera", "criteria"),
+    ("definately", "definitely"),
+    ("definatly", "definitely"),
+    ("dependancy", "dependency"),
+    ("desicion", "decision"),
+    ("developement", "development"),
+    ("didnt", "didn't"),
+    ("diffrent", "different"),
+    ("doesnt", "doesn't"),
+    ("dont", "don't"),
+    ("enforcable", "enforceable"),
+    ("enviroment", "environment"),
+    ("equiped", "equipped"),
+    ("examle", "example"),
+    ("excercise", "exercise"),
+    ("existance", "existence"),
+    ("exmaple", "example"),
+    ("experiance", "experience"),
+    ("explaination", "explanation"),
+    ("familar", "familiar"),
+    ("finaly", "finally"),
+    ("formated", "formatted"),
+    ("fucntion", "function"),
+    ("funtion", "function"),
+    ("futher", "further"),
+    ("gaurantee", "guarantee"),
+    ("goverment", "government"),
+    ("grammer", "grammar"),
+    ("guidence", "guidance"),
+    ("happend", "happened"),
+    ("hierachy", "hierarchy"),
+    ("immediatly", "immediately"),
+    ("implemention", "implementation"),
+    ("independant", "independent"),
+    ("instrucitons", "instructions"),
+    ("instuctions", "instructions"),
+    ("interupt", "interrupt"),
+    ("intial", "initial"),
+    ("irrelevent", "irrelevant"),
+    ("isnt", "isn't"),
+    ("knowlege", "knowledge"),
+    ("langauge", "language"),
+    ("lenght", "length"),
+    ("libary", "library"),
+    ("maintainance", "maintenance"),
+    ("maintenence", "maintenance"),
+    ("managment", "management"),
+    ("neccessary", "necessary"),
+    ("necesary", "necessary"),
+    ("noticable", "noticeable"),
+    ("occassion", "occasion"),
+    ("occurance", "occurrence"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("ommit", "omit"),
+    ("ommitted", "omitted"),
+    ("oppurtunity", "opportunity"),
+    ("paralell", "parallel"),
+    ("paramter", "parameter"),
+    ("paramters", "parameters"),
+    ("parrallel", "parallel"),
+    ("particulary", "particularly"),
+    ("perfomance", "performance"),
+    ("persistant", "persistent"),
+    ("prefered", "preferred"),
+    ("presense", "presence"),
+    ("priviledge", "privilege"),
+    ("probaly", "probably"),
+    ("proffesional", "professional"),
+    ("promt", "prompt"),
+    ("publically", "publicly"),
+    ("reccomend", "recommend"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("recieving", "receiving"),
+    ("recomend", "recommend"),
+    ("refered", "referred"),
+    ("relevent", "relevant"),
+    ("remeber", "remember"),
+    ("repitition", "repetition"),
+    ("reponse", "response"),
+    ("responce", "response"),
+    ("resposne", "response"),
+    ("retreive", "retrieve"),
+    ("seperate", "separate"),
+    ("seperately", "separately"),
+    ("succesful", "successful"),
+    ("successfull", "successful"),
+    ("sucess", "success"),
+    ("summerize", "summarize"),
+    ("suprise", "surprise"),
+    ("teh", "the"),
+    ("tendancy", "tendency"),
+    ("therefor", "therefore"),
+    ("thier", "their"),
+    ("threshhold", "threshold"),
+    ("tommorow", "tomorrow"),
+    ("truely", "truly"),
+    ("unfortunatly", "unfortunately"),
+    ("untill", "until"),
+    ("usefull", "useful"),
+    ("wich", "which"),
+    ("wierd", "weird"),
+    ("writting", "writing"),
+];
+
+/// Terms that usually name the same thing; mixing them within one prompt
+/// leaves the model guessing whether they differ
+const TERM_GROUPS: &[&[&str]] = &[
+    &["user", "customer", "client"],
+    &["ticket", "issue"],
+    &["assistant", "agent", "bot"],
+    &["folder", "directory"],
+];
+
+/// Words, plus inline code spans and URLs so they can be skipped
+static TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`]*`|https?://\S+|[A-Za-z][A-Za-z']*").unwrap());
+
+/// Prose words with their 1-based line numbers, skipping code and URLs
+fn prose_words(prompt: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut in_fence = false;
+
+    for (idx, line) in prompt.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for m in TOKEN.find_iter(line) {
+            let token = m.as_str();
+            if token.starts_with('`') || token.contains("://") {
+                continue;
+            }
+            words.push((idx + 1, token.trim_end_matches('\'')));
+        }
+    }
+
+    words
+}
+
+fn correction(word: &str) -> Option<&'static str> {
+    let lower = word.to_lowercase();
+    COMMON_MISSPELLINGS
+        .binary_search_by(|(wrong, _)| (*wrong).cmp(lower.as_str()))
+        .ok()
+        .map(|i| COMMON_MISSPELLINGS[i].1)
+}
+
+/// Singular form of `word` if it is one of `terms` (or its plural)
+fn term_of(word: &str, terms: &[&'static str]) -> Option<&'static str> {
+    let lower = word.to_lowercase();
+    let lower = lower.strip_suffix("'s").unwrap_or(&lower);
+    terms
+        .iter()
+        .find(|t| lower == **t || lower.strip_suffix('s') == Some(t))
+        .copied()
+}
+
+/// Analyze for spelling and terminology issues (LNG001-002)
+pub fn analyze_language(prompt: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let words = prose_words(prompt);
+
+    // LNG001: Misspellings, one issue per line
+    let mut line_fixes: Vec<(usize, Vec<String>)> = Vec::new();
+    for &(line, word) in &words {
+        let Some(fix) = correction(word) else {
+            continue;
+        };
+        if in_user_dictionary(word) {
+            continue;
+        }
+        let entry = format!("\"{}\" → \"{}\"", word, fix);
+        match line_fixes.last_mut() {
+            Some((l, fixes)) if *l == line => {
+                if !fixes.contains(&entry) {
+                    fixes.push(entry);
+                }
+            }
+            _ => line_fixes.push((line, vec![entry])),
+        }
+    }
+    for (line, fixes) in line_fixes {
+        issues.push(Issue {
+            id: "LNG001".to_string(),
+            category: CATEGORY.to_string(),
+            severity: Severity::Info,
+            message: format!("Possible misspelling: {}", fixes.join(", ")),
+            line: Some(line),
+            confidence: 0.9,
+            suggestion: Some(
+                "Fix the spelling, or add the word to language.dictionary if it is intentional."
+                    .to_string(),
+            ),
+        });
+    }
+
+    // LNG002: One concept named several ways
+    for group in TERM_GROUPS {
+        // (term, uses, first line)
+        let mut used: Vec<(&str, usize, usize)> = Vec::new();
+        for &(line, word) in &words {
+            if let Some(term) = term_of(word, group) {
+                match used.iter_mut().find(|(t, _, _)| *t == term) {
+                    Some(entry) => entry.1 += 1,
+                    None => used.push((term, 1, line)),
+                }
+            }
+        }
+        if used.len() < 2 {
+            continue;
+        }
+
+        // Most frequent first; the rest are the inconsistent variants
+        used.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+        let variants: Vec<String> = used
+            .iter()
+            .map(|(term, count, _)| format!("\"{}\" ({}×)", term, count))
+            .collect();
+        let minority_uses = used[1..].iter().map(|(_, count, _)| count).sum::<usize>();
+        let first_variant_line = used[1..].iter().map(|(_, _, line)| *line).min();
+
+        issues.push(Issue {
+            id: "LNG002".to_string(),
+            category: CATEGORY.to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "Inconsistent terminology: {} may name the same thing",
+                variants.join(", ")
+            ),
+            line: first_variant_line,
+            confidence: if minority_uses >= 2 { 0.8 } else { 0.6 },
+            suggestion: Some(format!(
+                "Pick one term (e.g. \"{}\") and use it throughout, or define how the terms differ.",
+                used[0].0
+            )),
+        });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misspellings_are_sorted() {
+        assert!(COMMON_MISSPELLINGS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_detect_misspellings() {
+        let prompt = "Seperate the results.\nRecieve the `recieve` payload from https://x.io/recieve\n```\nteh code\n```";
+        let issues = analyze_language(prompt);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].line, Some(1));
+        assert!(issues[0].message.contains("\"Seperate\" → \"separate\""));
+        assert_eq!(issues[1].line, Some(2));
+        assert_eq!(
+            issues[1].message,
+            "Possible misspelling: \"Recieve\" → \"receive\""
+        );
+    }
+
+    #[test]
+    fn test_detect_inconsistent_terminology() {
+        let prompt = "Greet the user by name. Look up the customer's orders.\n\
+            If the user asks about a refund, open a ticket. Customers may have several tickets.";
+        let issues = analyze_language(prompt);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "LNG002");
+        assert_eq!(
+            issues[0].message,
+            "Inconsistent terminology: \"user\" (2×), \"customer\" (2×) may name the same thing"
+        );
+        assert_eq!(issues[0].line, Some(1));
+        assert_eq!(issues[0].confidence, 0.8);
+
+        assert!(analyze_language("The user owns the ticket. Users can close tickets.").is_empty());
+    }
+}
@@ -5,9 +5,36 @@
 
 use anyhow::Result;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, OnceLock};
+
+use crate::error::CoptError;
+
+pub mod acronyms;
+pub mod cache;
+pub mod consolidate;
+pub mod corpus;
+pub mod coverage;
+pub mod deprecated;
+pub mod docs;
+pub mod examples;
+pub mod incremental;
+pub mod language;
+pub mod reachability;
+pub mod references;
+pub mod report;
+pub mod rule_pack;
+pub mod ruleset;
+pub mod segment;
+pub mod strengths;
+
+// Corpus snapshot tests
+#[cfg(test)]
+mod corpus_tests;
 
 /// Prompt type for context-aware rule application
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PromptType {
     Coding,
     QaAssistant,
@@ -74,13 +101,40 @@ pub fn classify_prompt(prompt: &str) -> PromptType {
     PromptType::General
 }
 
+/// Whether a prompt reads like a system prompt for a deployed assistant
+///
+/// System prompts set up a role or describe how to treat users' future
+/// messages, rather than asking for one piece of work.
+pub fn is_system_prompt(prompt: &str) -> bool {
+    static ROLE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?im)^\W*(you are|you're|act as|your role is|as an? [\w -]*(assistant|agent|bot)\b)",
+        )
+        .unwrap()
+    });
+    static USER_TURNS: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)\b(when|if|whenever) (the |a )?(user|customer|visitor|student)s?\b|\buser'?s? (messages?|questions?|requests?)\b")
+            .unwrap()
+    });
+
+    ROLE.is_match(prompt) || USER_TURNS.is_match(prompt)
+}
+
 /// Get applicable rule categories for a prompt type
 pub fn get_applicable_categories(prompt_type: PromptType) -> Vec<&'static str> {
     match prompt_type {
-        PromptType::Coding => vec!["explicitness", "style", "tools", "formatting", "agentic"],
-        PromptType::QaAssistant => vec!["explicitness", "style", "formatting"],
-        PromptType::Research => vec!["explicitness", "style", "agentic", "verbosity"],
-        PromptType::Creative => vec!["explicitness", "style", "formatting", "frontend"],
+        PromptType::Coding => vec![
+            "explicitness",
+            "style",
+            "tools",
+            "formatting",
+            "agentic",
+            "frontend",
+            "hygiene",
+        ],
+        PromptType::QaAssistant => vec!["explicitness", "style", "formatting", "hygiene"],
+        PromptType::Research => vec!["explicitness", "style", "agentic", "verbosity", "hygiene"],
+        PromptType::Creative => vec!["explicitness", "style", "formatting", "frontend", "hygiene"],
         PromptType::LongHorizon => vec![
             "explicitness",
             "style",
@@ -90,8 +144,11 @@ pub fn get_applicable_categories(prompt_type: PromptType) -> Vec<&'static str> {
             "agentic",
             "long_horizon",
             "frontend",
+            "hygiene",
         ],
-        PromptType::General => vec!["explicitness", "style", "formatting"],
+        // Frontend rules gate themselves on UI indicators, so they are safe to
+        // run for general prompts ("Build a React dashboard" classifies as General)
+        PromptType::General => vec!["explicitness", "style", "formatting", "frontend", "hygiene"],
     }
 }
 
@@ -105,22 +162,26 @@ pub struct XmlBlock {
     pub end: usize,
 }
 
+/// Tags whose content is preserved (not analyzed)
+pub const PRESERVED_TAGS: &[&str] = &[
+    "examples",
+    "example",
+    "input",
+    "output",
+    "context",
+    "background",
+];
+
 /// Extract XML blocks (examples, instructions, etc.) to prevent false positives
+///
+/// Each block is blanked out rather than removed: the cleaned text keeps one
+/// newline per line of the block, so line numbers in issues still point at
+/// the original prompt.
 pub fn extract_xml_blocks(prompt: &str) -> (String, Vec<XmlBlock>) {
     let mut blocks = Vec::new();
     let mut cleaned = prompt.to_string();
 
-    // Tags to extract and preserve (not analyze)
-    let preserve_tags = [
-        "examples",
-        "example",
-        "input",
-        "output",
-        "context",
-        "background",
-    ];
-
-    for tag in preserve_tags {
+    for tag in PRESERVED_TAGS {
         let pattern = format!(r"(?s)<{}>(.*?)</{}>", tag, tag);
         if let Ok(re) = Regex::new(&pattern) {
             for cap in re.captures_iter(prompt) {
@@ -133,16 +194,21 @@ pub fn extract_xml_blocks(prompt: &str) -> (String, Vec<XmlBlock>) {
                     });
                 }
             }
-            // Remove the matched blocks from cleaned text for analysis
-            cleaned = re.replace_all(&cleaned, "").to_string();
+            // Blank the matched blocks out of the cleaned text for analysis
+            cleaned = re
+                .replace_all(&cleaned, |cap: &regex::Captures| {
+                    "\n".repeat(cap[0].matches('\n').count())
+                })
+                .to_string();
         }
     }
 
     (cleaned, blocks)
 }
 
-/// Issue severity level
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Issue severity level, least severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Info,
     Warning,
@@ -150,16 +216,48 @@ pub enum Severity {
 }
 
 /// An issue detected in the prompt
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
     pub id: String,
     pub category: String,
     pub severity: Severity,
     pub message: String,
     pub line: Option<usize>,
+    /// Heuristic strength of the match (0.0-1.0); deterministic rules use 1.0
+    pub confidence: f32,
     pub suggestion: Option<String>,
 }
 
+/// Issues below this confidence are rendered dimmed
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.7;
+
+impl Issue {
+    /// Whether this issue is a weak heuristic match
+    pub fn is_low_confidence(&self) -> bool {
+        self.confidence < LOW_CONFIDENCE_THRESHOLD
+    }
+}
+
+/// Heuristic quality score (0-100) derived from detected issues
+///
+/// Each issue deducts points by severity, weighted by its confidence, so a
+/// handful of low-confidence info hints barely moves the score.
+pub fn quality_score(issues: &[Issue]) -> u8 {
+    let penalty: f32 = issues
+        .iter()
+        .map(|i| {
+            let weight = match i.severity {
+                Severity::Error => 15.0,
+                Severity::Warning => 8.0,
+                Severity::Info => 3.0,
+            };
+            weight * i.confidence
+        })
+        .sum();
+
+    (100.0 - penalty).clamp(0.0, 100.0).round() as u8
+}
+
 /// All available rule categories (used when explicit category check is requested)
 #[allow(dead_code)]
 pub const CATEGORIES: &[&str] = &[
@@ -171,10 +269,12 @@ pub const CATEGORIES: &[&str] = &[
     "agentic",
     "long_horizon",
     "frontend",
+    "hygiene",
+    language::CATEGORY,
 ];
 
 /// Analyze a prompt and return detected issues
-pub fn analyze(prompt: &str, check_categories: Option<&[String]>) -> Result<Vec<Issue>> {
+pub fn analyze(prompt: &str, check_categories: Option<&[String]>) -> Result<Vec<Issue>, CoptError> {
     let mut issues = Vec::new();
 
     // Extract XML blocks to prevent false positives from examples
@@ -184,61 +284,324 @@ pub fn analyze(prompt: &str, check_categories: Option<&[String]>) -> Result<Vec<
     let prompt_type = classify_prompt(prompt);
 
     // Determine which categories to check
-    let categories_to_check: Vec<&str> = match check_categories {
-        Some(cats) => cats.iter().map(|s| s.as_str()).collect(),
-        None => {
-            // Use context-aware categories based on prompt type
-            get_applicable_categories(prompt_type)
-        }
-    };
+    let categories_to_check = selected_categories(prompt_type, check_categories);
+
+    tracing::debug!(
+        prompt_type = ?prompt_type,
+        categories = categories_to_check.len(),
+        "analysis started"
+    );
 
     // Run all applicable analyzers on cleaned prompt (without XML blocks)
     for category in categories_to_check {
+        let started = std::time::Instant::now();
+        let before = issues.len();
         match category {
-            "explicitness" => issues.extend(analyze_explicitness(&cleaned_prompt, prompt_type)),
+            "explicitness" => {
+                issues.extend(analyze_explicitness(&cleaned_prompt, prompt_type));
+                // Task specificity also weighs context that cleaning strips
+                issues.extend(analyze_vague_task(&cleaned_prompt, prompt));
+                // Line numbers point into the prompt as written
+                issues.extend(references::analyze_references(prompt));
+            }
             "style" => issues.extend(analyze_style(&cleaned_prompt)),
             "tools" => issues.extend(analyze_tools(&cleaned_prompt)),
-            "formatting" => issues.extend(analyze_formatting(&cleaned_prompt)),
+            "formatting" => {
+                issues.extend(analyze_formatting(&cleaned_prompt));
+                // Structure rules need the XML tags that cleaning strips
+                issues.extend(analyze_structure(prompt));
+                issues.extend(reachability::analyze_reachability(prompt));
+            }
             "verbosity" => issues.extend(analyze_verbosity(&cleaned_prompt)),
             "agentic" => issues.extend(analyze_agentic(&cleaned_prompt)),
             "long_horizon" => issues.extend(analyze_long_horizon(&cleaned_prompt)),
             "frontend" => issues.extend(analyze_frontend(&cleaned_prompt)),
+            // Hygiene problems can hide anywhere, including inside examples
+            "hygiene" => issues.extend(analyze_hygiene(prompt)),
+            language::CATEGORY => issues.extend(language::analyze_language(&cleaned_prompt)),
             _ => {} // Unknown category, skip
         }
+        let elapsed = started.elapsed();
+        crate::utils::profile::record("analyze", category, elapsed);
+        tracing::debug!(
+            category,
+            issues = issues.len() - before,
+            elapsed_us = elapsed.as_micros() as u64,
+            "analyzed"
+        );
+    }
+
+    // Team rule pack (rules.remote), if one is installed
+    if let Some(pack) = rule_pack::active() {
+        let started = std::time::Instant::now();
+        let before = issues.len();
+        issues.extend(pack.analyze(&cleaned_prompt, check_categories));
+        crate::utils::profile::record("analyze", "rule pack", started.elapsed());
+        tracing::debug!(
+            category = "rule pack",
+            issues = issues.len() - before,
+            "analyzed"
+        );
     }
 
+    issues.retain(|issue| !is_disabled(issue));
+
     Ok(issues)
 }
 
-/// Analyze for explicitness issues (EXP001-006)
-fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
+/// Rules and categories turned off in the config
+#[derive(Debug, Default)]
+struct DisabledRules {
+    rules: Vec<String>,
+    categories: Vec<String>,
+}
+
+static DISABLED: OnceLock<DisabledRules> = OnceLock::new();
+
+/// Never report `rules` or rules in `categories` (`[rules] disabled` and
+/// `disabled_categories` in the config)
+///
+/// Categories are names like `frontend` or rule ID prefixes like `FED`.
+/// Categories the organization policy requires are never disabled. Only the
+/// first call takes effect.
+pub fn configure_disabled(rules: Vec<String>, categories: Vec<String>) {
+    let _ = DISABLED.set(DisabledRules { rules, categories });
+}
+
+/// Whether the config turned off the rule behind `issue`
+pub fn is_disabled(issue: &Issue) -> bool {
+    let required = REQUIRED_CATEGORIES
+        .get()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    DISABLED
+        .get()
+        .is_some_and(|disabled| disabled.covers(issue, required))
+}
+
+impl DisabledRules {
+    fn covers(&self, issue: &Issue, required: &[&str]) -> bool {
+        if required.contains(&issue.category.as_str()) {
+            return false;
+        }
+        self.rules.iter().any(|rule| rule == &issue.id)
+            || self.categories.iter().any(|category| {
+                category == &issue.category
+                    || (category.len() == 3
+                        && issue
+                            .id
+                            .get(..3)
+                            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(category)))
+            })
+    }
+}
+
+/// Rule IDs and categories the config turned off, for telling the LLM to
+/// leave them alone
+pub fn disabled() -> (&'static [String], &'static [String]) {
+    match DISABLED.get() {
+        Some(disabled) => (&disabled.rules, &disabled.categories),
+        None => (&[], &[]),
+    }
+}
+
+/// Categories to run: the explicit `--check` list, or those applicable to `prompt_type`
+fn selected_categories(prompt_type: PromptType, check_categories: Option<&[String]>) -> Vec<&str> {
+    let selected = match check_categories {
+        Some(cats) => cats.iter().map(|s| s.as_str()).collect(),
+        None => {
+            // Use context-aware categories based on prompt type
+            let mut categories = get_applicable_categories(prompt_type);
+            // Language rules are opt-in (language.enabled or --check language)
+            if language::enabled() {
+                categories.push(language::CATEGORY);
+            }
+            categories
+        }
+    };
+    with_required(
+        selected,
+        REQUIRED_CATEGORIES
+            .get()
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+    )
+}
+
+/// Categories an explicit `--check` runs, including any the organization
+/// policy requires
+pub fn checked_categories(check_categories: &[String]) -> Vec<&str> {
+    with_required(
+        check_categories.iter().map(String::as_str).collect(),
+        REQUIRED_CATEGORIES
+            .get()
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+    )
+}
+
+/// Categories the organization policy requires in every analysis
+static REQUIRED_CATEGORIES: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+/// Always check `categories`, even when `--check` names others or the
+/// prompt type would skip them (`[rules] required_categories` in the policy)
+///
+/// Unknown names are ignored. Only the first call takes effect.
+pub fn require_categories(categories: &[String]) {
+    let required = CATEGORIES
+        .iter()
+        .copied()
+        .filter(|category| categories.iter().any(|c| c == category))
+        .collect();
+    let _ = REQUIRED_CATEGORIES.set(required);
+}
+
+/// `selected` plus any `required` category it is missing
+fn with_required<'a>(mut selected: Vec<&'a str>, required: &[&'static str]) -> Vec<&'a str> {
+    for category in required {
+        if !selected.contains(category) {
+            selected.push(category);
+        }
+    }
+    selected
+}
+
+/// Scale confidence by how close a measurement is to its strongest signal
+///
+/// Returns 1.0 at (or beyond) `strongest` and falls linearly to 0.5 at
+/// `threshold`, the point where the rule stops firing.
+fn proximity_confidence(value: usize, strongest: usize, threshold: usize) -> f32 {
+    if strongest == threshold {
+        return 1.0;
+    }
+    let span = strongest.abs_diff(threshold) as f32;
+    let distance = value.abs_diff(strongest) as f32;
+    let beyond = if strongest < threshold {
+        value <= strongest
+    } else {
+        value >= strongest
+    };
+    if beyond {
+        return 1.0;
+    }
+    (1.0 - 0.5 * (distance / span)).clamp(0.5, 1.0)
+}
+
+/// Generic verbs that open an underspecified task ("Build a dashboard")
+const GENERIC_TASK_VERBS: &[&str] = &[
+    "create",
+    "build",
+    "make",
+    "write",
+    "implement",
+    "design",
+    "develop",
+    "add",
+    "fix",
+    "update",
+];
+
+/// Task blocks with this many words carry enough detail on their own
+const DETAILED_TASK_WORDS: usize = 20;
+
+/// Words of surrounding material that count as rich context for a short task
+const RICH_CONTEXT_WORDS: usize = 40;
+
+/// Analyze for vague tasks (EXP001)
+///
+/// Scores each task block — a paragraph opening with a generic verb — as a
+/// whole rather than line by line. Specificity comes from the block itself
+/// (length, measurable criteria, constraints) and from the rest of the raw
+/// prompt, so a terse task backed by a `<context>` section is not flagged.
+fn analyze_vague_task(prompt: &str, raw_prompt: &str) -> Vec<Issue> {
+    static MEASURABLE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)\d|%|\b(?:at least|at most|no more than|fewer than|exactly|within|under|up to|per|every|each|done when|success)\b",
+        )
+        .unwrap()
+    });
+    static CONSTRAINT: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)\b(?:using|with|without|only|must|should|so that|that|which|for|including|include|supports?|handles?)\b",
+        )
+        .unwrap()
+    });
+
     let mut issues = Vec::new();
     let lines: Vec<&str> = prompt.lines().collect();
+    let raw_words = raw_prompt.split_whitespace().count();
 
-    // EXP001: Vague instructions (short imperatives without detail)
-    let vague_patterns = Regex::new(
-        r"(?i)^(create|build|make|write|implement|design|develop|add|fix|update)\s+(?:a\s+|an\s+|the\s+)?[\w\s]{1,20}$"
-    ).unwrap();
+    let mut idx = 0;
+    while idx < lines.len() {
+        if lines[idx].trim().is_empty() {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        while idx < lines.len() && !lines[idx].trim().is_empty() {
+            idx += 1;
+        }
 
-    for (idx, line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-        if vague_patterns.is_match(trimmed) && trimmed.split_whitespace().count() < 8 {
-            issues.push(Issue {
-                id: "EXP001".to_string(),
-                category: "explicitness".to_string(),
-                severity: Severity::Warning,
-                message: format!("Vague instruction: \"{}\"", trimmed),
-                line: Some(idx + 1),
-                suggestion: Some(
-                    "Add specific details, features, and success criteria. \
-                    For example: \"Include as many relevant features as possible. \
-                    Go beyond the basics to create a fully-featured implementation.\""
-                        .to_string(),
-                ),
-            });
+        let block = lines[start..idx]
+            .iter()
+            .map(|l| l.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let first_word = block
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if !GENERIC_TASK_VERBS.contains(&first_word.as_str()) {
+            continue;
         }
+
+        let task_words = block.split_whitespace().count();
+        let context_words = raw_words.saturating_sub(task_words);
+        let specificity = usize::from(task_words >= DETAILED_TASK_WORDS)
+            + usize::from(MEASURABLE.is_match(&block))
+            + usize::from(CONSTRAINT.is_match(&block))
+            + match context_words {
+                n if n >= RICH_CONTEXT_WORDS => 2,
+                n if n >= RICH_CONTEXT_WORDS / 2 => 1,
+                _ => 0,
+            };
+
+        if specificity > 1 {
+            continue;
+        }
+
+        let excerpt: String = if block.chars().count() > 60 {
+            format!("{}…", block.chars().take(59).collect::<String>())
+        } else {
+            block.clone()
+        };
+        issues.push(Issue {
+            id: "EXP001".to_string(),
+            category: "explicitness".to_string(),
+            severity: Severity::Warning,
+            message: format!("Vague instruction: \"{}\"", excerpt),
+            line: Some(start + 1),
+            confidence: (proximity_confidence(task_words, 3, DETAILED_TASK_WORDS)
+                - 0.15 * specificity as f32)
+                .clamp(0.5, 1.0),
+            suggestion: Some(
+                "Add specific details, features, and success criteria. \
+                For example: \"Include as many relevant features as possible. \
+                Go beyond the basics to create a fully-featured implementation.\""
+                    .to_string(),
+            ),
+        });
     }
 
+    issues
+}
+
+/// Analyze for explicitness issues (EXP002-006; EXP001 is in `analyze_vague_task`)
+fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let lines: Vec<&str> = prompt.lines().collect();
+
     // EXP003: Indirect commands (Can you... / Could you...)
     let indirect_pattern =
         Regex::new(r"(?i)\b(can you|could you|would you|would you mind|is it possible to|i was wondering if)\b").unwrap();
@@ -252,6 +615,7 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
                 message: "Indirect command detected - Claude 4.5 may suggest rather than act"
                     .to_string(),
                 line: Some(idx + 1),
+                confidence: 1.0,
                 suggestion: Some(
                     "Use direct commands instead. Replace \"Can you...\" with imperative verbs."
                         .to_string(),
@@ -264,7 +628,8 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
     let bare_prohibition = Regex::new(r"(?i)^(always|never|don't|do not)\s+\w+[^.]*\.?$").unwrap();
     for (idx, line) in lines.iter().enumerate() {
         let trimmed = line.trim();
-        if bare_prohibition.is_match(trimmed) && trimmed.split_whitespace().count() < 10 {
+        let word_count = trimmed.split_whitespace().count();
+        if bare_prohibition.is_match(trimmed) && word_count < 10 {
             // Check if there's explanation nearby
             let has_context = lines
                 .get(idx + 1)
@@ -278,6 +643,7 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
                     severity: Severity::Info,
                     message: "Prohibition without context or motivation".to_string(),
                     line: Some(idx + 1),
+                    confidence: proximity_confidence(word_count, 3, 10),
                     suggestion: Some(
                         "Add context explaining why this rule exists to help Claude generalize."
                             .to_string(),
@@ -308,6 +674,7 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Complex task may benefit from explicit success criteria".to_string(),
                 line: None,
+                confidence: 0.6,
                 suggestion: Some(
                     "Define what constitutes successful completion of this task.".to_string(),
                 ),
@@ -339,6 +706,7 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
                 severity: Severity::Warning,
                 message: "Role-only prompt without specific action directives".to_string(),
                 line: Some(1),
+                confidence: 0.8,
                 suggestion: Some(
                     "Add explicit actions: 'When the user asks about X, respond with Y format.' \
                     Claude 4.5 follows instructions precisely - be specific about what you want."
@@ -369,6 +737,7 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
                 message: "Open-ended instruction without boundaries or format specification"
                     .to_string(),
                 line: None,
+                confidence: 0.8,
                 suggestion: Some(
                     "Specify: What format should responses use? What topics are in scope? \
                     How detailed should answers be? How to handle unknown information?"
@@ -381,6 +750,85 @@ fn analyze_explicitness(prompt: &str, prompt_type: PromptType) -> Vec<Issue> {
     issues
 }
 
+/// Confidence that the "think"/"thinking" at byte `start` of `line` is an
+/// instruction to the model, or `None` if it is quoted, dialogue, first-person,
+/// or a noun
+///
+/// Imperatives at the start of a sentence or clause ("Think about…", "Before
+/// answering, think…") score 1.0; directed uses later in a sentence ("Can
+/// you think…", "take a moment to think…") score 0.9.
+pub(crate) fn directive_think(line: &str, start: usize) -> Option<f32> {
+    static DIALOGUE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)^\s*(?:>|(?:user|customer|human|assistant|ai|q|a|input|output)\s*:)")
+            .unwrap()
+    });
+    static LIST_MARKER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(?:[-*+•]|\d+[.)]|step \d+:)\s*").unwrap());
+    static LEAD_IN: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(?:(?:please|first|then|also|now|and|so|always|carefully|really|next|finally),?\s+)+")
+            .unwrap()
+    });
+    static ADDRESSED: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?:\byou(?:\s+(?:should|must|need to|have to|will|can|could|may|might|would|shall|always|carefully|really|also|to))*|\blet's|\blet us|\bplease)\s*$",
+        )
+        .unwrap()
+    });
+    static CONTINUED: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\b(?:to|and|then)\s*$").unwrap());
+    static FIRST_PERSON: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(?:i|we|i'm|we're|i've|we've|i'd|we'd)\b").unwrap());
+
+    if DIALOGUE.is_match(line) {
+        return None;
+    }
+
+    // Inside a quotation
+    let before = &line[..start];
+    if before.matches('"').count() % 2 == 1
+        || before.matches('“').count() > before.matches('”').count()
+    {
+        return None;
+    }
+
+    // Proper nouns ("Think Tank", "Think Different") and compounds
+    let after = line[start..]
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .trim_matches(|c: char| !c.is_alphanumeric());
+    if after.chars().next().is_some_and(|c| c.is_uppercase())
+        || matches!(
+            after.to_lowercase().as_str(),
+            "tank" | "tanks" | "piece" | "pieces"
+        )
+    {
+        return None;
+    }
+
+    // Text since the start of the sentence
+    let sentence = before
+        .rfind(['.', '!', '?'])
+        .map(|i| &before[i + 1..])
+        .unwrap_or(before)
+        .trim_start()
+        .to_lowercase();
+    let sentence = LIST_MARKER.replace(&sentence, "");
+    let sentence = LEAD_IN.replace(&sentence, "");
+    let trimmed = sentence.trim_end();
+
+    if trimmed.is_empty() || trimmed.ends_with([',', ':', ';']) || trimmed.ends_with(" -") {
+        return Some(1.0);
+    }
+    if ADDRESSED.is_match(trimmed) {
+        return Some(0.9);
+    }
+    if CONTINUED.is_match(trimmed) && !FIRST_PERSON.is_match(trimmed) {
+        return Some(0.9);
+    }
+    None
+}
+
 /// Analyze for style issues (STY001-004)
 fn analyze_style(prompt: &str) -> Vec<Issue> {
     let mut issues = Vec::new();
@@ -389,6 +837,7 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
     // STY001: Negative instructions
     let negative_patterns =
         Regex::new(r"(?i)\b(don't|do not|never|avoid|stop|no\s+\w+ing)\b").unwrap();
+    let strong_negation = Regex::new(r"(?i)\b(don't|do not|never)\b").unwrap();
 
     for (idx, line) in lines.iter().enumerate() {
         if negative_patterns.is_match(line) {
@@ -401,6 +850,11 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
                     severity: Severity::Warning,
                     message: "Negative instruction detected".to_string(),
                     line: Some(idx + 1),
+                    confidence: if strong_negation.is_match(line) {
+                        0.9
+                    } else {
+                        0.6
+                    },
                     suggestion: Some(
                         "Reframe as positive guidance. Instead of \"Don't use X\", \
                         try \"Use Y instead\" or explain what to do."
@@ -418,9 +872,14 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
     ).unwrap();
     let multi_exclaim = Regex::new(r"!{2,}").unwrap();
 
+    let known_acronyms = acronyms::known_acronyms(prompt);
+
     for (idx, line) in lines.iter().enumerate() {
         // Only flag instructional words in ALL CAPS
-        let caps_matches: Vec<_> = instructional_caps.find_iter(line).collect();
+        let caps_matches: Vec<_> = instructional_caps
+            .find_iter(line)
+            .filter(|m| !known_acronyms.contains(m.as_str()))
+            .collect();
 
         if !caps_matches.is_empty() {
             issues.push(Issue {
@@ -432,6 +891,7 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
                     caps_matches.iter().map(|m| m.as_str()).collect::<Vec<_>>().join(", ")
                 ),
                 line: Some(idx + 1),
+                confidence: 1.0,
                 suggestion: Some(
                     "Claude 4.5 follows instructions precisely; aggressive emphasis may cause overtriggering. \
                     Use normal casing.".to_string()
@@ -446,6 +906,7 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Multiple exclamation marks detected".to_string(),
                 line: Some(idx + 1),
+                confidence: 1.0,
                 suggestion: Some(
                     "Reduce emphasis; Claude 4.5 doesn't need emphatic punctuation.".to_string(),
                 ),
@@ -454,16 +915,31 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
     }
 
     // STY003: Word "think" (when extended thinking might be disabled)
-    let think_pattern = Regex::new(r"(?i)\b(think|thinking|think about|think through)\b").unwrap();
+    // Only instructions to the model count; quoted text, dialogue, first-person
+    // opinions ("I think"), and nouns ("think tank") are left alone
+    let think_pattern = Regex::new(r"(?i)\b(?:think|thinking)\b").unwrap();
+    let mut in_fence = false;
 
     for (idx, line) in lines.iter().enumerate() {
-        if think_pattern.is_match(line) {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let confidence = think_pattern
+            .find_iter(line)
+            .filter_map(|m| directive_think(line, m.start()))
+            .reduce(f32::max);
+        if let Some(confidence) = confidence {
             issues.push(Issue {
                 id: "STY003".to_string(),
                 category: "style".to_string(),
                 severity: Severity::Warning,
                 message: "Word \"think\" detected - sensitive in Claude Opus 4.5 without extended thinking".to_string(),
                 line: Some(idx + 1),
+                confidence,
                 suggestion: Some(
                     "Replace with alternatives: \"consider\", \"evaluate\", \"reflect on\", \"work through\".".to_string()
                 ),
@@ -487,6 +963,7 @@ fn analyze_style(prompt: &str) -> Vec<Issue> {
                 trigger_count
             ),
             line: None,
+            confidence: proximity_confidence(trigger_count, 8, 3),
             suggestion: Some(
                 "Claude 4.5 is more responsive; dial back aggressive language. \
                 Simple instructions like \"Use this tool when...\" are sufficient."
@@ -517,6 +994,7 @@ fn analyze_tools(prompt: &str) -> Vec<Issue> {
                 message: "Request for suggestions may result in advice rather than action"
                     .to_string(),
                 line: Some(idx + 1),
+                confidence: 1.0,
                 suggestion: Some(
                     "If you want changes implemented, use direct language: \
                     \"Make these changes\" or \"Implement improvements\"."
@@ -545,6 +1023,7 @@ fn analyze_tools(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Multiple operations without parallel/sequential guidance".to_string(),
                 line: None,
+                confidence: 0.7,
                 suggestion: Some(
                     "Claude 4.5 excels at parallel tool calls. Consider adding: \
                     \"If independent, process in parallel for efficiency.\""
@@ -573,6 +1052,7 @@ fn analyze_tools(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Temporary file creation without cleanup instructions".to_string(),
                 line: None,
+                confidence: 0.7,
                 suggestion: Some(
                     "Add: \"Clean up any temporary files created during this process.\""
                         .to_string(),
@@ -612,6 +1092,7 @@ fn analyze_formatting(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "No explicit format specification for output".to_string(),
                 line: None,
+                confidence: 0.6,
                 suggestion: Some(
                     "Specify desired output format explicitly (prose, markdown, code blocks, etc.)."
                         .to_string(),
@@ -634,6 +1115,7 @@ fn analyze_formatting(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Warning,
                 message: "Negative format instruction detected".to_string(),
                 line: Some(idx + 1),
+                confidence: 1.0,
                 suggestion: Some(
                     "Reframe positively: instead of \"no markdown\", \
                     use \"write in flowing prose paragraphs\"."
@@ -656,6 +1138,7 @@ fn analyze_formatting(prompt: &str) -> Vec<Issue> {
             severity: Severity::Info,
             message: "Complex prompt may benefit from XML tag organization".to_string(),
             line: None,
+            confidence: 0.6,
             suggestion: Some(
                 "Consider using semantic XML tags to structure sections: \
                 <rules>, <examples>, <input>, <output_format>."
@@ -667,6 +1150,65 @@ fn analyze_formatting(prompt: &str) -> Vec<Issue> {
     issues
 }
 
+/// Minimum estimated tokens before a missing output format section is flagged
+const OUTPUT_FORMAT_MIN_TOKENS: usize = 500;
+
+/// Minimum estimated tokens before a missing task section is flagged
+const TASK_SECTION_MIN_TOKENS: usize = 300;
+
+fn analyze_structure(prompt: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let tokens = crate::utils::count_tokens(prompt);
+
+    if tokens < TASK_SECTION_MIN_TOKENS {
+        return issues;
+    }
+
+    let sections = segment::segment_prompt(prompt);
+
+    // FMT004: Long prompt without an output format section
+    if tokens >= OUTPUT_FORMAT_MIN_TOKENS
+        && !segment::has_section(&sections, segment::SectionKind::OutputFormat)
+    {
+        issues.push(Issue {
+            id: "FMT004".to_string(),
+            category: "formatting".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "No output format section in a {}+ token prompt",
+                OUTPUT_FORMAT_MIN_TOKENS
+            ),
+            line: None,
+            confidence: 0.7,
+            suggestion: Some(
+                "Add an <output_format> section describing the shape, length, \
+                and style of the expected response."
+                    .to_string(),
+            ),
+        });
+    }
+
+    // FMT005: Long prompt without an identifiable task
+    if !segment::has_section(&sections, segment::SectionKind::Task) {
+        issues.push(Issue {
+            id: "FMT005".to_string(),
+            category: "formatting".to_string(),
+            severity: Severity::Info,
+            message: "No identifiable task section; the actual request may get lost in context"
+                .to_string(),
+            line: None,
+            confidence: 0.6,
+            suggestion: Some(
+                "State the task in its own section (e.g. <instructions>) \
+                so it stands apart from background and rules."
+                    .to_string(),
+            ),
+        });
+    }
+
+    issues
+}
+
 /// Analyze for verbosity issues (VRB001-002)
 fn analyze_verbosity(prompt: &str) -> Vec<Issue> {
     let mut issues = Vec::new();
@@ -689,6 +1231,7 @@ fn analyze_verbosity(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Complex task without verbosity guidance".to_string(),
                 line: None,
+                confidence: 0.5,
                 suggestion: Some(
                     "Claude 4.5 tends toward efficiency. Add: \"After completing, \
                     provide a brief summary of changes made.\""
@@ -713,6 +1256,7 @@ fn analyze_verbosity(prompt: &str) -> Vec<Issue> {
             severity: Severity::Info,
             message: "Multi-step task without progress reporting guidance".to_string(),
             line: None,
+            confidence: 0.6,
             suggestion: Some(
                 "Consider adding: \"Provide a quick update after each step.\"".to_string(),
             ),
@@ -747,6 +1291,7 @@ fn analyze_agentic(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Warning,
                 message: "Code modification without exploration directive".to_string(),
                 line: None,
+                confidence: 0.7,
                 suggestion: Some(
                     "Add: \"First, read and understand the relevant files before making changes.\""
                         .to_string(),
@@ -772,6 +1317,7 @@ fn analyze_agentic(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Warning,
                 message: "Code question without hallucination prevention".to_string(),
                 line: None,
+                confidence: 0.6,
                 suggestion: Some(
                     "Add: \"Investigate the relevant files before answering. \
                     Do not speculate about code you haven't read.\""
@@ -799,6 +1345,7 @@ fn analyze_agentic(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Complex implementation without state management guidance".to_string(),
                 line: None,
+                confidence: 0.7,
                 suggestion: Some(
                     "Add state tracking: \"Track progress in a progress.txt file. \
                     Use git commits to checkpoint your work.\""
@@ -828,6 +1375,7 @@ fn analyze_agentic(prompt: &str) -> Vec<Issue> {
                 severity: Severity::Info,
                 message: "Open-ended implementation may lead to overengineering".to_string(),
                 line: None,
+                confidence: 0.6,
                 suggestion: Some(
                     "Add: \"Avoid over-engineering. Only implement what's directly needed.\""
                         .to_string(),
@@ -869,6 +1417,7 @@ fn analyze_long_horizon(prompt: &str) -> Vec<Issue> {
             severity: Severity::Warning,
             message: "Long task without state persistence strategy".to_string(),
             line: None,
+            confidence: 0.7,
             suggestion: Some(
                 "Add: \"If context runs low, save your progress and state before continuing.\""
                     .to_string(),
@@ -889,6 +1438,7 @@ fn analyze_long_horizon(prompt: &str) -> Vec<Issue> {
             severity: Severity::Info,
             message: "Large task scope without incremental progress guidance".to_string(),
             line: None,
+            confidence: 0.6,
             suggestion: Some(
                 "Add: \"Work incrementally, completing one component before moving to the next.\""
                     .to_string(),
@@ -909,6 +1459,7 @@ fn analyze_long_horizon(prompt: &str) -> Vec<Issue> {
             severity: Severity::Info,
             message: "Extended task without context window awareness".to_string(),
             line: None,
+            confidence: 0.6,
             suggestion: Some(
                 "Consider adding context awareness instructions for very long tasks.".to_string(),
             ),
@@ -918,19 +1469,71 @@ fn analyze_long_horizon(prompt: &str) -> Vec<Issue> {
     issues
 }
 
-/// Analyze for frontend design issues (FED001-002)
-fn analyze_frontend(prompt: &str) -> Vec<Issue> {
-    let mut issues = Vec::new();
+/// Frontend stack mentioned in a prompt (drives tailored FED guidance)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontendStack {
+    React,
+    Vue,
+    Svelte,
+    Html,
+    Unknown,
+}
+
+impl FrontendStack {
+    /// Human-readable stack name for messages
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FrontendStack::React => "React",
+            FrontendStack::Vue => "Vue",
+            FrontendStack::Svelte => "Svelte",
+            FrontendStack::Html => "HTML/CSS",
+            FrontendStack::Unknown => "Frontend",
+        }
+    }
+}
+
+/// Detect which frontend framework a prompt targets
+///
+/// Component frameworks are checked before plain HTML because React/Vue/Svelte
+/// prompts frequently mention HTML and CSS as well.
+pub fn detect_frontend_stack(prompt: &str) -> FrontendStack {
+    let react = Regex::new(r"(?i)\b(react|next\.?js|jsx|tsx|remix)\b").unwrap();
+    let vue = Regex::new(r"(?i)\b(vue(\.?js)?|nuxt)\b").unwrap();
+    let svelte = Regex::new(r"(?i)\b(svelte(kit)?)\b").unwrap();
+    let html = Regex::new(r"(?i)\b(html5?|css3?|vanilla\s+js|static\s+site)\b").unwrap();
+
+    if react.is_match(prompt) {
+        FrontendStack::React
+    } else if vue.is_match(prompt) {
+        FrontendStack::Vue
+    } else if svelte.is_match(prompt) {
+        FrontendStack::Svelte
+    } else if html.is_match(prompt) {
+        FrontendStack::Html
+    } else {
+        FrontendStack::Unknown
+    }
+}
 
-    // Check if this is a frontend-related prompt
+/// Whether a prompt asks for UI work, which the frontend rules check
+pub fn is_frontend_prompt(prompt: &str) -> bool {
     let frontend_indicators = Regex::new(
-        r"(?i)\b(ui|frontend|page|component|dashboard|form|button|layout|design|css|html|react|vue|web)\b"
+        r"(?i)\b(ui|frontend|page|component|dashboard|form|button|layout|design|css|html|react|vue|svelte|web)\b"
     ).unwrap();
+    frontend_indicators.is_match(prompt)
+}
+
+/// Analyze for frontend design issues (FED001-005)
+fn analyze_frontend(prompt: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
 
-    if !frontend_indicators.is_match(prompt) {
+    if !is_frontend_prompt(prompt) {
         return issues;
     }
 
+    let stack = detect_frontend_stack(prompt);
+
     // FED001: Generic UI request without aesthetic guidance
     let ui_creation =
         Regex::new(r"(?i)\b(create|build|make|design)\b.*\b(ui|page|component|form|dashboard)\b")
@@ -952,6 +1555,7 @@ fn analyze_frontend(prompt: &str) -> Vec<Issue> {
                 message: "UI request without aesthetic guidance may result in generic design"
                     .to_string(),
                 line: None,
+                confidence: 0.6,
                 suggestion: Some(
                     "Add design guidance: \"Create a distinctive, creative design. \
                     Avoid generic 'AI slop' aesthetics.\""
@@ -976,12 +1580,306 @@ fn analyze_frontend(prompt: &str) -> Vec<Issue> {
             severity: Severity::Info,
             message: "Frontend request without specific design guidance".to_string(),
             line: None,
+            confidence: 0.5,
             suggestion: Some(
                 "Consider specifying typography, color scheme, and motion preferences.".to_string(),
             ),
         });
     }
 
+    // Remaining rules only apply to requests that create UI
+    if !ui_creation.is_match(prompt) {
+        return issues;
+    }
+
+    // FED003: Missing responsive/accessibility requirements
+    // Whole words, so "automobile" isn't mobile
+    static RESPONSIVE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)\b(?:responsive|mobile|breakpoints?|screen sizes?)\b").unwrap()
+    });
+    let has_responsive = RESPONSIVE.is_match(prompt);
+    // Whole words, so "variety" isn't ARIA
+    static ACCESSIBILITY: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)\b(?:accessib\w*|a11y|aria|wcag|screen readers?|keyboard)\b").unwrap()
+    });
+    let has_accessibility = ACCESSIBILITY.is_match(prompt);
+
+    if !has_responsive || !has_accessibility {
+        let missing = match (has_responsive, has_accessibility) {
+            (false, false) => "responsive or accessibility",
+            (false, true) => "responsive",
+            _ => "accessibility",
+        };
+        let suggestion = match stack {
+            FrontendStack::React => {
+                "Add: \"Make the layout responsive across mobile and desktop breakpoints. \
+                Use semantic JSX elements, ARIA attributes, and full keyboard navigation.\""
+            }
+            FrontendStack::Vue => {
+                "Add: \"Make templates responsive across mobile and desktop breakpoints. \
+                Use semantic elements, bind ARIA attributes, and support keyboard navigation.\""
+            }
+            FrontendStack::Svelte => {
+                "Add: \"Make the layout responsive across breakpoints and resolve Svelte's \
+                a11y compiler warnings (labels, ARIA roles, keyboard handlers).\""
+            }
+            FrontendStack::Html | FrontendStack::Unknown => {
+                "Add: \"Use a mobile-first responsive layout and meet WCAG 2.1 AA: \
+                semantic HTML, sufficient contrast, visible focus, and keyboard navigation.\""
+            }
+        };
+        issues.push(Issue {
+            id: "FED003".to_string(),
+            category: "frontend".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "{} UI request without {} requirements",
+                stack.display_name(),
+                missing
+            ),
+            line: None,
+            confidence: 0.8,
+            suggestion: Some(suggestion.to_string()),
+        });
+    }
+
+    // FED004: Missing component-structure guidance
+    // Whole words, so "storefront" and "restore" aren't a store
+    static STRUCTURE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)\b(?:props|state management|hooks|composables?|stores?|slots?|reusable|split into|break into|file structure|folder structure)\b",
+        )
+        .unwrap()
+    });
+    let has_structure = STRUCTURE.is_match(prompt);
+
+    if !has_structure && stack != FrontendStack::Unknown {
+        let suggestion = match stack {
+            FrontendStack::React => {
+                "Describe the component breakdown: which components to create, their props, \
+                and where state lives (hooks, context, or a store)."
+            }
+            FrontendStack::Vue => {
+                "Describe the single-file components to create, their props/emits, \
+                and whether to use the Composition API or a Pinia store for shared state."
+            }
+            FrontendStack::Svelte => {
+                "Describe the components to create, their props, \
+                and which shared state belongs in Svelte stores."
+            }
+            FrontendStack::Html | FrontendStack::Unknown => {
+                "Describe the page structure (header, nav, main, footer) \
+                and how HTML, CSS, and JavaScript should be organized into files."
+            }
+        };
+        issues.push(Issue {
+            id: "FED004".to_string(),
+            category: "frontend".to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "{} request without component structure guidance",
+                stack.display_name()
+            ),
+            line: None,
+            confidence: 0.7,
+            suggestion: Some(suggestion.to_string()),
+        });
+    }
+
+    // FED005: Missing interaction-state specifications
+    // Whole words, so "unloading" isn't a loading state
+    static INTERACTION_STATES: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)\b(?:hover|focus|disabled|loading|empty states?|error states?|skeletons?)\b",
+        )
+        .unwrap()
+    });
+    let has_interaction_states = INTERACTION_STATES.is_match(prompt);
+
+    if !has_interaction_states {
+        issues.push(Issue {
+            id: "FED005".to_string(),
+            category: "frontend".to_string(),
+            severity: Severity::Info,
+            message: "UI request without interaction-state specifications".to_string(),
+            line: None,
+            confidence: 0.6,
+            suggestion: Some(
+                "Specify interaction states: hover, focus, active, disabled, \
+                plus loading, empty, and error states for data-driven views."
+                    .to_string(),
+            ),
+        });
+    }
+
+    issues
+}
+
+/// Invisible characters that are never intentional in a prompt: zero-width
+/// spaces and joiners, word joiner, soft hyphen, a BOM past the start of the
+/// text, and bidirectional embedding/isolate controls
+///
+/// Zero-width (non-)joiners between two non-ASCII characters are left out:
+/// they shape emoji sequences ("👩‍💻") and words in Persian and Indic
+/// scripts ("می‌خواهم").
+pub(crate) fn invisible_chars(text: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let joins = |c: Option<char>| c.is_some_and(|c| !c.is_ascii() && !c.is_whitespace());
+    text.char_indices().filter(move |&(i, c)| match c {
+        '\u{200C}' | '\u{200D}' => {
+            let before = text[..i].chars().next_back();
+            let after = text[i + c.len_utf8()..].chars().next();
+            !(joins(before) && joins(after))
+        }
+        _ => matches!(
+            c,
+            '\u{200B}'
+                | '\u{2060}'
+                | '\u{FEFF}'
+                | '\u{00AD}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2066}'..='\u{2069}'
+        ),
+    })
+}
+
+/// Blank lines in a row before HYG005 fires
+const MAX_CONSECUTIVE_BLANK_LINES: usize = 2;
+
+/// Analyze for whitespace and encoding hygiene issues (HYG001-005)
+///
+/// Runs on the raw prompt: these are byte-level problems that XML cleaning
+/// would hide.
+fn analyze_hygiene(prompt: &str) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let body = prompt.strip_prefix('\u{FEFF}').unwrap_or(prompt);
+
+    // HYG001: Mixed line endings
+    let crlf = body.matches("\r\n").count();
+    let lf = body.matches('\n').count() - crlf;
+    if crlf > 0 && lf > 0 {
+        issues.push(Issue {
+            id: "HYG001".to_string(),
+            category: "hygiene".to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "Mixed line endings ({} CRLF, {} LF); usually a sign of pasted fragments",
+                crlf, lf
+            ),
+            line: None,
+            confidence: 1.0,
+            suggestion: Some("Normalize all line endings to LF.".to_string()),
+        });
+    }
+
+    let lines: Vec<&str> = body.lines().collect();
+
+    // HYG002: Trailing whitespace
+    let trailing: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.len() != line.trim_end().len())
+        .map(|(idx, _)| idx + 1)
+        .collect();
+    if let Some(&first) = trailing.first() {
+        let wasted: usize = lines
+            .iter()
+            .map(|line| line.len() - line.trim_end().len())
+            .sum();
+        issues.push(Issue {
+            id: "HYG002".to_string(),
+            category: "hygiene".to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "Trailing whitespace on {} line{} ({} wasted characters)",
+                trailing.len(),
+                if trailing.len() == 1 { "" } else { "s" },
+                wasted
+            ),
+            line: Some(first),
+            confidence: proximity_confidence(trailing.len(), 5, 1),
+            suggestion: Some("Strip whitespace from the end of each line.".to_string()),
+        });
+    }
+
+    // HYG003: Zero-width and other invisible characters
+    let mut invisible: Vec<char> = Vec::new();
+    let mut first_line = None;
+    for (idx, line) in lines.iter().enumerate() {
+        for (_, c) in invisible_chars(line) {
+            first_line.get_or_insert(idx + 1);
+            invisible.push(c);
+        }
+    }
+    if !invisible.is_empty() {
+        let mut codes: Vec<String> = invisible
+            .iter()
+            .map(|c| format!("U+{:04X}", *c as u32))
+            .collect();
+        codes.sort();
+        codes.dedup();
+        issues.push(Issue {
+            id: "HYG003".to_string(),
+            category: "hygiene".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "{} invisible Unicode character{} ({}) can change how the model reads the text",
+                invisible.len(),
+                if invisible.len() == 1 { "" } else { "s" },
+                codes.join(", ")
+            ),
+            line: first_line,
+            confidence: 1.0,
+            suggestion: Some(
+                "Remove zero-width, soft-hyphen, and bidirectional control characters.".to_string(),
+            ),
+        });
+    }
+
+    // HYG004: Byte order mark
+    if prompt.starts_with('\u{FEFF}') {
+        issues.push(Issue {
+            id: "HYG004".to_string(),
+            category: "hygiene".to_string(),
+            severity: Severity::Info,
+            message: "Prompt starts with a byte order mark (BOM)".to_string(),
+            line: Some(1),
+            confidence: 1.0,
+            suggestion: Some("Save the file as UTF-8 without a BOM.".to_string()),
+        });
+    }
+
+    // HYG005: Excessive blank lines
+    let mut run = 0;
+    let mut runs = 0;
+    let mut first_run = None;
+    for (idx, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            run += 1;
+            if run == MAX_CONSECUTIVE_BLANK_LINES + 1 {
+                runs += 1;
+                first_run.get_or_insert(idx + 2 - run);
+            }
+        } else {
+            run = 0;
+        }
+    }
+    if runs > 0 {
+        issues.push(Issue {
+            id: "HYG005".to_string(),
+            category: "hygiene".to_string(),
+            severity: Severity::Info,
+            message: format!(
+                "{} run{} of {}+ consecutive blank lines",
+                runs,
+                if runs == 1 { "" } else { "s" },
+                MAX_CONSECUTIVE_BLANK_LINES + 1
+            ),
+            line: first_run,
+            confidence: 1.0,
+            suggestion: Some("Collapse runs of blank lines to a single blank line.".to_string()),
+        });
+    }
+
     issues
 }
 
@@ -989,6 +1887,46 @@ fn analyze_frontend(prompt: &str) -> Vec<Issue> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_required_categories_are_always_selected() {
+        let check = vec!["style".to_string()];
+        let selected = with_required(
+            selected_categories(PromptType::General, Some(&check)),
+            &["hygiene", "style"],
+        );
+        assert_eq!(selected, vec!["style", "hygiene"]);
+    }
+
+    #[test]
+    fn test_disabled_rules_and_categories() {
+        let issues = analyze("Can you think about a dashboard? Don't use markdown.", None).unwrap();
+        let ids = |disabled: &DisabledRules, required: &[&str]| -> Vec<String> {
+            issues
+                .iter()
+                .filter(|i| !disabled.covers(i, required))
+                .map(|i| i.id.clone())
+                .collect()
+        };
+        assert_eq!(
+            ids(&DisabledRules::default(), &[]),
+            ["EXP003", "STY001", "STY003"]
+        );
+
+        let by_rule = DisabledRules {
+            rules: vec!["STY003".to_string()],
+            categories: vec!["explicitness".to_string()],
+        };
+        assert_eq!(ids(&by_rule, &[]), ["STY001"]);
+
+        let by_prefix = DisabledRules {
+            rules: Vec::new(),
+            categories: vec!["sty".to_string()],
+        };
+        assert_eq!(ids(&by_prefix, &[]), ["EXP003"]);
+        // The organization policy outranks the user's config
+        assert_eq!(ids(&by_prefix, &["style"]), ["EXP003", "STY001", "STY003"]);
+    }
+
     #[test]
     fn test_detect_vague_instruction() {
         let issues = analyze("Create a dashboard", None).unwrap();
@@ -1013,6 +1951,52 @@ mod tests {
         assert!(issues.iter().any(|i| i.id == "STY003"));
     }
 
+    #[test]
+    fn test_think_in_instruction_position() {
+        let sty003 = |prompt: &str| {
+            analyze_style(prompt)
+                .into_iter()
+                .filter(|i| i.id == "STY003")
+                .map(|i| i.confidence)
+                .collect::<Vec<_>>()
+        };
+
+        // Imperatives and requests directed at the model
+        assert_eq!(sty003("Think about the edge cases."), vec![1.0]);
+        assert_eq!(sty003("- Please think step by step."), vec![1.0]);
+        assert_eq!(sty003("Before answering, think it through."), vec![1.0]);
+        assert_eq!(sty003("Can you think about the edge cases?"), vec![0.9]);
+        assert_eq!(sty003("Take a moment to think before replying."), vec![0.9]);
+        assert_eq!(sty003("Let's think this through."), vec![0.9]);
+    }
+
+    #[test]
+    fn test_think_outside_instruction_position() {
+        let flagged = |prompt: &str| analyze_style(prompt).iter().any(|i| i.id == "STY003");
+
+        // First-person opinion
+        assert!(!flagged("I think the user wants a refund."));
+        assert!(!flagged("We want to think bigger this quarter."));
+        // Quoted or dialogue text
+        assert!(!flagged(
+            "Reply to messages like \"I think my order is lost\" with empathy."
+        ));
+        assert!(!flagged("User: I think it broke. Can you think of a fix?"));
+        // Nouns and proper nouns
+        assert!(!flagged("Summarize the Think Tank report."));
+        assert!(!flagged("Cite the think tank by name."));
+        assert!(!flagged("Show your thinking in a scratchpad."));
+        // Code
+        assert!(!flagged("```\nthink(step)\n```"));
+        // Example blocks are stripped before analysis
+        let issues = analyze(
+            "Classify the message.\n<example>Think about it, I think it broke.</example>",
+            None,
+        )
+        .unwrap();
+        assert!(!issues.iter().any(|i| i.id == "STY003"));
+    }
+
     #[test]
     fn test_detect_suggestion_language() {
         let issues = analyze_tools("Please suggest some changes to improve the code");
@@ -1042,6 +2026,21 @@ mod tests {
         assert!(!blocks.is_empty());
     }
 
+    #[test]
+    fn test_xml_extraction_keeps_line_numbers() {
+        let prompt = "<example>\nCan you help?\n</example>\nCan you fix this?";
+        let (cleaned, _) = extract_xml_blocks(prompt);
+        assert_eq!(cleaned, "\n\n\nCan you fix this?");
+
+        let issues = analyze(prompt, Some(&["explicitness".to_string()])).unwrap();
+        let lines: Vec<_> = issues
+            .iter()
+            .filter(|i| i.id == "EXP003")
+            .map(|i| i.line)
+            .collect();
+        assert_eq!(lines, vec![Some(4)]);
+    }
+
     #[test]
     fn test_prompt_classifier() {
         assert_eq!(
@@ -1058,6 +2057,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_system_prompt_detection() {
+        assert!(is_system_prompt("You are a support agent for Acme."));
+        assert!(is_system_prompt(
+            "<role>\nAs a billing assistant, you help small businesses.\n</role>"
+        ));
+        assert!(is_system_prompt(
+            "Answer briefly. If the user asks about refunds, link the policy."
+        ));
+        assert!(!is_system_prompt("Summarize this quarterly report."));
+        assert!(!is_system_prompt(
+            "Fix the bug in the user authentication flow"
+        ));
+    }
+
     #[test]
     fn test_category_filtering() {
         let prompt = "Can you suggest some changes? Don't use markdown.";
@@ -1070,4 +2084,210 @@ mod tests {
         let tool_issues = analyze(prompt, Some(&["tools".to_string()])).unwrap();
         assert!(tool_issues.iter().all(|i| i.category == "tools"));
     }
+
+    #[test]
+    fn test_detect_frontend_stack() {
+        assert_eq!(
+            detect_frontend_stack("Build a React dashboard with JSX"),
+            FrontendStack::React
+        );
+        assert_eq!(
+            detect_frontend_stack("Create a Nuxt page"),
+            FrontendStack::Vue
+        );
+        assert_eq!(
+            detect_frontend_stack("Make a SvelteKit form"),
+            FrontendStack::Svelte
+        );
+        assert_eq!(
+            detect_frontend_stack("Build a landing page in plain HTML and CSS"),
+            FrontendStack::Html
+        );
+        assert_eq!(
+            detect_frontend_stack("Design a dashboard"),
+            FrontendStack::Unknown
+        );
+    }
+
+    #[test]
+    fn test_frontend_tailored_rules() {
+        let issues = analyze_frontend("Build a React dashboard for sales data");
+        let fed003 = issues.iter().find(|i| i.id == "FED003").unwrap();
+        assert!(fed003.message.starts_with("React"));
+        assert!(fed003.suggestion.as_deref().unwrap().contains("JSX"));
+        assert!(issues.iter().any(|i| i.id == "FED004"));
+        assert!(issues.iter().any(|i| i.id == "FED005"));
+    }
+
+    #[test]
+    fn test_frontend_rules_satisfied() {
+        let prompt = "Build a responsive, accessible React form with ARIA labels. \
+            Split into reusable components with typed props. \
+            Include hover, focus, disabled, and loading states.";
+        let issues = analyze_frontend(prompt);
+        assert!(!issues.iter().any(|i| i.id == "FED003"));
+        assert!(!issues.iter().any(|i| i.id == "FED004"));
+        assert!(!issues.iter().any(|i| i.id == "FED005"));
+    }
+
+    #[test]
+    fn test_frontend_keywords_match_whole_words() {
+        let issues = analyze_frontend(
+            "Build a responsive React storefront page that shows a variety of products",
+        );
+        assert!(issues.iter().any(|i| i.id == "FED003"));
+        assert!(issues.iter().any(|i| i.id == "FED004"));
+
+        let issues = analyze_frontend(
+            "Build an accessible React page listing automobile parts for unloading docks",
+        );
+        let fed003 = issues.iter().find(|i| i.id == "FED003").unwrap();
+        assert!(fed003.message.contains("without responsive requirements"));
+        assert!(issues.iter().any(|i| i.id == "FED005"));
+    }
+
+    #[test]
+    fn test_frontend_structure_rule_needs_known_stack() {
+        let issues = analyze_frontend("Design a dashboard");
+        assert!(!issues.iter().any(|i| i.id == "FED004"));
+    }
+
+    #[test]
+    fn test_proximity_confidence() {
+        assert_eq!(proximity_confidence(2, 2, 8), 1.0);
+        assert_eq!(proximity_confidence(1, 2, 8), 1.0);
+        assert!((proximity_confidence(5, 2, 8) - 0.75).abs() < f32::EPSILON);
+        assert_eq!(proximity_confidence(10, 8, 3), 1.0);
+        assert!(proximity_confidence(4, 8, 3) < 0.7);
+    }
+
+    #[test]
+    fn test_vague_task_spans_lines() {
+        let issues = analyze_vague_task("Build a website\nfor my shop.", "");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(1));
+        assert_eq!(
+            issues[0].message,
+            "Vague instruction: \"Build a website for my shop.\""
+        );
+    }
+
+    #[test]
+    fn test_specific_task_not_vague() {
+        // Measurable criteria and constraints in the task itself
+        let specific = "Write a Python script that retries failed uploads at most 3 times \
+            with exponential backoff.";
+        assert!(analyze_vague_task(specific, specific).is_empty());
+
+        // A terse task backed by rich context elsewhere in the prompt
+        let context = "Our billing service runs on Postgres 15 and stores invoices per \
+            customer. Finance reconciles invoices nightly and needs every adjustment \
+            audited with the operator's name and reason. Refunds above 500 EUR require \
+            a second approver before they are posted.";
+        let prompt = format!("<context>\n{}\n</context>\n\nWrite the migration.", context);
+        let (cleaned, _) = extract_xml_blocks(&prompt);
+        assert!(analyze_vague_task(&cleaned, &prompt).is_empty());
+
+        // Without the context, the same task is vague
+        assert_eq!(
+            analyze_vague_task("Write the migration.", "Write the migration.").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_vague_instruction_confidence_scales_with_length() {
+        let short = analyze("Create a dashboard", None).unwrap();
+        let short_exp = short.iter().find(|i| i.id == "EXP001").unwrap();
+
+        let longer = analyze("Create a sales dashboard now", None).unwrap();
+        let longer_exp = longer.iter().find(|i| i.id == "EXP001").unwrap();
+
+        assert!(short_exp.confidence > longer_exp.confidence);
+        assert!(!short_exp.is_low_confidence());
+    }
+
+    #[test]
+    fn test_confidence_in_range() {
+        let prompt = "Build a React dashboard. NEVER use inline styles!!\nCan you think about it?";
+        for issue in analyze(prompt, None).unwrap() {
+            assert!(
+                (0.0..=1.0).contains(&issue.confidence),
+                "{} has confidence {}",
+                issue.id,
+                issue.confidence
+            );
+        }
+    }
+
+    #[test]
+    fn test_quality_score() {
+        assert_eq!(quality_score(&[]), 100);
+
+        let weak = analyze("Write a poem about autumn leaves in the park", None).unwrap();
+        let poor = analyze(
+            "Can you think about this?\nNEVER use jargon!!\nDon't be vague.",
+            None,
+        )
+        .unwrap();
+        assert!(quality_score(&poor) < quality_score(&weak));
+    }
+
+    #[test]
+    fn test_missing_section_rules() {
+        let background = "Our platform serves retail customers across several regions. ".repeat(60);
+        let prompt = format!("{}\n\nSummarize the refund policy for agents.", background);
+        let issues = analyze(&prompt, Some(&["formatting".to_string()])).unwrap();
+        assert!(issues.iter().any(|i| i.id == "FMT004"));
+        assert!(!issues.iter().any(|i| i.id == "FMT005"));
+
+        let structured = format!(
+            "<context>\n{}\n</context>\n\n<output_format>\nThree bullet points.\n</output_format>",
+            background
+        );
+        let issues = analyze(&structured, Some(&["formatting".to_string()])).unwrap();
+        assert!(!issues.iter().any(|i| i.id == "FMT004"));
+        assert!(issues.iter().any(|i| i.id == "FMT005"));
+
+        let short = analyze("Summarize the policy.", Some(&["formatting".to_string()])).unwrap();
+        assert!(!short.iter().any(|i| i.id == "FMT004" || i.id == "FMT005"));
+    }
+
+    #[test]
+    fn test_hygiene_rules() {
+        let prompt =
+            "\u{FEFF}Summarize the report.  \r\nUse\u{200B} bullet points.\n\n\n\nKeep it short.";
+        let issues = analyze_hygiene(prompt);
+        let ids: Vec<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["HYG001", "HYG002", "HYG003", "HYG004", "HYG005"]);
+        assert!(issues[2].message.contains("U+200B"));
+        assert_eq!(issues[2].line, Some(2));
+        assert_eq!(issues[4].line, Some(3));
+
+        // The leading BOM is HYG004's alone
+        let bom_only = analyze_hygiene("\u{FEFF}Summarize the report.");
+        assert_eq!(bom_only.len(), 1);
+        assert_eq!(bom_only[0].id, "HYG004");
+
+        assert!(analyze_hygiene("Summarize the report.\n\nKeep it short.\n").is_empty());
+    }
+
+    #[test]
+    fn test_joiners_in_emoji_and_scripts_are_kept() {
+        for prompt in [
+            "Sign off with 👩\u{200D}💻.",
+            "Greet the user with \"می\u{200C}خواهم\".",
+        ] {
+            assert!(analyze_hygiene(prompt).is_empty(), "{}", prompt);
+        }
+        let issues = analyze_hygiene("Use\u{200C} bullet points.");
+        assert!(issues[0].message.contains("U+200C"));
+    }
+
+    #[test]
+    fn test_hygiene_runs_on_raw_prompt() {
+        let prompt = "Classify the ticket.\n<examples>\nRefund\u{200D} request\n</examples>";
+        let issues = analyze(prompt, None).unwrap();
+        assert!(issues.iter().any(|i| i.id == "HYG003"));
+    }
 }
@@ -0,0 +1,370 @@
+//! Sections and instructions the model can never act on
+//!
+//! Long prompts collect material that no longer connects to the task: an
+//! `<examples>` block demonstrating a tool the prompt later says is
+//! unavailable, or a custom `<product_notes>` section nothing ever points
+//! at (FMT006). They also collect instructions the declared output channel
+//! rules out, such as "ask a clarifying question" in a prompt whose JSON is
+//! parsed by a program, or "use a table" for a reply read aloud (FMT007).
+//! Both cost tokens and pull the model in two directions.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::segment::{self, Section, SectionKind, SectionSource};
+use super::{Issue, Severity};
+
+/// Where the response goes, when the prompt says
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    /// Parsed by a program
+    Api,
+    /// Spoken to the user
+    Voice,
+}
+
+impl Channel {
+    fn description(self) -> &'static str {
+        match self {
+            Channel::Api => "the response is parsed by a program",
+            Channel::Voice => "the response is spoken aloud",
+        }
+    }
+}
+
+static API_CHANNEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?:your|the) (?:response|output|reply|answer) (?:will be|is|gets) (?:parsed|consumed|read|processed|ingested) (?:by|with) (?:a |an |our |the |another )?(?:program|script|parser|api|system|service|code|pipeline|machine)|(?:respond|reply|answer) (?:only )?(?:with|in) (?:valid |raw |a single )?json|(?:return|output) only (?:valid |raw )?json|no (?:prose|text|commentary) (?:outside|besides|around|other than) (?:the )?json|machine[- ]readable)",
+    )
+    .unwrap()
+});
+
+static VOICE_CHANNEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:read aloud|spoken aloud|text[- ]to[- ]speech|converted to speech|voice (?:assistant|interface|channel|agent)|over the phone|phone call)\b",
+    )
+    .unwrap()
+});
+
+/// Visual formatting asked for as an instruction ("use a table")
+static VISUAL_FORMATTING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:use|include|add|show|with|format (?:\w+ )?(?:as|with|in)|present (?:\w+ )?(?:as|in))\s+(?:a |an |the )?(?:markdown|tables?|bullet(?:ed)? (?:points|lists?)|numbered lists?|code blocks?|headers|headings|bold(?: text)?|emojis?|(?:hyper)?links)\b",
+    )
+    .unwrap()
+});
+
+/// Talking to the person on the other end
+static CONVERSATIONAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:ask (?:the (?:user|customer) )?(?:a |any )?(?:clarifying |follow[- ]up )?questions?|ask the (?:user|customer)|greet (?:the )?(?:user|customer)|say hello|offer (?:further|more) help|end (?:with|by asking) a question)\b",
+    )
+    .unwrap()
+});
+
+static NEGATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:not|never|no|avoid|don't|without|instead of)\b").unwrap()
+});
+
+/// "You don't have access to `web_search`", "never call delete_records"
+static UNAVAILABLE_BEFORE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:you (?:do not|don't|no longer) have(?: access to)?|you (?:cannot|can't|can no longer) (?:use|call|access)|(?:do not|don't|never) (?:use|call|invoke)|(?:without|no) access to)\s+(?:the\s+)?(`[^`\s]+`|[A-Za-z_][\w.-]*)(\s+(?:tool|function))?",
+    )
+    .unwrap()
+});
+
+/// "`web_search` is unavailable", "the lookup tool is disabled"
+static UNAVAILABLE_AFTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(`[^`\s]+`|\b[A-Za-z_][\w.-]*)(\s+(?:tool|function))?\s+(?:is|are)\s+(?:not available|unavailable|disabled|deprecated|removed|no longer available|turned off)\b",
+    )
+    .unwrap()
+});
+
+/// A tool the prompt says the model can't use
+#[derive(Debug, PartialEq)]
+struct UnavailableTool {
+    name: String,
+    /// Line saying so (1-indexed)
+    line: usize,
+}
+
+/// Dead sections (FMT006) and instructions the output channel rules out (FMT007)
+///
+/// `prompt` is the raw prompt: section tags are what this reads.
+pub fn analyze_reachability(prompt: &str) -> Vec<Issue> {
+    let sections = segment::segment_prompt(prompt);
+    let lines: Vec<&str> = prompt.lines().collect();
+    let mut issues = dead_sections(&lines, &sections);
+    issues.extend(unreachable_instructions(prompt));
+    issues
+}
+
+/// Tools the prompt declares unavailable, outside example material
+fn unavailable_tools(prompt: &str) -> Vec<UnavailableTool> {
+    let (cleaned, _) = super::extract_xml_blocks(prompt);
+    let mut tools: Vec<UnavailableTool> = Vec::new();
+    for (idx, line) in cleaned.lines().enumerate() {
+        let captures = UNAVAILABLE_BEFORE
+            .captures_iter(line)
+            .chain(UNAVAILABLE_AFTER.captures_iter(line));
+        for caps in captures {
+            let written = &caps[1];
+            let name = written.trim_matches('`');
+            // A bare word is only a tool name when it looks like one
+            let tool_like = written.starts_with('`') || name.contains('_') || caps.get(2).is_some();
+            if tool_like && !tools.iter().any(|t| t.name == name) {
+                tools.push(UnavailableTool {
+                    name: name.to_string(),
+                    line: idx + 1,
+                });
+            }
+        }
+    }
+    tools
+}
+
+fn section_label(section: &Section) -> String {
+    match (&section.title, section.source) {
+        (Some(tag), SectionSource::XmlTag) => format!("<{}>", tag),
+        (Some(title), _) => format!("\"{}\"", title),
+        (None, _) => format!("{} section", section.kind.display_name()),
+    }
+}
+
+fn section_text(lines: &[&str], section: &Section) -> String {
+    lines[section.start_line - 1..section.end_line.min(lines.len())].join("\n")
+}
+
+fn dead_sections(lines: &[&str], sections: &[Section]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let prompt = lines.join("\n");
+
+    // Examples and tool docs for a tool the prompt has taken away
+    let tools = unavailable_tools(&prompt);
+    let mut flagged: Vec<usize> = Vec::new();
+    for tool in &tools {
+        let mention = Regex::new(&format!(r"\b{}\b", regex::escape(&tool.name))).unwrap();
+        for section in sections {
+            let is_tool_material = section.kind == SectionKind::Examples
+                || section
+                    .title
+                    .as_deref()
+                    .is_some_and(|t| t.to_lowercase().contains("tool"));
+            let declares = (section.start_line..=section.end_line).contains(&tool.line);
+            if !is_tool_material
+                || declares
+                || flagged.contains(&section.start_line)
+                || !mention.is_match(&section_text(lines, section))
+            {
+                continue;
+            }
+            flagged.push(section.start_line);
+            issues.push(Issue {
+                id: "FMT006".to_string(),
+                category: "formatting".to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "{} (lines {}-{}) uses `{}`, which line {} says is unavailable",
+                    section_label(section),
+                    section.start_line,
+                    section.end_line,
+                    tool.name,
+                    tool.line
+                ),
+                line: Some(section.start_line),
+                confidence: 0.8,
+                suggestion: Some(format!(
+                    "Remove or update the material for `{}`; the model may still try to call it.",
+                    tool.name
+                )),
+            });
+        }
+    }
+
+    // Custom tags nothing points at; standard ones (<context>, <rules>, ...)
+    // explain themselves
+    if crate::utils::count_tokens(&prompt) < super::TASK_SECTION_MIN_TOKENS {
+        return issues;
+    }
+    for section in sections {
+        let Some(tag) = section.title.as_deref() else {
+            continue;
+        };
+        if section.source != SectionSource::XmlTag
+            || segment::is_known_label(tag)
+            || flagged.contains(&section.start_line)
+        {
+            continue;
+        }
+        let outside: String = lines
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !(section.start_line..=section.end_line).contains(&(idx + 1)))
+            .map(|(_, line)| line.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tag = tag.to_lowercase();
+        if outside.contains(&tag) || outside.contains(&tag.replace(['_', '-'], " ")) {
+            continue;
+        }
+        issues.push(Issue {
+            id: "FMT006".to_string(),
+            category: "formatting".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "<{}> (lines {}-{}) is never referred to elsewhere in the prompt",
+                tag, section.start_line, section.end_line
+            ),
+            line: Some(section.start_line),
+            confidence: 0.5,
+            suggestion: Some(format!(
+                "Point the task at it (\"Using the details in <{}>, ...\") or remove it.",
+                tag
+            )),
+        });
+    }
+    issues
+}
+
+/// The channel the prompt declares, and the line declaring it
+fn declared_channel(cleaned: &str) -> Option<(Channel, usize)> {
+    cleaned.lines().enumerate().find_map(|(idx, line)| {
+        if API_CHANNEL.is_match(line) {
+            Some((Channel::Api, idx + 1))
+        } else if VOICE_CHANNEL.is_match(line) {
+            Some((Channel::Voice, idx + 1))
+        } else {
+            None
+        }
+    })
+}
+
+fn unreachable_instructions(prompt: &str) -> Vec<Issue> {
+    // Examples may show any channel; only instructions count
+    let (cleaned, _) = super::extract_xml_blocks(prompt);
+    let Some((channel, declared_at)) = declared_channel(&cleaned) else {
+        return Vec::new();
+    };
+    let patterns: &[(&Regex, &str)] = match channel {
+        Channel::Api => &[
+            (&CONVERSATIONAL, "has no reader to answer it"),
+            (&VISUAL_FORMATTING, "would break the parser"),
+        ],
+        Channel::Voice => &[(&VISUAL_FORMATTING, "can't be heard")],
+    };
+
+    let mut issues = Vec::new();
+    let mut in_fence = false;
+    for (idx, line) in cleaned.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || idx + 1 == declared_at {
+            continue;
+        }
+        for (pattern, problem) in patterns {
+            let Some(found) = pattern.find(line) else {
+                continue;
+            };
+            // "Don't ask follow-up questions" agrees with the channel
+            let sentence_start = line[..found.start()]
+                .rfind(['.', '!', '?', ';'])
+                .map_or(0, |i| i + 1);
+            if NEGATION.is_match(&line[sentence_start..found.start()]) {
+                continue;
+            }
+            issues.push(Issue {
+                id: "FMT007".to_string(),
+                category: "formatting".to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "\"{}\" {} (line {} says {})",
+                    found.as_str(),
+                    problem,
+                    declared_at,
+                    channel.description()
+                ),
+                line: Some(idx + 1),
+                confidence: 0.7,
+                suggestion: Some(
+                    "Drop the instruction, or say which channel it applies to if the prompt \
+                    serves more than one."
+                        .to_string(),
+                ),
+            });
+            break;
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(issues: &[Issue]) -> Vec<&str> {
+        issues.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_examples_for_an_unavailable_tool_are_dead() {
+        let prompt = "You are a research assistant.\n\n\
+            <examples>\n\
+            User: latest Rust release?\n\
+            Assistant: calls `web_search` with \"Rust release\"\n\
+            </examples>\n\n\
+            The web_search tool is disabled in this deployment; answer from the documents.";
+        assert_eq!(
+            unavailable_tools(prompt),
+            [UnavailableTool {
+                name: "web_search".to_string(),
+                line: 8
+            }]
+        );
+        let issues = analyze_reachability(prompt);
+        assert_eq!(ids(&issues), ["FMT006"]);
+        assert_eq!(issues[0].line, Some(3));
+        assert!(issues[0]
+            .message
+            .starts_with("<examples> (lines 3-6) uses `web_search`"));
+
+        // Prose words aren't tool names
+        assert!(unavailable_tools("Never use jargon. Search is unavailable.").is_empty());
+    }
+
+    #[test]
+    fn test_unreferenced_custom_tag_is_dead() {
+        let filler = "Keep answers grounded in the policy above and cite the section. ".repeat(30);
+        let prompt = format!(
+            "<refund_policy>\nRefunds within 30 days.\n</refund_policy>\n\
+            <legacy_notes>\nThe old portal closed in 2019.\n</legacy_notes>\n\
+            Answer using the refund policy. {filler}"
+        );
+        let issues = analyze_reachability(&prompt);
+        assert_eq!(ids(&issues), ["FMT006"]);
+        assert!(issues[0].message.starts_with("<legacy_notes>"));
+        assert!(issues[0].confidence < crate::analyzer::LOW_CONFIDENCE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_instructions_the_channel_rules_out() {
+        let prompt = "Respond only with valid JSON matching the schema.\n\
+            If the request is unclear, ask the user a clarifying question.\n\
+            Don't greet the user.\n\
+            <example>Hi! Ask me anything.</example>";
+        let issues = analyze_reachability(prompt);
+        assert_eq!(ids(&issues), ["FMT007"]);
+        assert_eq!(issues[0].line, Some(2));
+
+        let voice = "Your reply is read aloud to the caller.\nUse a table for the order items.\nNever use markdown.";
+        let issues = analyze_reachability(voice);
+        assert_eq!(ids(&issues), ["FMT007"]);
+        assert_eq!(issues[0].line, Some(2));
+
+        // No declared channel, nothing to contradict
+        assert!(analyze_reachability("Ask the user a clarifying question.").is_empty());
+    }
+}
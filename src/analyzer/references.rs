@@ -0,0 +1,289 @@
+//! File and folder references in a prompt
+//!
+//! Finds local paths a prompt points the model at ("read the docs in
+//! `alaska-docs/`", "update config.yaml") so they can be checked against the
+//! working directory or inventoried for `--bundle-docs`. A bare word only
+//! counts with a folder and a known extension (`src/api/handlers.rs`);
+//! otherwise it needs `./`, `../`, or backticks. URLs, emails, framework
+//! names like "Node.js", and prose like "input/output" are ignored.
+
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock};
+
+use super::{Issue, Severity};
+
+/// Directories references are resolved against, set once per process
+static PROJECT_ROOTS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+
+/// Files and folders that mark the top of a project
+const PROJECT_MARKERS: &[&str] = &[
+    ".git",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+];
+
+/// A local path mentioned in the prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathReference {
+    /// The path as written, without quotes or trailing punctuation
+    pub path: String,
+    /// First line mentioning the path (1-indexed)
+    pub line: usize,
+    /// Written as a folder (`docs/`)
+    pub is_dir: bool,
+}
+
+impl PathReference {
+    /// Whether the path exists relative to `root`
+    pub fn exists_in(&self, root: &Path) -> bool {
+        let path = root.join(self.path.trim_start_matches("./"));
+        if self.is_dir {
+            path.is_dir()
+        } else {
+            path.exists()
+        }
+    }
+}
+
+/// Check references against `roots` for the rest of the process
+///
+/// Only the first call takes effect. Until then EXP007 doesn't run, so
+/// analysis outside a project never depends on the working directory.
+pub fn configure(roots: Vec<PathBuf>) {
+    let _ = PROJECT_ROOTS.set(roots);
+}
+
+/// Nearest ancestor of `start` (inclusive) that looks like a project root
+pub fn project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| PROJECT_MARKERS.iter().any(|m| dir.join(m).exists()))
+        .map(Path::to_path_buf)
+}
+
+/// EXP007: references to files and folders missing from the project
+pub fn analyze_references(prompt: &str) -> Vec<Issue> {
+    match PROJECT_ROOTS.get() {
+        Some(roots) if !roots.is_empty() => dangling_references(prompt, roots),
+        _ => Vec::new(),
+    }
+}
+
+/// Issues for references that exist under none of `roots`
+fn dangling_references(prompt: &str, roots: &[PathBuf]) -> Vec<Issue> {
+    path_references(prompt)
+        .into_iter()
+        .filter(|r| !roots.iter().any(|root| r.exists_in(root)))
+        .map(|r| Issue {
+            id: "EXP007".to_string(),
+            category: "explicitness".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "{} `{}` doesn't exist in this project; the model may invent its contents",
+                if r.is_dir { "Folder" } else { "File" },
+                r.path
+            ),
+            line: Some(r.line),
+            confidence: if r.is_dir || r.path.contains('/') {
+                0.8
+            } else {
+                0.7
+            },
+            suggestion: Some(
+                "Fix the path, or paste or attach the file so the model reads the real content. \
+                Use --bundle-docs to list the referenced files that do exist."
+                    .to_string(),
+            ),
+        })
+        .collect()
+}
+
+/// Extensions that mark a bare word as a file name
+const FILE_EXTENSIONS: &[&str] = &[
+    "c", "cfg", "conf", "cpp", "css", "csv", "docx", "env", "go", "h", "html", "ini", "ipynb",
+    "java", "js", "json", "jsx", "kt", "lock", "log", "md", "pdf", "php", "py", "rb", "rs", "sh",
+    "sql", "swift", "toml", "ts", "tsx", "txt", "xml", "yaml", "yml",
+];
+
+/// Path-like tokens: `dir/`, `dir/file.ext`, `./x`, `file.ext`
+static CANDIDATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[\s(`'\x22])((?:\.{1,2}/)?(?:[\w.-]+/)*[\w.-]+/?)").unwrap()
+});
+
+/// "Node.js", "Next.js": the name of a framework, not a file
+static FRAMEWORK_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z][A-Za-z0-9]*\.js$").unwrap());
+
+/// URLs and emails, blanked before looking for paths
+static NOT_A_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[a-zA-Z][\w+.-]*://\S+|\b[\w.+-]+@[\w-]+\.[\w.-]+|\{\{[^}]*\}\}").unwrap()
+});
+
+/// Local paths referenced in `prompt`, first mention of each, in order
+///
+/// Paths inside code fences are skipped: they usually belong to sample code
+/// or output, not to files the model should read.
+pub fn path_references(prompt: &str) -> Vec<PathReference> {
+    let mut refs: Vec<PathReference> = Vec::new();
+    let mut in_fence = false;
+
+    for (idx, line) in prompt.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = NOT_A_PATH.replace_all(line, " ");
+        for caps in CANDIDATE.captures_iter(&line) {
+            let token = caps[1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            let quoted = caps[0].trim_start().starts_with('`');
+            let Some(reference) = classify(token, quoted, idx + 1) else {
+                continue;
+            };
+            if !refs.iter().any(|r| r.path == reference.path) {
+                refs.push(reference);
+            }
+        }
+    }
+
+    refs
+}
+
+/// A reference for `token`, if it looks like a local path; `quoted` when it
+/// is in backticks
+fn classify(token: &str, quoted: bool, line: usize) -> Option<PathReference> {
+    // "and/or", "1/2", "I/O": short or numeric segments are prose
+    let segments: Vec<&str> = token
+        .trim_start_matches("./")
+        .trim_start_matches("../")
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.is_empty()
+        || segments
+            .iter()
+            .any(|s| s.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    {
+        return None;
+    }
+
+    let is_dir = token.ends_with('/');
+    let has_extension = Path::new(segments[segments.len() - 1])
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| FILE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+
+    let explicit = token.starts_with("./") || token.starts_with("../");
+    let is_path = if explicit {
+        true
+    } else if FRAMEWORK_NAME.is_match(token) {
+        false
+    } else if is_dir {
+        // "docs/" alone is a folder; "and/" after a split word is not
+        segments.iter().all(|s| s.len() > 1) && segments.iter().any(|s| s.len() > 2)
+    } else if quoted {
+        segments.len() > 1 || (has_extension && segments[0].len() > 3)
+    } else {
+        // "input/output" and "pros/cons" are word pairs
+        segments.len() > 1 && has_extension
+    };
+
+    is_path.then(|| PathReference {
+        path: token.to_string(),
+        line,
+        is_dir,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(prompt: &str) -> Vec<String> {
+        path_references(prompt)
+            .into_iter()
+            .map(|r| r.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_files_and_folders() {
+        let prompt = "Read the docs in alaska-docs/ and update `config.yaml`.\n\
+            Follow src/api/handlers.rs, then ./scripts/deploy.sh.\n\
+            Check alaska-docs/ again.";
+        assert_eq!(
+            paths(prompt),
+            vec![
+                "alaska-docs/",
+                "config.yaml",
+                "src/api/handlers.rs",
+                "./scripts/deploy.sh"
+            ]
+        );
+        let refs = path_references(prompt);
+        assert!(refs[0].is_dir);
+        assert_eq!(refs[2].line, 2);
+    }
+
+    #[test]
+    fn test_ignores_prose_urls_and_fences() {
+        let prompt = "Use and/or carefully; split 1/2 of the I/O budget.\n\
+            See https://example.com/docs/ or mail ops@example.com.\n\
+            Fill in {{DOC_PATH}} e.g.\n\
+            ```\n\
+            cat fixtures/sample.json\n\
+            ```";
+        assert!(paths(prompt).is_empty(), "{:?}", paths(prompt));
+    }
+
+    #[test]
+    fn test_ignores_framework_names_and_word_pairs() {
+        let prompt = "Build it with Node.js and Next.js (or `Vue.js`).\n\
+            Describe the input/output format, the client/server split, and the pros/cons.\n\
+            Keep notes.txt and the src/api folder in mind.";
+        assert!(paths(prompt).is_empty(), "{:?}", paths(prompt));
+        assert_eq!(
+            paths("Update `next.config.js` and `src/api`."),
+            vec!["next.config.js", "src/api"]
+        );
+    }
+
+    #[test]
+    fn test_dangling_references() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("docs")).unwrap();
+        std::fs::write(root.path().join("docs/style.md"), "# Style").unwrap();
+        std::fs::write(root.path().join("Cargo.toml"), "").unwrap();
+
+        let prompt = "Follow docs/style.md and the examples in samples/.\n\
+            Record results in `budget.csv`.";
+        let issues = dangling_references(prompt, &[root.path().to_path_buf()]);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    Some(1),
+                    "Folder `samples/` doesn't exist in this project; the model may invent its contents"
+                ),
+                (
+                    Some(2),
+                    "File `budget.csv` doesn't exist in this project; the model may invent its contents"
+                ),
+            ]
+        );
+
+        let nested = root.path().join("docs");
+        assert_eq!(project_root(&nested).as_deref(), Some(root.path()));
+    }
+}
@@ -0,0 +1,615 @@
+//! Issues grouped for display
+//!
+//! [`AnalysisReport`] groups a flat issue list by category, then by rule,
+//! keeping each occurrence, so the linear output, the interactive tree, and
+//! `--format json` all count and aggregate the same way: one row per rule
+//! with its lines, categories sorted by name, rules in the order first found.
+//! Related rules that fire together share one row (see [`consolidate`]).
+//! [`AnalysisReport::sections`] rearranges the rows for `--group-by` and
+//! `--sort`.
+//!
+//! [`consolidate`]: super::consolidate
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use super::consolidate::{self, RelatedRules};
+use super::{Issue, Severity};
+
+/// Longest list of line numbers shown before switching to a count
+const MAX_LISTED_LINES: usize = 3;
+
+/// How the pretty output organizes issues (`--group-by`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// Under each category, one row per rule
+    #[default]
+    Category,
+    /// One row per rule, no headings
+    Rule,
+    /// Errors, then warnings, then info
+    Severity,
+    /// Under each line, in prompt order, for fixing top to bottom
+    Line,
+}
+
+/// Order of the rows and headings in the pretty output (`--sort`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Most occurrences first
+    Count,
+    /// Most severe first
+    Severity,
+    /// First line first; issues without a line last
+    Line,
+}
+
+/// Rows of the report under one heading
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// `None` for [`GroupBy::Rule`], which has no headings
+    pub title: Option<String>,
+    /// Occurrences across the rows
+    pub count: usize,
+    pub rules: Vec<RuleGroup>,
+}
+
+impl Section {
+    fn new(title: Option<String>, rules: Vec<RuleGroup>) -> Self {
+        Self {
+            title,
+            count: rules.iter().map(|r| r.count).sum(),
+            rules,
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        self.rules
+            .iter()
+            .map(|r| r.severity)
+            .max()
+            .unwrap_or(Severity::Info)
+    }
+
+    fn first_line(&self) -> Option<usize> {
+        self.rules
+            .iter()
+            .filter_map(|r| r.lines.first())
+            .min()
+            .copied()
+    }
+}
+
+/// Issues grouped by category and rule
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AnalysisReport {
+    pub categories: Vec<CategoryGroup>,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+}
+
+/// The rules of one category that fired
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryGroup {
+    pub category: String,
+    pub display_name: String,
+    /// Occurrences across all rules
+    pub count: usize,
+    pub rules: Vec<RuleGroup>,
+}
+
+/// Every occurrence of one rule, or of a set of related rules
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleGroup {
+    /// The rule's id; `STY001+STY004` for related rules grouped together
+    #[serde(rename = "id")]
+    pub rule_id: String,
+    /// Key of the related-rule set this row groups, e.g. `tone`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub severity: Severity,
+    /// The message shared by all occurrences, without per-line details when
+    /// they differ
+    pub message: String,
+    /// Occurrences; a group counts each line once, however many of its
+    /// rules fired there
+    pub count: usize,
+    /// Distinct lines the rule fired on, ascending
+    pub lines: Vec<usize>,
+    /// One row per rule of a group, in the order found
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<RuleGroup>,
+    #[serde(skip)]
+    pub occurrences: Vec<Issue>,
+}
+
+impl AnalysisReport {
+    /// Group `issues`; the input order decides rule order within a category
+    pub fn from_issues(issues: &[Issue]) -> Self {
+        let mut report = Self::default();
+        for issue in issues {
+            let group = consolidate::group_of(&issue.id, issues);
+            if group.is_none() {
+                match issue.severity {
+                    Severity::Error => report.errors += 1,
+                    Severity::Warning => report.warnings += 1,
+                    Severity::Info => report.infos += 1,
+                }
+            }
+            // A group lives in the category of the rule found first
+            let existing = group.and_then(|set| report.find_group(set.key));
+            let category = match existing.or_else(|| {
+                report
+                    .categories
+                    .iter()
+                    .position(|c| c.category == issue.category)
+            }) {
+                Some(i) => &mut report.categories[i],
+                None => {
+                    report.categories.push(CategoryGroup {
+                        category: issue.category.clone(),
+                        display_name: category_display_name(&issue.category),
+                        count: 0,
+                        rules: Vec::new(),
+                    });
+                    report.categories.last_mut().unwrap()
+                }
+            };
+
+            match group {
+                Some(set) => {
+                    match category
+                        .rules
+                        .iter_mut()
+                        .find(|r| r.group.as_deref() == Some(set.key))
+                    {
+                        Some(rule) => rule.add_related(issue),
+                        None => category.rules.push(RuleGroup::related(set, issue)),
+                    }
+                }
+                None => {
+                    category.count += 1;
+                    match category
+                        .rules
+                        .iter_mut()
+                        .find(|r| r.group.is_none() && r.rule_id == issue.id)
+                    {
+                        Some(rule) => rule.add(issue),
+                        None => category.rules.push(RuleGroup::new(issue)),
+                    }
+                }
+            }
+        }
+
+        // Groups are counted once they hold all their occurrences
+        for category in &mut report.categories {
+            for rule in category.rules.iter().filter(|r| r.group.is_some()) {
+                category.count += rule.count;
+                match rule.severity {
+                    Severity::Error => report.errors += rule.count,
+                    Severity::Warning => report.warnings += rule.count,
+                    Severity::Info => report.infos += rule.count,
+                }
+            }
+        }
+        report
+            .categories
+            .sort_by(|a, b| a.category.cmp(&b.category));
+        report
+    }
+
+    /// Index of the category holding the group with `key`
+    fn find_group(&self, key: &str) -> Option<usize> {
+        self.categories
+            .iter()
+            .position(|c| c.rules.iter().any(|r| r.group.as_deref() == Some(key)))
+    }
+
+    /// Total occurrences
+    pub fn total(&self) -> usize {
+        self.errors + self.warnings + self.infos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Every issue, in report order
+    pub fn issues(&self) -> impl Iterator<Item = &Issue> {
+        self.categories
+            .iter()
+            .flat_map(|c| &c.rules)
+            .flat_map(|r| &r.occurrences)
+    }
+
+    /// The rows under headings for `group_by`, in `sort` order
+    ///
+    /// Without `sort`, categories keep their name order, severities go from
+    /// errors to info, lines go top to bottom, and rows keep report order.
+    /// Under [`GroupBy::Line`] each row is one rule on that line; related
+    /// rules get a row each.
+    pub fn sections(&self, group_by: GroupBy, sort: Option<SortBy>) -> Vec<Section> {
+        let rules = || self.categories.iter().flat_map(|c| c.rules.iter().cloned());
+        let mut sections: Vec<Section> = match group_by {
+            GroupBy::Category => self
+                .categories
+                .iter()
+                .map(|c| Section::new(Some(c.display_name.clone()), c.rules.clone()))
+                .collect(),
+            GroupBy::Rule => vec![Section::new(None, rules().collect())],
+            GroupBy::Severity => [
+                (Severity::Error, "Errors"),
+                (Severity::Warning, "Warnings"),
+                (Severity::Info, "Info"),
+            ]
+            .into_iter()
+            .map(|(severity, title)| {
+                let rows = rules().filter(|r| r.severity == severity).collect();
+                Section::new(Some(title.to_string()), rows)
+            })
+            .filter(|section| !section.rules.is_empty())
+            .collect(),
+            GroupBy::Line => {
+                // Issues without a line sort after the numbered ones
+                let mut lines: BTreeMap<(bool, Option<usize>), Vec<RuleGroup>> = BTreeMap::new();
+                for issue in self.issues() {
+                    let rows = lines.entry((issue.line.is_none(), issue.line)).or_default();
+                    match rows.iter_mut().find(|r| r.rule_id == issue.id) {
+                        Some(row) => row.add(issue),
+                        None => rows.push(RuleGroup::new(issue)),
+                    }
+                }
+                lines
+                    .into_iter()
+                    .map(|((_, line), rows)| {
+                        let title = match line {
+                            Some(line) => format!("Line {}", line),
+                            None => "Whole prompt".to_string(),
+                        };
+                        Section::new(Some(title), rows)
+                    })
+                    .collect()
+            }
+        };
+
+        if let Some(sort) = sort {
+            for section in &mut sections {
+                match sort {
+                    SortBy::Count => section.rules.sort_by_key(|r| Reverse(r.count)),
+                    SortBy::Severity => section.rules.sort_by_key(|r| Reverse(r.severity)),
+                    SortBy::Line => section
+                        .rules
+                        .sort_by_key(|r| (r.lines.is_empty(), r.lines.first().copied())),
+                }
+            }
+            match sort {
+                SortBy::Count => sections.sort_by_key(|s| Reverse(s.count)),
+                SortBy::Severity => sections.sort_by_key(|s| Reverse(s.severity())),
+                SortBy::Line => sections.sort_by_key(|s| {
+                    let line = s.first_line();
+                    (line.is_none(), line)
+                }),
+            }
+        }
+        sections
+    }
+}
+
+impl RuleGroup {
+    fn new(issue: &Issue) -> Self {
+        Self {
+            rule_id: issue.id.clone(),
+            group: None,
+            severity: issue.severity,
+            message: issue.message.clone(),
+            count: 1,
+            lines: issue.line.into_iter().collect(),
+            findings: Vec::new(),
+            occurrences: vec![issue.clone()],
+        }
+    }
+
+    /// A row for the related rules of `set`, starting with `issue`
+    fn related(set: &RelatedRules, issue: &Issue) -> Self {
+        let mut group = Self {
+            rule_id: String::new(),
+            group: Some(set.key.to_string()),
+            severity: issue.severity,
+            message: set.title.to_string(),
+            count: 0,
+            lines: Vec::new(),
+            findings: Vec::new(),
+            occurrences: Vec::new(),
+        };
+        group.add_related(issue);
+        group
+    }
+
+    fn add_related(&mut self, issue: &Issue) {
+        match self.findings.iter_mut().find(|f| f.rule_id == issue.id) {
+            Some(finding) => finding.add(issue),
+            None => {
+                self.findings.push(Self::new(issue));
+                self.rule_id = self
+                    .findings
+                    .iter()
+                    .map(|f| f.rule_id.as_str())
+                    .collect::<Vec<_>>()
+                    .join("+");
+            }
+        }
+        self.severity = self.severity.max(issue.severity);
+        if let Some(line) = issue.line {
+            if let Err(i) = self.lines.binary_search(&line) {
+                self.lines.insert(i, line);
+            }
+        }
+        // Findings without a line are one more occurrence between them
+        let unplaced = self.occurrences.iter().any(|i| i.line.is_none()) || issue.line.is_none();
+        self.count = self.lines.len() + usize::from(unplaced);
+        self.occurrences.push(issue.clone());
+    }
+
+    fn add(&mut self, issue: &Issue) {
+        if issue.message != self.message {
+            // "Indirect request: 'Can you'" and "Indirect request: 'Could
+            // you'" are both "Indirect request"
+            self.message = base_message(&self.occurrences[0].message).to_string();
+        }
+        if let Some(line) = issue.line {
+            if let Err(i) = self.lines.binary_search(&line) {
+                self.lines.insert(i, line);
+            }
+        }
+        self.count += 1;
+        self.occurrences.push(issue.clone());
+    }
+
+    /// Where the rule fired: "L3", "L3, L7", "5 lines", "2 times", or empty
+    pub fn location(&self) -> String {
+        match self.lines.len() {
+            0 if self.count > 1 => format!("{} times", self.count),
+            0 => String::new(),
+            n if n <= MAX_LISTED_LINES => self
+                .lines
+                .iter()
+                .map(|l| format!("L{}", l))
+                .collect::<Vec<_>>()
+                .join(", "),
+            n => format!("{} lines", n),
+        }
+    }
+
+    /// The message cut to `max_chars`, ending in "..." when cut
+    pub fn truncated_message(&self, max_chars: usize) -> String {
+        if self.message.chars().count() <= max_chars {
+            return self.message.clone();
+        }
+        let kept: String = self
+            .message
+            .chars()
+            .take(max_chars.saturating_sub(3))
+            .collect();
+        format!("{}...", kept)
+    }
+
+    /// Whether every occurrence is a weak heuristic match
+    pub fn is_low_confidence(&self) -> bool {
+        self.occurrences.iter().all(Issue::is_low_confidence)
+    }
+}
+
+fn base_message(message: &str) -> &str {
+    message.split(':').next().unwrap_or(message)
+}
+
+/// Human-readable category name, e.g. "Tool Usage" for `tools`
+pub fn category_display_name(category: &str) -> String {
+    match category.to_lowercase().as_str() {
+        "explicitness" => "Explicitness",
+        "style" => "Style",
+        "tools" => "Tool Usage",
+        "formatting" => "Formatting",
+        "verbosity" => "Verbosity",
+        "agentic" => "Agentic Coding",
+        "long_horizon" => "Long-Horizon",
+        "frontend" => "Frontend Design",
+        "hygiene" => "Hygiene",
+        "language" => "Language",
+        "custom" => "Custom",
+        _ => category,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(id: &str, category: &str, message: &str, line: Option<usize>) -> Issue {
+        Issue {
+            id: id.to_string(),
+            category: category.to_string(),
+            severity: Severity::Warning,
+            message: message.to_string(),
+            line,
+            confidence: 1.0,
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_by_category_then_rule() {
+        let report = AnalysisReport::from_issues(&[
+            issue("STY001", "style", "Negative instruction", Some(4)),
+            issue(
+                "EXP003",
+                "explicitness",
+                "Indirect request: 'Can you'",
+                Some(3),
+            ),
+            issue(
+                "EXP003",
+                "explicitness",
+                "Indirect request: 'Could you'",
+                Some(1),
+            ),
+            issue("EXP001", "explicitness", "Vague task", None),
+        ]);
+
+        assert_eq!(report.total(), 4);
+        assert_eq!(report.warnings, 4);
+        let names: Vec<_> = report
+            .categories
+            .iter()
+            .map(|c| c.category.as_str())
+            .collect();
+        assert_eq!(names, ["explicitness", "style"]);
+
+        let explicitness = &report.categories[0];
+        assert_eq!(explicitness.count, 3);
+        assert_eq!(explicitness.rules.len(), 2);
+        let exp003 = &explicitness.rules[0];
+        assert_eq!(exp003.rule_id, "EXP003");
+        assert_eq!(exp003.message, "Indirect request");
+        assert_eq!(exp003.lines, [1, 3]);
+        assert_eq!(exp003.location(), "L1, L3");
+        assert_eq!(explicitness.rules[1].location(), "");
+
+        let ids: Vec<_> = report.issues().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["EXP003", "EXP003", "EXP001", "STY001"]);
+    }
+
+    #[test]
+    fn test_location_and_truncation() {
+        let issues: Vec<_> = (1..=5)
+            .map(|l| issue("HYG002", "hygiene", "Trailing whitespace", Some(l)))
+            .collect();
+        let report = AnalysisReport::from_issues(&issues);
+        let rule = &report.categories[0].rules[0];
+        assert_eq!(rule.location(), "5 lines");
+        assert_eq!(rule.message, "Trailing whitespace");
+        assert_eq!(rule.truncated_message(11), "Trailing...");
+        assert_eq!(rule.truncated_message(50), "Trailing whitespace");
+
+        let report = AnalysisReport::from_issues(&[
+            issue("FMT001", "formatting", "Markdown heavy", None),
+            issue("FMT001", "formatting", "Markdown heavy", None),
+        ]);
+        assert_eq!(report.categories[0].rules[0].location(), "2 times");
+    }
+
+    #[test]
+    fn test_related_rules_share_a_row() {
+        let mut shout = issue("STY002", "style", "ALL CAPS emphasis: 'NEVER'", Some(2));
+        shout.severity = Severity::Info;
+        let report = AnalysisReport::from_issues(&[
+            issue("STY001", "style", "Negative instruction", Some(2)),
+            issue("EXP003", "explicitness", "Indirect request", Some(1)),
+            shout,
+            issue("STY001", "style", "Negative instruction", Some(5)),
+            issue("FMT001", "formatting", "No format", None),
+        ]);
+
+        // Lines 2 and 5, not three separate findings
+        assert_eq!(report.total(), 4);
+        assert_eq!(report.warnings, 4);
+        assert_eq!(report.infos, 0);
+        let style = &report.categories[2];
+        assert_eq!(style.count, 2);
+        let tone = &style.rules[0];
+        assert_eq!(tone.rule_id, "STY001+STY002");
+        assert_eq!(tone.group.as_deref(), Some("tone"));
+        assert_eq!(tone.message, "Aggressive or negative tone");
+        assert_eq!(tone.severity, Severity::Warning);
+        assert_eq!(tone.location(), "L2, L5");
+        assert_eq!(tone.findings.len(), 2);
+        assert_eq!(tone.findings[0].count, 2);
+        assert_eq!(tone.occurrences.len(), 3);
+        assert_eq!(report.issues().count(), 5);
+
+        // A related rule firing alone keeps its own row
+        assert_eq!(report.categories[1].rules[0].rule_id, "FMT001");
+        assert!(report.categories[1].rules[0].group.is_none());
+    }
+
+    #[test]
+    fn test_sections_follow_group_by_and_sort() {
+        let mut error = issue("TLS001", "tools", "Tool without description", Some(9));
+        error.severity = Severity::Error;
+        let report = AnalysisReport::from_issues(&[
+            issue("STY003", "style", "Think", Some(4)),
+            error,
+            issue("EXP003", "explicitness", "Indirect request", Some(4)),
+            issue("EXP003", "explicitness", "Indirect request", Some(2)),
+            issue("FMT001", "formatting", "No format", None),
+        ]);
+        let layout = |group_by, sort| {
+            report
+                .sections(group_by, sort)
+                .iter()
+                .map(|s| {
+                    let rows: Vec<_> = s.rules.iter().map(|r| r.rule_id.as_str()).collect();
+                    format!("{}: {}", s.title.as_deref().unwrap_or("-"), rows.join(" "))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The default matches the report itself
+        assert_eq!(
+            layout(GroupBy::Category, None),
+            [
+                "Explicitness: EXP003",
+                "Formatting: FMT001",
+                "Style: STY003",
+                "Tool Usage: TLS001"
+            ]
+        );
+        assert_eq!(
+            layout(GroupBy::Category, Some(SortBy::Line)),
+            [
+                "Explicitness: EXP003",
+                "Style: STY003",
+                "Tool Usage: TLS001",
+                "Formatting: FMT001"
+            ]
+        );
+        assert_eq!(
+            layout(GroupBy::Rule, Some(SortBy::Count)),
+            ["-: EXP003 FMT001 STY003 TLS001"]
+        );
+        assert_eq!(
+            layout(GroupBy::Severity, None),
+            ["Errors: TLS001", "Warnings: EXP003 FMT001 STY003"]
+        );
+        assert_eq!(
+            layout(GroupBy::Line, None),
+            [
+                "Line 2: EXP003",
+                "Line 4: EXP003 STY003",
+                "Line 9: TLS001",
+                "Whole prompt: FMT001"
+            ]
+        );
+        assert_eq!(
+            layout(GroupBy::Line, Some(SortBy::Severity)),
+            [
+                "Line 9: TLS001",
+                "Line 2: EXP003",
+                "Line 4: EXP003 STY003",
+                "Whole prompt: FMT001"
+            ]
+        );
+        assert_eq!(report.sections(GroupBy::Line, None)[1].count, 2);
+    }
+
+    #[test]
+    fn test_category_display_name() {
+        assert_eq!(category_display_name("explicitness"), "Explicitness");
+        assert_eq!(category_display_name("long_horizon"), "Long-Horizon");
+        assert_eq!(category_display_name("unknown"), "unknown");
+    }
+}
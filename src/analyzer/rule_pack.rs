@@ -0,0 +1,298 @@
+//! Custom rule packs
+//!
+//! A rule pack is a TOML file of regex-based rules and suggestion templates
+//! maintained outside copt (typically shared by a team via `rules.remote`).
+//! Once installed, pack rules run alongside the built-in analyzers.
+//!
+//! ```toml
+//! [[rules]]
+//! id = "ACME001"
+//! category = "style"          # optional; defaults to "custom"
+//! severity = "warning"        # info, warning, error
+//! pattern = "(?i)\\bsynergy\\b"
+//! message = "Avoid marketing jargon"
+//! suggestion = "Say what the feature does"
+//! example = { before = "Unlock synergy", after = "Share drafts with your team" }
+//! docs_url = "https://wiki.acme.dev/style#jargon"   # optional; opened by `copt docs`
+//!
+//! [[suggestions]]
+//! id = "acme_tone"
+//! label = "Acme tone of voice"
+//! template = "<tone>Plain, friendly, no jargon.</tone>"
+//!
+//! [[enhancements]]                  # appended by --enhance; see optimizer::enhance
+//! id = "acme_tests"
+//! patterns = ["(?i)\\btests?\\b"]
+//! template = "Run `make test` before finishing."
+//! ```
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use super::{Issue, Severity};
+use crate::optimizer::enhance::{self, Enhancement};
+
+/// Category used for pack rules that don't name one
+pub const CUSTOM_CATEGORY: &str = "custom";
+
+/// Pack installed for this process
+static ACTIVE: OnceLock<RulePack> = OnceLock::new();
+
+/// Rule pack file contents
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulePackFile {
+    #[serde(default)]
+    rules: Vec<CustomRule>,
+    #[serde(default)]
+    suggestions: Vec<CustomSuggestion>,
+    #[serde(default)]
+    enhancements: Vec<Enhancement>,
+}
+
+/// A regex rule as written in the pack
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    pub id: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    pub pattern: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f32,
+    pub example: Option<CustomExample>,
+    pub docs_url: Option<String>,
+}
+
+/// A before/after pair shown alongside a pack rule's issues
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomExample {
+    pub before: String,
+    pub after: String,
+}
+
+/// A suggestion template as written in the pack
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomSuggestion {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: String,
+    pub template: String,
+}
+
+fn default_category() -> String {
+    CUSTOM_CATEGORY.to_string()
+}
+
+fn default_severity() -> Severity {
+    Severity::Warning
+}
+
+fn default_confidence() -> f32 {
+    1.0
+}
+
+/// A validated rule pack with compiled patterns
+#[derive(Debug, Default)]
+pub struct RulePack {
+    rules: Vec<(CustomRule, Regex)>,
+    pub suggestions: Vec<CustomSuggestion>,
+    pub enhancements: enhance::Library,
+}
+
+impl RulePack {
+    /// Parse and validate a rule pack
+    pub fn parse(content: &str) -> Result<Self> {
+        let file: RulePackFile = toml::from_str(content).context("Invalid rule pack")?;
+
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for rule in file.rules {
+            if rule.id.trim().is_empty() {
+                anyhow::bail!("Rule pack contains a rule without an id");
+            }
+            if !(0.0..=1.0).contains(&rule.confidence) {
+                anyhow::bail!(
+                    "Rule {}: confidence {} is not between 0.0 and 1.0",
+                    rule.id,
+                    rule.confidence
+                );
+            }
+            let regex = Regex::new(&rule.pattern)
+                .with_context(|| format!("Rule {}: invalid pattern", rule.id))?;
+            rules.push((rule, regex));
+        }
+
+        for suggestion in &file.suggestions {
+            if suggestion.id.trim().is_empty() {
+                anyhow::bail!("Rule pack contains a suggestion without an id");
+            }
+        }
+
+        Ok(Self {
+            rules,
+            suggestions: file.suggestions,
+            enhancements: enhance::Library::from_enhancements(file.enhancements)?,
+        })
+    }
+
+    /// Number of rules in the pack
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// The pack's rules, in pack order
+    pub fn rules(&self) -> impl Iterator<Item = &CustomRule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    /// Whether the pack defines a rule with `rule_id`
+    pub fn has_rule(&self, rule_id: &str) -> bool {
+        self.rules.iter().any(|(rule, _)| rule.id == rule_id)
+    }
+
+    /// Example for the pack rule with `rule_id`, if it has one
+    pub fn example(&self, rule_id: &str) -> Option<&CustomExample> {
+        self.rules
+            .iter()
+            .find(|(rule, _)| rule.id == rule_id)
+            .and_then(|(rule, _)| rule.example.as_ref())
+    }
+
+    /// Documentation link for the pack rule with `rule_id`, if it has one
+    pub fn docs_url(&self, rule_id: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(rule, _)| rule.id == rule_id)
+            .and_then(|(rule, _)| rule.docs_url.as_deref())
+    }
+
+    /// Run the pack's rules, one issue per matching line
+    ///
+    /// With an explicit `--check`, only rules in a checked category (or
+    /// `custom`) run.
+    pub fn analyze(&self, prompt: &str, check_categories: Option<&[String]>) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        for (rule, regex) in &self.rules {
+            if let Some(cats) = check_categories {
+                if !cats
+                    .iter()
+                    .any(|c| c == &rule.category || c == CUSTOM_CATEGORY)
+                {
+                    continue;
+                }
+            }
+
+            for (idx, line) in prompt.lines().enumerate() {
+                if regex.is_match(line) {
+                    issues.push(Issue {
+                        id: rule.id.clone(),
+                        category: rule.category.clone(),
+                        severity: rule.severity,
+                        message: rule.message.clone(),
+                        line: Some(idx + 1),
+                        confidence: rule.confidence,
+                        suggestion: rule.suggestion.clone(),
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+/// Install the pack used by [`super::analyze`] for the rest of the process
+///
+/// Only the first install takes effect.
+pub fn install(pack: RulePack) {
+    let _ = ACTIVE.set(pack);
+}
+
+/// The installed pack, if any
+pub fn active() -> Option<&'static RulePack> {
+    ACTIVE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK: &str = r#"
+[[rules]]
+id = "ACME001"
+pattern = "(?i)\\bsynergy\\b"
+message = "Avoid marketing jargon"
+example = { before = "Unlock synergy", after = "Share drafts with your team" }
+docs_url = "https://wiki.acme.dev/style#jargon"
+
+[[rules]]
+id = "ACME002"
+category = "style"
+severity = "info"
+pattern = "TODO"
+message = "Unresolved TODO"
+confidence = 0.6
+
+[[suggestions]]
+id = "acme_tone"
+label = "Acme tone of voice"
+template = "<tone>Plain, friendly, no jargon.</tone>"
+"#;
+
+    #[test]
+    fn test_pack_rules_match_lines() {
+        let pack = RulePack::parse(PACK).unwrap();
+        assert_eq!(pack.rule_count(), 2);
+        assert_eq!(pack.suggestions[0].id, "acme_tone");
+
+        let issues = pack.analyze("Leverage synergy.\nTODO: add examples", None);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].id, "ACME001");
+        assert_eq!(issues[0].category, "custom");
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[1].line, Some(2));
+        assert_eq!(issues[1].confidence, 0.6);
+
+        assert_eq!(pack.example("ACME001").unwrap().before, "Unlock synergy");
+        assert!(pack.example("ACME002").is_none());
+        assert_eq!(
+            pack.docs_url("ACME001"),
+            Some("https://wiki.acme.dev/style#jargon")
+        );
+        assert!(pack.docs_url("ACME002").is_none());
+    }
+
+    #[test]
+    fn test_check_categories_filter_pack_rules() {
+        let pack = RulePack::parse(PACK).unwrap();
+        let style = pack.analyze("synergy TODO", Some(&["style".to_string()]));
+        assert_eq!(style.len(), 1);
+        assert_eq!(style[0].id, "ACME002");
+
+        let custom = pack.analyze("synergy TODO", Some(&["custom".to_string()]));
+        assert_eq!(custom.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_packs_are_rejected() {
+        let bad_regex = "[[rules]]\nid = \"X\"\npattern = \"(\"\nmessage = \"m\"";
+        assert!(RulePack::parse(bad_regex).is_err());
+
+        let unknown_field = "[[rules]]\nid = \"X\"\npattern = \"x\"\nmessage = \"m\"\nlevel = 1";
+        assert!(RulePack::parse(unknown_field).is_err());
+
+        let bad_confidence =
+            "[[rules]]\nid = \"X\"\npattern = \"x\"\nmessage = \"m\"\nconfidence = 2.0";
+        assert!(RulePack::parse(bad_confidence).is_err());
+    }
+}
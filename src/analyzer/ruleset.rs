@@ -0,0 +1,68 @@
+//! Fingerprint of the rule set an analysis runs with
+//!
+//! Saved metadata records the copt version and this hash, so `copt outdated`
+//! can tell which optimizations were made under different rules. The hash
+//! covers what changes analysis results: the release (built-in rule logic
+//! ships with it), the built-in rule IDs, the active rule pack's rules, and
+//! the rules and categories the config turns off or the policy requires.
+
+use sha2::{Digest, Sha256};
+
+use super::{docs, language, rule_pack};
+
+/// Short hex digest identifying the current rule set
+pub fn hash() -> String {
+    let mut hasher = Sha256::new();
+    let mut field = |label: &str, value: &str| {
+        hasher.update(label.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    };
+
+    field("version", env!("CARGO_PKG_VERSION"));
+    for id in docs::builtin_rule_ids() {
+        field("builtin", id);
+    }
+    if let Some(pack) = rule_pack::active() {
+        for rule in pack.rules() {
+            field("pack.id", &rule.id);
+            field("pack.category", &rule.category);
+            field("pack.severity", &format!("{:?}", rule.severity));
+            field("pack.pattern", &rule.pattern);
+            field("pack.message", &rule.message);
+            field("pack.confidence", &rule.confidence.to_string());
+        }
+    }
+    let (rules, categories) = super::disabled();
+    for rule in rules {
+        field("disabled.rule", rule);
+    }
+    for category in categories {
+        field("disabled.category", category);
+    }
+    for category in super::REQUIRED_CATEGORIES.get().into_iter().flatten() {
+        field("required", category);
+    }
+    field("language", &language::enabled().to_string());
+
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_stable_short_hex() {
+        let hash = hash();
+        assert_eq!(hash.len(), 16);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(hash, super::hash());
+    }
+}
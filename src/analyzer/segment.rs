@@ -0,0 +1,493 @@
+//! Prompt segmentation
+//!
+//! Splits a prompt into logical sections (role, context, rules, examples,
+//! task, output format) using XML tags, headings, and paragraph heuristics.
+//! The detected structure is shown in the analysis output and drives the
+//! missing-section rules (FMT004-005).
+
+use regex::Regex;
+use serde::Serialize;
+
+/// Logical section of a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Role,
+    Context,
+    Rules,
+    Examples,
+    Task,
+    OutputFormat,
+}
+
+impl SectionKind {
+    /// Human-readable section name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SectionKind::Role => "Role",
+            SectionKind::Context => "Context",
+            SectionKind::Rules => "Rules",
+            SectionKind::Examples => "Examples",
+            SectionKind::Task => "Task",
+            SectionKind::OutputFormat => "Output format",
+        }
+    }
+
+    /// XML tag conventionally used for this section
+    pub fn xml_tag(&self) -> &'static str {
+        match self {
+            SectionKind::Role => "role",
+            SectionKind::Context => "context",
+            SectionKind::Rules => "rules",
+            SectionKind::Examples => "examples",
+            SectionKind::Task => "instructions",
+            SectionKind::OutputFormat => "output_format",
+        }
+    }
+
+    /// Classify a heading or XML tag name
+    fn from_label(label: &str) -> Option<Self> {
+        let lower = label.to_lowercase().replace(['_', '-'], " ");
+        let lower = lower.trim();
+
+        let matches = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+
+        if matches(&["output", "format", "response", "deliverable"]) {
+            Some(SectionKind::OutputFormat)
+        } else if matches(&["example", "sample", "demonstration"]) {
+            Some(SectionKind::Examples)
+        } else if matches(&[
+            "rule",
+            "constraint",
+            "guideline",
+            "requirement",
+            "policy",
+            "policies",
+            "do not",
+            "don't",
+        ]) {
+            Some(SectionKind::Rules)
+        } else if matches(&["role", "persona", "identity", "who you are", "about you"]) {
+            Some(SectionKind::Role)
+        } else if matches(&[
+            "task",
+            "instruction",
+            "goal",
+            "objective",
+            "steps",
+            "what to do",
+        ]) {
+            Some(SectionKind::Task)
+        } else if matches(&[
+            "context",
+            "background",
+            "document",
+            "data",
+            "situation",
+            "overview",
+        ]) {
+            Some(SectionKind::Context)
+        } else {
+            None
+        }
+    }
+}
+
+/// How a section boundary was detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionSource {
+    XmlTag,
+    Heading,
+    Heuristic,
+}
+
+/// A detected section of the prompt
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Section {
+    pub kind: SectionKind,
+    /// Heading text or XML tag name, when the section was explicitly labeled
+    pub title: Option<String>,
+    /// First line of the section (1-indexed)
+    pub start_line: usize,
+    /// Last line of the section (1-indexed, inclusive)
+    pub end_line: usize,
+    pub source: SectionSource,
+}
+
+/// Segment a prompt into logical sections
+pub fn segment_prompt(prompt: &str) -> Vec<Section> {
+    let lines: Vec<&str> = prompt.lines().collect();
+    let xml_open = Regex::new(r"^\s*<([a-zA-Z_][\w-]*)(\s[^>]*)?>\s*$").unwrap();
+    let heading =
+        Regex::new(r"^\s*(#{1,6}\s+(.+?)|([A-Za-z][\w /&-]{1,40}):|([A-Z][A-Z /&-]{2,40}))\s*$")
+            .unwrap();
+
+    let mut sections: Vec<Section> = Vec::new();
+    let mut idx = 0;
+
+    while idx < lines.len() {
+        let line = lines[idx];
+
+        if line.trim().is_empty() {
+            idx += 1;
+            continue;
+        }
+
+        // XML-tagged block: runs to the matching close tag
+        if let Some(caps) = xml_open.captures(line) {
+            let tag = caps[1].to_string();
+            let close = format!("</{}>", tag);
+            let end = lines[idx..]
+                .iter()
+                .position(|l| l.contains(&close))
+                .map(|p| p + idx);
+            if let Some(end) = end {
+                if let Some(kind) = SectionKind::from_label(&tag) {
+                    push_section(
+                        &mut sections,
+                        kind,
+                        Some(tag),
+                        idx,
+                        end,
+                        SectionSource::XmlTag,
+                    );
+                } else {
+                    // Unknown tag: classify by contents
+                    let body = lines[idx + 1..end].join("\n");
+                    let kind = classify_paragraph(&body);
+                    push_section(
+                        &mut sections,
+                        kind,
+                        Some(tag),
+                        idx,
+                        end,
+                        SectionSource::XmlTag,
+                    );
+                }
+                idx = end + 1;
+                continue;
+            }
+        }
+
+        // Heading: runs until the next heading, XML block, or end of prompt
+        if let Some(caps) = heading.captures(line) {
+            let title = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().trim().to_string())
+                .unwrap_or_default();
+
+            if let Some(kind) = SectionKind::from_label(&title) {
+                // Markdown headings span paragraphs; "Title:" labels cover one
+                let is_markdown = caps.get(2).is_some();
+                let end = (idx + 1..lines.len())
+                    .find(|&i| {
+                        heading.is_match(lines[i])
+                            || xml_open.is_match(lines[i])
+                            || (!is_markdown && lines[i].trim().is_empty())
+                    })
+                    .map(|i| i - 1)
+                    .unwrap_or(lines.len() - 1);
+                let end = last_non_blank(&lines, idx, end);
+                push_section(
+                    &mut sections,
+                    kind,
+                    Some(title),
+                    idx,
+                    end,
+                    SectionSource::Heading,
+                );
+                idx = end + 1;
+                continue;
+            }
+        }
+
+        // Plain paragraph: classify heuristically
+        let end = (idx..lines.len())
+            .find(|&i| lines[i].trim().is_empty())
+            .map(|i| i - 1)
+            .unwrap_or(lines.len() - 1);
+        let body = lines[idx..=end].join("\n");
+        let kind = classify_paragraph(&body);
+        push_section(
+            &mut sections,
+            kind,
+            None,
+            idx,
+            end,
+            SectionSource::Heuristic,
+        );
+        idx = end + 1;
+    }
+
+    sections
+}
+
+/// Whether a heading or tag name reads as a standard section (`rules`, `output_format`, ...)
+pub fn is_known_label(label: &str) -> bool {
+    SectionKind::from_label(label).is_some()
+}
+
+/// Whether the prompt contains a section of the given kind
+pub fn has_section(sections: &[Section], kind: SectionKind) -> bool {
+    sections.iter().any(|s| s.kind == kind)
+}
+
+/// One-line summary of the structure (e.g. "Role → Context → Task")
+pub fn structure_summary(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| s.kind.display_name())
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// An XML tag that doesn't pair up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct XmlProblem {
+    /// Line of the offending tag (1-indexed)
+    pub line: usize,
+    pub message: String,
+}
+
+/// Tags that never take a closing tag
+const VOID_TAGS: &[&str] = &["br", "hr", "img"];
+
+/// Find XML tags that are never closed, closed twice, or closed out of order
+///
+/// Code fences and inline code are skipped, so markup quoted as an example
+/// doesn't count. A tag that doesn't start its line (`List<Item>`, "fill in
+/// <placeholder>") only has to close on the same line, if at all: it is a
+/// generic type or a placeholder more often than a section.
+pub fn check_xml_balance(prompt: &str) -> Vec<XmlProblem> {
+    let tag = Regex::new(r"<(/?)([A-Za-z_][\w-]*)(?:\s[^<>]*?)?(/?)>").unwrap();
+    let inline_code = Regex::new(r"`[^`]*`").unwrap();
+
+    let mut problems = Vec::new();
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut in_fence = false;
+
+    for (idx, line) in prompt.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line_no = idx + 1;
+        let line = inline_code.replace_all(line, "");
+        // Tags opened mid-line; any still open at the end of the line are
+        // dropped
+        let mut inline: Vec<String> = Vec::new();
+        for caps in tag.captures_iter(&line) {
+            let name = caps[2].to_string();
+            if !caps[3].is_empty() || VOID_TAGS.contains(&name.to_lowercase().as_str()) {
+                continue;
+            }
+            let starts_line = line[..caps.get(0).unwrap().start()].trim().is_empty();
+            if caps[1].is_empty() {
+                if starts_line {
+                    open.push((name, line_no));
+                } else {
+                    inline.push(name);
+                }
+                continue;
+            }
+            if let Some(pos) = inline.iter().rposition(|n| *n == name) {
+                inline.truncate(pos);
+                continue;
+            }
+
+            match open.iter().rposition(|(n, _)| *n == name) {
+                Some(pos) => {
+                    for (unclosed, opened) in open.drain(pos + 1..) {
+                        problems.push(XmlProblem {
+                            line: opened,
+                            message: format!(
+                                "<{}> is never closed (</{}> on line {} closes the enclosing tag)",
+                                unclosed, name, line_no
+                            ),
+                        });
+                    }
+                    open.pop();
+                }
+                None => problems.push(XmlProblem {
+                    line: line_no,
+                    message: format!("</{}> has no matching <{}>", name, name),
+                }),
+            }
+        }
+    }
+
+    problems.extend(open.into_iter().map(|(name, line)| XmlProblem {
+        line,
+        message: format!("<{}> is never closed", name),
+    }));
+    problems.sort_by_key(|p| p.line);
+    problems
+}
+
+/// Append a section, merging with the previous one when both are unlabeled
+/// paragraphs of the same kind
+fn push_section(
+    sections: &mut Vec<Section>,
+    kind: SectionKind,
+    title: Option<String>,
+    start: usize,
+    end: usize,
+    source: SectionSource,
+) {
+    if let Some(last) = sections.last_mut() {
+        if source == SectionSource::Heuristic
+            && last.source == SectionSource::Heuristic
+            && last.kind == kind
+        {
+            last.end_line = end + 1;
+            return;
+        }
+    }
+
+    sections.push(Section {
+        kind,
+        title,
+        start_line: start + 1,
+        end_line: end + 1,
+        source,
+    });
+}
+
+/// Trim trailing blank lines from a section range
+fn last_non_blank(lines: &[&str], start: usize, end: usize) -> usize {
+    (start..=end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .unwrap_or(start)
+}
+
+/// Classify an unlabeled paragraph by its wording
+fn classify_paragraph(text: &str) -> SectionKind {
+    let lower = text.trim().to_lowercase();
+
+    let role = Regex::new(r"^(you are|you're|act as|as an? \w+,)").unwrap();
+    let output = Regex::new(
+        r"\b(respond (with|in|using)|format (your|the) (response|output|answer)|output (as|in)|return (only )?(a |an |the )?(json|yaml|xml|markdown|list|table)|your (response|answer|output) should)\b",
+    )
+    .unwrap();
+    let examples =
+        Regex::new(r"^(example|e\.g\.|for example|input:|output:|user:|assistant:)").unwrap();
+    let rule_line =
+        Regex::new(r"^\s*([-*•]|\d+[.)])?\s*(always|never|don't|do not|must|only|avoid)\b")
+            .unwrap();
+    let task = Regex::new(
+        r"^(your task|your job|your goal|please |write|create|build|implement|summarize|analy[sz]e|review|generate|translate|answer|fix|refactor|design|explain|list|extract|classify|compare|draft)\b",
+    )
+    .unwrap();
+
+    let lines: Vec<&str> = lower.lines().collect();
+    let rule_lines = lines.iter().filter(|l| rule_line.is_match(l)).count();
+
+    if role.is_match(&lower) {
+        SectionKind::Role
+    } else if examples.is_match(&lower) {
+        SectionKind::Examples
+    } else if output.is_match(&lower) {
+        SectionKind::OutputFormat
+    } else if rule_lines > 0 && rule_lines * 2 >= lines.len() {
+        SectionKind::Rules
+    } else if task.is_match(&lower) {
+        SectionKind::Task
+    } else {
+        SectionKind::Context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_xml_tags() {
+        let prompt = "<context>\nWe sell shoes.\n</context>\n\n<examples>\n<example>Hi</example>\n</examples>\n\n<output_format>\nJSON\n</output_format>";
+        let sections = segment_prompt(prompt);
+        let kinds: Vec<_> = sections.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SectionKind::Context,
+                SectionKind::Examples,
+                SectionKind::OutputFormat
+            ]
+        );
+        assert_eq!(sections[0].start_line, 1);
+        assert_eq!(sections[0].end_line, 3);
+        assert_eq!(sections[0].source, SectionSource::XmlTag);
+    }
+
+    #[test]
+    fn test_segment_headings() {
+        let prompt = "## Background\nThe API is slow.\n\n## Rules\n- Never cache auth\n\nOutput format:\nA table.";
+        let sections = segment_prompt(prompt);
+        let kinds: Vec<_> = sections.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SectionKind::Context,
+                SectionKind::Rules,
+                SectionKind::OutputFormat
+            ]
+        );
+        assert_eq!(sections[1].title.as_deref(), Some("Rules"));
+        assert_eq!(sections[1].end_line, 5);
+    }
+
+    #[test]
+    fn test_segment_heuristic_paragraphs() {
+        let prompt = "You are a support agent for Acme.\n\nCustomers are small businesses.\n\nAnswer the customer's question.\n\nAlways cite the docs.\nNever guess.\n\nRespond with a short paragraph.";
+        let sections = segment_prompt(prompt);
+        assert_eq!(
+            structure_summary(&sections),
+            "Role → Context → Task → Rules → Output format"
+        );
+        assert!(sections
+            .iter()
+            .all(|s| s.source == SectionSource::Heuristic));
+    }
+
+    #[test]
+    fn test_adjacent_context_paragraphs_merge() {
+        let sections = segment_prompt("We sell shoes.\n\nMost customers are in Europe.");
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].end_line, 3);
+    }
+
+    #[test]
+    fn test_xml_balance() {
+        let balanced = "<role>\nYou review code.\n</role>\n<rules>Use <b>bold</b> sparingly.<br></rules>\n```html\n<div>\n```\nQuote `<tag>` inline.";
+        assert!(check_xml_balance(balanced).is_empty());
+
+        // Generic types and placeholders aren't sections
+        let prose = "<task>\nReturn a List<Item> sorted by Map<String, Vec<u8>> size.\n\
+            Replace <placeholder> with the customer's name.\n</task>";
+        assert!(
+            check_xml_balance(prose).is_empty(),
+            "{:?}",
+            check_xml_balance(prose)
+        );
+
+        let problems = check_xml_balance(
+            "<instructions>\n<context>\nData\n</instructions>\n</output_format>\n<examples>",
+        );
+        let lines: Vec<usize> = problems.iter().map(|p| p.line).collect();
+        assert_eq!(lines, vec![2, 5, 6]);
+        assert!(problems[0].message.starts_with("<context> is never closed"));
+        assert_eq!(
+            problems[1].message,
+            "</output_format> has no matching <output_format>"
+        );
+        assert_eq!(problems[2].message, "<examples> is never closed");
+    }
+}
@@ -0,0 +1,99 @@
+//! What a prompt already does well
+//!
+//! A clean analysis on its own reads as "looks good" with nothing behind it,
+//! and for a short prompt most categories may not have run at all. The
+//! strengths report names the best practice behind each category that was
+//! checked and passed, and counts the categories that didn't apply, so a
+//! clean result says what it actually confirmed. Frontend rules only look at
+//! prompts that ask for UI work; elsewhere their pass confirms nothing.
+
+use serde::Serialize;
+
+use super::coverage::{CategoryCoverage, CoverageStatus};
+
+/// A best practice the prompt follows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Strength {
+    pub category: String,
+    pub display_name: String,
+    pub practice: &'static str,
+}
+
+/// Strengths of a prompt, with how much of the analysis they cover
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StrengthReport {
+    pub strengths: Vec<Strength>,
+    /// Categories that didn't run (not applicable, opt-in, disabled, ...)
+    pub not_checked: usize,
+}
+
+/// The practice a category's rules look for
+pub fn practice(category: &str) -> &'static str {
+    match category {
+        "explicitness" => "Gives direct, specific instructions with the context behind them",
+        "style" => "Phrases instructions positively and calmly, without all-caps emphasis",
+        "tools" => "Tells the model when to act with its tools, not just suggest",
+        "formatting" => "Says what form the output should take",
+        "verbosity" => "Sets how much to say and when to report progress",
+        "agentic" => {
+            "Asks for exploration before edits and guards against guessing and over-engineering"
+        }
+        "long_horizon" => "Plans for saved state, incremental progress, and context limits",
+        "frontend" => "Specifies design, responsiveness, accessibility, and interaction states",
+        "hygiene" => {
+            "Clean text: consistent line endings, no invisible characters or stray whitespace"
+        }
+        "language" => "Consistent spelling and terminology",
+        "custom" => "Follows the team's rule pack",
+        _ => "Passed every check in the category",
+    }
+}
+
+/// Strengths of `prompt` behind its coverage: one per category that passed
+pub fn report(prompt: &str, coverage: &[CategoryCoverage]) -> StrengthReport {
+    let confirmed = |c: &&CategoryCoverage| {
+        c.status == CoverageStatus::Passed
+            && (c.category != "frontend" || super::is_frontend_prompt(prompt))
+    };
+    StrengthReport {
+        strengths: coverage
+            .iter()
+            .filter(confirmed)
+            .map(|c| Strength {
+                category: c.category.clone(),
+                display_name: c.display_name.clone(),
+                practice: practice(&c.category),
+            })
+            .collect(),
+        not_checked: coverage
+            .iter()
+            .filter(|c| c.status != CoverageStatus::Flagged && !confirmed(c))
+            .count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_confirmed_categories_only() {
+        let prompt = "Summarize the attached incident report in three bullet points for the on-call engineer.";
+        let issues = crate::analyzer::analyze(prompt, None).unwrap();
+        let coverage = crate::analyzer::coverage::coverage(prompt, None, &issues);
+        let report = report(prompt, &coverage);
+
+        let listed: Vec<&str> = report
+            .strengths
+            .iter()
+            .map(|s| s.category.as_str())
+            .collect();
+        assert!(listed.contains(&"hygiene"), "{:?}", listed);
+        // Frontend rules ran but had no UI request to check
+        assert!(!listed.contains(&"frontend"), "{:?}", listed);
+        assert_eq!(listed.len() + report.not_checked, coverage.len());
+        for strength in &report.strengths {
+            assert_ne!(strength.practice, practice("unknown"));
+        }
+    }
+}
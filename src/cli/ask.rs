@@ -0,0 +1,87 @@
+//! Questions asked on the terminal
+//!
+//! Confirmations and pickers come from dialoguer behind the `interactive`
+//! feature. Builds without it never ask: [`enabled`] is false, so callers
+//! take the path they take when stdin isn't a terminal, and the functions
+//! below answer with their defaults.
+
+use anyhow::Result;
+
+/// Whether this build can ask questions at all
+pub fn enabled() -> bool {
+    cfg!(feature = "interactive")
+}
+
+#[cfg(feature = "interactive")]
+mod prompts {
+    use anyhow::Result;
+    use dialoguer::theme::ColorfulTheme;
+    use dialoguer::{Confirm, MultiSelect, Password, Select};
+
+    pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+        Ok(Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(default)
+            .interact()?)
+    }
+
+    pub fn select(prompt: &str, items: &[&str]) -> Result<Option<usize>> {
+        Ok(Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .interact_opt()?)
+    }
+
+    pub fn multi_select(items: &[String]) -> Result<Vec<usize>> {
+        Ok(MultiSelect::with_theme(&ColorfulTheme::default())
+            .items(items)
+            .defaults(&vec![false; items.len()])
+            .interact()?)
+    }
+
+    pub fn password(prompt: &str) -> Result<String> {
+        Ok(Password::new().with_prompt(prompt).interact()?)
+    }
+}
+
+#[cfg(not(feature = "interactive"))]
+mod prompts {
+    use anyhow::Result;
+
+    pub fn confirm(_prompt: &str, default: bool) -> Result<bool> {
+        Ok(default)
+    }
+
+    pub fn select(_prompt: &str, _items: &[&str]) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    pub fn multi_select(_items: &[String]) -> Result<Vec<usize>> {
+        Ok(Vec::new())
+    }
+
+    pub fn password(prompt: &str) -> Result<String> {
+        Err(crate::CoptError::feature_disabled("interactive", prompt).into())
+    }
+}
+
+/// Yes or no, `default` on Enter
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    prompts::confirm(prompt, default)
+}
+
+/// One of `items` (the first on Enter), or `None` on Esc
+pub fn select(prompt: &str, items: &[&str]) -> Result<Option<usize>> {
+    prompts::select(prompt, items)
+}
+
+/// Any of `items`, none selected to start with
+pub fn multi_select(items: &[String]) -> Result<Vec<usize>> {
+    prompts::multi_select(items)
+}
+
+/// A secret typed without echo
+pub fn password(prompt: &str) -> Result<String> {
+    prompts::password(prompt)
+}
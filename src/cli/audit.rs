@@ -0,0 +1,326 @@
+//! Quality gate for already-optimized prompts (`copt audit`)
+//!
+//! Where `copt lint` surveys many files, `audit` checks one prompt that is
+//! meant to be finished: it reports residual issues, the quality score, and
+//! structural problems (unbalanced XML tags, missing task or output format
+//! sections), then exits non-zero when the prompt falls short so it can gate
+//! CI.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::lint::FailOn;
+use crate::analyzer::coverage::{self, CategoryCoverage};
+use crate::analyzer::segment::{self, Section, SectionKind, XmlProblem};
+use crate::analyzer::{self, Issue, Severity};
+use crate::tui::severity;
+
+/// Sections every finished prompt is expected to have
+const EXPECTED_SECTIONS: &[SectionKind] = &[SectionKind::Task, SectionKind::OutputFormat];
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    /// Prompt file to audit
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Fail when the quality score is below this (0-100)
+    #[arg(long, value_name = "SCORE", default_value = "80", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub min_score: u8,
+
+    /// Fail on residual issues at or above this severity
+    #[arg(long, value_enum, value_name = "SEVERITY", default_value = "error")]
+    pub fail_on: FailOn,
+
+    /// Also fail when the task or output format section is missing
+    #[arg(long)]
+    pub require_sections: bool,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: AuditFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuditFormat {
+    Text,
+    Json,
+}
+
+/// Audit results for one prompt
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    pub path: String,
+    pub passed: bool,
+    pub score: u8,
+    pub min_score: u8,
+    pub sections: Vec<Section>,
+    pub missing_sections: Vec<SectionKind>,
+    pub xml_problems: Vec<XmlProblem>,
+    pub issues: Vec<Issue>,
+    /// Every rule category, checked or skipped and why
+    pub coverage: Vec<CategoryCoverage>,
+    /// Why the audit failed, one entry per failed check
+    pub failures: Vec<String>,
+}
+
+/// Audit `prompt` against the thresholds in `args`; `check_categories` is
+/// the `--check` the issues were found with
+pub fn audit(
+    path: String,
+    prompt: &str,
+    issues: Vec<Issue>,
+    check_categories: Option<&[String]>,
+    args: &AuditArgs,
+) -> AuditReport {
+    let score = analyzer::quality_score(&issues);
+    let sections = segment::segment_prompt(prompt);
+    let missing_sections: Vec<SectionKind> = EXPECTED_SECTIONS
+        .iter()
+        .copied()
+        .filter(|kind| !segment::has_section(&sections, *kind))
+        .collect();
+    let xml_problems = segment::check_xml_balance(prompt);
+    let coverage = coverage::coverage(prompt, check_categories, &issues);
+
+    let mut failures = Vec::new();
+    if score < args.min_score {
+        failures.push(format!(
+            "quality score {} is below --min-score {}",
+            score, args.min_score
+        ));
+    }
+    let failing = issues
+        .iter()
+        .filter(|i| args.fail_on.is_triggered_by(&i.severity))
+        .count();
+    if failing > 0 {
+        failures.push(format!(
+            "{} issue{} at or above {:?} severity",
+            failing,
+            plural(failing),
+            args.fail_on
+        ));
+    }
+    if !xml_problems.is_empty() {
+        failures.push(format!(
+            "{} unbalanced XML tag{}",
+            xml_problems.len(),
+            plural(xml_problems.len())
+        ));
+    }
+    if args.require_sections && !missing_sections.is_empty() {
+        failures.push(format!(
+            "missing sections: {}",
+            section_names(&missing_sections)
+        ));
+    }
+
+    AuditReport {
+        path,
+        passed: failures.is_empty(),
+        score,
+        min_score: args.min_score,
+        sections,
+        missing_sections,
+        xml_problems,
+        issues,
+        coverage,
+        failures,
+    }
+}
+
+/// Run `copt audit`
+pub fn run(
+    args: &AuditArgs,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let prompt = crate::utils::file::read_prompt_file(&args.file)?;
+    let prompt = super::provenance::strip(&prompt);
+    let mut issues = analyzer::cache::analyze(prompt, check_categories)?;
+    if let Some(min) = min_confidence {
+        issues.retain(|i| i.confidence >= min);
+    }
+    let issues = crate::utils::redact::issues(&issues);
+
+    let report = audit(
+        args.file.display().to_string(),
+        prompt,
+        issues,
+        check_categories,
+        args,
+    );
+    match args.format {
+        AuditFormat::Text => print_report(&report),
+        AuditFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    if !report.passed {
+        anyhow::bail!("Audit failed: {}", report.failures.join("; "));
+    }
+    Ok(())
+}
+
+fn print_report(report: &AuditReport) {
+    let check = |ok: bool| {
+        if ok {
+            "✓".green()
+        } else {
+            "✗".red()
+        }
+    };
+
+    println!();
+    println!("  {} {}", "Audit".bold(), report.path);
+    println!();
+    println!(
+        "  {} Quality score {}/100 (minimum {})",
+        check(report.score >= report.min_score),
+        report.score,
+        report.min_score
+    );
+
+    let structure = if report.sections.is_empty() {
+        "no sections detected".to_string()
+    } else {
+        segment::structure_summary(&report.sections)
+    };
+    println!(
+        "  {} Structure: {}",
+        check(report.missing_sections.is_empty()),
+        structure
+    );
+    if !report.missing_sections.is_empty() {
+        println!(
+            "      {}",
+            format!("Missing: {}", section_names(&report.missing_sections)).yellow()
+        );
+    }
+
+    if report.xml_problems.is_empty() {
+        println!("  {} XML tags balanced", check(true));
+    } else {
+        println!(
+            "  {} {} unbalanced XML tag{}",
+            check(false),
+            report.xml_problems.len(),
+            plural(report.xml_problems.len())
+        );
+        for problem in &report.xml_problems {
+            println!(
+                "      {} {}",
+                format!("L{}", problem.line).bright_black(),
+                problem.message
+            );
+        }
+    }
+
+    if report.issues.is_empty() {
+        println!("  {} No residual issues", check(true));
+    } else {
+        println!(
+            "  {} {} residual issue{}",
+            "•".bright_black(),
+            report.issues.len(),
+            plural(report.issues.len())
+        );
+        for issue in &report.issues {
+            let label = match issue.severity {
+                Severity::Error => "error  ",
+                Severity::Warning => "warning",
+                Severity::Info => "info   ",
+            };
+            let severity = severity::paint(issue.severity, label);
+            let line = issue
+                .line
+                .map(|l| format!("L{:<4}", l))
+                .unwrap_or_else(|| " ".repeat(5));
+            println!(
+                "      {} {} {} {}",
+                severity,
+                line.bright_black(),
+                issue.id.bold(),
+                issue.message
+            );
+        }
+    }
+
+    println!();
+    let _ = crate::tui::linear::write_coverage(&mut std::io::stdout(), &report.coverage);
+    if report.passed {
+        println!("  {}", "PASS".green().bold());
+    } else {
+        println!("  {} {}", "FAIL".red().bold(), report.failures.join("; "));
+    }
+    println!();
+}
+
+fn section_names(kinds: &[SectionKind]) -> String {
+    kinds
+        .iter()
+        .map(|k| k.display_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        audit: AuditArgs,
+    }
+
+    fn args(extra: &[&str]) -> AuditArgs {
+        let mut argv = vec!["copt", "prompt.md"];
+        argv.extend_from_slice(extra);
+        TestCli::parse_from(argv).audit
+    }
+
+    fn run_audit(prompt: &str, args: &AuditArgs) -> AuditReport {
+        let issues = analyzer::analyze(prompt, None).unwrap();
+        audit("prompt.md".to_string(), prompt, issues, None, args)
+    }
+
+    const OPTIMIZED: &str = "<instructions>\nSummarize the incident report for the on-call engineer so they can decide whether to page the database team.\n</instructions>\n\n<output_format>\nReturn three bullet points: impact, root cause, and next step.\n</output_format>";
+
+    #[test]
+    fn test_clean_prompt_passes() {
+        let report = run_audit(OPTIMIZED, &args(&["--require-sections"]));
+        assert!(report.passed, "{:?}", report.failures);
+        assert!(report.missing_sections.is_empty());
+        assert!(report.xml_problems.is_empty());
+        assert!(report
+            .coverage
+            .iter()
+            .any(|c| c.category == "style" && c.status.evaluated()));
+    }
+
+    #[test]
+    fn test_unbalanced_xml_and_low_score_fail() {
+        let prompt = OPTIMIZED.replace("</output_format>", "");
+        let report = run_audit(&prompt, &args(&[]));
+        assert!(!report.passed);
+        assert_eq!(report.xml_problems.len(), 1);
+        assert!(report.failures[0].contains("unbalanced XML tag"));
+
+        let report = run_audit("Can you make a dashboard?", &args(&["--min-score", "100"]));
+        assert!(report.failures[0].starts_with("quality score"));
+        assert!(!report.missing_sections.is_empty());
+    }
+}
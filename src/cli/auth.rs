@@ -0,0 +1,149 @@
+//! API key management (`copt auth login/status/logout`)
+//!
+//! Saves the Anthropic API key in the OS keychain so it doesn't have to live
+//! in a shell profile. Bedrock uses the AWS credential chain and needs no
+//! key here.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use std::io::{IsTerminal, Read};
+
+use crate::llm::credentials::{self, KeySource, ANTHROPIC_API_KEY_ENV};
+
+#[derive(Args, Debug)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthCommand {
+    /// Save an API key in the OS keychain (prompts, or reads stdin when piped)
+    Login {
+        #[arg(long, value_enum, default_value = "anthropic")]
+        provider: AuthProvider,
+    },
+    /// Show which API key copt will use and where it comes from
+    Status {
+        #[arg(long, value_enum, default_value = "anthropic")]
+        provider: AuthProvider,
+    },
+    /// Remove the saved API key from the OS keychain
+    Logout {
+        #[arg(long, value_enum, default_value = "anthropic")]
+        provider: AuthProvider,
+    },
+}
+
+/// Providers that authenticate with an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuthProvider {
+    Anthropic,
+}
+
+/// Run `copt auth`
+pub fn run(args: &AuthArgs) -> Result<()> {
+    match args.command {
+        AuthCommand::Login {
+            provider: AuthProvider::Anthropic,
+        } => login(),
+        AuthCommand::Status {
+            provider: AuthProvider::Anthropic,
+        } => status(),
+        AuthCommand::Logout {
+            provider: AuthProvider::Anthropic,
+        } => logout(),
+    }
+}
+
+fn login() -> Result<()> {
+    // Without the key prompt, a terminal on stdin reads the key until Ctrl-D
+    let key = if super::ask::enabled() && std::io::stdin().is_terminal() {
+        super::ask::password("Anthropic API key").context("Failed to read the API key")?
+    } else {
+        let mut key = String::new();
+        std::io::stdin()
+            .read_to_string(&mut key)
+            .context("Failed to read the API key from stdin")?;
+        key
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("No API key given");
+    }
+    if !key.starts_with("sk-ant-") {
+        eprintln!(
+            "  {} This doesn't look like an Anthropic API key (they start with sk-ant-); saving it anyway",
+            "⚠".yellow()
+        );
+    }
+
+    credentials::store_anthropic_key(key)?;
+    println!(
+        "{} Saved Anthropic API key {} to the OS keychain",
+        "✓".green(),
+        credentials::mask_key(key).bright_black()
+    );
+    if std::env::var(ANTHROPIC_API_KEY_ENV).is_ok() {
+        println!(
+            "  {}",
+            format!(
+                "The saved key takes precedence over {}; you can remove it from your shell profile.",
+                ANTHROPIC_API_KEY_ENV
+            )
+            .bright_black()
+        );
+    }
+    Ok(())
+}
+
+fn status() -> Result<()> {
+    let keychain = match credentials::keychain_anthropic_key() {
+        Ok(Some(key)) => credentials::mask_key(&key),
+        Ok(None) => "not set".to_string(),
+        Err(e) => format!("unavailable ({:#})", e),
+    };
+    let env = match std::env::var(ANTHROPIC_API_KEY_ENV) {
+        Ok(key) if !key.trim().is_empty() => credentials::mask_key(&key),
+        _ => "not set".to_string(),
+    };
+
+    println!();
+    println!("  {}", "Anthropic API key".bold());
+    println!("    {:<22} {}", "OS keychain", keychain);
+    println!("    {:<22} {}", ANTHROPIC_API_KEY_ENV, env);
+    println!();
+    match credentials::anthropic_api_key() {
+        Ok((_, source)) => {
+            println!("  {} Using the key from the {}", "✓".green(), source);
+            Ok(())
+        }
+        Err(_) => anyhow::bail!(
+            "No Anthropic API key configured. Run `copt auth login --provider anthropic` or set {}.",
+            ANTHROPIC_API_KEY_ENV
+        ),
+    }
+}
+
+fn logout() -> Result<()> {
+    if credentials::delete_anthropic_key()? {
+        println!(
+            "{} Removed the Anthropic API key from the OS keychain",
+            "✓".green()
+        );
+    } else {
+        println!("No Anthropic API key was saved in the OS keychain");
+    }
+    if std::env::var(ANTHROPIC_API_KEY_ENV).is_ok() {
+        println!(
+            "  {}",
+            format!(
+                "The {} is still set and will be used.",
+                KeySource::Environment
+            )
+            .bright_black()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,572 @@
+//! Optimize every prompt in a directory (`copt batch`)
+//!
+//! Reads the prompt files under a directory, analyzes and optimizes each, and
+//! writes the results to the same relative paths under the output directory,
+//! so `prompts/agents/review.md` becomes `copt-output/agents/review.md`. LLM
+//! calls run concurrently, at most `--jobs` at a time. A file that can't be
+//! read, is over the input limit, or whose LLM call fails is reported in the
+//! summary and nothing is written for it; the rest still finish.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use futures::StreamExt;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::inspect::InspectFormat;
+use super::lint::{self, FileReport};
+use super::provenance::{self, Provenance};
+use crate::analyzer;
+use crate::llm::{self, LlmClient};
+use crate::optimizer;
+use crate::utils;
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Directory of prompt files (subdirectories included)
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+
+    /// Only files whose path under DIR matches this glob (e.g. '*.md', 'agents/*')
+    #[arg(long, value_name = "PATTERN")]
+    pub glob: Option<String>,
+
+    /// LLM calls to run at once
+    #[arg(
+        short,
+        long,
+        default_value = "4",
+        hide_default_value = true,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub jobs: u16,
+
+    /// Output format: text, json (one line per file, as each finishes)
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: InspectFormat,
+}
+
+/// How each prompt is optimized and where results go
+pub struct Settings<'a> {
+    /// `None` for static fixes only (`--offline`)
+    pub client: Option<&'a dyn LlmClient>,
+    /// Recorded in the provenance footer
+    pub provider: &'a str,
+    pub model: &'a str,
+    /// Root of the mirrored output tree
+    pub output_dir: &'a Path,
+    pub check_categories: Option<&'a [String]>,
+    pub min_confidence: Option<f32>,
+    pub max_bytes: u64,
+}
+
+/// One file's analysis and where its optimized prompt went
+#[derive(Debug, Serialize)]
+pub struct BatchItem {
+    #[serde(flatten)]
+    pub report: FileReport,
+    /// Quality score of the optimized prompt, when there is one
+    pub optimized_score: Option<u8>,
+    /// The optimized prompt, when it was written
+    pub output: Option<String>,
+    /// Why the file wasn't optimized
+    pub error: Option<String>,
+    /// False when the file couldn't be read or was too large to analyze
+    #[serde(skip)]
+    analyzed: bool,
+}
+
+impl BatchItem {
+    /// A file that was never analyzed, and why
+    fn failed(name: &str, error: &anyhow::Error) -> Self {
+        Self {
+            report: FileReport::new(name.to_string(), Vec::new()),
+            optimized_score: None,
+            output: None,
+            error: Some(format!("{:#}", error)),
+            analyzed: false,
+        }
+    }
+}
+
+/// Results for every matched file, in path order
+#[derive(Debug, Serialize)]
+pub struct BatchReport {
+    pub files: Vec<BatchItem>,
+}
+
+impl BatchReport {
+    pub fn failed(&self) -> usize {
+        self.files.iter().filter(|f| f.error.is_some()).count()
+    }
+}
+
+/// The prompt files under `dir` whose relative path matches `glob`, leaving
+/// out `output_dir` so earlier results aren't optimized again
+pub fn matching_prompts(
+    dir: &Path,
+    glob: Option<&str>,
+    output_dir: &Path,
+) -> Result<Vec<(String, Result<String>)>> {
+    let mut prompts = utils::file::read_prompts_from_dir(dir, Some(output_dir))?;
+    if let Some(glob) = glob {
+        let pattern =
+            glob::Pattern::new(glob).with_context(|| format!("Invalid glob pattern: {}", glob))?;
+        prompts.retain(|(name, _)| pattern.matches(name));
+    }
+
+    if prompts.is_empty() {
+        match glob {
+            Some(glob) => anyhow::bail!("No prompt files in {} match {}", dir.display(), glob),
+            None => anyhow::bail!("No prompt files in {}", dir.display()),
+        }
+    }
+    Ok(prompts)
+}
+
+/// Analyze and optimize `prompts`, writing each result under
+/// `settings.output_dir` and passing it to `on_file` as it finishes
+///
+/// A prompt that couldn't be read or is over `settings.max_bytes` becomes a
+/// failed item rather than stopping the batch.
+pub async fn optimize_all(
+    prompts: &[(String, Result<String>)],
+    settings: &Settings<'_>,
+    jobs: usize,
+    mut on_file: impl FnMut(usize, &BatchItem) -> Result<()>,
+) -> Result<BatchReport> {
+    let total = prompts.len();
+    // Results arrive as they finish; the index puts the report back in
+    // path order
+    let mut results = futures::stream::iter(prompts.iter().enumerate())
+        .map(|(i, (name, prompt))| async move {
+            utils::progress::item(i + 1, total, Some(name.clone()));
+            (i, process(name, prompt, settings).await)
+        })
+        .buffer_unordered(jobs.max(1));
+
+    let mut files = Vec::with_capacity(total);
+    while let Some((i, item)) = results.next().await {
+        let item = item?;
+        on_file(files.len() + 1, &item)?;
+        files.push((i, item));
+    }
+    files.sort_by_key(|(i, _)| *i);
+    Ok(BatchReport {
+        files: files.into_iter().map(|(_, item)| item).collect(),
+    })
+}
+
+/// One file's result; a prompt that couldn't be read or is too large fails
+/// without being analyzed
+async fn process(
+    name: &str,
+    prompt: &Result<String>,
+    settings: &Settings<'_>,
+) -> Result<BatchItem> {
+    let prompt = match prompt {
+        Ok(prompt) => prompt,
+        Err(e) => return Ok(BatchItem::failed(name, e)),
+    };
+    if let Err(e) = utils::input::check_size(prompt.len() as u64, settings.max_bytes, name) {
+        return Ok(BatchItem::failed(name, &e));
+    }
+    optimize_one(name, provenance::strip(prompt), settings).await
+}
+
+async fn optimize_one(name: &str, prompt: &str, settings: &Settings<'_>) -> Result<BatchItem> {
+    let mut issues = analyzer::cache::analyze(prompt, settings.check_categories)?;
+    if let Some(min) = settings.min_confidence {
+        issues.retain(|i| i.confidence >= min);
+    }
+
+    let optimized = match settings.client {
+        Some(client) => {
+            optimizer::optimize_with_llm(
+                prompt,
+                &issues,
+                client,
+                settings.model,
+                analyzer::classify_prompt(prompt),
+                settings.check_categories,
+            )
+            .await
+        }
+        None => optimizer::optimize_static(prompt, &issues),
+    };
+
+    let saved = optimized.and_then(|optimized| {
+        let mut optimized_issues = analyzer::cache::analyze(&optimized, settings.check_categories)?;
+        if let Some(min) = settings.min_confidence {
+            optimized_issues.retain(|i| i.confidence >= min);
+        }
+        let path = save(name, prompt, &optimized, settings)?;
+        Ok((path, analyzer::quality_score(&optimized_issues)))
+    });
+    let (output, optimized_score, error) = match saved {
+        Ok((path, score)) => (Some(path.display().to_string()), Some(score), None),
+        Err(e) => {
+            tracing::debug!(file = name, "batch item failed: {:#}", e);
+            (None, None, Some(format!("{:#}", e)))
+        }
+    };
+
+    let issues = utils::redact::issues(&issues);
+    Ok(BatchItem {
+        report: FileReport::new(name.to_string(), issues),
+        optimized_score,
+        output,
+        error,
+        analyzed: true,
+    })
+}
+
+/// Write `optimized` to `name` under the output directory, ending with how
+/// it was produced
+///
+/// Like single-prompt saves, the file never shows what `--redact` masks.
+fn save(name: &str, original: &str, optimized: &str, settings: &Settings<'_>) -> Result<PathBuf> {
+    let path = settings.output_dir.join(name);
+    let original = utils::redact::apply(original);
+    let optimized = utils::redact::apply(optimized);
+    let content = if provenance::enabled() {
+        let model = settings
+            .client
+            .map(|_| llm::models::provider_id(settings.provider, settings.model));
+        provenance::append(
+            &optimized,
+            &Provenance::new(settings.provider, model, &original),
+        )
+    } else {
+        optimized.into_owned()
+    };
+    utils::file::write_prompt_file(&path, &content)?;
+    Ok(path)
+}
+
+/// Run `copt batch`
+pub async fn run(args: &BatchArgs, settings: &Settings<'_>) -> Result<()> {
+    utils::sandbox::ensure_writable("Writing batch results")?;
+
+    ensure_separate_output(&args.dir, settings.output_dir)?;
+
+    let started = Instant::now();
+    let prompts = matching_prompts(&args.dir, args.glob.as_deref(), settings.output_dir)?;
+    let total = prompts.len();
+    let text = args.format == InspectFormat::Text;
+
+    let report = optimize_all(&prompts, settings, args.jobs as usize, |done, item| {
+        // Spend is tallied process-wide, so with several calls in flight an
+        // item's record may carry some of another's; the month's total is
+        // exact either way. Files that were never analyzed aren't runs.
+        if item.analyzed {
+            super::metrics::record(&super::metrics::RunRecord::new(
+                settings.provider,
+                settings.client.is_none(),
+                &item.report.issues,
+                item.report.score,
+                item.optimized_score.unwrap_or(item.report.score),
+                llm::budget::take_spent(),
+            ));
+        }
+        if text {
+            print_progress(done, total, item);
+            Ok(())
+        } else {
+            lint::write_json_line(&mut std::io::stdout().lock(), item)
+        }
+    })
+    .await?;
+
+    if text {
+        print_table(&report, settings.output_dir);
+    }
+
+    let optimized = total - report.failed();
+    super::notify::finished(
+        started,
+        "copt: batch complete",
+        &format!(
+            "Optimized {} of {} prompt{}",
+            optimized,
+            total,
+            if total == 1 { "" } else { "s" }
+        ),
+    );
+
+    if report.failed() > 0 {
+        anyhow::bail!("{} of {} files failed", report.failed(), total);
+    }
+    Ok(())
+}
+
+/// Refuse to mirror results onto the prompts they came from
+fn ensure_separate_output(dir: &Path, output_dir: &Path) -> Result<()> {
+    let same = match (dir.canonicalize(), output_dir.canonicalize()) {
+        (Ok(dir), Ok(output_dir)) => dir == output_dir,
+        _ => false,
+    };
+    if same {
+        anyhow::bail!(
+            "The output directory is the input directory ({}); results would overwrite the \
+             original prompts. Pass a different --output-dir.",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// "[3/40] agents/review.md" as each file finishes
+fn print_progress(done: usize, total: usize, item: &BatchItem) {
+    let mark = if item.error.is_some() {
+        "✗".red()
+    } else {
+        "✓".green()
+    };
+    println!(
+        "{} {} {}",
+        format!("[{}/{}]", done, total).bright_black(),
+        mark,
+        item.report.path.white()
+    );
+}
+
+fn print_table(report: &BatchReport, output_dir: &Path) {
+    let count = |n: usize| {
+        let text = format!("{:>8}", n);
+        if n == 0 {
+            text.bright_black().to_string()
+        } else {
+            text
+        }
+    };
+
+    println!();
+    println!(
+        "  {:<44} {:>8} {:>8} {:>8} {:>7}  {}",
+        "FILE".bright_black(),
+        "ERRORS".bright_black(),
+        "WARNINGS".bright_black(),
+        "INFO".bright_black(),
+        "SCORE".bright_black(),
+        "RESULT".bright_black()
+    );
+    println!("  {}", "─".repeat(88).bright_black());
+
+    for item in &report.files {
+        let file = &item.report;
+        let result = match item.error {
+            Some(_) => "failed".red(),
+            None => "saved".green(),
+        };
+        println!(
+            "  {:<44} {} {} {} {}  {}",
+            lint::truncate_path(&file.path, 44),
+            count(file.errors),
+            count(file.warnings),
+            count(file.infos),
+            lint::format_score(file.score),
+            result
+        );
+    }
+    println!("  {}", "─".repeat(88).bright_black());
+
+    let failed = report.failed();
+    let saved = report.files.len() - failed;
+    println!(
+        "  {} optimized into {}{}",
+        format!("{} file{}", saved, if saved == 1 { "" } else { "s" })
+            .white()
+            .bold(),
+        output_dir.display(),
+        if failed > 0 {
+            format!(", {}", format!("{} failed", failed).red())
+        } else {
+            String::new()
+        }
+    );
+    for item in report.files.iter().filter(|f| f.error.is_some()) {
+        println!(
+            "  {} {}: {}",
+            "✗".red(),
+            item.report.path,
+            item.error.as_deref().unwrap_or_default()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+    use tempfile::TempDir;
+
+    fn settings<'a>(client: Option<&'a dyn LlmClient>, output_dir: &'a Path) -> Settings<'a> {
+        Settings {
+            client,
+            provider: "mock",
+            model: "claude-sonnet-4-5",
+            output_dir,
+            check_categories: None,
+            min_confidence: None,
+            max_bytes: 1024 * 1024,
+        }
+    }
+
+    #[test]
+    fn test_glob_filters_relative_paths() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("agents")).unwrap();
+        std::fs::write(dir.path().join("agents/review.md"), "Review it.").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "Notes.").unwrap();
+
+        let out = dir.path().join("copt-output");
+        std::fs::create_dir(&out).unwrap();
+        std::fs::write(out.join("old.md"), "Old result.").unwrap();
+
+        let names = |glob| {
+            matching_prompts(dir.path(), glob, &out)
+                .unwrap()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(None), ["agents/review.md", "notes.txt"]);
+        assert_eq!(names(Some("*.md")), ["agents/review.md"]);
+        assert!(matching_prompts(dir.path(), Some("*.yaml"), &out).is_err());
+    }
+
+    #[test]
+    fn test_output_dir_must_differ_from_input() {
+        let dir = TempDir::new().unwrap();
+        assert!(ensure_separate_output(dir.path(), dir.path()).is_err());
+        assert!(ensure_separate_output(dir.path(), &dir.path().join(".")).is_err());
+        assert!(ensure_separate_output(dir.path(), &dir.path().join("copt-output")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_results_mirror_the_tree_and_failures_are_reported() {
+        let out = TempDir::new().unwrap();
+        let prompts = vec![
+            (
+                "a.md".to_string(),
+                Ok("Can you think about this?".to_string()),
+            ),
+            (
+                "sub/b.md".to_string(),
+                Ok("Summarize the report.".to_string()),
+            ),
+        ];
+        let client = MockLlmClient::new();
+        client.push_response("Think about this carefully.");
+        client.push_error("service unavailable");
+
+        let mut seen = Vec::new();
+        let report = optimize_all(
+            &prompts,
+            &settings(Some(&client), out.path()),
+            1,
+            |done, item| {
+                seen.push((done, item.report.path.clone()));
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(seen, [(1, "a.md".to_string()), (2, "sub/b.md".to_string())]);
+        let saved = std::fs::read_to_string(out.path().join("a.md")).unwrap();
+        assert!(saved.starts_with("Think about this carefully."));
+        assert_eq!(report.failed(), 1);
+        assert!(report.files[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("service unavailable"));
+        assert!(!out.path().join("sub/b.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_and_oversized_files_fail_alone() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        std::fs::write(dir.path().join("huge.md"), "Summarize it. ".repeat(100)).unwrap();
+        std::fs::write(dir.path().join("ok.md"), "Summarize the report.").unwrap();
+        let out = dir.path().join("copt-output");
+
+        let prompts = matching_prompts(dir.path(), None, &out).unwrap();
+        let mut settings = settings(None, &out);
+        settings.max_bytes = 512;
+        let report = optimize_all(&prompts, &settings, 2, |_, _| Ok(()))
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed(), 2);
+        assert!(report.files[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("binary.md"));
+        assert!(report.files[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("input limit"));
+        assert!(report.files[2].error.is_none());
+        assert!(out.join("ok.md").exists());
+        assert!(!out.join("huge.md").exists());
+    }
+
+    /// Answers prompts mentioning "slow" only after the others
+    struct SlowClient;
+
+    #[async_trait::async_trait]
+    impl LlmClient for SlowClient {
+        async fn complete(&self, _: &str, message: &str, _: &str, _: u32) -> Result<String> {
+            if message.contains("slow") {
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            }
+            Ok("Summarize the report in three bullet points.".to_string())
+        }
+
+        fn provider_name(&self) -> &str {
+            "slow"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_results_stream_as_they_finish() {
+        let out = TempDir::new().unwrap();
+        let prompts = vec![
+            (
+                "a.md".to_string(),
+                Ok("Summarize the slow report.".to_string()),
+            ),
+            ("b.md".to_string(), Ok("Summarize the report.".to_string())),
+        ];
+
+        let mut seen = Vec::new();
+        let report = optimize_all(
+            &prompts,
+            &settings(Some(&SlowClient), out.path()),
+            2,
+            |_, item| {
+                seen.push(item.report.path.clone());
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(seen, ["b.md", "a.md"]);
+        let paths: Vec<&str> = report
+            .files
+            .iter()
+            .map(|f| f.report.path.as_str())
+            .collect();
+        assert_eq!(paths, ["a.md", "b.md"]);
+    }
+}
@@ -8,6 +8,8 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::error::CoptError;
+
 /// Main configuration structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
@@ -22,6 +24,26 @@ pub struct Config {
     pub output: OutputConfig,
     /// Rules settings
     pub rules: RulesConfig,
+    /// Local metrics settings
+    pub metrics: MetricsConfig,
+    /// Spelling and terminology rule settings
+    pub language: LanguageConfig,
+    /// Input size limits
+    pub input: InputConfig,
+    /// Names masked by --redact
+    pub redact: RedactConfig,
+    /// Model aliases
+    pub models: ModelsConfig,
+    /// Duplicate-run detection
+    pub history: HistoryConfig,
+    /// LLM spend limits
+    pub budget: BudgetConfig,
+    /// Details --anonymize keeps from the LLM provider
+    pub anonymize: AnonymizeConfig,
+    /// Issue severity colors and icons
+    pub severity: SeverityConfig,
+    /// Interactive TUI key bindings, by action
+    pub keys: std::collections::BTreeMap<String, KeyList>,
 }
 
 /// Default configuration settings
@@ -53,6 +75,14 @@ pub struct AnthropicConfig {
     pub max_tokens: u32,
     /// API base URL (for custom endpoints)
     pub base_url: Option<String>,
+    /// Extra headers for a gateway; `${VAR}` in a value reads the environment
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// Command that prints an access token for the gateway (e.g. an OAuth token)
+    pub auth_command: Option<String>,
+    /// Header that carries the `auth_command` token
+    pub auth_header: String,
+    /// Send the Anthropic API key to `base_url` as well
+    pub send_api_key: bool,
 }
 
 impl Default for AnthropicConfig {
@@ -61,6 +91,23 @@ impl Default for AnthropicConfig {
             api_key_env: "ANTHROPIC_API_KEY".to_string(),
             max_tokens: 4096,
             base_url: None,
+            headers: std::collections::BTreeMap::new(),
+            auth_command: None,
+            auth_header: "Authorization".to_string(),
+            send_api_key: false,
+        }
+    }
+}
+
+impl AnthropicConfig {
+    /// Gateway settings for the Anthropic client
+    pub fn gateway(&self) -> crate::llm::GatewayOptions {
+        crate::llm::GatewayOptions {
+            base_url: self.base_url.clone(),
+            headers: self.headers.clone(),
+            auth_command: self.auth_command.clone(),
+            auth_header: self.auth_header.clone(),
+            send_api_key: self.send_api_key,
         }
     }
 }
@@ -75,6 +122,8 @@ pub struct BedrockConfig {
     pub profile: Option<String>,
     /// Maximum tokens for requests
     pub max_tokens: u32,
+    /// Switch to a region that offers the model's inference profile instead of failing
+    pub region_failover: bool,
 }
 
 impl Default for BedrockConfig {
@@ -83,6 +132,7 @@ impl Default for BedrockConfig {
             region: "us-west-2".to_string(),
             profile: None,
             max_tokens: 4096,
+            region_failover: true,
         }
     }
 }
@@ -97,6 +147,14 @@ pub struct OutputConfig {
     pub format: String,
     /// Show diff by default
     pub show_diff: bool,
+    /// Page long pretty output through $PAGER
+    pub pager: bool,
+    /// End saved optimized prompts with a provenance comment
+    pub provenance: bool,
+    /// Post a desktop notification when a run takes longer than `notify_after`
+    pub notify: bool,
+    /// How long a run takes before it notifies (e.g. 30s, 2m)
+    pub notify_after: String,
 }
 
 impl Default for OutputConfig {
@@ -105,6 +163,10 @@ impl Default for OutputConfig {
             color: true,
             format: "pretty".to_string(),
             show_diff: false,
+            pager: true,
+            provenance: true,
+            notify: true,
+            notify_after: "30s".to_string(),
         }
     }
 }
@@ -122,6 +184,14 @@ pub struct RulesConfig {
     /// Severity overrides (rule_id -> severity)
     #[serde(default)]
     pub severity_overrides: std::collections::HashMap<String, String>,
+    /// URL of a shared rule pack (TOML) pulled into every analysis
+    pub remote: Option<String>,
+    /// Expected SHA-256 of the remote rule pack (hex); mismatches are rejected
+    pub remote_sha256: Option<String>,
+    /// Hours a cached remote rule pack is used before it is re-fetched
+    pub remote_ttl_hours: u64,
+    /// Domain acronyms never treated as ALL CAPS emphasis (e.g. "SLA", "HIPAA")
+    pub acronyms: Vec<String>,
 }
 
 impl Default for RulesConfig {
@@ -131,10 +201,158 @@ impl Default for RulesConfig {
             disabled: Vec::new(),
             disabled_categories: Vec::new(),
             severity_overrides: std::collections::HashMap::new(),
+            remote: None,
+            remote_sha256: None,
+            remote_ttl_hours: 24,
+            acronyms: Vec::new(),
+        }
+    }
+}
+
+/// Local metrics configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Record runs to the local metrics file (never sent anywhere)
+    pub enabled: bool,
+    /// Take `metrics.jsonl.lock` while appending, for parallel runs on shared storage
+    pub lock: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lock: false,
+        }
+    }
+}
+
+/// Input size limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputConfig {
+    /// Largest prompt read from a file, stdin, or the command line, in bytes
+    pub max_bytes: u64,
+    /// Largest prompt sent to the LLM in one request, in estimated tokens
+    pub max_llm_tokens: usize,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            // The rewrite comes back in at most 4096 output tokens
+            max_llm_tokens: 3000,
         }
     }
 }
 
+/// Redaction configuration (`--redact`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactConfig {
+    /// Names masked in saved files and reports, alongside emails and API keys
+    pub names: Vec<String>,
+}
+
+/// Model configuration (`copt models`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelsConfig {
+    /// Extra `--model` aliases, e.g. `fast = "haiku"` or a full model ID
+    pub aliases: std::collections::BTreeMap<String, String>,
+}
+
+/// Duplicate-run detection (`history.jsonl`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Record LLM runs and warn before optimizing the same prompt again
+    pub enabled: bool,
+    /// How far back a previous run counts as a duplicate
+    pub window_hours: u64,
+    /// Stop instead of warning when a run is a duplicate
+    pub fail_on_duplicate: bool,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_hours: 24,
+            fail_on_duplicate: false,
+        }
+    }
+}
+
+/// LLM spend limits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Estimated LLM spend allowed per calendar month, in USD
+    pub monthly_cap: Option<f64>,
+}
+
+/// Details `--anonymize` replaces with placeholders, besides the ones it
+/// detects on its own
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnonymizeConfig {
+    /// People's names
+    pub names: Vec<String>,
+    /// Company, customer, and product names
+    pub companies: Vec<String>,
+    /// Internal domains; every host under one is replaced
+    pub domains: Vec<String>,
+}
+
+/// Issue severity colors and icons (`[severity.error]`, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityConfig {
+    pub error: SeverityStyleConfig,
+    pub warning: SeverityStyleConfig,
+    pub info: SeverityStyleConfig,
+}
+
+/// How one severity is shown
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityStyleConfig {
+    /// Color name ("magenta", "bright blue") or "#rrggbb"
+    pub color: Option<String>,
+    /// Icon shown in place of the built-in one
+    pub icon: Option<String>,
+}
+
+/// Keys for one TUI action: `copy = "y"` or `quit = ["q", "ctrl+q"]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Language rule configuration (LNG)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// Run language rules on every analysis, not only with --check language
+    pub enabled: bool,
+    /// Words LNG001 never flags as misspellings (product names, jargon)
+    pub dictionary: Vec<String>,
+}
+
 /// Provider configuration enum for runtime use
 #[derive(Debug, Clone)]
 pub enum ProviderConfig {
@@ -166,8 +384,13 @@ pub fn load_config_from_path(path: &PathBuf) -> Result<Config> {
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
-    let config: Config = toml::from_str(&content)
-        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    let config: Config = toml::from_str(&content).map_err(|e| {
+        let diagnostic = super::config_check::parse_error(&content, &e);
+        CoptError::Config(format!(
+            "Failed to parse config file {}",
+            diagnostic.render(path)
+        ))
+    })?;
 
     Ok(config)
 }
@@ -223,21 +446,21 @@ impl Config {
         // Validate provider
         let valid_providers = ["anthropic", "bedrock"];
         if !valid_providers.contains(&self.default.provider.as_str()) {
-            anyhow::bail!(
+            return Err(CoptError::Config(format!(
                 "Invalid provider '{}'. Valid options: {:?}",
-                self.default.provider,
-                valid_providers
-            );
+                self.default.provider, valid_providers
+            ))
+            .into());
         }
 
         // Validate output format
         let valid_formats = ["pretty", "json", "quiet"];
         if !valid_formats.contains(&self.output.format.as_str()) {
-            anyhow::bail!(
+            return Err(CoptError::Config(format!(
                 "Invalid output format '{}'. Valid options: {:?}",
-                self.output.format,
-                valid_formats
-            );
+                self.output.format, valid_formats
+            ))
+            .into());
         }
 
         Ok(())
@@ -255,8 +478,11 @@ impl Config {
 
     /// Check if a rule is enabled
     pub fn is_rule_enabled(&self, rule_id: &str) -> bool {
-        // Check if explicitly disabled
-        if self.rules.disabled.contains(&rule_id.to_string()) {
+        // Check if explicitly disabled, under its current or a retired ID
+        if crate::analyzer::deprecated::migrate(&self.rules.disabled)
+            .iter()
+            .any(|id| id == rule_id)
+        {
             return false;
         }
 
@@ -283,12 +509,24 @@ impl Config {
 
     /// Get severity override for a rule
     pub fn get_severity_override(&self, rule_id: &str) -> Option<&String> {
-        self.rules.severity_overrides.get(rule_id)
+        self.rules.severity_overrides.get(rule_id).or_else(|| {
+            // An override written for the rule's retired ID
+            self.rules
+                .severity_overrides
+                .iter()
+                .find(|(id, _)| {
+                    crate::analyzer::deprecated::lookup(id).is_some()
+                        && crate::analyzer::deprecated::current_ids(id)
+                            .iter()
+                            .any(|current| current == rule_id)
+                })
+                .map(|(_, severity)| severity)
+        })
     }
 }
 
 /// Map rule prefix to category name
-fn category_from_prefix(prefix: &str) -> Option<&'static str> {
+pub(crate) fn category_from_prefix(prefix: &str) -> Option<&'static str> {
     match prefix.to_uppercase().as_str() {
         "EXP" => Some("explicitness"),
         "STY" => Some("style"),
@@ -298,6 +536,8 @@ fn category_from_prefix(prefix: &str) -> Option<&'static str> {
         "AGT" => Some("agentic"),
         "LHT" => Some("long_horizon"),
         "FED" => Some("frontend"),
+        "HYG" => Some("hygiene"),
+        "LNG" => Some("language"),
         _ => None,
     }
 }
@@ -357,4 +597,42 @@ mod tests {
         let parsed: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.default.provider, config.default.provider);
     }
+
+    #[test]
+    fn test_language_config() {
+        let config: Config =
+            toml::from_str("[language]\nenabled = true\ndictionary = [\"Acme\"]").unwrap();
+        assert!(config.language.enabled);
+        assert_eq!(config.language.dictionary, vec!["Acme"]);
+        assert!(!Config::default().language.enabled);
+    }
+
+    #[test]
+    fn test_input_config() {
+        let config: Config = toml::from_str("[input]\nmax_llm_tokens = 8000").unwrap();
+        assert_eq!(config.input.max_llm_tokens, 8000);
+        assert_eq!(config.input.max_bytes, 1024 * 1024);
+    }
+
+    #[test]
+    fn test_models_config() {
+        let config: Config =
+            toml::from_str("[models.aliases]\nfast = \"haiku\"\nreview = \"opus\"").unwrap();
+        assert_eq!(config.models.aliases["fast"], "haiku");
+        assert_eq!(config.models.aliases.len(), 2);
+    }
+
+    #[test]
+    fn test_anthropic_gateway_config() {
+        let config: Config = toml::from_str(
+            "[anthropic]\nbase_url = \"https://gw.example.com\"\nauth_command = \"get-token\"\n\n\
+             [anthropic.headers]\nx-api-gateway-key = \"${GATEWAY_KEY}\"",
+        )
+        .unwrap();
+        let gateway = config.anthropic.gateway();
+        assert!(gateway.is_gateway());
+        assert_eq!(gateway.headers["x-api-gateway-key"], "${GATEWAY_KEY}");
+        assert_eq!(gateway.auth_header, "Authorization");
+        assert!(!gateway.send_api_key);
+    }
 }
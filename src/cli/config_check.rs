@@ -0,0 +1,687 @@
+//! Config file diagnostics (`copt config validate`)
+//!
+//! serde stops at the first problem in a config file and reports it as a bare
+//! toml error, and a misspelled key (`[output] colour = false`) is no problem
+//! to serde at all: it's dropped and copt quietly runs on the default. This
+//! module reads the file the way copt does and reports every problem with its
+//! key path, line and column, and the values the key accepts. Errors stop
+//! every command before it runs; unknown keys and rule IDs copt doesn't ship
+//! are warnings.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::Serialize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use super::config::{self, Config};
+use super::inspect::InspectFormat;
+use crate::analyzer::{self, deprecated, docs};
+use crate::error::CoptError;
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Check the config file and report every problem with its line and the allowed values
+    Validate(ValidateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Config file to check (defaults to the one copt reads)
+    pub file: Option<PathBuf>,
+
+    /// Fail on warnings (unknown keys and rule IDs) as well as errors
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: InspectFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// copt can't use the value
+    Error,
+    /// copt ignores the key or value
+    Warning,
+}
+
+/// One problem in a config file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// Dotted key path, e.g. `output.format`; empty for syntax errors
+    pub key: String,
+    /// 1-based line, when the problem can be located
+    pub line: Option<usize>,
+    /// 1-based column, in characters
+    pub column: Option<usize>,
+    pub message: String,
+    /// The values the key accepts, when there is a fixed set
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+}
+
+impl Diagnostic {
+    /// `config.toml:4:10: output.format: ... (allowed: pretty, json, quiet)`
+    pub fn render(&self, path: &Path) -> String {
+        let mut text = path.display().to_string();
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            text.push_str(&format!(":{}:{}", line, column));
+        }
+        text.push_str(": ");
+        if !self.key.is_empty() {
+            text.push_str(&format!("{}: ", self.key));
+        }
+        text.push_str(&self.message);
+        if !self.allowed.is_empty() {
+            text.push_str(&format!(" (allowed: {})", self.allowed.join(", ")));
+        }
+        text
+    }
+}
+
+/// Where each key of a config file is, by dotted path
+struct Locations<'a> {
+    content: &'a str,
+    entries: Vec<Entry>,
+}
+
+/// One key or array element; array elements are `path.index`
+struct Entry {
+    path: String,
+    key: Option<Range<usize>>,
+    value: Option<Range<usize>>,
+}
+
+impl<'a> Locations<'a> {
+    fn parse(content: &'a str) -> Self {
+        let mut entries = Vec::new();
+        if let Ok(document) = toml_edit::ImDocument::parse(content) {
+            index(document.as_table(), "", &mut entries);
+        }
+        Self { content, entries }
+    }
+
+    /// Line and column of the key at `path`
+    fn find(&self, path: &str) -> (Option<usize>, Option<usize>) {
+        self.entries
+            .iter()
+            .find(|entry| entry.path == path)
+            .and_then(|entry| entry.key.clone().or_else(|| entry.value.clone()))
+            .map_or((None, None), |span| {
+                let (line, column) = line_column(self.content, span.start);
+                (Some(line), Some(column))
+            })
+    }
+
+    /// The innermost key whose value covers byte `offset`
+    fn key_at(&self, offset: usize) -> Option<&str> {
+        let covers = |span: &Option<Range<usize>>| {
+            span.as_ref()
+                .is_some_and(|s| s.start <= offset && offset < s.end.max(s.start + 1))
+        };
+        self.entries
+            .iter()
+            .filter(|entry| covers(&entry.value) || covers(&entry.key))
+            .min_by_key(|entry| {
+                entry
+                    .value
+                    .as_ref()
+                    .or(entry.key.as_ref())
+                    .map_or(usize::MAX, |s| s.len())
+            })
+            .map(|entry| entry.path.as_str())
+    }
+}
+
+fn index(table: &dyn toml_edit::TableLike, prefix: &str, entries: &mut Vec<Entry>) {
+    for (name, item) in table.iter() {
+        let path = join(prefix, name);
+        let key_span = table.get_key_value(name).and_then(|(key, _)| key.span());
+        entries.push(Entry {
+            path: path.clone(),
+            key: key_span,
+            value: item.span(),
+        });
+        if let Some(inner) = item.as_table_like() {
+            index(inner, &path, entries);
+        } else if let Some(array) = item.as_array() {
+            for (i, value) in array.iter().enumerate() {
+                entries.push(Entry {
+                    path: format!("{}.{}", path, i),
+                    key: None,
+                    value: value.span(),
+                });
+            }
+        }
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// 1-based line and column of byte `offset`
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// The diagnostic for a file that doesn't parse into [`Config`]
+pub fn parse_error(content: &str, error: &toml::de::Error) -> Diagnostic {
+    let locations = Locations::parse(content);
+    let span = error.span();
+    let (line, column) = match span {
+        Some(ref span) => {
+            let (line, column) = line_column(content, span.start);
+            (Some(line), Some(column))
+        }
+        None => (None, None),
+    };
+    Diagnostic {
+        level: Level::Error,
+        key: span
+            .and_then(|span| locations.key_at(span.start))
+            .unwrap_or_default()
+            .to_string(),
+        line,
+        column,
+        message: error.message().trim().to_string(),
+        allowed: Vec::new(),
+    }
+}
+
+/// Every problem in a config file, in file order
+pub fn check(content: &str) -> Vec<Diagnostic> {
+    let locations = Locations::parse(content);
+    let mut unknown = Vec::new();
+    let parsed: Result<Config, _> =
+        serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+            let mut segments = Vec::new();
+            path_segments(&path, &mut segments);
+            unknown.push(segments.join("."));
+        });
+    let config = match parsed {
+        Ok(config) => config,
+        Err(e) => return vec![parse_error(content, &e)],
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = unknown
+        .iter()
+        .map(|path| unknown_key(path, &locations))
+        .collect();
+    check_values(&config, &locations, &mut diagnostics);
+    diagnostics.sort_by_key(|d| (d.line.unwrap_or(usize::MAX), d.column));
+    diagnostics
+}
+
+fn path_segments(path: &serde_ignored::Path, segments: &mut Vec<String>) {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            path_segments(parent, segments);
+            segments.push(index.to_string());
+        }
+        Path::Map { parent, key } => {
+            path_segments(parent, segments);
+            segments.push(key.clone());
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_segments(parent, segments),
+    }
+}
+
+fn unknown_key(path: &str, locations: &Locations) -> Diagnostic {
+    let (table, name) = path.rsplit_once('.').unwrap_or(("", path));
+    let known = known_keys(table);
+    let closest = known
+        .iter()
+        .map(|key| (crate::llm::models::edit_distance(name, key), key))
+        .filter(|(distance, key)| *distance <= (key.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance);
+    let place = if table.is_empty() {
+        "at the top level".to_string()
+    } else {
+        format!("in [{}]", table)
+    };
+    let mut message = format!("unknown key {}; copt ignores it", place);
+    if let Some((_, key)) = closest {
+        message.push_str(&format!(". Did you mean '{}'?", key));
+    }
+    let (line, column) = locations.find(path);
+    Diagnostic {
+        level: Level::Warning,
+        key: path.to_string(),
+        line,
+        column,
+        message,
+        allowed: known.iter().map(|k| k.to_string()).collect(),
+    }
+}
+
+/// The keys a config table accepts
+fn known_keys(table: &str) -> &'static [&'static str] {
+    match table {
+        "" => fields::<Config>(),
+        "default" => fields::<config::DefaultConfig>(),
+        "anthropic" => fields::<config::AnthropicConfig>(),
+        "bedrock" => fields::<config::BedrockConfig>(),
+        "output" => fields::<config::OutputConfig>(),
+        "rules" => fields::<config::RulesConfig>(),
+        "metrics" => fields::<config::MetricsConfig>(),
+        "language" => fields::<config::LanguageConfig>(),
+        "input" => fields::<config::InputConfig>(),
+        "redact" => fields::<config::RedactConfig>(),
+        "models" => fields::<config::ModelsConfig>(),
+        "history" => fields::<config::HistoryConfig>(),
+        "budget" => fields::<config::BudgetConfig>(),
+        "anonymize" => fields::<config::AnonymizeConfig>(),
+        "severity" => fields::<config::SeverityConfig>(),
+        "severity.error" | "severity.warning" | "severity.info" => {
+            fields::<config::SeverityStyleConfig>()
+        }
+        _ => &[],
+    }
+}
+
+/// The field names of a config struct, as its `Deserialize` impl lists them
+fn fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// A deserializer that only records the fields a struct asks for
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Values that parse but that copt can't use
+fn check_values(config: &Config, locations: &Locations, out: &mut Vec<Diagnostic>) {
+    let mut push = |level: Level, path: String, message: String, allowed: &[&str]| {
+        let (line, column) = locations.find(&path);
+        let key = match path.rsplit_once('.') {
+            // Array elements are reported under the array's key
+            Some((array, index)) if index.parse::<usize>().is_ok() => array.to_string(),
+            _ => path,
+        };
+        out.push(Diagnostic {
+            level,
+            key,
+            line,
+            column,
+            message,
+            allowed: allowed.iter().map(|a| a.to_string()).collect(),
+        });
+    };
+
+    const PROVIDERS: &[&str] = &["anthropic", "bedrock"];
+    if !PROVIDERS.contains(&config.default.provider.as_str()) {
+        push(
+            Level::Error,
+            "default.provider".into(),
+            format!("'{}' is not a provider", config.default.provider),
+            PROVIDERS,
+        );
+    }
+
+    const FORMATS: &[&str] = &["pretty", "json", "quiet"];
+    if !FORMATS.contains(&config.output.format.as_str()) {
+        push(
+            Level::Error,
+            "output.format".into(),
+            format!("'{}' is not an output format", config.output.format),
+            FORMATS,
+        );
+    }
+    if let Err(e) = crate::llm::parse_duration(&config.output.notify_after) {
+        push(Level::Error, "output.notify_after".into(), e, &[]);
+    }
+
+    let mut with_all = vec!["all"];
+    with_all.extend_from_slice(analyzer::CATEGORIES);
+    for (i, category) in config.rules.enabled_categories.iter().enumerate() {
+        if !with_all.contains(&category.as_str()) {
+            push(
+                Level::Error,
+                format!("rules.enabled_categories.{}", i),
+                format!("'{}' is not a rule category", category),
+                &with_all,
+            );
+        }
+    }
+    for (i, category) in config.rules.disabled_categories.iter().enumerate() {
+        // A rule ID prefix (HYG) works as well as the category name
+        let prefix = category.len() == 3
+            && docs::builtin_rule_ids().any(|id| id[..3].eq_ignore_ascii_case(category));
+        if !prefix && !analyzer::CATEGORIES.contains(&category.as_str()) {
+            push(
+                Level::Error,
+                format!("rules.disabled_categories.{}", i),
+                format!("'{}' is not a rule category or rule prefix", category),
+                analyzer::CATEGORIES,
+            );
+        }
+    }
+    let builtin = |id: &str| docs::builtin_rule_ids().any(|b| b == id);
+    for (i, id) in config.rules.disabled.iter().enumerate() {
+        if let Some(deprecation) = deprecated::lookup(id) {
+            push(
+                Level::Warning,
+                format!("rules.disabled.{}", i),
+                retired(deprecation),
+                &[],
+            );
+        } else if !builtin(id) {
+            push(
+                Level::Warning,
+                format!("rules.disabled.{}", i),
+                format!(
+                    "'{}' is not a built-in rule; it only matters if a rule pack defines it",
+                    id
+                ),
+                &[],
+            );
+        }
+    }
+    const SEVERITIES: &[&str] = &["error", "warning", "info"];
+    let mut overrides: Vec<_> = config.rules.severity_overrides.iter().collect();
+    overrides.sort();
+    for (id, severity) in overrides {
+        let path = format!("rules.severity_overrides.{}", id);
+        if !SEVERITIES.contains(&severity.as_str()) {
+            push(
+                Level::Error,
+                path.clone(),
+                format!("'{}' is not a severity", severity),
+                SEVERITIES,
+            );
+        }
+        if let Some(deprecation) = deprecated::lookup(id) {
+            push(Level::Warning, path, retired(deprecation), &[]);
+        } else if !builtin(id) {
+            push(
+                Level::Warning,
+                path,
+                format!("'{}' is not a built-in rule", id),
+                &[],
+            );
+        }
+    }
+    if let Some(ref sha) = config.rules.remote_sha256 {
+        let hex = sha.trim();
+        let hex = hex.strip_prefix("sha256:").unwrap_or(hex);
+        if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            push(
+                Level::Error,
+                "rules.remote_sha256".into(),
+                format!("'{}' is not a SHA-256 checksum (64 hex digits)", sha),
+                &[],
+            );
+        }
+    }
+
+    for (alias, model) in &config.models.aliases {
+        if !super::is_valid_model(model) {
+            push(
+                Level::Error,
+                format!("models.aliases.{}", alias),
+                format!(
+                    "'{}' is not a model copt knows; use an alias (sonnet, opus, haiku) \
+                    or a full model ID",
+                    model
+                ),
+                &[],
+            );
+        }
+    }
+
+    let severities = [
+        ("error", &config.severity.error),
+        ("warning", &config.severity.warning),
+        ("info", &config.severity.info),
+    ];
+    for (name, style) in severities {
+        if let Some(ref color) = style.color {
+            if let Err(e) = crate::tui::severity::parse_color(color) {
+                push(Level::Error, format!("severity.{}.color", name), e, &[]);
+            }
+        }
+        if style.icon.as_deref().is_some_and(|i| i.trim().is_empty()) {
+            push(
+                Level::Error,
+                format!("severity.{}.icon", name),
+                "empty icon; remove the key to use the built-in one".into(),
+                &[],
+            );
+        }
+    }
+
+    #[cfg(feature = "tui")]
+    {
+        use crate::tui::keys::KeyMap;
+        let mut entry_failed = false;
+        for (action, keys) in &config.keys {
+            let one = std::collections::BTreeMap::from([(action.clone(), keys.clone())]);
+            if let Err(e) = KeyMap::from_config(&one) {
+                entry_failed = true;
+                push(Level::Error, format!("keys.{}", action), e.to_string(), &[]);
+            }
+        }
+        if !entry_failed {
+            if let Err(e) = KeyMap::from_config(&config.keys) {
+                push(Level::Error, "keys".into(), e.to_string(), &[]);
+            }
+        }
+    }
+}
+
+/// The warning for a retired rule ID in the config
+fn retired(deprecation: &deprecated::Deprecation) -> String {
+    format!(
+        "{}; copt applies it to {} until the config names {}",
+        deprecation.describe(),
+        deprecated::current_ids(deprecation.id).join(", "),
+        if deprecation.successors.len() == 1 {
+            "the new ID"
+        } else {
+            "the new IDs"
+        }
+    )
+}
+
+/// Check the config file copt reads, before anything else reads it
+///
+/// Errors fail the run so copt never quietly falls back to the defaults;
+/// warnings go to stderr.
+pub fn check_installed() -> Result<()> {
+    let path = config::get_config_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let diagnostics = check(&content);
+    let (errors, warnings): (Vec<_>, Vec<_>) =
+        diagnostics.iter().partition(|d| d.level == Level::Error);
+    for warning in warnings {
+        eprintln!("  {} {}", "⚠".yellow(), warning.render(&path));
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let lines: Vec<String> = errors.iter().map(|d| d.render(&path)).collect();
+    Err(CoptError::Config(format!("Invalid config file\n{}", lines.join("\n"))).into())
+}
+
+#[derive(Serialize)]
+struct ValidateReport<'a> {
+    file: String,
+    errors: usize,
+    warnings: usize,
+    diagnostics: &'a [Diagnostic],
+}
+
+pub fn run(args: &ConfigArgs) -> Result<()> {
+    match args.command {
+        ConfigCommand::Validate(ref args) => validate(args),
+    }
+}
+
+fn validate(args: &ValidateArgs) -> Result<()> {
+    let path = args.file.clone().unwrap_or_else(config::get_config_path);
+    if args.file.is_none() && !path.exists() {
+        println!(
+            "  {} No config file at {}; copt uses the defaults",
+            "ℹ".cyan(),
+            path.display()
+        );
+        return Ok(());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        CoptError::Config(format!(
+            "Failed to read config file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let diagnostics = check(&content);
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    match args.format {
+        InspectFormat::Json => {
+            let report = ValidateReport {
+                file: path.display().to_string(),
+                errors,
+                warnings,
+                diagnostics: &diagnostics,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        InspectFormat::Text => {
+            println!();
+            for diagnostic in &diagnostics {
+                let icon = match diagnostic.level {
+                    Level::Error => "✗".red(),
+                    Level::Warning => "⚠".yellow(),
+                };
+                println!("  {} {}", icon, diagnostic.render(&path));
+            }
+            if diagnostics.is_empty() {
+                println!("  {} {} is valid", "✓".green(), path.display());
+            } else {
+                println!(
+                    "\n  {} error{}, {} warning{} in {}",
+                    errors,
+                    if errors == 1 { "" } else { "s" },
+                    warnings,
+                    if warnings == 1 { "" } else { "s" },
+                    path.display()
+                );
+            }
+            println!();
+        }
+    }
+
+    if errors > 0 || (args.strict && warnings > 0) {
+        return Err(CoptError::Config(format!(
+            "{} has {} error{} and {} warning{}",
+            path.display(),
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" },
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_keys_and_bad_values_are_located() {
+        let content = "[output]\ncolour = false\nformat = \"yaml\"\n\n\
+                       [rules]\ndisabled_categories = [\"style\", \"HYG\", \"tone\"]\n";
+        let diagnostics = check(content);
+        assert_eq!(diagnostics.len(), 3);
+
+        assert_eq!(diagnostics[0].level, Level::Warning);
+        assert_eq!(diagnostics[0].key, "output.colour");
+        assert_eq!(
+            (diagnostics[0].line, diagnostics[0].column),
+            (Some(2), Some(1))
+        );
+        assert!(diagnostics[0].message.ends_with("Did you mean 'color'?"));
+
+        assert_eq!(diagnostics[1].level, Level::Error);
+        assert_eq!(diagnostics[1].key, "output.format");
+        assert_eq!(diagnostics[1].line, Some(3));
+        assert_eq!(diagnostics[1].allowed, ["pretty", "json", "quiet"]);
+
+        assert_eq!(diagnostics[2].key, "rules.disabled_categories");
+        assert_eq!(
+            (diagnostics[2].line, diagnostics[2].column),
+            (Some(6), Some(40))
+        );
+        assert!(diagnostics[2].message.starts_with("'tone'"));
+    }
+
+    #[test]
+    fn test_type_errors_name_the_key() {
+        let diagnostics = check("[metrics]\nenabled = \"yes\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].key, "metrics.enabled");
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0].message.contains("expected a boolean"));
+
+        let diagnostics = check("[output\nformat = 1\n");
+        assert_eq!(diagnostics[0].line, Some(1));
+        assert!(check(&toml::to_string(&Config::default()).unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+//! Developer tooling subcommands (`copt devtest ...`)
+//!
+//! Not part of the user-facing CLI; used while working on the analyzer.
+
+use crate::analyzer::corpus::{self, DEFAULT_CORPUS_DIR, SNAPSHOT_DIR};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Args, Debug)]
+pub struct DevtestArgs {
+    #[command(subcommand)]
+    pub command: DevtestCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DevtestCommand {
+    /// Regenerate analyzer snapshots for the prompt corpus
+    Corpus {
+        /// Corpus directory (one subdirectory per category)
+        #[arg(long, value_name = "DIR", default_value = DEFAULT_CORPUS_DIR)]
+        dir: PathBuf,
+
+        /// Snapshot output directory
+        #[arg(long, value_name = "DIR", default_value = SNAPSHOT_DIR)]
+        snapshots: PathBuf,
+
+        /// Report stale snapshots without writing (exit 1 if any changed)
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Run the criterion benchmarks (`cargo bench --bench analyzer`)
+    Bench {
+        /// Only run benchmarks whose ID matches (e.g. analyze/10KB)
+        #[arg(value_name = "FILTER")]
+        filter: Option<String>,
+
+        /// Save the results as a named baseline
+        #[arg(long, value_name = "NAME", conflicts_with = "baseline")]
+        save_baseline: Option<String>,
+
+        /// Compare against a saved baseline
+        #[arg(long, value_name = "NAME")]
+        baseline: Option<String>,
+
+        /// Fail if any benchmark's mean is this many percent slower than the baseline
+        #[arg(long, value_name = "PCT", requires = "baseline")]
+        max_regression: Option<f64>,
+
+        /// Stop sampling once results are statistically significant
+        #[arg(long)]
+        quick: bool,
+    },
+}
+
+/// Run a devtest subcommand
+pub fn run(args: &DevtestArgs) -> Result<()> {
+    match &args.command {
+        DevtestCommand::Corpus {
+            dir,
+            snapshots,
+            check,
+        } => run_corpus(dir, snapshots, *check),
+        DevtestCommand::Bench {
+            filter,
+            save_baseline,
+            baseline,
+            max_regression,
+            quick,
+        } => run_bench(
+            filter.as_deref(),
+            save_baseline.as_deref(),
+            baseline.as_deref(),
+            *max_regression,
+            *quick,
+        ),
+    }
+}
+
+/// Regenerate (or verify) the corpus snapshots
+fn run_corpus(dir: &Path, snapshots: &Path, check: bool) -> Result<()> {
+    let entries = corpus::load_corpus(dir)?;
+    let mut changed = 0;
+
+    for entry in &entries {
+        let report = corpus::fired_rules_report(&entry.prompt)?;
+        let rule_ids: Vec<&str> = report.lines().collect();
+
+        let stale = if check {
+            corpus::is_snapshot_stale(snapshots, entry)?
+        } else {
+            corpus::write_snapshot(snapshots, entry)?
+        };
+
+        let marker = if stale {
+            changed += 1;
+            if check {
+                "✗".red().to_string()
+            } else {
+                "↻".yellow().to_string()
+            }
+        } else {
+            "✓".green().to_string()
+        };
+
+        println!(
+            "  {} {:<40} {}",
+            marker,
+            entry.snapshot_name(),
+            rule_ids.join(", ").bright_black()
+        );
+    }
+
+    println!();
+    if check {
+        if changed > 0 {
+            anyhow::bail!(
+                "{} of {} corpus snapshots are stale. Run `copt devtest corpus` to regenerate.",
+                changed,
+                entries.len()
+            );
+        }
+        println!(
+            "{} {} corpus snapshots up to date",
+            "✓".green(),
+            entries.len()
+        );
+    } else {
+        println!(
+            "{} Updated {} of {} corpus snapshots in {}",
+            "✓".green(),
+            changed,
+            entries.len(),
+            snapshots.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run the benchmarks through cargo, then check for regressions against `baseline`
+fn run_bench(
+    filter: Option<&str>,
+    save_baseline: Option<&str>,
+    baseline: Option<&str>,
+    max_regression: Option<f64>,
+    quick: bool,
+) -> Result<()> {
+    let mut args = vec!["bench", "--bench", "analyzer", "--"];
+    args.extend(filter);
+    if let Some(name) = save_baseline {
+        args.extend(["--save-baseline", name]);
+    }
+    if let Some(name) = baseline {
+        args.extend(["--baseline", name]);
+    }
+    if quick {
+        args.push("--quick");
+    }
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let started = SystemTime::now();
+    let status = std::process::Command::new(&cargo)
+        .args(&args)
+        .status()
+        .with_context(|| format!("Failed to run {} (run from the copt source tree)", cargo))?;
+    if !status.success() {
+        anyhow::bail!("cargo bench failed ({})", status);
+    }
+
+    let Some(max) = max_regression else {
+        return Ok(());
+    };
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target"));
+    let changes = bench_changes(&target.join("criterion"), started)?;
+
+    println!();
+    let mut regressed = 0;
+    for (id, percent) in &changes {
+        let marker = if *percent > max {
+            regressed += 1;
+            "✗".red().to_string()
+        } else {
+            "✓".green().to_string()
+        };
+        println!("  {} {:<32} {:>+7.1}%", marker, id, percent);
+    }
+    println!();
+    if regressed > 0 {
+        anyhow::bail!(
+            "{} of {} benchmarks slowed down by more than {}% against baseline '{}'",
+            regressed,
+            changes.len(),
+            max,
+            baseline.unwrap_or_default()
+        );
+    }
+    println!(
+        "{} {} benchmarks within {}% of baseline",
+        "✓".green(),
+        changes.len(),
+        max
+    );
+    Ok(())
+}
+
+/// Mean change in percent for each benchmark criterion compared since `since`
+///
+/// Criterion writes `<group>/<id>/change/estimates.json` when a run is
+/// compared against a baseline; older files belong to benchmarks that did not
+/// run this time and are skipped.
+fn bench_changes(criterion_dir: &Path, since: SystemTime) -> Result<Vec<(String, f64)>> {
+    let pattern = criterion_dir.join("**/change/estimates.json");
+    let mut changes = Vec::new();
+    for path in glob::glob(&pattern.to_string_lossy())?.flatten() {
+        let modified = std::fs::metadata(&path)?.modified()?;
+        if modified < since {
+            continue;
+        }
+        let estimates: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path)?)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let Some(mean) = estimates["mean"]["point_estimate"].as_f64() else {
+            continue;
+        };
+        // <criterion_dir>/<group>/<id>/change/estimates.json
+        let id = path
+            .parent()
+            .and_then(Path::parent)
+            .and_then(|dir| dir.strip_prefix(criterion_dir).ok())
+            .map(|id| id.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        changes.push((id, mean * 100.0));
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_changes_reads_fresh_estimates() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |id: &str, mean: f64| {
+            let change = dir.path().join(id).join("change");
+            std::fs::create_dir_all(&change).unwrap();
+            std::fs::write(
+                change.join("estimates.json"),
+                format!(r#"{{"mean":{{"point_estimate":{}}}}}"#, mean),
+            )
+            .unwrap();
+        };
+        write("analyze/small", 0.25);
+        write("diff/10KB", -0.5);
+
+        let changes = bench_changes(dir.path(), SystemTime::UNIX_EPOCH).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                ("analyze/small".to_string(), 25.0),
+                ("diff/10KB".to_string(), -50.0)
+            ]
+        );
+
+        // Nothing was compared after "now"
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+        assert!(bench_changes(dir.path(), later).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+//! Prompts scattered across a repository (`copt discover`)
+//!
+//! Walks a directory tree (skipping hidden, dependency, and build
+//! directories) and lists content that is likely a prompt: agent
+//! instruction files such as `CLAUDE.md`, files in `prompts/` directories
+//! or named after prompts, and long string literals in Python,
+//! JavaScript/TypeScript, and Rust that read like instructions to a model.
+//! Each candidate gets a confidence and the reasons for it. `--analyze`
+//! runs the analyzer on each one; issues in a string literal are reported
+//! at their line in the source file.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::analyzer::{self, Issue};
+use crate::tui::severity;
+use crate::utils::literals::{self, Literal, SourceLanguage};
+
+#[derive(Args, Debug)]
+pub struct DiscoverArgs {
+    /// Directory to scan
+    #[arg(value_name = "DIR", default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Analyze each candidate and report its score and issues
+    #[arg(long)]
+    pub analyze: bool,
+
+    /// Lowest confidence to list, in percent
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 50,
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    pub threshold: u8,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: DiscoverFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiscoverFormat {
+    Text,
+    Json,
+}
+
+/// Where a candidate was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateKind {
+    /// Agent instructions (`CLAUDE.md`, `.claude/agents/*.md`)
+    Instructions,
+    PromptFile,
+    /// A string literal in source code
+    Literal,
+}
+
+/// Content that is likely a prompt
+#[derive(Debug, Serialize)]
+pub struct Candidate {
+    pub path: String,
+    /// Line the prompt starts on (1-based)
+    pub line: usize,
+    pub kind: CandidateKind,
+    /// Variable, constant, or key a string literal is assigned to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<SourceLanguage>,
+    /// 0.0-1.0
+    pub confidence: f32,
+    pub reasons: Vec<&'static str>,
+    pub chars: usize,
+    /// Set by `--analyze`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    /// Set by `--analyze`; lines are lines of the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issues: Option<Vec<Issue>>,
+    #[serde(skip)]
+    text: String,
+    /// File line of each line of `text` (string literals only)
+    #[serde(skip)]
+    lines: Vec<usize>,
+}
+
+/// Directories that hold dependencies, build output, or caches
+const SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    "vendor",
+    "venv",
+    "env",
+    "__pycache__",
+    "site-packages",
+    "coverage",
+    "copt-output",
+];
+
+/// Agent instruction files, wherever they sit in a monorepo
+const INSTRUCTION_FILES: &[&str] = &["CLAUDE.md", "CLAUDE.local.md", "AGENTS.md"];
+
+/// Shorter literals are only candidates when their name says prompt
+const MIN_LITERAL_CHARS: usize = 200;
+const MIN_NAMED_LITERAL_CHARS: usize = 80;
+
+static PROMPT_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)prompt|system|instruction|persona|preamble|guideline|directive|template")
+        .unwrap()
+});
+
+/// How prompts tend to open
+static ROLE_OPENER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:you are|you're|act as|your (?:task|job|role|goal) is|i want you to|as an? [a-z ]{0,30}(?:assistant|agent|expert)|<(?:role|system|instructions|task)>|#+ (?:role|instructions|system|task))",
+    )
+    .unwrap()
+});
+
+/// Words addressed to a model
+static DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:you|your|respond|reply|answer|must|always|never|do not|don't|should|assistant|the user)\b")
+        .unwrap()
+});
+
+static XML_SECTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"</([a-z_][a-z0-9_-]*)>").unwrap());
+
+/// Markup and code that happen to be long strings
+static CODE_LIKE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*(?:select\s|insert\s|update\s|delete\s|create\s+table|with\s+\w+\s+as\s*\(|<!doctype|<html|<div|<svg|\{\s*\x22|import\s|def\s|function\s)").unwrap()
+});
+
+/// Every candidate under `dir`, in path and line order
+pub fn discover(dir: &Path, max_bytes: u64) -> Result<Vec<Candidate>> {
+    let mut files = Vec::new();
+    walk(dir, &mut files)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    files.sort();
+
+    let mut candidates = Vec::new();
+    for path in files {
+        let too_large = std::fs::metadata(&path).map_or(true, |m| m.len() > max_bytes);
+        let Some(text) = (!too_large)
+            .then(|| std::fs::read_to_string(&path).ok())
+            .flatten()
+        else {
+            continue;
+        };
+        let display = display_path(&path);
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+
+        if let Some(language) = SourceLanguage::from_path(&path) {
+            candidates.extend(
+                literals::extract(&text, language)
+                    .into_iter()
+                    .filter_map(|literal| literal_candidate(&display, language, &literal)),
+            );
+        } else if let Some((kind, confidence, reason)) = classify_file(relative) {
+            let text = super::provenance::strip(&text).to_string();
+            candidates.push(Candidate {
+                path: display,
+                line: 1,
+                kind,
+                name: None,
+                language: None,
+                confidence,
+                reasons: vec![reason],
+                chars: text.chars().count(),
+                score: None,
+                issues: None,
+                text,
+                lines: Vec::new(),
+            });
+        }
+    }
+    Ok(candidates)
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if file_type.is_dir() {
+            // `.claude/` holds agent and command definitions
+            let hidden = name.starts_with('.') && name != ".claude";
+            if !hidden && !SKIP_DIRS.contains(&name.as_ref()) {
+                walk(&entry.path(), files)?;
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn display_path(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.display().to_string()
+}
+
+/// Whether a non-source file is a prompt, judging by its name and place
+fn classify_file(relative: &Path) -> Option<(CandidateKind, f32, &'static str)> {
+    let name = relative.file_name()?.to_string_lossy();
+    let ext = crate::utils::file::get_extension(relative).unwrap_or_default();
+    let in_dir = |names: &[&str]| {
+        relative.parent().is_some_and(|p| {
+            p.components()
+                .any(|c| names.contains(&&*c.as_os_str().to_string_lossy()))
+        })
+    };
+
+    if INSTRUCTION_FILES.contains(&name.as_ref()) {
+        return Some((CandidateKind::Instructions, 0.95, "agent instructions file"));
+    }
+    if ext == "md" && in_dir(&[".claude"]) && (in_dir(&["agents"]) || in_dir(&["commands"])) {
+        return Some((
+            CandidateKind::Instructions,
+            0.9,
+            "agent or command definition",
+        ));
+    }
+    if ext == "prompt" {
+        return Some((CandidateKind::PromptFile, 0.9, ".prompt file"));
+    }
+    let prompt_ext = ["txt", "md", "text"].contains(&ext.as_str());
+    if prompt_ext && !name.eq_ignore_ascii_case("README.md") && in_dir(&["prompts", "prompt"]) {
+        return Some((CandidateKind::PromptFile, 0.85, "in a prompts directory"));
+    }
+    if prompt_ext && name.to_lowercase().contains("prompt") {
+        return Some((CandidateKind::PromptFile, 0.7, "file name mentions prompt"));
+    }
+    None
+}
+
+/// A string literal as a candidate, if it is long enough to be a prompt
+pub fn literal_candidate(
+    path: &str,
+    language: SourceLanguage,
+    literal: &Literal,
+) -> Option<Candidate> {
+    if literal.docstring {
+        return None;
+    }
+    let named = literal
+        .name
+        .as_deref()
+        .is_some_and(|name| PROMPT_NAME.is_match(name));
+    let chars = literal.text.trim().chars().count();
+    let min = if named {
+        MIN_NAMED_LITERAL_CHARS
+    } else {
+        MIN_LITERAL_CHARS
+    };
+    if chars < min {
+        return None;
+    }
+
+    let (confidence, reasons) = literal_confidence(&literal.text, named);
+    // Point at the first line with text, past a leading newline
+    let first = literal
+        .text
+        .lines()
+        .position(|l| !l.trim().is_empty())
+        .unwrap_or(0);
+    Some(Candidate {
+        path: path.to_string(),
+        line: literal.source_line(first + 1),
+        kind: CandidateKind::Literal,
+        name: literal.name.clone(),
+        language: Some(language),
+        confidence,
+        reasons,
+        chars,
+        score: None,
+        issues: None,
+        text: literal.text.clone(),
+        lines: literal.lines.clone(),
+    })
+}
+
+/// How much a string literal reads like a prompt, and why
+pub fn literal_confidence(text: &str, named: bool) -> (f32, Vec<&'static str>) {
+    let mut confidence: f32 = 0.1;
+    let mut reasons = Vec::new();
+    let trimmed = text.trim_start();
+
+    // A name alone isn't enough: test fixtures are called `prompt` too
+    if named {
+        confidence += 0.35;
+        reasons.push("name says prompt");
+    }
+    if ROLE_OPENER.is_match(trimmed) {
+        confidence += 0.3;
+        reasons.push("opens with a role or task");
+    }
+    let words = trimmed.split_whitespace().count().max(1);
+    let directives = DIRECTIVE.find_iter(trimmed).count();
+    if directives >= 3 && directives * 100 / words >= 3 {
+        confidence += 0.25;
+        reasons.push("addresses a model");
+    }
+    if XML_SECTION.is_match(trimmed) {
+        confidence += 0.15;
+        reasons.push("XML sections");
+    }
+    let code_chars = trimmed
+        .chars()
+        .filter(|c| matches!(c, '{' | '}' | ';' | '=' | '(' | ')'))
+        .count();
+    if CODE_LIKE.is_match(trimmed) || code_chars * 100 / trimmed.len().max(1) > 4 {
+        confidence -= 0.4;
+        reasons.push("looks like code or markup");
+    }
+    (confidence.clamp(0.0, 1.0), reasons)
+}
+
+/// Analyze a candidate, reporting issues at their line in the file
+pub fn analyze(
+    candidate: &mut Candidate,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let text = if candidate.lines.is_empty() {
+        candidate.text.clone()
+    } else {
+        // Code indentation isn't part of the prompt; lines stay in place
+        textwrap::dedent(&candidate.text)
+    };
+    let mut issues = analyzer::analyze(&text, check_categories)?;
+    if let Some(min) = min_confidence {
+        issues.retain(|i| i.confidence >= min);
+    }
+    if !candidate.lines.is_empty() {
+        for issue in &mut issues {
+            issue.line = issue.line.map(|line| {
+                candidate
+                    .lines
+                    .get(line.saturating_sub(1))
+                    .copied()
+                    .unwrap_or(candidate.line)
+            });
+        }
+    }
+    candidate.score = Some(analyzer::quality_score(&issues));
+    candidate.issues = Some(issues);
+    Ok(())
+}
+
+/// Run `copt discover`
+pub fn run(
+    args: &DiscoverArgs,
+    max_bytes: u64,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let threshold = f32::from(args.threshold) / 100.0;
+    let mut candidates: Vec<Candidate> = discover(&args.dir, max_bytes)?
+        .into_iter()
+        .filter(|c| c.confidence >= threshold)
+        .collect();
+    if args.analyze {
+        for candidate in &mut candidates {
+            analyze(candidate, check_categories, min_confidence)?;
+        }
+    }
+
+    match args.format {
+        DiscoverFormat::Json => println!("{}", serde_json::to_string_pretty(&candidates)?),
+        DiscoverFormat::Text => print_candidates(&candidates, &args.dir),
+    }
+    Ok(())
+}
+
+pub fn print_candidates(candidates: &[Candidate], dir: &Path) {
+    println!();
+    let literals = candidates
+        .iter()
+        .filter(|c| c.kind == CandidateKind::Literal)
+        .count();
+    let files = candidates.len() - literals;
+    println!(
+        "  {} likely prompt{} in {} ({} file{}, {} string literal{})",
+        candidates.len().to_string().white().bold(),
+        if candidates.len() == 1 { "" } else { "s" },
+        dir.display(),
+        files,
+        if files == 1 { "" } else { "s" },
+        literals,
+        if literals == 1 { "" } else { "s" },
+    );
+    println!();
+
+    for candidate in candidates {
+        let location = match candidate.kind {
+            CandidateKind::Literal => format!("{}:{}", candidate.path, candidate.line),
+            _ => candidate.path.clone(),
+        };
+        let confidence = format!("{:>4.0}%", candidate.confidence * 100.0);
+        let confidence = if candidate.confidence >= 0.8 {
+            confidence.green()
+        } else {
+            confidence.yellow()
+        };
+        match candidate.name {
+            Some(ref name) => println!(
+                "  {}  {} {}",
+                confidence,
+                location.white().bold(),
+                name.cyan()
+            ),
+            None => println!("  {}  {}", confidence, location.white().bold()),
+        }
+        println!("         {}", candidate.reasons.join(" · ").bright_black());
+
+        let (Some(score), Some(issues)) = (candidate.score, &candidate.issues) else {
+            continue;
+        };
+        println!("         score {}/100", score);
+        for issue in issues {
+            let severity = format!("{:<7}", format!("{:?}", issue.severity).to_lowercase());
+            let severity = severity::paint(issue.severity, &severity);
+            println!(
+                "         {} {} {} {}",
+                issue
+                    .line
+                    .map(|l| format!("L{:<4}", l))
+                    .unwrap_or_else(|| " ".repeat(5))
+                    .bright_black(),
+                severity,
+                issue.id,
+                issue.message
+            );
+        }
+        println!();
+    }
+    if candidates.iter().all(|c| c.score.is_none()) {
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_finds_prompt_files_and_literals() {
+        let dir = TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "services/billing/CLAUDE.md",
+            "Run the tests before committing.",
+        );
+        write("prompts/support.md", "You are a support agent.");
+        write("prompts/README.md", "How these prompts are used.");
+        write("docs/guide.md", "A user guide.");
+        write("node_modules/pkg/CLAUDE.md", "Vendored.");
+        write(
+            "src/agent.py",
+            "\"\"\"Agent entry point, with a docstring long enough to be a candidate if docstrings counted as prompts; they don't, because they describe code rather than instruct a model.\"\"\"\n\n\
+             SYSTEM_PROMPT = \"\"\"\n    You are a billing assistant for Acme.\n    Always answer in two sentences or fewer.\n    Can you look up the invoice before you answer?\n\"\"\"\n\n\
+             QUERY = \"SELECT id, total, customer_id, created_at FROM invoices WHERE customer_id = %s AND created_at > now() - interval '30 days' ORDER BY created_at DESC LIMIT 100; -- padded out to be long enough for a candidate\"\n",
+        );
+
+        let candidates = discover(dir.path(), 1 << 20).unwrap();
+        let found: Vec<(String, CandidateKind)> = candidates
+            .iter()
+            .map(|c| {
+                let path = Path::new(&c.path).strip_prefix(dir.path()).unwrap();
+                (path.display().to_string(), c.kind)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("prompts/support.md".to_string(), CandidateKind::PromptFile),
+                (
+                    "services/billing/CLAUDE.md".to_string(),
+                    CandidateKind::Instructions
+                ),
+                ("src/agent.py".to_string(), CandidateKind::Literal),
+                ("src/agent.py".to_string(), CandidateKind::Literal),
+            ]
+        );
+
+        let prompt = &candidates[2];
+        assert_eq!(prompt.name.as_deref(), Some("SYSTEM_PROMPT"));
+        assert_eq!(prompt.line, 4);
+        assert!(prompt.confidence >= 0.8, "{:?}", prompt);
+        let query = &candidates[3];
+        assert!(query.confidence < 0.5, "{:?}", query);
+    }
+
+    #[test]
+    fn test_literal_issues_point_at_file_lines() {
+        let source = "def build():\n    prompt = f\"\"\"\n        You are a reviewer for the payments team.\n        Can you check the diff for missing tests?\n        Reply with a list of findings.\n    \"\"\"\n";
+        let literal = literals::extract(source, SourceLanguage::Python)
+            .into_iter()
+            .next()
+            .unwrap();
+        let mut candidate =
+            literal_candidate("review.py", SourceLanguage::Python, &literal).unwrap();
+        assert_eq!(candidate.line, 3);
+        analyze(&mut candidate, None, None).unwrap();
+
+        let issues = candidate.issues.unwrap();
+        let exp003 = issues.iter().find(|i| i.id == "EXP003").unwrap();
+        assert_eq!(exp003.line, Some(4));
+    }
+}
@@ -0,0 +1,57 @@
+//! Rule documentation (`copt docs`)
+//!
+//! Opens the Anthropic best-practices section behind a rule in the browser,
+//! so the reasoning for an issue is one command away.
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+
+use crate::analyzer::{deprecated, docs};
+use crate::utils::browser;
+
+#[derive(Args, Debug)]
+pub struct DocsArgs {
+    /// Rule ID (e.g. EXP003)
+    #[arg(value_name = "RULE")]
+    pub rule: String,
+
+    /// Print the link instead of opening a browser
+    #[arg(long)]
+    pub print: bool,
+}
+
+/// Run `copt docs`
+pub fn run(args: &DocsArgs) -> Result<()> {
+    let mut rule = args.rule.trim().to_uppercase();
+    if let Some(deprecation) = deprecated::lookup(&rule) {
+        let current = deprecated::current_ids(&rule);
+        eprintln!(
+            "{} {}; showing {}",
+            "warning:".yellow().bold(),
+            deprecation.describe(),
+            current[0]
+        );
+        rule = current[0].clone();
+    }
+    let Some(url) = docs::docs_url(&rule) else {
+        anyhow::bail!(
+            "No documentation for rule {}. Rule IDs look like EXP003; see docs/RULES.md for the full list.",
+            rule
+        );
+    };
+
+    if args.print {
+        println!("{}", url);
+        return Ok(());
+    }
+
+    match browser::open_url(&url) {
+        Ok(()) => println!("{} {} {}", "Opening".green(), rule.bold(), url.dimmed()),
+        Err(e) => {
+            eprintln!("{} {}", "warning:".yellow().bold(), e);
+            println!("{}", url);
+        }
+    }
+    Ok(())
+}
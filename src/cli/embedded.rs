@@ -0,0 +1,260 @@
+//! Prompts in string literals, optimized in place (`copt --embedded`)
+//!
+//! Finds the string literals in a source file that `copt discover` would
+//! list as prompts, hands each one to the optimizer without the code's
+//! indentation, and puts the result back into the same literal: same
+//! prefix and quotes, same indentation, same blank lines around the text.
+//! A rewrite that can't go back unchanged in meaning is left out with the
+//! reason: one that adds, drops, or renames a `{placeholder}` the code
+//! fills in, or one a raw string can't hold.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use super::discover::{self, Candidate};
+use crate::utils::literals::{self, Literal, SourceLanguage};
+
+/// A prompt in a string literal
+pub struct EmbeddedPrompt {
+    pub candidate: Candidate,
+    /// The literal's text without the code's indentation
+    pub prompt: String,
+    literal: Literal,
+    /// The literal as written, delimiters included
+    source: String,
+    layout: Layout,
+}
+
+/// Where the text sits in its literal, so a rewrite can sit the same way
+#[derive(Debug, PartialEq)]
+struct Layout {
+    /// Whitespace before the text, through the last newline before it
+    leading: String,
+    /// Indentation of the text's lines in the code
+    indent: String,
+    /// The text starts on the line of the opening quote
+    inline: bool,
+    /// Whitespace after the text
+    trailing: String,
+}
+
+/// `{name}`, `{0}`, `{}`, `{value:>8}`: filled in by `str.format` or `format!`
+static FORMAT_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{[A-Za-z0-9_.\[\]]*(?:![rsa])?(?::[^{}\n]*)?\}").unwrap());
+
+/// `{expression}` in a Python f-string
+static FSTRING_FIELD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{[^{}\n]*\}").unwrap());
+
+/// `${expression}` in a template literal
+static TEMPLATE_FIELD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{[^}]*\}").unwrap());
+
+/// The literals in `source` that are likely prompts
+pub fn find(
+    path: &str,
+    source: &str,
+    language: SourceLanguage,
+    threshold: f32,
+) -> Vec<EmbeddedPrompt> {
+    literals::extract(source, language)
+        .into_iter()
+        .filter_map(|literal| {
+            let candidate = discover::literal_candidate(path, language, &literal)?;
+            if candidate.confidence < threshold {
+                return None;
+            }
+            let (layout, prompt) = split(&literal.text);
+            let range =
+                literal.span.start - literal.open.len()..literal.span.end + literal.close.len();
+            Some(EmbeddedPrompt {
+                candidate,
+                prompt,
+                source: source[range].to_string(),
+                literal,
+                layout,
+            })
+        })
+        .collect()
+}
+
+impl EmbeddedPrompt {
+    /// Byte range of the literal in the source, delimiters included
+    pub fn range(&self) -> Range<usize> {
+        let start = self.literal.span.start - self.literal.open.len();
+        start..start + self.source.len()
+    }
+
+    /// The literal's source with `optimized` in place of the prompt, or why
+    /// it can't be written back
+    pub fn rewrite(&self, language: SourceLanguage, optimized: &str) -> Result<String, String> {
+        let text = join(&self.layout, optimized.trim());
+        let encoded = literals::encode(&self.literal, language, &text)?;
+
+        let before = placeholders(language, &self.literal.open, &self.source);
+        let after = placeholders(language, &self.literal.open, &encoded);
+        if before != after {
+            return Err(format!(
+                "the rewrite changes its placeholders ({} → {})",
+                list(&before),
+                list(&after)
+            ));
+        }
+        Ok(encoded)
+    }
+}
+
+/// `source` with each range replaced
+pub fn splice(source: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    let mut out = source.to_string();
+    for (range, replacement) in edits {
+        out.replace_range(range, &replacement);
+    }
+    out
+}
+
+/// Split a literal's text into its layout and the prompt itself
+fn split(text: &str) -> (Layout, String) {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    let leading = match text[..start].rfind('\n') {
+        Some(newline) => &text[..=newline],
+        None => &text[..start],
+    };
+    let inline = !leading.contains('\n');
+    let core = &text[leading.len()..end];
+
+    let mut lines: Vec<&str> = core.lines().collect();
+    // An inline first line has no indentation of its own
+    let indent = lines
+        .iter()
+        .skip(usize::from(inline))
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .reduce(|common, indent| {
+            let shared = common
+                .char_indices()
+                .zip(indent.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(indent.len()), |((i, _), _)| i);
+            &common[..shared]
+        })
+        .unwrap_or("")
+        .to_string();
+    for (i, line) in lines.iter_mut().enumerate() {
+        if !(inline && i == 0) {
+            *line = line
+                .strip_prefix(indent.as_str())
+                .unwrap_or(line.trim_start());
+        }
+    }
+
+    let layout = Layout {
+        leading: leading.to_string(),
+        indent,
+        inline,
+        trailing: text[end..].to_string(),
+    };
+    (layout, lines.join("\n"))
+}
+
+/// A prompt laid out like the text it replaces
+fn join(layout: &Layout, prompt: &str) -> String {
+    let lines: Vec<String> = prompt
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if line.is_empty() || (layout.inline && i == 0) {
+                line.to_string()
+            } else {
+                format!("{}{}", layout.indent, line)
+            }
+        })
+        .collect();
+    format!("{}{}{}", layout.leading, lines.join("\n"), layout.trailing)
+}
+
+/// The fields the code fills into a literal, sorted, from its source
+fn placeholders(language: SourceLanguage, open: &str, source: &str) -> Vec<String> {
+    let (pattern, source) = match language {
+        SourceLanguage::JavaScript if open == "`" => (&*TEMPLATE_FIELD, source.replace("\\$", "")),
+        SourceLanguage::JavaScript => return Vec::new(),
+        _ => {
+            let unescaped = source.replace("{{", "").replace("}}", "");
+            if language == SourceLanguage::Python && open.contains(['f', 'F']) {
+                (&*FSTRING_FIELD, unescaped)
+            } else {
+                (&*FORMAT_FIELD, unescaped)
+            }
+        }
+    };
+    let mut found: Vec<String> = pattern
+        .find_iter(&source)
+        .map(|m| m.as_str().to_string())
+        .collect();
+    found.sort();
+    found
+}
+
+fn list(fields: &[String]) -> String {
+    if fields.is_empty() {
+        "none".to_string()
+    } else {
+        fields.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "def build(product):\n    system = f\"\"\"\n        You are a support agent for {product}.\n\n          - Answer billing and account questions from customers.\n    \"\"\"\n    return system\n";
+
+    fn prompt() -> EmbeddedPrompt {
+        find("agent.py", SOURCE, SourceLanguage::Python, 0.0)
+            .into_iter()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rewrite_keeps_indentation_and_quotes() {
+        let prompt = prompt();
+        assert_eq!(
+            prompt.prompt,
+            "You are a support agent for {product}.\n\n  - Answer billing and account questions from customers."
+        );
+        assert_eq!(&SOURCE[prompt.range()], prompt.source);
+
+        let literal = prompt
+            .rewrite(
+                SourceLanguage::Python,
+                "<role>\nYou are a support agent for {product}.\n</role>\n",
+            )
+            .unwrap();
+        let updated = splice(SOURCE, vec![(prompt.range(), literal)]);
+        assert_eq!(
+            updated,
+            "def build(product):\n    system = f\"\"\"\n        <role>\n        You are a support agent for {product}.\n        </role>\n    \"\"\"\n    return system\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_that_changes_placeholders_is_refused() {
+        let prompt = prompt();
+        let reason = prompt
+            .rewrite(
+                SourceLanguage::Python,
+                "You are a support agent for {product_name}.",
+            )
+            .unwrap_err();
+        assert!(reason.contains("{product} → {product_name}"), "{}", reason);
+        // Escaped braces are text, not fields
+        assert!(prompt
+            .rewrite(
+                SourceLanguage::Python,
+                "Support {product}. Reply as {{\"answer\": \"...\"}}."
+            )
+            .is_ok());
+    }
+}
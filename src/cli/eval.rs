@@ -0,0 +1,467 @@
+//! Evaluation harness (`copt eval`)
+//!
+//! Runs the original and optimized prompts against the target model for each
+//! golden test case and compares assertion pass rates, so an optimization can
+//! be shown not to regress behavior.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer;
+use crate::llm::LlmClient;
+use crate::optimizer;
+use crate::utils::json::select_json_path;
+
+#[derive(Args, Debug)]
+pub struct EvalArgs {
+    /// YAML file with test cases
+    #[arg(long, value_name = "FILE")]
+    pub cases: PathBuf,
+
+    /// Prompt under test (used as the system prompt)
+    #[arg(long, value_name = "FILE")]
+    pub prompt: PathBuf,
+
+    /// Already-optimized prompt (skips optimization)
+    #[arg(long, value_name = "FILE")]
+    pub optimized: Option<PathBuf>,
+
+    /// Max tokens per case response
+    #[arg(long, default_value = "1024", hide_default_value = true)]
+    pub max_tokens: u32,
+
+    /// Exit with an error if the optimized prompt passes fewer assertions
+    #[arg(long)]
+    pub fail_on_regression: bool,
+}
+
+/// Test case file (`cases.yaml`)
+#[derive(Debug, Deserialize)]
+pub struct CaseFile {
+    pub cases: Vec<EvalCase>,
+}
+
+/// A single golden test case
+#[derive(Debug, Deserialize)]
+pub struct EvalCase {
+    pub name: String,
+    /// User message sent alongside the prompt under test
+    pub input: String,
+    /// Written as single-key maps (`- contains: text`) rather than YAML tags
+    #[serde(
+        default,
+        rename = "assert",
+        with = "serde_yaml::with::singleton_map_recursive"
+    )]
+    pub assertions: Vec<Assertion>,
+}
+
+/// An expectation about the model's response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Assertion {
+    /// Response contains the text (case-sensitive)
+    Contains(String),
+    /// Response does not contain the text
+    NotContains(String),
+    /// Response matches the regex
+    Regex(String),
+    /// Response is JSON and the path exists (optionally equal to a value)
+    JsonPath {
+        path: String,
+        #[serde(default)]
+        equals: Option<serde_json::Value>,
+    },
+}
+
+impl Assertion {
+    /// Short description for reports
+    pub fn describe(&self) -> String {
+        match self {
+            Assertion::Contains(s) => format!("contains {:?}", s),
+            Assertion::NotContains(s) => format!("not_contains {:?}", s),
+            Assertion::Regex(r) => format!("regex /{}/", r),
+            Assertion::JsonPath { path, equals } => match equals {
+                Some(v) => format!("json_path {} == {}", path, v),
+                None => format!("json_path {} exists", path),
+            },
+        }
+    }
+
+    /// Check the assertion against a model response
+    pub fn check(&self, response: &str) -> Result<bool> {
+        Ok(match self {
+            Assertion::Contains(s) => response.contains(s.as_str()),
+            Assertion::NotContains(s) => !response.contains(s.as_str()),
+            Assertion::Regex(r) => Regex::new(r)
+                .with_context(|| format!("Invalid regex in assertion: {}", r))?
+                .is_match(response),
+            Assertion::JsonPath { path, equals } => {
+                let Some(doc) = parse_json_response(response) else {
+                    return Ok(false);
+                };
+                match (select_json_path(&doc, path)?, equals) {
+                    (Some(actual), Some(expected)) => actual == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            }
+        })
+    }
+}
+
+/// Parse a response as JSON, tolerating a surrounding ```json fence
+fn parse_json_response(response: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(strip_fence(response)).ok()
+}
+
+/// The body of a response wrapped in a ``` fence, with or without a language tag
+pub fn strip_fence(response: &str) -> &str {
+    let trimmed = response.trim();
+    trimmed
+        .strip_prefix("```")
+        .and_then(|s| s.strip_suffix("```"))
+        .map(|s| s.trim_start_matches(|c: char| c.is_ascii_alphanumeric()))
+        .unwrap_or(trimmed)
+        .trim()
+}
+
+/// Load and validate a case file
+pub fn load_cases(path: &Path) -> Result<CaseFile> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cases file: {}", path.display()))?;
+    let file: CaseFile = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse cases file: {}", path.display()))?;
+
+    if file.cases.is_empty() {
+        anyhow::bail!("No cases defined in {}", path.display());
+    }
+    for case in &file.cases {
+        if case.assertions.is_empty() {
+            anyhow::bail!("Case '{}' has no assertions", case.name);
+        }
+    }
+
+    Ok(file)
+}
+
+/// Assertion results for one prompt variant on one case
+#[derive(Debug, Clone, Default)]
+pub struct VariantResult {
+    pub passed: usize,
+    pub total: usize,
+    pub failures: Vec<String>,
+}
+
+/// Results for one case across both prompt variants
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub name: String,
+    pub original: VariantResult,
+    pub optimized: VariantResult,
+}
+
+/// Aggregate results for the whole run
+#[derive(Debug, Clone, Default)]
+pub struct EvalReport {
+    pub cases: Vec<CaseResult>,
+}
+
+impl EvalReport {
+    fn totals(&self, pick: fn(&CaseResult) -> &VariantResult) -> (usize, usize) {
+        self.cases
+            .iter()
+            .map(pick)
+            .fold((0, 0), |(p, t), r| (p + r.passed, t + r.total))
+    }
+
+    /// Pass rate (0.0-1.0) of the original prompt
+    pub fn original_pass_rate(&self) -> f64 {
+        rate(self.totals(|c| &c.original))
+    }
+
+    /// Pass rate (0.0-1.0) of the optimized prompt
+    pub fn optimized_pass_rate(&self) -> f64 {
+        rate(self.totals(|c| &c.optimized))
+    }
+
+    /// Whether the optimized prompt passed fewer assertions
+    pub fn is_regression(&self) -> bool {
+        self.totals(|c| &c.optimized).0 < self.totals(|c| &c.original).0
+    }
+}
+
+fn rate((passed, total): (usize, usize)) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        passed as f64 / total as f64
+    }
+}
+
+/// Run one prompt variant against a case
+async fn run_variant(
+    client: &dyn LlmClient,
+    model: &str,
+    system: &str,
+    case: &EvalCase,
+    max_tokens: u32,
+) -> Result<VariantResult> {
+    let response = client
+        .complete(system, &case.input, model, max_tokens)
+        .await
+        .with_context(|| format!("Model call failed for case '{}'", case.name))?;
+
+    let mut result = VariantResult {
+        total: case.assertions.len(),
+        ..Default::default()
+    };
+    for assertion in &case.assertions {
+        if assertion.check(&response)? {
+            result.passed += 1;
+        } else {
+            result.failures.push(assertion.describe());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Evaluate both prompt variants across every case
+pub async fn evaluate(
+    client: &dyn LlmClient,
+    model: &str,
+    original: &str,
+    optimized: &str,
+    cases: &CaseFile,
+    max_tokens: u32,
+) -> Result<EvalReport> {
+    let mut report = EvalReport::default();
+
+    for case in &cases.cases {
+        let original_result = run_variant(client, model, original, case, max_tokens).await?;
+        let optimized_result = run_variant(client, model, optimized, case, max_tokens).await?;
+        report.cases.push(CaseResult {
+            name: case.name.clone(),
+            original: original_result,
+            optimized: optimized_result,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Entry point for `copt eval`
+pub async fn run(
+    args: &EvalArgs,
+    client: &dyn LlmClient,
+    model: &str,
+    check_categories: Option<&[String]>,
+    json_output: bool,
+) -> Result<()> {
+    let cases = load_cases(&args.cases)?;
+    let original = std::fs::read_to_string(&args.prompt)
+        .with_context(|| format!("Failed to read prompt: {}", args.prompt.display()))?;
+
+    let optimized = match args.optimized {
+        Some(ref path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read optimized prompt: {}", path.display()))?,
+        None => {
+            let issues = analyzer::analyze(&original, check_categories)?;
+            let prompt_type = analyzer::classify_prompt(&original);
+            optimizer::optimize_with_llm(
+                &original,
+                &issues,
+                client,
+                model,
+                prompt_type,
+                check_categories,
+            )
+            .await?
+        }
+    };
+
+    let report = evaluate(
+        client,
+        model,
+        &original,
+        &optimized,
+        &cases,
+        args.max_tokens,
+    )
+    .await?;
+
+    if json_output {
+        print_json(&report)?;
+    } else {
+        print_report(&report);
+    }
+
+    if args.fail_on_regression && report.is_regression() {
+        anyhow::bail!(
+            "Optimized prompt regressed: {:.0}% → {:.0}% assertions passed",
+            report.original_pass_rate() * 100.0,
+            report.optimized_pass_rate() * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+fn print_json(report: &EvalReport) -> Result<()> {
+    let json = serde_json::json!({
+        "original_pass_rate": report.original_pass_rate(),
+        "optimized_pass_rate": report.optimized_pass_rate(),
+        "delta": report.optimized_pass_rate() - report.original_pass_rate(),
+        "cases": report.cases.iter().map(|c| serde_json::json!({
+            "name": c.name,
+            "original": { "passed": c.original.passed, "total": c.original.total, "failures": c.original.failures },
+            "optimized": { "passed": c.optimized.passed, "total": c.optimized.total, "failures": c.optimized.failures },
+        })).collect::<Vec<_>>(),
+    });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn print_report(report: &EvalReport) {
+    println!();
+    println!(
+        "  {:<32} {:>10} {:>10}",
+        "CASE".bright_black(),
+        "ORIGINAL".bright_black(),
+        "OPTIMIZED".bright_black()
+    );
+    println!("  {}", "─".repeat(54).bright_black());
+
+    for case in &report.cases {
+        let original = format!("{}/{}", case.original.passed, case.original.total);
+        let optimized = format!("{}/{}", case.optimized.passed, case.optimized.total);
+        let optimized = if case.optimized.passed < case.original.passed {
+            optimized.red().to_string()
+        } else if case.optimized.passed > case.original.passed {
+            optimized.green().to_string()
+        } else {
+            optimized
+        };
+        println!(
+            "  {:<32} {:>10} {:>10}",
+            crate::utils::text::truncate(&case.name, 32),
+            original,
+            optimized
+        );
+        for failure in &case.optimized.failures {
+            println!("    {} {}", "✗".red(), failure.bright_black());
+        }
+    }
+
+    let original_rate = report.original_pass_rate() * 100.0;
+    let optimized_rate = report.optimized_pass_rate() * 100.0;
+    let delta = optimized_rate - original_rate;
+    let delta_str = format!("{:+.0}%", delta);
+    let delta_str = if delta < 0.0 {
+        delta_str.red().bold()
+    } else if delta > 0.0 {
+        delta_str.green().bold()
+    } else {
+        delta_str.white().bold()
+    };
+
+    println!("  {}", "─".repeat(54).bright_black());
+    println!(
+        "  {:<32} {:>9.0}% {:>9.0}%   {}",
+        "Pass rate".white().bold(),
+        original_rate,
+        optimized_rate,
+        delta_str
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    const CASES: &str = r#"
+cases:
+  - name: refund window
+    input: How long do I have to request a refund?
+    assert:
+      - contains: 30 days
+      - regex: "(?i)refund"
+  - name: structured status
+    input: Return the order status as JSON
+    assert:
+      - json_path:
+          path: $.status
+          equals: shipped
+      - not_contains: sorry
+"#;
+
+    #[test]
+    fn test_parse_cases() {
+        let file: CaseFile = serde_yaml::from_str(CASES).unwrap();
+        assert_eq!(file.cases.len(), 2);
+        assert_eq!(file.cases[0].assertions.len(), 2);
+        assert!(matches!(
+            file.cases[1].assertions[0],
+            Assertion::JsonPath { .. }
+        ));
+    }
+
+    #[test]
+    fn test_assertions() {
+        assert!(Assertion::Contains("30 days".into())
+            .check("You have 30 days")
+            .unwrap());
+        assert!(!Assertion::NotContains("sorry".into())
+            .check("sorry, no")
+            .unwrap());
+        assert!(Assertion::Regex("(?i)refund".into())
+            .check("REFUND")
+            .unwrap());
+
+        let json_path = Assertion::JsonPath {
+            path: "$.status".into(),
+            equals: Some(serde_json::json!("shipped")),
+        };
+        assert!(json_path
+            .check("```json\n{\"status\": \"shipped\"}\n```")
+            .unwrap());
+        assert!(!json_path.check("{\"status\": \"pending\"}").unwrap());
+        assert!(!json_path.check("not json").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_reports_delta() {
+        let cases: CaseFile = serde_yaml::from_str(CASES).unwrap();
+        // Responses alternate original/optimized per case
+        let client = MockLlmClient::with_responses([
+            "Sorry, I can't say.",
+            "Refunds are accepted within 30 days.",
+            "sorry, status unknown",
+            "{\"status\": \"shipped\"}",
+        ]);
+
+        let report = evaluate(&client, "m", "orig", "opt", &cases, 256)
+            .await
+            .unwrap();
+
+        assert_eq!(report.cases[0].original.passed, 0);
+        assert_eq!(report.cases[0].optimized.passed, 2);
+        assert_eq!(report.cases[1].original.passed, 0);
+        assert_eq!(report.cases[1].optimized.passed, 2);
+        assert_eq!(report.original_pass_rate(), 0.0);
+        assert_eq!(report.optimized_pass_rate(), 1.0);
+        assert!(!report.is_regression());
+
+        let calls = client.calls();
+        assert_eq!(calls[0].system, "orig");
+        assert_eq!(calls[1].system, "opt");
+        assert_eq!(calls[0].max_tokens, 256);
+    }
+}
@@ -0,0 +1,264 @@
+//! Recent-run history for duplicate detection
+//!
+//! Each LLM optimization appends one line to `history.jsonl` in the local
+//! data directory: a hash of the normalized prompt, when it ran, the model,
+//! a hash of the flags that shaped the result, and where the result was
+//! saved. Before calling the LLM, copt looks for the same prompt, model, and
+//! flags within `[history] window_hours` and warns (or, with
+//! `fail_on_duplicate`, stops) unless `--force` is given, so re-running a
+//! command from shell history doesn't pay for the same rewrite twice
+//! unnoticed. The prompt text is never stored;
+//! a MinHash signature of its words lets `copt similar` find near-duplicates.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One LLM optimization run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// When the run finished (RFC 3339)
+    pub timestamp: String,
+    /// [`prompt_hash`] of the input prompt
+    pub prompt_hash: String,
+    pub provider: String,
+    pub model: String,
+    /// [`options_hash`] of the flags that shaped the result (empty in
+    /// entries written before it was recorded)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub options: String,
+    /// Saved optimized prompt, if the run saved one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// [`minhash`](crate::utils::text::minhash) of the input prompt (empty
+    /// in entries written before it was recorded)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signature: Vec<u32>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        prompt: &str,
+        provider: &str,
+        model: &str,
+        options: &str,
+        output: Option<&Path>,
+    ) -> Self {
+        Self {
+            timestamp: Local::now().to_rfc3339(),
+            prompt_hash: prompt_hash(prompt),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            options: options.to_string(),
+            // Absolute, so `copt similar` finds it from any directory
+            output: output.map(|path| {
+                std::path::absolute(path)
+                    .unwrap_or_else(|_| path.to_path_buf())
+                    .display()
+                    .to_string()
+            }),
+            signature: crate::utils::text::minhash(prompt),
+        }
+    }
+
+    /// When the run finished, if the timestamp parses
+    pub fn time(&self) -> Option<DateTime<Local>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|time| time.with_timezone(&Local))
+    }
+}
+
+/// SHA-256 of the prompt with line endings, trailing whitespace, and
+/// surrounding blank lines normalized, so a re-pasted prompt still matches
+pub fn prompt_hash(prompt: &str) -> String {
+    let normalized = prompt
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "sha256:{}",
+        super::rules::sha256_hex(normalized.trim_matches('\n'))
+    )
+}
+
+/// Short hash of a description of the flags that shape a run's result, so
+/// a rerun with different `--suggest` or `--level` isn't a duplicate
+pub fn options_hash(description: &str) -> String {
+    super::rules::sha256_hex(description)[..16].to_string()
+}
+
+/// Location of the history file
+fn default_history_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.data_dir().join("history.jsonl"))
+}
+
+/// History settings (`[history]`), defaults if the config can't be read
+fn settings() -> super::config::HistoryConfig {
+    super::config::load_config()
+        .map(|c| c.history)
+        .unwrap_or_default()
+}
+
+/// Whether a duplicate run stops (`[history] fail_on_duplicate`) rather
+/// than warns
+pub fn fails_on_duplicate() -> bool {
+    settings().fail_on_duplicate
+}
+
+/// The most recent run of the same prompt with the same provider, model, and
+/// options inside the configured window
+pub fn find_recent(
+    prompt: &str,
+    provider: &str,
+    model: &str,
+    options: &str,
+) -> Option<HistoryEntry> {
+    let settings = settings();
+    if !settings.enabled {
+        return None;
+    }
+    let path = default_history_path()?;
+    let entries = load(&path)
+        .map_err(|e| tracing::debug!("Failed to read history: {:#}", e))
+        .ok()?;
+    let window = chrono::Duration::hours(settings.window_hours as i64);
+    let run = HistoryEntry::new(prompt, provider, model, options, None);
+    find_in(&entries, &run, Local::now() - window)
+}
+
+/// The latest entry since `since` for the same prompt, provider, model, and
+/// options as `run`
+fn find_in(
+    entries: &[HistoryEntry],
+    run: &HistoryEntry,
+    since: DateTime<Local>,
+) -> Option<HistoryEntry> {
+    entries
+        .iter()
+        .rev()
+        .filter(|e| {
+            e.prompt_hash == run.prompt_hash
+                && e.provider == run.provider
+                && e.model == run.model
+                && e.options == run.options
+        })
+        .find(|e| e.time().is_some_and(|time| time >= since))
+        .cloned()
+}
+
+/// Every recorded run, oldest first (empty if there is no history)
+pub fn entries() -> Result<Vec<HistoryEntry>> {
+    match default_history_path() {
+        Some(path) => load(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Append a run to the history file
+///
+/// History is best-effort and never fails the run; `--sandbox` skips it.
+pub fn record(entry: &HistoryEntry) {
+    if crate::utils::sandbox::enabled() || !settings().enabled {
+        return;
+    }
+    if let Some(path) = default_history_path() {
+        if let Err(e) = append(&path, entry) {
+            tracing::debug!("Failed to record history: {:#}", e);
+        }
+    }
+}
+
+fn append(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    // One write per entry, so concurrent appends can't interleave mid-line
+    let line = format!("{}\n", serde_json::to_string(entry)?);
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Read all entries, skipping malformed lines
+fn load(path: &Path) -> Result<Vec<HistoryEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read history: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_hash_ignores_whitespace_noise() {
+        let hash = prompt_hash("Summarize the report.\nBe brief.");
+        assert_eq!(
+            hash,
+            prompt_hash("\nSummarize the report.  \r\nBe brief.\n\n")
+        );
+        assert_ne!(hash, prompt_hash("Summarize the report.\nBe thorough."));
+        assert!(hash.starts_with("sha256:"));
+    }
+
+    #[test]
+    fn test_find_recent_matches_prompt_model_options_and_window() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let prompt = "Summarize the report.";
+        let options = options_hash("level=standard");
+        let mut old = HistoryEntry::new(prompt, "bedrock", "sonnet", &options, None);
+        old.timestamp = (Local::now() - chrono::Duration::hours(30)).to_rfc3339();
+        let recent = HistoryEntry::new(
+            prompt,
+            "bedrock",
+            "sonnet",
+            &options,
+            Some(Path::new("copt-output/optimized_1.txt")),
+        );
+        for entry in [&old, &recent] {
+            append(&path, entry).unwrap();
+        }
+        std::fs::write(
+            &path,
+            std::fs::read_to_string(&path).unwrap() + "not json\n",
+        )
+        .unwrap();
+
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        let since = Local::now() - chrono::Duration::hours(24);
+        let run =
+            |model: &str, options: &str| HistoryEntry::new(prompt, "bedrock", model, options, None);
+        assert_eq!(
+            find_in(&entries, &run("sonnet", &options), since),
+            Some(recent)
+        );
+        assert_eq!(find_in(&entries, &run("haiku", &options), since), None);
+        assert_eq!(
+            find_in(
+                &entries,
+                &run("sonnet", &options_hash("level=aggressive")),
+                since
+            ),
+            None
+        );
+        assert_eq!(
+            find_in(&entries[..1], &run("sonnet", &options), since),
+            None
+        );
+    }
+}
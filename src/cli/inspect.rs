@@ -0,0 +1,421 @@
+//! Structural facts about a prompt (`copt inspect`)
+//!
+//! Where `lint` and `audit` judge a prompt, `inspect` only describes it: the
+//! detected prompt type, sections, XML tags, `{{VARIABLES}}`, code blocks,
+//! size, natural language, and the files and tools it mentions. The JSON
+//! form is meant as input for other tooling and dashboards.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::analyzer::references::{self, PathReference};
+use crate::analyzer::segment::{self, Section};
+use crate::analyzer::{self, FrontendStack, PromptType};
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// Prompt file to inspect (reads stdin when omitted)
+    #[arg(short, long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: InspectFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InspectFormat {
+    Text,
+    Json,
+}
+
+/// What a prompt contains, without judging it
+#[derive(Debug, Serialize)]
+pub struct PromptFacts {
+    pub prompt_type: PromptType,
+    /// Written as a system prompt ("You are…") rather than a one-off task
+    pub system_prompt: bool,
+    /// Frontend framework the prompt targets, if any
+    pub frontend_stack: Option<FrontendStack>,
+    pub language: Option<Language>,
+    pub size: Size,
+    pub sections: Vec<Section>,
+    pub xml_tags: Vec<XmlTag>,
+    /// `{{VARIABLE}}` placeholders, in order of first appearance
+    pub variables: Vec<String>,
+    pub code_blocks: Vec<CodeBlock>,
+    /// Local paths the prompt mentions (outside code blocks)
+    pub files: Vec<PathReference>,
+    /// Tool and function names the prompt tells the model to use
+    pub tools: Vec<ToolReference>,
+}
+
+/// Natural language of the prose (code blocks excluded)
+#[derive(Debug, Serialize)]
+pub struct Language {
+    /// ISO 639-3 code, e.g. `eng`
+    pub code: &'static str,
+    pub name: &'static str,
+    pub confidence: f64,
+    /// Whether the text was long and distinctive enough to trust the guess
+    pub reliable: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Size {
+    pub chars: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub tokens: usize,
+}
+
+/// An XML tag name and how often it is opened
+#[derive(Debug, Serialize, PartialEq)]
+pub struct XmlTag {
+    pub name: String,
+    pub count: usize,
+    /// First line opening the tag (1-indexed)
+    pub line: usize,
+}
+
+/// A fenced code block
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CodeBlock {
+    /// The fence's language tag (```python), if any
+    pub language: Option<String>,
+    /// Opening fence line (1-indexed)
+    pub start_line: usize,
+    /// Closing fence line, or the last line when the block is never closed
+    pub end_line: usize,
+}
+
+/// A tool or function named in the prompt
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ToolReference {
+    pub name: String,
+    /// First line naming the tool (1-indexed)
+    pub line: usize,
+}
+
+/// Opening (or self-closing) XML tags; closing tags are not counted
+static XML_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<([A-Za-z_][\w.-]*)(?:\s[^<>]*)?/?>").unwrap());
+
+/// Ways prompts name tools: "`search_docs` tool", "the run_tests tool",
+/// "call `fetch_page`", "tool `lookup`"
+static TOOL_NAMES: LazyLock<[Regex; 3]> = LazyLock::new(|| {
+    [
+        Regex::new(r"`([A-Za-z_][\w.-]*)`\s+(?:tool|function)s?\b").unwrap(),
+        Regex::new(r"(?i)\b(?:tool|function|call|invoke)\s+`([A-Za-z_][\w.-]*)`").unwrap(),
+        Regex::new(r"\b([a-z][a-z0-9]*(?:_[a-z0-9]+)+)\s+(?:tool|function)s?\b").unwrap(),
+    ]
+});
+
+/// Collect the structural facts of `prompt`
+pub fn inspect(prompt: &str) -> PromptFacts {
+    let blocks = code_blocks(prompt);
+    let stack = analyzer::detect_frontend_stack(prompt);
+    PromptFacts {
+        prompt_type: analyzer::classify_prompt(prompt),
+        system_prompt: analyzer::is_system_prompt(prompt),
+        frontend_stack: (stack != FrontendStack::Unknown).then_some(stack),
+        language: language(&prose(prompt, &blocks)),
+        size: Size {
+            chars: prompt.chars().count(),
+            words: prompt.split_whitespace().count(),
+            lines: prompt.lines().count(),
+            tokens: crate::utils::count_tokens(prompt),
+        },
+        sections: segment::segment_prompt(prompt),
+        xml_tags: xml_tags(prompt, &blocks),
+        variables: super::workbench::find_variables(prompt),
+        files: references::path_references(prompt),
+        tools: tools(prompt),
+        code_blocks: blocks,
+    }
+}
+
+/// Fenced code blocks, matching ``` with ``` and ~~~ with ~~~
+pub(crate) fn code_blocks(prompt: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(&str, CodeBlock)> = None;
+    let mut last_line = 0;
+
+    for (idx, line) in prompt.lines().enumerate() {
+        last_line = idx + 1;
+        let trimmed = line.trim_start();
+        let fence = ["```", "~~~"]
+            .into_iter()
+            .find(|fence| trimmed.starts_with(fence));
+        match (&mut open, fence) {
+            (Some((marker, block)), Some(fence)) if *marker == fence => {
+                block.end_line = idx + 1;
+                blocks.push(open.take().unwrap().1);
+            }
+            (None, Some(fence)) => {
+                let tag = trimmed[fence.len()..].split_whitespace().next();
+                open = Some((
+                    fence,
+                    CodeBlock {
+                        language: tag.map(str::to_string),
+                        start_line: idx + 1,
+                        end_line: idx + 1,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+    if let Some((_, mut block)) = open {
+        block.end_line = last_line;
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Whether 1-indexed `line` falls inside one of `blocks`
+pub(crate) fn in_code(blocks: &[CodeBlock], line: usize) -> bool {
+    blocks
+        .iter()
+        .any(|b| (b.start_line..=b.end_line).contains(&line))
+}
+
+/// The prompt without its code blocks
+fn prose(prompt: &str, blocks: &[CodeBlock]) -> String {
+    prompt
+        .lines()
+        .enumerate()
+        .filter(|(idx, _)| !in_code(blocks, idx + 1))
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn language(prose: &str) -> Option<Language> {
+    let info = whatlang::detect(prose)?;
+    Some(Language {
+        code: info.lang().code(),
+        name: info.lang().eng_name(),
+        confidence: (info.confidence() * 100.0).round() / 100.0,
+        reliable: info.is_reliable(),
+    })
+}
+
+/// XML tags opened outside code blocks, in order of first appearance
+fn xml_tags(prompt: &str, blocks: &[CodeBlock]) -> Vec<XmlTag> {
+    let mut tags: Vec<XmlTag> = Vec::new();
+    for (idx, line) in prompt.lines().enumerate() {
+        if in_code(blocks, idx + 1) {
+            continue;
+        }
+        for caps in XML_TAG.captures_iter(line) {
+            let name = &caps[1];
+            match tags.iter_mut().find(|t| t.name == name) {
+                Some(tag) => tag.count += 1,
+                None => tags.push(XmlTag {
+                    name: name.to_string(),
+                    count: 1,
+                    line: idx + 1,
+                }),
+            }
+        }
+    }
+    tags
+}
+
+/// Tool names, in order of first appearance
+fn tools(prompt: &str) -> Vec<ToolReference> {
+    let mut found: Vec<(usize, usize, String)> = Vec::new();
+    for (idx, line) in prompt.lines().enumerate() {
+        for pattern in TOOL_NAMES.iter() {
+            for caps in pattern.captures_iter(line) {
+                let name = caps.get(1).unwrap();
+                found.push((idx + 1, name.start(), name.as_str().to_string()));
+            }
+        }
+    }
+    found.sort();
+
+    let mut tools: Vec<ToolReference> = Vec::new();
+    for (line, _, name) in found {
+        if !tools.iter().any(|t| t.name == name) {
+            tools.push(ToolReference { name, line });
+        }
+    }
+    tools
+}
+
+/// Run `copt inspect`
+pub fn run(args: &InspectArgs, max_bytes: u64) -> Result<()> {
+    let prompt = match args.file {
+        Some(ref path) => crate::utils::input::read_file_blocking(path, max_bytes)?,
+        None if !std::io::stdin().is_terminal() => {
+            crate::utils::input::read_limited(std::io::stdin().lock(), max_bytes, "stdin")?
+        }
+        None => anyhow::bail!("No prompt to inspect: pass -f FILE or pipe it on stdin"),
+    };
+
+    let facts = inspect(&prompt);
+    match args.format {
+        InspectFormat::Text => print_facts(&facts),
+        InspectFormat::Json => println!("{}", serde_json::to_string_pretty(&facts)?),
+    }
+    Ok(())
+}
+
+fn print_facts(facts: &PromptFacts) {
+    let row = |label: &str, value: String| {
+        println!("  {:<12} {}", label.bright_black(), value);
+    };
+    let none = || "none".bright_black().to_string();
+
+    println!();
+    row("Type", format!("{:?}", facts.prompt_type));
+    if facts.system_prompt {
+        row("", "system prompt".to_string());
+    }
+    if let Some(stack) = facts.frontend_stack {
+        row("Frontend", stack.display_name().to_string());
+    }
+    row(
+        "Language",
+        match facts.language {
+            Some(ref lang) if lang.reliable => format!("{} ({})", lang.name, lang.code),
+            Some(ref lang) => format!("{} ({}, low confidence)", lang.name, lang.code),
+            None => none(),
+        },
+    );
+    row(
+        "Size",
+        format!(
+            "{} lines, {} words, ~{} tokens",
+            facts.size.lines, facts.size.words, facts.size.tokens
+        ),
+    );
+    row(
+        "Sections",
+        if facts.sections.is_empty() {
+            none()
+        } else {
+            segment::structure_summary(&facts.sections)
+        },
+    );
+    let list = |items: Vec<String>| {
+        if items.is_empty() {
+            none()
+        } else {
+            items.join(", ")
+        }
+    };
+    row(
+        "XML tags",
+        list(
+            facts
+                .xml_tags
+                .iter()
+                .map(|t| match t.count {
+                    1 => format!("<{}>", t.name),
+                    n => format!("<{}> ×{}", t.name, n),
+                })
+                .collect(),
+        ),
+    );
+    row(
+        "Variables",
+        list(
+            facts
+                .variables
+                .iter()
+                .map(|v| format!("{{{{{}}}}}", v))
+                .collect(),
+        ),
+    );
+    row(
+        "Code blocks",
+        list(
+            facts
+                .code_blocks
+                .iter()
+                .map(|b| {
+                    format!(
+                        "{} (L{}-{})",
+                        b.language.as_deref().unwrap_or("plain"),
+                        b.start_line,
+                        b.end_line
+                    )
+                })
+                .collect(),
+        ),
+    );
+    row(
+        "Files",
+        list(facts.files.iter().map(|f| f.path.clone()).collect()),
+    );
+    row(
+        "Tools",
+        list(facts.tools.iter().map(|t| t.name.clone()).collect()),
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect_collects_structure() {
+        let prompt = "You are a support agent for {{COMPANY}}.\n\
+            <context>\n\
+            See docs/refunds.md before answering.\n\
+            </context>\n\
+            Use the `lookup_order` tool, then call `issue_refund` if needed.\n\
+            ```json\n\
+            {\"order\": \"<id>\"}\n\
+            ```\n\
+            Reply to {{CUSTOMER}} in two sentences.";
+        let facts = inspect(prompt);
+
+        assert!(facts.system_prompt);
+        assert_eq!(facts.variables, ["COMPANY", "CUSTOMER"]);
+        assert_eq!(
+            facts.xml_tags,
+            [XmlTag {
+                name: "context".to_string(),
+                count: 1,
+                line: 2
+            }]
+        );
+        assert_eq!(
+            facts.code_blocks,
+            [CodeBlock {
+                language: Some("json".to_string()),
+                start_line: 6,
+                end_line: 8
+            }]
+        );
+        assert_eq!(facts.files.len(), 1);
+        assert_eq!(facts.files[0].path, "docs/refunds.md");
+        let tools: Vec<&str> = facts.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tools, ["lookup_order", "issue_refund"]);
+        assert_eq!(facts.size.lines, 9);
+    }
+
+    #[test]
+    fn test_language_ignores_code() {
+        let prompt = "Résume ce texte en trois phrases et garde un ton neutre pour le lecteur.\n\
+            ```python\nprint('hello world, this is English text in code')\n```";
+        let facts = inspect(prompt);
+        assert_eq!(facts.language.unwrap().code, "fra");
+
+        // An unclosed fence runs to the end of the prompt
+        let blocks = code_blocks("text\n~~~\ncode\n```\nmore");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!((blocks[0].start_line, blocks[0].end_line), (2, 5));
+    }
+}
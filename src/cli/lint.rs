@@ -0,0 +1,586 @@
+//! Prompt linting across many files (`copt lint`)
+//!
+//! Read-only counterpart to optimization: analyzes every prompt matched by
+//! one or more glob patterns and reports issues per file, as a table, JSON,
+//! JSON lines streamed as each file finishes, SARIF for code-scanning
+//! integrations, or a Markdown report with a before/after example for every
+//! rule that fired.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::analyzer::{self, Issue, Severity};
+use crate::tui::severity;
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// Glob patterns for prompt files (e.g. 'prompts/**/*.md')
+    #[arg(required = true, value_name = "GLOB")]
+    pub patterns: Vec<String>,
+
+    /// Report format: table, json, jsonl (one line per file, as each finishes), sarif, markdown
+    #[arg(long, value_enum, default_value = "table", hide_default_value = true)]
+    pub format: LintFormat,
+
+    /// Exit with an error if any issue at or above this severity is found
+    #[arg(long, value_enum, value_name = "SEVERITY")]
+    pub fail_on: Option<FailOn>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    Table,
+    Json,
+    /// One compact JSON object per file, written as soon as it's analyzed
+    Jsonl,
+    Sarif,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Info,
+}
+
+impl FailOn {
+    pub fn is_triggered_by(&self, severity: &Severity) -> bool {
+        match self {
+            FailOn::Error => matches!(severity, Severity::Error),
+            FailOn::Warning => matches!(severity, Severity::Error | Severity::Warning),
+            FailOn::Info => true,
+        }
+    }
+}
+
+/// Lint results for a single file
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    pub path: String,
+    pub score: u8,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl FileReport {
+    pub(crate) fn new(path: String, issues: Vec<Issue>) -> Self {
+        let count = |s: Severity| issues.iter().filter(|i| i.severity == s).count();
+        Self {
+            score: analyzer::quality_score(&issues),
+            errors: count(Severity::Error),
+            warnings: count(Severity::Warning),
+            infos: count(Severity::Info),
+            path,
+            issues,
+        }
+    }
+}
+
+/// Lint results for all matched files
+#[derive(Debug, Serialize)]
+pub struct LintReport {
+    pub files: Vec<FileReport>,
+}
+
+impl LintReport {
+    pub fn total_errors(&self) -> usize {
+        self.files.iter().map(|f| f.errors).sum()
+    }
+
+    pub fn total_warnings(&self) -> usize {
+        self.files.iter().map(|f| f.warnings).sum()
+    }
+
+    pub fn total_infos(&self) -> usize {
+        self.files.iter().map(|f| f.infos).sum()
+    }
+
+    /// Mean quality score across files (0 when empty)
+    pub fn average_score(&self) -> f32 {
+        if self.files.is_empty() {
+            return 0.0;
+        }
+        let sum: u32 = self.files.iter().map(|f| f.score as u32).sum();
+        sum as f32 / self.files.len() as f32
+    }
+}
+
+/// Expand glob patterns into a sorted, de-duplicated file list
+pub fn expand_patterns(patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for pattern in patterns {
+        let paths =
+            glob::glob(pattern).with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+        for path in paths {
+            let path = path.with_context(|| format!("Failed to read match for {}", pattern))?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files.dedup();
+
+    if files.is_empty() {
+        anyhow::bail!("No files matched: {}", patterns.join(", "));
+    }
+
+    Ok(files)
+}
+
+/// Analyze every file and collect per-file reports
+pub fn lint_files(
+    files: &[PathBuf],
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<LintReport> {
+    lint_files_with(files, check_categories, min_confidence, |_| Ok(()))
+}
+
+/// [`lint_files`], handing each file's report to `on_file` as soon as it's
+/// ready
+pub fn lint_files_with(
+    files: &[PathBuf],
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+    mut on_file: impl FnMut(&FileReport) -> Result<()>,
+) -> Result<LintReport> {
+    let mut reports = Vec::with_capacity(files.len());
+
+    for path in files {
+        let prompt = crate::utils::file::read_prompt_file(path)?;
+        let prompt = super::provenance::strip(&prompt);
+        let mut issues = analyzer::cache::analyze(prompt, check_categories)?;
+        if let Some(min) = min_confidence {
+            issues.retain(|i| i.confidence >= min);
+        }
+        // Lint reports get shared too; mask what --redact covers in messages
+        let issues = crate::utils::redact::issues(&issues);
+        let report = FileReport::new(path.display().to_string(), issues);
+        on_file(&report)?;
+        reports.push(report);
+    }
+
+    let cache = analyzer::cache::stats();
+    tracing::debug!(
+        files = files.len(),
+        cache_hits = cache.hits,
+        cache_misses = cache.misses,
+        "lint finished"
+    );
+
+    Ok(LintReport { files: reports })
+}
+
+/// Run `copt lint`
+pub fn run(
+    args: &LintArgs,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let files = expand_patterns(&args.patterns)?;
+    let report = if args.format == LintFormat::Jsonl {
+        let mut stdout = std::io::stdout().lock();
+        lint_files_with(&files, check_categories, min_confidence, |file| {
+            write_json_line(&mut stdout, file)
+        })?
+    } else {
+        lint_files(&files, check_categories, min_confidence)?
+    };
+
+    match args.format {
+        LintFormat::Table => print_table(&report),
+        LintFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        LintFormat::Jsonl => {}
+        LintFormat::Sarif => println!("{}", serde_json::to_string_pretty(&to_sarif(&report))?),
+        LintFormat::Markdown => print!("{}", to_markdown(&report)),
+    }
+
+    if let Some(threshold) = args.fail_on {
+        let failing = report
+            .files
+            .iter()
+            .flat_map(|f| &f.issues)
+            .filter(|i| threshold.is_triggered_by(&i.severity))
+            .count();
+        if failing > 0 {
+            anyhow::bail!(
+                "{} issue{} at or above {:?} severity",
+                failing,
+                if failing == 1 { "" } else { "s" },
+                threshold
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `value` as one compact JSON line and flush it, so a consumer
+/// reading the stream sees each result as soon as it's done
+pub fn write_json_line(w: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer(&mut *w, value)?;
+    writeln!(w)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Build a SARIF 2.1.0 log from the lint report
+pub fn to_sarif(report: &LintReport) -> serde_json::Value {
+    // One rule entry per distinct rule ID that fired
+    let mut rules: BTreeMap<&str, &Issue> = BTreeMap::new();
+    for issue in report.files.iter().flat_map(|f| &f.issues) {
+        rules.entry(issue.id.as_str()).or_insert(issue);
+    }
+
+    let rules: Vec<_> = rules
+        .values()
+        .map(|issue| {
+            let mut rule = serde_json::json!({
+                "id": issue.id,
+                "shortDescription": { "text": issue.message },
+                "help": { "text": issue.suggestion.clone().unwrap_or_default() },
+                "properties": { "category": issue.category },
+            });
+            if let Some(url) = analyzer::docs::docs_url(&issue.id) {
+                rule["helpUri"] = url.into();
+            }
+            rule
+        })
+        .collect();
+
+    let results: Vec<_> = report
+        .files
+        .iter()
+        .flat_map(|file| {
+            file.issues.iter().map(move |issue| {
+                let mut location = serde_json::json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": file.path },
+                    }
+                });
+                if let Some(line) = issue.line {
+                    location["physicalLocation"]["region"] =
+                        serde_json::json!({ "startLine": line });
+                }
+                serde_json::json!({
+                    "ruleId": issue.id,
+                    "level": sarif_level(&issue.severity),
+                    "message": { "text": issue.message },
+                    "locations": [location],
+                    "properties": { "confidence": issue.confidence },
+                })
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "copt",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// Render the lint report as Markdown
+///
+/// A summary table, the issues for each file, and a before/after example
+/// for each rule that fired.
+pub fn to_markdown(report: &LintReport) -> String {
+    let mut out = String::from("# Prompt Lint Report\n\n");
+
+    out.push_str("| File | Errors | Warnings | Info | Score |\n");
+    out.push_str("|------|-------:|---------:|-----:|------:|\n");
+    for file in &report.files {
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            file.path, file.errors, file.warnings, file.infos, file.score
+        ));
+    }
+
+    let mut fired: Vec<&str> = Vec::new();
+    for file in report.files.iter().filter(|f| !f.issues.is_empty()) {
+        out.push_str(&format!("\n## `{}`\n\n", file.path));
+        for issue in &file.issues {
+            let line = issue.line.map(|l| format!(", L{}", l)).unwrap_or_default();
+            out.push_str(&format!(
+                "- **{}** ({}{}): {}\n",
+                issue.id,
+                sarif_level(&issue.severity),
+                line,
+                issue.message
+            ));
+            if let Some(ref suggestion) = issue.suggestion {
+                out.push_str(&format!("  - {}\n", suggestion));
+            }
+            if !fired.contains(&issue.id.as_str()) {
+                fired.push(&issue.id);
+            }
+        }
+    }
+
+    fired.sort_unstable();
+    let examples: Vec<_> = fired
+        .into_iter()
+        .filter_map(|id| analyzer::examples::rule_example(id).map(|e| (id, e)))
+        .collect();
+    if !examples.is_empty() {
+        out.push_str("\n## Examples\n");
+        for (id, example) in examples {
+            out.push_str(&format!(
+                "\n### {}\n\n❌ **Before**:\n\n```\n{}\n```\n\n✅ **After**:\n\n```\n{}\n```\n",
+                id, example.before, example.after
+            ));
+        }
+    }
+
+    out
+}
+
+fn sarif_level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+fn print_table(report: &LintReport) {
+    let count = |n: usize, color: fn(String) -> colored::ColoredString| {
+        let text = format!("{:>8}", n);
+        if n == 0 {
+            text.bright_black().to_string()
+        } else {
+            color(text).to_string()
+        }
+    };
+
+    println!();
+    println!(
+        "  {:<44} {:>8} {:>8} {:>8} {:>7}",
+        "FILE".bright_black(),
+        "ERRORS".bright_black(),
+        "WARNINGS".bright_black(),
+        "INFO".bright_black(),
+        "SCORE".bright_black()
+    );
+    println!("  {}", "─".repeat(79).bright_black());
+
+    for file in &report.files {
+        println!(
+            "  {:<44} {} {} {} {}",
+            truncate_path(&file.path, 44),
+            count(file.errors, |s| severity::paint(Severity::Error, &s)),
+            count(file.warnings, |s| severity::paint(Severity::Warning, &s)),
+            count(file.infos, |s| severity::paint(Severity::Info, &s)),
+            format_score(file.score)
+        );
+    }
+
+    println!("  {}", "─".repeat(79).bright_black());
+    println!(
+        "  {:<44} {:>8} {:>8} {:>8} {:>7}",
+        format!(
+            "{} file{}",
+            report.files.len(),
+            if report.files.len() == 1 { "" } else { "s" }
+        )
+        .white()
+        .bold(),
+        report.total_errors(),
+        report.total_warnings(),
+        report.total_infos(),
+        format!("{:.0}", report.average_score())
+    );
+    println!();
+}
+
+/// Keep the end of a long path, where the file name is
+pub(crate) fn truncate_path(path: &str, max_len: usize) -> String {
+    let len = path.chars().count();
+    if len <= max_len {
+        return path.to_string();
+    }
+    let tail: String = path.chars().skip(len - (max_len - 3)).collect();
+    format!("...{}", tail)
+}
+
+/// Right-aligned score colored by band
+pub(crate) fn format_score(score: u8) -> String {
+    let text = format!("{:>7}", score);
+    if score >= 80 {
+        text.green().to_string()
+    } else if score >= 50 {
+        text.yellow().to_string()
+    } else {
+        text.red().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_prompts(dir: &TempDir) {
+        fs::create_dir_all(dir.path().join("prompts/nested")).unwrap();
+        fs::write(
+            dir.path().join("prompts/vague.md"),
+            "Can you think about this?\nNEVER use jargon!!",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("prompts/nested/clean.md"),
+            "Summarize the attached incident report in three bullet points.",
+        )
+        .unwrap();
+        fs::write(dir.path().join("prompts/notes.txt"), "ignored").unwrap();
+    }
+
+    #[test]
+    fn test_expand_patterns() {
+        let dir = TempDir::new().unwrap();
+        write_prompts(&dir);
+        let pattern = format!("{}/prompts/**/*.md", dir.path().display());
+
+        let files = expand_patterns(&[pattern.clone(), pattern]).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("nested/clean.md"));
+
+        let missing = format!("{}/nothing/*.md", dir.path().display());
+        assert!(expand_patterns(&[missing]).is_err());
+    }
+
+    #[test]
+    fn test_lint_report_totals() {
+        let dir = TempDir::new().unwrap();
+        write_prompts(&dir);
+        let files =
+            expand_patterns(&[format!("{}/prompts/**/*.md", dir.path().display())]).unwrap();
+
+        let report = lint_files(&files, None, None).unwrap();
+        let vague = report
+            .files
+            .iter()
+            .find(|f| f.path.ends_with("vague.md"))
+            .unwrap();
+        assert!(vague.warnings > 0);
+        assert!(vague.score < 100);
+        assert_eq!(
+            report.total_warnings(),
+            report.files.iter().map(|f| f.warnings).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_truncate_path() {
+        assert_eq!(truncate_path("a/b.md", 10), "a/b.md");
+        assert_eq!(
+            truncate_path("prompts/agents/reviewer.md", 14),
+            "...reviewer.md"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_stream_one_file_per_line() {
+        let dir = TempDir::new().unwrap();
+        write_prompts(&dir);
+        let files =
+            expand_patterns(&[format!("{}/prompts/**/*.md", dir.path().display())]).unwrap();
+
+        let mut out = Vec::new();
+        let mut seen = 0;
+        let report = lint_files_with(&files, None, None, |file| {
+            seen += 1;
+            write_json_line(&mut out, file)
+        })
+        .unwrap();
+
+        assert_eq!(seen, report.files.len());
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(lines.len(), report.files.len());
+        for (line, file) in lines.iter().zip(&report.files) {
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(json["path"], file.path.as_str());
+            assert_eq!(json["issues"].as_array().unwrap().len(), file.issues.len());
+        }
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let report = LintReport {
+            files: vec![FileReport::new(
+                "prompts/a.md".to_string(),
+                analyzer::analyze("Can you think about this?", None).unwrap(),
+            )],
+        };
+        let sarif = to_sarif(&report);
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "copt");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let rule = rules.iter().find(|r| r["id"] == "STY003").unwrap();
+        assert!(rule["helpUri"]
+            .as_str()
+            .unwrap()
+            .ends_with("#thinking-sensitivity"));
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), report.files[0].issues.len());
+        let exp003 = results.iter().find(|r| r["ruleId"] == "EXP003").unwrap();
+        assert_eq!(
+            exp003["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "prompts/a.md"
+        );
+        assert_eq!(
+            exp003["locations"][0]["physicalLocation"]["region"]["startLine"],
+            1
+        );
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let report = LintReport {
+            files: vec![
+                FileReport::new(
+                    "prompts/a.md".to_string(),
+                    analyzer::analyze("Can you think about this?", None).unwrap(),
+                ),
+                FileReport::new("prompts/clean.md".to_string(), Vec::new()),
+            ],
+        };
+        let markdown = to_markdown(&report);
+
+        assert!(markdown.starts_with("# Prompt Lint Report\n"));
+        assert!(markdown.contains("| `prompts/clean.md` | 0 | 0 | 0 | 100 |"));
+        assert!(markdown.contains("## `prompts/a.md`"));
+        assert!(!markdown.contains("## `prompts/clean.md`"));
+        assert!(markdown.contains("- **EXP003** (warning, L1): "));
+        assert!(markdown.contains(
+            "### EXP003\n\n❌ **Before**:\n\n```\nCan you refactor this function to be more efficient?\n```"
+        ));
+        // One example per rule, however often it fired
+        assert_eq!(markdown.matches("### EXP003").count(), 1);
+    }
+}
@@ -0,0 +1,394 @@
+//! Side-by-side comparison of candidate prompts (`copt matrix`)
+//!
+//! Analyzes several drafts for the same task and lays them out as columns:
+//! quality score, size, issue counts per category, and structure (sections,
+//! XML tags, code blocks, variables), with the best value of each scored
+//! row highlighted. Everything is static analysis; no LLM calls.
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::inspect;
+use crate::analyzer::report::{category_display_name, AnalysisReport};
+use crate::analyzer::segment::SectionKind;
+use crate::analyzer::{self, Issue, PromptType};
+
+/// Widest a prompt's column gets before its name is shortened
+const MAX_COLUMN_WIDTH: usize = 24;
+
+/// Width of the row labels
+const LABEL_WIDTH: usize = 20;
+
+/// Sections compared, in display order
+const SECTIONS: &[SectionKind] = &[
+    SectionKind::Role,
+    SectionKind::Context,
+    SectionKind::Rules,
+    SectionKind::Examples,
+    SectionKind::Task,
+    SectionKind::OutputFormat,
+];
+
+#[derive(Args, Debug)]
+pub struct MatrixArgs {
+    /// Candidate prompt files to compare
+    #[arg(value_name = "FILE", num_args = 2.., required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: MatrixFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MatrixFormat {
+    Text,
+    Json,
+}
+
+/// One candidate's column
+#[derive(Debug, Serialize)]
+pub struct Candidate {
+    pub path: String,
+    pub prompt_type: PromptType,
+    pub score: u8,
+    pub tokens: usize,
+    pub words: usize,
+    /// Issues as the report counts them (related rules on a line count once)
+    pub issues: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    /// Issue count per category that reported any
+    pub categories: BTreeMap<String, usize>,
+    /// Sections detected, in prompt order
+    pub sections: Vec<SectionKind>,
+    /// Distinct XML tags
+    pub xml_tags: usize,
+    pub code_blocks: usize,
+    pub variables: usize,
+}
+
+/// Analyze one candidate; `issues` are its (filtered) analysis results
+pub fn candidate(path: String, prompt: &str, issues: &[Issue]) -> Candidate {
+    let facts = inspect::inspect(prompt);
+    let report = AnalysisReport::from_issues(issues);
+
+    let mut sections: Vec<SectionKind> = Vec::new();
+    for section in &facts.sections {
+        if !sections.contains(&section.kind) {
+            sections.push(section.kind);
+        }
+    }
+
+    Candidate {
+        path,
+        prompt_type: facts.prompt_type,
+        score: analyzer::quality_score(issues),
+        tokens: facts.size.tokens,
+        words: facts.size.words,
+        issues: report.total(),
+        errors: report.errors,
+        warnings: report.warnings,
+        infos: report.infos,
+        categories: report
+            .categories
+            .iter()
+            .map(|c| (c.category.clone(), c.count))
+            .collect(),
+        sections,
+        xml_tags: facts.xml_tags.len(),
+        code_blocks: facts.code_blocks.len(),
+        variables: facts.variables.len(),
+    }
+}
+
+/// Run `copt matrix`
+pub fn run(
+    args: &MatrixArgs,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let mut candidates = Vec::new();
+    for path in &args.files {
+        let prompt = crate::utils::file::read_prompt_file(path)?;
+        let prompt = super::provenance::strip(&prompt);
+        let mut issues = analyzer::analyze(prompt, check_categories)?;
+        if let Some(min) = min_confidence {
+            issues.retain(|i| i.confidence >= min);
+        }
+        candidates.push(candidate(path.display().to_string(), prompt, &issues));
+    }
+
+    match args.format {
+        MatrixFormat::Text => print!("{}", render(&candidates)),
+        MatrixFormat::Json => println!("{}", serde_json::to_string_pretty(&candidates)?),
+    }
+    Ok(())
+}
+
+/// Which value of a row is best
+#[derive(Clone, Copy)]
+enum Best {
+    Highest,
+    Lowest,
+}
+
+/// One table row: label, a cell per candidate, and which cells to highlight
+struct Row {
+    label: String,
+    cells: Vec<String>,
+    best: Vec<bool>,
+}
+
+impl Row {
+    fn new(label: &str, cells: Vec<String>) -> Self {
+        Self {
+            label: label.to_string(),
+            cells,
+            best: Vec::new(),
+        }
+    }
+
+    /// Numeric row, highlighting the best value unless every candidate ties
+    fn scored(label: &str, values: &[usize], best: Best) -> Self {
+        let target = match best {
+            Best::Highest => values.iter().max(),
+            Best::Lowest => values.iter().min(),
+        };
+        let best = match target {
+            Some(target) if values.iter().any(|v| v != target) => {
+                values.iter().map(|v| v == target).collect()
+            }
+            _ => Vec::new(),
+        };
+        Self {
+            label: label.to_string(),
+            cells: values.iter().map(usize::to_string).collect(),
+            best,
+        }
+    }
+}
+
+/// The comparison table, one column per candidate
+fn render(candidates: &[Candidate]) -> String {
+    let numbers =
+        |f: &dyn Fn(&Candidate) -> usize| -> Vec<usize> { candidates.iter().map(f).collect() };
+
+    let mut rows: Vec<Option<Row>> = Vec::new();
+    let scores = numbers(&|c| c.score as usize);
+    let mut score_row = Row::scored("Quality score", &scores, Best::Highest);
+    score_row.cells = scores.iter().map(|s| format!("{}/100", s)).collect();
+    rows.push(Some(score_row));
+    rows.push(Some(Row::new(
+        "Type",
+        candidates
+            .iter()
+            .map(|c| format!("{:?}", c.prompt_type))
+            .collect(),
+    )));
+    rows.push(Some(Row::new(
+        "Tokens",
+        numbers(&|c| c.tokens)
+            .iter()
+            .map(usize::to_string)
+            .collect(),
+    )));
+    rows.push(Some(Row::new(
+        "Words",
+        numbers(&|c| c.words).iter().map(usize::to_string).collect(),
+    )));
+
+    rows.push(None);
+    rows.push(Some(Row::scored(
+        "Issues",
+        &numbers(&|c| c.issues),
+        Best::Lowest,
+    )));
+    for (label, counts) in [
+        ("  errors", numbers(&|c| c.errors)),
+        ("  warnings", numbers(&|c| c.warnings)),
+        ("  info", numbers(&|c| c.infos)),
+    ] {
+        if counts.iter().any(|&n| n > 0) {
+            rows.push(Some(Row::scored(label, &counts, Best::Lowest)));
+        }
+    }
+
+    // Categories any candidate was flagged in, built-in ones in rule order
+    let mut categories: Vec<&str> = analyzer::CATEGORIES
+        .iter()
+        .copied()
+        .filter(|cat| candidates.iter().any(|c| c.categories.contains_key(*cat)))
+        .collect();
+    for candidate in candidates {
+        for category in candidate.categories.keys() {
+            if !categories.contains(&category.as_str()) {
+                categories.push(category);
+            }
+        }
+    }
+    for category in categories {
+        let counts = numbers(&|c| c.categories.get(category).copied().unwrap_or(0));
+        rows.push(Some(Row::scored(
+            &format!("  {}", category_display_name(category)),
+            &counts,
+            Best::Lowest,
+        )));
+    }
+
+    rows.push(None);
+    for kind in SECTIONS {
+        let present = numbers(&|c| usize::from(c.sections.contains(kind)));
+        if present.iter().all(|&p| p == 0) {
+            continue;
+        }
+        let mut row = Row::scored(kind.display_name(), &present, Best::Highest);
+        row.cells = present
+            .iter()
+            .map(|&p| if p == 1 { "✓" } else { "✗" }.to_string())
+            .collect();
+        rows.push(Some(row));
+    }
+    for (label, counts) in [
+        ("XML tags", numbers(&|c| c.xml_tags)),
+        ("Code blocks", numbers(&|c| c.code_blocks)),
+        ("Variables", numbers(&|c| c.variables)),
+    ] {
+        rows.push(Some(Row::new(
+            label,
+            counts.iter().map(usize::to_string).collect(),
+        )));
+    }
+
+    let names = column_names(candidates);
+    let widths: Vec<usize> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            rows.iter()
+                .flatten()
+                .map(|row| row.cells[i].chars().count())
+                .chain(std::iter::once(name.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let pad = |text: &str, width: usize| {
+        console::pad_str(text, width, console::Alignment::Left, None).into_owned()
+    };
+
+    let mut out = String::from("\n");
+    out.push_str(&pad("", LABEL_WIDTH + 2));
+    for (name, width) in names.iter().zip(&widths) {
+        out.push_str(&format!("  {}", pad(name, *width).bold()));
+    }
+    out.push_str("\n\n");
+    for row in &rows {
+        let Some(row) = row else {
+            out.push('\n');
+            continue;
+        };
+        out.push_str(&format!(
+            "  {}",
+            pad(&row.label, LABEL_WIDTH).bright_black()
+        ));
+        for (i, (cell, width)) in row.cells.iter().zip(&widths).enumerate() {
+            let cell = pad(cell, *width);
+            if row.best.get(i).copied().unwrap_or(false) {
+                out.push_str(&format!("  {}", cell.green().bold()));
+            } else {
+                out.push_str(&format!("  {}", cell));
+            }
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+    out
+}
+
+/// Column headings: file names, or paths when names repeat, shortened to
+/// `MAX_COLUMN_WIDTH`
+fn column_names(candidates: &[Candidate]) -> Vec<String> {
+    let file_name = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .map_or(path.to_string(), |n| n.to_string_lossy().into_owned())
+    };
+    let names: Vec<String> = candidates.iter().map(|c| file_name(&c.path)).collect();
+    let unique = names
+        .iter()
+        .all(|n| names.iter().filter(|m| *m == n).count() == 1);
+
+    candidates
+        .iter()
+        .zip(names)
+        .map(|(c, name)| {
+            let name = if unique { name } else { c.path.clone() };
+            let len = name.chars().count();
+            if len <= MAX_COLUMN_WIDTH {
+                name
+            } else {
+                // Keep the end, where names usually differ
+                let tail: String = name.chars().skip(len - (MAX_COLUMN_WIDTH - 1)).collect();
+                format!("…{}", tail)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyzed(path: &str, prompt: &str) -> Candidate {
+        let issues = analyzer::analyze(prompt, None).unwrap();
+        candidate(path.to_string(), prompt, &issues)
+    }
+
+    #[test]
+    fn test_candidates_compare_score_issues_and_structure() {
+        let rough = analyzed("drafts/rough.md", "Can you make a dashboard?");
+        let tidy = analyzed(
+            "drafts/tidy.md",
+            "<instructions>\nBuild a sales dashboard for {{REGION}} with a revenue chart and a table of the top ten accounts.\n</instructions>\n\n<output_format>\nReturn one React component in a single code block.\n</output_format>",
+        );
+
+        assert!(tidy.score > rough.score);
+        assert!(rough.issues > 0);
+        assert!(rough.categories.contains_key("explicitness"));
+        assert_eq!(tidy.variables, 1);
+        assert_eq!(tidy.xml_tags, 2);
+        assert!(tidy.sections.contains(&SectionKind::OutputFormat));
+
+        colored::control::set_override(false);
+        let table = render(&[rough, tidy]);
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.contains("rough.md") && header.contains("tidy.md"));
+        assert!(table
+            .lines()
+            .any(|l| l.trim_start().starts_with("Quality score")));
+        assert!(table
+            .lines()
+            .any(|l| l.contains("Output format") && l.contains('✗')));
+    }
+
+    #[test]
+    fn test_column_names_disambiguate_and_shorten() {
+        let named = |path: &str| analyzed(path, "Summarize the report.");
+        let names = column_names(&[named("a/prompt.md"), named("b/prompt.md")]);
+        assert_eq!(names, ["a/prompt.md", "b/prompt.md"]);
+
+        let names = column_names(&[
+            named("one.md"),
+            named("a-very-long-candidate-prompt-name-v2.md"),
+        ]);
+        assert_eq!(names[0], "one.md");
+        assert_eq!(names[1].chars().count(), MAX_COLUMN_WIDTH);
+        assert!(names[1].starts_with('…') && names[1].ends_with("-v2.md"));
+    }
+}
@@ -0,0 +1,495 @@
+//! Local usage metrics (`copt metrics`)
+//!
+//! Each optimization run appends one line to a JSONL file in the local data
+//! directory: when it ran, which rules fired, and the before/after quality
+//! scores. `copt serve` keeps a second file with each scheduled re-audit's
+//! per-prompt scores, so trends survive restarts. Prompt text is never recorded, and nothing is ever sent over the
+//! network — `copt metrics export` is the only way data leaves the file, and
+//! it requires an explicit `--format`.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::analyzer::Issue;
+
+#[derive(Args, Debug)]
+pub struct MetricsArgs {
+    #[command(subcommand)]
+    pub command: MetricsCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MetricsCommand {
+    /// Summarize recorded runs
+    Show,
+
+    /// Export recorded metrics
+    Export {
+        /// Export format (required)
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+
+        /// Rows to export: one per day, one per rule, or one per prompt per `copt serve` pass
+        #[arg(long, value_enum, default_value = "day")]
+        by: ExportGrouping,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportGrouping {
+    Day,
+    Rule,
+    /// One row per prompt per scheduled re-audit (`copt serve`)
+    Audit,
+}
+
+/// One recorded optimization run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Local date of the run (YYYY-MM-DD)
+    pub date: String,
+    pub provider: String,
+    pub offline: bool,
+    /// Rule IDs that fired, one entry per issue
+    pub rules: Vec<String>,
+    pub original_score: u8,
+    pub optimized_score: u8,
+    /// Estimated LLM spend in USD (see [`crate::llm::budget`])
+    #[serde(default)]
+    pub cost: f64,
+}
+
+impl RunRecord {
+    pub fn new(
+        provider: &str,
+        offline: bool,
+        issues: &[Issue],
+        original_score: u8,
+        optimized_score: u8,
+        cost: f64,
+    ) -> Self {
+        Self {
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            provider: provider.to_string(),
+            offline,
+            rules: issues.iter().map(|i| i.id.clone()).collect(),
+            original_score,
+            optimized_score,
+            cost,
+        }
+    }
+}
+
+/// One prompt's result in a scheduled re-audit (`copt serve`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the pass ran (RFC 3339)
+    pub timestamp: String,
+    pub path: String,
+    pub score: u8,
+    /// Rule IDs that fired, one entry per issue
+    pub rules: Vec<String>,
+}
+
+/// Per-day aggregate
+#[derive(Debug, Default, PartialEq)]
+pub struct DaySummary {
+    pub runs: usize,
+    pub issues: usize,
+    pub avg_original_score: f32,
+    pub avg_optimized_score: f32,
+}
+
+/// Per-rule aggregate
+#[derive(Debug, Default, PartialEq)]
+pub struct RuleSummary {
+    /// Total issues raised by the rule
+    pub fired: usize,
+    /// Runs in which the rule fired at least once
+    pub runs: usize,
+}
+
+/// Location of the metrics file
+fn default_metrics_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.data_dir().join("metrics.jsonl"))
+}
+
+/// Location of the scheduled re-audit results
+fn default_audits_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.data_dir().join("audits.jsonl"))
+}
+
+/// Local metrics settings (`[metrics]`), defaults if the config can't be read
+fn settings() -> super::config::MetricsConfig {
+    super::config::load_config()
+        .map(|c| c.metrics)
+        .unwrap_or_default()
+}
+
+/// Append a run to the local metrics file
+///
+/// Metrics are best-effort and never fail the run; `--sandbox` skips them.
+pub fn record(run: &RunRecord) {
+    if crate::utils::sandbox::enabled() {
+        return;
+    }
+    let settings = settings();
+    if !settings.enabled {
+        return;
+    }
+    if let Some(path) = default_metrics_path() {
+        if let Err(e) = append(&path, run, settings.lock) {
+            tracing::debug!("Failed to record metrics: {:#}", e);
+        }
+    }
+}
+
+/// Append one re-audit pass to the local audit trend file
+///
+/// Best-effort like [`record`], and skipped under the same conditions.
+pub fn record_audits(records: &[AuditRecord]) {
+    if crate::utils::sandbox::enabled() {
+        return;
+    }
+    let settings = settings();
+    if !settings.enabled {
+        return;
+    }
+    if let Some(path) = default_audits_path() {
+        for record in records {
+            if let Err(e) = append(&path, record, settings.lock) {
+                tracing::debug!("Failed to record audit: {:#}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// Estimated LLM spend recorded so far this calendar month, in USD
+pub fn month_spend() -> f64 {
+    let month = chrono::Local::now().format("%Y-%m-").to_string();
+    default_metrics_path()
+        .and_then(|path| load::<RunRecord>(&path).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter(|run| run.date.starts_with(&month))
+        .map(|run| run.cost)
+        .sum()
+}
+
+/// Every recorded re-audit result, oldest first
+pub fn load_audits() -> Result<Vec<AuditRecord>> {
+    match default_audits_path() {
+        Some(path) => load(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// How long a run waits for another run's metrics lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn append(path: &Path, record: &impl Serialize, lock: bool) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _lock = lock
+        .then(|| crate::utils::file::LockFile::acquire(path, LOCK_TIMEOUT))
+        .transpose()?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    // One write per record, so concurrent appends can't interleave mid-line
+    let line = format!("{}\n", serde_json::to_string(record)?);
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Read all records of a metrics file, skipping malformed lines
+fn load<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read metrics: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Aggregate runs by date
+pub fn summarize_by_day(runs: &[RunRecord]) -> BTreeMap<String, DaySummary> {
+    let mut days: BTreeMap<String, (usize, usize, u32, u32)> = BTreeMap::new();
+    for run in runs {
+        let day = days.entry(run.date.clone()).or_default();
+        day.0 += 1;
+        day.1 += run.rules.len();
+        day.2 += run.original_score as u32;
+        day.3 += run.optimized_score as u32;
+    }
+
+    days.into_iter()
+        .map(|(date, (runs, issues, original, optimized))| {
+            (
+                date,
+                DaySummary {
+                    runs,
+                    issues,
+                    avg_original_score: original as f32 / runs as f32,
+                    avg_optimized_score: optimized as f32 / runs as f32,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Aggregate runs by rule ID
+pub fn summarize_by_rule(runs: &[RunRecord]) -> BTreeMap<String, RuleSummary> {
+    let mut rules: BTreeMap<String, RuleSummary> = BTreeMap::new();
+    for run in runs {
+        let mut seen: Vec<String> = Vec::new();
+        for id in &run.rules {
+            // Runs recorded before a rename count toward the new ID
+            let id = crate::analyzer::deprecated::renamed_to(id).unwrap_or_else(|| id.clone());
+            let rule = rules.entry(id.clone()).or_default();
+            rule.fired += 1;
+            if !seen.contains(&id) {
+                rule.runs += 1;
+                seen.push(id);
+            }
+        }
+    }
+    rules
+}
+
+/// Render runs (or, `by` audit, re-audit results) as CSV
+pub fn to_csv(runs: &[RunRecord], audits: &[AuditRecord], by: ExportGrouping) -> String {
+    let mut out = String::new();
+    match by {
+        ExportGrouping::Day => {
+            out.push_str("date,runs,issues,avg_original_score,avg_optimized_score\n");
+            for (date, day) in summarize_by_day(runs) {
+                out.push_str(&format!(
+                    "{},{},{},{:.1},{:.1}\n",
+                    date, day.runs, day.issues, day.avg_original_score, day.avg_optimized_score
+                ));
+            }
+        }
+        ExportGrouping::Rule => {
+            out.push_str("rule_id,times_fired,runs\n");
+            for (id, rule) in summarize_by_rule(runs) {
+                out.push_str(&format!("{},{},{}\n", id, rule.fired, rule.runs));
+            }
+        }
+        ExportGrouping::Audit => {
+            out.push_str("timestamp,path,score,issues\n");
+            for audit in audits {
+                out.push_str(&format!(
+                    "{},{},{},{}\n",
+                    audit.timestamp,
+                    csv_field(&audit.path),
+                    audit.score,
+                    audit.rules.len()
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Quote a CSV field when it needs it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Run `copt metrics`
+pub fn run(args: &MetricsArgs) -> Result<()> {
+    let path = default_metrics_path().context("Could not determine the data directory")?;
+    let runs = load(&path)?;
+
+    match args.command {
+        MetricsCommand::Show => print_summary(&runs, &path),
+        MetricsCommand::Export {
+            format: ExportFormat::Csv,
+            by,
+            ref output,
+        } => {
+            let audits = if by == ExportGrouping::Audit {
+                load_audits()?
+            } else {
+                Vec::new()
+            };
+            let csv = to_csv(&runs, &audits, by);
+            match output {
+                Some(file) => {
+                    crate::utils::sandbox::ensure_writable(&format!("Writing {}", file.display()))?;
+                    std::fs::write(file, csv)
+                        .with_context(|| format!("Failed to write: {}", file.display()))?
+                }
+                None => print!("{}", csv),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_summary(runs: &[RunRecord], path: &Path) {
+    println!();
+    if runs.is_empty() {
+        println!(
+            "  {}  No runs recorded yet ({})",
+            "ℹ".blue(),
+            path.display().to_string().bright_black()
+        );
+        println!();
+        return;
+    }
+
+    let days = summarize_by_day(runs);
+    let avg = |f: fn(&RunRecord) -> u8| {
+        runs.iter().map(|r| f(r) as u32).sum::<u32>() as f32 / runs.len() as f32
+    };
+
+    println!("  {}", "LOCAL METRICS".white().bold());
+    println!("  {}", "─".repeat(50).bright_black());
+    println!(
+        "  {:<20} {} over {} day{}",
+        "Runs:".bright_black(),
+        runs.len(),
+        days.len(),
+        if days.len() == 1 { "" } else { "s" }
+    );
+    println!(
+        "  {:<20} {:.0} → {:.0}",
+        "Avg quality score:".bright_black(),
+        avg(|r| r.original_score),
+        avg(|r| r.optimized_score)
+    );
+    let month = chrono::Local::now().format("%Y-%m-").to_string();
+    let spend: f64 = runs
+        .iter()
+        .filter(|r| r.date.starts_with(&month))
+        .map(|r| r.cost)
+        .sum();
+    if spend > 0.0 {
+        println!(
+            "  {:<20} {} this month (estimated)",
+            "LLM spend:".bright_black(),
+            crate::llm::budget::dollars(spend)
+        );
+    }
+
+    let mut rules: Vec<_> = summarize_by_rule(runs).into_iter().collect();
+    rules.sort_by(|a, b| b.1.fired.cmp(&a.1.fired).then(a.0.cmp(&b.0)));
+    if !rules.is_empty() {
+        println!("  {}", "Top rules:".bright_black());
+        for (id, rule) in rules.iter().take(5) {
+            println!(
+                "    {:<10} {} ({} run{})",
+                id.cyan(),
+                rule.fired,
+                rule.runs,
+                if rule.runs == 1 { "" } else { "s" }
+            );
+        }
+    }
+    println!("  {}", "─".repeat(50).bright_black());
+    println!(
+        "  {}",
+        format!("Stored locally in {}", path.display()).bright_black()
+    );
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(date: &str, rules: &[&str], original: u8, optimized: u8) -> RunRecord {
+        RunRecord {
+            date: date.to_string(),
+            provider: "mock".to_string(),
+            offline: false,
+            rules: rules.iter().map(|s| s.to_string()).collect(),
+            original_score: original,
+            optimized_score: optimized,
+            cost: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested/metrics.jsonl");
+        append(&path, &run("2026-01-01", &["EXP003"], 70, 95), false).unwrap();
+        append(&path, &run("2026-01-02", &[], 100, 100), true).unwrap();
+        assert!(!dir.path().join("nested/metrics.jsonl.lock").exists());
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let runs: Vec<RunRecord> = load(&path).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].rules, vec!["EXP003"]);
+        assert!(load::<RunRecord>(&dir.path().join("missing.jsonl"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_csv_by_day_and_rule() {
+        let runs = vec![
+            run("2026-01-01", &["EXP003", "STY003", "STY003"], 60, 90),
+            run("2026-01-01", &["EXP003"], 80, 100),
+            run("2026-01-02", &[], 100, 100),
+        ];
+
+        assert_eq!(
+            to_csv(&runs, &[], ExportGrouping::Day),
+            "date,runs,issues,avg_original_score,avg_optimized_score\n\
+             2026-01-01,2,4,70.0,95.0\n\
+             2026-01-02,1,0,100.0,100.0\n"
+        );
+        assert_eq!(
+            to_csv(&runs, &[], ExportGrouping::Rule),
+            "rule_id,times_fired,runs\nEXP003,2,2\nSTY003,2,1\n"
+        );
+
+        let audits = vec![AuditRecord {
+            timestamp: "2026-01-02T09:00:00+00:00".to_string(),
+            path: "prompts/a, b.md".to_string(),
+            score: 88,
+            rules: vec!["EXP003".to_string(), "STY003".to_string()],
+        }];
+        assert_eq!(
+            to_csv(&[], &audits, ExportGrouping::Audit),
+            "timestamp,path,score,issues\n2026-01-02T09:00:00+00:00,\"prompts/a, b.md\",88,2\n"
+        );
+    }
+}
@@ -4,8 +4,36 @@
 
 #![allow(dead_code)]
 
+pub mod ask;
+pub mod audit;
+pub mod auth;
+pub mod batch;
 pub mod config;
+pub mod config_check;
+pub mod devtest;
+pub mod discover;
+pub mod docs;
+pub mod embedded;
+pub mod eval;
+pub mod history;
+pub mod inspect;
+pub mod lint;
+pub mod matrix;
+pub mod metrics;
+pub mod models;
+pub mod notify;
+pub mod outdated;
+pub mod policy;
+pub mod provenance;
+pub mod rules;
+pub mod serve;
+pub mod similar;
+pub mod skeleton;
 pub mod suggest;
+pub mod textconv;
+pub mod todo;
+pub mod verify;
+pub mod workbench;
 
 /// Default model to use for optimization (Bedrock inference profile ID)
 pub const DEFAULT_MODEL: &str = "us.anthropic.claude-sonnet-4-5-20250929-v1:0";
@@ -13,57 +41,40 @@ pub const DEFAULT_MODEL: &str = "us.anthropic.claude-sonnet-4-5-20250929-v1:0";
 /// Default max tokens for optimization requests
 pub const DEFAULT_MAX_TOKENS: u32 = 4096;
 
-/// Available Claude 4.5 models (Bedrock inference profile IDs)
-pub const AVAILABLE_MODELS: &[&str] = &[
-    "us.anthropic.claude-sonnet-4-5-20250929-v1:0",
-    "global.anthropic.claude-opus-4-5-20251101-v1:0",
-    "us.anthropic.claude-haiku-4-5-20251001-v1:0",
-];
-
-/// Short aliases for models
-pub const MODEL_ALIASES: &[(&str, &str)] = &[
-    ("sonnet", "us.anthropic.claude-sonnet-4-5-20250929-v1:0"),
-    ("sonnet-4.5", "us.anthropic.claude-sonnet-4-5-20250929-v1:0"),
-    ("opus", "global.anthropic.claude-opus-4-5-20251101-v1:0"),
-    ("opus-4.5", "global.anthropic.claude-opus-4-5-20251101-v1:0"),
-    ("haiku", "us.anthropic.claude-haiku-4-5-20251001-v1:0"),
-    ("haiku-4.5", "us.anthropic.claude-haiku-4-5-20251001-v1:0"),
-];
-
 /// Resolve a model name or alias to a full model ID
+///
+/// User aliases from `[models.aliases]` are expanded first; built-in aliases
+/// resolve to the Bedrock inference profile ID.
 pub fn resolve_model_id(model: &str) -> String {
-    // Check if it's an alias
-    for (alias, full_id) in MODEL_ALIASES {
-        if model.eq_ignore_ascii_case(alias) {
-            return full_id.to_string();
-        }
-    }
-
-    // If it already looks like a full ID, use it directly
-    if model.contains("anthropic.claude") {
-        return model.to_string();
-    }
-
-    // Default: return as-is
-    model.to_string()
+    crate::llm::models::bedrock_id(&crate::llm::models::expand_alias(model))
 }
 
 /// Check if a model string is valid
 pub fn is_valid_model(model: &str) -> bool {
-    // Check direct matches
-    if AVAILABLE_MODELS.contains(&model) {
-        return true;
-    }
+    let model = crate::llm::models::expand_alias(model);
+    // Accept any anthropic model pattern, including models newer than the catalog
+    crate::llm::models::lookup(&model).is_some()
+        || model.contains("anthropic.claude")
+        || model.starts_with("claude-")
+        || crate::llm::models::is_arn(&model)
+}
 
-    // Check aliases
-    for (alias, _) in MODEL_ALIASES {
-        if model.eq_ignore_ascii_case(alias) {
-            return true;
-        }
+/// Reject a `--model` no provider would accept, suggesting the closest alias
+pub fn validate_model(model: &str) -> anyhow::Result<()> {
+    if is_valid_model(model) {
+        return Ok(());
     }
-
-    // Accept any anthropic model pattern
-    model.contains("anthropic.claude")
+    let hint = match crate::llm::models::closest_alias(model) {
+        Some(alias) => format!(" Did you mean '{}'?", alias),
+        None => String::new(),
+    };
+    Err(crate::error::CoptError::Config(format!(
+        "Unknown model '{}'.{}\n\n\
+        Use an alias (sonnet, opus, haiku) or a full model ID. \
+        Run `copt models` to list them.",
+        model, hint
+    ))
+    .into())
 }
 
 #[cfg(test)]
@@ -81,6 +92,19 @@ mod tests {
         assert!(is_valid_model("sonnet"));
         assert!(is_valid_model("opus-4.5"));
         assert!(!is_valid_model("gpt-4"));
+        assert!(is_valid_model("claude-sonnet-4-6"));
+        assert!(is_valid_model(
+            "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/a1b2c3"
+        ));
+    }
+
+    #[test]
+    fn test_validate_model() {
+        assert!(validate_model("haiku").is_ok());
+        let err = validate_model("sonet").unwrap_err().to_string();
+        assert!(err.starts_with("Unknown model 'sonet'. Did you mean 'sonnet'?"));
+        let err = validate_model("gpt-4").unwrap_err().to_string();
+        assert!(!err.contains("Did you mean"));
     }
 
     #[test]
@@ -0,0 +1,411 @@
+//! Model listing (`copt models`)
+//!
+//! Shows the aliases `--model` accepts, the ID each resolves to for the
+//! selected provider, context window and list price, and any aliases from
+//! `[models.aliases]` in the config file. With `--check`, each model is
+//! probed with a one-token request to see whether the account can use it
+//! (for Bedrock, in the selected `--region`).
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::llm::models::{self, MODELS};
+use crate::llm::LlmClient;
+
+#[derive(Args, Debug)]
+pub struct ModelsArgs {
+    /// Send a one-token request to each model to check availability
+    #[arg(long)]
+    pub check: bool,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: ModelsFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ModelsFormat {
+    Text,
+    Json,
+}
+
+/// Whether the account can call a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    Available,
+    /// Credentials work but lack access (model not enabled, missing permission)
+    NoAccess,
+    /// The provider doesn't offer the model here (e.g. not in this region)
+    NotOffered,
+    /// The check itself failed (credentials, network)
+    Unknown,
+}
+
+/// One catalog model as seen from the selected provider
+#[derive(Debug, Serialize)]
+pub struct ModelRow {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// ID sent to the selected provider
+    pub id: String,
+    pub context_window: u32,
+    pub max_output: u32,
+    pub input_price: f64,
+    pub output_price: f64,
+    /// Resolved from the current `--model`
+    pub current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Availability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An alias from `[models.aliases]`
+#[derive(Debug, Serialize)]
+pub struct UserAlias {
+    pub alias: String,
+    pub target: String,
+    /// ID sent to the selected provider
+    pub id: String,
+}
+
+/// Everything `copt models` reports
+#[derive(Debug, Serialize)]
+pub struct ModelsReport {
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub models: Vec<ModelRow>,
+    pub user_aliases: Vec<UserAlias>,
+}
+
+/// Build the report for `provider`; `current_model` is the resolved `--model`
+pub fn report(provider: &str, region: Option<&str>, current_model: &str) -> ModelsReport {
+    let current = models::lookup(current_model).map(|info| info.name);
+    ModelsReport {
+        provider: provider.to_string(),
+        region: region.map(str::to_string),
+        models: MODELS
+            .iter()
+            .map(|info| ModelRow {
+                name: info.name,
+                aliases: info.aliases,
+                id: models::provider_id(provider, info.bedrock_id),
+                context_window: info.context_window,
+                max_output: info.max_output,
+                input_price: info.input_price,
+                output_price: info.output_price,
+                current: current == Some(info.name),
+                availability: None,
+                error: None,
+            })
+            .collect(),
+        user_aliases: models::user_aliases()
+            .map(|(alias, target)| UserAlias {
+                alias: alias.to_string(),
+                target: target.to_string(),
+                id: models::provider_id(provider, target),
+            })
+            .collect(),
+    }
+}
+
+/// Probe each model in `report` with a one-token request
+pub async fn check_availability(report: &mut ModelsReport, client: &dyn LlmClient) {
+    for row in &mut report.models {
+        let started = std::time::Instant::now();
+        let result = client.complete("Reply with OK.", "hi", &row.id, 1).await;
+        tracing::debug!(
+            model = row.id.as_str(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            ok = result.is_ok(),
+            "availability check"
+        );
+        let (availability, error) = match result {
+            Ok(_) => (Availability::Available, None),
+            Err(e) => {
+                let message = format!("{:#}", e);
+                (classify_error(&message), Some(message))
+            }
+        };
+        row.availability = Some(availability);
+        // Throttling means the call got through
+        row.error = error.filter(|_| availability != Availability::Available);
+    }
+}
+
+/// A model to offer when the current one can't be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alternative {
+    /// What to pass as `--model`
+    pub alias: &'static str,
+    /// "Claude Haiku 4.5 (haiku, fast)", with user aliases that point at it
+    pub label: String,
+}
+
+/// Catalog models other than `current_model` that the account can call
+///
+/// Probes each model that `allowed` accepts (by canonical alias) the way
+/// `copt models --check` does.
+pub async fn accessible_alternatives(
+    provider: &str,
+    region: Option<&str>,
+    current_model: &str,
+    client: &dyn LlmClient,
+    allowed: impl Fn(&str) -> bool,
+) -> Vec<Alternative> {
+    let mut report = report(provider, region, current_model);
+    report.models.retain(|row| allowed(row.aliases[0]));
+    check_availability(&mut report, client).await;
+    report
+        .models
+        .iter()
+        .filter(|row| !row.current && row.availability == Some(Availability::Available))
+        .map(|row| {
+            let alias = row.aliases[0];
+            let names: Vec<&str> = std::iter::once(alias)
+                .chain(
+                    report
+                        .user_aliases
+                        .iter()
+                        .filter(|user| user.id == row.id)
+                        .map(|user| user.alias.as_str()),
+                )
+                .collect();
+            Alternative {
+                alias,
+                label: format!("{} ({})", row.name, names.join(", ")),
+            }
+        })
+        .collect()
+}
+
+/// Map a failed probe to an availability status
+fn classify_error(message: &str) -> Availability {
+    if message.contains("ThrottlingException") || message.contains("rate_limit") {
+        Availability::Available
+    } else if message.contains("AccessDenied") || message.contains("permission") {
+        Availability::NoAccess
+    } else if message.contains("ResourceNotFound")
+        || message.contains("ValidationException")
+        || message.contains("not_found_error")
+    {
+        Availability::NotOffered
+    } else {
+        Availability::Unknown
+    }
+}
+
+/// Run `copt models`
+pub async fn run(
+    args: &ModelsArgs,
+    provider: &str,
+    region: Option<&str>,
+    current_model: &str,
+    client: Option<&dyn LlmClient>,
+) -> Result<()> {
+    let mut report = report(provider, region, current_model);
+    if let Some(client) = client.filter(|_| args.check) {
+        check_availability(&mut report, client).await;
+    }
+
+    match args.format {
+        ModelsFormat::Text => print_report(&report, args.check),
+        ModelsFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(())
+}
+
+fn print_report(report: &ModelsReport, checked: bool) {
+    let location = match &report.region {
+        Some(region) => format!("{} ({})", report.provider, region),
+        None => report.provider.clone(),
+    };
+    println!();
+    println!("  {} {}", "Models for".bold(), location.bold());
+    println!();
+
+    let alias_width = report
+        .models
+        .iter()
+        .map(|row| row.aliases.join(", ").len())
+        .max()
+        .unwrap_or(0)
+        .max("ALIASES".len());
+    let id_width = report
+        .models
+        .iter()
+        .map(|row| row.id.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "    {:<aw$}  {:<iw$}  {:>7}  {:>15}{}",
+        "ALIASES",
+        "MODEL ID",
+        "CONTEXT",
+        "$/MTOK IN/OUT",
+        if checked { "  AVAILABLE" } else { "" },
+        aw = alias_width,
+        iw = id_width
+    );
+    for row in &report.models {
+        let marker = if row.current {
+            "*".cyan()
+        } else {
+            " ".normal()
+        };
+        let price = format!(
+            "{} / {}",
+            dollars(row.input_price),
+            dollars(row.output_price)
+        );
+        let availability = row
+            .availability
+            .map(|a| format!("  {}", availability_label(a)))
+            .unwrap_or_default();
+        println!(
+            "  {} {:<aw$}  {:<iw$}  {:>7}  {:>15}{}",
+            marker,
+            row.aliases.join(", "),
+            row.id,
+            tokens(row.context_window),
+            price,
+            availability,
+            aw = alias_width,
+            iw = id_width
+        );
+    }
+
+    if !report.user_aliases.is_empty() {
+        println!();
+        println!("  {}", "From [models.aliases]:".bold());
+        for alias in &report.user_aliases {
+            let id = if alias.id == alias.target {
+                String::new()
+            } else {
+                format!(" ({})", alias.id).bright_black().to_string()
+            };
+            println!("    {} → {}{}", alias.alias.cyan(), alias.target, id);
+        }
+    }
+
+    println!();
+    if report.models.iter().any(|row| row.current) {
+        println!("  {}", "* current --model".bright_black());
+    }
+    if checked {
+        if let Some(error) = report.models.iter().find_map(|row| row.error.as_deref()) {
+            let first_line = error.lines().next().unwrap_or(error);
+            println!("  {} {}", "First error:".yellow(), first_line);
+        }
+    } else {
+        println!(
+            "  {}",
+            "Run with --check to test which models this account can call.".bright_black()
+        );
+    }
+}
+
+fn availability_label(availability: Availability) -> colored::ColoredString {
+    match availability {
+        Availability::Available => "✓ yes".green(),
+        Availability::NoAccess => "✗ no access".red(),
+        Availability::NotOffered => "✗ not offered".red(),
+        Availability::Unknown => "? unknown".yellow(),
+    }
+}
+
+/// "200K", "1M"
+fn tokens(count: u32) -> String {
+    if count >= 1_000_000 && count.is_multiple_of(1_000_000) {
+        format!("{}M", count / 1_000_000)
+    } else {
+        format!("{}K", count / 1000)
+    }
+}
+
+/// "$3", "$0.80"
+fn dollars(price: f64) -> String {
+    if price.fract() == 0.0 {
+        format!("${}", price)
+    } else {
+        format!("${:.2}", price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    #[test]
+    fn test_report_resolves_ids_per_provider() {
+        let report = report(
+            "anthropic",
+            None,
+            "us.anthropic.claude-opus-4-5-20251101-v1:0",
+        );
+        let opus = report.models.iter().find(|r| r.current).unwrap();
+        assert_eq!(opus.id, "claude-opus-4-5-20251101");
+        assert_eq!(report.models.iter().filter(|r| r.current).count(), 1);
+
+        let report = super::report("bedrock", Some("us-west-2"), "sonnet");
+        assert_eq!(
+            report.models[0].id,
+            "us.anthropic.claude-sonnet-4-5-20250929-v1:0"
+        );
+        assert!(report.models[0].current);
+    }
+
+    #[tokio::test]
+    async fn test_check_availability() {
+        let client = MockLlmClient::new();
+        client.push_error("AccessDeniedException: model access not enabled");
+        client.push_response("OK");
+        client.push_error("ValidationException: invalid model identifier");
+
+        let mut report = report("bedrock", Some("us-west-2"), "sonnet");
+        check_availability(&mut report, &client).await;
+        let statuses: Vec<_> = report.models.iter().map(|r| r.availability).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(Availability::NoAccess),
+                Some(Availability::Available),
+                Some(Availability::NotOffered)
+            ]
+        );
+        assert!(report.models[1].error.is_none());
+        assert_eq!(client.calls()[0].max_tokens, 1);
+    }
+
+    #[tokio::test]
+    async fn test_accessible_alternatives_skip_current_and_unavailable() {
+        let client = MockLlmClient::new();
+        client.push_error("AccessDeniedException: model access not enabled");
+        client.push_error("AccessDeniedException: model access not enabled");
+        client.push_response("OK");
+
+        let alternatives =
+            accessible_alternatives("bedrock", Some("us-west-2"), "sonnet", &client, |_| true)
+                .await;
+        assert_eq!(
+            alternatives,
+            vec![Alternative {
+                alias: "haiku",
+                label: "Claude Haiku 4.5 (haiku)".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_formatting_helpers() {
+        assert_eq!(tokens(200_000), "200K");
+        assert_eq!(tokens(1_000_000), "1M");
+        assert_eq!(dollars(3.0), "$3");
+        assert_eq!(dollars(0.8), "$0.80");
+    }
+}
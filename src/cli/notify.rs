@@ -0,0 +1,79 @@
+//! Desktop notification when a long run finishes
+//!
+//! A slow Opus rewrite or a batch of prompts can take minutes. When a run
+//! takes longer than `[output] notify_after` (30s by default) and stdout is
+//! a terminal, copt posts a desktop notification so you can switch away in
+//! the meantime. `[output] notify = false` turns it off; builds without the
+//! `notify` feature never notify.
+
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use super::config::OutputConfig;
+use crate::llm::parse_duration;
+
+/// Used when `notify_after` doesn't parse
+const DEFAULT_AFTER: Duration = Duration::from_secs(30);
+
+/// Notify that a run started at `started` finished, if it took long enough
+pub fn finished(started: Instant, summary: &str, body: &str) {
+    let config = super::config::load_config()
+        .map(|c| c.output)
+        .unwrap_or_default();
+    if due(&config, started.elapsed(), std::io::stdout().is_terminal()) {
+        send(summary, body);
+    }
+}
+
+/// Whether a run that took `elapsed` notifies
+fn due(config: &OutputConfig, elapsed: Duration, terminal: bool) -> bool {
+    let after = parse_duration(&config.notify_after).unwrap_or(DEFAULT_AFTER);
+    config.notify && terminal && elapsed >= after
+}
+
+#[cfg(feature = "notify")]
+fn send(summary: &str, body: &str) {
+    // A desktop without a notification service is no reason to fail the run
+    if let Err(e) = notify_rust::Notification::new()
+        .appname("copt")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        tracing::debug!("Desktop notification failed: {}", e);
+    }
+}
+
+#[cfg(not(feature = "notify"))]
+fn send(_summary: &str, _body: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_long_runs_on_a_terminal_notify() {
+        let config = OutputConfig::default();
+        let minute = Duration::from_secs(60);
+        assert!(due(&config, minute, true));
+        assert!(!due(&config, Duration::from_secs(5), true));
+        assert!(!due(&config, minute, false));
+
+        let config = OutputConfig {
+            notify_after: "2m".to_string(),
+            ..Default::default()
+        };
+        assert!(!due(&config, minute, true));
+        let config = OutputConfig {
+            notify: false,
+            ..Default::default()
+        };
+        assert!(!due(&config, minute, true));
+        // An unreadable threshold falls back to 30s
+        let config = OutputConfig {
+            notify_after: "soon".to_string(),
+            ..Default::default()
+        };
+        assert!(due(&config, minute, true));
+    }
+}
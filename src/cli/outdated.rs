@@ -0,0 +1,293 @@
+//! Saved optimizations made under different rules (`copt outdated`)
+//!
+//! Every saved result has a metadata file recording the copt version, the
+//! rule-set hash, and the issues found. When the hash no longer matches,
+//! the saved original is analyzed again; prompts whose rule IDs differ are
+//! listed, and `--reoptimize` refreshes them through the normal optimize
+//! flow (see `main.rs`).
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{self, deprecated, ruleset};
+
+#[derive(Args, Debug)]
+pub struct OutdatedArgs {
+    /// Directories of saved optimizations (default: --output-dir)
+    #[arg(value_name = "DIR")]
+    pub dirs: Vec<PathBuf>,
+
+    /// Optimize each outdated prompt again and overwrite its saved result
+    #[arg(long)]
+    pub reoptimize: bool,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: OutdatedFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutdatedFormat {
+    Text,
+    Json,
+}
+
+/// A saved optimization whose analysis would differ today
+#[derive(Debug, Clone, Serialize)]
+pub struct OutdatedPrompt {
+    pub metadata: PathBuf,
+    pub original: PathBuf,
+    pub optimized: PathBuf,
+    /// Recorded at save time; missing for results saved before it was
+    pub copt_version: Option<String>,
+    pub rule_set: Option<String>,
+    /// Rules that fire now but didn't then
+    pub added: Vec<String>,
+    /// Rules that fired then but don't now
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutdatedReport {
+    pub rule_set: String,
+    /// Saved optimizations found
+    pub scanned: usize,
+    pub outdated: Vec<OutdatedPrompt>,
+}
+
+/// Metadata fields `outdated` reads
+#[derive(Debug, serde::Deserialize)]
+struct SavedMetadata {
+    files: SavedFiles,
+    copt_version: Option<String>,
+    rule_set: Option<String>,
+    #[serde(default)]
+    issues: Vec<SavedIssue>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SavedFiles {
+    original: String,
+    optimized: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SavedIssue {
+    id: String,
+}
+
+/// Compare every saved optimization in `dirs` against the current rules
+pub fn find_outdated(
+    dirs: &[PathBuf],
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<OutdatedReport> {
+    let current = ruleset::hash();
+    let mut scanned = 0;
+    let mut outdated = Vec::new();
+
+    for dir in dirs {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        entries.sort();
+
+        for metadata_path in entries {
+            // Other JSON files in the directory aren't ours
+            let Some(saved) = std::fs::read_to_string(&metadata_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<SavedMetadata>(&content).ok())
+            else {
+                continue;
+            };
+            let original = dir.join(&saved.files.original);
+            let Ok(prompt) = crate::utils::file::read_prompt_file(&original) else {
+                continue;
+            };
+            scanned += 1;
+            if saved.rule_set.as_deref() == Some(current.as_str()) {
+                continue;
+            }
+
+            let mut issues = analyzer::analyze(&prompt, check_categories)?;
+            if let Some(min) = min_confidence {
+                issues.retain(|i| i.confidence >= min);
+            }
+            let now: BTreeSet<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+            // A renamed rule's findings carry over under its new ID
+            let renamed: Vec<String> = saved
+                .issues
+                .iter()
+                .map(|i| deprecated::renamed_to(&i.id).unwrap_or_else(|| i.id.clone()))
+                .collect();
+            let then: BTreeSet<&str> = renamed.iter().map(String::as_str).collect();
+            if now == then {
+                continue;
+            }
+
+            outdated.push(OutdatedPrompt {
+                optimized: dir.join(&saved.files.optimized),
+                metadata: metadata_path,
+                original,
+                copt_version: saved.copt_version,
+                rule_set: saved.rule_set,
+                added: now.difference(&then).map(|s| s.to_string()).collect(),
+                removed: then.difference(&now).map(|s| s.to_string()).collect(),
+            });
+        }
+    }
+
+    Ok(OutdatedReport {
+        rule_set: current,
+        scanned,
+        outdated,
+    })
+}
+
+/// Run `copt outdated` and return the outdated prompts, for `--reoptimize`
+pub fn run(
+    args: &OutdatedArgs,
+    output_dir: &Path,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<Vec<OutdatedPrompt>> {
+    let dirs = if !args.dirs.is_empty() {
+        args.dirs.clone()
+    } else if output_dir.is_dir() {
+        vec![output_dir.to_path_buf()]
+    } else {
+        // Nothing has been auto-saved yet
+        Vec::new()
+    };
+    let report = find_outdated(&dirs, check_categories, min_confidence)?;
+
+    match args.format {
+        OutdatedFormat::Text => print_report(&report, args.reoptimize),
+        OutdatedFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(report.outdated)
+}
+
+fn print_report(report: &OutdatedReport, reoptimizing: bool) {
+    println!();
+    if report.outdated.is_empty() {
+        println!(
+            "  {} All {} saved optimization{} match the current rules ({})",
+            "✓".green(),
+            report.scanned,
+            if report.scanned == 1 { "" } else { "s" },
+            report.rule_set.bright_black()
+        );
+        println!();
+        return;
+    }
+
+    println!(
+        "  {} of {} saved optimization{} would be analyzed differently now ({})",
+        report.outdated.len().to_string().yellow().bold(),
+        report.scanned,
+        if report.scanned == 1 { "" } else { "s" },
+        report.rule_set.bright_black()
+    );
+    println!();
+    for prompt in &report.outdated {
+        let recorded = match (&prompt.copt_version, &prompt.rule_set) {
+            (Some(version), Some(hash)) => format!("v{}, {}", version, hash),
+            (Some(version), None) => format!("v{}", version),
+            (None, _) => "before versioned metadata".to_string(),
+        };
+        println!(
+            "  {} {}",
+            prompt.optimized.display().to_string().white().bold(),
+            format!("({})", recorded).bright_black()
+        );
+        let mut changes: Vec<String> = prompt
+            .added
+            .iter()
+            .map(|id| format!("+{}", id).red().to_string())
+            .collect();
+        changes.extend(
+            prompt
+                .removed
+                .iter()
+                .map(|id| format!("-{}", id).green().to_string()),
+        );
+        println!("    {}", changes.join(" "));
+    }
+    println!();
+    if !reoptimizing {
+        println!(
+            "  {}",
+            "Run `copt outdated --reoptimize` to refresh them.".bright_black()
+        );
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn save(dir: &Path, name: &str, prompt: &str, rule_set: &str, issues: &[&str]) {
+        std::fs::write(dir.join(format!("original_{}.txt", name)), prompt).unwrap();
+        std::fs::write(dir.join(format!("optimized_{}.txt", name)), "<task/>").unwrap();
+        let metadata = serde_json::json!({
+            "files": {
+                "original": format!("original_{}.txt", name),
+                "optimized": format!("optimized_{}.txt", name),
+            },
+            "copt_version": "0.1.0",
+            "rule_set": rule_set,
+            "issues": issues.iter().map(|id| serde_json::json!({ "id": id })).collect::<Vec<_>>(),
+        });
+        std::fs::write(
+            dir.join(format!("optimized_{}.json", name)),
+            metadata.to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_lists_only_prompts_whose_rules_changed() {
+        let dir = TempDir::new().unwrap();
+        let prompt = "Can you write a short poem about autumn leaves?";
+        let ids: Vec<String> = analyzer::analyze(prompt, None)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+
+        // Older rule set, same findings: not outdated
+        save(dir.path(), "same", prompt, "0000000000000000", &ids);
+        // Older rule set, different findings
+        save(
+            dir.path(),
+            "changed",
+            prompt,
+            "0000000000000000",
+            &["ZZZ999"],
+        );
+        // Current rule set: trusted without re-analysis
+        save(dir.path(), "current", prompt, &ruleset::hash(), &["ZZZ999"]);
+        std::fs::write(dir.path().join("notes.json"), "{\"unrelated\": true}").unwrap();
+
+        let report = find_outdated(&[dir.path().to_path_buf()], None, None).unwrap();
+        assert_eq!(report.scanned, 3);
+        assert_eq!(report.outdated.len(), 1);
+        let outdated = &report.outdated[0];
+        assert!(outdated.optimized.ends_with("optimized_changed.txt"));
+        assert_eq!(outdated.removed, ["ZZZ999"]);
+        assert_eq!(
+            outdated.added.len(),
+            ids.iter().collect::<BTreeSet<_>>().len()
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Organization policy (`COPT_POLICY`)
+//!
+//! Admins distribute a `policy.toml` and point `COPT_POLICY` at it. Unlike
+//! the config file, nothing in it can be overridden by user config or
+//! flags: banned providers and Bedrock regions are refused, `--redact` is
+//! always on, models above `max_tier` are rejected, and the required rule
+//! categories run in every analysis. A policy that can't be read stops copt
+//! instead of letting it run unrestricted.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::error::CoptError;
+use crate::llm::models;
+
+/// Environment variable naming the policy file
+pub const POLICY_ENV: &str = "COPT_POLICY";
+
+/// Model tiers, cheapest first; `max_tier` names one of these
+const TIERS: &[&str] = &["haiku", "sonnet", "opus"];
+
+/// Restrictions from `policy.toml`
+///
+/// Unknown keys are rejected so a typo can't silently drop a restriction.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    pub providers: ProvidersPolicy,
+    pub bedrock: BedrockPolicy,
+    pub redact: RedactPolicy,
+    pub models: ModelsPolicy,
+    pub rules: RulesPolicy,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProvidersPolicy {
+    /// Providers that may not be used ("anthropic", "bedrock")
+    pub banned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BedrockPolicy {
+    /// AWS regions requests may not be sent to
+    pub banned_regions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactPolicy {
+    /// Turn on `--redact` for every run
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelsPolicy {
+    /// Most capable model family allowed: haiku, sonnet, or opus
+    pub max_tier: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesPolicy {
+    /// Rule categories checked in every analysis, whatever `--check` says
+    pub required_categories: Vec<String>,
+}
+
+static POLICY: OnceLock<Policy> = OnceLock::new();
+
+/// The policy named by `COPT_POLICY`, read once per process
+///
+/// Without `COPT_POLICY` every check passes.
+pub fn load() -> Result<&'static Policy> {
+    if let Some(policy) = POLICY.get() {
+        return Ok(policy);
+    }
+    let policy = match std::env::var_os(POLICY_ENV) {
+        Some(path) if !path.is_empty() => load_from_path(Path::new(&path))?,
+        _ => Policy::default(),
+    };
+    Ok(POLICY.get_or_init(|| policy))
+}
+
+/// Read and check a policy file
+pub fn load_from_path(path: &Path) -> Result<Policy> {
+    let content = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "Failed to read the organization policy {} (set by {})",
+            path.display(),
+            POLICY_ENV
+        )
+    })?;
+    let policy: Policy = toml::from_str(&content).map_err(|e| {
+        anyhow::Error::new(e).context(CoptError::Config(format!(
+            "Failed to parse the organization policy: {}",
+            path.display()
+        )))
+    })?;
+    policy.validate()?;
+    Ok(policy)
+}
+
+impl Policy {
+    fn validate(&self) -> Result<()> {
+        if let Some(ref tier) = self.models.max_tier {
+            if !TIERS.contains(&tier.as_str()) {
+                return Err(CoptError::Config(format!(
+                    "Invalid max_tier '{}' in the organization policy. Valid options: {}",
+                    tier,
+                    TIERS.join(", ")
+                ))
+                .into());
+            }
+        }
+        for category in &self.rules.required_categories {
+            if !crate::analyzer::CATEGORIES.contains(&category.as_str()) {
+                return Err(CoptError::Config(format!(
+                    "Unknown rule category '{}' in the organization policy. Valid options: {}",
+                    category,
+                    crate::analyzer::CATEGORIES.join(", ")
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse a banned provider
+    pub fn check_provider(&self, provider: &str) -> Result<()> {
+        if self.providers.banned.iter().any(|p| p == provider) {
+            return Err(violation(format!(
+                "The {} provider is not allowed by your organization's policy.",
+                provider
+            )));
+        }
+        Ok(())
+    }
+
+    /// Refuse a banned Bedrock region
+    pub fn check_region(&self, region: &str) -> Result<()> {
+        if self.bedrock.banned_regions.iter().any(|r| r == region) {
+            return Err(violation(format!(
+                "Bedrock region {} is not allowed by your organization's policy. \
+                Pass another --region.",
+                region
+            )));
+        }
+        Ok(())
+    }
+
+    /// Refuse a model above `max_tier`, or one whose tier can't be told
+    pub fn check_model(&self, model: &str) -> Result<()> {
+        let Some(ref max_tier) = self.models.max_tier else {
+            return Ok(());
+        };
+        match tier(model) {
+            Some(tier) if tier <= tier_rank(max_tier) => Ok(()),
+            Some(_) => Err(violation(format!(
+                "Model '{}' is above the {} tier allowed by your organization's policy. \
+                Use {} or a smaller model.",
+                model, max_tier, max_tier
+            ))),
+            None => Err(violation(format!(
+                "Model '{}' isn't in copt's catalog, so its tier can't be checked against \
+                your organization's policy (max tier: {}). Use an alias such as {}.",
+                model, max_tier, max_tier
+            ))),
+        }
+    }
+
+    /// Whether [`check_model`](Self::check_model) accepts `model`
+    pub fn allows_model(&self, model: &str) -> bool {
+        self.check_model(model).is_ok()
+    }
+}
+
+/// Rank of a catalog model's family in [`TIERS`]
+fn tier(model: &str) -> Option<usize> {
+    let info = models::lookup(model)?;
+    TIERS.iter().position(|tier| info.aliases.contains(tier))
+}
+
+fn tier_rank(tier: &str) -> usize {
+    TIERS.iter().position(|t| *t == tier).unwrap_or(0)
+}
+
+fn violation(message: String) -> anyhow::Error {
+    CoptError::PolicyViolation(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Result<Policy> {
+        let policy: Policy = toml::from_str(toml)?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    #[test]
+    fn test_policy_restrictions() {
+        let policy = parse(
+            r#"
+            [providers]
+            banned = ["anthropic"]
+
+            [bedrock]
+            banned_regions = ["us-west-1"]
+
+            [models]
+            max_tier = "sonnet"
+            "#,
+        )
+        .unwrap();
+
+        assert!(policy.check_provider("bedrock").is_ok());
+        let err = policy.check_provider("anthropic").unwrap_err();
+        assert_eq!(
+            crate::error::ErrorInfo::from_error(&err).code,
+            "policy_violation"
+        );
+        assert!(policy.check_region("us-west-1").is_err());
+        assert!(policy.check_region("us-east-1").is_ok());
+
+        assert!(policy.allows_model("haiku"));
+        assert!(policy.allows_model("us.anthropic.claude-sonnet-4-5-20250929-v1:0"));
+        assert!(!policy.allows_model("opus"));
+        assert!(!policy.allows_model("claude-custom-model"));
+        assert!(Policy::default().allows_model("claude-custom-model"));
+    }
+
+    #[test]
+    fn test_policy_rejects_typos() {
+        assert!(parse("[redact]\nrequierd = true").is_err());
+        assert!(parse("[models]\nmax_tier = \"gpt\"").is_err());
+        assert!(parse("[rules]\nrequired_categories = [\"secrets\"]").is_err());
+        assert!(parse("[rules]\nrequired_categories = [\"hygiene\"]").is_ok());
+    }
+}
@@ -0,0 +1,159 @@
+//! Provenance footer on saved optimized prompts
+//!
+//! A saved prompt can turn up in a repository long after its metadata file
+//! is gone. The footer is a single HTML comment at the end of the file
+//! (invisible in rendered Markdown) recording the copt version, provider
+//! and model, when it ran, a hash of the optimizer's meta-prompt, and the
+//! input hash `history.jsonl` uses, so the run can be traced and repeated.
+//! `[output] provenance = false` turns it off. copt strips the footer when
+//! it reads a prompt, so a saved result can be optimized or audited again.
+
+use chrono::Local;
+
+use crate::llm::OPTIMIZER_SYSTEM_PROMPT;
+
+const MARKER: &str = "<!-- copt-provenance ";
+
+/// How a saved prompt was produced
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    pub version: String,
+    pub provider: String,
+    /// Model ID sent to the provider; `None` when no LLM rewrote the prompt
+    /// (offline, or the LLM step failed)
+    pub model: Option<String>,
+    /// RFC 3339
+    pub timestamp: String,
+    /// See [`meta_prompt_hash`]
+    pub meta_prompt: String,
+    /// See [`super::history::prompt_hash`]
+    pub input: String,
+}
+
+impl Provenance {
+    /// Provenance for a run finishing now
+    pub fn new(provider: &str, model: Option<String>, input: &str) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            provider: provider.to_string(),
+            model,
+            timestamp: Local::now().to_rfc3339(),
+            meta_prompt: meta_prompt_hash(),
+            input: super::history::prompt_hash(input),
+        }
+    }
+
+    /// The footer line
+    pub fn footer(&self) -> String {
+        format!(
+            "{}version={} provider={} model={} timestamp={} meta_prompt={} input={} -->",
+            MARKER,
+            self.version,
+            self.provider,
+            self.model.as_deref().unwrap_or("none"),
+            self.timestamp,
+            self.meta_prompt,
+            self.input
+        )
+    }
+
+    /// Read the footer at the end of `text`, if it has one
+    pub fn parse(text: &str) -> Option<Self> {
+        let (_, line) = split(text)?;
+        let fields = line.strip_prefix(MARKER)?.strip_suffix("-->")?;
+        let mut provenance = Self {
+            version: String::new(),
+            provider: String::new(),
+            model: None,
+            timestamp: String::new(),
+            meta_prompt: String::new(),
+            input: String::new(),
+        };
+        for (key, value) in fields.split_whitespace().filter_map(|f| f.split_once('=')) {
+            let value = value.to_string();
+            match key {
+                "version" => provenance.version = value,
+                "provider" => provenance.provider = value,
+                "model" => provenance.model = (value != "none").then_some(value),
+                "timestamp" => provenance.timestamp = value,
+                "meta_prompt" => provenance.meta_prompt = value,
+                "input" => provenance.input = value,
+                _ => {}
+            }
+        }
+        Some(provenance)
+    }
+}
+
+/// Short hash of the optimizer's system prompt, which changes between
+/// releases more often than the version number suggests
+pub fn meta_prompt_hash() -> String {
+    super::rules::sha256_hex(OPTIMIZER_SYSTEM_PROMPT)[..16].to_string()
+}
+
+/// Whether saved prompts get a footer (`[output] provenance`)
+pub fn enabled() -> bool {
+    super::config::load_config()
+        .map(|c| c.output.provenance)
+        .unwrap_or(true)
+}
+
+/// `prompt` followed by a blank line and the footer
+pub fn append(prompt: &str, provenance: &Provenance) -> String {
+    format!("{}\n\n{}\n", prompt.trim_end(), provenance.footer())
+}
+
+/// `text` without a trailing provenance footer
+pub fn strip(text: &str) -> &str {
+    match split(text) {
+        Some((prompt, _)) => prompt.trim_end(),
+        None => text,
+    }
+}
+
+/// The text before the last line and that line, when it is a footer
+fn split(text: &str) -> Option<(&str, &str)> {
+    let trimmed = text.trim_end();
+    let start = trimmed.rfind('\n').map_or(0, |i| i + 1);
+    let line = &trimmed[start..];
+    line.starts_with(MARKER).then(|| (&trimmed[..start], line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_round_trips() {
+        let provenance = Provenance::new(
+            "bedrock",
+            Some("us.anthropic.claude-sonnet-4-5-20250929-v1:0".to_string()),
+            "Could you summarize this report?",
+        );
+        let saved = append("<task>\nSummarize the report.\n</task>", &provenance);
+
+        assert!(
+            saved.starts_with("<task>\nSummarize the report.\n</task>\n\n<!-- copt-provenance ")
+        );
+        assert_eq!(Provenance::parse(&saved), Some(provenance.clone()));
+        assert_eq!(strip(&saved), "<task>\nSummarize the report.\n</task>");
+        assert_eq!(
+            provenance.input,
+            super::super::history::prompt_hash("Could you summarize this report?")
+        );
+
+        let offline = Provenance::new("bedrock", None, "Summarize.");
+        assert!(offline.footer().contains(" model=none "));
+        assert_eq!(
+            Provenance::parse(&append("Summarize.", &offline)),
+            Some(offline)
+        );
+    }
+
+    #[test]
+    fn test_text_without_footer_is_untouched() {
+        let text = "Summarize the report.\n<!-- reviewer note -->\n";
+        assert_eq!(Provenance::parse(text), None);
+        assert_eq!(strip(text), text);
+    }
+}
@@ -0,0 +1,456 @@
+//! Team rule packs (`rules.remote`, `copt rules sync`)
+//!
+//! Fetches the rule pack configured as `rules.remote`, verifies it against
+//! the pinned `rules.remote_sha256`, and caches it so analysis keeps working
+//! offline between syncs. The cached pack is re-fetched automatically once
+//! it is older than `rules.remote_ttl_hours`.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use super::config::{self, RulesConfig};
+use super::inspect::InspectFormat;
+use crate::analyzer::rule_pack::{self, RulePack};
+use crate::analyzer::{deprecated, docs};
+
+#[derive(Args, Debug)]
+pub struct RulesArgs {
+    #[command(subcommand)]
+    pub command: RulesCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RulesCommand {
+    /// Re-fetch the remote rule pack (rules.remote) now
+    Sync,
+    /// List the built-in rule IDs
+    List {
+        /// List retired rule IDs and the rules that replaced them instead
+        #[arg(long)]
+        deprecated: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: InspectFormat,
+    },
+}
+
+/// Metadata stored next to the cached pack
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    sha256: String,
+    /// Unix timestamp of the last successful fetch
+    fetched_at: i64,
+}
+
+/// A resolved rule pack and where it came from
+#[derive(Debug)]
+pub struct ResolvedPack {
+    pub content: String,
+    pub sha256: String,
+    /// Served from the cache rather than freshly fetched
+    pub from_cache: bool,
+}
+
+/// Directory holding the cached remote pack
+fn default_cache_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.cache_dir().join("rules"))
+}
+
+/// Lowercase hex SHA-256 of `content`
+pub fn sha256_hex(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Check `content` against a pinned checksum (`abc…` or `sha256:abc…`)
+fn verify_checksum(content: &str, expected: Option<&str>) -> Result<String> {
+    let actual = sha256_hex(content);
+    if let Some(expected) = expected {
+        let expected = expected.trim();
+        let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+        if !expected.eq_ignore_ascii_case(&actual) {
+            anyhow::bail!(
+                "Rule pack checksum mismatch: expected {}, got {}",
+                expected,
+                actual
+            );
+        }
+    }
+    Ok(actual)
+}
+
+/// Cached pack for `url`, if one exists
+fn read_cache(cache_dir: &Path, url: &str) -> Option<(String, CacheMeta)> {
+    let meta: CacheMeta =
+        serde_json::from_str(&std::fs::read_to_string(cache_dir.join("remote.json")).ok()?).ok()?;
+    if meta.url != url {
+        return None;
+    }
+    let content = std::fs::read_to_string(cache_dir.join("remote.toml")).ok()?;
+    Some((content, meta))
+}
+
+fn write_cache(cache_dir: &Path, url: &str, content: &str, sha256: &str) -> Result<()> {
+    std::fs::create_dir_all(cache_dir)
+        .with_context(|| format!("Failed to create cache directory: {}", cache_dir.display()))?;
+    std::fs::write(cache_dir.join("remote.toml"), content).context("Failed to cache rule pack")?;
+    let meta = CacheMeta {
+        url: url.to_string(),
+        sha256: sha256.to_string(),
+        fetched_at: chrono::Utc::now().timestamp(),
+    };
+    std::fs::write(
+        cache_dir.join("remote.json"),
+        serde_json::to_string_pretty(&meta)?,
+    )
+    .context("Failed to cache rule pack metadata")?;
+    Ok(())
+}
+
+/// Download the pack (`https://…`, `http://…`, or `file://…`)
+async fn fetch(url: &str) -> Result<String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rule pack: {}", path));
+    }
+
+    let response = reqwest::get(url)
+        .await
+        .with_context(|| format!("Failed to fetch rule pack: {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Failed to fetch rule pack: {}", url))?;
+    Ok(response.text().await?)
+}
+
+/// `hours` in seconds; a TTL too large to count never expires
+fn ttl_secs(hours: u64) -> i64 {
+    i64::try_from(hours.saturating_mul(3600)).unwrap_or(i64::MAX)
+}
+
+/// Resolve the configured pack from the cache or the network
+///
+/// A fresh cache entry is used as-is unless `force` is set. When fetching
+/// fails, a cached copy that still matches the pinned checksum is used.
+pub async fn resolve(
+    rules: &RulesConfig,
+    cache_dir: &Path,
+    force: bool,
+) -> Result<Option<ResolvedPack>> {
+    let Some(ref url) = rules.remote else {
+        return Ok(None);
+    };
+    let pinned = rules.remote_sha256.as_deref();
+
+    let cached = read_cache(cache_dir, url)
+        .and_then(|(content, meta)| Some((verify_checksum(&content, pinned).ok()?, content, meta)));
+
+    if let Some((ref sha256, ref content, ref meta)) = cached {
+        let age_secs = chrono::Utc::now().timestamp() - meta.fetched_at;
+        if !force && age_secs < ttl_secs(rules.remote_ttl_hours) {
+            return Ok(Some(ResolvedPack {
+                content: content.clone(),
+                sha256: sha256.clone(),
+                from_cache: true,
+            }));
+        }
+    }
+
+    let fetched = async {
+        let content = fetch(url).await?;
+        let sha256 = verify_checksum(&content, pinned)?;
+        // Never cache a pack that would fail to load
+        RulePack::parse(&content)?;
+        Ok::<_, anyhow::Error>((content, sha256))
+    }
+    .await;
+
+    match (fetched, cached) {
+        (Ok((content, sha256)), _) => {
+            // Sandboxed runs use the download for this run only
+            if !crate::utils::sandbox::enabled() {
+                write_cache(cache_dir, url, &content, &sha256)?;
+            }
+            Ok(Some(ResolvedPack {
+                content,
+                sha256,
+                from_cache: false,
+            }))
+        }
+        (Err(e), Some((sha256, content, _))) if !force => {
+            eprintln!("  {} {:#}; using cached rule pack", "⚠".yellow(), e);
+            Ok(Some(ResolvedPack {
+                content,
+                sha256,
+                from_cache: true,
+            }))
+        }
+        (Err(e), _) => Err(e),
+    }
+}
+
+/// The cached copy of the configured pack, however old, if it still
+/// matches the pinned checksum
+fn cached_pack(rules: &RulesConfig, cache_dir: &Path) -> Option<ResolvedPack> {
+    let url = rules.remote.as_deref()?;
+    let (content, _) = read_cache(cache_dir, url)?;
+    let sha256 = verify_checksum(&content, rules.remote_sha256.as_deref()).ok()?;
+    Some(ResolvedPack {
+        content,
+        sha256,
+        from_cache: true,
+    })
+}
+
+/// Load the configured remote pack and install it for this run
+///
+/// `--offline` runs use the cached pack without refreshing it. Problems are
+/// reported as warnings; analysis continues with the built-in rules only.
+pub async fn install_configured(offline: bool) {
+    let Ok(config) = config::load_config() else {
+        return;
+    };
+    if config.rules.remote.is_none() {
+        return;
+    }
+    let Some(cache_dir) = default_cache_dir() else {
+        return;
+    };
+
+    let resolved = if offline {
+        cached_pack(&config.rules, &cache_dir)
+            .map(Some)
+            .context("not cached yet; run `copt rules sync` while online")
+    } else {
+        resolve(&config.rules, &cache_dir, false).await
+    };
+    let pack = match resolved {
+        Ok(Some(resolved)) => RulePack::parse(&resolved.content),
+        Ok(None) => return,
+        Err(e) => Err(e),
+    };
+
+    match pack {
+        Ok(pack) => {
+            super::suggest::register_custom_suggestions(&pack.suggestions);
+            rule_pack::install(pack);
+        }
+        Err(e) => eprintln!("  {} Rule pack not loaded: {:#}", "⚠".yellow(), e),
+    }
+}
+
+/// Run `copt rules`
+pub async fn run(args: &RulesArgs) -> Result<()> {
+    match args.command {
+        RulesCommand::List { deprecated, format } => {
+            if deprecated {
+                list_deprecated(format)
+            } else {
+                list(format)
+            }
+        }
+        RulesCommand::Sync => {
+            let config = config::load_config()?;
+            let Some(ref url) = config.rules.remote else {
+                anyhow::bail!(
+                    "No remote rule pack configured. Set rules.remote in {}",
+                    config::get_config_path().display()
+                );
+            };
+            let cache_dir =
+                default_cache_dir().context("Could not determine the cache directory")?;
+
+            let resolved = resolve(&config.rules, &cache_dir, true)
+                .await?
+                .context("No remote rule pack configured")?;
+            let pack = RulePack::parse(&resolved.content)?;
+
+            println!(
+                "  {} Synced {} rule{} and {} suggestion{} from {}",
+                "✓".green(),
+                pack.rule_count(),
+                if pack.rule_count() == 1 { "" } else { "s" },
+                pack.suggestions.len(),
+                if pack.suggestions.len() == 1 { "" } else { "s" },
+                url
+            );
+            println!("    {} {}", "sha256".bright_black(), resolved.sha256);
+            Ok(())
+        }
+    }
+}
+
+/// One row of `copt rules list`
+#[derive(Serialize)]
+struct RuleRow {
+    id: &'static str,
+    category: &'static str,
+    docs_url: Option<String>,
+}
+
+fn list(format: InspectFormat) -> Result<()> {
+    let rows: Vec<RuleRow> = docs::builtin_rule_ids()
+        .map(|id| RuleRow {
+            id,
+            category: config::category_from_prefix(&id[..3]).unwrap_or("custom"),
+            docs_url: docs::docs_url(id),
+        })
+        .collect();
+    match format {
+        InspectFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        InspectFormat::Text => {
+            println!();
+            for row in &rows {
+                println!(
+                    "  {}  {:<13} {}",
+                    row.id.bold(),
+                    row.category,
+                    row.docs_url.as_deref().unwrap_or_default().bright_black()
+                );
+            }
+            println!(
+                "
+  {} built-in rules
+",
+                rows.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// One row of `copt rules list --deprecated`
+#[derive(Serialize)]
+struct DeprecatedRow {
+    #[serde(flatten)]
+    deprecation: deprecated::Deprecation,
+    /// The rules the ID stands for today, after any later renames
+    current: Vec<String>,
+}
+
+fn list_deprecated(format: InspectFormat) -> Result<()> {
+    let rows: Vec<DeprecatedRow> = deprecated::DEPRECATED
+        .iter()
+        .map(|deprecation| DeprecatedRow {
+            deprecation: *deprecation,
+            current: deprecated::current_ids(deprecation.id),
+        })
+        .collect();
+    match format {
+        InspectFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        InspectFormat::Text if rows.is_empty() => {
+            println!(
+                "  {} No retired rule IDs; every rule still has the ID it shipped with",
+                "✓".green()
+            );
+        }
+        InspectFormat::Text => {
+            println!();
+            for row in &rows {
+                println!(
+                    "  {} → {}  {}",
+                    row.deprecation.id.bold(),
+                    row.current.join(", "),
+                    format!(
+                        "since {}: {}",
+                        row.deprecation.since, row.deprecation.reason
+                    )
+                    .bright_black()
+                );
+            }
+            println!(
+                "\n  Retired IDs in config files still work, with a warning, as the rules on the right\n"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PACK: &str =
+        "[[rules]]\nid = \"ACME001\"\npattern = \"synergy\"\nmessage = \"Avoid jargon\"\n";
+
+    fn rules_for(url: String, sha256: Option<String>) -> RulesConfig {
+        RulesConfig {
+            remote: Some(url),
+            remote_sha256: sha256,
+            ..RulesConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_ttl_secs_saturates() {
+        assert_eq!(ttl_secs(24), 86_400);
+        assert_eq!(ttl_secs(u64::MAX / 2), i64::MAX);
+        assert_eq!(ttl_secs(u64::MAX), i64::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fetches_and_caches() {
+        let dir = TempDir::new().unwrap();
+        let pack_path = dir.path().join("pack.toml");
+        std::fs::write(&pack_path, PACK).unwrap();
+        let cache = dir.path().join("cache");
+        let rules = rules_for(format!("file://{}", pack_path.display()), None);
+
+        let first = resolve(&rules, &cache, false).await.unwrap().unwrap();
+        assert!(!first.from_cache);
+        assert_eq!(first.sha256, sha256_hex(PACK));
+
+        // Within the TTL the cache wins, even if the source changes
+        std::fs::write(&pack_path, "not = [valid").unwrap();
+        let second = resolve(&rules, &cache, false).await.unwrap().unwrap();
+        assert!(second.from_cache);
+        assert_eq!(second.content, PACK);
+
+        // A forced sync re-fetches and rejects the broken pack
+        assert!(resolve(&rules, &cache, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_pack_never_fetches() {
+        let dir = TempDir::new().unwrap();
+        let pack_path = dir.path().join("pack.toml");
+        let cache = dir.path().join("cache");
+        let rules = rules_for(format!("file://{}", pack_path.display()), None);
+        assert!(cached_pack(&rules, &cache).is_none());
+
+        std::fs::write(&pack_path, PACK).unwrap();
+        resolve(&rules, &cache, false).await.unwrap();
+        std::fs::remove_file(&pack_path).unwrap();
+        let cached = cached_pack(&rules, &cache).unwrap();
+        assert_eq!(cached.content, PACK);
+        assert!(cached.from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_pinning() {
+        let dir = TempDir::new().unwrap();
+        let pack_path = dir.path().join("pack.toml");
+        std::fs::write(&pack_path, PACK).unwrap();
+        let url = format!("file://{}", pack_path.display());
+
+        let pinned = rules_for(url.clone(), Some(format!("sha256:{}", sha256_hex(PACK))));
+        assert!(resolve(&pinned, &dir.path().join("a"), false)
+            .await
+            .unwrap()
+            .is_some());
+
+        let wrong = rules_for(url, Some("0".repeat(64)));
+        let err = resolve(&wrong, &dir.path().join("b"), false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+    }
+}
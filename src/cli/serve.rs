@@ -0,0 +1,373 @@
+//! Scheduled re-audit of a prompt directory (`copt serve`)
+//!
+//! Rules change between releases, so a prompt that passed last month can
+//! score lower today without anyone touching it. `copt serve` re-analyzes
+//! every prompt under a directory on an interval, appends the scores to the
+//! local metrics store (`copt metrics export --by audit`), and reports the
+//! prompts whose score dropped since the previous pass, optionally by
+//! POSTing a JSON summary to a webhook. Analysis is static; no LLM calls.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::lint;
+use super::metrics::{self, AuditRecord};
+use crate::error::CoptError;
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Directory of prompts to re-audit (searched recursively)
+    #[arg(long, value_name = "DIR")]
+    pub audit_dir: PathBuf,
+
+    /// Time between passes, e.g. 30m, 6h, 24h, 7d
+    #[arg(long, value_name = "DURATION", default_value = "24h", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// POST a JSON summary here when a prompt's score drops (has a Slack-compatible `text` field)
+    #[arg(long, value_name = "URL", env = "COPT_WEBHOOK_URL")]
+    pub webhook: Option<String>,
+
+    /// Run one pass and exit, for cron or CI
+    #[arg(long)]
+    pub once: bool,
+}
+
+/// A prompt that scores lower than in the previous pass
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Regression {
+    pub path: String,
+    pub previous_score: u8,
+    pub score: u8,
+    /// Rules firing now that didn't before
+    pub new_rules: Vec<String>,
+}
+
+/// One re-audit pass
+#[derive(Debug, Serialize)]
+pub struct Pass {
+    pub timestamp: String,
+    pub audited: usize,
+    pub average_score: f32,
+    pub regressions: Vec<Regression>,
+}
+
+/// Parse `90s`, `30m`, `24h`, or `7d`
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or("add a unit: s, m, h, or d (e.g. 24h)")?;
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid interval: {}", value))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("unknown unit '{}': use s, m, h, or d", unit)),
+    };
+    if number == 0 {
+        return Err("the interval must be greater than zero".to_string());
+    }
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("interval too long: {}", value))
+}
+
+/// Prompt files under `dir`, skipping hidden files and directories
+fn prompt_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = dir.join("**").join("*");
+    let options = glob::MatchOptions {
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let mut files: Vec<PathBuf> = glob::glob_with(&pattern.to_string_lossy(), options)
+        .with_context(|| format!("Invalid directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|path| path.is_file() && crate::utils::file::is_prompt_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Compare a pass against each prompt's previous result
+pub fn regressions(
+    previous: &HashMap<String, AuditRecord>,
+    current: &[AuditRecord],
+) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|record| {
+            let before = previous.get(&record.path)?;
+            if record.score >= before.score {
+                return None;
+            }
+            let mut new_rules: Vec<String> = Vec::new();
+            for rule in &record.rules {
+                if !before.rules.contains(rule) && !new_rules.contains(rule) {
+                    new_rules.push(rule.clone());
+                }
+            }
+            Some(Regression {
+                path: record.path.clone(),
+                previous_score: before.score,
+                score: record.score,
+                new_rules,
+            })
+        })
+        .collect()
+}
+
+/// Lint `files` one by one, reporting and skipping any that can't be read
+/// (deleted since the listing, binary, too large) so the pass goes on
+fn lint_each(
+    files: &[PathBuf],
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> lint::LintReport {
+    let mut report = lint::LintReport { files: Vec::new() };
+    for file in files {
+        match lint::lint_files(std::slice::from_ref(file), check_categories, min_confidence) {
+            Ok(linted) => report.files.extend(linted.files),
+            Err(e) => eprintln!("  {} Skipped {}: {:#}", "⚠".yellow(), file.display(), e),
+        }
+    }
+    report
+}
+
+/// Analyze every prompt under `dir` and update `previous` to this pass
+fn audit_pass(
+    dir: &Path,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+    previous: &mut HashMap<String, AuditRecord>,
+) -> Result<Pass> {
+    let timestamp = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    let files = prompt_files(dir)?;
+    let report = lint_each(&files, check_categories, min_confidence);
+
+    let records: Vec<AuditRecord> = report
+        .files
+        .iter()
+        .map(|file| AuditRecord {
+            timestamp: timestamp.clone(),
+            path: file.path.clone(),
+            score: file.score,
+            rules: file.issues.iter().map(|i| i.id.clone()).collect(),
+        })
+        .collect();
+    metrics::record_audits(&records);
+
+    let regressions = regressions(previous, &records);
+    for record in records {
+        previous.insert(record.path.clone(), record);
+    }
+
+    Ok(Pass {
+        timestamp,
+        audited: report.files.len(),
+        average_score: report.average_score(),
+        regressions,
+    })
+}
+
+fn print_pass(pass: &Pass, dir: &Path) {
+    let regressed = pass.regressions.len();
+    let status = if regressed == 0 {
+        "no regressions".green().to_string()
+    } else {
+        format!("{} regressed", regressed.to_string().red().bold())
+    };
+    println!(
+        "  {}  {} prompt{} in {}, avg score {:.0}, {}",
+        pass.timestamp.bright_black(),
+        pass.audited,
+        if pass.audited == 1 { "" } else { "s" },
+        dir.display(),
+        pass.average_score,
+        status
+    );
+    for regression in &pass.regressions {
+        println!("    {}", summary_line(regression));
+    }
+}
+
+/// `prompts/a.md 92 → 80 (new: EXP003, STY001)`
+fn summary_line(regression: &Regression) -> String {
+    let mut line = format!(
+        "{} {} → {}",
+        regression.path, regression.previous_score, regression.score
+    );
+    if !regression.new_rules.is_empty() {
+        line.push_str(&format!(" (new: {})", regression.new_rules.join(", ")));
+    }
+    line
+}
+
+/// Webhook body: a Slack-style `text` summary plus the structured pass
+fn webhook_payload(pass: &Pass, dir: &Path) -> serde_json::Value {
+    let mut text = format!(
+        "copt: {} prompt{} in {} regressed",
+        pass.regressions.len(),
+        if pass.regressions.len() == 1 { "" } else { "s" },
+        dir.display()
+    );
+    for regression in &pass.regressions {
+        text.push_str(&format!("\n• {}", summary_line(regression)));
+    }
+    serde_json::json!({
+        "text": text,
+        "directory": dir.display().to_string(),
+        "pass": pass,
+    })
+}
+
+async fn notify(client: &reqwest::Client, url: &str, payload: &serde_json::Value) -> Result<()> {
+    client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach webhook: {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Webhook rejected the notification: {}", url))?;
+    Ok(())
+}
+
+/// Run `copt serve`
+pub async fn run(
+    args: &ServeArgs,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    if !args.audit_dir.is_dir() {
+        return Err(CoptError::Io(format!("Not a directory: {}", args.audit_dir.display())).into());
+    }
+    // Recorded paths are absolute, so `prompts/` and `./prompts` share a trend
+    let dir = std::path::absolute(&args.audit_dir)?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+
+    // Start from the last recorded result of each prompt, so a restart
+    // still catches a drop since the previous run
+    let mut previous: HashMap<String, AuditRecord> = metrics::load_audits()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|record| (record.path.clone(), record))
+        .collect();
+
+    println!();
+    loop {
+        // Neither a failed pass nor a missed notification stops the schedule
+        match audit_pass(&dir, check_categories, min_confidence, &mut previous) {
+            Ok(pass) => {
+                print_pass(&pass, &dir);
+                if let (Some(url), false) = (&args.webhook, pass.regressions.is_empty()) {
+                    if let Err(e) = notify(&client, url, &webhook_payload(&pass, &dir)).await {
+                        eprintln!("  {} {:#}", "⚠".yellow(), e);
+                    }
+                }
+            }
+            Err(e) if args.once => return Err(e),
+            Err(e) => eprintln!("  {} Audit pass failed: {:#}", "⚠".yellow(), e),
+        }
+
+        if args.once {
+            return Ok(());
+        }
+        tokio::time::sleep(args.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(path: &str, score: u8, rules: &[&str]) -> AuditRecord {
+        AuditRecord {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            path: path.to_string(),
+            score,
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_interval("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_interval("24h"), Ok(Duration::from_secs(86400)));
+        assert_eq!(parse_interval("7d"), Ok(Duration::from_secs(604800)));
+        assert!(parse_interval("24").is_err());
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("1w").is_err());
+        assert!(parse_interval("18446744073709551615d").is_err());
+    }
+
+    #[test]
+    fn test_regressions_only_for_lower_scores() {
+        let previous: HashMap<String, AuditRecord> = [
+            record("a.md", 90, &["EXP003"]),
+            record("b.md", 80, &["STY001"]),
+        ]
+        .into_iter()
+        .map(|r| (r.path.clone(), r))
+        .collect();
+        let current = [
+            record("a.md", 75, &["EXP003", "STY002", "STY002"]),
+            record("b.md", 85, &[]),
+            record("c.md", 40, &["EXP001"]),
+        ];
+
+        assert_eq!(
+            regressions(&previous, &current),
+            [Regression {
+                path: "a.md".to_string(),
+                previous_score: 90,
+                score: 75,
+                new_rules: vec!["STY002".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_prompt_files_skip_hidden_and_non_prompts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("team/.drafts")).unwrap();
+        std::fs::write(dir.path().join("a.md"), "Summarize.").unwrap();
+        std::fs::write(dir.path().join("team/b.txt"), "Translate.").unwrap();
+        std::fs::write(dir.path().join("team/.drafts/c.md"), "Draft.").unwrap();
+        std::fs::write(dir.path().join("logo.png"), [0u8; 4]).unwrap();
+
+        let files = prompt_files(dir.path()).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(names, [PathBuf::from("a.md"), PathBuf::from("team/b.txt")]);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_skipped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let readable = dir.path().join("a.md");
+        std::fs::write(&readable, "Summarize.").unwrap();
+        let binary = dir.path().join("b.md");
+        std::fs::write(&binary, [0xffu8, 0xfe, 0x00, 0x01]).unwrap();
+        let deleted = dir.path().join("c.md");
+
+        let report = lint_each(&[readable.clone(), binary, deleted], None, None);
+        let paths: Vec<_> = report.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, [readable.display().to_string()]);
+    }
+}
@@ -0,0 +1,280 @@
+//! Near-duplicate search over past optimizations (`copt similar`)
+//!
+//! Compares a new prompt against the MinHash signatures in the run history
+//! and lists earlier LLM optimizations of similar prompts, so a small edit
+//! to an already optimized prompt can start from the saved result instead of
+//! paying for another rewrite. `--reuse` prints the closest saved
+//! optimization; on a terminal copt offers to.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use super::history::{self, HistoryEntry};
+use crate::utils::text::{minhash, minhash_similarity};
+
+#[derive(Args, Debug)]
+pub struct SimilarArgs {
+    /// Prompt file to compare (reads stdin when omitted)
+    #[arg(short, long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Lowest similarity to list, in percent of shared words
+    #[arg(long, value_name = "PERCENT", default_value = "60", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub min_similarity: u8,
+
+    /// Most matches to list
+    #[arg(long, value_name = "N", default_value = "5")]
+    pub limit: usize,
+
+    /// Print the closest match's saved optimization instead of the list
+    #[arg(long)]
+    pub reuse: bool,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: SimilarFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SimilarFormat {
+    Text,
+    Json,
+}
+
+/// An earlier optimization of a similar prompt
+#[derive(Debug, Serialize)]
+pub struct SimilarRun {
+    /// Estimated share of words in common (0.0-1.0); 1.0 for the same prompt
+    pub similarity: f64,
+    /// Same prompt, up to whitespace
+    pub exact: bool,
+    pub timestamp: String,
+    pub provider: String,
+    pub model: String,
+    /// Saved optimized prompt, if the run saved one
+    pub output: Option<String>,
+    /// Whether `output` still exists
+    pub saved: bool,
+}
+
+/// Runs of prompts at least `min_similarity` alike, most similar first
+///
+/// Each distinct prompt is listed once, by its latest matching run. Entries written
+/// before signatures were recorded only match the exact same prompt.
+pub fn find_similar(
+    entries: &[HistoryEntry],
+    prompt: &str,
+    min_similarity: f64,
+) -> Vec<SimilarRun> {
+    let hash = history::prompt_hash(prompt);
+    let signature = minhash(prompt);
+    let mut seen = std::collections::HashSet::new();
+
+    let mut runs: Vec<SimilarRun> = entries
+        .iter()
+        .rev()
+        .filter_map(|entry| {
+            let exact = entry.prompt_hash == hash;
+            let similarity = if exact {
+                1.0
+            } else {
+                minhash_similarity(&signature, &entry.signature)
+            };
+            (similarity >= min_similarity).then_some((entry, similarity, exact))
+        })
+        .filter(|(entry, ..)| seen.insert(entry.prompt_hash.as_str()))
+        .map(|(entry, similarity, exact)| SimilarRun {
+            similarity,
+            exact,
+            timestamp: entry.timestamp.clone(),
+            provider: entry.provider.clone(),
+            model: entry.model.clone(),
+            saved: entry
+                .output
+                .as_deref()
+                .is_some_and(|p| Path::new(p).is_file()),
+            output: entry.output.clone(),
+        })
+        .collect();
+    // Stable, so equally similar runs stay newest first
+    runs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    runs
+}
+
+/// Run `copt similar`
+pub fn run(args: &SimilarArgs, max_bytes: u64) -> Result<()> {
+    let prompt = match args.file {
+        Some(ref path) => crate::utils::input::read_file_blocking(path, max_bytes)?,
+        None if !std::io::stdin().is_terminal() => {
+            crate::utils::input::read_limited(std::io::stdin().lock(), max_bytes, "stdin")?
+        }
+        None => anyhow::bail!("No prompt to compare: pass -f FILE or pipe it on stdin"),
+    };
+
+    let mut runs = find_similar(
+        &history::entries()?,
+        &prompt,
+        f64::from(args.min_similarity) / 100.0,
+    );
+    runs.truncate(args.limit);
+
+    if args.reuse {
+        let run = runs.iter().find(|run| run.saved).with_context(|| {
+            format!(
+                "No saved optimization of a prompt at least {}% similar",
+                args.min_similarity
+            )
+        })?;
+        print!("{}", read_saved(run)?);
+        return Ok(());
+    }
+
+    match args.format {
+        SimilarFormat::Json => println!("{}", serde_json::to_string_pretty(&runs)?),
+        SimilarFormat::Text => {
+            print_runs(&runs, args.min_similarity);
+            offer_reuse(&runs)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_saved(run: &SimilarRun) -> Result<String> {
+    let path = run.output.as_deref().unwrap_or_default();
+    std::fs::read_to_string(path)
+        .map(|saved| match super::provenance::strip(&saved) {
+            prompt if prompt.len() < saved.len() => format!("{}\n", prompt),
+            _ => saved,
+        })
+        .with_context(|| format!("Failed to read saved optimization: {}", path))
+}
+
+fn print_runs(runs: &[SimilarRun], min_similarity: u8) {
+    println!();
+    if runs.is_empty() {
+        println!(
+            "  {}",
+            format!(
+                "No earlier optimization of a prompt at least {}% similar",
+                min_similarity
+            )
+            .bright_black()
+        );
+        println!();
+        return;
+    }
+
+    for run in runs {
+        let when = chrono::DateTime::parse_from_rfc3339(&run.timestamp)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| run.timestamp.clone());
+        let percent = format!("{:>3.0}%", run.similarity * 100.0);
+        let percent = if run.exact {
+            percent.green().bold()
+        } else {
+            percent.yellow()
+        };
+        let output = match run.output {
+            Some(ref path) if run.saved => path.normal(),
+            Some(ref path) => format!("{} (missing)", path).bright_black(),
+            None => "not saved".bright_black(),
+        };
+        println!(
+            "  {}  {}  {}  {}",
+            percent,
+            when.bright_black(),
+            format!("{}/{}", run.provider, run.model).cyan(),
+            output
+        );
+    }
+    println!();
+    if runs.iter().any(|run| run.saved) {
+        println!(
+            "  {} copt similar --reuse -f FILE > optimized.md, then adapt the copy",
+            "Reuse:".bright_black()
+        );
+        println!();
+    }
+}
+
+/// On a terminal, offer to print one of the saved optimizations
+fn offer_reuse(runs: &[SimilarRun]) -> Result<()> {
+    let saved: Vec<&SimilarRun> = runs.iter().filter(|run| run.saved).collect();
+    if saved.is_empty()
+        || !super::ask::enabled()
+        || !std::io::stdin().is_terminal()
+        || !std::io::stdout().is_terminal()
+    {
+        return Ok(());
+    }
+
+    let mut items: Vec<String> = saved
+        .iter()
+        .map(|run| {
+            format!(
+                "Reuse {} ({:.0}% similar)",
+                run.output.as_deref().unwrap_or_default(),
+                run.similarity * 100.0
+            )
+        })
+        .collect();
+    items.push("Optimize from scratch".to_string());
+    let items: Vec<&str> = items.iter().map(String::as_str).collect();
+
+    if let Some(choice) = super::ask::select("Start from an earlier optimization?", &items)? {
+        if let Some(run) = saved.get(choice) {
+            println!("\n{}", read_saved(run)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_similar_ranks_and_dedupes() {
+        let base = "Summarize the incident report for the on-call engineer in three bullet points covering impact, root cause, and next step.";
+        let edited = "Summarize the incident report for the on-call engineer in three bullet points covering impact, root cause, and owner.";
+        let mut older = HistoryEntry::new(base, "bedrock", "sonnet", "", None);
+        older.timestamp = "2026-01-01T00:00:00+00:00".to_string();
+        let mut legacy = HistoryEntry::new(edited, "bedrock", "sonnet", "", None);
+        legacy.signature.clear();
+        let entries = vec![
+            older,
+            HistoryEntry::new(edited, "anthropic", "opus", "", None),
+            HistoryEntry::new(base, "bedrock", "haiku", "", None),
+            HistoryEntry::new(
+                "Write a haiku about autumn leaves.",
+                "bedrock",
+                "sonnet",
+                "",
+                None,
+            ),
+            legacy,
+        ];
+
+        let runs = find_similar(&entries, base, 0.6);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].exact);
+        assert_eq!(runs[0].model, "haiku");
+        assert!(!runs[1].exact);
+        assert!(runs[1].similarity >= 0.6 && runs[1].similarity < 1.0);
+
+        // The edited prompt's newest run has no signature; it still matches
+        // that exact prompt, and hides the older run of it
+        let runs = find_similar(&entries, edited, 0.0);
+        assert!(runs[0].exact);
+        assert_eq!(runs[0].provider, "bedrock");
+        assert!(runs.iter().all(|run| run.model != "opus"));
+    }
+}
@@ -0,0 +1,393 @@
+//! Structural outline of a long prompt (`copt skeleton`)
+//!
+//! A 5,000-token system prompt grows one paragraph at a time, and nobody
+//! can say which part got big. The skeleton nests the prompt's XML tags and
+//! markdown headings into a tree and gives each node its line range, the
+//! instructions it contains, and its share of the tokens as a bar, so the
+//! bloat is visible before anything is optimized.
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use super::inspect::{code_blocks, in_code, CodeBlock, InspectFormat};
+
+#[derive(Args, Debug)]
+pub struct SkeletonArgs {
+    /// Prompt file to outline (reads stdin when omitted)
+    #[arg(short, long, value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// Show nodes down to this nesting depth (1 = top level only)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..))]
+    pub depth: Option<u8>,
+
+    /// Report format: text, json
+    #[arg(long, value_enum, default_value = "text", hide_default_value = true)]
+    pub format: InspectFormat,
+}
+
+/// What opened a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    XmlTag,
+    Heading,
+}
+
+/// An XML tag or heading, with everything nested under it
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    pub kind: NodeKind,
+    /// Tag name or heading text
+    pub name: String,
+    /// First line (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed, inclusive)
+    pub end_line: usize,
+    /// Instructions in the node, its children's included
+    pub instructions: usize,
+    pub tokens: usize,
+    /// Share of the prompt's tokens (0.0-1.0)
+    pub share: f64,
+    pub children: Vec<Node>,
+    #[serde(skip)]
+    heading_level: usize,
+}
+
+/// The outline of a prompt
+#[derive(Debug, Serialize)]
+pub struct Skeleton {
+    pub lines: usize,
+    pub tokens: usize,
+    pub instructions: usize,
+    pub nodes: Vec<Node>,
+    /// Tokens outside every top-level node
+    pub untitled_tokens: usize,
+}
+
+/// A tag opening at the start of a line; self-closing tags aren't sections
+static OPEN_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*<([A-Za-z_][\w.-]*)(?:\s[^<>]*)?>").unwrap());
+
+static CLOSE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"</([A-Za-z_][\w.-]*)>\s*$").unwrap());
+
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s{0,3}(#{1,6})\s+(.+?)[\s#]*$").unwrap());
+
+static LIST_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:[-*+•]|\d+[.)])\s+").unwrap());
+
+/// A sentence that tells the model what to do: an imperative opening or a
+/// modal ("you should", "never")
+static INSTRUCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:please\s+)?(?:always|never|do not|don't|avoid|use|write|keep|include|make|ensure|return|respond|reply|answer|list|explain|check|read|ask|follow|prefer|call|run|give|provide|add|remove|format|summarize|only|be|focus|limit|cite|state|describe|output|start|begin|end|stop|treat|refuse|decline|escalate|search|verify|mention|consider|note|remember)\b|\b(?:must|should|need to|have to|always|never)\b",
+    )
+    .unwrap()
+});
+
+/// Outline `prompt`
+pub fn skeleton(prompt: &str) -> Skeleton {
+    let lines: Vec<&str> = prompt.lines().collect();
+    let blocks = code_blocks(prompt);
+
+    let mut roots: Vec<Node> = Vec::new();
+    let mut open: Vec<Node> = Vec::new();
+    // Close the innermost node at `end_line` and attach it to its parent;
+    // blank lines before the next heading aren't part of a section
+    let close = |open: &mut Vec<Node>, roots: &mut Vec<Node>, mut end_line: usize| {
+        let mut node = open.pop().expect("an open node");
+        while end_line > node.start_line && lines[end_line - 1].trim().is_empty() {
+            end_line -= 1;
+        }
+        node.end_line = end_line.max(node.start_line);
+        match open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    };
+
+    for (idx, line) in lines.iter().enumerate() {
+        let number = idx + 1;
+        if in_code(&blocks, number) {
+            continue;
+        }
+
+        if let Some(caps) = HEADING.captures(line) {
+            let level = caps[1].len();
+            // A heading ends the headings at its level or deeper, up to the enclosing tag
+            while open
+                .last()
+                .is_some_and(|n| n.kind == NodeKind::Heading && n.heading_level >= level)
+            {
+                close(&mut open, &mut roots, number - 1);
+            }
+            open.push(node(NodeKind::Heading, &caps[2], number, level));
+            continue;
+        }
+
+        if let Some(caps) = OPEN_TAG.captures(line) {
+            let name = caps[1].to_string();
+            let closes_here = line.contains(&format!("</{}>", name));
+            if !line.trim_end().ends_with("/>") {
+                open.push(node(NodeKind::XmlTag, &name, number, 0));
+                if closes_here {
+                    close(&mut open, &mut roots, number);
+                }
+                continue;
+            }
+        }
+
+        if let Some(caps) = CLOSE_TAG.captures(line) {
+            let name = &caps[1];
+            let is_open = open
+                .iter()
+                .any(|n| n.kind == NodeKind::XmlTag && n.name == name);
+            if is_open {
+                // Headings inside the tag end with it
+                while open.last().is_some_and(|n| n.name != name) {
+                    close(&mut open, &mut roots, number - 1);
+                }
+                close(&mut open, &mut roots, number);
+            }
+        }
+    }
+    // Unclosed tags and trailing headings run to the end
+    while !open.is_empty() {
+        close(&mut open, &mut roots, lines.len());
+    }
+
+    let tokens = crate::utils::count_tokens(prompt);
+    for node in &mut roots {
+        measure(node, &lines, &blocks, tokens);
+    }
+    let covered: usize = roots.iter().map(|n| n.tokens).sum();
+    Skeleton {
+        lines: lines.len(),
+        tokens,
+        instructions: count_instructions(&lines, 1, lines.len(), &blocks),
+        untitled_tokens: tokens.saturating_sub(covered),
+        nodes: roots,
+    }
+}
+
+fn node(kind: NodeKind, name: &str, start_line: usize, heading_level: usize) -> Node {
+    Node {
+        kind,
+        name: name.trim().to_string(),
+        start_line,
+        end_line: start_line,
+        instructions: 0,
+        tokens: 0,
+        share: 0.0,
+        children: Vec::new(),
+        heading_level,
+    }
+}
+
+/// Fill in the size of `node` and its children
+fn measure(node: &mut Node, lines: &[&str], blocks: &[CodeBlock], total: usize) {
+    let text = lines[node.start_line - 1..node.end_line].join("\n");
+    node.tokens = crate::utils::count_tokens(&text);
+    node.share = if total == 0 {
+        0.0
+    } else {
+        node.tokens as f64 / total as f64
+    };
+    node.instructions = count_instructions(lines, node.start_line, node.end_line, blocks);
+    for child in &mut node.children {
+        measure(child, lines, blocks, total);
+    }
+}
+
+/// Instructions on lines `start..=end` (1-indexed), outside code and markup
+fn count_instructions(lines: &[&str], start: usize, end: usize, blocks: &[CodeBlock]) -> usize {
+    (start..=end)
+        .filter(|&number| !in_code(blocks, number))
+        .map(|number| lines[number - 1])
+        .filter(|line| !HEADING.is_match(line))
+        .map(|line| {
+            let text = CLOSE_TAG.replace(line, "");
+            let text = OPEN_TAG.replace(&text, "");
+            let text = LIST_MARKER.replace(&text, "");
+            text.split_inclusive(['.', '!', '?', ';'])
+                .map(str::trim)
+                .filter(|sentence| INSTRUCTION.is_match(sentence))
+                .count()
+        })
+        .sum()
+}
+
+/// Run `copt skeleton`
+pub fn run(args: &SkeletonArgs, max_bytes: u64) -> Result<()> {
+    let prompt = match args.file {
+        Some(ref path) => crate::utils::input::read_file_blocking(path, max_bytes)?,
+        None if !std::io::stdin().is_terminal() => {
+            crate::utils::input::read_limited(std::io::stdin().lock(), max_bytes, "stdin")?
+        }
+        None => anyhow::bail!("No prompt to outline: pass -f FILE or pipe it on stdin"),
+    };
+
+    let mut skeleton = skeleton(&prompt);
+    if let Some(depth) = args.depth {
+        prune(&mut skeleton.nodes, depth as usize);
+    }
+    match args.format {
+        InspectFormat::Text => print_skeleton(&skeleton, args.file.as_deref()),
+        InspectFormat::Json => println!("{}", serde_json::to_string_pretty(&skeleton)?),
+    }
+    Ok(())
+}
+
+/// Drop nodes nested deeper than `depth`
+fn prune(nodes: &mut [Node], depth: usize) {
+    for node in nodes {
+        if depth <= 1 {
+            node.children.clear();
+        } else {
+            prune(&mut node.children, depth - 1);
+        }
+    }
+}
+
+const BAR_WIDTH: usize = 20;
+
+fn print_skeleton(skeleton: &Skeleton, file: Option<&std::path::Path>) {
+    let name = file.map_or("stdin".to_string(), |f| f.display().to_string());
+    println!();
+    println!(
+        "  {} {}",
+        name.bold(),
+        format!(
+            "· {} lines · ~{} tokens · {} instructions",
+            skeleton.lines, skeleton.tokens, skeleton.instructions
+        )
+        .bright_black()
+    );
+    println!();
+    if skeleton.nodes.is_empty() {
+        println!(
+            "  {}",
+            "No XML tags or headings; the prompt is one block".bright_black()
+        );
+        println!();
+        return;
+    }
+
+    let mut rows = Vec::new();
+    flatten(&skeleton.nodes, 0, &mut rows);
+    let label_width = rows
+        .iter()
+        .map(|(depth, node)| depth * 2 + label(node).chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(40);
+    for (depth, node) in rows {
+        let label = format!("{}{}", "  ".repeat(depth), label(node));
+        let label: String = if label.chars().count() > label_width {
+            let cut: String = label.chars().take(label_width - 1).collect();
+            format!("{cut}…")
+        } else {
+            label
+        };
+        let filled = (node.share * BAR_WIDTH as f64).round() as usize;
+        println!(
+            "  {:<width$}  {:>9}  {:>4} instr  {:>6} tok  {}{}  {:>3.0}%",
+            label,
+            format!("L{}-{}", node.start_line, node.end_line).bright_black(),
+            node.instructions,
+            node.tokens,
+            "█".repeat(filled).cyan(),
+            "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)).bright_black(),
+            node.share * 100.0,
+            width = label_width,
+        );
+    }
+    if skeleton.untitled_tokens > 0 {
+        println!();
+        println!(
+            "  {}",
+            format!(
+                "~{} tokens sit outside any tag or heading",
+                skeleton.untitled_tokens
+            )
+            .bright_black()
+        );
+    }
+    println!();
+}
+
+fn label(node: &Node) -> String {
+    match node.kind {
+        NodeKind::XmlTag => format!("<{}>", node.name),
+        NodeKind::Heading => node.name.clone(),
+    }
+}
+
+fn flatten<'a>(nodes: &'a [Node], depth: usize, rows: &mut Vec<(usize, &'a Node)>) {
+    for node in nodes {
+        rows.push((depth, node));
+        flatten(&node.children, depth + 1, rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton_nests_tags_and_headings() {
+        let prompt = "You are a support agent.\n\
+            <rules>\n\
+            # Tone\n\
+            Be friendly. Never promise refunds.\n\
+            ## Escalation\n\
+            - Escalate legal questions.\n\
+            # Tools\n\
+            Use the `lookup_order` tool first.\n\
+            </rules>\n\
+            <example>Customer: where is my order?</example>\n\
+            ```\n\
+            # not a heading\n\
+            ```\n\
+            Reply in two sentences.";
+        let skeleton = skeleton(prompt);
+
+        assert_eq!(skeleton.nodes.len(), 2);
+        let rules = &skeleton.nodes[0];
+        assert_eq!(
+            (rules.name.as_str(), rules.kind),
+            ("rules", NodeKind::XmlTag)
+        );
+        assert_eq!((rules.start_line, rules.end_line), (2, 9));
+        let children: Vec<&str> = rules.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(children, ["Tone", "Tools"]);
+        let tone = &rules.children[0];
+        assert_eq!((tone.start_line, tone.end_line), (3, 6));
+        assert_eq!(tone.children[0].name, "Escalation");
+        assert_eq!(tone.instructions, 3);
+        assert_eq!(rules.children[1].end_line, 8);
+        assert_eq!(rules.instructions, 4);
+
+        let example = &skeleton.nodes[1];
+        assert_eq!((example.start_line, example.end_line), (10, 10));
+        assert_eq!(skeleton.instructions, 5);
+        assert!(skeleton.untitled_tokens > 0);
+        assert!(rules.share > example.share && rules.share < 1.0);
+    }
+
+    #[test]
+    fn test_prune_limits_depth() {
+        let mut skeleton = skeleton("<a>\n# One\n## Two\n</a>");
+        prune(&mut skeleton.nodes, 2);
+        assert_eq!(skeleton.nodes[0].children.len(), 1);
+        assert!(skeleton.nodes[0].children[0].children.is_empty());
+    }
+}
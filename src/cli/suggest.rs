@@ -1,12 +1,14 @@
 //! Interactive suggestion module for vague prompt improvement
 //!
-//! When prompts trigger EXP005 (role-only) or EXP006 (open-ended),
-//! this module offers interactive suggestions to improve them.
+//! When prompts trigger EXP005 (role-only), EXP006 (open-ended), or
+//! LHT001/LHT002 (long-horizon tasks without state handling), this module
+//! offers suggestions to improve them, interactively or by ID.
 
+use crate::analyzer::rule_pack::CustomSuggestion;
 use crate::analyzer::Issue;
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
+use std::sync::OnceLock;
 
 /// Suggestion templates for improving vague prompts
 #[derive(Debug, Clone)]
@@ -120,9 +122,103 @@ Maintain a [professional/friendly/casual] tone. Be:
     },
 ];
 
+/// Available suggestions for long-horizon tasks (LHT001/LHT002)
+pub const LONG_HORIZON_SUGGESTIONS: &[Suggestion] = &[Suggestion {
+    id: "state_management",
+    label: "State management scaffolding",
+    description: "Progress file, git checkpoints, and context-budget handling",
+    template: r#"
+<state_management>
+This task spans many steps and may outlive a single context window.
+- Track progress in a progress.txt file: what is done, what is in progress, and what is next
+- Record structured state (e.g. test results, remaining items) in a JSON file such as tests.json
+- Commit to git after each completed component so work can be resumed from a known checkpoint
+- Work incrementally, finishing one component before starting the next
+- As your context budget runs low, save progress and state to these files before continuing
+- When starting fresh, read progress.txt, the state file, and recent git history before doing anything else
+</state_management>"#,
+}];
+
+/// Suggestion templates from the team rule pack (`rules.remote`)
+static CUSTOM_SUGGESTIONS: OnceLock<Vec<Suggestion>> = OnceLock::new();
+
+/// Make rule-pack suggestions available by ID for the rest of the process
+///
+/// Only the first registration takes effect. The strings are leaked so pack
+/// templates can be used wherever the built-in `'static` ones are.
+pub fn register_custom_suggestions(suggestions: &[CustomSuggestion]) {
+    let leak = |s: &str| -> &'static str { Box::leak(s.to_string().into_boxed_str()) };
+    let _ = CUSTOM_SUGGESTIONS.set(
+        suggestions
+            .iter()
+            .map(|s| Suggestion {
+                id: leak(&s.id),
+                label: leak(&s.label),
+                description: leak(&s.description),
+                template: leak(&format!("\n{}", s.template.trim())),
+            })
+            .collect(),
+    );
+}
+
+/// Built-in and rule-pack suggestions
+fn all_suggestions() -> impl Iterator<Item = &'static Suggestion> {
+    ROLE_SUGGESTIONS
+        .iter()
+        .chain(OPENENDED_SUGGESTIONS)
+        .chain(LONG_HORIZON_SUGGESTIONS)
+        .chain(CUSTOM_SUGGESTIONS.get().into_iter().flatten())
+}
+
+/// Issue IDs that have suggestion templates
+pub const SUGGESTION_TRIGGERS: &[&str] = &["EXP005", "EXP006", "LHT001", "LHT002"];
+
 /// Check if issues warrant interactive suggestions
 pub fn should_suggest(issues: &[Issue]) -> bool {
-    issues.iter().any(|i| i.id == "EXP005" || i.id == "EXP006")
+    issues
+        .iter()
+        .any(|i| SUGGESTION_TRIGGERS.contains(&i.id.as_str()))
+}
+
+/// Look up a suggestion by ID across all template sets
+pub fn find_suggestion(id: &str) -> Option<&'static Suggestion> {
+    all_suggestions().find(|s| s.id == id)
+}
+
+/// Append suggestion templates to a prompt
+pub fn apply_suggestions(prompt: &str, suggestions: &[&Suggestion]) -> String {
+    if suggestions.is_empty() {
+        return prompt.to_string();
+    }
+
+    let mut enhanced = prompt.trim().to_string();
+    enhanced.push('\n');
+
+    for suggestion in suggestions {
+        enhanced.push_str(suggestion.template);
+        enhanced.push('\n');
+    }
+
+    enhanced
+}
+
+/// Append suggestion templates selected by ID (non-interactive `--suggest=ID,...`)
+pub fn apply_suggestions_by_id(prompt: &str, ids: &[String]) -> Result<String> {
+    let suggestions = ids
+        .iter()
+        .map(|id| {
+            find_suggestion(id).ok_or_else(|| {
+                let known: Vec<_> = all_suggestions().map(|s| s.id).collect();
+                anyhow::anyhow!(
+                    "Unknown suggestion '{}'. Available: {}",
+                    id,
+                    known.join(", ")
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(apply_suggestions(prompt, &suggestions))
 }
 
 /// Get relevant suggestions based on detected issues
@@ -131,6 +227,7 @@ pub fn get_suggestions_for_issues(issues: &[Issue]) -> Vec<&'static Suggestion>
 
     let has_exp005 = issues.iter().any(|i| i.id == "EXP005");
     let has_exp006 = issues.iter().any(|i| i.id == "EXP006");
+    let has_lht = issues.iter().any(|i| i.id == "LHT001" || i.id == "LHT002");
 
     if has_exp005 {
         suggestions.extend(ROLE_SUGGESTIONS.iter());
@@ -140,6 +237,10 @@ pub fn get_suggestions_for_issues(issues: &[Issue]) -> Vec<&'static Suggestion>
         suggestions.extend(OPENENDED_SUGGESTIONS.iter());
     }
 
+    if has_lht {
+        suggestions.extend(LONG_HORIZON_SUGGESTIONS.iter());
+    }
+
     // Deduplicate by id (in case of overlap)
     suggestions.sort_by_key(|s| s.id);
     suggestions.dedup_by_key(|s| s.id);
@@ -157,18 +258,20 @@ pub fn run_interactive_suggestions(
         return Ok(None);
     }
 
+    let heading = if issues.iter().any(|i| i.id == "EXP005" || i.id == "EXP006") {
+        "Vague prompt detected"
+    } else {
+        "Long-horizon task without state management"
+    };
+
     println!();
-    println!(
-        "  {}  {}",
-        "⚠".yellow(),
-        "Vague prompt detected".yellow().bold()
-    );
+    println!("  {}  {}", "⚠".yellow(), heading.yellow().bold());
     println!();
 
     // Show which issues were detected
     for issue in issues
         .iter()
-        .filter(|i| i.id == "EXP005" || i.id == "EXP006")
+        .filter(|i| SUGGESTION_TRIGGERS.contains(&i.id.as_str()))
     {
         println!(
             "     {} {}: {}",
@@ -187,10 +290,10 @@ pub fn run_interactive_suggestions(
     println!();
 
     // Ask if user wants suggestions
-    let wants_suggestions = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Would you like to add specific improvements to this prompt?")
-        .default(true)
-        .interact()?;
+    let wants_suggestions = super::ask::confirm(
+        "Would you like to add specific improvements to this prompt?",
+        true,
+    )?;
 
     if !wants_suggestions {
         return Ok(None);
@@ -222,10 +325,7 @@ pub fn run_interactive_suggestions(
     println!();
 
     // Multi-select dialog
-    let selected_indices = MultiSelect::with_theme(&ColorfulTheme::default())
-        .items(&items)
-        .defaults(&vec![false; items.len()])
-        .interact()?;
+    let selected_indices = super::ask::multi_select(&items)?;
 
     if selected_indices.is_empty() {
         println!(
@@ -237,14 +337,8 @@ pub fn run_interactive_suggestions(
     }
 
     // Build enhanced prompt
-    let mut enhanced = original_prompt.trim().to_string();
-    enhanced.push('\n');
-
-    for idx in &selected_indices {
-        let suggestion = suggestions[*idx];
-        enhanced.push_str(suggestion.template);
-        enhanced.push('\n');
-    }
+    let selected: Vec<&Suggestion> = selected_indices.iter().map(|&i| suggestions[i]).collect();
+    let enhanced = apply_suggestions(original_prompt, &selected);
 
     // Show preview
     println!();
@@ -264,10 +358,7 @@ pub fn run_interactive_suggestions(
     println!();
 
     // Confirm
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt("Apply these improvements?")
-        .default(true)
-        .interact()?;
+    let confirm = super::ask::confirm("Apply these improvements?", true)?;
 
     if confirm {
         println!(
@@ -298,14 +389,20 @@ pub fn print_suggestions(issues: &[Issue]) {
     println!();
 
     for suggestion in suggestions {
-        println!("     {} {}", "•".cyan(), suggestion.label.white());
+        println!(
+            "     {} {} {}",
+            "•".cyan(),
+            suggestion.label.white(),
+            format!("({})", suggestion.id).bright_black()
+        );
         println!("       {}", suggestion.description.bright_black());
     }
 
     println!();
     println!(
         "     {}",
-        "Run with --suggest to interactively add these improvements.".bright_black()
+        "Run with --suggest to pick interactively, or --suggest=<id,...> to add them directly."
+            .bright_black()
     );
 }
 
@@ -321,6 +418,7 @@ mod tests {
             severity: Severity::Warning,
             message: "Test issue".to_string(),
             line: None,
+            confidence: 1.0,
             suggestion: None,
         }
     }
@@ -358,4 +456,25 @@ mod tests {
         assert!(!suggestions.is_empty());
         assert!(suggestions.iter().any(|s| s.id == "scope_boundaries"));
     }
+
+    #[test]
+    fn test_get_suggestions_long_horizon() {
+        let issues = vec![make_issue("LHT001")];
+        assert!(should_suggest(&issues));
+        let suggestions = get_suggestions_for_issues(&issues);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].id, "state_management");
+    }
+
+    #[test]
+    fn test_apply_suggestions_by_id() {
+        let enhanced =
+            apply_suggestions_by_id("Migrate the monorepo.  ", &["state_management".to_string()])
+                .unwrap();
+        assert!(enhanced.starts_with("Migrate the monorepo.\n\n<state_management>"));
+        assert!(enhanced.contains("progress.txt"));
+
+        let err = apply_suggestions_by_id("x", &["nope".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("state_management"));
+    }
 }
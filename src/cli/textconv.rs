@@ -0,0 +1,147 @@
+//! Prompt files as `git diff` sees them (`copt git-textconv`)
+//!
+//! Used as a git textconv driver: git runs it on both sides of a change and
+//! diffs the output, so a header with the quality score and one line per
+//! issue turns a prompt edit into a visible score and issue delta during
+//! review. Issue lines carry no line numbers, so edits elsewhere in the
+//! prompt don't show up as churn. Files that aren't prompts (binary,
+//! invalid UTF-8, over `[input] max_bytes`) pass through unchanged; the
+//! driver must never break `git diff`.
+
+use anyhow::{Context, Result};
+use clap::Args;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::analyzer::{self, Issue, Severity};
+
+/// Prefix of the annotation lines
+const PREFIX: &str = "[copt]";
+
+#[derive(Args, Debug)]
+pub struct TextconvArgs {
+    /// File git asks to convert
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+}
+
+/// Run `copt git-textconv`
+pub fn run(
+    args: &TextconvArgs,
+    max_bytes: u64,
+    check_categories: Option<&[String]>,
+    min_confidence: Option<f32>,
+) -> Result<()> {
+    let bytes = std::fs::read(&args.file)
+        .with_context(|| format!("Failed to read file: {}", args.file.display()))?;
+    let mut stdout = std::io::stdout().lock();
+
+    let prompt = (bytes.len() as u64 <= max_bytes && !crate::utils::input::looks_binary(&bytes))
+        .then(|| std::str::from_utf8(&bytes).ok())
+        .flatten();
+    let Some(prompt) = prompt else {
+        stdout.write_all(&bytes)?;
+        return Ok(());
+    };
+
+    let prompt = super::provenance::strip(prompt);
+    // Show the file even when analysis fails
+    let issues = analyzer::analyze(prompt, check_categories).map(|mut issues| {
+        if let Some(min) = min_confidence {
+            issues.retain(|i| i.confidence >= min);
+        }
+        issues
+    });
+    let converted = match issues {
+        Ok(issues) => render(prompt, &issues),
+        Err(e) => format!("{} analysis failed: {}\n\n{}", PREFIX, e, prompt),
+    };
+    stdout.write_all(converted.as_bytes())?;
+    Ok(())
+}
+
+/// The annotation header followed by the prompt
+pub fn render(prompt: &str, issues: &[Issue]) -> String {
+    let count = |severity: Severity| issues.iter().filter(|i| i.severity == severity).count();
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    let mut out = format!(
+        "{} score {}/100 · {} error{} · {} warning{} · {} info\n",
+        PREFIX,
+        analyzer::quality_score(issues),
+        errors,
+        plural(errors),
+        warnings,
+        plural(warnings),
+        count(Severity::Info),
+    );
+
+    // Stable order, so only real changes show in the diff
+    let mut sorted: Vec<&Issue> = issues.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.id.cmp(&b.id))
+            .then_with(|| a.message.cmp(&b.message))
+    });
+    for issue in sorted {
+        out.push_str(&format!(
+            "{} {:<7} {} {}\n",
+            PREFIX,
+            format!("{:?}", issue.severity).to_lowercase(),
+            issue.id,
+            issue.message
+        ));
+    }
+
+    out.push('\n');
+    out.push_str(prompt);
+    if !prompt.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_lists_score_and_sorted_issues() {
+        let prompt = "Can you think about the API design?\n";
+        let issues = analyzer::analyze(prompt, None).unwrap();
+        assert!(!issues.is_empty());
+        let converted = render(prompt, &issues);
+
+        let header: Vec<&str> = converted.lines().take_while(|l| !l.is_empty()).collect();
+        assert!(header[0].starts_with(&format!(
+            "[copt] score {}/100 · ",
+            analyzer::quality_score(&issues)
+        )));
+        assert_eq!(header.len(), issues.len() + 1);
+        let mut sorted = header[1..].to_vec();
+        sorted.sort_by_key(|line| {
+            let severity = line.split_whitespace().nth(1).unwrap();
+            ["error", "warning", "info"]
+                .iter()
+                .position(|s| *s == severity)
+        });
+        assert_eq!(sorted, header[1..]);
+        assert!(converted.ends_with("\n\nCan you think about the API design?\n"));
+    }
+
+    #[test]
+    fn test_clean_prompt_has_score_line_only() {
+        assert_eq!(
+            render("Summarize.", &[]),
+            "[copt] score 100/100 · 0 errors · 0 warnings · 0 info\n\nSummarize.\n"
+        );
+    }
+}
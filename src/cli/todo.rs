@@ -0,0 +1,116 @@
+//! Issues as TODO comments in the prompt (`--emit todo`)
+//!
+//! Prints a copy of the prompt with an HTML comment above each line that has
+//! an issue, e.g. `<!-- TODO(copt EXP004): Define success criteria -->`, so
+//! authors can work through the issues in their own editor. Issues without a
+//! line go at the top.
+
+use crate::analyzer::Issue;
+
+/// `prompt` with a TODO comment for each issue
+pub fn annotate(prompt: &str, issues: &[Issue]) -> String {
+    let lines: Vec<&str> = prompt.lines().collect();
+    // Issues pointing past the end (or at an empty prompt) go after the last line
+    let slot = |issue: &Issue| match issue.line {
+        Some(line) if line >= 1 => (line - 1).min(lines.len()),
+        _ => 0,
+    };
+
+    let mut todos: Vec<(usize, &Issue)> = issues.iter().map(|i| (slot(i), i)).collect();
+    todos.sort_by_key(|(slot, _)| *slot);
+
+    let mut out = String::with_capacity(prompt.len() + issues.len() * 64);
+    let mut todos = todos.into_iter().peekable();
+    for idx in 0..=lines.len() {
+        let line = lines.get(idx).copied();
+        let indent = line
+            .map(|l| &l[..l.len() - l.trim_start().len()])
+            .unwrap_or("");
+        while let Some((_, issue)) = todos.next_if(|(slot, _)| *slot == idx) {
+            out.push_str(indent);
+            out.push_str(&todo_comment(issue));
+            out.push('\n');
+        }
+        if let Some(line) = line {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    if !prompt.ends_with('\n') && out.ends_with('\n') && !prompt.is_empty() {
+        out.pop();
+    }
+    out
+}
+
+/// `<!-- TODO(copt ID): … -->` for one issue
+fn todo_comment(issue: &Issue) -> String {
+    let text = issue
+        .suggestion
+        .as_deref()
+        .map(first_sentence)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(&issue.message);
+    // "--" would end the comment early
+    let text = text.replace("--", "-");
+    format!("<!-- TODO(copt {}): {} -->", issue.id, text.trim())
+}
+
+/// First sentence of `text`, without the closing period
+fn first_sentence(text: &str) -> &str {
+    let end = text.find(". ").unwrap_or(text.len());
+    text[..end].trim().trim_end_matches('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Severity;
+
+    fn issue(id: &str, line: Option<usize>, suggestion: Option<&str>) -> Issue {
+        Issue {
+            id: id.to_string(),
+            category: "explicitness".to_string(),
+            severity: Severity::Warning,
+            message: format!("{} message", id),
+            line,
+            confidence: 1.0,
+            suggestion: suggestion.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_annotate_inserts_comments_above_lines() {
+        let prompt = "Research API design.\n  Can you list options?\n";
+        let issues = [
+            issue(
+                "EXP003",
+                Some(2),
+                Some("Use direct commands instead. Replace \"Can you...\"."),
+            ),
+            issue("EXP004", None, Some("Define success criteria.")),
+            issue("FMT001", Some(9), None),
+        ];
+
+        assert_eq!(
+            annotate(prompt, &issues),
+            "<!-- TODO(copt EXP004): Define success criteria -->\n\
+             Research API design.\n  \
+             <!-- TODO(copt EXP003): Use direct commands instead -->\n  \
+             Can you list options?\n\
+             <!-- TODO(copt FMT001): FMT001 message -->\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_keeps_prompt_without_issues() {
+        assert_eq!(annotate("One line", &[]), "One line");
+        assert_eq!(
+            annotate(
+                "No newline",
+                &[issue("EXP001", Some(1), Some("Be -- specific"))]
+            ),
+            "<!-- TODO(copt EXP001): Be - specific -->\nNo newline"
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! Response format contract tests (`copt verify`)
+//!
+//! Runs a prompt against the model with a sample input and checks that the
+//! response parses as the format the prompt promises and, given a JSON
+//! Schema, matches it. An optimization that rewords the output format can
+//! read better and still break the parser downstream; this catches that
+//! before the prompt ships.
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::llm::LlmClient;
+use crate::utils::schema::{self, Violation};
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Prompt to verify (used as the system prompt)
+    #[arg(short, long, value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Format the response must parse as
+    #[arg(long, value_enum, default_value_t = Expect::Json)]
+    pub expect: Expect,
+
+    /// JSON Schema (JSON or YAML) the parsed response must match
+    #[arg(long, value_name = "FILE")]
+    pub schema: Option<PathBuf>,
+
+    /// Sample user message sent with the prompt
+    #[arg(
+        long,
+        value_name = "TEXT",
+        conflicts_with = "input_file",
+        required_unless_present = "input_file"
+    )]
+    pub input: Option<String>,
+
+    /// Read the sample user message from a file
+    #[arg(long, value_name = "FILE")]
+    pub input_file: Option<PathBuf>,
+
+    /// Number of responses to check; every one must pass
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=20))]
+    pub runs: u32,
+
+    /// Max tokens per response
+    #[arg(long, default_value = "1024", hide_default_value = true)]
+    pub max_tokens: u32,
+}
+
+/// Response formats a contract can declare
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Expect {
+    Json,
+    Yaml,
+}
+
+impl Expect {
+    fn name(self) -> &'static str {
+        match self {
+            Expect::Json => "JSON",
+            Expect::Yaml => "YAML",
+        }
+    }
+
+    /// Parse a response, tolerating a surrounding ``` fence
+    pub fn parse(self, response: &str) -> Result<Value, String> {
+        let body = super::eval::strip_fence(response);
+        match self {
+            Expect::Json => serde_json::from_str(body).map_err(|e| e.to_string()),
+            Expect::Yaml => serde_yaml::from_str(body).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// How one response held up against the contract
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub response: String,
+    /// Why the response didn't parse
+    pub parse_error: Option<String>,
+    pub violations: Vec<Violation>,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.parse_error.is_none() && self.violations.is_empty()
+    }
+}
+
+/// Check a response against the expected format and schema
+pub fn check(response: String, expect: Expect, schema: Option<&Value>) -> Check {
+    let (parse_error, violations) = match expect.parse(&response) {
+        Ok(doc) => (
+            None,
+            schema.map_or_else(Vec::new, |s| schema::validate(s, &doc)),
+        ),
+        Err(e) => (Some(e), Vec::new()),
+    };
+    Check {
+        response,
+        parse_error,
+        violations,
+    }
+}
+
+/// Load a schema file; YAML is a superset of JSON, so either works
+pub fn load_schema(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read schema: {}", path.display()))?;
+    serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to parse schema: {}", path.display()))
+}
+
+/// Entry point for `copt verify`
+pub async fn run(
+    args: &VerifyArgs,
+    client: &dyn LlmClient,
+    model: &str,
+    json_output: bool,
+) -> Result<()> {
+    let prompt = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("Failed to read prompt: {}", args.file.display()))?;
+    let prompt = super::provenance::strip(&prompt);
+    let input = match (&args.input, &args.input_file) {
+        (Some(input), _) => input.clone(),
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read input: {}", path.display()))?,
+        (None, None) => unreachable!("clap requires --input or --input-file"),
+    };
+    let schema = args.schema.as_deref().map(load_schema).transpose()?;
+
+    let mut checks = Vec::new();
+    for run in 1..=args.runs {
+        let response = client
+            .complete(prompt, &input, model, args.max_tokens)
+            .await
+            .with_context(|| format!("Model call failed for response {}", run))?;
+        checks.push(check(response, args.expect, schema.as_ref()));
+    }
+
+    if json_output {
+        print_json(args, &checks)?;
+    } else {
+        print_report(args, &checks);
+    }
+
+    let failed = checks.iter().filter(|c| !c.passed()).count();
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} responses broke the {} contract of {}",
+            failed,
+            checks.len(),
+            args.expect.name(),
+            args.file.display()
+        );
+    }
+    Ok(())
+}
+
+fn print_json(args: &VerifyArgs, checks: &[Check]) -> Result<()> {
+    let json = serde_json::json!({
+        "prompt": args.file.display().to_string(),
+        "expect": args.expect.name().to_lowercase(),
+        "schema": args.schema.as_ref().map(|p| p.display().to_string()),
+        "passed": checks.iter().filter(|c| c.passed()).count(),
+        "total": checks.len(),
+        "responses": checks.iter().map(|c| serde_json::json!({
+            "passed": c.passed(),
+            "parse_error": c.parse_error,
+            "violations": c.violations.iter().map(|v| serde_json::json!({
+                "path": v.path,
+                "message": v.message,
+            })).collect::<Vec<_>>(),
+            "response": c.response,
+        })).collect::<Vec<_>>(),
+    });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn print_report(args: &VerifyArgs, checks: &[Check]) {
+    let contract = match args.schema {
+        Some(ref path) => format!("valid {} matching {}", args.expect.name(), path.display()),
+        None => format!("valid {}", args.expect.name()),
+    };
+
+    println!();
+    for (i, check) in checks.iter().enumerate() {
+        let label = format!("Response {}/{}", i + 1, checks.len());
+        if check.passed() {
+            println!("  {} {} is {}", "✓".green(), label, contract);
+            continue;
+        }
+        match check.parse_error {
+            Some(ref error) => println!(
+                "  {} {} is not valid {}: {}",
+                "✗".red(),
+                label,
+                args.expect.name(),
+                error
+            ),
+            None => println!("  {} {} doesn't match the schema:", "✗".red(), label),
+        }
+        for violation in &check.violations {
+            println!("    {} {}", violation.path.yellow(), violation.message);
+        }
+        for line in check.response.trim().lines().take(8) {
+            println!(
+                "    {}",
+                crate::utils::text::truncate(line, 100).bright_black()
+            );
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["sentiment"],
+            "properties": { "sentiment": { "enum": ["positive", "negative"] } }
+        })
+    }
+
+    #[test]
+    fn test_check_parses_then_validates() {
+        let passed = check(
+            "```json\n{\"sentiment\": \"positive\"}\n```".to_string(),
+            Expect::Json,
+            Some(&schema()),
+        );
+        assert!(passed.passed());
+
+        let prose = check(
+            "Sure! The sentiment is positive.".to_string(),
+            Expect::Json,
+            Some(&schema()),
+        );
+        assert!(prose.parse_error.is_some());
+
+        let renamed = check(
+            "{\"Sentiment\": \"Positive\"}".to_string(),
+            Expect::Json,
+            Some(&schema()),
+        );
+        assert_eq!(renamed.violations.len(), 1);
+        assert_eq!(
+            renamed.violations[0].to_string(),
+            "$: missing required property \"sentiment\""
+        );
+
+        let yaml = check(
+            "```yaml\nsentiment: negative\n```".to_string(),
+            Expect::Yaml,
+            Some(&schema()),
+        );
+        assert!(yaml.passed(), "{:?}", yaml);
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_when_any_response_breaks_the_contract() {
+        let dir = tempfile::tempdir().unwrap();
+        let prompt = dir.path().join("prompt.md");
+        std::fs::write(&prompt, "Classify the sentiment. Reply with JSON.").unwrap();
+        let schema_path = dir.path().join("out.schema.yaml");
+        std::fs::write(&schema_path, serde_yaml::to_string(&schema()).unwrap()).unwrap();
+        let args = VerifyArgs {
+            file: prompt,
+            expect: Expect::Json,
+            schema: Some(schema_path),
+            input: Some("I love it".to_string()),
+            input_file: None,
+            runs: 2,
+            max_tokens: 256,
+        };
+
+        let client = MockLlmClient::with_responses([
+            r#"{"sentiment": "positive"}"#,
+            r#"{"sentiment": "positive"}"#,
+        ]);
+        run(&args, &client, "sonnet", true).await.unwrap();
+        let calls = client.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].system, "Classify the sentiment. Reply with JSON.");
+        assert_eq!(calls[0].user_message, "I love it");
+
+        let client = MockLlmClient::with_responses([
+            r#"{"sentiment": "positive"}"#,
+            r#"{"sentiment": "mixed"}"#,
+        ]);
+        let err = run(&args, &client, "sonnet", true).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("1 of 2 responses broke the JSON contract"),
+            "{}",
+            err
+        );
+    }
+}
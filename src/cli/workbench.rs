@@ -0,0 +1,236 @@
+//! Anthropic Console Workbench export (`--emit`)
+//!
+//! Packages an optimized prompt for hand-off to the Console prompt
+//! workbench: task sections become the user message, everything else
+//! (role, context, rules, examples, output format) becomes the system
+//! prompt, and `{{VARIABLE}}` placeholders are listed so they can be
+//! filled in as test-case variables.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::analyzer::segment::{self, SectionKind};
+use crate::OptimizationResult;
+
+/// Console page that opens a new Workbench prompt
+const WORKBENCH_URL: &str = "https://console.anthropic.com/workbench/new";
+
+/// Export format for `--emit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EmitTarget {
+    /// Print the Workbench link and the parts of the prompt to paste into it
+    ConsoleUrl,
+    /// Print a Workbench JSON document (Messages API shape plus variables)
+    #[value(name = "workbench.json")]
+    WorkbenchJson,
+    /// Print the original prompt with a TODO comment above each issue
+    Todo,
+}
+
+/// A single Workbench message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkbenchMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Prompt packaged for the Console Workbench
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkbenchPrompt {
+    pub model: String,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub system: String,
+    pub messages: Vec<WorkbenchMessage>,
+    /// `{{VARIABLE}}` placeholders found in the prompt, with empty values to fill in
+    pub variables: BTreeMap<String, String>,
+}
+
+impl WorkbenchPrompt {
+    /// Split a prompt into system and user parts for `model`
+    pub fn from_prompt(prompt: &str, model: &str) -> Self {
+        let (system, user) = split_system_user(prompt);
+        let variables = find_variables(prompt)
+            .into_iter()
+            .map(|name| (name, String::new()))
+            .collect();
+
+        Self {
+            model: console_model_id(model),
+            max_tokens: super::DEFAULT_MAX_TOKENS,
+            system,
+            messages: vec![WorkbenchMessage {
+                role: "user".to_string(),
+                content: user,
+            }],
+            variables,
+        }
+    }
+
+    /// The Workbench link followed by the model, system prompt, user
+    /// message, and variables, each ready to paste into its field
+    ///
+    /// The Console has no documented way to prefill a prompt from a link.
+    pub fn console_handoff(&self) -> String {
+        let mut out = format!(
+            "Open {} and paste:\n\nModel: {}\n",
+            WORKBENCH_URL, self.model
+        );
+        if !self.system.is_empty() {
+            out.push_str(&format!("\n--- System prompt ---\n{}\n", self.system));
+        }
+        for message in &self.messages {
+            out.push_str(&format!("\n--- User message ---\n{}\n", message.content));
+        }
+        if !self.variables.is_empty() {
+            out.push_str("\n--- Variables ---\n");
+            for name in self.variables.keys() {
+                out.push_str(name);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Render the export for `target`
+pub fn emit(target: EmitTarget, result: &OptimizationResult, model: &str) -> Result<String> {
+    let workbench = || WorkbenchPrompt::from_prompt(&result.optimized, model);
+    match target {
+        EmitTarget::ConsoleUrl => Ok(workbench().console_handoff()),
+        EmitTarget::WorkbenchJson => Ok(serde_json::to_string_pretty(&workbench())?),
+        EmitTarget::Todo => Ok(super::todo::annotate(&result.original, &result.issues)),
+    }
+}
+
+/// Split a prompt into (system, user): task sections go to the user turn
+///
+/// Prompts without a detectable task are sent entirely as the user turn.
+fn split_system_user(prompt: &str) -> (String, String) {
+    let sections = segment::segment_prompt(prompt);
+    if !segment::has_section(&sections, SectionKind::Task) {
+        return (String::new(), prompt.trim().to_string());
+    }
+
+    let lines: Vec<&str> = prompt.lines().collect();
+    let mut system = Vec::new();
+    let mut user = Vec::new();
+
+    for section in &sections {
+        let body = lines[section.start_line - 1..section.end_line].join("\n");
+        if section.kind == SectionKind::Task {
+            user.push(body);
+        } else {
+            system.push(body);
+        }
+    }
+
+    (system.join("\n\n"), user.join("\n\n"))
+}
+
+/// Distinct `{{VARIABLE}}` names in order of first appearance
+pub fn find_variables(prompt: &str) -> Vec<String> {
+    let re = Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap();
+    let mut names: Vec<String> = Vec::new();
+    for caps in re.captures_iter(prompt) {
+        let name = caps[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Convert a model alias or Bedrock ID to the Anthropic API model name
+///
+/// e.g. `us.anthropic.claude-sonnet-4-5-20250929-v1:0` → `claude-sonnet-4-5-20250929`
+fn console_model_id(model: &str) -> String {
+    let resolved = super::resolve_model_id(model);
+    let name = resolved
+        .split_once("anthropic.")
+        .map(|(_, rest)| rest)
+        .unwrap_or(&resolved);
+    let name = name.split_once(':').map(|(n, _)| n).unwrap_or(name);
+    name.strip_suffix("-v1")
+        .or_else(|| name.strip_suffix("-v2"))
+        .unwrap_or(name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROMPT: &str = "You are a support agent for Acme.\n\n<context>\nCustomer: {{CUSTOMER_NAME}}\n</context>\n\n<instructions>\nAnswer {{QUESTION}} for {{CUSTOMER_NAME}}.\n</instructions>";
+
+    /// Result whose original and optimized prompts are both `prompt`
+    fn result(prompt: &str) -> OptimizationResult {
+        OptimizationResult {
+            original: prompt.to_string(),
+            optimized: prompt.to_string(),
+            issues: Vec::new(),
+            stats: Default::default(),
+        }
+    }
+    #[test]
+    fn test_split_system_and_user() {
+        let workbench = WorkbenchPrompt::from_prompt(PROMPT, "sonnet");
+        assert!(workbench.system.starts_with("You are a support agent"));
+        assert!(workbench.system.contains("<context>"));
+        assert_eq!(
+            workbench.messages[0].content,
+            "<instructions>\nAnswer {{QUESTION}} for {{CUSTOMER_NAME}}.\n</instructions>"
+        );
+        assert_eq!(
+            workbench.variables.keys().collect::<Vec<_>>(),
+            vec!["CUSTOMER_NAME", "QUESTION"]
+        );
+        assert_eq!(workbench.model, "claude-sonnet-4-5-20250929");
+    }
+
+    #[test]
+    fn test_prompt_without_task_is_all_user() {
+        let workbench = WorkbenchPrompt::from_prompt("We sell shoes.", "claude-haiku-4-5");
+        assert!(workbench.system.is_empty());
+        assert_eq!(workbench.messages[0].content, "We sell shoes.");
+        assert_eq!(workbench.model, "claude-haiku-4-5");
+
+        let json: serde_json::Value = serde_json::from_str(
+            &emit(EmitTarget::WorkbenchJson, &result("We sell shoes."), "opus").unwrap(),
+        )
+        .unwrap();
+        assert!(json.get("system").is_none());
+        assert_eq!(json["messages"][0]["role"], "user");
+    }
+
+    #[test]
+    fn test_console_handoff_lists_the_parts_to_paste() {
+        let handoff = emit(EmitTarget::ConsoleUrl, &result(PROMPT), "sonnet").unwrap();
+        assert!(handoff.starts_with(&format!("Open {} and paste:", WORKBENCH_URL)));
+        assert!(!handoff.contains('?'), "{}", handoff);
+        assert!(handoff.contains("Model: claude-sonnet-4-5-20250929"));
+        assert!(handoff.contains("--- System prompt ---\nYou are a support agent"));
+        assert!(handoff.contains("--- User message ---\n<instructions>"));
+        assert!(handoff.ends_with("--- Variables ---\nCUSTOMER_NAME\nQUESTION\n"));
+
+        // Long prompts are handed off like any other
+        let long = format!("Summarize this.\n\n{}", "word ".repeat(5000));
+        let handoff = emit(EmitTarget::ConsoleUrl, &result(&long), "sonnet").unwrap();
+        assert!(handoff.contains("word word"));
+    }
+
+    #[test]
+    fn test_todo_annotates_the_original() {
+        let result = OptimizationResult {
+            optimized: "Rewritten".to_string(),
+            issues: crate::analyzer::analyze("Can you list options?", None).unwrap(),
+            ..result("Can you list options?")
+        };
+        let todo = emit(EmitTarget::Todo, &result, "sonnet").unwrap();
+        assert!(todo.starts_with("<!-- TODO(copt "));
+        assert!(todo.ends_with("\nCan you list options?"));
+    }
+}
@@ -0,0 +1,344 @@
+//! Error kinds with stable codes
+//!
+//! The library entry points (`analyzer::analyze`, `optimizer::optimize_static`,
+//! `optimizer::optimize_with_llm`) return a [`CoptError`], so callers can match
+//! on the failure. Internally copt reports failures through `anyhow`, raising
+//! the ones a script may want to branch on as a `CoptError` inside that chain;
+//! [`ErrorInfo::from_error`] finds the most specific one, both when an entry
+//! point converts its error and at the CLI boundary, where `--format json`
+//! prints its code (`{"error": {"code": "provider_throttled"}}`) instead of
+//! leaving callers to parse messages.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::llm::LlmTimeout;
+
+/// What went wrong talking to the LLM provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderErrorKind {
+    /// Missing, invalid, or expired credentials, or no permission
+    Auth,
+    /// Rate limited or over capacity
+    Throttled,
+    /// The model doesn't exist or isn't enabled for this account or region
+    ModelUnavailable,
+    /// The request didn't finish in time
+    Timeout,
+    /// The provider couldn't be reached
+    Network,
+    /// The provider rejected the request itself
+    InvalidRequest,
+    /// The model declined the task and answered with a refusal
+    Refused,
+    /// Anything else the provider reported
+    Other,
+}
+
+impl ProviderErrorKind {
+    /// Whether the same request may succeed if tried again later
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::Throttled | Self::Timeout | Self::Network | Self::Other
+        )
+    }
+}
+
+/// A failure with a stable [`code`](CoptError::code)
+#[derive(Debug, thiserror::Error)]
+pub enum CoptError {
+    /// Invalid config file, flag, or model name
+    #[error("{0}")]
+    Config(String),
+    /// The LLM provider failed or refused the request
+    #[error("{message}")]
+    Provider {
+        kind: ProviderErrorKind,
+        retryable: bool,
+        message: String,
+    },
+    /// The prompt couldn't be analyzed or optimized
+    #[error("{0}")]
+    Analysis(String),
+    /// Reading or writing a file failed
+    #[error("{0}")]
+    Io(String),
+    /// The same prompt was optimized recently and `[history]
+    /// fail_on_duplicate` is set; `--force` runs it again
+    #[error("{0}")]
+    DuplicateRun(String),
+    /// The organization policy (`COPT_POLICY`) forbids the request
+    #[error("{0}")]
+    PolicyViolation(String),
+    /// An LLM call would go over `--max-cost` or the monthly cap
+    #[error("{0}")]
+    CostLimit(String),
+    /// An LLM call didn't finish within `--timeout`
+    #[error(transparent)]
+    Timeout(#[from] LlmTimeout),
+    /// A failure none of the other kinds describe
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// Classify an internal error chain by its most specific cause, keeping the
+/// whole chain's message
+impl From<anyhow::Error> for CoptError {
+    fn from(err: anyhow::Error) -> Self {
+        if let Some(timeout) = err.chain().find_map(|c| c.downcast_ref::<LlmTimeout>()) {
+            return Self::Timeout(*timeout);
+        }
+        let info = ErrorInfo::from_error(&err);
+        let message = info.message;
+        match info.code {
+            "config_invalid" => Self::Config(message),
+            "provider_auth" => Self::provider(ProviderErrorKind::Auth, message),
+            "provider_throttled" => Self::provider(ProviderErrorKind::Throttled, message),
+            "provider_model_unavailable" => {
+                Self::provider(ProviderErrorKind::ModelUnavailable, message)
+            }
+            "provider_timeout" => Self::provider(ProviderErrorKind::Timeout, message),
+            "provider_network" => Self::provider(ProviderErrorKind::Network, message),
+            "provider_invalid_request" => {
+                Self::provider(ProviderErrorKind::InvalidRequest, message)
+            }
+            "provider_refused" => Self::provider(ProviderErrorKind::Refused, message),
+            "provider_error" => Self::provider(ProviderErrorKind::Other, message),
+            "analysis_failed" => Self::Analysis(message),
+            "io_error" => Self::Io(message),
+            "duplicate_run" => Self::DuplicateRun(message),
+            "policy_violation" => Self::PolicyViolation(message),
+            "cost_limit" => Self::CostLimit(message),
+            _ => Self::Internal(message),
+        }
+    }
+}
+
+impl CoptError {
+    /// A provider error, retryable according to its kind
+    pub fn provider(kind: ProviderErrorKind, message: impl Into<String>) -> Self {
+        Self::Provider {
+            kind,
+            retryable: kind.retryable(),
+            message: message.into(),
+        }
+    }
+
+    /// `what` needs a cargo feature this build was compiled without
+    pub fn feature_disabled(feature: &str, what: &str) -> Self {
+        Self::Config(format!(
+            "{} needs the `{}` feature, which this build of copt leaves out\n\n\
+            Reinstall with it: cargo install copt --features {}",
+            what, feature, feature
+        ))
+    }
+
+    /// Stable identifier for scripts, e.g. `provider_throttled`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Config(_) => "config_invalid",
+            Self::Provider { kind, .. } => match kind {
+                ProviderErrorKind::Auth => "provider_auth",
+                ProviderErrorKind::Throttled => "provider_throttled",
+                ProviderErrorKind::ModelUnavailable => "provider_model_unavailable",
+                ProviderErrorKind::Timeout => "provider_timeout",
+                ProviderErrorKind::Network => "provider_network",
+                ProviderErrorKind::InvalidRequest => "provider_invalid_request",
+                ProviderErrorKind::Refused => "provider_refused",
+                ProviderErrorKind::Other => "provider_error",
+            },
+            Self::Analysis(_) => "analysis_failed",
+            Self::Io(_) => "io_error",
+            Self::DuplicateRun(_) => "duplicate_run",
+            Self::PolicyViolation(_) => "policy_violation",
+            Self::CostLimit(_) => "cost_limit",
+            Self::Timeout(_) => "provider_timeout",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Whether the same command may succeed if run again later
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Provider { retryable, .. } => *retryable,
+            Self::Timeout(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Every code [`ErrorInfo`] can carry
+const CODES: &[&str] = &[
+    "config_invalid",
+    "provider_auth",
+    "provider_throttled",
+    "provider_model_unavailable",
+    "provider_timeout",
+    "provider_network",
+    "provider_invalid_request",
+    "provider_refused",
+    "provider_error",
+    "analysis_failed",
+    "io_error",
+    "duplicate_run",
+    "policy_violation",
+    "cost_limit",
+    "internal_error",
+];
+
+/// How a failed run is reported in JSON output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorInfo {
+    pub code: &'static str,
+    pub message: String,
+    pub retryable: bool,
+}
+
+impl ErrorInfo {
+    /// Classify `err` by the most specific cause in its chain
+    ///
+    /// The message is the full chain, as `{:#}` formats it.
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+        // Also finds a CoptError attached with `.context(…)`
+        if let Some(e) = err.downcast_ref::<CoptError>() {
+            return Self {
+                code: e.code(),
+                message,
+                retryable: e.retryable(),
+            };
+        }
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<CoptError>() {
+                return Self {
+                    code: e.code(),
+                    message,
+                    retryable: e.retryable(),
+                };
+            }
+            if cause.downcast_ref::<LlmTimeout>().is_some() {
+                return Self::from_kind(ProviderErrorKind::Timeout, message);
+            }
+        }
+
+        // Errors from libraries that were never wrapped
+        for cause in err.chain() {
+            if cause.downcast_ref::<std::io::Error>().is_some() {
+                return Self {
+                    code: "io_error",
+                    message,
+                    retryable: false,
+                };
+            }
+            if cause.downcast_ref::<toml::de::Error>().is_some() {
+                return Self {
+                    code: "config_invalid",
+                    message,
+                    retryable: false,
+                };
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                let kind = if e.is_timeout() {
+                    ProviderErrorKind::Timeout
+                } else {
+                    ProviderErrorKind::Network
+                };
+                return Self::from_kind(kind, message);
+            }
+        }
+
+        Self {
+            code: "internal_error",
+            message,
+            retryable: false,
+        }
+    }
+
+    fn from_kind(kind: ProviderErrorKind, message: String) -> Self {
+        let err = CoptError::provider(kind, String::new());
+        Self {
+            code: err.code(),
+            message,
+            retryable: err.retryable(),
+        }
+    }
+}
+
+/// Read back from JSON, e.g. a saved session; unknown codes become
+/// `internal_error`
+impl<'de> Deserialize<'de> for ErrorInfo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Stored {
+            code: String,
+            message: String,
+            retryable: bool,
+        }
+
+        let stored = Stored::deserialize(deserializer)?;
+        Ok(Self {
+            code: CODES
+                .iter()
+                .find(|c| **c == stored.code)
+                .copied()
+                .unwrap_or("internal_error"),
+            message: stored.message,
+            retryable: stored.retryable,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_codes_survive_context() {
+        let err = anyhow::Error::new(CoptError::provider(
+            ProviderErrorKind::Throttled,
+            "Too many requests",
+        ))
+        .context("Optimization failed");
+        let info = ErrorInfo::from_error(&err);
+        assert_eq!(info.code, "provider_throttled");
+        assert!(info.retryable);
+        assert_eq!(info.message, "Optimization failed: Too many requests");
+
+        let err = anyhow::Error::new(CoptError::Config("Unknown model 'sonet'".into()));
+        assert_eq!(ErrorInfo::from_error(&err).code, "config_invalid");
+    }
+
+    #[test]
+    fn test_untyped_errors_are_classified() {
+        let err = anyhow::Error::new(LlmTimeout(std::time::Duration::from_secs(5)));
+        assert_eq!(ErrorInfo::from_error(&err).code, "provider_timeout");
+
+        let err = std::fs::read_to_string("/nonexistent/prompt.txt")
+            .context("Failed to read prompt")
+            .unwrap_err();
+        assert_eq!(ErrorInfo::from_error(&err).code, "io_error");
+
+        let info = ErrorInfo::from_error(&anyhow::anyhow!("something odd"));
+        assert_eq!(info.code, "internal_error");
+        assert!(!info.retryable);
+    }
+
+    #[test]
+    fn test_entry_point_errors_keep_their_kind() {
+        let err: CoptError = anyhow::Error::new(CoptError::CostLimit("Over budget".into()))
+            .context("Optimization failed")
+            .into();
+        assert_eq!(err.code(), "cost_limit");
+        assert_eq!(err.to_string(), "Optimization failed: Over budget");
+
+        let timeout = LlmTimeout(std::time::Duration::from_secs(5));
+        let err: CoptError = anyhow::Error::new(timeout).context("Request failed").into();
+        assert!(matches!(err, CoptError::Timeout(_)));
+        assert!(err.retryable());
+        assert!(crate::llm::is_timeout(&err.into()));
+
+        let err: CoptError = anyhow::anyhow!("something odd").into();
+        assert_eq!(err.code(), "internal_error");
+    }
+}
@@ -0,0 +1,80 @@
+//! English messages (reference catalog)
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Linear output
+    ("input", "Input:"),
+    ("analysis.title", "Analysis Results"),
+    ("analysis.structure", "Structure:"),
+    (
+        "analysis.no_issues",
+        "No issues detected - your prompt looks good!",
+    ),
+    ("analysis.strengths", "Already follows:"),
+    ("results.title", "Optimization Results"),
+    ("results.tokens", "TOKEN ANALYSIS"),
+    ("results.original", "Original:"),
+    ("results.optimized", "Optimized:"),
+    ("results.change", "Change:"),
+    ("results.performance", "PERFORMANCE"),
+    ("results.processing_time", "Processing time:"),
+    ("results.llm_call", "LLM call:"),
+    ("results.timed_out", "timed out, static result shown"),
+    ("results.llm_failed", "failed, static result shown"),
+    ("results.issues_detected", "Issues detected:"),
+    ("results.rules_fixed", "Rules fixed:"),
+    ("results.edits", "edits"),
+    ("results.quality", "QUALITY"),
+    ("results.heuristic_score", "Heuristic score:"),
+    ("results.judge", "Judge:"),
+    ("results.changes", "WHAT CHANGED"),
+    ("results.static_fixes", "STATIC FIXES"),
+    ("results.test_queries", "TEST QUERIES"),
+    ("results.provider", "PROVIDER"),
+    ("results.service", "Service:"),
+    ("results.model", "Model:"),
+    // Status bar hints
+    ("hint.nav", "nav"),
+    ("hint.toggle", "toggle"),
+    ("hint.expand", "expand"),
+    ("hint.collapse", "collapse"),
+    ("hint.diff", "diff"),
+    ("hint.copy", "copy"),
+    ("hint.save", "save"),
+    ("hint.edit", "edit"),
+    ("hint.docs", "docs"),
+    ("hint.help", "help"),
+    ("hint.quit", "quit"),
+    ("hint.return", "return"),
+    ("hint.scroll", "scroll"),
+    ("hint.original", "original"),
+    ("hint.optimized", "optimized"),
+    // Help screen
+    ("help.title", "Keyboard Shortcuts"),
+    ("help.navigation", "NAVIGATION"),
+    ("help.views", "VIEWS"),
+    ("help.actions", "ACTIONS"),
+    ("help.general", "GENERAL"),
+    ("help.move", "Move selection up/down"),
+    ("help.expand", "Expand/collapse category"),
+    ("help.scroll", "Scroll content"),
+    ("help.top", "Go to top"),
+    ("help.diff", "Toggle diff view"),
+    ("help.preview", "Diff view: full-screen original/optimized"),
+    ("help.help", "Toggle help (this screen)"),
+    ("help.return", "Return to main view"),
+    ("help.copy", "Copy optimized prompt to clipboard"),
+    ("help.save", "Save optimized prompt to file"),
+    ("help.edit", "Open optimized prompt in editor"),
+    ("help.docs", "Open the selected rule's documentation"),
+    ("help.quit", "Quit application"),
+    // Errors
+    ("error.label", "Error:"),
+    (
+        "error.needs_terminal",
+        "Interactive mode requires a terminal. Use without -i for piped output.",
+    ),
+    (
+        "error.no_prompt",
+        "No prompt provided. Use --help for usage information.",
+    ),
+];
@@ -0,0 +1,89 @@
+//! Spanish messages
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Linear output
+    ("input", "Entrada:"),
+    ("analysis.title", "Resultados del análisis"),
+    ("analysis.structure", "Estructura:"),
+    (
+        "analysis.no_issues",
+        "No se detectaron problemas: ¡tu prompt se ve bien!",
+    ),
+    ("analysis.strengths", "Ya cumple con:"),
+    ("results.title", "Resultados de la optimización"),
+    ("results.tokens", "ANÁLISIS DE TOKENS"),
+    ("results.original", "Original:"),
+    ("results.optimized", "Optimizado:"),
+    ("results.change", "Cambio:"),
+    ("results.performance", "RENDIMIENTO"),
+    ("results.processing_time", "Tiempo de proceso:"),
+    ("results.llm_call", "Llamada LLM:"),
+    (
+        "results.timed_out",
+        "tiempo agotado, se muestra el resultado estático",
+    ),
+    (
+        "results.llm_failed",
+        "falló, se muestra el resultado estático",
+    ),
+    ("results.issues_detected", "Problemas:"),
+    ("results.rules_fixed", "Reglas corregidas:"),
+    ("results.edits", "ediciones"),
+    ("results.quality", "CALIDAD"),
+    ("results.heuristic_score", "Puntuación:"),
+    ("results.judge", "Juez:"),
+    ("results.changes", "QUÉ CAMBIÓ"),
+    ("results.static_fixes", "CORRECCIONES ESTÁTICAS"),
+    ("results.test_queries", "CONSULTAS DE PRUEBA"),
+    ("results.provider", "PROVEEDOR"),
+    ("results.service", "Servicio:"),
+    ("results.model", "Modelo:"),
+    // Status bar hints
+    ("hint.nav", "navegar"),
+    ("hint.toggle", "alternar"),
+    ("hint.expand", "expandir"),
+    ("hint.collapse", "contraer"),
+    ("hint.diff", "diferencias"),
+    ("hint.copy", "copiar"),
+    ("hint.save", "guardar"),
+    ("hint.edit", "editar"),
+    ("hint.docs", "docs"),
+    ("hint.help", "ayuda"),
+    ("hint.quit", "salir"),
+    ("hint.return", "volver"),
+    ("hint.scroll", "desplazar"),
+    ("hint.original", "original"),
+    ("hint.optimized", "optimizado"),
+    // Help screen
+    ("help.title", "Atajos de teclado"),
+    ("help.navigation", "NAVEGACIÓN"),
+    ("help.views", "VISTAS"),
+    ("help.actions", "ACCIONES"),
+    ("help.general", "GENERAL"),
+    ("help.move", "Mover la selección arriba/abajo"),
+    ("help.expand", "Expandir/contraer categoría"),
+    ("help.scroll", "Desplazar contenido"),
+    ("help.top", "Ir al inicio"),
+    ("help.diff", "Mostrar/ocultar diferencias"),
+    (
+        "help.preview",
+        "Diferencias: original/optimizado a pantalla completa",
+    ),
+    ("help.help", "Mostrar/ocultar ayuda (esta pantalla)"),
+    ("help.return", "Volver a la vista principal"),
+    ("help.copy", "Copiar el prompt optimizado al portapapeles"),
+    ("help.save", "Guardar el prompt optimizado en un archivo"),
+    ("help.edit", "Abrir el prompt optimizado en el editor"),
+    ("help.docs", "Abrir la documentación de la regla elegida"),
+    ("help.quit", "Salir de la aplicación"),
+    // Errors
+    ("error.label", "Error:"),
+    (
+        "error.needs_terminal",
+        "El modo interactivo requiere una terminal. Úsalo sin -i para salida por tubería.",
+    ),
+    (
+        "error.no_prompt",
+        "No se proporcionó ningún prompt. Usa --help para ver las opciones.",
+    ),
+];
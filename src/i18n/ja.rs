@@ -0,0 +1,80 @@
+//! Japanese messages
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // Linear output
+    ("input", "入力:"),
+    ("analysis.title", "分析結果"),
+    ("analysis.structure", "構成:"),
+    (
+        "analysis.no_issues",
+        "問題は見つかりませんでした。良いプロンプトです！",
+    ),
+    ("analysis.strengths", "実践できていること:"),
+    ("results.title", "最適化結果"),
+    ("results.tokens", "トークン分析"),
+    ("results.original", "元:"),
+    ("results.optimized", "最適化後:"),
+    ("results.change", "変化:"),
+    ("results.performance", "パフォーマンス"),
+    ("results.processing_time", "処理時間:"),
+    ("results.llm_call", "LLM 呼び出し:"),
+    ("results.timed_out", "タイムアウト（静的最適化の結果を表示）"),
+    ("results.llm_failed", "失敗（静的最適化の結果を表示）"),
+    ("results.issues_detected", "検出された問題:"),
+    ("results.rules_fixed", "修正したルール:"),
+    ("results.edits", "箇所"),
+    ("results.quality", "品質"),
+    ("results.heuristic_score", "品質スコア:"),
+    ("results.judge", "ジャッジ:"),
+    ("results.changes", "変更点"),
+    ("results.static_fixes", "静的修正"),
+    ("results.test_queries", "テスト用クエリ"),
+    ("results.provider", "プロバイダー"),
+    ("results.service", "サービス:"),
+    ("results.model", "モデル:"),
+    // Status bar hints
+    ("hint.nav", "移動"),
+    ("hint.toggle", "切替"),
+    ("hint.expand", "展開"),
+    ("hint.collapse", "折りたたむ"),
+    ("hint.diff", "差分"),
+    ("hint.copy", "コピー"),
+    ("hint.save", "保存"),
+    ("hint.edit", "編集"),
+    ("hint.docs", "ドキュメント"),
+    ("hint.help", "ヘルプ"),
+    ("hint.quit", "終了"),
+    ("hint.return", "戻る"),
+    ("hint.scroll", "スクロール"),
+    ("hint.original", "元の文"),
+    ("hint.optimized", "最適化後"),
+    // Help screen
+    ("help.title", "キーボードショートカット"),
+    ("help.navigation", "ナビゲーション"),
+    ("help.views", "表示"),
+    ("help.actions", "操作"),
+    ("help.general", "全般"),
+    ("help.move", "選択を上下に移動"),
+    ("help.expand", "カテゴリを展開/折りたたむ"),
+    ("help.scroll", "内容をスクロール"),
+    ("help.top", "先頭へ移動"),
+    ("help.diff", "差分表示の切替"),
+    ("help.preview", "差分表示: 元の文/最適化後を全画面表示"),
+    ("help.help", "ヘルプの切替（この画面）"),
+    ("help.return", "メイン画面に戻る"),
+    ("help.copy", "最適化したプロンプトをクリップボードにコピー"),
+    ("help.save", "最適化したプロンプトをファイルに保存"),
+    ("help.edit", "最適化したプロンプトをエディタで開く"),
+    ("help.docs", "選択したルールのドキュメントを開く"),
+    ("help.quit", "アプリケーションを終了"),
+    // Errors
+    ("error.label", "エラー:"),
+    (
+        "error.needs_terminal",
+        "インタラクティブモードにはターミナルが必要です。パイプ出力では -i を付けずに実行してください。",
+    ),
+    (
+        "error.no_prompt",
+        "プロンプトが指定されていません。使い方は --help を参照してください。",
+    ),
+];
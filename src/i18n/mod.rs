@@ -0,0 +1,134 @@
+//! Localized user-facing strings
+//!
+//! Messages are looked up by key in a per-locale catalog, falling back to
+//! English for anything a catalog doesn't cover. The locale comes from
+//! `--lang` (or `COPT_LANG`), then the standard `LC_ALL`, `LC_MESSAGES`,
+//! and `LANG` environment variables.
+
+mod en;
+mod es;
+mod ja;
+
+use clap::ValueEnum;
+use std::sync::OnceLock;
+
+/// Supported interface languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    /// English
+    En,
+    /// Spanish
+    Es,
+    /// Japanese
+    Ja,
+}
+
+impl Locale {
+    /// Parse a POSIX locale value such as `es_MX.UTF-8` or `ja`
+    pub fn from_posix(value: &str) -> Option<Self> {
+        let lang = value
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match lang.as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            "es" => Some(Locale::Es),
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    /// Locale from the environment, defaulting to English
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_posix(&value))
+            .unwrap_or(Locale::En)
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::Es => es::MESSAGES,
+            Locale::Ja => ja::MESSAGES,
+        }
+    }
+}
+
+/// Locale selected for this process
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Select the interface language (only the first call takes effect)
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Current interface language
+pub fn locale() -> Locale {
+    // Tests render in English regardless of the developer's environment
+    if cfg!(test) {
+        return *LOCALE.get_or_init(|| Locale::En);
+    }
+    *LOCALE.get_or_init(Locale::from_env)
+}
+
+/// Look up `key` in `locale`, falling back to English and then the key itself
+pub fn translate(locale: Locale, key: &'static str) -> &'static str {
+    let find = |catalog: &'static [(&'static str, &'static str)]| {
+        catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+    find(locale.catalog())
+        .or_else(|| find(en::MESSAGES))
+        .unwrap_or(key)
+}
+
+/// Localized message for `key` in the current locale
+pub fn t(key: &'static str) -> &'static str {
+    translate(locale(), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_posix() {
+        assert_eq!(Locale::from_posix("es_MX.UTF-8"), Some(Locale::Es));
+        assert_eq!(Locale::from_posix("ja_JP.UTF-8"), Some(Locale::Ja));
+        assert_eq!(Locale::from_posix("C.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::from_posix("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_catalogs_cover_every_english_key() {
+        for locale in [Locale::Es, Locale::Ja] {
+            for (key, _) in en::MESSAGES {
+                assert!(
+                    locale.catalog().iter().any(|(k, _)| k == key),
+                    "{:?} is missing {}",
+                    locale,
+                    key
+                );
+            }
+            for (key, _) in locale.catalog() {
+                assert!(
+                    en::MESSAGES.iter().any(|(k, _)| k == key),
+                    "{:?} has unknown key {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_falls_back() {
+        assert_eq!(translate(Locale::Es, "hint.quit"), "salir");
+        assert_eq!(translate(Locale::Ja, "hint.quit"), "終了");
+        assert_eq!(translate(Locale::En, "hint.quit"), "quit");
+        assert_eq!(translate(Locale::Es, "no.such.key"), "no.such.key");
+    }
+}
@@ -0,0 +1,84 @@
+//! copt - Claude Optimizer
+//!
+//! Library half of the `copt` binary: the analyzer, optimizer, LLM clients,
+//! and terminal UI. The CLI in `main.rs` wires them together; the split also
+//! lets the benchmarks in `benches/` call the analyzer directly.
+
+pub mod analyzer;
+pub mod cli;
+pub mod error;
+pub mod i18n;
+pub mod llm;
+pub mod optimizer;
+pub mod rules;
+pub mod tui;
+pub mod utils;
+
+// Re-export types from analyzer for use throughout the crate
+pub use analyzer::{Issue, Severity};
+pub use error::CoptError;
+
+/// Main optimization result structure
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    pub original: String,
+    pub optimized: String,
+    pub issues: Vec<Issue>,
+    pub stats: OptimizationStats,
+}
+
+/// Statistics about the optimization
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct OptimizationStats {
+    pub original_chars: usize,
+    pub optimized_chars: usize,
+    pub original_tokens: usize,
+    pub optimized_tokens: usize,
+    /// Number of issues the analyzer detected
+    pub issues_detected: usize,
+    pub categories_improved: usize,
+    pub processing_time_ms: u64,
+    pub provider: String,
+    pub model: String,
+    /// Heuristic quality score (0-100) of the original prompt
+    pub original_score: u8,
+    /// Heuristic quality score (0-100) of the optimized prompt
+    pub optimized_score: u8,
+    /// LLM judge rubric scores (with --judge)
+    pub judge: Option<llm::judge::JudgeComparison>,
+    /// Generated user messages to try against a system prompt (with --generate-tests)
+    pub test_queries: Vec<llm::test_queries::TestQuery>,
+    /// Static transforms that actually modified the prompt
+    pub transforms: optimizer::TransformReport,
+    /// Plain-language summary of what changed, one sentence per change
+    pub changelog: Vec<String>,
+    /// Ids of the `--enhance` guidance appended to the optimized prompt
+    pub enhancements: Vec<String>,
+    /// The LLM call hit `--timeout`; the optimized prompt is the static result
+    pub timed_out: bool,
+    /// The LLM call failed after analysis (including timeouts); the optimized
+    /// prompt is the static result
+    pub degraded: Option<error::ErrorInfo>,
+}
+
+impl OptimizationStats {
+    /// Copy with prompt text in the stats masked (`--redact`)
+    pub fn redacted(&self) -> Self {
+        let mut stats = self.clone();
+        // Judge rationales can quote the prompt
+        if let Some(judge) = stats.judge.as_mut() {
+            for scores in [&mut judge.original, &mut judge.optimized] {
+                scores.rationale = utils::redact::apply(&scores.rationale).into_owned();
+            }
+        }
+        for query in &mut stats.test_queries {
+            query.query = utils::redact::apply(&query.query).into_owned();
+        }
+        // Added sections are named after the prompt's own headings
+        for entry in &mut stats.changelog {
+            *entry = utils::redact::apply(entry).into_owned();
+        }
+        stats
+    }
+}
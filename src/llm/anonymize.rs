@@ -0,0 +1,414 @@
+//! Placeholders for sensitive details sent to the LLM (`--anonymize`)
+//!
+//! Before a request leaves the machine, emails, internal hostnames and
+//! private IP addresses, people's names, and company names are replaced
+//! with placeholders such as `{{PERSON_1}}`; the response gets the
+//! originals back. The same text always maps to the same placeholder for
+//! the whole run, so the model sees a consistent prompt and a retry or
+//! judge call refers to the same entities. Names and companies are found
+//! from `[anonymize]` in the config plus a few patterns (a title before a
+//! name, a legal suffix after a company). Unlike `--redact`, which masks
+//! reports, this only changes what the provider sees.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use super::judge::JUDGE_SYSTEM_PROMPT;
+use super::test_queries::TEST_QUERIES_SYSTEM_PROMPT;
+use super::{LlmClient, OPTIMIZER_SYSTEM_PROMPT};
+
+/// What a placeholder stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Person,
+    Company,
+    Email,
+    Host,
+    Ip,
+}
+
+impl EntityKind {
+    fn label(self) -> &'static str {
+        match self {
+            EntityKind::Person => "PERSON",
+            EntityKind::Company => "COMPANY",
+            EntityKind::Email => "EMAIL",
+            EntityKind::Host => "HOST",
+            EntityKind::Ip => "IP",
+        }
+    }
+}
+
+/// Names, companies, and domains to hide, from `[anonymize]`
+#[derive(Debug, Clone, Default)]
+pub struct Entities {
+    pub names: Vec<String>,
+    pub companies: Vec<String>,
+    /// Internal domains; hosts under them are hidden too
+    pub domains: Vec<String>,
+}
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").unwrap());
+
+/// Hosts under top-level names used only inside networks
+static INTERNAL_HOST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+(?:internal|corp|local|lan|intranet|private|home\.arpa)\b",
+    )
+    .unwrap()
+});
+
+/// RFC 1918 addresses
+static PRIVATE_IP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:10(?:\.\d{1,3}){3}|192\.168(?:\.\d{1,3}){2}|172\.(?:1[6-9]|2\d|3[01])(?:\.\d{1,3}){2})\b",
+    )
+    .unwrap()
+});
+
+/// A name after a title ("Dr. Ana Lopez"); the title stays
+static TITLED_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:Mr|Mrs|Ms|Mx|Dr|Prof)\.?\s+([A-Z][a-z]+(?:\s+[A-Z][a-z]+)?)").unwrap()
+});
+
+/// A company with a legal suffix ("Globex Corporation", "Initech, Inc.")
+static COMPANY_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b[A-Z][A-Za-z0-9&]*(?:\s+[A-Z][A-Za-z0-9&]*)?,?\s+(?:(?:Inc|Ltd|Corp)\b\.?|(?:LLC|Corporation|GmbH|PLC)\b)",
+    )
+    .unwrap()
+});
+
+/// A placeholder in a response, allowing for spaces the model may add
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*(PERSON|COMPANY|EMAIL|HOST|IP)_(\d+)\s*\}\}").unwrap());
+
+/// Replaces entities with placeholders and back
+#[derive(Debug)]
+pub struct Anonymizer {
+    /// Configured names, companies, and domains, by kind
+    listed: Vec<(Regex, EntityKind)>,
+    state: Mutex<Mapping>,
+}
+
+/// Placeholders handed out so far
+#[derive(Debug, Default)]
+struct Mapping {
+    by_text: HashMap<(EntityKind, String), String>,
+    originals: HashMap<String, String>,
+    counts: HashMap<EntityKind, usize>,
+}
+
+impl Anonymizer {
+    pub fn new(entities: Entities) -> Self {
+        let words = |list: &[String]| {
+            let mut escaped: Vec<String> = list
+                .iter()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(regex::escape)
+                .collect();
+            // Longest first, so "Ana Lopez" wins over "Ana"
+            escaped.sort_by_key(|s| std::cmp::Reverse(s.len()));
+            escaped
+        };
+        let mut listed = Vec::new();
+        for (list, kind) in [
+            (&entities.names, EntityKind::Person),
+            (&entities.companies, EntityKind::Company),
+        ] {
+            let list = words(list);
+            if !list.is_empty() {
+                let pattern = format!(r"(?i)\b(?:{})\b", list.join("|"));
+                listed.push((Regex::new(&pattern).unwrap(), kind));
+            }
+        }
+        let domains = words(&entities.domains);
+        if !domains.is_empty() {
+            let pattern = format!(
+                r"(?i)\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)*(?:{})\b",
+                domains.join("|")
+            );
+            listed.push((Regex::new(&pattern).unwrap(), EntityKind::Host));
+        }
+        Self {
+            listed,
+            state: Mutex::new(Mapping::default()),
+        }
+    }
+
+    /// `text` with every entity replaced by its placeholder
+    pub fn anonymize(&self, text: &str) -> String {
+        let mut found: Vec<(Range<usize>, EntityKind)> = Vec::new();
+        // Emails first: the domain inside one isn't a separate host
+        let patterns = [
+            (&*EMAIL, EntityKind::Email),
+            (&*INTERNAL_HOST, EntityKind::Host),
+            (&*PRIVATE_IP, EntityKind::Ip),
+            (&*COMPANY_SUFFIX, EntityKind::Company),
+        ];
+        for (pattern, kind) in patterns
+            .into_iter()
+            .chain(self.listed.iter().map(|(p, k)| (p, *k)))
+        {
+            found.extend(pattern.find_iter(text).map(|m| (m.range(), kind)));
+        }
+        found.extend(
+            TITLED_NAME
+                .captures_iter(text)
+                .filter_map(|c| c.get(1))
+                .map(|m| (m.range(), EntityKind::Person)),
+        );
+        // Earliest first, and the longest of those starting together
+        found.sort_by_key(|(range, _)| (range.start, std::cmp::Reverse(range.end)));
+
+        let mut state = self.state.lock().unwrap();
+        let mut out = String::with_capacity(text.len());
+        let mut end = 0;
+        for (range, kind) in found {
+            if range.start < end {
+                continue;
+            }
+            out.push_str(&text[end..range.start]);
+            out.push_str(&state.placeholder(kind, &text[range.clone()], text));
+            end = range.end;
+        }
+        out.push_str(&text[end..]);
+        out
+    }
+
+    /// `text` with the placeholders handed out so far replaced by the originals
+    pub fn restore(&self, text: &str) -> String {
+        let state = self.state.lock().unwrap();
+        PLACEHOLDER
+            .replace_all(text, |c: &Captures| {
+                let placeholder = format!("{{{{{}_{}}}}}", &c[1], &c[2]);
+                state
+                    .originals
+                    .get(&placeholder)
+                    .cloned()
+                    .unwrap_or_else(|| c[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// How many distinct entities have been replaced
+    pub fn count(&self) -> usize {
+        self.state.lock().unwrap().originals.len()
+    }
+}
+
+impl Mapping {
+    /// The placeholder for `original`, handing out the next one if it is new
+    ///
+    /// Skips placeholders `text` already contains, so restoring can't touch
+    /// the prompt's own text.
+    fn placeholder(&mut self, kind: EntityKind, original: &str, text: &str) -> String {
+        let key = (kind, original.to_string());
+        if let Some(placeholder) = self.by_text.get(&key) {
+            return placeholder.clone();
+        }
+        let placeholder = loop {
+            let count = self.counts.entry(kind).or_default();
+            *count += 1;
+            let placeholder = format!("{{{{{}_{}}}}}", kind.label(), count);
+            if !text.contains(&placeholder) {
+                break placeholder;
+            }
+        };
+        self.by_text.insert(key, placeholder.clone());
+        self.originals
+            .insert(placeholder.clone(), original.to_string());
+        placeholder
+    }
+}
+
+/// Anonymizer for this run, set only when `--anonymize` was given
+static ANONYMIZER: OnceLock<Anonymizer> = OnceLock::new();
+
+/// Anonymize every LLM request for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn enable(entities: Entities) {
+    let _ = ANONYMIZER.set(Anonymizer::new(entities));
+}
+
+/// Whether `--anonymize` is in effect
+pub fn enabled() -> bool {
+    ANONYMIZER.get().is_some()
+}
+
+/// How many distinct entities this run has kept from the provider
+pub fn count() -> usize {
+    ANONYMIZER.get().map_or(0, Anonymizer::count)
+}
+
+/// copt's own instructions to the model, sent as the system prompt as-is
+///
+/// Any other system prompt is the user's (`copt verify`, `copt eval`) and
+/// is anonymized like the message.
+const META_PROMPTS: &[&str] = &[
+    OPTIMIZER_SYSTEM_PROMPT,
+    JUDGE_SYSTEM_PROMPT,
+    TEST_QUERIES_SYSTEM_PROMPT,
+];
+
+/// Client that sends placeholders and returns the originals
+pub struct AnonymizingClient {
+    inner: Box<dyn LlmClient>,
+}
+
+impl AnonymizingClient {
+    pub fn new(inner: Box<dyn LlmClient>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl LlmClient for AnonymizingClient {
+    async fn complete(
+        &self,
+        system: &str,
+        user_message: &str,
+        model: &str,
+        max_tokens: u32,
+    ) -> Result<String> {
+        let Some(anonymizer) = ANONYMIZER.get() else {
+            return self
+                .inner
+                .complete(system, user_message, model, max_tokens)
+                .await;
+        };
+        let system = match META_PROMPTS.contains(&system) {
+            true => system.to_string(),
+            false => anonymizer.anonymize(system),
+        };
+        let anonymized = anonymizer.anonymize(user_message);
+        let response = self
+            .inner
+            .complete(&system, &anonymized, model, max_tokens)
+            .await?;
+        Ok(anonymizer.restore(&response))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Client that keeps the system prompt and message it was sent and
+    /// echoes the message back
+    struct Echo(Arc<Mutex<(String, String)>>);
+
+    #[async_trait]
+    impl LlmClient for Echo {
+        async fn complete(
+            &self,
+            system: &str,
+            user_message: &str,
+            _: &str,
+            _: u32,
+        ) -> Result<String> {
+            *self.0.lock().unwrap() = (system.to_string(), user_message.to_string());
+            Ok(format!("Rewritten: {}", user_message))
+        }
+
+        fn provider_name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    fn anonymizer() -> Anonymizer {
+        Anonymizer::new(Entities {
+            names: vec!["Priya".into(), "Priya Raman".into()],
+            companies: vec!["Initrode".into()],
+            domains: vec!["initrode.io".into()],
+        })
+    }
+
+    #[test]
+    fn test_entities_get_consistent_placeholders() {
+        let anonymizer = anonymizer();
+        let text = "You support Initrode. Escalate to Priya Raman (priya@initrode.io) \
+                    or Dr. Sam Okafor; logs are on build.initrode.io, db.corp, and 10.2.3.4. \
+                    Never mention Globex Corporation. Priya Raman owns billing.";
+        let anonymized = anonymizer.anonymize(text);
+        assert_eq!(
+            anonymized,
+            "You support {{COMPANY_1}}. Escalate to {{PERSON_1}} ({{EMAIL_1}}) \
+             or Dr. {{PERSON_2}}; logs are on {{HOST_1}}, {{HOST_2}}, and {{IP_1}}. \
+             Never mention {{COMPANY_2}}. {{PERSON_1}} owns billing."
+        );
+        assert_eq!(anonymizer.count(), 8);
+        assert_eq!(anonymizer.restore(&anonymized), text);
+        // Spacing the model adds is tolerated; unknown placeholders stay
+        assert_eq!(
+            anonymizer.restore("Ask {{ PERSON_2 }} about {{PERSON_9}}."),
+            "Ask Sam Okafor about {{PERSON_9}}."
+        );
+    }
+
+    #[test]
+    fn test_existing_placeholders_are_not_reused() {
+        let anonymizer = Anonymizer::new(Entities::default());
+        let anonymized = anonymizer.anonymize("Email ops@example.com, not {{EMAIL_1}}.");
+        assert_eq!(anonymized, "Email {{EMAIL_2}}, not {{EMAIL_1}}.");
+        assert_eq!(
+            anonymizer.restore(&anonymized),
+            "Email ops@example.com, not {{EMAIL_1}}."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_placeholders_and_restores_the_response() {
+        enable(Entities::default());
+        let sent = Arc::new(Mutex::new(Default::default()));
+        let client = AnonymizingClient::new(Box::new(Echo(sent.clone())));
+        let response = client
+            .complete(
+                OPTIMIZER_SYSTEM_PROMPT,
+                "Page oncall@example.com about 192.168.1.20.",
+                "sonnet",
+                100,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *sent.lock().unwrap(),
+            (
+                OPTIMIZER_SYSTEM_PROMPT.to_string(),
+                "Page {{EMAIL_1}} about {{IP_1}}.".to_string()
+            )
+        );
+        assert_eq!(
+            response,
+            "Rewritten: Page oncall@example.com about 192.168.1.20."
+        );
+
+        // The user's prompt as the system prompt (`copt verify`, `copt eval`)
+        client
+            .complete(
+                "You route tickets for Globex Corporation. Escalate to oncall@example.com.",
+                "Where do I send this?",
+                "sonnet",
+                100,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            sent.lock().unwrap().0,
+            "You route tickets for {{COMPANY_1}}. Escalate to {{EMAIL_1}}."
+        );
+    }
+}
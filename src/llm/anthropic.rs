@@ -1,13 +1,16 @@
 //! Anthropic API client implementation
 //!
-//! Provides direct access to the Anthropic Claude API.
+//! Provides direct access to the Anthropic Claude API, or to the Messages
+//! API behind an enterprise gateway that needs its own headers or tokens.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use super::LlmClient;
+use crate::error::{CoptError, ProviderErrorKind};
 
 /// Anthropic API base URL
 const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
@@ -18,7 +21,35 @@ const ANTHROPIC_VERSION: &str = "2023-06-01";
 /// Anthropic API client
 pub struct AnthropicClient {
     client: reqwest::Client,
+    /// Sent as `x-api-key` unless empty (a gateway may authenticate instead)
     api_key: String,
+    /// Messages endpoint
+    url: String,
+    /// Gateway headers, sent with every request
+    extra_headers: HeaderMap,
+}
+
+/// How to reach the Messages API through a gateway (`[anthropic]` config)
+#[derive(Debug, Clone, Default)]
+pub struct GatewayOptions {
+    /// Gateway base URL; requests go to `<base_url>/v1/messages`
+    pub base_url: Option<String>,
+    /// Extra headers; `${VAR}` in a value is replaced by that environment variable
+    pub headers: BTreeMap<String, String>,
+    /// Shell command printing a token (e.g. an OAuth access token), run once per client
+    pub auth_command: Option<String>,
+    /// Header carrying the `auth_command` token; `Authorization` gets a `Bearer` scheme
+    pub auth_header: String,
+    /// Also send the Anthropic API key to `base_url`; off by default so the
+    /// key never reaches a third-party host by accident
+    pub send_api_key: bool,
+}
+
+impl GatewayOptions {
+    /// Whether requests go somewhere other than api.anthropic.com
+    pub fn is_gateway(&self) -> bool {
+        self.base_url.is_some()
+    }
 }
 
 impl AnthropicClient {
@@ -29,8 +60,126 @@ impl AnthropicClient {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client, api_key })
+        Ok(Self {
+            client,
+            api_key,
+            url: ANTHROPIC_API_URL.to_string(),
+            extra_headers: HeaderMap::new(),
+        })
+    }
+
+    /// Create a client with the saved key: OS keychain first, then `ANTHROPIC_API_KEY`
+    pub fn from_saved_key() -> Result<Self> {
+        let (api_key, source) = super::credentials::anthropic_api_key()?;
+        tracing::debug!(%source, "Anthropic API key");
+        Self::new(api_key)
+    }
+
+    /// Send requests through the gateway described by `gateway`
+    ///
+    /// The API key is dropped unless `send_api_key` is set. Runs
+    /// `auth_command` now, so a failing token command is reported
+    /// before any prompt is sent.
+    pub fn with_gateway(mut self, gateway: &GatewayOptions) -> Result<Self> {
+        if let Some(ref base_url) = gateway.base_url {
+            self.url = messages_url(base_url);
+            if !gateway.send_api_key {
+                self.api_key.clear();
+            }
+        }
+
+        for (name, value) in &gateway.headers {
+            let value = expand_env(value)
+                .with_context(|| format!("Failed to build gateway header {}", name))?;
+            insert_header(&mut self.extra_headers, name, &value)?;
+        }
+
+        if let Some(ref command) = gateway.auth_command {
+            let token = run_auth_command(command)?;
+            let header = if gateway.auth_header.is_empty() {
+                "Authorization"
+            } else {
+                gateway.auth_header.as_str()
+            };
+            let value = if header.eq_ignore_ascii_case("authorization") && !token.contains(' ') {
+                format!("Bearer {}", token)
+            } else {
+                token
+            };
+            insert_header(&mut self.extra_headers, header, &value)?;
+        }
+        Ok(self)
+    }
+}
+
+/// `<base_url>/v1/messages`, unless `base_url` already names the endpoint
+fn messages_url(base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    if base.ends_with("/v1/messages") {
+        base.to_string()
+    } else {
+        format!("{}/v1/messages", base)
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) -> Result<()> {
+    let name = HeaderName::from_bytes(name.as_bytes())
+        .with_context(|| format!("Invalid gateway header name: {}", name))?;
+    let mut value = HeaderValue::from_str(value)
+        .with_context(|| format!("Invalid value for gateway header {}", name))?;
+    value.set_sensitive(true);
+    headers.insert(name, value);
+    Ok(())
+}
+
+/// Replace each `${VAR}` in `value` with the environment variable
+fn expand_env(value: &str) -> Result<String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let var = &rest[start + 2..start + 2 + len];
+        let resolved = std::env::var(var)
+            .with_context(|| format!("Environment variable {} is not set", var))?;
+        out.push_str(&rest[..start]);
+        out.push_str(&resolved);
+        rest = &rest[start + 3 + len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Run `command` through the shell and return its trimmed stdout
+fn run_auth_command(command: &str) -> Result<String> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    let output = cmd
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run auth_command: {}", command))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "auth_command exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        anyhow::bail!("auth_command printed no token: {}", command);
     }
+    Ok(token)
 }
 
 #[async_trait]
@@ -43,7 +192,7 @@ impl LlmClient for AnthropicClient {
         max_tokens: u32,
     ) -> Result<String> {
         let request = AnthropicRequest {
-            model: model.to_string(),
+            model: super::models::anthropic_id(model),
             max_tokens,
             temperature: Some(0.3),
             system: Some(system.to_string()),
@@ -55,18 +204,22 @@ impl LlmClient for AnthropicClient {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert(
-            "x-api-key",
-            HeaderValue::from_str(&self.api_key).context("Invalid API key format")?,
-        );
+        if !self.api_key.is_empty() {
+            headers.insert(
+                "x-api-key",
+                HeaderValue::from_str(&self.api_key).context("Invalid API key format")?,
+            );
+        }
         headers.insert(
             "anthropic-version",
             HeaderValue::from_static(ANTHROPIC_VERSION),
         );
+        // Gateway headers win, e.g. a gateway that replaces x-api-key
+        headers.extend(self.extra_headers.clone());
 
         let response = self
             .client
-            .post(ANTHROPIC_API_URL)
+            .post(&self.url)
             .headers(headers)
             .json(&request)
             .send()
@@ -76,11 +229,14 @@ impl LlmClient for AnthropicClient {
         let status = response.status();
         if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!(
-                "Anthropic API request failed with status {}: {}",
-                status,
-                error_text
-            );
+            return Err(CoptError::provider(
+                status_kind(status),
+                format!(
+                    "Anthropic API request failed with status {}: {}",
+                    status, error_text
+                ),
+            )
+            .into());
         }
 
         let api_response: AnthropicResponse = response
@@ -88,6 +244,14 @@ impl LlmClient for AnthropicClient {
             .await
             .context("Failed to parse Anthropic API response")?;
 
+        if let Some(ref usage) = api_response.usage {
+            tracing::debug!(
+                input_tokens = usage.input_tokens,
+                output_tokens = usage.output_tokens,
+                "Anthropic usage"
+            );
+        }
+
         // Extract text from the first content block
         let text = api_response
             .content
@@ -137,7 +301,6 @@ struct AnthropicResponse {
     model: String,
     #[allow(dead_code)]
     stop_reason: Option<String>,
-    #[allow(dead_code)]
     usage: Option<AnthropicUsage>,
 }
 
@@ -152,12 +315,24 @@ struct ContentBlock {
 
 /// Usage statistics
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct AnthropicUsage {
     input_tokens: u32,
     output_tokens: u32,
 }
 
+/// Classify a failed Messages API response by its status
+fn status_kind(status: reqwest::StatusCode) -> ProviderErrorKind {
+    match status.as_u16() {
+        401 | 403 => ProviderErrorKind::Auth,
+        // 529 is Anthropic's "overloaded"
+        429 | 529 => ProviderErrorKind::Throttled,
+        404 => ProviderErrorKind::ModelUnavailable,
+        408 | 504 => ProviderErrorKind::Timeout,
+        400..=499 => ProviderErrorKind::InvalidRequest,
+        _ => ProviderErrorKind::Other,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +348,139 @@ mod tests {
         let client = AnthropicClient::new("test-api-key".to_string()).unwrap();
         assert_eq!(client.provider_name(), "anthropic");
     }
+
+    #[test]
+    fn test_messages_url() {
+        assert_eq!(
+            messages_url("https://gw.example.com/anthropic/"),
+            "https://gw.example.com/anthropic/v1/messages"
+        );
+        assert_eq!(
+            messages_url("https://gw.example.com/v1/messages"),
+            "https://gw.example.com/v1/messages"
+        );
+    }
+
+    #[test]
+    fn test_expand_env() {
+        std::env::set_var("COPT_TEST_GATEWAY_KEY", "k-123");
+        assert_eq!(
+            expand_env("Key ${COPT_TEST_GATEWAY_KEY}!").unwrap(),
+            "Key k-123!"
+        );
+        assert_eq!(expand_env("no vars").unwrap(), "no vars");
+        assert!(expand_env("${COPT_TEST_UNSET_VAR}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_headers_and_auth_command() {
+        use wiremock::matchers::{header, header_exists, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/anthropic/v1/messages"))
+            .and(header("x-api-gateway-key", "team-key"))
+            .and(header("authorization", "Bearer token-abc"))
+            .and(header_exists("anthropic-version"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{"type": "text", "text": "Rewritten"}],
+                "model": "claude-sonnet-4-5-20250929",
+                "stop_reason": "end_turn"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let gateway = GatewayOptions {
+            base_url: Some(format!("{}/anthropic", server.uri())),
+            headers: BTreeMap::from([("x-api-gateway-key".to_string(), "team-key".to_string())]),
+            auth_command: Some("echo token-abc".to_string()),
+            auth_header: "Authorization".to_string(),
+            send_api_key: false,
+        };
+        let client = AnthropicClient::new("sk-ant-secret".to_string())
+            .unwrap()
+            .with_gateway(&gateway)
+            .unwrap();
+        let text = client.complete("system", "hi", "sonnet", 16).await.unwrap();
+        assert_eq!(text, "Rewritten");
+        // The Anthropic key stays home unless the config opts in
+        let requests = server.received_requests().await.unwrap();
+        assert!(!requests[0].headers.contains_key("x-api-key"));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_gets_the_api_key_on_request() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("x-api-key", "sk-ant-secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{"type": "text", "text": "Rewritten"}],
+                "model": "claude-sonnet-4-5-20250929",
+                "stop_reason": "end_turn"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let gateway = GatewayOptions {
+            base_url: Some(server.uri()),
+            send_api_key: true,
+            ..Default::default()
+        };
+        let client = AnthropicClient::new("sk-ant-secret".to_string())
+            .unwrap()
+            .with_gateway(&gateway)
+            .unwrap();
+        assert_eq!(
+            client.complete("system", "hi", "sonnet", 16).await.unwrap(),
+            "Rewritten"
+        );
+    }
+
+    #[test]
+    fn test_failing_auth_command() {
+        let gateway = GatewayOptions {
+            auth_command: Some("echo denied >&2; exit 3".to_string()),
+            ..Default::default()
+        };
+        let err = AnthropicClient::new(String::new())
+            .unwrap()
+            .with_gateway(&gateway)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("denied"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_a_typed_error() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("rate_limit_error"))
+            .mount(&server)
+            .await;
+
+        let gateway = GatewayOptions {
+            base_url: Some(server.uri()),
+            ..Default::default()
+        };
+        let client = AnthropicClient::new("test-api-key".to_string())
+            .unwrap()
+            .with_gateway(&gateway)
+            .unwrap();
+        let err = client
+            .complete("system", "hi", "sonnet", 16)
+            .await
+            .unwrap_err();
+        let info = crate::error::ErrorInfo::from_error(&err);
+        assert_eq!(info.code, "provider_throttled");
+        assert!(info.retryable);
+    }
 }
@@ -10,6 +10,7 @@ use aws_sdk_bedrockruntime::Client as BedrockRuntimeClient;
 use serde::{Deserialize, Serialize};
 
 use super::LlmClient;
+use crate::error::{CoptError, ProviderErrorKind};
 
 /// AWS Bedrock client
 pub struct BedrockClient {
@@ -69,90 +70,82 @@ impl BedrockClient {
             .send()
             .await;
 
-        match result {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                let error_str = format!("{:?}", e);
-
-                // Provide helpful error messages based on common failure modes
-                if error_str.contains("credentials")
-                    || error_str.contains("NoCredentialsError")
-                    || error_str.contains("ExpiredToken")
-                    || error_str.contains("InvalidIdentityToken")
-                {
-                    anyhow::bail!(
-                        "AWS credentials not found or invalid.\n\n\
-                        Please ensure you have valid AWS credentials configured:\n\
-                        • Set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment variables, or\n\
-                        • Configure credentials in ~/.aws/credentials, or\n\
-                        • Use AWS SSO: run 'aws sso login'\n\n\
-                        Region: {}\n\
-                        Error: {}",
-                        self.region,
-                        e
-                    );
-                } else if error_str.contains("AccessDenied")
-                    || error_str.contains("UnauthorizedAccess")
-                {
-                    anyhow::bail!(
-                        "Access denied to AWS Bedrock.\n\n\
-                        Your AWS credentials are valid but don't have permission to access Bedrock.\n\
-                        Please ensure:\n\
-                        • Your IAM user/role has the 'bedrock:InvokeModel' permission\n\
-                        • You have requested access to Claude models in the Bedrock console\n\n\
-                        Region: {}\n\
-                        Model: {}\n\
-                        Error: {}",
-                        self.region,
-                        model_id,
-                        e
-                    );
-                } else if error_str.contains("ResourceNotFoundException")
-                    || error_str.contains("ValidationException")
-                    || error_str.contains("model")
-                {
-                    anyhow::bail!(
-                        "Model not available in AWS Bedrock.\n\n\
-                        The specified model may not be available in your region or account.\n\
-                        Please ensure:\n\
-                        • You have enabled the model in AWS Bedrock console\n\
-                        • The model is available in the '{}' region\n\
-                        • You're using the correct model ID\n\n\
-                        Model: {}\n\
-                        Error: {}",
-                        self.region,
-                        model_id,
-                        e
-                    );
-                } else if error_str.contains("timeout")
-                    || error_str.contains("connect")
-                    || error_str.contains("network")
-                {
-                    anyhow::bail!(
-                        "Network error connecting to AWS Bedrock.\n\n\
-                        Please check your internet connection and try again.\n\n\
-                        Region: {}\n\
-                        Error: {}",
-                        self.region,
-                        e
-                    );
-                } else if error_str.contains("ThrottlingException") {
-                    // Throttling actually means we connected successfully!
-                    // The credentials work, we just hit a rate limit
-                    Ok(())
-                } else {
-                    anyhow::bail!(
-                        "Failed to connect to AWS Bedrock.\n\n\
-                        Region: {}\n\
-                        Model: {}\n\
-                        Error: {}",
-                        self.region,
-                        model_id,
-                        e
-                    );
-                }
+        let e = match result {
+            Ok(_) => return Ok(()),
+            Err(e) => e,
+        };
+        let error_str = format!("{:?}", e);
+        let kind = error_kind(&error_str);
+
+        // Provide helpful error messages based on common failure modes
+        let message = match kind {
+            // Throttling actually means we connected successfully!
+            // The credentials work, we just hit a rate limit
+            ProviderErrorKind::Throttled => return Ok(()),
+            ProviderErrorKind::Auth
+                if !error_str.contains("AccessDenied")
+                    && !error_str.contains("UnauthorizedAccess") =>
+            {
+                format!(
+                    "AWS credentials not found or invalid.\n\n\
+                    Please ensure you have valid AWS credentials configured:\n\
+                    • Set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment variables, or\n\
+                    • Configure credentials in ~/.aws/credentials, or\n\
+                    • Use AWS SSO: run 'aws sso login'\n\n\
+                    Region: {}\n\
+                    Error: {}",
+                    self.region, e
+                )
             }
-        }
+            ProviderErrorKind::Auth => format!(
+                "Access denied to AWS Bedrock.\n\n\
+                Your AWS credentials are valid but don't have permission to access Bedrock.\n\
+                Please ensure:\n\
+                • Your IAM user/role has the 'bedrock:InvokeModel' permission\n\
+                • You have requested access to Claude models in the Bedrock console\n\n\
+                Region: {}\n\
+                Model: {}\n\
+                Error: {}",
+                self.region, model_id, e
+            ),
+            ProviderErrorKind::ModelUnavailable | ProviderErrorKind::InvalidRequest => {
+                let regions = super::regions::profile_regions(&model_id)
+                    .map(|regions| format!("Regions for this profile: {}\n", regions.join(", ")))
+                    .unwrap_or_default();
+                format!(
+                    "Model not available in AWS Bedrock.\n\n\
+                    The specified model may not be available in your region or account.\n\
+                    Please ensure:\n\
+                    • You have enabled the model in AWS Bedrock console\n\
+                    • The model is available in the '{}' region\n\
+                    • You're using the correct model ID\n\n\
+                    Model: {}\n\
+                    {}\
+                    Error: {}",
+                    self.region, model_id, regions, e
+                )
+            }
+            ProviderErrorKind::Timeout | ProviderErrorKind::Network => format!(
+                "Network error connecting to AWS Bedrock.\n\n\
+                Please check your internet connection and try again.\n\n\
+                Region: {}\n\
+                Error: {}",
+                self.region, e
+            ),
+            ProviderErrorKind::Other | ProviderErrorKind::Refused => format!(
+                "Failed to connect to AWS Bedrock.\n\n\
+                Region: {}\n\
+                Model: {}\n\
+                Error: {}",
+                self.region, model_id, e
+            ),
+        };
+        // Any failure here means the model can't be used from this region
+        let kind = match kind {
+            ProviderErrorKind::InvalidRequest => ProviderErrorKind::ModelUnavailable,
+            kind => kind,
+        };
+        Err(CoptError::provider(kind, message).into())
     }
 
     /// Get the configured region
@@ -161,38 +154,10 @@ impl BedrockClient {
     }
 
     /// Convert Anthropic model ID to Bedrock inference profile model ID
+    ///
+    /// Inference profile ARNs are sent as written.
     fn get_bedrock_model_id(model: &str) -> String {
-        match model {
-            // Direct inference profile IDs - pass through
-            m if m.starts_with("us.anthropic.") || m.starts_with("global.anthropic.") => {
-                m.to_string()
-            }
-
-            // Map short names to inference profile IDs
-            "claude-sonnet-4-5-20250929" | "claude-sonnet-4.5" | "sonnet-4.5" | "sonnet" => {
-                "us.anthropic.claude-sonnet-4-5-20250929-v1:0".to_string()
-            }
-            "claude-haiku-4-5-20251001" | "claude-haiku-4.5" | "haiku-4.5" | "haiku" => {
-                "us.anthropic.claude-haiku-4-5-20251001-v1:0".to_string()
-            }
-            "claude-opus-4-5-20251101" | "claude-opus-4.5" | "opus-4.5" | "opus" => {
-                "global.anthropic.claude-opus-4-5-20251101-v1:0".to_string()
-            }
-
-            // Legacy model ID format - convert to inference profile
-            "anthropic.claude-sonnet-4-5-20250929-v1:0" => {
-                "us.anthropic.claude-sonnet-4-5-20250929-v1:0".to_string()
-            }
-            "anthropic.claude-haiku-4-5-20251001-v1:0" => {
-                "us.anthropic.claude-haiku-4-5-20251001-v1:0".to_string()
-            }
-            "anthropic.claude-opus-4-5-20251101-v1:0" => {
-                "global.anthropic.claude-opus-4-5-20251101-v1:0".to_string()
-            }
-
-            // Default: assume it's already a valid model ID
-            other => other.to_string(),
-        }
+        super::models::bedrock_id(model)
     }
 }
 
@@ -232,12 +197,24 @@ impl LlmClient for BedrockClient {
             .body(Blob::new(body_bytes))
             .send()
             .await
-            .context("Failed to invoke Bedrock model")?;
+            .map_err(|e| {
+                let kind = error_kind(&format!("{:?}", e));
+                anyhow::Error::new(e)
+                    .context(CoptError::provider(kind, "Failed to invoke Bedrock model"))
+            })?;
 
         let response_bytes = response.body.as_ref();
         let api_response: BedrockResponse =
             serde_json::from_slice(response_bytes).context("Failed to parse Bedrock response")?;
 
+        if let Some(ref usage) = api_response.usage {
+            tracing::debug!(
+                input_tokens = usage.input_tokens,
+                output_tokens = usage.output_tokens,
+                "Bedrock usage"
+            );
+        }
+
         // Extract text from the first content block
         let text = api_response
             .content
@@ -285,7 +262,6 @@ struct BedrockResponse {
     content: Vec<ContentBlock>,
     #[allow(dead_code)]
     stop_reason: Option<String>,
-    #[allow(dead_code)]
     usage: Option<BedrockUsage>,
 }
 
@@ -300,12 +276,49 @@ struct ContentBlock {
 
 /// Usage statistics from Bedrock
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct BedrockUsage {
     input_tokens: u32,
     output_tokens: u32,
 }
 
+/// Classify a Bedrock SDK error from its debug output
+fn error_kind(error_str: &str) -> ProviderErrorKind {
+    let has = |needles: &[&str]| needles.iter().any(|n| error_str.contains(n));
+    if has(&["ThrottlingException", "ServiceQuotaExceededException"]) {
+        ProviderErrorKind::Throttled
+    } else if has(&["don't have access to the model"]) {
+        // AccessDeniedException for a model that isn't enabled in the account
+        ProviderErrorKind::ModelUnavailable
+    } else if has(&[
+        "credentials",
+        "NoCredentialsError",
+        "ExpiredToken",
+        "InvalidIdentityToken",
+        "AccessDenied",
+        "UnauthorizedAccess",
+    ]) {
+        ProviderErrorKind::Auth
+    } else if has(&["ModelTimeoutException", "TimeoutError"]) {
+        ProviderErrorKind::Timeout
+    } else if has(&[
+        "ModelNotReadyException",
+        "ServiceUnavailableException",
+        "InternalServerException",
+    ]) {
+        ProviderErrorKind::Other
+    } else if has(&["ResourceNotFoundException"]) {
+        ProviderErrorKind::ModelUnavailable
+    } else if has(&["ValidationException"]) {
+        ProviderErrorKind::InvalidRequest
+    } else if has(&["DispatchFailure", "timeout", "connect", "network"]) {
+        ProviderErrorKind::Network
+    } else if error_str.contains("model") {
+        ProviderErrorKind::ModelUnavailable
+    } else {
+        ProviderErrorKind::Other
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -354,4 +367,40 @@ mod tests {
             "us.anthropic.claude-sonnet-4-5-20250929-v1:0"
         );
     }
+
+    #[test]
+    fn test_model_id_conversion_arn() {
+        let arn =
+            "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/a1b2c3d4e5f6";
+        assert_eq!(BedrockClient::get_bedrock_model_id(arn), arn);
+        let system = "arn:aws:bedrock:us-east-1:123456789012:inference-profile/us.anthropic.claude-sonnet-4-5-20250929-v1:0";
+        assert_eq!(BedrockClient::get_bedrock_model_id(system), system);
+    }
+
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(
+            error_kind("ServiceError { source: ThrottlingException(…) }"),
+            ProviderErrorKind::Throttled
+        );
+        assert_eq!(
+            error_kind("ServiceError { source: AccessDeniedException(…) }"),
+            ProviderErrorKind::Auth
+        );
+        assert_eq!(
+            error_kind(
+                "AccessDeniedException { message: \"You don't have access to the model with the specified model ID.\" }"
+            ),
+            ProviderErrorKind::ModelUnavailable
+        );
+        assert_eq!(
+            error_kind("ServiceError { source: ResourceNotFoundException(…) }"),
+            ProviderErrorKind::ModelUnavailable
+        );
+        assert_eq!(
+            error_kind("DispatchFailure(DispatchFailure { source: ConnectorError … })"),
+            ProviderErrorKind::Network
+        );
+        assert!(error_kind("ServiceError { source: ModelNotReadyException(…) }").retryable());
+    }
 }
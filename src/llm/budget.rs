@@ -0,0 +1,322 @@
+//! Spend limits for LLM calls (`--max-cost`, `[budget] monthly_cap`)
+//!
+//! Before each call, [`BudgetClient`] estimates its cost from the request's
+//! tokens and the model's list price, and refuses a call that would take
+//! the run past `--max-cost` or the month past the monthly cap. A call's
+//! estimate counts against the limits while it is in flight, so concurrent
+//! calls can't overshoot them together. Estimates use the same token
+//! heuristic as the rest of copt, so they are close, not billing-exact.
+//! What each run spent goes into the local metrics file, which is where the
+//! monthly total comes from.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use super::models::{self, MODELS};
+use super::LlmClient;
+use crate::error::CoptError;
+use crate::utils::text::count_tokens;
+
+/// Limits for this process
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Budget {
+    /// `--max-cost`, in USD
+    pub max_cost: Option<f64>,
+    /// `[budget] monthly_cap`, in USD
+    pub monthly_cap: Option<f64>,
+    /// Spent earlier this month, in USD
+    pub month_spent: f64,
+}
+
+/// A call over a limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exceeded {
+    /// `--max-cost`; `total` is the run's spend including the call
+    Run { limit: f64, total: f64 },
+    /// `[budget] monthly_cap`; `total` is the month's spend including the call
+    Month { cap: f64, total: f64 },
+}
+
+impl Exceeded {
+    /// Why a call costing about `cost` was refused
+    pub fn message(&self, cost: f64) -> String {
+        match *self {
+            Exceeded::Run { limit, total } => format!(
+                "This LLM call (about {}) would bring the run to {}, over --max-cost {}",
+                dollars(cost),
+                dollars(total),
+                dollars(limit)
+            ),
+            Exceeded::Month { cap, total } => format!(
+                "This LLM call (about {}) would bring this month's spend to {}, over the \
+                [budget] monthly_cap of {}",
+                dollars(cost),
+                dollars(total),
+                dollars(cap)
+            ),
+        }
+    }
+}
+
+static BUDGET: OnceLock<Budget> = OnceLock::new();
+
+/// Estimated spend of this process, which `--max-cost` caps
+static SPENT: Mutex<f64> = Mutex::new(0.0);
+
+/// The part of [`SPENT`] already handed out by [`take_spent`]
+static RECORDED: Mutex<f64> = Mutex::new(0.0);
+
+/// Estimates of calls in flight, held against the limits until they finish
+///
+/// Locked after [`SPENT`] and before [`RECORDED`] wherever more than one is
+/// held.
+static RESERVED: Mutex<f64> = Mutex::new(0.0);
+
+/// The user agreed to go over the monthly cap for this run
+static MONTH_OVERRUN_ACCEPTED: AtomicBool = AtomicBool::new(false);
+
+/// Apply limits for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(budget: Budget) {
+    let _ = BUDGET.set(budget);
+}
+
+/// Let calls past the monthly cap through for the rest of the run
+pub fn accept_month_overrun() {
+    MONTH_OVERRUN_ACCEPTED.store(true, Ordering::Relaxed);
+}
+
+/// USD per million input and output tokens for `model`
+///
+/// Models outside the catalog (inference profile ARNs, new releases) are
+/// priced like the most expensive one, so a limit is never undercounted.
+pub fn prices(model: &str) -> (f64, f64) {
+    match models::lookup(model) {
+        Some(info) => (info.input_price, info.output_price),
+        None => MODELS
+            .iter()
+            .map(|info| (info.input_price, info.output_price))
+            .fold((0.0, 0.0), |(a, b), (c, d)| (a.max(c), b.max(d))),
+    }
+}
+
+/// Estimated cost of a call, in USD
+///
+/// The response is assumed to be about as long as the user message (a
+/// rewrite of the prompt in it), up to `max_tokens`.
+pub fn estimate(model: &str, system: &str, user_message: &str, max_tokens: u32) -> f64 {
+    let user_tokens = count_tokens(user_message);
+    let input = count_tokens(system) + user_tokens;
+    let output = user_tokens.min(max_tokens as usize);
+    cost(model, input, output)
+}
+
+fn cost(model: &str, input_tokens: usize, output_tokens: usize) -> f64 {
+    let (input_price, output_price) = prices(model);
+    (input_tokens as f64 * input_price + output_tokens as f64 * output_price) / 1_000_000.0
+}
+
+/// Whether spending `cost` more stays within the limits
+pub fn check(cost: f64) -> Result<(), Exceeded> {
+    let spent = SPENT.lock().unwrap();
+    let reserved = RESERVED.lock().unwrap();
+    within_limits(*spent + *reserved, cost)
+}
+
+/// Hold `cost` against the limits while a call is in flight
+///
+/// Calls made concurrently (`copt batch`) each see the others'
+/// reservations, so together they can't pass `--max-cost`. The hold is
+/// released when the [`Reservation`] is dropped.
+fn reserve(cost: f64) -> Result<Reservation, Exceeded> {
+    let spent = SPENT.lock().unwrap();
+    let mut reserved = RESERVED.lock().unwrap();
+    within_limits(*spent + *reserved, cost)?;
+    *reserved += cost;
+    Ok(Reservation(cost))
+}
+
+/// An in-flight call's estimated cost; see [`reserve`]
+struct Reservation(f64);
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let mut reserved = RESERVED.lock().unwrap_or_else(|e| e.into_inner());
+        *reserved = (*reserved - self.0).max(0.0);
+    }
+}
+
+/// Whether `cost` more on top of `spent` this run stays within the limits
+///
+/// The month's spend is what earlier runs recorded plus this run's.
+fn within_limits(spent: f64, cost: f64) -> Result<(), Exceeded> {
+    let budget = BUDGET.get().copied().unwrap_or_default();
+    if let Some(limit) = budget.max_cost {
+        let total = spent + cost;
+        if total > limit {
+            return Err(Exceeded::Run { limit, total });
+        }
+    }
+    if let Some(cap) = budget.monthly_cap {
+        let total = budget.month_spent + spent + cost;
+        if total > cap && !MONTH_OVERRUN_ACCEPTED.load(Ordering::Relaxed) {
+            return Err(Exceeded::Month { cap, total });
+        }
+    }
+    Ok(())
+}
+
+/// Estimated spend since the last call
+///
+/// Each optimization's share is recorded in its metrics entry. The run's
+/// total, which `--max-cost` caps, keeps counting it.
+pub fn take_spent() -> f64 {
+    let spent = *SPENT.lock().unwrap();
+    let mut recorded = RECORDED.lock().unwrap();
+    let share = (spent - *recorded).max(0.0);
+    *recorded = spent;
+    share
+}
+
+/// "$0.50", "$0.0042"
+pub fn dollars(amount: f64) -> String {
+    if amount >= 0.01 || amount == 0.0 {
+        format!("${:.2}", amount)
+    } else {
+        format!("${:.4}", amount)
+    }
+}
+
+/// Client that refuses calls over the budget and tallies what calls cost
+pub struct BudgetClient {
+    inner: Box<dyn LlmClient>,
+}
+
+impl BudgetClient {
+    pub fn new(inner: Box<dyn LlmClient>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl LlmClient for BudgetClient {
+    async fn complete(
+        &self,
+        system: &str,
+        user_message: &str,
+        model: &str,
+        max_tokens: u32,
+    ) -> Result<String> {
+        let estimated = estimate(model, system, user_message, max_tokens);
+        let _reservation = match reserve(estimated) {
+            Ok(reservation) => reservation,
+            Err(exceeded) => {
+                return Err(CoptError::CostLimit(exceeded.message(estimated)).into());
+            }
+        };
+
+        let response = self
+            .inner
+            .complete(system, user_message, model, max_tokens)
+            .await?;
+        let spent = cost(
+            model,
+            count_tokens(system) + count_tokens(user_message),
+            count_tokens(&response),
+        );
+        *SPENT.lock().unwrap() += spent;
+        Ok(response)
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    #[test]
+    fn test_estimate_uses_list_price() {
+        let prompt = "word ".repeat(1000);
+        let sonnet = estimate("sonnet", "", &prompt, 4096);
+        let haiku = estimate("claude-haiku-4-5-20251001", "", &prompt, 4096);
+        assert!(
+            (sonnet / haiku - 3.0).abs() < 1e-9,
+            "{} vs {}",
+            sonnet,
+            haiku
+        );
+        // Unknown models cost as much as the priciest known one
+        assert_eq!(
+            estimate(
+                "arn:aws:bedrock:us-east-1:1:application-inference-profile/x",
+                "",
+                &prompt,
+                4096
+            ),
+            estimate("opus", "", &prompt, 4096)
+        );
+        // The response is capped by max_tokens
+        assert!(estimate("sonnet", "", &prompt, 10) < sonnet);
+        assert_eq!(dollars(0.5), "$0.50");
+        assert_eq!(dollars(0.00042), "$0.0004");
+    }
+
+    // The limits are process-wide, so one test covers them in order
+    #[tokio::test]
+    async fn test_calls_over_the_limits_are_refused() {
+        configure(Budget {
+            max_cost: Some(0.10),
+            monthly_cap: Some(10.05),
+            month_spent: 10.0,
+        });
+        let client = BudgetClient::new(Box::new(MockLlmClient::with_responses(["ok", "ok"])));
+        let prompt = "word ".repeat(2000);
+        let estimated = estimate("sonnet", "", &prompt, 4096);
+
+        // Estimated at about $0.046; the two-word response costs far less
+        client.complete("", &prompt, "sonnet", 4096).await.unwrap();
+        assert!(*SPENT.lock().unwrap() > 0.0);
+
+        // Over the monthly cap: $10 earlier, this run's spend, and another
+        // $0.046
+        let err = client
+            .complete("", &prompt, "sonnet", 4096)
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("monthly_cap of $10.05"), "{}", message);
+        assert!(matches!(
+            err.downcast_ref::<CoptError>(),
+            Some(CoptError::CostLimit(_))
+        ));
+
+        // Each share is handed out once
+        assert!(take_spent() > 0.0);
+        assert_eq!(take_spent(), 0.0);
+        accept_month_overrun();
+        client.complete("", &prompt, "sonnet", 4096).await.unwrap();
+
+        // A call in flight holds its estimate, so a concurrent one can't
+        // also fit under --max-cost
+        let in_flight = reserve(estimated).unwrap();
+        assert!(matches!(reserve(estimated), Err(Exceeded::Run { .. })));
+        assert!(check(estimated).is_err());
+        drop(in_flight);
+        assert!(reserve(estimated).is_ok());
+
+        // Recording a share doesn't reset the run's total (one batch item
+        // after another)
+        take_spent();
+        assert!(matches!(
+            check(0.09),
+            Err(Exceeded::Run { limit, .. }) if limit == 0.10
+        ));
+    }
+}
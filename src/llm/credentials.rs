@@ -0,0 +1,176 @@
+//! API key storage in the OS keychain (`copt auth`)
+//!
+//! Keys saved with `copt auth login` live in the macOS Keychain, Windows
+//! Credential Manager, or the Linux kernel keyring, so they don't have to
+//! sit in a shell profile. The keychain takes precedence over the
+//! `ANTHROPIC_API_KEY` environment variable. Builds without the `anthropic`
+//! feature leave out the keychain and read only the environment.
+
+use anyhow::Result;
+use std::fmt;
+
+use crate::error::{CoptError, ProviderErrorKind};
+
+/// Environment variable read when the keychain has no key
+pub const ANTHROPIC_API_KEY_ENV: &str = "ANTHROPIC_API_KEY";
+
+/// Where an API key came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Keychain,
+    Environment,
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Keychain => write!(f, "OS keychain"),
+            KeySource::Environment => write!(f, "{} environment variable", ANTHROPIC_API_KEY_ENV),
+        }
+    }
+}
+
+#[cfg(feature = "anthropic")]
+mod keychain {
+    use anyhow::{Context, Result};
+
+    /// Keychain service name for copt's entries
+    const SERVICE: &str = "copt";
+
+    /// Keychain account holding the Anthropic API key
+    const ANTHROPIC_ACCOUNT: &str = "anthropic-api-key";
+
+    fn anthropic_entry() -> Result<keyring::Entry> {
+        keyring::Entry::new(SERVICE, ANTHROPIC_ACCOUNT).context("Failed to open the OS keychain")
+    }
+
+    pub fn read() -> Result<Option<String>> {
+        match anthropic_entry()?.get_password() {
+            Ok(key) => Ok(Some(key)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read the API key from the OS keychain"),
+        }
+    }
+
+    pub fn store(key: &str) -> Result<()> {
+        anthropic_entry()?
+            .set_password(key)
+            .context("Failed to save the API key to the OS keychain")
+    }
+
+    pub fn delete() -> Result<bool> {
+        match anthropic_entry()?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).context("Failed to remove the API key from the OS keychain"),
+        }
+    }
+}
+
+#[cfg(not(feature = "anthropic"))]
+mod keychain {
+    use anyhow::Result;
+
+    use crate::error::CoptError;
+
+    fn disabled<T>() -> Result<T> {
+        Err(CoptError::feature_disabled("anthropic", "The OS keychain").into())
+    }
+
+    pub fn read() -> Result<Option<String>> {
+        disabled()
+    }
+
+    pub fn store(_key: &str) -> Result<()> {
+        disabled()
+    }
+
+    pub fn delete() -> Result<bool> {
+        disabled()
+    }
+}
+
+/// The Anthropic API key saved in the keychain, if any
+pub fn keychain_anthropic_key() -> Result<Option<String>> {
+    keychain::read()
+}
+
+/// Save `key` as the Anthropic API key, replacing any saved key
+pub fn store_anthropic_key(key: &str) -> Result<()> {
+    keychain::store(key)
+}
+
+/// Remove the saved Anthropic API key; `false` if there was none
+pub fn delete_anthropic_key() -> Result<bool> {
+    keychain::delete()
+}
+
+/// The Anthropic API key to use: keychain first, then the environment
+pub fn anthropic_api_key() -> Result<(String, KeySource)> {
+    // An unusable keychain (no keyring daemon, CI) falls back to the environment
+    let keychain = keychain_anthropic_key().unwrap_or_else(|e| {
+        tracing::debug!("Keychain unavailable: {:#}", e);
+        None
+    });
+    pick_key(keychain, std::env::var(ANTHROPIC_API_KEY_ENV).ok()).ok_or_else(|| {
+        CoptError::provider(
+            ProviderErrorKind::Auth,
+            format!(
+                "No Anthropic API key found.\n\n\
+                Save one in the OS keychain:\n\
+                copt auth login --provider anthropic\n\n\
+                Or set the {} environment variable.",
+                ANTHROPIC_API_KEY_ENV
+            ),
+        )
+        .into()
+    })
+}
+
+/// Keychain over environment, ignoring blank values
+fn pick_key(keychain: Option<String>, env: Option<String>) -> Option<(String, KeySource)> {
+    let usable = |key: Option<String>| key.filter(|k| !k.trim().is_empty());
+    usable(keychain)
+        .map(|key| (key, KeySource::Keychain))
+        .or_else(|| usable(env).map(|key| (key, KeySource::Environment)))
+}
+
+/// `sk-ant-…wxyz` for display
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 12 {
+        return "…".to_string();
+    }
+    let head: String = chars[..7].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keychain_takes_precedence() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            pick_key(some("from-keychain"), some("from-env")),
+            Some(("from-keychain".to_string(), KeySource::Keychain))
+        );
+        assert_eq!(
+            pick_key(None, some("from-env")),
+            Some(("from-env".to_string(), KeySource::Environment))
+        );
+        assert_eq!(
+            pick_key(some("  "), some("from-env")).map(|(_, source)| source),
+            Some(KeySource::Environment)
+        );
+        assert_eq!(pick_key(None, some("")), None);
+    }
+
+    #[test]
+    fn test_mask_key() {
+        assert_eq!(mask_key("sk-ant-REDACTED"), "sk-ant-…mnop");
+        assert_eq!(mask_key("short"), "…");
+    }
+}
@@ -0,0 +1,180 @@
+//! LLM judge for rubric-based prompt scoring
+//!
+//! Asks a model to grade a prompt on clarity, specificity, structure, and fit
+//! for Claude 4.5, returning structured 1-10 scores.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::LlmClient;
+
+/// System prompt for the judge model
+pub const JUDGE_SYSTEM_PROMPT: &str = r#"You are an expert prompt engineer grading prompts written for Claude 4.5 models.
+
+Score the prompt inside <prompt> tags on each criterion from 1 (poor) to 10 (excellent):
+
+- clarity: Is it unambiguous what Claude should do?
+- specificity: Are requirements, constraints, and success criteria explicit?
+- structure: Is it organized into clear sections (e.g. XML tags, headings) with context separated from instructions?
+- claude_fit: Does it follow Claude 4.5 best practices (direct commands, positive framing, motivation for rules, no aggressive emphasis)?
+
+Respond with only a JSON object, no prose:
+{"clarity": <1-10>, "specificity": <1-10>, "structure": <1-10>, "claude_fit": <1-10>, "rationale": "<one sentence>"}"#;
+
+/// Max tokens for a judge response (scores plus a one-sentence rationale)
+const JUDGE_MAX_TOKENS: u32 = 512;
+
+/// Rubric scores for a single prompt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JudgeScores {
+    pub clarity: u8,
+    pub specificity: u8,
+    pub structure: u8,
+    pub claude_fit: u8,
+    #[serde(default)]
+    pub rationale: String,
+}
+
+impl JudgeScores {
+    /// Mean of the four criteria (1.0-10.0)
+    pub fn average(&self) -> f32 {
+        let sum = self.clarity as u32
+            + self.specificity as u32
+            + self.structure as u32
+            + self.claude_fit as u32;
+        sum as f32 / 4.0
+    }
+
+    /// Criterion name/score pairs in display order
+    pub fn criteria(&self) -> [(&'static str, u8); 4] {
+        [
+            ("Clarity", self.clarity),
+            ("Specificity", self.specificity),
+            ("Structure", self.structure),
+            ("Claude 4.5 fit", self.claude_fit),
+        ]
+    }
+
+    fn clamped(mut self) -> Self {
+        for score in [
+            &mut self.clarity,
+            &mut self.specificity,
+            &mut self.structure,
+            &mut self.claude_fit,
+        ] {
+            *score = (*score).clamp(1, 10);
+        }
+        self
+    }
+}
+
+/// Judge scores for the original and optimized prompts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JudgeComparison {
+    pub model: String,
+    pub original: JudgeScores,
+    pub optimized: JudgeScores,
+}
+
+/// Score a single prompt with the judge model
+pub async fn judge_prompt(
+    client: &dyn LlmClient,
+    model: &str,
+    prompt: &str,
+) -> Result<JudgeScores> {
+    let message = format!("<prompt>\n{}\n</prompt>", prompt);
+    tracing::debug!(
+        model,
+        request_chars = JUDGE_SYSTEM_PROMPT.len() + message.len(),
+        "judge request"
+    );
+    let started = std::time::Instant::now();
+    let response = client
+        .complete(JUDGE_SYSTEM_PROMPT, &message, model, JUDGE_MAX_TOKENS)
+        .await;
+    crate::utils::profile::record("llm", "judge", started.elapsed());
+    let response = response?;
+    tracing::debug!(
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        response_chars = response.len(),
+        "judge response"
+    );
+    parse_scores(&response)
+}
+
+/// Score both prompts with the judge model
+pub async fn judge_prompts(
+    client: &dyn LlmClient,
+    model: &str,
+    original: &str,
+    optimized: &str,
+) -> Result<JudgeComparison> {
+    let original_scores = judge_prompt(client, model, original)
+        .await
+        .context("Judge failed to score the original prompt")?;
+    let optimized_scores = judge_prompt(client, model, optimized)
+        .await
+        .context("Judge failed to score the optimized prompt")?;
+
+    Ok(JudgeComparison {
+        model: model.to_string(),
+        original: original_scores,
+        optimized: optimized_scores,
+    })
+}
+
+/// Parse judge output, tolerating prose or code fences around the JSON object
+fn parse_scores(response: &str) -> Result<JudgeScores> {
+    let start = response.find('{');
+    let end = response.rfind('}');
+    let json = match (start, end) {
+        (Some(s), Some(e)) if s < e => &response[s..=e],
+        _ => anyhow::bail!("Judge response did not contain a JSON object"),
+    };
+
+    let scores: JudgeScores =
+        serde_json::from_str(json).context("Judge response was not valid rubric JSON")?;
+    Ok(scores.clamped())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    #[test]
+    fn test_parse_scores() {
+        let scores = parse_scores(
+            "```json\n{\"clarity\": 7, \"specificity\": 4, \"structure\": 12, \"claude_fit\": 0, \"rationale\": \"ok\"}\n```",
+        )
+        .unwrap();
+        assert_eq!(scores.clarity, 7);
+        assert_eq!(scores.structure, 10);
+        assert_eq!(scores.claude_fit, 1);
+        assert_eq!(scores.rationale, "ok");
+        assert!((scores.average() - 5.5).abs() < f32::EPSILON);
+
+        assert!(parse_scores("no json here").is_err());
+        assert!(parse_scores("{\"clarity\": \"high\"}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_judge_prompts() {
+        let client = MockLlmClient::with_responses([
+            r#"{"clarity": 3, "specificity": 2, "structure": 2, "claude_fit": 4}"#,
+            r#"{"clarity": 8, "specificity": 7, "structure": 9, "claude_fit": 8}"#,
+        ]);
+
+        let comparison = judge_prompts(&client, "judge-model", "before", "after")
+            .await
+            .unwrap();
+        assert_eq!(comparison.original.clarity, 3);
+        assert_eq!(comparison.optimized.structure, 9);
+        assert_eq!(comparison.model, "judge-model");
+
+        let calls = client.calls();
+        assert_eq!(calls[0].system, JUDGE_SYSTEM_PROMPT);
+        assert!(calls[0].user_message.contains("before"));
+        assert!(calls[1].user_message.contains("after"));
+    }
+}
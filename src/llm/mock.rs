@@ -0,0 +1,196 @@
+//! Mock LLM client
+//!
+//! Returns canned or scripted responses without network access. Used by the
+//! test suite and exposed as `--provider mock` for demos.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use super::judge::JUDGE_SYSTEM_PROMPT;
+use super::test_queries::TEST_QUERIES_SYSTEM_PROMPT;
+use super::LlmClient;
+
+/// A recorded call to the mock client
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    pub system: String,
+    pub user_message: String,
+    pub model: String,
+    pub max_tokens: u32,
+}
+
+/// Mock client that replays scripted responses, then falls back to a canned rewrite
+#[derive(Default)]
+pub struct MockLlmClient {
+    responses: Mutex<VecDeque<Result<String, String>>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockLlmClient {
+    /// Create a mock that always returns the canned rewrite
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a mock that returns the given responses in order
+    pub fn with_responses<I, S>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let client = Self::new();
+        for response in responses {
+            client.push_response(response);
+        }
+        client
+    }
+
+    /// Queue a successful response
+    pub fn push_response(&self, response: impl Into<String>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back(Ok(response.into()));
+    }
+
+    /// Queue an error response
+    pub fn push_error(&self, message: impl Into<String>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back(Err(message.into()));
+    }
+
+    /// All calls made so far, in order
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LlmClient for MockLlmClient {
+    async fn complete(
+        &self,
+        system: &str,
+        user_message: &str,
+        model: &str,
+        max_tokens: u32,
+    ) -> Result<String> {
+        self.calls.lock().unwrap().push(MockCall {
+            system: system.to_string(),
+            user_message: user_message.to_string(),
+            model: model.to_string(),
+            max_tokens,
+        });
+
+        let scripted = self.responses.lock().unwrap().pop_front();
+        match scripted {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(message)) => anyhow::bail!(message),
+            None if system == JUDGE_SYSTEM_PROMPT => Ok(canned_judge(user_message)),
+            None if system == TEST_QUERIES_SYSTEM_PROMPT => Ok(canned_test_queries(user_message)),
+            None => Ok(canned_rewrite(user_message)),
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        "mock"
+    }
+}
+
+/// Deterministic rewrite of the prompt embedded in an optimization request
+///
+/// Wraps the original prompt in `<instructions>` and appends an output-format
+/// section, which is enough to demo the diff and stats views.
+fn canned_rewrite(user_message: &str) -> String {
+    let original = extract_tag(user_message, "original_prompt").unwrap_or(user_message);
+
+    format!(
+        "<instructions>\n{}\n</instructions>\n\n<output_format>\n\
+        Respond with a short summary first, then the details as a bulleted list.\n\
+        </output_format>",
+        original.trim()
+    )
+}
+
+/// Deterministic rubric scores for judge requests
+///
+/// Prompts with XML structure score higher, so the canned rewrite always
+/// shows an improvement in demos.
+fn canned_judge(user_message: &str) -> String {
+    let prompt = extract_tag(user_message, "prompt").unwrap_or(user_message);
+    let structured = prompt.contains("<instructions>");
+    let (clarity, specificity, structure, fit) = if structured {
+        (8, 7, 9, 8)
+    } else {
+        (5, 4, 3, 5)
+    };
+
+    format!(
+        r#"{{"clarity": {}, "specificity": {}, "structure": {}, "claude_fit": {}, "rationale": "Mock judge score."}}"#,
+        clarity, specificity, structure, fit
+    )
+}
+
+/// Deterministic test queries, cycling through the three scenarios
+fn canned_test_queries(user_message: &str) -> String {
+    let count = extract_tag(user_message, "count")
+        .and_then(|c| c.trim().parse().ok())
+        .unwrap_or(3);
+    let scenarios = ["typical", "edge_case", "out_of_scope"];
+    let queries: Vec<_> = (0..count)
+        .map(|i| {
+            serde_json::json!({
+                "scenario": scenarios[i % scenarios.len()],
+                "query": format!("Mock {} question #{}", scenarios[i % scenarios.len()], i + 1),
+            })
+        })
+        .collect();
+    serde_json::Value::from(queries).to_string()
+}
+
+/// Extract the contents of the first `<tag>...</tag>` block
+fn extract_tag<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = text.find(&open)? + open.len();
+    let end = text[start..].find(&close)? + start;
+    Some(&text[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::build_optimization_message;
+
+    #[tokio::test]
+    async fn test_scripted_responses_then_canned() {
+        let client = MockLlmClient::with_responses(["first"]);
+        client.push_error("rate limited");
+
+        let message = build_optimization_message("Write a haiku", "", "creative", None);
+
+        assert_eq!(
+            client.complete("sys", &message, "m", 10).await.unwrap(),
+            "first"
+        );
+        let err = client.complete("sys", &message, "m", 10).await.unwrap_err();
+        assert_eq!(err.to_string(), "rate limited");
+
+        let canned = client.complete("sys", &message, "m", 10).await.unwrap();
+        assert!(canned.starts_with("<instructions>\nWrite a haiku\n</instructions>"));
+
+        let calls = client.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].system, "sys");
+        assert_eq!(calls[0].max_tokens, 10);
+    }
+
+    #[test]
+    fn test_extract_tag() {
+        assert_eq!(extract_tag("<a>x</a>", "a"), Some("x"));
+        assert_eq!(extract_tag("<a>x", "a"), None);
+    }
+}
@@ -3,14 +3,38 @@
 //! Provides unified interface for Claude 4.5 API access via:
 //! - Anthropic API (direct)
 //! - AWS Bedrock
+//! - Mock (canned responses for tests and demos)
+//!
+//! Any client can be wrapped in a [`TimeoutClient`] to bound each call, and
+//! in a [`BudgetClient`] to hold calls to the spend limits, and in an
+//! [`AnonymizingClient`] to keep sensitive details from the provider.
 
 #![allow(dead_code)]
 
+pub mod anonymize;
 mod anthropic;
+#[cfg(feature = "bedrock")]
 mod bedrock;
-
-pub use anthropic::AnthropicClient;
+pub mod budget;
+pub mod credentials;
+pub mod judge;
+mod mock;
+pub mod models;
+#[cfg(not(feature = "bedrock"))]
+mod no_bedrock;
+pub mod regions;
+pub mod test_queries;
+mod timeout;
+
+pub use anonymize::AnonymizingClient;
+pub use anthropic::{AnthropicClient, GatewayOptions};
+#[cfg(feature = "bedrock")]
 pub use bedrock::BedrockClient;
+pub use budget::BudgetClient;
+pub use mock::MockLlmClient;
+#[cfg(not(feature = "bedrock"))]
+pub use no_bedrock::BedrockClient;
+pub use timeout::{format_duration, is_timeout, parse_duration, LlmTimeout, TimeoutClient};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -120,11 +144,18 @@ If the prompt contains XML blocks like <examples>, <example>, <instructions>, <c
 </output_requirements>"#;
 
 /// Build the user message for optimization
+///
+/// `scope`, when set, limits the rewrite to what the user asked copt to
+/// check (`--check`, disabled rules).
 pub fn build_optimization_message(
     original_prompt: &str,
     issues_json: &str,
     prompt_type: &str,
+    scope: Option<&str>,
 ) -> String {
+    let scope = scope
+        .map(|scope| format!("<scope>\n{scope}\n</scope>\n\n"))
+        .unwrap_or_default();
     format!(
         r#"Optimize this prompt for Claude 4.5:
 
@@ -138,7 +169,7 @@ pub fn build_optimization_message(
 {issues_json}
 </detected_issues>
 
-Return the optimized prompt only."#
+{scope}Return the optimized prompt only."#
     )
 }
 
@@ -152,10 +183,22 @@ mod tests {
             "Create a dashboard",
             r#"[{"id": "EXP001", "message": "Vague instruction"}]"#,
             "coding",
+            None,
         );
 
         assert!(message.contains("Create a dashboard"));
         assert!(message.contains("EXP001"));
         assert!(message.contains("<prompt_type>coding</prompt_type>"));
+        assert!(!message.contains("<scope>"));
+
+        let message = build_optimization_message(
+            "Create a dashboard",
+            "[]",
+            "coding",
+            Some("Only address issues in these categories: style."),
+        );
+        assert!(message.contains(
+            "<scope>\nOnly address issues in these categories: style.\n</scope>\n\nReturn"
+        ));
     }
 }
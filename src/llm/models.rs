@@ -0,0 +1,252 @@
+//! Model catalog and alias resolution
+//!
+//! One table of the Claude models copt knows about: their short aliases,
+//! the ID each provider expects, context window, and list price. Users can
+//! add their own aliases under `[models.aliases]` in the config file.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// A Claude model and how each provider names it
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ModelInfo {
+    /// Display name, e.g. "Claude Sonnet 4.5"
+    pub name: &'static str,
+    /// Short names accepted by `--model`; the first is the canonical alias
+    pub aliases: &'static [&'static str],
+    /// Anthropic API model ID
+    pub anthropic_id: &'static str,
+    /// Bedrock inference profile ID
+    pub bedrock_id: &'static str,
+    /// Context window in tokens
+    pub context_window: u32,
+    /// Largest response in tokens
+    pub max_output: u32,
+    /// USD per million input tokens
+    pub input_price: f64,
+    /// USD per million output tokens
+    pub output_price: f64,
+}
+
+/// Models copt knows about, newest family first
+pub const MODELS: &[ModelInfo] = &[
+    ModelInfo {
+        name: "Claude Sonnet 4.5",
+        aliases: &["sonnet", "sonnet-4.5"],
+        anthropic_id: "claude-sonnet-4-5-20250929",
+        bedrock_id: "us.anthropic.claude-sonnet-4-5-20250929-v1:0",
+        context_window: 200_000,
+        max_output: 64_000,
+        input_price: 3.0,
+        output_price: 15.0,
+    },
+    ModelInfo {
+        name: "Claude Opus 4.5",
+        aliases: &["opus", "opus-4.5"],
+        anthropic_id: "claude-opus-4-5-20251101",
+        bedrock_id: "global.anthropic.claude-opus-4-5-20251101-v1:0",
+        context_window: 200_000,
+        max_output: 64_000,
+        input_price: 5.0,
+        output_price: 25.0,
+    },
+    ModelInfo {
+        name: "Claude Haiku 4.5",
+        aliases: &["haiku", "haiku-4.5"],
+        anthropic_id: "claude-haiku-4-5-20251001",
+        bedrock_id: "us.anthropic.claude-haiku-4-5-20251001-v1:0",
+        context_window: 200_000,
+        max_output: 64_000,
+        input_price: 1.0,
+        output_price: 5.0,
+    },
+];
+
+/// Aliases from `[models.aliases]`, set once per process
+static USER_ALIASES: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+/// Apply `[models.aliases]` for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(aliases: BTreeMap<String, String>) {
+    let _ = USER_ALIASES.set(aliases);
+}
+
+/// Aliases from the config file (empty until [`configure`] is called)
+pub fn user_aliases() -> impl Iterator<Item = (&'static str, &'static str)> {
+    USER_ALIASES
+        .get()
+        .into_iter()
+        .flatten()
+        .map(|(alias, target)| (alias.as_str(), target.as_str()))
+}
+
+/// What a user alias points at, or `model` unchanged
+///
+/// Built-in aliases are left for the provider to resolve, so a user alias
+/// may point at one ("fast" = "haiku").
+pub fn expand_alias(model: &str) -> String {
+    user_aliases()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(model))
+        .map(|(_, target)| target.to_string())
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// The catalog entry for an alias or any provider's ID for a model
+///
+/// Accepts "sonnet", "claude-sonnet-4.5", "claude-sonnet-4-5-20250929",
+/// Bedrock profile IDs for any region, and the legacy
+/// "anthropic.claude-…-v1:0" form.
+pub fn lookup(model: &str) -> Option<&'static ModelInfo> {
+    let model = model.trim();
+    let short = model.strip_prefix("claude-").unwrap_or(model);
+    // "us.", "eu.", "global." … name the same model in other regions
+    let bedrock = model
+        .find("anthropic.")
+        .map(|start| &model[start + "anthropic.".len()..])
+        .and_then(|id| id.strip_suffix("-v1:0"));
+    MODELS.iter().find(|info| {
+        info.aliases.iter().any(|a| a.eq_ignore_ascii_case(short))
+            || info.anthropic_id == model
+            || bedrock == Some(info.anthropic_id)
+    })
+}
+
+/// Model ID for the Anthropic API; unknown models pass through unchanged
+pub fn anthropic_id(model: &str) -> String {
+    lookup(model)
+        .map(|info| info.anthropic_id.to_string())
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// Model ID for Bedrock; unknown models pass through unchanged
+///
+/// Cross-region profile IDs ("us.anthropic…", "eu.anthropic…") and
+/// inference profile ARNs are kept as written, so a profile other than the
+/// catalog's default still works.
+pub fn bedrock_id(model: &str) -> String {
+    if is_arn(model) || model.contains(".anthropic.") {
+        return model.to_string();
+    }
+    lookup(model)
+        .map(|info| info.bedrock_id.to_string())
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// ID sent to `provider` ("anthropic", "bedrock", …) for `model`
+pub fn provider_id(provider: &str, model: &str) -> String {
+    match provider {
+        "anthropic" => anthropic_id(model),
+        _ => bedrock_id(model),
+    }
+}
+
+/// Whether `model` is a Bedrock ARN, e.g. an application inference profile
+/// (`arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/…`)
+pub fn is_arn(model: &str) -> bool {
+    model.starts_with("arn:") && model.contains(":bedrock:")
+}
+
+/// The region an ARN belongs to; requests must be sent there
+pub fn arn_region(model: &str) -> Option<&str> {
+    if !is_arn(model) {
+        return None;
+    }
+    model.split(':').nth(3).filter(|region| !region.is_empty())
+}
+
+/// The built-in or user alias closest to a mistyped `model`, if any is close
+pub fn closest_alias(model: &str) -> Option<&'static str> {
+    let model = model.to_ascii_lowercase();
+    MODELS
+        .iter()
+        .flat_map(|info| info.aliases.iter().copied())
+        .chain(user_aliases().map(|(alias, _)| alias))
+        .map(|alias| (edit_distance(&model, &alias.to_ascii_lowercase()), alias))
+        // Two typos, or one per three characters for long aliases, so
+        // "gpt-4" doesn't become "opus"
+        .filter(|(distance, alias)| *distance <= (alias.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, alias)| alias)
+}
+
+/// Levenshtein distance between `a` and `b`, by character
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_accepts_every_spelling() {
+        for spelling in [
+            "sonnet",
+            "Sonnet-4.5",
+            "claude-sonnet-4.5",
+            "claude-sonnet-4-5-20250929",
+            "us.anthropic.claude-sonnet-4-5-20250929-v1:0",
+            "anthropic.claude-sonnet-4-5-20250929-v1:0",
+            "eu.anthropic.claude-sonnet-4-5-20250929-v1:0",
+        ] {
+            assert_eq!(
+                lookup(spelling).map(|m| m.name),
+                Some("Claude Sonnet 4.5"),
+                "{}",
+                spelling
+            );
+        }
+        assert!(lookup("gpt-4").is_none());
+    }
+
+    #[test]
+    fn test_provider_ids() {
+        assert_eq!(
+            anthropic_id("us.anthropic.claude-haiku-4-5-20251001-v1:0"),
+            "claude-haiku-4-5-20251001"
+        );
+        assert_eq!(
+            bedrock_id("opus"),
+            "global.anthropic.claude-opus-4-5-20251101-v1:0"
+        );
+        assert_eq!(
+            bedrock_id("eu.anthropic.claude-sonnet-4-5-20250929-v1:0"),
+            "eu.anthropic.claude-sonnet-4-5-20250929-v1:0"
+        );
+        assert_eq!(anthropic_id("claude-custom"), "claude-custom");
+    }
+
+    #[test]
+    fn test_arns() {
+        let arn = "arn:aws:bedrock:eu-central-1:123456789012:application-inference-profile/a1b2c3";
+        assert!(is_arn(arn));
+        assert_eq!(arn_region(arn), Some("eu-central-1"));
+        assert_eq!(bedrock_id(arn), arn);
+        assert_eq!(provider_id("bedrock", arn), arn);
+        assert!(!is_arn("us.anthropic.claude-sonnet-4-5-20250929-v1:0"));
+        assert_eq!(arn_region("sonnet"), None);
+    }
+
+    #[test]
+    fn test_closest_alias() {
+        assert_eq!(closest_alias("sonet"), Some("sonnet"));
+        assert_eq!(closest_alias("OPUS-4.6"), Some("opus-4.5"));
+        assert_eq!(closest_alias("haikoo"), Some("haiku"));
+        assert_eq!(closest_alias("gpt-4"), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,45 @@
+//! Bedrock stand-in for builds without the `bedrock` feature
+//!
+//! [`BedrockClient`] can't be constructed: `new` reports the missing
+//! feature, so `--provider bedrock` fails the way a bad flag does instead of
+//! every caller checking the build.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::LlmClient;
+use crate::error::CoptError;
+
+/// AWS Bedrock client (not compiled in)
+pub enum BedrockClient {}
+
+impl BedrockClient {
+    pub async fn new(_region: &str) -> Result<Self> {
+        Err(CoptError::feature_disabled("bedrock", "--provider bedrock").into())
+    }
+
+    pub async fn check_connectivity(&self, _model_id: &str) -> Result<()> {
+        match *self {}
+    }
+
+    pub fn region(&self) -> &str {
+        match *self {}
+    }
+}
+
+#[async_trait]
+impl LlmClient for BedrockClient {
+    async fn complete(
+        &self,
+        _system: &str,
+        _user_message: &str,
+        _model: &str,
+        _max_tokens: u32,
+    ) -> Result<String> {
+        match *self {}
+    }
+
+    fn provider_name(&self) -> &str {
+        "bedrock"
+    }
+}
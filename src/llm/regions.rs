@@ -0,0 +1,158 @@
+//! Bedrock region capability map
+//!
+//! Cross-region inference profiles can only be called from the regions in
+//! their geography: a `us.` profile from US regions, `eu.` from EU regions,
+//! and `global.` from most commercial regions. Calling one from elsewhere
+//! fails with a bare ValidationException, so the region is checked against
+//! this map before the first request.
+//!
+//! The map follows the AWS documentation for inference profiles and may lag
+//! new regions; unknown profiles and model IDs are never second-guessed.
+
+/// Source regions for each inference profile geography, most common first
+const PROFILE_REGIONS: &[(&str, &[&str])] = &[
+    ("us", &["us-east-1", "us-east-2", "us-west-2", "us-west-1"]),
+    (
+        "eu",
+        &[
+            "eu-central-1",
+            "eu-west-1",
+            "eu-west-3",
+            "eu-north-1",
+            "eu-south-1",
+            "eu-south-2",
+        ],
+    ),
+    (
+        "apac",
+        &[
+            "ap-northeast-1",
+            "ap-northeast-2",
+            "ap-northeast-3",
+            "ap-south-1",
+            "ap-south-2",
+            "ap-southeast-1",
+            "ap-southeast-2",
+            "ap-southeast-4",
+        ],
+    ),
+    ("jp", &["ap-northeast-1", "ap-northeast-3"]),
+    ("au", &["ap-southeast-2", "ap-southeast-4"]),
+    (
+        "global",
+        &[
+            "us-east-1",
+            "us-east-2",
+            "us-west-2",
+            "us-west-1",
+            "ca-central-1",
+            "sa-east-1",
+            "eu-central-1",
+            "eu-west-1",
+            "eu-west-2",
+            "eu-west-3",
+            "eu-north-1",
+            "eu-south-1",
+            "eu-south-2",
+            "ap-northeast-1",
+            "ap-northeast-2",
+            "ap-northeast-3",
+            "ap-south-1",
+            "ap-south-2",
+            "ap-southeast-1",
+            "ap-southeast-2",
+            "ap-southeast-4",
+        ],
+    ),
+];
+
+/// Regions the inference profile `model_id` can be called from, if known
+pub fn profile_regions(model_id: &str) -> Option<&'static [&'static str]> {
+    let (geography, rest) = model_id.split_once('.')?;
+    if !rest.starts_with("anthropic.") {
+        return None;
+    }
+    PROFILE_REGIONS
+        .iter()
+        .find(|(prefix, _)| *prefix == geography)
+        .map(|(_, regions)| *regions)
+}
+
+/// Whether `region` can call `model_id` (always true for unknown profiles)
+pub fn supports(model_id: &str, region: &str) -> bool {
+    profile_regions(model_id).is_none_or(|regions| regions.contains(&region))
+}
+
+/// The region to use instead of `region` for `model_id`
+///
+/// Prefers a region in the same area ("eu-west-9" → "eu-central-1"), then
+/// the profile's most common region.
+pub fn nearest_region(model_id: &str, region: &str) -> Option<&'static str> {
+    let regions = profile_regions(model_id)?;
+    let area = region.split('-').next().unwrap_or(region);
+    regions
+        .iter()
+        .find(|r| r.split('-').next() == Some(area))
+        .or_else(|| regions.first())
+        .copied()
+}
+
+/// `model_id` under the geographic profile that covers `region`, if any
+///
+/// "us.anthropic.claude-…" in eu-west-1 becomes "eu.anthropic.claude-…".
+/// Whether that profile exists for the model is up to Bedrock.
+pub fn local_profile(model_id: &str, region: &str) -> Option<String> {
+    let (_, rest) = model_id.split_once('.')?;
+    profile_regions(model_id)?;
+    let (geography, _) = PROFILE_REGIONS
+        .iter()
+        .find(|(prefix, regions)| *prefix != "global" && regions.contains(&region))?;
+    Some(format!("{}.{}", geography, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONNET_US: &str = "us.anthropic.claude-sonnet-4-5-20250929-v1:0";
+
+    #[test]
+    fn test_supports() {
+        assert!(supports(SONNET_US, "us-west-2"));
+        assert!(!supports(SONNET_US, "eu-west-1"));
+        assert!(supports(
+            "global.anthropic.claude-opus-4-5-20251101-v1:0",
+            "eu-west-1"
+        ));
+        // On-demand IDs and unknown profiles are left to Bedrock
+        assert!(supports(
+            "anthropic.claude-3-haiku-20240307-v1:0",
+            "eu-west-1"
+        ));
+        assert!(supports("xx.anthropic.claude-new-v1:0", "eu-west-1"));
+    }
+
+    #[test]
+    fn test_nearest_region() {
+        assert_eq!(
+            nearest_region(SONNET_US, "us-gov-west-1"),
+            Some("us-east-1")
+        );
+        assert_eq!(nearest_region(SONNET_US, "eu-west-1"), Some("us-east-1"));
+        assert_eq!(
+            nearest_region("eu.anthropic.claude-sonnet-4-5-20250929-v1:0", "eu-west-2"),
+            Some("eu-central-1")
+        );
+        assert_eq!(nearest_region("claude-custom", "eu-west-1"), None);
+    }
+
+    #[test]
+    fn test_local_profile() {
+        assert_eq!(
+            local_profile(SONNET_US, "eu-west-1").as_deref(),
+            Some("eu.anthropic.claude-sonnet-4-5-20250929-v1:0")
+        );
+        assert_eq!(local_profile(SONNET_US, "me-central-1"), None);
+        assert_eq!(local_profile("claude-custom", "eu-west-1"), None);
+    }
+}
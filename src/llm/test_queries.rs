@@ -0,0 +1,123 @@
+//! Test query generation for system prompts (`--generate-tests`)
+//!
+//! Asks a model for representative user messages to try against a new
+//! system prompt: typical requests, edge cases, and requests the prompt
+//! should decline or redirect.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::LlmClient;
+
+/// System prompt for the test query generator
+pub const TEST_QUERIES_SYSTEM_PROMPT: &str = r#"You are a QA engineer writing test conversations for a system prompt that will be deployed with Claude.
+
+Read the system prompt inside <system_prompt> tags and write the number of user messages given in <count> tags. Cover the scenarios the prompt must handle:
+
+- typical: everyday requests the assistant exists for
+- edge_case: ambiguous, incomplete, or unusually complex requests
+- out_of_scope: requests the prompt should decline, redirect, or handle with care
+
+Write each message as a real user would type it. Respond with only a JSON array, no prose:
+[{"scenario": "typical", "query": "<user message>"}]"#;
+
+/// Max tokens for a batch of test queries
+const TEST_QUERIES_MAX_TOKENS: u32 = 2048;
+
+/// A user message to try against a system prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestQuery {
+    /// typical, edge_case, or out_of_scope
+    pub scenario: String,
+    pub query: String,
+}
+
+/// Generate `count` test queries for `system_prompt`
+pub async fn generate_test_queries(
+    client: &dyn LlmClient,
+    model: &str,
+    system_prompt: &str,
+    count: usize,
+) -> Result<Vec<TestQuery>> {
+    let message = format!(
+        "<count>{}</count>\n\n<system_prompt>\n{}\n</system_prompt>",
+        count, system_prompt
+    );
+    tracing::debug!(
+        model,
+        count,
+        request_chars = TEST_QUERIES_SYSTEM_PROMPT.len() + message.len(),
+        "test query request"
+    );
+    let started = std::time::Instant::now();
+    let response = client
+        .complete(
+            TEST_QUERIES_SYSTEM_PROMPT,
+            &message,
+            model,
+            TEST_QUERIES_MAX_TOKENS,
+        )
+        .await;
+    crate::utils::profile::record("llm", "test queries", started.elapsed());
+    let response = response?;
+    tracing::debug!(
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        response_chars = response.len(),
+        "test query response"
+    );
+
+    let mut queries = parse_queries(&response)?;
+    queries.truncate(count);
+    Ok(queries)
+}
+
+/// Parse generator output, tolerating prose or code fences around the JSON array
+fn parse_queries(response: &str) -> Result<Vec<TestQuery>> {
+    let start = response.find('[');
+    let end = response.rfind(']');
+    let json = match (start, end) {
+        (Some(s), Some(e)) if s < e => &response[s..=e],
+        _ => anyhow::bail!("Test query response did not contain a JSON array"),
+    };
+
+    let queries: Vec<TestQuery> =
+        serde_json::from_str(json).context("Test query response was not valid JSON")?;
+    Ok(queries
+        .into_iter()
+        .filter(|q| !q.query.trim().is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    #[test]
+    fn test_parse_queries() {
+        let queries = parse_queries(
+            "Here you go:\n```json\n[{\"scenario\": \"typical\", \"query\": \"Where is my order?\"}, {\"scenario\": \"edge_case\", \"query\": \" \"}]\n```",
+        )
+        .unwrap();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query, "Where is my order?");
+
+        assert!(parse_queries("no json here").is_err());
+        assert!(parse_queries("[{\"query\": 3}]").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_test_queries() {
+        let client = MockLlmClient::new();
+        let queries = generate_test_queries(&client, "m", "You are a support agent.", 4)
+            .await
+            .unwrap();
+        assert_eq!(queries.len(), 4);
+        assert!(queries.iter().any(|q| q.scenario == "out_of_scope"));
+
+        let calls = client.calls();
+        assert_eq!(calls[0].system, TEST_QUERIES_SYSTEM_PROMPT);
+        assert!(calls[0].user_message.contains("<count>4</count>"));
+        assert!(calls[0].user_message.contains("You are a support agent."));
+    }
+}
@@ -0,0 +1,151 @@
+//! Deadline for LLM calls (`--timeout`)
+//!
+//! Wraps any client so a hung request is cancelled instead of leaving the
+//! spinner running forever. The error is typed so callers can tell a timeout
+//! apart from other failures and fall back to the static result.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::LlmClient;
+use crate::error::CoptError;
+
+/// A `complete()` call that did not finish within the deadline
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("LLM call timed out after {}", format_duration(*.0))]
+pub struct LlmTimeout(pub Duration);
+
+/// Whether `err` is a `--timeout` deadline, raised directly or returned by
+/// a library entry point as [`CoptError::Timeout`]
+pub fn is_timeout(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause.is::<LlmTimeout>() || matches!(cause.downcast_ref(), Some(CoptError::Timeout(_)))
+    })
+}
+
+/// Human-readable duration: "90s", "500ms", "2m"
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if !millis.is_multiple_of(1000) {
+        format!("{}ms", millis)
+    } else if millis >= 60_000 && millis.is_multiple_of(60_000) {
+        format!("{}m", millis / 60_000)
+    } else {
+        format!("{}s", millis / 1000)
+    }
+}
+
+/// Parse a duration such as "60s", "2m", "500ms", or a bare number of seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a duration (e.g. 60s, 2m, 500ms)", s))?;
+    match unit {
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "ms" => Ok(Duration::from_millis(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        _ => Err(format!("unknown unit '{}' (use ms, s, or m)", unit)),
+    }
+}
+
+/// Client that cancels calls exceeding `timeout`
+pub struct TimeoutClient {
+    inner: Box<dyn LlmClient>,
+    timeout: Duration,
+}
+
+impl TimeoutClient {
+    pub fn new(inner: Box<dyn LlmClient>, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+#[async_trait]
+impl LlmClient for TimeoutClient {
+    async fn complete(
+        &self,
+        system: &str,
+        user_message: &str,
+        model: &str,
+        max_tokens: u32,
+    ) -> Result<String> {
+        // Dropping the future on timeout cancels the in-flight request
+        match tokio::time::timeout(
+            self.timeout,
+            self.inner.complete(system, user_message, model, max_tokens),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::debug!(timeout_ms = self.timeout.as_millis() as u64, "LLM timeout");
+                Err(LlmTimeout(self.timeout).into())
+            }
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    /// Client that never answers in time
+    struct HungClient;
+
+    #[async_trait]
+    impl LlmClient for HungClient {
+        async fn complete(&self, _: &str, _: &str, _: &str, _: u32) -> Result<String> {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(String::new())
+        }
+
+        fn provider_name(&self) -> &str {
+            "hung"
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("60s"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5h").is_err());
+
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m");
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+    }
+
+    #[tokio::test]
+    async fn test_hung_call_times_out() {
+        let client = TimeoutClient::new(Box::new(HungClient), Duration::from_millis(50));
+        let err = client
+            .complete("system", "user", "model", 10)
+            .await
+            .unwrap_err();
+
+        assert!(err.is::<LlmTimeout>());
+        assert_eq!(err.to_string(), "LLM call timed out after 50ms");
+    }
+
+    #[tokio::test]
+    async fn test_fast_call_passes_through() {
+        let client = TimeoutClient::new(
+            Box::new(MockLlmClient::with_responses(["done"])),
+            Duration::from_secs(60),
+        );
+        assert_eq!(client.complete("s", "u", "m", 10).await.unwrap(), "done");
+        assert_eq!(client.provider_name(), "mock");
+    }
+}
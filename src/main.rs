@@ -5,21 +5,31 @@
 
 use anyhow::{Context, Result};
 use chrono::Local;
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use std::io::{self, IsTerminal, Read};
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
-mod analyzer;
-mod cli;
-mod llm;
-mod optimizer;
-mod rules;
-mod tui;
-mod utils;
+use copt::error::{ErrorInfo, ProviderErrorKind};
+use copt::{
+    analyzer, cli, i18n, llm, optimizer, tui, utils, CoptError, Issue, OptimizationResult,
+    OptimizationStats,
+};
 
-// Re-export types from analyzer for use throughout the crate
-pub use analyzer::{Issue, Severity};
+/// Exit status when the LLM call failed and the static result was used
+///
+/// Distinct from 1 (nothing produced) so batch scripts can keep the output
+/// and retry just these prompts later.
+const EXIT_DEGRADED: i32 = 3;
+
+/// Lowest `copt discover` confidence `--embedded` treats as a prompt
+const EMBEDDED_THRESHOLD: f32 = 0.5;
+
+/// `--provider` when none is given: Bedrock, unless this build leaves it out
+#[cfg(feature = "bedrock")]
+const DEFAULT_PROVIDER: &str = "bedrock";
+#[cfg(not(feature = "bedrock"))]
+const DEFAULT_PROVIDER: &str = "anthropic";
 
 /// Claude Optimizer - A beautiful CLI tool to optimize prompts for Claude 4.5 models
 #[derive(Parser, Debug)]
@@ -30,6 +40,9 @@ pub use analyzer::{Issue, Severity};
     after_help = "Examples:\n  copt \"Your prompt here\"\n  copt -f prompt.txt\n  copt -f prompt.txt --offline\n  cat prompt.txt | copt"
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Prompt text to optimize
     #[arg(value_name = "PROMPT")]
     prompt: Option<String>,
@@ -38,10 +51,18 @@ struct Cli {
     #[arg(short, long, value_name = "FILE")]
     file: Option<PathBuf>,
 
-    /// Save optimized prompt to file
+    /// Save optimized prompt to file (or s3://bucket/prefix/)
     #[arg(short, long, value_name = "FILE")]
     output: Option<PathBuf>,
 
+    /// Server-side encryption for S3 output: AES256, aws:kms
+    #[arg(long, value_enum, value_name = "MODE")]
+    sse: Option<utils::s3::SseMode>,
+
+    /// KMS key ID or ARN for S3 output (implies --sse aws:kms)
+    #[arg(long, value_name = "KEY")]
+    sse_kms_key_id: Option<String>,
+
     /// Output directory for auto-save
     #[arg(
         long,
@@ -55,17 +76,21 @@ struct Cli {
     #[arg(long)]
     no_save: bool,
 
-    /// Provider: anthropic, bedrock
+    /// Analyze every prompt again instead of reusing cached results
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Provider: anthropic, bedrock, mock
     #[arg(
         short,
         long,
         value_enum,
-        default_value = "bedrock",
+        default_value = DEFAULT_PROVIDER,
         hide_default_value = true
     )]
     provider: Provider,
 
-    /// Model ID or alias
+    /// Model ID, alias, or Bedrock inference profile ARN
     #[arg(
         short,
         long,
@@ -86,10 +111,48 @@ struct Cli {
     #[arg(long)]
     diff: bool,
 
+    /// Wrap detected sections in semantic XML tags (shows the diff)
+    #[arg(long)]
+    structure: bool,
+
+    /// Append matching guidance from the enhancement library (built-in or rule pack)
+    #[arg(long)]
+    enhance: bool,
+
+    /// List the local files and folders the prompt references in an <available_documents> section
+    #[arg(long)]
+    bundle_docs: bool,
+
+    /// Apply only one class of static fixes (implies --offline): hygiene
+    #[arg(long, value_enum, value_name = "KIND")]
+    fix: Option<FixKind>,
+
     /// Display optimized prompt
     #[arg(long)]
     show_prompt: bool,
 
+    /// Print only this to stdout (implies --format quiet): optimized, original, issues, diff
+    #[arg(long, value_enum, value_name = "WHAT", conflicts_with = "emit")]
+    print: Option<PrintTarget>,
+
+    /// Print one value from the JSON output (implies --format json), e.g. .stats.optimized_tokens
+    #[arg(
+        long,
+        visible_alias = "jq",
+        value_name = "PATH",
+        value_parser = parse_json_path,
+        conflicts_with_all = ["print", "emit"]
+    )]
+    json_path: Option<String>,
+
+    /// Export for the Anthropic Console (console-url, workbench.json) or as TODO comments (todo)
+    #[arg(long, value_enum, value_name = "TARGET")]
+    emit: Option<cli::workbench::EmitTarget>,
+
+    /// Print long output directly instead of through $PAGER
+    #[arg(long)]
+    no_pager: bool,
+
     /// Quiet mode (prompt only)
     #[arg(short, long)]
     quiet: bool,
@@ -98,17 +161,88 @@ struct Cli {
     #[arg(long)]
     analyze: bool,
 
+    /// Optimize the prompts in the string literals of a Python, JavaScript/TypeScript,
+    /// or Rust file (-f) and write them back in place
+    #[arg(long, requires = "file", conflicts_with_all = ["prompt", "interactive", "editor"])]
+    embedded: bool,
+
     /// Offline mode (no API calls)
     #[arg(long)]
     offline: bool,
 
+    /// Prompts over the LLM input limit: warn (default), abort, analyze, chunk
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        default_value = "warn",
+        hide_default_value = true
+    )]
+    oversized: Oversized,
+
+    /// Cancel LLM calls slower than this and fall back to static fixes (e.g. 60s, 2m; 0 disables)
+    #[arg(long, value_name = "DURATION", default_value = "120s", value_parser = llm::parse_duration)]
+    timeout: std::time::Duration,
+
+    /// Warn when the LLM rewrites more than this share of lines (e.g. 40%)
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    max_change: Option<f32>,
+
+    /// Fail instead of warning when a rewrite exceeds --max-change
+    #[arg(long, requires = "max_change")]
+    strict: bool,
+
+    /// Refuse LLM calls that would take this run's estimated cost over this many USD (e.g. 0.50)
+    #[arg(long, value_name = "USD", value_parser = parse_cost)]
+    max_cost: Option<f64>,
+
+    /// Score original and optimized prompts with an LLM judge
+    #[arg(long)]
+    judge: bool,
+
+    /// Model for --judge (defaults to --model)
+    #[arg(long, value_name = "MODEL", requires = "judge")]
+    judge_model: Option<String>,
+
+    /// Generate N user test queries for system prompts
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=20))]
+    generate_tests: Option<u8>,
+
     /// Check specific categories
     #[arg(long, value_delimiter = ',', value_name = "CAT")]
     check: Option<Vec<String>>,
 
-    /// Interactively suggest improvements for vague prompts (default when TTY)
-    #[arg(long, hide = true)]
-    suggest: bool,
+    /// Hide issues below this confidence (0.0-1.0)
+    #[arg(long, value_name = "SCORE", value_parser = parse_confidence)]
+    min_confidence: Option<f32>,
+
+    /// List every rule category as checked (passed/flagged) or skipped, and why
+    #[arg(long)]
+    coverage: bool,
+
+    /// Organize the issue list under: category, rule, severity, line
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BY",
+        default_value = "category",
+        hide_default_value = true
+    )]
+    group_by: analyzer::report::GroupBy,
+
+    /// Order the issue list by: count, severity, line (default: as found)
+    #[arg(long, value_enum, value_name = "KEY")]
+    sort: Option<analyzer::report::SortBy>,
+
+    /// Add suggestion templates by ID (e.g. --suggest=state_management), or pick interactively
+    #[arg(
+        long,
+        value_name = "ID",
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ','
+    )]
+    suggest: Option<Vec<String>>,
 
     /// Disable auto-suggestions for vague prompts
     #[arg(long)]
@@ -118,23 +252,587 @@ struct Cli {
     #[arg(short, long)]
     interactive: bool,
 
+    /// Reopen the last interactive session where it left off, including an
+    /// optimized prompt that wasn't saved
+    #[arg(long, conflicts_with_all = ["prompt", "file", "editor"])]
+    resume: bool,
+
     /// Open editor for multi-line input
     #[arg(short = 'e', long)]
     editor: bool,
 
+    /// Wait for GUI editors to close the file (true adds --wait for editors copt doesn't know; false never waits)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    editor_wait: Option<bool>,
+
     /// Skip connectivity check
     #[arg(long)]
     skip_connectivity_check: bool,
 
-    /// Verbose output
+    /// Optimize even if the same prompt was optimized with this model recently
+    #[arg(long)]
+    force: bool,
+
+    /// Rewrite with the LLM even when the analysis finds no issues
+    #[arg(long, conflicts_with_all = ["analyze", "offline"])]
+    still_optimize: bool,
+
+    /// How freely the LLM rewrites: conservative, standard, aggressive; CATEGORY=LEVEL sets one category (repeatable)
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        value_parser = optimizer::level::parse_arg,
+        conflicts_with_all = ["analyze", "offline"]
+    )]
+    level: Vec<optimizer::level::LevelArg>,
+
+    /// Show per-issue examples, plus a timeline of the run on stderr
     #[arg(short, long)]
     verbose: bool,
+
+    /// Print time spent per analyzer category, static transform, and LLM call to stderr
+    #[arg(long)]
+    profile: bool,
+
+    /// Write progress to stderr as JSON lines (phase started/finished, percent, file) for wrapping tools
+    #[arg(long)]
+    progress_events: bool,
+
+    /// Mask emails, API keys, and names listed under [redact] in saved files and reports
+    #[arg(long)]
+    redact: bool,
+
+    /// Send names, companies, emails, and internal hosts to the LLM as placeholders, restored in the result
+    #[arg(long)]
+    anonymize: bool,
+
+    /// Write no files and open no editor; print results to stdout only
+    #[arg(long, conflicts_with_all = ["output", "editor"])]
+    sandbox: bool,
+
+    /// Interface language: en, es, ja (defaults to LANG)
+    #[arg(long, value_enum, value_name = "LANG", env = "COPT_LANG")]
+    lang: Option<i18n::Locale>,
+}
+
+/// Check `--model` and `--judge-model` after user aliases are expanded,
+/// along with the organization policy
+fn validate_models(cli: &Cli) -> Result<()> {
+    cli::validate_model(&cli.model)?;
+    if let Some(ref judge_model) = cli.judge_model {
+        cli::validate_model(judge_model).context("Invalid --judge-model")?;
+    }
+    if cli.provider == Provider::Anthropic && llm::models::is_arn(&cli.model) {
+        return Err(CoptError::Config(
+            "Inference profile ARNs only work with --provider bedrock. \
+            Use a model ID or alias with the Anthropic API."
+                .to_string(),
+        )
+        .into());
+    }
+    enforce_policy(cli)?;
+    let policy = cli::policy::load()?;
+    policy.check_model(&cli.model)?;
+    if let Some(ref judge_model) = cli.judge_model {
+        policy
+            .check_model(judge_model)
+            .context("Invalid --judge-model")?;
+    }
+    Ok(())
+}
+
+/// Refuse a provider or Bedrock region the organization policy bans
+fn enforce_policy(cli: &Cli) -> Result<()> {
+    let policy = cli::policy::load()?;
+    policy.check_provider(&provider_name(cli))?;
+    if cli.provider == Provider::Bedrock {
+        policy.check_region(&cli.region)?;
+    }
+    Ok(())
+}
+
+/// Check `--region` against the inference profile of `--model`
+///
+/// Profiles can only be called from regions in their geography. Unless
+/// `bedrock.region_failover` is off, switch to the nearest region that can
+/// call it; otherwise fail with the list of regions that can.
+fn select_bedrock_region(cli: &mut Cli) -> Result<()> {
+    let model_id = llm::models::bedrock_id(&cli.model);
+    let arn_region = llm::models::arn_region(&model_id);
+    if arn_region.is_none_or(|region| region == cli.region)
+        && llm::regions::supports(&model_id, &cli.region)
+    {
+        return Ok(());
+    }
+
+    let failover = cli::config::load_config()
+        .map(|config| config.bedrock.region_failover)
+        .unwrap_or(true);
+
+    // An ARN names its region; the profile can't be called from any other
+    if let Some(region) = arn_region {
+        if !failover {
+            return Err(CoptError::Config(format!(
+                "The --model ARN belongs to {}, but --region is {}. Pass --region {}.",
+                region, cli.region, region
+            ))
+            .into());
+        }
+        if !cli.quiet && cli.format != OutputFormat::Quiet {
+            eprintln!(
+                "  {} The --model ARN belongs to {}; using it instead of {}",
+                "ℹ".cyan(),
+                region,
+                cli.region
+            );
+        }
+        cli.region = region.to_string();
+        return Ok(());
+    }
+
+    // Requests leave the chosen region, so say how to stay in it
+    let stay = llm::regions::local_profile(&model_id, &cli.region)
+        .map(|profile| format!(" (to stay in {}, try --model {})", cli.region, profile))
+        .unwrap_or_default();
+    match llm::regions::nearest_region(&model_id, &cli.region) {
+        Some(region) if failover => {
+            if !cli.quiet && cli.format != OutputFormat::Quiet {
+                eprintln!(
+                    "  {} {} isn't offered in {}; using {} instead{}",
+                    "ℹ".cyan(),
+                    model_id,
+                    cli.region,
+                    region,
+                    stay
+                );
+            }
+            cli.region = region.to_string();
+            Ok(())
+        }
+        _ => Err(CoptError::Config(format!(
+            "{} isn't offered in {}{}.\n\n\
+            Regions that offer it: {}\n\
+            Pass one with --region, or pick another model with `copt models`.",
+            model_id,
+            cli.region,
+            stay,
+            llm::regions::profile_regions(&model_id)
+                .unwrap_or_default()
+                .join(", ")
+        ))
+        .into()),
+    }
+}
+
+/// Create the LLM client for the selected provider, bounded by `--timeout`
+async fn build_llm_client(cli: &Cli) -> Result<Box<dyn llm::LlmClient>> {
+    let client: Box<dyn llm::LlmClient> = match cli.provider {
+        Provider::Anthropic => {
+            let gateway = cli::config::load_config()?.anthropic.gateway();
+            // A gateway may authenticate with its own headers instead of an
+            // API key, and only gets the key when the config says so
+            let client = if gateway.is_gateway() && !gateway.send_api_key {
+                llm::AnthropicClient::new(String::new())?
+            } else {
+                match llm::AnthropicClient::from_saved_key() {
+                    Err(_) if gateway.is_gateway() => llm::AnthropicClient::new(String::new())?,
+                    client => client?,
+                }
+            };
+            Box::new(client.with_gateway(&gateway)?)
+        }
+        Provider::Bedrock => Box::new(llm::BedrockClient::new(&cli.region).await?),
+        Provider::Mock => Box::new(llm::MockLlmClient::new()),
+    };
+    let client: Box<dyn llm::LlmClient> = if llm::anonymize::enabled() {
+        Box::new(llm::AnonymizingClient::new(client))
+    } else {
+        client
+    };
+    let client: Box<dyn llm::LlmClient> = if cli.timeout.is_zero() {
+        client
+    } else {
+        Box::new(llm::TimeoutClient::new(client, cli.timeout))
+    };
+    // The mock provider costs nothing
+    if cli.provider == Provider::Mock {
+        return Ok(client);
+    }
+    Ok(Box::new(llm::BudgetClient::new(client)))
+}
+
+/// Apply `--max-cost` and `[budget] monthly_cap` to every LLM call
+fn install_budget(cli: &Cli) {
+    let monthly_cap = cli::config::load_config()
+        .ok()
+        .and_then(|config| config.budget.monthly_cap);
+    llm::budget::configure(llm::budget::Budget {
+        max_cost: cli.max_cost,
+        monthly_cap,
+        month_spent: monthly_cap.map_or(0.0, |_| cli::metrics::month_spend()),
+    });
+}
+
+/// Stop before the run when its optimization call alone would go over a
+/// spend limit
+///
+/// Over the monthly cap, a terminal user may go ahead anyway; `--max-cost`
+/// is never waived.
+fn confirm_spend(cli: &Cli, prompt: &str) -> Result<()> {
+    let estimate = llm::budget::estimate(&cli.model, llm::OPTIMIZER_SYSTEM_PROMPT, prompt, 4096);
+    let exceeded = match llm::budget::check(estimate) {
+        Ok(()) => return Ok(()),
+        Err(exceeded) => exceeded,
+    };
+    let message = exceeded.message(estimate);
+    if matches!(exceeded, llm::budget::Exceeded::Month { .. })
+        && cli::ask::enabled()
+        && io::stdin().is_terminal()
+        && io::stderr().is_terminal()
+    {
+        eprintln!();
+        eprintln!("  {} {}", "⚠".yellow().bold(), message.yellow().bold());
+        if cli::ask::confirm("Optimize anyway?", false)? {
+            eprintln!();
+            llm::budget::accept_month_overrun();
+            return Ok(());
+        }
+    }
+    Err(CoptError::CostLimit(format!(
+        "{}\n\nUse --offline for static fixes only, or a cheaper --model.",
+        message
+    ))
+    .into())
+}
+
+/// Hold an LLM rewrite to `--max-change`
+///
+/// A rewrite that changes a larger share of lines fails the run with
+/// `--strict`; otherwise it is reported, and on a terminal the user can take
+/// the static-only result instead.
+fn gate_rewrite(
+    cli: &Cli,
+    original: &str,
+    rewritten: String,
+    static_only: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    let Some(max_change) = cli.max_change else {
+        return Ok(rewritten);
+    };
+    let changed = tui::diff::diff_stats(original, &rewritten).change_ratio();
+    if changed <= max_change {
+        return Ok(rewritten);
+    }
+
+    let summary = format!(
+        "The LLM rewrite changed {:.0}% of lines, over the --max-change limit of {:.0}%",
+        changed * 100.0,
+        max_change * 100.0
+    );
+    if cli.strict {
+        anyhow::bail!(
+            "{}\n\n\
+            Raise --max-change to accept larger rewrites, or use --offline for static fixes only.",
+            summary
+        );
+    }
+
+    eprintln!();
+    eprintln!("  {} {}", "⚠".yellow().bold(), summary.yellow().bold());
+    if cli::ask::enabled() && io::stdin().is_terminal() && io::stderr().is_terminal() {
+        let use_static = cli::ask::confirm("Use the static-only result instead?", true)?;
+        eprintln!();
+        if use_static {
+            return static_only();
+        }
+    } else {
+        eprintln!(
+            "    {}",
+            "Keeping the rewrite; add --strict to fail instead, or use --offline for static fixes only."
+                .bright_black()
+        );
+        eprintln!();
+    }
+    Ok(rewritten)
+}
+
+/// Warn that the LLM timed out and the static result is shown instead
+fn print_timeout_banner(timeout: std::time::Duration) {
+    eprintln!();
+    eprintln!(
+        "  {} {}",
+        "⚠".yellow().bold(),
+        format!(
+            "LLM call timed out after {} — showing the statically optimized prompt instead",
+            llm::format_duration(timeout)
+        )
+        .yellow()
+        .bold()
+    );
+    eprintln!(
+        "    {}",
+        "Raise --timeout to wait longer, or use --offline to skip the LLM.".bright_black()
+    );
+    eprintln!();
+}
+
+/// Warn on stderr that the LLM call failed and the static result is shown
+fn print_degraded_banner(failure: &ErrorInfo) {
+    eprintln!();
+    eprintln!(
+        "  {} {}",
+        "⚠".yellow().bold(),
+        format!(
+            "LLM optimization failed ({}) — showing the statically optimized prompt instead",
+            failure.code
+        )
+        .yellow()
+        .bold()
+    );
+    eprintln!("    {}", failure.message.bright_black());
+    let hint = if failure.retryable {
+        "Run again later to get the LLM rewrite, or use --offline to skip the LLM."
+    } else {
+        "Fix the error above to get the LLM rewrite, or use --offline to skip the LLM."
+    };
+    eprintln!("    {}", hint.bright_black());
+    eprintln!();
+}
+
+/// Input limits from the config file (`[input]`)
+fn input_config() -> cli::config::InputConfig {
+    cli::config::load_config()
+        .map(|c| c.input)
+        .unwrap_or_default()
+}
+
+/// Apply `--oversized` to a prompt too large for a single LLM request
+fn guard_oversized(cli: &mut Cli, prompt: &str) -> Result<()> {
+    let limit = input_config().max_llm_tokens;
+    let tokens = utils::count_tokens(prompt);
+    if cli.offline || cli.analyze || tokens <= limit {
+        return Ok(());
+    }
+
+    let note = |text: String| {
+        if !cli.quiet && cli.format != OutputFormat::Quiet {
+            eprintln!("  {} {}", "ℹ".cyan(), text.bright_black());
+        }
+    };
+    match cli.oversized {
+        Oversized::Warn => {
+            if !cli.quiet && cli.format != OutputFormat::Quiet {
+                eprintln!(
+                    "  {} Prompt is ~{} tokens (limit {}); the rewrite may be cut short. \
+                    --oversized chunk rewrites it section by section.",
+                    "⚠".yellow(),
+                    tokens,
+                    limit
+                );
+            }
+        }
+        Oversized::Abort => anyhow::bail!(
+            "The prompt is ~{} tokens, over the {} the optimizer rewrites in one request.\n\n\
+            Choose how to handle it:\n\
+            --oversized analyze   report issues without rewriting\n\
+            --oversized chunk     rewrite section by section and join the results\n\
+            --offline             apply static fixes only\n\n\
+            Or raise max_llm_tokens under [input] in the config file.",
+            tokens,
+            limit
+        ),
+        Oversized::Analyze => {
+            note(format!(
+                "Prompt is ~{} tokens (limit {}); analyzing only",
+                tokens, limit
+            ));
+            cli.analyze = true;
+        }
+        Oversized::Chunk => note(format!(
+            "Prompt is ~{} tokens (limit {}); optimizing in {} chunks",
+            tokens,
+            limit,
+            utils::text::split_chunks(prompt, limit).len()
+        )),
+    }
+    Ok(())
+}
+
+/// Rewrite the prompt with the LLM, chunk by chunk under `--oversized chunk`
+async fn optimize_with_llm(
+    cli: &Cli,
+    prompt: &str,
+    issues: &[Issue],
+    client: &dyn llm::LlmClient,
+    prompt_type: analyzer::PromptType,
+) -> Result<String> {
+    let limit = input_config().max_llm_tokens;
+    if cli.oversized != Oversized::Chunk || utils::count_tokens(prompt) <= limit {
+        return optimizer::optimize_with_llm(
+            prompt,
+            issues,
+            client,
+            &cli.model,
+            prompt_type,
+            cli.check.as_deref(),
+        )
+        .await
+        .map_err(Into::into);
+    }
+
+    let chunks = utils::text::split_chunks(prompt, limit);
+    let mut optimized = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        tracing::debug!(
+            chunk = i + 1,
+            of = chunks.len(),
+            tokens = utils::count_tokens(chunk),
+            "optimizing chunk"
+        );
+        let chunk_issues = analyze_prompt(cli, chunk)?;
+        optimized.push(
+            optimizer::optimize_with_llm(
+                chunk,
+                &chunk_issues,
+                client,
+                &cli.model,
+                prompt_type,
+                cli.check.as_deref(),
+            )
+            .await?,
+        );
+    }
+    Ok(optimized.join("\n\n"))
+}
+
+/// Whether long pretty output may be paged (`--no-pager` or `output.pager = false` disable it)
+fn pager_enabled(cli: &Cli) -> bool {
+    !cli.no_pager
+        && cli::config::load_config()
+            .map(|c| c.output.pager)
+            .unwrap_or(true)
+}
+
+/// Parse a share such as "40%" (or "40") into a fraction, rejecting values over 100%
+fn parse_percent(s: &str) -> Result<f32, String> {
+    let value: f32 = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("'{}' is not a percentage (e.g. 40%)", s))?;
+    if !(0.0..=100.0).contains(&value) {
+        return Err(format!("{}% is not between 0% and 100%", value));
+    }
+    Ok(value / 100.0)
+}
+
+/// A `--max-cost` amount: "0.50" or "$0.50"
+fn parse_cost(s: &str) -> Result<f64, String> {
+    let value: f64 = s
+        .trim()
+        .trim_start_matches('$')
+        .parse()
+        .map_err(|_| format!("'{}' is not an amount in USD (e.g. 0.50)", s))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("{} is not a positive amount", s));
+    }
+    Ok(value)
+}
+
+/// Check a `--json-path` expression before any work is done
+fn parse_json_path(s: &str) -> Result<String, String> {
+    utils::json::select_json_path(&serde_json::Value::Null, s).map_err(|e| format!("{:#}", e))?;
+    Ok(s.to_string())
+}
+
+/// Parse a confidence threshold, rejecting values outside 0.0-1.0
+fn parse_confidence(s: &str) -> Result<f32, String> {
+    let value: f32 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{} is not between 0.0 and 1.0", value));
+    }
+    Ok(value)
+}
+
+/// Run the analyzer and drop issues below `--min-confidence`
+fn analyze_prompt(cli: &Cli, prompt: &str) -> Result<Vec<Issue>> {
+    let mut issues = analyzer::cache::analyze(prompt, cli.check.as_deref())?;
+    if let Some(min) = cli.min_confidence {
+        issues.retain(|i| i.confidence >= min);
+    }
+    Ok(issues)
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Compare original vs optimized prompt on golden test cases
+    Eval(cli::eval::EvalArgs),
+
+    /// Check that a prompt's responses parse as the declared format and match a JSON Schema
+    Verify(cli::verify::VerifyArgs),
+
+    /// Analyze prompt files matching glob patterns (read-only)
+    Lint(cli::lint::LintArgs),
+
+    /// Optimize every prompt in a directory into a mirrored output tree
+    Batch(cli::batch::BatchArgs),
+
+    /// Check a finished prompt for residual issues and structure (CI gate)
+    Audit(cli::audit::AuditArgs),
+
+    /// Print structural facts about a prompt (type, sections, tags, variables, ...) without judging it
+    Inspect(cli::inspect::InspectArgs),
+
+    /// Outline a prompt's tags and headings with instruction counts and each part's share of the tokens
+    Skeleton(cli::skeleton::SkeletonArgs),
+
+    /// Find earlier optimizations of similar prompts and reuse their results
+    Similar(cli::similar::SimilarArgs),
+
+    /// Compare candidate prompts side by side: score, issues per category, size, structure
+    Matrix(cli::matrix::MatrixArgs),
+
+    /// Re-audit a prompt directory on a schedule and report score regressions
+    Serve(cli::serve::ServeArgs),
+
+    /// List saved optimizations whose analysis differs under the current rules
+    Outdated(cli::outdated::OutdatedArgs),
+
+    /// Find likely prompts in a repository: instruction files, prompt files, and string literals in code
+    Discover(cli::discover::DiscoverArgs),
+
+    /// Print a prompt file with its score and issues on top, as a git textconv driver
+    GitTextconv(cli::textconv::TextconvArgs),
+
+    /// Save, check, or remove API keys in the OS keychain
+    Auth(cli::auth::AuthArgs),
+
+    /// Manage the team rule pack (rules.remote)
+    Rules(cli::rules::RulesArgs),
+
+    /// Check the config file for unknown keys and values copt can't use
+    Config(cli::config_check::ConfigArgs),
+
+    /// Open the best-practices documentation behind a rule
+    Docs(cli::docs::DocsArgs),
+
+    /// List model aliases, provider IDs, context windows, and prices
+    Models(cli::models::ModelsArgs),
+
+    /// Show or export local usage metrics (never sent anywhere)
+    Metrics(cli::metrics::MetricsArgs),
+
+    /// Developer tooling (corpus snapshots)
+    #[command(hide = true)]
+    Devtest(cli::devtest::DevtestArgs),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum Provider {
     Anthropic,
     Bedrock,
+    /// Canned responses, no network (for demos and tests)
+    Mock,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -144,51 +842,933 @@ enum OutputFormat {
     Quiet,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    if std::env::var("RUST_LOG").is_ok() {
-        tracing_subscriber::fmt::init();
+/// What `--print` writes to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PrintTarget {
+    /// The optimized prompt
+    Optimized,
+    /// The input prompt
+    Original,
+    /// Detected issues as a JSON array
+    Issues,
+    /// Unified diff from the original to the optimized prompt
+    Diff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FixKind {
+    /// Line endings, trailing whitespace, invisible characters, BOM, blank lines
+    Hygiene,
+}
+
+/// What to do with a prompt too large for one LLM request (`[input] max_llm_tokens`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Oversized {
+    /// Send it in one request anyway, with a warning
+    Warn,
+    /// Stop and explain the options
+    Abort,
+    /// Report issues without rewriting
+    Analyze,
+    /// Rewrite section by section and join the results
+    Chunk,
+}
+
+impl FixKind {
+    /// Rule category whose static fixes this kind applies
+    fn category(self) -> &'static str {
+        match self {
+            FixKind::Hygiene => "hygiene",
+        }
     }
+}
 
+fn main() -> Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse();
+    // With --json-path stdout carries only the selected value
+    let json = cli.format == OutputFormat::Json && cli.json_path.is_none();
+
+    let result = start(cli);
+    match result {
+        Ok(()) => utils::progress::done("ok", None),
+        Err(ref e) => {
+            utils::progress::done("error", serde_json::to_value(ErrorInfo::from_error(e)).ok())
+        }
+    }
+    match result {
+        // Scripts branch on the code; people still read the message on stderr
+        Err(e) if json => {
+            let info = ErrorInfo::from_error(&e);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({ "error": info }))?
+            );
+            eprintln!("{} {:#}", i18n::t("error.label").red().bold(), e);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+/// Everything after argument parsing; errors are reported by `main`
+fn start(mut cli: Cli) -> Result<()> {
+    prepare(&mut cli)?;
+    // Editor integrations and pre-commit hooks run the analysis on every
+    // save; it needs no network, so it skips starting the async runtime
+    if analysis_only(&cli) {
+        return run_analysis(cli);
+    }
+    tokio::runtime::Runtime::new()?.block_on(run(cli))
+}
+
+/// Logging, locale, policy, and the flags that imply other flags
+fn prepare(cli: &mut Cli) -> Result<()> {
+    // Initialize logging: RUST_LOG takes precedence; --verbose prints copt's
+    // own timeline to stderr (never in the TUI, which owns the terminal)
+    if std::env::var("RUST_LOG").is_ok() {
+        tracing_subscriber::fmt::init();
+    } else if cli.verbose && !cli.interactive {
+        init_verbose_timeline();
+    }
+
+    if let Some(lang) = cli.lang {
+        i18n::set_locale(lang);
+    }
+
+    if cli.profile {
+        utils::profile::enable();
+    }
+    if cli.progress_events {
+        utils::progress::enable();
+    }
+    utils::editor::configure(cli.editor_wait);
+    optimizer::level::configure(optimizer::level::Levels::from_args(&cli.level));
+
+    // A config file copt can't use fails here, once, instead of every reader
+    // quietly falling back to the defaults; `copt config validate` reports it
+    if !matches!(cli.command, Some(Commands::Config(_))) {
+        cli::config_check::check_installed()?;
+
+        // A bad [severity] section fails before anything is printed
+        if let Ok(config) = cli::config::load_config() {
+            tui::severity::configure(&config.severity)?;
+        }
+    }
+
+    // The organization policy wins over flags and the config file
+    let policy = cli::policy::load()?;
+    if policy.redact.required {
+        cli.redact = true;
+    }
+    analyzer::require_categories(&policy.rules.required_categories);
+
+    if cli.redact {
+        let names = cli::config::load_config()
+            .map(|config| config.redact.names)
+            .unwrap_or_default();
+        utils::redact::enable(names);
+    }
+
+    if cli.anonymize {
+        let config = cli::config::load_config()
+            .map(|config| config.anonymize)
+            .unwrap_or_default();
+        llm::anonymize::enable(llm::anonymize::Entities {
+            names: config.names,
+            companies: config.companies,
+            domains: config.domains,
+        });
+    }
+
+    // Nothing is saved, so the optimized prompt has to reach stdout
+    if cli.sandbox {
+        utils::sandbox::enable();
+        cli.no_save = true;
+        cli.show_prompt = true;
+    }
+
+    // Unchanged prompts skip analysis; nothing is cached when sandboxed, and
+    // --profile times the analysis itself
+    if !cli.no_cache && !cli.profile && !utils::sandbox::enabled() {
+        if let Some(dir) = analyzer::cache::default_dir() {
+            analyzer::cache::configure(dir);
+        }
+    }
+
+    // Ctrl+C restores the terminal, drops unfinished writes, and reports
+    // what completed before exiting
+    if let Err(e) = tui::terminal::install_signal_handlers() {
+        tracing::debug!("Failed to install signal handler: {}", e);
+    }
+
+    // --emit output is meant to be piped, so suppress progress and reports
+    if cli.emit.is_some() {
+        cli.quiet = true;
+    }
+
+    // --print picks one artifact for a pipeline; nothing else goes to stdout
+    if cli.print.is_some() {
+        cli.format = OutputFormat::Quiet;
+    }
+    if cli.json_path.is_some() {
+        cli.format = OutputFormat::Json;
+    }
+
+    // TODO comments annotate the prompt as written; nothing is rewritten
+    if cli.emit == Some(cli::workbench::EmitTarget::Todo) {
+        cli.analyze = true;
+    }
+
+    // --fix is a mechanical cleanup; it never calls an LLM
+    if cli.fix.is_some() {
+        cli.offline = true;
+    }
+    Ok(())
+}
+
+/// Skip the LLM rewrite of a prompt the analysis finds nothing wrong with
+///
+/// The report lists the prompt's strengths instead; `--still-optimize`
+/// rewrites it anyway, as do flags that ask for more than fixes (scoring,
+/// restructuring, added guidance or documents).
+fn guard_clean(cli: &mut Cli, prompt: &str) -> Result<()> {
+    let more_than_fixes = cli.judge
+        || cli.generate_tests.is_some()
+        || cli.structure
+        || cli.enhance
+        || cli.bundle_docs;
+    if cli.offline || cli.analyze || cli.still_optimize || more_than_fixes {
+        return Ok(());
+    }
+    if !analyze_prompt(cli, prompt)?.is_empty() {
+        return Ok(());
+    }
+    if !cli.quiet && cli.format != OutputFormat::Quiet {
+        eprintln!(
+            "  {} {}",
+            "ℹ".cyan(),
+            "No issues found, so the prompt wasn't sent to the LLM; pass --still-optimize to rewrite it anyway"
+                .bright_black()
+        );
+    }
+    cli.analyze = true;
+    Ok(())
+}
+
+/// Whether this run is `--offline --analyze` on a prompt, with nothing
+/// that needs the network, the TUI, or async file I/O
+fn analysis_only(cli: &Cli) -> bool {
+    cli.command.is_none()
+        && cli.offline
+        && cli.analyze
+        && !cli.interactive
+        && !cli.resume
+        && !cli.editor
+        && !cli.embedded
+        && cli.output.is_none()
+        // A remote rule pack may be due for a refresh
+        && cli::config::load_config().map_or(true, |config| config.rules.remote.is_none())
+}
+
+/// `--offline --analyze` without the async runtime
+///
+/// Same report as the full path, from the same stages; only the reads and
+/// the rule pack refresh are left out.
+fn run_analysis(mut cli: Cli) -> Result<()> {
+    install_config(&mut cli);
+
+    let prompt = read_input_prompt(&cli)?;
+    require_prompt(&prompt);
+    utils::input::check_size(prompt.len() as u64, input_config().max_bytes, "The prompt")?;
+
+    let start_time = std::time::Instant::now();
+    utils::progress::item(1, 1, progress_file(&cli));
+    let analysis = analyze_stage(&cli, &prompt)?;
+    let result = analysis_result(&cli, analysis, start_time)?;
+    let result = if utils::redact::enabled() {
+        redacted(&result)
+    } else {
+        result
+    };
+    print_result(&cli, &result)?;
+    record_metrics(&cli, &result.issues, &result.stats);
+
+    print_profile();
+    Ok(())
+}
+
+/// Everything after [`prepare`] on the async runtime
+async fn run(mut cli: Cli) -> Result<()> {
+    // Pull in the team rule pack, acronyms, and language settings; corpus
+    // snapshots stay on built-in defaults
+    if !matches!(
+        cli.command,
+        Some(Commands::Rules(_))
+            | Some(Commands::Config(_))
+            | Some(Commands::Metrics(_))
+            | Some(Commands::Devtest(_))
+            | Some(Commands::Auth(_))
+    ) {
+        cli::rules::install_configured(cli.offline).await;
+        install_config(&mut cli);
+    }
+    install_budget(&cli);
+
+    // Move Bedrock to a region that offers the model before the first request
+    if cli.provider == Provider::Bedrock
+        && !cli.offline
+        && matches!(
+            cli.command,
+            None | Some(Commands::Eval(_))
+                | Some(Commands::Verify(_))
+                | Some(Commands::Models(_))
+                | Some(Commands::Batch(_))
+        )
+    {
+        select_bedrock_region(&mut cli)?;
+    }
+
+    // Refreshing outdated prompts runs the optimize flow once per prompt
+    if matches!(cli.command, Some(Commands::Outdated(ref args)) if args.reoptimize) {
+        let Some(Commands::Outdated(args)) = cli.command.take() else {
+            unreachable!()
+        };
+        return reoptimize_outdated(cli, &args).await;
+    }
+
+    // Subcommands run independently of the optimize flow
+    if let Some(ref command) = cli.command {
+        let result = match command {
+            Commands::Eval(args) => {
+                validate_models(&cli)?;
+                let client = build_llm_client(&cli).await?;
+                cli::eval::run(
+                    args,
+                    client.as_ref(),
+                    &cli.model,
+                    cli.check.as_deref(),
+                    cli.format == OutputFormat::Json,
+                )
+                .await
+            }
+            Commands::Verify(args) => {
+                validate_models(&cli)?;
+                let client = build_llm_client(&cli).await?;
+                cli::verify::run(
+                    args,
+                    client.as_ref(),
+                    &cli.model,
+                    cli.format == OutputFormat::Json,
+                )
+                .await
+            }
+            Commands::Lint(args) => cli::lint::run(args, cli.check.as_deref(), cli.min_confidence),
+            Commands::Batch(args) => {
+                let client = if cli.offline {
+                    None
+                } else {
+                    validate_models(&cli)?;
+                    Some(build_llm_client(&cli).await?)
+                };
+                let provider = provider_name(&cli);
+                let settings = cli::batch::Settings {
+                    client: client.as_deref(),
+                    provider: &provider,
+                    model: &cli.model,
+                    output_dir: &cli.output_dir,
+                    check_categories: cli.check.as_deref(),
+                    min_confidence: cli.min_confidence,
+                    max_bytes: input_config().max_bytes,
+                };
+                cli::batch::run(args, &settings).await
+            }
+            Commands::Audit(args) => {
+                cli::audit::run(args, cli.check.as_deref(), cli.min_confidence)
+            }
+            Commands::Inspect(args) => cli::inspect::run(args, input_config().max_bytes),
+            Commands::Skeleton(args) => cli::skeleton::run(args, input_config().max_bytes),
+            Commands::Similar(args) => cli::similar::run(args, input_config().max_bytes),
+            Commands::Matrix(args) => {
+                cli::matrix::run(args, cli.check.as_deref(), cli.min_confidence)
+            }
+            Commands::Serve(args) => {
+                cli::serve::run(args, cli.check.as_deref(), cli.min_confidence).await
+            }
+            Commands::Outdated(args) => cli::outdated::run(
+                args,
+                &cli.output_dir,
+                cli.check.as_deref(),
+                cli.min_confidence,
+            )
+            .map(|_| ()),
+            Commands::Discover(args) => cli::discover::run(
+                args,
+                input_config().max_bytes,
+                cli.check.as_deref(),
+                cli.min_confidence,
+            ),
+            Commands::GitTextconv(args) => cli::textconv::run(
+                args,
+                input_config().max_bytes,
+                cli.check.as_deref(),
+                cli.min_confidence,
+            ),
+            Commands::Auth(args) => cli::auth::run(args),
+            Commands::Rules(args) => cli::rules::run(args).await,
+            Commands::Config(args) => cli::config_check::run(args),
+            Commands::Docs(args) => cli::docs::run(args),
+            Commands::Models(args) => {
+                let client = if args.check {
+                    enforce_policy(&cli)?;
+                    Some(build_llm_client(&cli).await?)
+                } else {
+                    None
+                };
+                let provider = cli.provider.to_possible_value().unwrap();
+                let region = (cli.provider == Provider::Bedrock).then_some(cli.region.as_str());
+                cli::models::run(
+                    args,
+                    provider.get_name(),
+                    region,
+                    &cli.model,
+                    client.as_deref(),
+                )
+                .await
+            }
+            Commands::Metrics(args) => cli::metrics::run(args),
+            Commands::Devtest(args) => cli::devtest::run(args),
+        };
+        print_profile();
+        return result;
+    }
+
+    // -i and --resume need the full-screen TUI, which minimal builds leave out
+    if (cli.interactive || cli.resume) && !cfg!(feature = "tui") {
+        return Err(CoptError::feature_disabled("tui", "Interactive mode (-i, --resume)").into());
+    }
 
     // Interactive mode requires TTY
-    if cli.interactive && !io::stdout().is_terminal() {
+    if (cli.interactive || cli.resume) && !io::stdout().is_terminal() {
         eprintln!(
-            "{} Interactive mode requires a terminal. Use without -i for piped output.",
-            "Error:".red().bold()
+            "{} {}",
+            i18n::t("error.label").red().bold(),
+            i18n::t("error.needs_terminal")
         );
         std::process::exit(1);
     }
 
+    // A bad [keys] section fails before any analysis or LLM call
+    #[cfg(feature = "tui")]
+    if cli.interactive || cli.resume {
+        if let Ok(config) = cli::config::load_config() {
+            tui::keys::configure(&config.keys)?;
+        }
+    }
+
+    // A resumed session already has its analysis and LLM result
+    #[cfg(feature = "tui")]
+    if cli.resume {
+        let mut model = tui::session::load()?.restore();
+        model.output_dir = cli.output_dir.clone();
+        tui::app::run_interactive(&mut model, &|text| {
+            analyze_prompt(&cli, text).unwrap_or_default()
+        })?;
+        save_interactive_result(&cli, &model).await?;
+        return Ok(());
+    }
+
+    // Catch model typos before they reach the provider as a cryptic error
+    if !cli.offline {
+        validate_models(&cli)?;
+    }
+
     // Check provider connectivity on first use (unless offline or skipped)
     if !cli.offline && !cli.skip_connectivity_check {
-        check_provider_connectivity(&cli).await?;
+        if let Err(e) = check_provider_connectivity(&cli).await {
+            retry_with_other_model(&mut cli, e).await?;
+        }
+    }
+
+    if cli.embedded {
+        return optimize_embedded(cli).await;
     }
 
     // Get the input prompt
     let prompt = get_input_prompt(&cli).await?;
-
-    if prompt.trim().is_empty() {
-        eprintln!(
-            "{} No prompt provided. Use --help for usage information.",
-            "Error:".red().bold()
-        );
-        std::process::exit(1);
+    require_prompt(&prompt);
+
+    utils::input::check_size(prompt.len() as u64, input_config().max_bytes, "The prompt")?;
+    guard_oversized(&mut cli, &prompt)?;
+    guard_clean(&mut cli, &prompt)?;
+
+    // Re-running a command from shell history shouldn't pay for the same
+    // rewrite twice unnoticed; the mock provider costs nothing
+    if uses_paid_llm(&cli) && !cli.force {
+        if let Some(previous) = find_duplicate(&cli, &prompt) {
+            if cli::history::fails_on_duplicate() {
+                return Err(duplicate_run_error(&previous));
+            }
+            warn_duplicate_run(&previous);
+        }
+    }
+    if uses_paid_llm(&cli) {
+        confirm_spend(&cli, &prompt)?;
     }
 
     // Run in interactive TUI mode or standard mode
     if cli.interactive {
+        #[cfg(feature = "tui")]
         run_interactive_mode(&cli, &prompt).await?;
     } else {
         // Standard mode
+        let started = std::time::Instant::now();
+        utils::progress::item(1, 1, progress_file(&cli));
         let result = run_optimization(&cli, &prompt).await?;
+        if !cli.analyze {
+            tui::terminal::set_interrupt_message(Some(
+                "optimization finished but saving did not; no partially written files were left"
+                    .to_string(),
+            ));
+        }
+        let saved = handle_output(&cli, &result).await?;
+        tui::terminal::set_interrupt_message(None);
+        record_metrics(&cli, &result.issues, &result.stats);
+        if uses_paid_llm(&cli) && result.stats.degraded.is_none() {
+            cli::history::record(&cli::history::HistoryEntry::new(
+                &prompt,
+                &result.stats.provider,
+                &cli.model,
+                &run_options(&cli),
+                saved.as_deref(),
+            ));
+        }
+        if !cli.analyze {
+            notify_finished(started, &result, saved.as_deref());
+        }
+        if result.stats.degraded.is_some() {
+            print_profile();
+            utils::progress::done("degraded", None);
+            std::process::exit(EXIT_DEGRADED);
+        }
+    }
+
+    print_profile();
+    Ok(())
+}
+
+/// `copt outdated --reoptimize`: list the outdated prompts, then optimize
+/// each saved original again, overwriting its result and metadata
+async fn reoptimize_outdated(mut cli: Cli, args: &cli::outdated::OutdatedArgs) -> Result<()> {
+    let outdated = cli::outdated::run(
+        args,
+        &cli.output_dir,
+        cli.check.as_deref(),
+        cli.min_confidence,
+    )?;
+    if outdated.is_empty() {
+        return Ok(());
+    }
+    // Re-optimizing overwrites the saved results and their metadata
+    utils::sandbox::ensure_writable("Re-optimizing outdated prompts")?;
+    if !cli.offline {
+        validate_models(&cli)?;
+    }
+
+    let started = std::time::Instant::now();
+    for (i, saved) in outdated.iter().enumerate() {
+        if cli.format == OutputFormat::Pretty {
+            println!(
+                "{} {}",
+                format!("[{}/{}]", i + 1, outdated.len()).bright_black(),
+                saved.original.display().to_string().white().bold()
+            );
+        }
+        let prompt = utils::file::read_prompt_file(&saved.original)?;
+        utils::progress::item(
+            i + 1,
+            outdated.len(),
+            Some(saved.original.display().to_string()),
+        );
+        cli.file = Some(saved.original.clone());
+        cli.output = Some(saved.optimized.clone());
+
+        let result = run_optimization(&cli, &prompt).await?;
+        let written = handle_output(&cli, &result).await?;
+        record_metrics(&cli, &result.issues, &result.stats);
+        if uses_paid_llm(&cli) && result.stats.degraded.is_none() {
+            cli::history::record(&cli::history::HistoryEntry::new(
+                &prompt,
+                &result.stats.provider,
+                &cli.model,
+                &run_options(&cli),
+                written.as_deref(),
+            ));
+        }
+    }
+
+    cli::notify::finished(
+        started,
+        "copt: optimization complete",
+        &format!(
+            "Re-optimized {} outdated prompt{}",
+            outdated.len(),
+            if outdated.len() == 1 { "" } else { "s" }
+        ),
+    );
+    print_profile();
+    Ok(())
+}
+
+/// Desktop notification for a finished optimization (see [`cli::notify`])
+fn notify_finished(
+    started: std::time::Instant,
+    result: &OptimizationResult,
+    saved: Option<&std::path::Path>,
+) {
+    let summary = if result.stats.degraded.is_some() {
+        "copt: optimization finished with static fixes only"
+    } else {
+        "copt: optimization complete"
+    };
+    let body = match saved {
+        Some(path) => format!("Saved to {}", path.display()),
+        None => format!(
+            "{} → {} tokens",
+            result.stats.original_tokens, result.stats.optimized_tokens
+        ),
+    };
+    cli::notify::finished(started, summary, &body);
+}
+
+/// The input file as `--progress-events` reports it; stdin and `--text` have none
+fn progress_file(cli: &Cli) -> Option<String> {
+    cli.file.as_ref().map(|p| p.display().to_string())
+}
+
+/// `--embedded`: optimize each prompt literal in the source file and write
+/// the results back into their literals
+async fn optimize_embedded(mut cli: Cli) -> Result<()> {
+    let Some(path) = cli.file.clone() else {
+        unreachable!("--embedded requires --file")
+    };
+    let Some(language) = utils::literals::SourceLanguage::from_path(&path) else {
+        return Err(CoptError::Config(format!(
+            "--embedded reads Python, JavaScript/TypeScript, or Rust files, not {}",
+            path.display()
+        ))
+        .into());
+    };
+    let source = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read source file: {}", path.display()))?;
+    utils::input::check_size(source.len() as u64, input_config().max_bytes, "The file")?;
+
+    let display = path.display().to_string();
+    let mut prompts = cli::embedded::find(&display, &source, language, EMBEDDED_THRESHOLD);
+    if cli.analyze {
+        for prompt in &mut prompts {
+            cli::discover::analyze(
+                &mut prompt.candidate,
+                cli.check.as_deref(),
+                cli.min_confidence,
+            )?;
+        }
+        let candidates: Vec<_> = prompts.into_iter().map(|p| p.candidate).collect();
+        match cli.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&candidates)?),
+            _ => cli::discover::print_candidates(&candidates, &path),
+        }
+        return Ok(());
+    }
+    if prompts.is_empty() {
+        eprintln!("  {} No prompts found in {}", "⚠".yellow(), display);
+        return Ok(());
+    }
+
+    // Checked before the first call; with `fail_on_duplicate` a repeated
+    // literal is left as it is and the rest still run
+    let mut duplicates = vec![false; prompts.len()];
+    if uses_paid_llm(&cli) && !cli.force {
+        let fail = cli::history::fails_on_duplicate();
+        for (prompt, duplicate) in prompts.iter().zip(&mut duplicates) {
+            let Some(previous) = find_duplicate(&cli, &prompt.prompt) else {
+                continue;
+            };
+            if fail {
+                *duplicate = true;
+                eprintln!(
+                    "  {} Left {}:{} as it was: {}",
+                    "⚠".yellow(),
+                    display,
+                    prompt.candidate.line,
+                    duplicate_run_message(&previous)
+                );
+            } else {
+                warn_duplicate_run(&previous);
+            }
+        }
+    }
+
+    // Each result is shown, not saved; the source file is the output
+    let target = cli.output.take().unwrap_or_else(|| path.clone());
+    cli.no_save = true;
+    let started = std::time::Instant::now();
+    let mut edits = Vec::new();
+    let mut degraded = false;
+    for (i, prompt) in prompts.iter().enumerate() {
+        if duplicates[i] {
+            continue;
+        }
+        let candidate = &prompt.candidate;
+        if cli.format == OutputFormat::Pretty {
+            println!(
+                "{} {}",
+                format!("[{}/{}]", i + 1, prompts.len()).bright_black(),
+                format!(
+                    "{}:{} {}",
+                    display,
+                    candidate.line,
+                    candidate.name.as_deref().unwrap_or_default()
+                )
+                .trim_end()
+                .white()
+                .bold()
+            );
+        }
+        utils::progress::item(
+            i + 1,
+            prompts.len(),
+            Some(format!("{}:{}", display, candidate.line)),
+        );
+        let result = run_optimization(&cli, &prompt.prompt).await?;
         handle_output(&cli, &result).await?;
+        record_metrics(&cli, &result.issues, &result.stats);
+        degraded |= result.stats.degraded.is_some();
+        if uses_paid_llm(&cli) && result.stats.degraded.is_none() {
+            cli::history::record(&cli::history::HistoryEntry::new(
+                &prompt.prompt,
+                &result.stats.provider,
+                &cli.model,
+                &run_options(&cli),
+                (!cli.sandbox).then_some(target.as_path()),
+            ));
+        }
+
+        match prompt.rewrite(language, &result.optimized) {
+            Ok(literal) => edits.push((prompt.range(), literal)),
+            Err(reason) => eprintln!(
+                "  {} Left {}:{} as it was: {}",
+                "⚠".yellow(),
+                display,
+                candidate.line,
+                reason
+            ),
+        }
+    }
+
+    let written = edits.len();
+    let updated = cli::embedded::splice(&source, edits);
+    if cli.sandbox {
+        print!("{}", updated);
+    } else if written > 0 {
+        utils::file::write_atomic(&target, &updated)?;
+        println!(
+            "\n{} Updated {} ({} of {} prompt{})\n",
+            "✓".green(),
+            tui::hyperlink::file(&target.display().to_string()),
+            written,
+            prompts.len(),
+            if prompts.len() == 1 { "" } else { "s" }
+        );
+    }
+
+    cli::notify::finished(
+        started,
+        "copt: optimization complete",
+        &format!(
+            "Updated {} of {} prompts in {}",
+            written,
+            prompts.len(),
+            target.display()
+        ),
+    );
+    print_profile();
+    if degraded {
+        utils::progress::done("degraded", None);
+        std::process::exit(EXIT_DEGRADED);
+    }
+    Ok(())
+}
+
+/// Acronyms, language, disabled rules, and model aliases from the config
+/// file, and the project roots EXP007 resolves references against
+fn install_config(cli: &mut Cli) {
+    if let Ok(config) = cli::config::load_config() {
+        analyzer::acronyms::configure(config.rules.acronyms);
+        analyzer::language::configure(config.language.enabled, config.language.dictionary);
+        // Retired IDs were warned about by the config check; disable their successors
+        analyzer::configure_disabled(
+            analyzer::deprecated::migrate(&config.rules.disabled),
+            config.rules.disabled_categories,
+        );
+        llm::models::configure(config.models.aliases);
+    }
+    cli.model = llm::models::expand_alias(&cli.model);
+    cli.judge_model = cli
+        .judge_model
+        .take()
+        .map(|m| llm::models::expand_alias(&m));
+    // EXP007 only checks references when copt runs inside a project
+    if let Ok(cwd) = std::env::current_dir() {
+        if let Some(root) = analyzer::references::project_root(&cwd) {
+            let mut roots = vec![cwd];
+            if roots[0] != root {
+                roots.push(root);
+            }
+            analyzer::references::configure(roots);
+        }
+    }
+}
+
+/// Exit with a usage error when there is no prompt to work on
+fn require_prompt(prompt: &str) {
+    if prompt.trim().is_empty() {
+        eprintln!(
+            "{} {}",
+            i18n::t("error.label").red().bold(),
+            i18n::t("error.no_prompt")
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Whether this run sends the prompt to a billed provider
+fn uses_paid_llm(cli: &Cli) -> bool {
+    !cli.offline && !cli.analyze && cli.provider != Provider::Mock
+}
+
+/// "anthropic", "bedrock", as recorded in stats and history
+fn provider_name(cli: &Cli) -> String {
+    format!("{:?}", cli.provider).to_lowercase()
+}
+
+/// Hash of the flags that change what an optimization produces
+fn run_options(cli: &Cli) -> String {
+    cli::history::options_hash(&format!(
+        "{:?}",
+        (
+            cli.structure,
+            cli.enhance,
+            cli.bundle_docs,
+            &cli.check,
+            cli.min_confidence,
+            &cli.suggest,
+            cli.no_suggest,
+            cli.still_optimize,
+            &cli.level,
+            cli.max_change,
+        )
+    ))
+}
+
+/// A recent run of `prompt` with the same provider, model, and options
+fn find_duplicate(cli: &Cli, prompt: &str) -> Option<cli::history::HistoryEntry> {
+    cli::history::find_recent(prompt, &provider_name(cli), &cli.model, &run_options(cli))
+}
+
+/// "already optimized with sonnet 12 minutes ago"
+fn duplicate_run_message(previous: &cli::history::HistoryEntry) -> String {
+    let ago = previous
+        .time()
+        .map(|time| format!(" {} ago", format_age(Local::now() - time)))
+        .unwrap_or_default();
+    format!("already optimized with {}{}", previous.model, ago)
+}
+
+/// Where the earlier run's result went
+fn duplicate_run_result(previous: &cli::history::HistoryEntry) -> String {
+    match previous.output {
+        Some(ref output) => format!("Result: {}", output),
+        None => "The result wasn't saved.".to_string(),
+    }
+}
+
+/// Point out that `previous` already optimized this prompt, then go on
+fn warn_duplicate_run(previous: &cli::history::HistoryEntry) {
+    eprintln!(
+        "  {} This prompt was {}. {}",
+        "⚠".yellow(),
+        duplicate_run_message(previous),
+        duplicate_run_result(previous)
+    );
+}
+
+/// Stop before optimizing a prompt that `previous` already optimized
+/// (`[history] fail_on_duplicate`)
+fn duplicate_run_error(previous: &cli::history::HistoryEntry) -> anyhow::Error {
+    CoptError::DuplicateRun(format!(
+        "This prompt was {}.\n\n\
+        {}\n\
+        Pass --force to optimize it again.",
+        duplicate_run_message(previous),
+        duplicate_run_result(previous)
+    ))
+    .into()
+}
+
+/// "3 minutes", "2 hours"
+fn format_age(age: chrono::Duration) -> String {
+    let (count, unit) = if age.num_hours() > 0 {
+        (age.num_hours(), "hour")
+    } else if age.num_minutes() > 0 {
+        (age.num_minutes(), "minute")
+    } else {
+        (age.num_seconds().max(0), "second")
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Print `--profile` timings to stderr, if profiling is enabled
+fn print_profile() {
+    if let Some(profile) = utils::profile::take() {
+        let _ = profile.write(&mut io::stderr().lock());
     }
+}
 
-    Ok(())
+/// Timeline of analysis, transforms, LLM calls, and saves on stderr
+fn init_verbose_timeline() {
+    use tracing_subscriber::fmt::time::uptime;
+
+    tracing_subscriber::fmt()
+        .with_env_filter("copt=debug")
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_timer(uptime())
+        .with_target(false)
+        .with_level(false)
+        .compact()
+        .init();
+}
+
+/// What completed if Ctrl+C arrives while the prompt is being optimized
+fn interrupted_after_analysis(issues: &[Issue], analysis_shown: bool) -> String {
+    if analysis_shown {
+        return "the analysis above is complete; optimization did not finish and nothing was saved"
+            .to_string();
+    }
+    let mut ids: Vec<&str> = issues.iter().map(|i| i.id.as_str()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    format!(
+        "analysis found {} issue{}{}; optimization did not finish and nothing was saved",
+        issues.len(),
+        if issues.len() == 1 { "" } else { "s" },
+        if ids.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", ids.join(", "))
+        }
+    )
 }
 
 /// Check connectivity to the configured provider
@@ -225,20 +1805,37 @@ async fn check_provider_connectivity(cli: &Cli) -> Result<()> {
                 }
             }
         }
+        // Mock provider never leaves the process
+        Provider::Mock => Ok(()),
         Provider::Anthropic => {
-            // Check if API key is set
-            if std::env::var("ANTHROPIC_API_KEY").is_err() {
-                anyhow::bail!(
-                    "ANTHROPIC_API_KEY environment variable not set.\n\n\
-                    Please set your Anthropic API key:\n\
-                    export ANTHROPIC_API_KEY=\"your-api-key-here\"\n\n\
-                    Or switch to AWS Bedrock provider:\n\
-                    copt --provider bedrock \"your prompt\""
-                );
+            let gateway = cli::config::load_config()?.anthropic.gateway();
+            if let Some(ref base_url) = gateway.base_url {
+                if !cli.quiet && cli.format != OutputFormat::Quiet {
+                    println!("{} Using Anthropic API via {}", "✓".green(), base_url);
+                    println!();
+                }
+                return Ok(());
             }
 
+            // Check if API key is set (keychain or environment)
+            let (_, source) = llm::credentials::anthropic_api_key().map_err(|e| {
+                CoptError::provider(
+                    ProviderErrorKind::Auth,
+                    format!(
+                        "{}\n\n\
+                        Or switch to AWS Bedrock provider:\n\
+                        copt --provider bedrock \"your prompt\"",
+                        e
+                    ),
+                )
+            })?;
+
             if !cli.quiet && cli.format != OutputFormat::Quiet {
-                println!("{} Using Anthropic API (API key configured)", "✓".green());
+                println!(
+                    "{} Using Anthropic API (API key from the {})",
+                    "✓".green(),
+                    source
+                );
                 println!();
             }
             Ok(())
@@ -246,6 +1843,71 @@ async fn check_provider_connectivity(cli: &Cli) -> Result<()> {
     }
 }
 
+/// Offer models the account can use after Bedrock refused `--model`
+///
+/// Only for model-access errors in an interactive terminal; anything else
+/// returns `err` unchanged.
+async fn retry_with_other_model(cli: &mut Cli, err: anyhow::Error) -> Result<()> {
+    let interactive = cli::ask::enabled()
+        && io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+        && !cli.quiet
+        && cli.format == OutputFormat::Pretty;
+    if cli.provider != Provider::Bedrock
+        || !interactive
+        || ErrorInfo::from_error(&err).code != "provider_model_unavailable"
+    {
+        return Err(err);
+    }
+
+    eprintln!("{} {:#}", i18n::t("error.label").red().bold(), err);
+    eprintln!();
+    print!(
+        "{} Checking which models this account can use in {}... ",
+        "⚡".cyan(),
+        cli.region.bright_black()
+    );
+    let _ = io::stdout().flush();
+    let client = build_llm_client(cli).await?;
+    // Models the organization policy forbids are never probed
+    let policy = cli::policy::load()?;
+    let alternatives = cli::models::accessible_alternatives(
+        "bedrock",
+        Some(&cli.region),
+        &cli.model,
+        client.as_ref(),
+        |alias| policy.allows_model(alias),
+    )
+    .await;
+    if alternatives.is_empty() {
+        println!("{}", "none".red());
+        println!();
+        return Err(err);
+    }
+    println!("{}", format!("{} found", alternatives.len()).green());
+    println!();
+
+    let mut items: Vec<&str> = alternatives.iter().map(|a| a.label.as_str()).collect();
+    items.push("Cancel");
+    let choice =
+        cli::ask::select("Use another model?", &items).context("Failed to read model choice")?;
+    let Some(alternative) = choice.and_then(|i| alternatives.get(i)) else {
+        return Err(err);
+    };
+
+    cli.model = alternative.alias.to_string();
+    println!(
+        "  {}",
+        format!(
+            "Next time, pass --model {} to skip this step.",
+            alternative.alias
+        )
+        .bright_black()
+    );
+    println!();
+    check_provider_connectivity(cli).await
+}
+
 /// Get the input prompt from various sources
 async fn get_input_prompt(cli: &Cli) -> Result<String> {
     // Priority: direct argument > file > stdin > interactive
@@ -254,20 +1916,20 @@ async fn get_input_prompt(cli: &Cli) -> Result<String> {
         return Ok(prompt.clone());
     }
 
+    // Files and stdin are checked for size and binary content before use
+    let max_bytes = input_config().max_bytes;
+
+    // A saved result's provenance footer isn't part of the prompt
     if let Some(ref file_path) = cli.file {
-        let content = tokio::fs::read_to_string(file_path)
+        return utils::input::read_file(file_path, max_bytes)
             .await
-            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
-        return Ok(content);
+            .map(|prompt| cli::provenance::strip(&prompt).to_string());
     }
 
     // Check if stdin has data (not a terminal)
     if !io::stdin().is_terminal() {
-        let mut buffer = String::new();
-        io::stdin()
-            .read_to_string(&mut buffer)
-            .context("Failed to read from stdin")?;
-        return Ok(buffer);
+        return utils::input::read_limited(io::stdin().lock(), max_bytes, "stdin")
+            .map(|prompt| cli::provenance::strip(&prompt).to_string());
     }
 
     if cli.editor {
@@ -278,6 +1940,22 @@ async fn get_input_prompt(cli: &Cli) -> Result<String> {
     Ok(String::new())
 }
 
+/// [`get_input_prompt`] without the editor, reading files synchronously
+fn read_input_prompt(cli: &Cli) -> Result<String> {
+    let max_bytes = input_config().max_bytes;
+    if let Some(ref prompt) = cli.prompt {
+        Ok(prompt.clone())
+    } else if let Some(ref file_path) = cli.file {
+        utils::input::read_file_blocking(file_path, max_bytes)
+            .map(|prompt| cli::provenance::strip(&prompt).to_string())
+    } else if !io::stdin().is_terminal() {
+        utils::input::read_limited(io::stdin().lock(), max_bytes, "stdin")
+            .map(|prompt| cli::provenance::strip(&prompt).to_string())
+    } else {
+        Ok(String::new())
+    }
+}
+
 /// Editor-based multi-line input mode
 async fn editor_input() -> Result<String> {
     println!("\n📝 Opening editor for multi-line input...\n");
@@ -290,37 +1968,33 @@ async fn editor_input() -> Result<String> {
     std::fs::write(&temp_path, initial_content)
         .with_context(|| format!("Failed to create temp file: {}", temp_path.display()))?;
 
-    // Get the editor from environment or use sensible defaults
-    let editor = std::env::var("EDITOR")
-        .or_else(|_| std::env::var("VISUAL"))
-        .unwrap_or_else(|_| {
-            // Try to find a common editor
-            if cfg!(target_os = "macos") {
-                "nano".to_string()
-            } else if cfg!(target_os = "windows") {
-                "notepad".to_string()
-            } else {
-                "vi".to_string()
-            }
-        });
-
-    // Build editor command with appropriate wait flags for GUI editors
-    // GUI editors fork and return immediately unless told to wait
-    let (editor_cmd, editor_args) = build_editor_command(&editor, &temp_path);
-
-    // Clone for the blocking task
-    let editor_cmd_clone = editor_cmd.clone();
-    let editor_args_clone = editor_args.clone();
+    // GUI editors return at once unless given their wait flag
+    let editor = utils::editor::command(&temp_path);
+    if let Some(reason) = editor.unavailable() {
+        let _ = std::fs::remove_file(&temp_path);
+        anyhow::bail!(
+            "Can't open an editor ({}); pass the prompt as an argument, with -f, or on stdin",
+            reason
+        );
+    }
+    if !editor.waits {
+        let _ = std::fs::remove_file(&temp_path);
+        anyhow::bail!(
+            "{} returns before the prompt is written; drop --editor-wait=false or use a terminal editor",
+            editor.program
+        );
+    }
+    let program = editor.program.clone();
 
     // Use spawn_blocking to properly wait for the editor process
     let status = tokio::task::spawn_blocking(move || {
-        std::process::Command::new(&editor_cmd_clone)
-            .args(&editor_args_clone)
+        std::process::Command::new(&editor.program)
+            .args(&editor.args)
             .status()
     })
     .await
     .context("Failed to spawn editor task")?
-    .with_context(|| format!("Failed to execute editor: {}", editor_cmd))?;
+    .with_context(|| format!("Failed to execute editor: {}", program))?;
 
     if !status.success() {
         // Clean up temp file
@@ -347,72 +2021,153 @@ async fn editor_input() -> Result<String> {
     Ok(prompt)
 }
 
-/// Build editor command with appropriate wait flags for GUI editors
+/// Append this run to the local metrics file (`copt metrics`)
+fn record_metrics(cli: &Cli, issues: &[Issue], stats: &OptimizationStats) {
+    cli::metrics::record(&cli::metrics::RunRecord::new(
+        &stats.provider,
+        cli.offline,
+        issues,
+        stats.original_score,
+        stats.optimized_score,
+        llm::budget::take_spent(),
+    ));
+}
+
+/// Score both prompts with the LLM judge when `--judge` is set
 ///
-/// GUI editors fork and return immediately unless given a --wait flag.
-/// We only add flags for editors we've verified support them.
-fn build_editor_command(editor: &str, file_path: &std::path::Path) -> (String, Vec<String>) {
-    let editor_lower = editor.to_lowercase();
-    let file_arg = file_path.to_string_lossy().to_string();
+/// Judge failures are reported as warnings; they never fail the optimization.
+async fn run_judge(
+    cli: &Cli,
+    original: &str,
+    optimized: &str,
+) -> Option<llm::judge::JudgeComparison> {
+    if !cli.judge {
+        return None;
+    }
+    if cli.offline {
+        eprintln!(
+            "  {} --judge requires an LLM; skipped in offline mode",
+            "⚠".yellow()
+        );
+        return None;
+    }
 
-    // Extract just the binary name for matching (handle full paths)
-    let editor_name = std::path::Path::new(editor)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(editor)
-        .to_lowercase();
+    let judge_model = cli.judge_model.as_deref().unwrap_or(&cli.model);
+    let result = match build_llm_client(cli).await {
+        Ok(client) => {
+            llm::judge::judge_prompts(client.as_ref(), judge_model, original, optimized).await
+        }
+        Err(e) => Err(e),
+    };
 
-    // VSCode: `code --wait` (verified)
-    if editor_name.contains("code") || editor_lower.contains("visual studio code") {
-        return (editor.to_string(), vec!["--wait".to_string(), file_arg]);
+    match result {
+        Ok(comparison) => Some(comparison),
+        Err(e) => {
+            eprintln!("  {} Judge scoring failed: {:#}", "⚠".yellow(), e);
+            None
+        }
     }
+}
 
-    // Zed: `zed --wait` or `/path/to/Zed.app/.../cli --wait` (verified)
-    if editor_name == "cli" && editor_lower.contains("zed") {
-        return (editor.to_string(), vec!["--wait".to_string(), file_arg]);
+/// Generate user test queries for the optimized prompt with `--generate-tests`
+///
+/// Only system prompts get queries. Like the judge, failures are warnings.
+async fn run_test_queries(
+    cli: &Cli,
+    original: &str,
+    optimized: &str,
+) -> Vec<llm::test_queries::TestQuery> {
+    let Some(count) = cli.generate_tests else {
+        return Vec::new();
+    };
+    if cli.offline {
+        eprintln!(
+            "  {} --generate-tests requires an LLM; skipped in offline mode",
+            "⚠".yellow()
+        );
+        return Vec::new();
     }
-    if editor_name.contains("zed") {
-        return (editor.to_string(), vec!["--wait".to_string(), file_arg]);
+    if !analyzer::is_system_prompt(original) {
+        eprintln!(
+            "  {} --generate-tests skipped: this doesn't look like a system prompt (no role or user-facing instructions)",
+            "⚠".yellow()
+        );
+        return Vec::new();
     }
 
-    // Default: terminal editors (vim, nano, emacs, etc.) block by default
-    (editor.to_string(), vec![file_arg])
+    let result = match build_llm_client(cli).await {
+        Ok(client) => {
+            llm::test_queries::generate_test_queries(
+                client.as_ref(),
+                &cli.model,
+                optimized,
+                count as usize,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(queries) => queries,
+        Err(e) => {
+            eprintln!("  {} Test query generation failed: {:#}", "⚠".yellow(), e);
+            Vec::new()
+        }
+    }
 }
 
-/// Main optimization result structure
-#[derive(Debug, Clone)]
-pub struct OptimizationResult {
-    pub original: String,
-    pub optimized: String,
-    pub issues: Vec<Issue>,
-    pub stats: OptimizationStats,
+/// Append the `<available_documents>` manifest for `--bundle-docs`
+///
+/// Paths are resolved against the working directory; references that don't
+/// exist are reported, since the model would otherwise invent their contents.
+fn bundle_docs(cli: &Cli, prompt: &str, optimized: &str) -> Result<String> {
+    let root = std::env::current_dir().context("Failed to read the working directory")?;
+    let bundle = optimizer::bundle::DocsBundle::collect(prompt, &root);
+
+    for reference in &bundle.missing {
+        eprintln!(
+            "  {} --bundle-docs: {} (line {}) does not exist in {}",
+            "⚠".yellow(),
+            reference.path,
+            reference.line,
+            root.display()
+        );
+    }
+    if bundle.documents.is_empty() && !cli.quiet {
+        eprintln!(
+            "  {} --bundle-docs: no referenced files found; prompt left as is",
+            "⚠".yellow()
+        );
+    }
+    Ok(bundle.append_to(optimized))
 }
 
-/// Statistics about the optimization
-#[derive(Debug, Clone, Default)]
-pub struct OptimizationStats {
-    pub original_chars: usize,
-    pub optimized_chars: usize,
-    pub original_tokens: usize,
-    pub optimized_tokens: usize,
-    pub rules_applied: usize,
-    pub categories_improved: usize,
-    pub processing_time_ms: u64,
-    pub provider: String,
-    pub model: String,
+/// The analyzed prompt, ready for optimizing
+struct Analysis {
+    /// The prompt with any accepted suggestions applied
+    prompt: String,
+    issues: Vec<Issue>,
+    /// The pretty report's state, when one is shown
+    model: Option<tui::model::Model>,
+    /// Whether the suggestion flow was offered
+    suggested: bool,
 }
 
-/// Run the optimization process
-async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult> {
+/// Analyze the prompt and apply suggestions, the stage shared by the
+/// full run and `--analyze`
+fn analyze_stage(cli: &Cli, prompt: &str) -> Result<Analysis> {
     use tui::model::{AppPhase, Model};
 
-    let start_time = std::time::Instant::now();
     let use_new_renderer = !cli.quiet && cli.format == OutputFormat::Pretty;
 
     // Build model for new renderer
     let mut model = if use_new_renderer {
         let mut m = Model::new();
         m.offline_mode = cli.offline;
+        m.verbose = cli.verbose;
+        m.group_by = cli.group_by;
+        m.sort_by = cli.sort;
         m.original_prompt = prompt.to_string();
         m.input_file = cli.file.as_ref().map(|p| p.display().to_string());
         m.phase = AppPhase::Analyzing;
@@ -422,23 +2177,38 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
     };
 
     // Analyze the prompt
-    let issues = analyzer::analyze(prompt, cli.check.as_deref())?;
-
-    // Classify prompt type for context-aware LLM optimization
-    let prompt_type = analyzer::classify_prompt(prompt);
+    utils::progress::started(utils::progress::Phase::Analyze);
+    let issues = analyze_prompt(cli, prompt)?;
+    utils::progress::finished(utils::progress::Phase::Analyze);
 
     // Update model with issues
     if let Some(ref mut m) = model {
-        m.set_issues(&issues);
+        m.set_issues(&utils::redact::issues(&issues));
+        if cli.coverage {
+            m.coverage = analyzer::coverage::coverage(prompt, cli.check.as_deref(), &issues);
+        }
+        if issues.is_empty() {
+            m.strengths = Some(analyzer::strengths::report(
+                prompt,
+                &analyzer::coverage::coverage(prompt, cli.check.as_deref(), &issues),
+            ));
+        }
     }
 
-    // Auto-suggest improvements for vague prompts (EXP005/EXP006)
-    // Triggers automatically when: TTY + vague prompt + not --no-suggest
-    let is_tty = io::stdout().is_terminal();
-    let should_auto_suggest =
-        (cli.suggest || is_tty) && !cli.no_suggest && cli::suggest::should_suggest(&issues);
-
-    let prompt = if should_auto_suggest {
+    // Auto-suggest improvements for vague prompts (EXP005/EXP006) and
+    // long-horizon tasks (LHT001/LHT002)
+    // Triggers automatically when: TTY + matching issues + not --no-suggest
+    // An explicit --suggest=ID,... applies templates without prompting
+    let requested_suggestions = cli.suggest.as_deref().unwrap_or_default();
+    let is_tty = cli::ask::enabled() && io::stdout().is_terminal();
+    let should_auto_suggest = (cli.suggest.is_some() || is_tty)
+        && requested_suggestions.is_empty()
+        && !cli.no_suggest
+        && cli::suggest::should_suggest(&issues);
+
+    let prompt = if !requested_suggestions.is_empty() {
+        cli::suggest::apply_suggestions_by_id(prompt, requested_suggestions)?
+    } else if should_auto_suggest {
         // Render header/analysis first so user sees context
         if let Some(ref mut m) = model {
             m.phase = AppPhase::AnalysisDone;
@@ -460,36 +2230,75 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
     } else {
         prompt.to_string()
     };
-    let prompt = prompt.as_str();
+
+    Ok(Analysis {
+        prompt,
+        issues,
+        model,
+        suggested: should_auto_suggest,
+    })
+}
+
+/// The `--analyze` result: the prompt unchanged, with its analysis shown
+fn analysis_result(
+    cli: &Cli,
+    mut analysis: Analysis,
+    start_time: std::time::Instant,
+) -> Result<OptimizationResult> {
+    let prompt = analysis.prompt.as_str();
+    let issues = &analysis.issues;
+    let stats = OptimizationStats {
+        original_chars: prompt.len(),
+        optimized_chars: prompt.len(),
+        original_tokens: utils::count_tokens(prompt),
+        optimized_tokens: utils::count_tokens(prompt),
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+        provider: format!("{:?}", cli.provider).to_lowercase(),
+        model: cli.model.clone(),
+        original_score: analyzer::quality_score(issues),
+        optimized_score: analyzer::quality_score(issues),
+        ..Default::default()
+    };
+
+    // Update model phase and render
+    if let Some(ref mut m) = analysis.model {
+        m.phase = tui::model::AppPhase::AnalysisDone;
+        tui::linear::render(m)?;
+    }
+
+    Ok(OptimizationResult {
+        original: analysis.prompt.clone(),
+        optimized: analysis.prompt,
+        issues: analysis.issues,
+        stats,
+    })
+}
+
+/// Run the optimization process
+async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult> {
+    use tui::model::AppPhase;
+
+    let start_time = std::time::Instant::now();
+    let analysis = analyze_stage(cli, prompt)?;
 
     // If analyze-only mode, return early without optimization
     // In LLM mode, we still optimize even if no static rules triggered
     // (the LLM can enhance prompts beyond what static rules detect)
     if cli.analyze {
-        let stats = OptimizationStats {
-            original_chars: prompt.len(),
-            optimized_chars: prompt.len(),
-            original_tokens: utils::count_tokens(prompt),
-            optimized_tokens: utils::count_tokens(prompt),
-            processing_time_ms: start_time.elapsed().as_millis() as u64,
-            provider: format!("{:?}", cli.provider).to_lowercase(),
-            model: cli.model.clone(),
-            ..Default::default()
-        };
+        return analysis_result(cli, analysis, start_time);
+    }
 
-        // Update model phase and render
-        if let Some(ref mut m) = model {
-            m.phase = AppPhase::AnalysisDone;
-            tui::linear::render(m)?;
-        }
+    // Classify prompt type for context-aware LLM optimization
+    let prompt_type = analyzer::classify_prompt(prompt);
 
-        return Ok(OptimizationResult {
-            original: prompt.to_string(),
-            optimized: prompt.to_string(),
-            issues,
-            stats,
-        });
-    }
+    let Analysis {
+        prompt,
+        issues,
+        mut model,
+        suggested: should_auto_suggest,
+    } = analysis;
+    let prompt = prompt.as_str();
+    let use_new_renderer = model.is_some();
 
     // Show header and analysis before optimization starts
     if let Some(ref mut m) = model {
@@ -497,6 +2306,10 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
         // Render header, input info, and analysis
         tui::linear::render(m)?;
     }
+    tui::terminal::set_interrupt_message(Some(interrupted_after_analysis(
+        &issues,
+        model.is_some(),
+    )));
 
     // Show suggestion hint in offline mode if vague prompt detected (only if suggestions were skipped)
     if cli.offline && !should_auto_suggest && cli::suggest::should_suggest(&issues) && !cli.quiet {
@@ -504,44 +2317,133 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
     }
 
     // Perform optimization
+    utils::progress::started(utils::progress::Phase::Optimize);
+    // Static fixes run in both modes (optimize_with_llm applies them before
+    // the LLM sees the prompt); trace them here for the report
+    let fix_issues: Vec<analyzer::Issue> = match cli.fix {
+        Some(kind) => issues
+            .iter()
+            .filter(|i| i.category == kind.category())
+            .cloned()
+            .collect(),
+        None => issues.clone(),
+    };
+    let (static_optimized, mut transforms) =
+        optimizer::optimize_static_traced(prompt, &fix_issues)?;
+    let mut timed_out = false;
+    let mut degraded = None;
+    for applied in &transforms.applied {
+        tracing::debug!(
+            rule = %applied.rule_id,
+            edits = applied.replacements,
+            "static transform applied"
+        );
+    }
     let optimized = if cli.offline {
         // Static rules only
-        optimizer::optimize_static(prompt, &issues)?
+        static_optimized
     } else {
         // Start optimization spinner for LLM mode
         let spinner = if use_new_renderer {
-            Some(tui::renderer::start_optimizing_spinner(&cli.model))
+            Some(tui::linear::start_spinner(&cli.model))
         } else {
             None
         };
 
         // LLM-powered optimization
-        let client: Box<dyn llm::LlmClient> = match cli.provider {
-            Provider::Anthropic => Box::new(llm::AnthropicClient::new(
-                std::env::var("ANTHROPIC_API_KEY")
-                    .context("ANTHROPIC_API_KEY environment variable not set")?,
-            )?),
-            Provider::Bedrock => Box::new(llm::BedrockClient::new(&cli.region).await?),
+        let result = match build_llm_client(cli).await {
+            Ok(client) => {
+                optimize_with_llm(cli, prompt, &issues, client.as_ref(), prompt_type).await
+            }
+            Err(e) => Err(e),
         };
-
-        let result =
-            optimizer::optimize_with_llm(prompt, &issues, client.as_ref(), &cli.model, prompt_type)
-                .await?;
         if let Some(s) = spinner {
-            tui::renderer::stop_optimizing_spinner(s);
+            tui::linear::finish_spinner(s);
+            let hidden = llm::anonymize::count();
+            if hidden > 0 {
+                eprintln!(
+                    "  {} {} detail{} sent as placeholders (--anonymize)",
+                    "ℹ".blue(),
+                    hidden,
+                    if hidden == 1 { "" } else { "s" }
+                );
+            }
         }
-        result
+        match result {
+            Ok(optimized) => gate_rewrite(cli, prompt, optimized, move || Ok(static_optimized))?,
+            // The analysis already succeeded; keep it and the static fixes
+            // rather than losing the whole run to one failed request
+            Err(e) => {
+                let failure = ErrorInfo::from_error(&e);
+                if llm::is_timeout(&e) {
+                    print_timeout_banner(cli.timeout);
+                    timed_out = true;
+                } else {
+                    print_degraded_banner(&failure);
+                }
+                degraded = Some(failure);
+                static_optimized
+            }
+        }
+    };
+
+    // Explicit restructuring runs on top of whatever the optimizer produced
+    let optimized = if cli.structure {
+        let structured = optimizer::structure_with_xml(&optimized);
+        transforms.record("FMT003", optimizer::count_edits(&optimized, &structured));
+        structured
+    } else {
+        optimized
+    };
+
+    let (optimized, enhancements) = if cli.enhance {
+        optimizer::enhance::apply(prompt, &optimized)
+    } else {
+        (optimized, Vec::new())
+    };
+
+    let optimized = if cli.bundle_docs {
+        bundle_docs(cli, prompt, &optimized)?
+    } else {
+        optimized
     };
+    utils::progress::finished(utils::progress::Phase::Optimize);
 
     let processing_time = start_time.elapsed().as_millis() as u64;
 
+    // Score the rewrite with the same heuristics used on the input
+    tracing::debug!(elapsed_ms = processing_time, "scoring optimized prompt");
+    let optimized_issues = analyze_prompt(cli, &optimized)?;
+    let mut changelog = optimizer::changelog::summarize(
+        prompt,
+        &optimized,
+        &transforms,
+        &issues,
+        &optimized_issues,
+    );
+    if !enhancements.is_empty() {
+        changelog.push(format!(
+            "Appended enhancements: {}",
+            enhancements.join(", ")
+        ));
+    }
+    let scored = cli.judge || cli.generate_tests.is_some();
+    if scored {
+        utils::progress::started(utils::progress::Phase::Judge);
+    }
+    let judge = run_judge(cli, prompt, &optimized).await;
+    let test_queries = run_test_queries(cli, prompt, &optimized).await;
+    if scored {
+        utils::progress::finished(utils::progress::Phase::Judge);
+    }
+
     // Calculate stats
     let stats = OptimizationStats {
         original_chars: prompt.len(),
         optimized_chars: optimized.len(),
         original_tokens: utils::count_tokens(prompt),
         optimized_tokens: utils::count_tokens(&optimized),
-        rules_applied: issues.len(),
+        issues_detected: issues.len(),
         categories_improved: issues
             .iter()
             .map(|i| i.category.as_str())
@@ -550,6 +2452,15 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
         processing_time_ms: processing_time,
         provider: format!("{:?}", cli.provider).to_lowercase(),
         model: cli.model.clone(),
+        original_score: analyzer::quality_score(&issues),
+        optimized_score: analyzer::quality_score(&optimized_issues),
+        judge,
+        test_queries,
+        transforms,
+        changelog,
+        enhancements,
+        timed_out,
+        degraded,
     };
 
     Ok(OptimizationResult {
@@ -561,97 +2472,72 @@ async fn run_optimization(cli: &Cli, prompt: &str) -> Result<OptimizationResult>
 }
 
 /// Handle output based on CLI options
-async fn handle_output(cli: &Cli, result: &OptimizationResult) -> Result<()> {
-    use tui::model::{AppPhase, Model};
-
-    match cli.format {
-        OutputFormat::Json => {
-            let json = serde_json::json!({
-                "original": result.original,
-                "optimized": result.optimized,
-                "issues": result.issues.iter().map(|i| serde_json::json!({
-                    "id": i.id,
-                    "category": i.category,
-                    "severity": format!("{:?}", i.severity).to_lowercase(),
-                    "message": i.message,
-                    "line": i.line,
-                    "suggestion": i.suggestion,
-                })).collect::<Vec<_>>(),
-                "stats": {
-                    "original_chars": result.stats.original_chars,
-                    "optimized_chars": result.stats.optimized_chars,
-                    "original_tokens": result.stats.original_tokens,
-                    "optimized_tokens": result.stats.optimized_tokens,
-                    "rules_applied": result.stats.rules_applied,
-                    "categories_improved": result.stats.categories_improved,
-                    "processing_time_ms": result.stats.processing_time_ms,
-                    "provider": result.stats.provider,
-                    "model": result.stats.model,
-                }
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        }
-        OutputFormat::Quiet => {
-            println!("{}", result.optimized);
-        }
-        OutputFormat::Pretty => {
-            // Use new linear renderer for stats
-            if !cli.offline && !result.issues.is_empty() {
-                let mut model = Model::new();
-                model.offline_mode = cli.offline;
-                model.original_prompt = result.original.clone();
-                model.input_file = cli.file.as_ref().map(|p| p.display().to_string());
-                model.set_issues(&result.issues);
-                model.set_optimization_result(result.optimized.clone(), result.stats.clone());
-                model.phase = AppPhase::Done;
-
-                // Render stats section only (header/analysis already shown)
-                tui::linear::render_stats_only(&model)?;
-            }
-
-            if cli.diff {
-                tui::diff::print_diff(&result.original, &result.optimized);
-            }
+///
+/// Returns where the optimized prompt was saved, if it was.
+async fn handle_output(cli: &Cli, result: &OptimizationResult) -> Result<Option<PathBuf>> {
+    // Reports and saved files never show what --redact masks; the result
+    // itself stays as the optimizer produced it
+    let redacted_result;
+    let result = if utils::redact::enabled() {
+        redacted_result = redacted(result);
+        &redacted_result
+    } else {
+        result
+    };
 
-            // In offline mode, show helpful message
-            if cli.offline {
-                println!();
-                println!("  {}", "─".repeat(70).bright_black());
-                println!(
-                    "  {}  {}",
-                    "💡".cyan(),
-                    "To optimize this prompt with an LLM, run without --offline".white()
-                );
-                println!("  {}", "─".repeat(70).bright_black());
-                println!();
-            } else if !cli.diff && cli.show_prompt {
-                tui::renderer::print_optimized_prompt(&result.optimized);
-            }
-        }
-    }
+    print_result(cli, result)?;
+    utils::progress::started(utils::progress::Phase::Save);
 
     // Determine the output path
     // In offline mode, don't auto-save unless user explicitly specifies -o
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let default_filename =
+        utils::file::output_file_name("optimized", &timestamp, &result.optimized, "txt");
     let output_path = if let Some(ref explicit_output) = cli.output {
         // User specified explicit output path (always respect this)
-        Some(explicit_output.clone())
+        match utils::s3::S3Location::parse(explicit_output).transpose()? {
+            // An S3 prefix gets a generated name, like --output-dir
+            Some(location) if location.is_prefix() => Some(PathBuf::from(
+                location.sibling(&default_filename).to_string(),
+            )),
+            _ => Some(explicit_output.clone()),
+        }
     } else if !cli.no_save && !cli.offline && !cli.analyze && cli.format != OutputFormat::Json {
-        // Auto-save to output directory (only when not in offline mode or analyze mode)
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("optimized_{}.txt", timestamp);
-        Some(cli.output_dir.join(filename))
+        // Auto-save to output directory (only when not in offline mode or analyze mode).
+        // The name is claimed up front so concurrent runs never share it.
+        tokio::fs::create_dir_all(&cli.output_dir)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create output directory: {}",
+                    cli.output_dir.display()
+                )
+            })?;
+        Some(utils::file::reserve_output_path(
+            &cli.output_dir,
+            &default_filename,
+        )?)
     } else {
         None
     };
 
     // Save the optimized prompt and original prompt for comparison
     if let Some(ref path) = output_path {
-        // Create output directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent).await.with_context(|| {
-                format!("Failed to create output directory: {}", parent.display())
-            })?;
-        }
+        utils::sandbox::ensure_writable(&format!("Saving {}", path.display()))?;
+        // s3:// destinations are uploaded; local ones need their directory
+        let uploader = if utils::s3::S3Location::parse(path).is_some() {
+            Some(
+                utils::s3::S3Uploader::new(&cli.region, cli.sse, cli.sse_kms_key_id.clone())
+                    .await?,
+            )
+        } else {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await.with_context(|| {
+                    format!("Failed to create output directory: {}", parent.display())
+                })?;
+            }
+            None
+        };
 
         // Derive original prompt path from optimized path
         let original_path = {
@@ -660,20 +2546,41 @@ async fn handle_output(cli: &Cli, result: &OptimizationResult) -> Result<()> {
             path.with_file_name(original_filename)
         };
 
-        // Write the optimized prompt
-        tokio::fs::write(path, &result.optimized)
+        // Write the optimized prompt, ending with how it was produced
+        let provenance = cli::provenance::Provenance::new(
+            &result.stats.provider,
+            (!cli.offline && result.stats.degraded.is_none())
+                .then(|| llm::models::provider_id(&result.stats.provider, &result.stats.model)),
+            &result.original,
+        );
+        let saved = if cli::provenance::enabled() {
+            cli::provenance::append(&result.optimized, &provenance)
+        } else {
+            result.optimized.clone()
+        };
+        save_artifact(uploader.as_ref(), path, &saved, "text/plain")
             .await
             .with_context(|| format!("Failed to write to: {}", path.display()))?;
 
         // Write the original prompt for comparison
-        tokio::fs::write(&original_path, &result.original)
-            .await
-            .with_context(|| format!("Failed to write original: {}", original_path.display()))?;
+        save_artifact(
+            uploader.as_ref(),
+            &original_path,
+            &result.original,
+            "text/plain",
+        )
+        .await
+        .with_context(|| format!("Failed to write original: {}", original_path.display()))?;
 
         // Also write metadata JSON alongside
         let metadata_path = path.with_extension("json");
         let metadata = serde_json::json!({
             "timestamp": Local::now().to_rfc3339(),
+            // What `copt outdated` compares against
+            "copt_version": env!("CARGO_PKG_VERSION"),
+            "rule_set": analyzer::ruleset::hash(),
+            "meta_prompt": provenance.meta_prompt,
+            "input_hash": provenance.input,
             "files": {
                 "original": original_path.file_name().unwrap().to_string_lossy(),
                 "optimized": path.file_name().unwrap().to_string_lossy(),
@@ -682,32 +2589,280 @@ async fn handle_output(cli: &Cli, result: &OptimizationResult) -> Result<()> {
             "optimized_length": result.stats.optimized_chars,
             "original_tokens": result.stats.original_tokens,
             "optimized_tokens": result.stats.optimized_tokens,
-            "rules_applied": result.stats.rules_applied,
+            "issues_detected": result.stats.issues_detected,
+            "rules_fixed": result.stats.transforms.rules_fixed(),
+            "transforms": result.stats.transforms,
+            "changelog": result.stats.changelog,
+            "enhancements": result.stats.enhancements,
             "categories_improved": result.stats.categories_improved,
             "processing_time_ms": result.stats.processing_time_ms,
             "provider": result.stats.provider,
             "model": result.stats.model,
+            // What the provider was sent, e.g. the inference profile ARN
+            "model_id": llm::models::provider_id(&result.stats.provider, &result.stats.model),
+            "level": optimizer::level::current().describe(),
+            "timed_out": result.stats.timed_out,
+            "degraded": result.stats.degraded,
+            "original_score": result.stats.original_score,
+            "optimized_score": result.stats.optimized_score,
+            "judge": result.stats.judge,
+            "test_queries": result.stats.test_queries,
             "issues": result.issues.iter().map(|i| serde_json::json!({
                 "id": i.id,
                 "category": i.category,
                 "severity": format!("{:?}", i.severity).to_lowercase(),
                 "message": i.message,
+                "confidence": i.confidence,
             })).collect::<Vec<_>>(),
         });
 
-        tokio::fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)
-            .await
-            .with_context(|| format!("Failed to write metadata: {}", metadata_path.display()))?;
+        save_artifact(
+            uploader.as_ref(),
+            &metadata_path,
+            &serde_json::to_string_pretty(&metadata)?,
+            "application/json",
+        )
+        .await
+        .with_context(|| format!("Failed to write metadata: {}", metadata_path.display()))?;
 
         if !cli.quiet && cli.format != OutputFormat::Quiet {
-            tui::stats::print_save_success(&path.display().to_string(), false);
+            tui::linear::write_saved(&mut io::stdout(), &path.display().to_string())?;
+        }
+    }
+    utils::progress::finished(utils::progress::Phase::Save);
+
+    Ok(output_path)
+}
+
+/// Print the report, export, or `--print` artifact for a result
+fn print_result(cli: &Cli, result: &OptimizationResult) -> Result<()> {
+    use tui::model::{AppPhase, Model};
+
+    if let Some(target) = cli.emit {
+        // Export replaces the normal report so it can be piped or redirected
+        let exported = cli::workbench::emit(target, result, &cli.model)?;
+        // A prompt copy keeps its own trailing newline
+        if exported.ends_with('\n') {
+            print!("{}", exported);
+        } else {
+            println!("{}", exported);
+        }
+    } else {
+        match cli.format {
+            OutputFormat::Json => {
+                let coverage = analyzer::coverage::coverage(
+                    &result.original,
+                    cli.check.as_deref(),
+                    &result.issues,
+                );
+                let json = serde_json::json!({
+                    "original": result.original,
+                    "optimized": result.optimized,
+                    "issues": issues_json(&result.issues),
+                    "summary": analyzer::report::AnalysisReport::from_issues(&result.issues),
+                    "stats": {
+                        "original_chars": result.stats.original_chars,
+                        "optimized_chars": result.stats.optimized_chars,
+                        "original_tokens": result.stats.original_tokens,
+                        "optimized_tokens": result.stats.optimized_tokens,
+                        "issues_detected": result.stats.issues_detected,
+                        // Deprecated alias of issues_detected
+                        "rules_applied": result.stats.issues_detected,
+                        "categories_improved": result.stats.categories_improved,
+                        "processing_time_ms": result.stats.processing_time_ms,
+                        "provider": result.stats.provider,
+                        "model": result.stats.model,
+                        "model_id": llm::models::provider_id(&result.stats.provider, &result.stats.model),
+                        "rules_fixed": result.stats.transforms.rules_fixed(),
+                        "transforms": result.stats.transforms,
+                        "changelog": result.stats.changelog,
+                        "enhancements": result.stats.enhancements,
+                        "timed_out": result.stats.timed_out,
+                        "degraded": result.stats.degraded,
+                    },
+                    "structure": analyzer::segment::segment_prompt(&result.original),
+                    "coverage": coverage,
+                    "strengths": analyzer::strengths::report(&result.original, &coverage).strengths,
+                    "quality": {
+                        "original_score": result.stats.original_score,
+                        "optimized_score": result.stats.optimized_score,
+                        "judge": result.stats.judge,
+                    },
+                    "test_queries": result.stats.test_queries,
+                });
+                match cli.json_path {
+                    Some(ref path) => print_json_value(&json, path)?,
+                    None => println!("{}", serde_json::to_string_pretty(&json)?),
+                }
+            }
+            OutputFormat::Quiet => match cli.print.unwrap_or(PrintTarget::Optimized) {
+                PrintTarget::Optimized => println!("{}", result.optimized),
+                PrintTarget::Original => println!("{}", result.original),
+                PrintTarget::Issues => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&issues_json(&result.issues))?
+                ),
+                PrintTarget::Diff => print!(
+                    "{}",
+                    tui::diff::unified_diff(&result.original, &result.optimized)
+                ),
+            },
+            OutputFormat::Pretty => {
+                // Buffer the report so long output can go through the pager
+                let mut out = Vec::new();
+
+                // Header and analysis were shown before optimizing
+                let mut model = Model::new();
+                model.offline_mode = cli.offline;
+                model.verbose = cli.verbose;
+                model.show_diff = cli.diff || cli.structure;
+                model.original_prompt = result.original.clone();
+                model.input_file = cli.file.as_ref().map(|p| p.display().to_string());
+                model.set_issues(&result.issues);
+                model.set_optimization_result(result.optimized.clone(), result.stats.clone());
+                model.phase = AppPhase::Done;
+                tui::linear::write_results(&mut out, &model)?;
+
+                // In offline mode, point at what else is available
+                if cli.offline {
+                    let hint = if result.stats.transforms.is_empty() {
+                        "No static fixes apply; to optimize this prompt with an LLM, run without --offline"
+                    } else if cli.diff || cli.structure {
+                        "For a full rewrite beyond static fixes, run without --offline"
+                    } else {
+                        "Run with --diff to review the static fixes, or without --offline for a full rewrite"
+                    };
+                    writeln!(out)?;
+                    writeln!(out, "  {}", "─".repeat(70).bright_black())?;
+                    writeln!(out, "  {}  {}", "💡".cyan(), hint.white())?;
+                    writeln!(out, "  {}", "─".repeat(70).bright_black())?;
+                    writeln!(out)?;
+                } else if !cli.diff && cli.show_prompt {
+                    tui::linear::write_optimized_prompt(&mut out, &result.optimized)?;
+                }
+
+                tui::pager::page(&out, pager_enabled(cli))?;
+            }
         }
     }
+    Ok(())
+}
 
+/// Print the value at `--json-path`: strings bare, anything else as JSON
+fn print_json_value(json: &serde_json::Value, path: &str) -> Result<()> {
+    match utils::json::select_json_path(json, path)? {
+        Some(serde_json::Value::String(text)) => println!("{}", text),
+        Some(value) => println!("{}", serde_json::to_string_pretty(value)?),
+        None => {
+            return Err(CoptError::Config(format!(
+                "Nothing at '{}' in the JSON output (run with --format json to see the fields)",
+                path
+            ))
+            .into())
+        }
+    }
     Ok(())
 }
 
+/// Issues as they appear in JSON output and `--print issues`
+fn issues_json(issues: &[Issue]) -> Vec<serde_json::Value> {
+    issues
+        .iter()
+        .map(|i| {
+            serde_json::json!({
+                "id": i.id,
+                "category": i.category,
+                "severity": format!("{:?}", i.severity).to_lowercase(),
+                "message": i.message,
+                "line": i.line,
+                "confidence": i.confidence,
+                "suggestion": i.suggestion,
+            })
+        })
+        .collect()
+}
+
+/// `result` with secrets and listed names masked (`--redact`)
+fn redacted(result: &OptimizationResult) -> OptimizationResult {
+    let mut result = result.clone();
+    result.original = utils::redact::apply(&result.original).into_owned();
+    result.optimized = utils::redact::apply(&result.optimized).into_owned();
+    result.issues = utils::redact::issues(&result.issues);
+    result.stats = result.stats.redacted();
+    result
+}
+
+/// Write a saved file locally, or upload it when `path` is an s3:// location
+async fn save_artifact(
+    uploader: Option<&utils::s3::S3Uploader>,
+    path: &std::path::Path,
+    contents: &str,
+    content_type: &str,
+) -> Result<()> {
+    tracing::debug!(path = %path.display(), bytes = contents.len(), "save");
+    match (uploader, utils::s3::S3Location::parse(path)) {
+        (Some(uploader), Some(location)) => uploader.put(&location?, contents, content_type).await,
+        // Temp file + rename, so an interrupted save never leaves a partial file
+        _ => {
+            let (path, contents) = (path.to_path_buf(), contents.to_string());
+            tokio::task::spawn_blocking(move || utils::file::write_atomic(&path, &contents)).await?
+        }
+    }
+}
+
+/// Save or print the optimized prompt once the TUI exits
+///
+/// Returns where it was saved, if it was.
+#[cfg(feature = "tui")]
+async fn save_interactive_result(cli: &Cli, model: &tui::model::Model) -> Result<Option<PathBuf>> {
+    let Some(ref optimized) = model.optimized_prompt else {
+        return Ok(None);
+    };
+    if cli.sandbox {
+        println!("{}", utils::redact::apply(optimized));
+        return Ok(None);
+    }
+    if cli.no_save || cli.offline || model.offline_mode {
+        return Ok(None);
+    }
+
+    let optimized = utils::redact::apply(optimized);
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let filename = utils::file::output_file_name("optimized", &timestamp, &optimized, "txt");
+
+    // Create output directory if it doesn't exist
+    tokio::fs::create_dir_all(&cli.output_dir).await?;
+    let output_path = utils::file::reserve_output_path(&cli.output_dir, &filename)?;
+
+    // Write the optimized prompt
+    let saved = if cli::provenance::enabled() {
+        let provenance = cli::provenance::Provenance::new(
+            &format!("{:?}", cli.provider).to_lowercase(),
+            Some(llm::models::provider_id(
+                &format!("{:?}", cli.provider).to_lowercase(),
+                &cli.model,
+            )),
+            &utils::redact::apply(&model.original_prompt),
+        );
+        cli::provenance::append(&optimized, &provenance)
+    } else {
+        optimized.to_string()
+    };
+    save_artifact(None, &output_path, &saved, "text/plain").await?;
+
+    // Print save message after TUI exits
+    println!(
+        "\n{} Saved to: {}\n",
+        "✓".green(),
+        tui::hyperlink::file(&output_path.display().to_string())
+            .white()
+            .bold()
+    );
+    Ok(Some(output_path))
+}
+
 /// Run the full-screen interactive TUI mode
+#[cfg(feature = "tui")]
 async fn run_interactive_mode(cli: &Cli, prompt: &str) -> Result<()> {
     use tui::model::{AppPhase, ErrorState, Model, RenderMode};
 
@@ -717,39 +2872,48 @@ async fn run_interactive_mode(cli: &Cli, prompt: &str) -> Result<()> {
     let mut model = Model::new();
     model.render_mode = RenderMode::Interactive;
     model.offline_mode = cli.offline;
+    model.verbose = cli.verbose;
     model.original_prompt = prompt.to_string();
     model.input_file = cli.file.as_ref().map(|p| p.display().to_string());
+    model.output_dir = cli.output_dir.clone();
 
     // Analyze the prompt
     model.phase = AppPhase::Analyzing;
-    let issues = analyzer::analyze(prompt, cli.check.as_deref())?;
+    let issues = analyze_prompt(cli, prompt)?;
     model.set_issues(&issues);
 
     // If not offline, optimize with LLM (even if no static rules triggered,
     // the LLM can enhance prompts beyond what static rules detect)
     if !cli.offline && !cli.analyze {
         model.phase = AppPhase::Optimizing;
+        tui::terminal::set_interrupt_message(Some(interrupted_after_analysis(&issues, false)));
 
         // Run LLM optimization
-        let client: Box<dyn llm::LlmClient> = match cli.provider {
-            Provider::Anthropic => Box::new(llm::AnthropicClient::new(
-                std::env::var("ANTHROPIC_API_KEY")
-                    .context("ANTHROPIC_API_KEY environment variable not set")?,
-            )?),
-            Provider::Bedrock => Box::new(llm::BedrockClient::new(&cli.region).await?),
-        };
-
         let prompt_type = analyzer::classify_prompt(prompt);
-        match optimizer::optimize_with_llm(
-            prompt,
-            &issues,
-            client.as_ref(),
-            &cli.model,
-            prompt_type,
-        )
-        .await
-        {
-            Ok(optimized) => {
+        let result = match build_llm_client(cli).await {
+            Ok(client) => {
+                optimize_with_llm(cli, prompt, &issues, client.as_ref(), prompt_type).await
+            }
+            Err(e) => Err(e),
+        };
+        let outcome = match result {
+            Ok(optimized) => gate_rewrite(cli, prompt, optimized, || {
+                Ok(optimizer::optimize_static(prompt, &issues)?)
+            })
+            .map(|optimized| (optimized, None)),
+            // Fall back to the static result; the dashboard notes the failure
+            Err(e) => optimizer::optimize_static(prompt, &issues)
+                .map(|o| (o, Some(e)))
+                .map_err(Into::into),
+        };
+        match outcome {
+            Ok((optimized, failure)) => {
+                let (optimized, enhancements) = if cli.enhance {
+                    optimizer::enhance::apply(prompt, &optimized)
+                } else {
+                    (optimized, Vec::new())
+                };
+                let timed_out = failure.as_ref().is_some_and(llm::is_timeout);
                 let processing_time = start_time.elapsed().as_millis() as u64;
 
                 let stats = OptimizationStats {
@@ -757,7 +2921,7 @@ async fn run_interactive_mode(cli: &Cli, prompt: &str) -> Result<()> {
                     optimized_chars: optimized.len(),
                     original_tokens: utils::count_tokens(prompt),
                     optimized_tokens: utils::count_tokens(&optimized),
-                    rules_applied: issues.len(),
+                    issues_detected: issues.len(),
                     categories_improved: issues
                         .iter()
                         .map(|i| i.category.as_str())
@@ -766,7 +2930,16 @@ async fn run_interactive_mode(cli: &Cli, prompt: &str) -> Result<()> {
                     processing_time_ms: processing_time,
                     provider: format!("{:?}", cli.provider).to_lowercase(),
                     model: cli.model.clone(),
+                    original_score: analyzer::quality_score(&issues),
+                    optimized_score: analyzer::quality_score(&analyze_prompt(cli, &optimized)?),
+                    judge: run_judge(cli, prompt, &optimized).await,
+                    test_queries: run_test_queries(cli, prompt, &optimized).await,
+                    enhancements,
+                    timed_out,
+                    degraded: failure.as_ref().map(ErrorInfo::from_error),
+                    ..Default::default()
                 };
+                record_metrics(cli, &issues, &stats);
 
                 model.set_optimization_result(optimized, stats);
             }
@@ -780,29 +2953,23 @@ async fn run_interactive_mode(cli: &Cli, prompt: &str) -> Result<()> {
     }
 
     // Run the interactive TUI
-    tui::app::run_interactive(&mut model)?;
+    tui::terminal::set_interrupt_message(None);
+    tui::app::run_interactive(&mut model, &|text| {
+        analyze_prompt(cli, text).unwrap_or_default()
+    })?;
 
     // After TUI exits, handle auto-save if we have results
-    if let Some(ref optimized) = model.optimized_prompt {
-        if !cli.no_save && !cli.offline {
-            let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-            let filename = format!("optimized_{}.txt", timestamp);
-            let output_path = cli.output_dir.join(filename);
-
-            // Create output directory if it doesn't exist
-            if let Some(parent) = output_path.parent() {
-                tokio::fs::create_dir_all(parent).await?;
-            }
-
-            // Write the optimized prompt
-            tokio::fs::write(&output_path, optimized).await?;
-
-            // Print save message after TUI exits
-            println!(
-                "\n{} Saved to: {}\n",
-                "✓".green(),
-                output_path.display().to_string().white().bold()
-            );
+    let saved = save_interactive_result(cli, &model).await?;
+    if model.optimized_prompt.is_some() {
+        let degraded = model.stats.as_ref().is_some_and(|s| s.degraded.is_some());
+        if uses_paid_llm(cli) && !degraded {
+            cli::history::record(&cli::history::HistoryEntry::new(
+                prompt,
+                &provider_name(cli),
+                &cli.model,
+                &run_options(cli),
+                saved.as_deref(),
+            ));
         }
     }
 
@@ -0,0 +1,148 @@
+//! Document manifest for prompts that point at local files (`--bundle-docs`)
+//!
+//! Prompts like "read the docs in alaska-docs/" leave the model to guess
+//! what the folder holds. This inventories each referenced file and folder
+//! and appends an `<available_documents>` section listing what actually
+//! exists, so the model reads real files instead of inventing them.
+
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::references::{path_references, PathReference};
+use crate::utils::file::format_file_size;
+
+/// Most files listed in one manifest; larger folders are summarized
+pub const MAX_DOCUMENTS: usize = 100;
+
+/// Referenced documents found (and not found) under a project root
+#[derive(Debug, Default)]
+pub struct DocsBundle {
+    /// Existing files as (path relative to the root, size in bytes), sorted
+    pub documents: Vec<(String, u64)>,
+    /// Files left out of the manifest past [`MAX_DOCUMENTS`]
+    pub omitted: usize,
+    /// References that don't exist under the root
+    pub missing: Vec<PathReference>,
+}
+
+impl DocsBundle {
+    /// Inventory the files and folders `prompt` references, relative to `root`
+    pub fn collect(prompt: &str, root: &Path) -> Self {
+        let mut bundle = Self::default();
+        let mut files = Vec::new();
+
+        for reference in path_references(prompt) {
+            if !reference.exists_in(root) {
+                bundle.missing.push(reference);
+                continue;
+            }
+            let path = root.join(reference.path.trim_start_matches("./"));
+            if path.is_dir() {
+                walk(&path, &mut files);
+            } else {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+        files.dedup();
+        bundle.omitted = files.len().saturating_sub(MAX_DOCUMENTS);
+        bundle.documents = files
+            .into_iter()
+            .take(MAX_DOCUMENTS)
+            .map(|path| {
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                (relative.to_string_lossy().replace('\\', "/"), size)
+            })
+            .collect();
+        bundle
+    }
+
+    /// The `<available_documents>` section, or `None` when nothing was found
+    pub fn manifest(&self) -> Option<String> {
+        if self.documents.is_empty() {
+            return None;
+        }
+
+        let mut out = String::from(
+            "<available_documents>\n\
+             These local files are available. Read the ones relevant to the task before relying on their contents.\n\n",
+        );
+        for (path, size) in &self.documents {
+            out.push_str(&format!("- {} ({})\n", path, format_file_size(*size)));
+        }
+        if self.omitted > 0 {
+            out.push_str(&format!("- … and {} more\n", self.omitted));
+        }
+        out.push_str("</available_documents>");
+        Some(out)
+    }
+
+    /// `prompt` with the manifest appended (unchanged when there is none)
+    pub fn append_to(&self, prompt: &str) -> String {
+        match self.manifest() {
+            Some(manifest) => format!("{}\n\n{}", prompt.trim_end(), manifest),
+            None => prompt.to_string(),
+        }
+    }
+}
+
+/// Collect files under `dir`, skipping hidden files and folders
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, files);
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_manifest_lists_referenced_documents() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("alaska-docs/routes")).unwrap();
+        fs::write(root.path().join("alaska-docs/intro.md"), "Welcome").unwrap();
+        fs::write(root.path().join("alaska-docs/routes/denali.md"), "Trail").unwrap();
+        fs::write(root.path().join("alaska-docs/.draft.md"), "hidden").unwrap();
+        fs::write(root.path().join("config.yaml"), "a: 1").unwrap();
+
+        let prompt = "Read the docs in alaska-docs/ and `config.yaml`, then check `faq.md`.";
+        let bundle = DocsBundle::collect(prompt, root.path());
+
+        assert_eq!(
+            bundle
+                .missing
+                .iter()
+                .map(|r| r.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["faq.md"]
+        );
+        let optimized = bundle.append_to("Plan the trip.\n");
+        assert!(optimized.starts_with("Plan the trip.\n\n<available_documents>\n"));
+        assert!(optimized.contains(
+            "- alaska-docs/intro.md (7 B)\n- alaska-docs/routes/denali.md (5 B)\n- config.yaml (4 B)\n</available_documents>"
+        ));
+        assert!(!optimized.contains(".draft.md"));
+    }
+
+    #[test]
+    fn test_no_references_leaves_prompt_unchanged() {
+        let root = tempfile::tempdir().unwrap();
+        let bundle = DocsBundle::collect("Summarize the report.", root.path());
+        assert!(bundle.manifest().is_none());
+        assert_eq!(bundle.append_to("Summarize."), "Summarize.");
+    }
+}
@@ -0,0 +1,214 @@
+//! Plain-language summary of what an optimization changed
+//!
+//! Built from the static transform report and a comparison of the prompt
+//! before and after, so it covers LLM rewrites too without asking the model
+//! to describe its own work: "Converted 3 indirect requests to direct
+//! commands", "Added an output format section", "Resolved EXP001, CTX002".
+
+use std::collections::BTreeSet;
+
+use super::TransformReport;
+use crate::analyzer::segment::{segment_prompt, SectionKind, SectionSource};
+use crate::analyzer::Issue;
+
+/// One sentence per change, most specific first; empty when nothing changed
+pub fn summarize(
+    original: &str,
+    optimized: &str,
+    transforms: &TransformReport,
+    original_issues: &[Issue],
+    optimized_issues: &[Issue],
+) -> Vec<String> {
+    if original == optimized {
+        return Vec::new();
+    }
+    let mut entries = Vec::new();
+
+    let mut hygiene = Vec::new();
+    for transform in &transforms.applied {
+        let n = transform.replacements;
+        let entry = match transform.rule_id.as_str() {
+            "EXP003" => format!(
+                "Converted {} indirect {} to direct commands",
+                n,
+                plural(n, "request", "requests")
+            ),
+            "STY001" => format!(
+                "Rephrased {} negative {} as positive guidance",
+                n,
+                plural(n, "instruction", "instructions")
+            ),
+            "STY002" => format!("Normalized ALL CAPS emphasis in {}", places(n)),
+            "STY003" => format!("Replaced \"think\" with consider/evaluate in {}", places(n)),
+            "STY004" => format!("Toned down overtriggering language in {}", places(n)),
+            // Reported with the sections below
+            "FMT003" => continue,
+            "HYG001" => {
+                hygiene.push("line endings");
+                continue;
+            }
+            "HYG002" => {
+                hygiene.push("trailing whitespace");
+                continue;
+            }
+            "HYG003" => {
+                hygiene.push("invisible characters");
+                continue;
+            }
+            "HYG004" => {
+                hygiene.push("byte order mark");
+                continue;
+            }
+            "HYG005" => {
+                hygiene.push("blank lines");
+                continue;
+            }
+            rule_id => match super::transform_description(rule_id) {
+                Some(description) => description.to_string(),
+                None => format!("Applied the {} fix", rule_id),
+            },
+        };
+        entries.push(entry);
+    }
+    if !hygiene.is_empty() {
+        entries.push(format!("Cleaned up {}", join_words(&hygiene)));
+    }
+
+    entries.extend(section_changes(original, optimized));
+
+    // Rules fixed without a static transform, i.e. by the LLM rewrite
+    let remaining: BTreeSet<&str> = optimized_issues.iter().map(|i| i.id.as_str()).collect();
+    let resolved: BTreeSet<&str> = original_issues
+        .iter()
+        .map(|i| i.id.as_str())
+        .filter(|id| !remaining.contains(id) && !transforms.contains(id))
+        .collect();
+    if !resolved.is_empty() {
+        entries.push(format!(
+            "Resolved {}",
+            resolved.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    entries
+}
+
+/// Restructuring into XML tags and section kinds the original didn't have
+fn section_changes(original: &str, optimized: &str) -> Vec<String> {
+    let before = segment_prompt(original);
+    let after = segment_prompt(optimized);
+    let mut entries = Vec::new();
+
+    let tagged = |sections: &[crate::analyzer::segment::Section]| {
+        sections.iter().any(|s| s.source == SectionSource::XmlTag)
+    };
+    if !tagged(&before) && tagged(&after) {
+        entries.push("Structured the prompt into XML sections".to_string());
+    }
+
+    // Every prompt has a task, labeled or not; wrapping it in <instructions>
+    // adds nothing new
+    let mut existing: Vec<SectionKind> = before.iter().map(|s| s.kind).collect();
+    existing.push(SectionKind::Task);
+    let mut added: Vec<String> = Vec::new();
+    for section in &after {
+        if existing.contains(&section.kind) {
+            continue;
+        }
+        let name = match section.title {
+            Some(ref title) => title.replace(['_', '-'], " ").to_lowercase(),
+            None => section.kind.display_name().to_lowercase(),
+        };
+        if !added.contains(&name) {
+            added.push(name);
+        }
+    }
+    if !added.is_empty() {
+        let names: Vec<&str> = added.iter().map(String::as_str).collect();
+        entries.push(match names.as_slice() {
+            [name] => format!("Added {} {} section", article(name), name),
+            _ => format!("Added {} sections", join_words(&names)),
+        });
+    }
+
+    entries
+}
+
+fn plural<'a>(n: usize, one: &'a str, many: &'a str) -> &'a str {
+    if n == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+fn places(n: usize) -> String {
+    format!("{} {}", n, plural(n, "place", "places"))
+}
+
+fn article(word: &str) -> &'static str {
+    if word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
+/// "a", "a and b", "a, b, and c"
+fn join_words(words: &[&str]) -> String {
+    match words {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [first, second] => format!("{} and {}", first, second),
+        [rest @ .., last] => format!("{}, and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::analyze;
+
+    #[test]
+    fn test_summarize_transforms_sections_and_resolved_rules() {
+        let original = "Could you write a summary of the report?\r\nNEVER use jargon.";
+        let optimized = "<instructions>\nWrite a summary of the report.\nUse plain language.\n</instructions>\n\n<output_format>\nThree bullet points.\n</output_format>";
+        let mut transforms = TransformReport::default();
+        transforms.record("EXP003", 1);
+        transforms.record("HYG001", 1);
+        transforms.record("HYG002", 2);
+
+        let entries = summarize(
+            original,
+            optimized,
+            &transforms,
+            &analyze(original, None).unwrap(),
+            &analyze(optimized, None).unwrap(),
+        );
+
+        assert_eq!(
+            entries[0],
+            "Converted 1 indirect request to direct commands"
+        );
+        assert_eq!(
+            entries[1],
+            "Cleaned up line endings and trailing whitespace"
+        );
+        assert!(entries.contains(&"Structured the prompt into XML sections".to_string()));
+        assert!(entries.contains(&"Added an output format section".to_string()));
+        assert!(!entries.iter().any(|e| e.contains("EXP003")));
+    }
+
+    #[test]
+    fn test_summarize_unchanged_prompt() {
+        let prompt = "Summarize the report.";
+        assert!(summarize(prompt, prompt, &TransformReport::default(), &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_join_words() {
+        assert_eq!(join_words(&["a"]), "a");
+        assert_eq!(join_words(&["a", "b"]), "a and b");
+        assert_eq!(join_words(&["a", "b", "c"]), "a, b, and c");
+    }
+}
@@ -0,0 +1,241 @@
+//! Enhancement library for `--enhance`
+//!
+//! An enhancement is a short paragraph of guidance appended to the optimized
+//! prompt when the original matches one of its patterns, such as "read the
+//! code before changing it" for bug-fix requests. The built-in library lives
+//! in `enhancements.toml`; a rule pack can add its own under the same
+//! `[[enhancements]]` key, or replace a built-in one by reusing its id:
+//!
+//! ```toml
+//! [[enhancements]]
+//! id = "exploration"
+//! description = "Inspect code before changing it"
+//! patterns = ["(?i)\\b(fix|bug|change|update)"]
+//! min_chars = 2000                  # optional; only prompts this long
+//! template = "Read the relevant code before making changes."
+//! ```
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::analyzer::rule_pack;
+
+/// Library shipped with copt
+const BUILTIN: &str = include_str!("enhancements.toml");
+
+/// An enhancement as written in TOML
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Enhancement {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    /// Regexes checked against the original prompt; any match applies it
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// When set, only prompts at least this many bytes long qualify
+    pub min_chars: Option<usize>,
+    pub template: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LibraryFile {
+    #[serde(default)]
+    enhancements: Vec<Enhancement>,
+}
+
+/// Validated enhancements with compiled patterns
+#[derive(Debug, Clone, Default)]
+pub struct Library {
+    entries: Vec<(Enhancement, Vec<Regex>)>,
+}
+
+impl Library {
+    /// Parse and validate a TOML library
+    pub fn parse(content: &str) -> Result<Self> {
+        let file: LibraryFile = toml::from_str(content).context("Invalid enhancement library")?;
+        Self::from_enhancements(file.enhancements)
+    }
+
+    /// Validate enhancements and compile their patterns
+    pub fn from_enhancements(enhancements: Vec<Enhancement>) -> Result<Self> {
+        let mut entries = Vec::with_capacity(enhancements.len());
+        for enhancement in enhancements {
+            if enhancement.id.trim().is_empty() {
+                anyhow::bail!("Enhancement library contains an enhancement without an id");
+            }
+            if enhancement.template.trim().is_empty() {
+                anyhow::bail!("Enhancement {}: empty template", enhancement.id);
+            }
+            if enhancement.patterns.is_empty() {
+                anyhow::bail!(
+                    "Enhancement {}: needs patterns to know when it applies",
+                    enhancement.id
+                );
+            }
+            let regexes = enhancement
+                .patterns
+                .iter()
+                .map(|p| Regex::new(p))
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("Enhancement {}: invalid pattern", enhancement.id))?;
+            entries.push((enhancement, regexes));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Add `other`'s enhancements, replacing any with the same id
+    pub fn merge(&mut self, other: &Library) {
+        for (enhancement, regexes) in &other.entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|(existing, _)| existing.id == enhancement.id)
+            {
+                Some(existing) => *existing = (enhancement.clone(), regexes.clone()),
+                None => self.entries.push((enhancement.clone(), regexes.clone())),
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Enhancements whose conditions match `prompt`, in library order
+    pub fn applicable<'a>(&'a self, prompt: &'a str) -> impl Iterator<Item = &'a Enhancement> {
+        self.entries
+            .iter()
+            .filter(move |(enhancement, regexes)| {
+                regexes.iter().any(|r| r.is_match(prompt))
+                    && enhancement.min_chars.is_none_or(|min| prompt.len() >= min)
+            })
+            .map(|(enhancement, _)| enhancement)
+    }
+
+    /// `optimized` with the enhancements that match `original` appended,
+    /// and the ids of those appended
+    ///
+    /// An enhancement whose text is already in the prompt is skipped.
+    pub fn apply(&self, original: &str, optimized: &str) -> (String, Vec<String>) {
+        let mut result = optimized.trim_end().to_string();
+        let mut applied = Vec::new();
+        for enhancement in self.applicable(original) {
+            let template = enhancement.template.trim();
+            if result.contains(template) {
+                continue;
+            }
+            result.push_str("\n\n");
+            result.push_str(template);
+            applied.push(enhancement.id.clone());
+        }
+        if applied.is_empty() {
+            return (optimized.to_string(), applied);
+        }
+        (result, applied)
+    }
+}
+
+/// The built-in library plus the installed rule pack's enhancements
+pub fn library() -> &'static Library {
+    static LIBRARY: OnceLock<Library> = OnceLock::new();
+    LIBRARY.get_or_init(|| {
+        let mut library = Library::parse(BUILTIN).expect("built-in enhancement library is valid");
+        if let Some(pack) = rule_pack::active() {
+            library.merge(&pack.enhancements);
+        }
+        library
+    })
+}
+
+/// Append the enhancements that match `original` to `optimized`
+pub fn apply(original: &str, optimized: &str) -> (String, Vec<String>) {
+    library().apply(original, optimized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_library_parses() {
+        let library = Library::parse(BUILTIN).unwrap();
+        assert_eq!(library.len(), 4);
+        let ids: Vec<&str> = library
+            .applicable("Fix the login bug in each of these files")
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(ids, ["parallel_tools", "exploration"]);
+        let long = "Write a story. ".repeat(40);
+        assert_eq!(library.applicable(&long).count(), 0);
+        let ids: Vec<&str> = library
+            .applicable("Refactor the parser")
+            .map(|e| e.id.as_str())
+            .collect();
+        assert!(ids.is_empty());
+        let long = format!("Refactor the parser. {}", "Keep the API. ".repeat(40));
+        let ids: Vec<&str> = library.applicable(&long).map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["summary"]);
+    }
+
+    #[test]
+    fn test_apply_appends_once() {
+        let library = Library::parse(BUILTIN).unwrap();
+        let (enhanced, applied) = library.apply("Fix the bug", "Fix the bug.\n");
+        assert_eq!(applied, ["exploration"]);
+        assert!(enhanced.starts_with("Fix the bug.\n\nRead and understand"));
+
+        let (again, applied) = library.apply("Fix the bug", &enhanced);
+        assert!(applied.is_empty());
+        assert_eq!(again, enhanced);
+
+        let (unchanged, applied) = library.apply("Write a haiku", "Write a haiku.\n");
+        assert!(applied.is_empty());
+        assert_eq!(unchanged, "Write a haiku.\n");
+    }
+
+    #[test]
+    fn test_merge_replaces_by_id() {
+        let mut library = Library::parse(BUILTIN).unwrap();
+        let pack = Library::parse(
+            r#"
+            [[enhancements]]
+            id = "exploration"
+            patterns = ["(?i)\\bfix\\b"]
+            template = "Reproduce the bug before fixing it."
+
+            [[enhancements]]
+            id = "acme_tests"
+            patterns = ["(?i)\\btests?\\b"]
+            template = "Run `make test` before finishing."
+            "#,
+        )
+        .unwrap();
+        library.merge(&pack);
+        assert_eq!(library.len(), 5);
+        let (enhanced, applied) = library.apply("Fix the tests", "Fix the tests.");
+        assert_eq!(applied, ["exploration", "acme_tests"]);
+        assert!(enhanced.contains("Reproduce the bug"));
+    }
+
+    #[test]
+    fn test_invalid_enhancements_are_rejected() {
+        assert!(Library::parse("[[enhancements]]\nid = \"x\"\ntemplate = \"t\"").is_err());
+        assert!(Library::parse(
+            "[[enhancements]]\nid = \"x\"\npatterns = [\"(\"]\ntemplate = \"t\""
+        )
+        .is_err());
+        assert!(Library::parse("[[enhancement]]\nid = \"x\"").is_err());
+        assert!(
+            Library::parse("[[enhancements]]\nid = \"x\"\nmin_chars = 10\ntemplate = \"t\"")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! How freely the LLM rewrites (`--level`)
+//!
+//! `conservative` sends only errors and warnings and asks for the smallest
+//! change that fixes them; `aggressive` lets the LLM restructure the prompt
+//! as a whole. `standard` is the rewrite copt has always asked for. A level
+//! can be set for the whole run or per category (`--level style=aggressive`),
+//! so a team can keep its hand-tuned tool instructions while letting the
+//! LLM loose on tone.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::{Issue, Severity};
+
+/// How assertively the LLM is told to rewrite
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// Fix only the flagged errors and warnings, changing as little as possible
+    Conservative,
+    /// Fix every flagged issue and tidy around it
+    #[default]
+    Standard,
+    /// Restructure freely while keeping the intent
+    Aggressive,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Conservative => "conservative",
+            Level::Standard => "standard",
+            Level::Aggressive => "aggressive",
+        }
+    }
+
+    /// Whether issues of `severity` are sent to the LLM at this level
+    pub fn sends(self, severity: Severity) -> bool {
+        match self {
+            Level::Conservative => severity >= Severity::Warning,
+            Level::Standard | Level::Aggressive => true,
+        }
+    }
+}
+
+/// The run's level, with per-category overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Levels {
+    pub default: Level,
+    pub categories: BTreeMap<String, Level>,
+}
+
+/// One `--level` value: `LEVEL` or `CATEGORY=LEVEL`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelArg {
+    pub category: Option<String>,
+    pub level: Level,
+}
+
+/// Parse a `--level` value
+pub fn parse_arg(s: &str) -> Result<LevelArg, String> {
+    let (category, level) = match s.split_once('=') {
+        Some((category, level)) => (Some(category.trim().to_lowercase()), level),
+        None => (None, s),
+    };
+    let level = Level::from_str(level.trim(), true).map_err(|_| {
+        format!(
+            "'{}' is not a level: conservative, standard, aggressive",
+            level.trim()
+        )
+    })?;
+    if let Some(category) = &category {
+        let known =
+            crate::analyzer::CATEGORIES.contains(&category.as_str()) || category == "custom";
+        if !known {
+            return Err(format!(
+                "'{}' is not a rule category: {}, custom",
+                category,
+                crate::analyzer::CATEGORIES.join(", ")
+            ));
+        }
+    }
+    Ok(LevelArg { category, level })
+}
+
+impl Levels {
+    /// Levels from `--level` values; later values win
+    pub fn from_args(args: &[LevelArg]) -> Self {
+        let mut levels = Levels::default();
+        for arg in args {
+            match &arg.category {
+                Some(category) => {
+                    levels.categories.insert(category.clone(), arg.level);
+                }
+                None => levels.default = arg.level,
+            }
+        }
+        levels
+    }
+
+    /// The level for issues in `category`
+    pub fn for_category(&self, category: &str) -> Level {
+        self.categories
+            .get(category)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// The issues sent to the LLM at each issue's category level
+    pub fn filter(&self, issues: &[Issue]) -> Vec<Issue> {
+        issues
+            .iter()
+            .filter(|issue| self.for_category(&issue.category).sends(issue.severity))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether no category may be rewritten beyond its flagged issues
+    pub fn all_conservative(&self) -> bool {
+        self.default == Level::Conservative
+            && self.categories.values().all(|l| *l == Level::Conservative)
+    }
+
+    /// Instructions for the optimization message; none at `standard`
+    pub fn instructions(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let overridden = |level: Level| -> Vec<&str> {
+            self.categories
+                .iter()
+                .filter(|(_, l)| **l == level && level != self.default)
+                .map(|(c, _)| c.as_str())
+                .collect()
+        };
+        match self.default {
+            Level::Conservative => lines.push(
+                "Fix only the listed issues and change as little as possible: keep the wording, \
+                order, and structure of everything else, and add no new sections."
+                    .to_string(),
+            ),
+            Level::Aggressive => lines.push(
+                "Beyond the listed issues, restructure freely: reorder, merge, split, or add \
+                sections wherever that makes the prompt clearer, as long as its intent is kept."
+                    .to_string(),
+            ),
+            Level::Standard => {}
+        }
+        let conservative = overridden(Level::Conservative);
+        if !conservative.is_empty() {
+            lines.push(format!(
+                "For issues in {}, fix only what is listed and keep the surrounding text as written.",
+                conservative.join(", ")
+            ));
+        }
+        let standard = overridden(Level::Standard);
+        if !standard.is_empty() {
+            lines.push(format!(
+                "For issues in {}, fix what is listed without restructuring the prompt.",
+                standard.join(", ")
+            ));
+        }
+        let aggressive = overridden(Level::Aggressive);
+        if !aggressive.is_empty() {
+            lines.push(format!(
+                "For issues in {}, rewrite the related instructions freely, keeping their intent.",
+                aggressive.join(", ")
+            ));
+        }
+        lines
+    }
+
+    /// `conservative`, or `standard (style=aggressive)` for reports
+    pub fn describe(&self) -> String {
+        let overrides: Vec<String> = self
+            .categories
+            .iter()
+            .filter(|(_, l)| **l != self.default)
+            .map(|(c, l)| format!("{}={}", c, l.as_str()))
+            .collect();
+        if overrides.is_empty() {
+            self.default.as_str().to_string()
+        } else {
+            format!("{} ({})", self.default.as_str(), overrides.join(", "))
+        }
+    }
+}
+
+static LEVELS: Mutex<Option<Levels>> = Mutex::new(None);
+
+/// Use `levels` for every optimization in the rest of the process
+pub fn configure(levels: Levels) {
+    *LEVELS.lock().unwrap_or_else(|e| e.into_inner()) = Some(levels);
+}
+
+/// The configured levels (`standard` everywhere unless `--level` was given)
+pub fn current() -> Levels {
+    LEVELS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(category: &str, severity: Severity) -> Issue {
+        Issue {
+            id: "X001".to_string(),
+            category: category.to_string(),
+            severity,
+            message: String::new(),
+            line: None,
+            confidence: 1.0,
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_arg() {
+        assert_eq!(
+            parse_arg("Aggressive").unwrap(),
+            LevelArg {
+                category: None,
+                level: Level::Aggressive
+            }
+        );
+        assert_eq!(
+            parse_arg("style=conservative").unwrap(),
+            LevelArg {
+                category: Some("style".to_string()),
+                level: Level::Conservative
+            }
+        );
+        assert!(parse_arg("bold").is_err());
+        assert!(parse_arg("styles=standard").is_err());
+    }
+
+    #[test]
+    fn test_conservative_sends_errors_and_warnings_only() {
+        let levels = Levels::from_args(&[
+            parse_arg("conservative").unwrap(),
+            parse_arg("style=aggressive").unwrap(),
+        ]);
+        let issues = vec![
+            issue("tools", Severity::Info),
+            issue("tools", Severity::Warning),
+            issue("style", Severity::Info),
+        ];
+        let sent = levels.filter(&issues);
+        assert_eq!(sent.len(), 2);
+        assert!(sent
+            .iter()
+            .all(|i| i.category == "style" || i.severity == Severity::Warning));
+        assert!(!levels.all_conservative());
+        assert_eq!(levels.describe(), "conservative (style=aggressive)");
+    }
+
+    #[test]
+    fn test_instructions_by_level() {
+        assert!(Levels::default().instructions().is_empty());
+
+        let levels = Levels::from_args(&[parse_arg("conservative").unwrap()]);
+        let lines = levels.instructions();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("Fix only the listed issues"));
+
+        let levels = Levels::from_args(&[
+            parse_arg("tools=conservative").unwrap(),
+            parse_arg("style=aggressive").unwrap(),
+        ]);
+        assert_eq!(
+            levels.instructions(),
+            [
+                "For issues in tools, fix only what is listed and keep the surrounding text as written.",
+                "For issues in style, rewrite the related instructions freely, keeping their intent.",
+            ]
+        );
+    }
+}
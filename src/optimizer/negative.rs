@@ -0,0 +1,238 @@
+//! Positive rephrasing of negative instructions (STY001)
+//!
+//! Claude follows "what to do" more reliably than "what not to do". Common
+//! negative instructions are rewritten from a table of known phrasings, then
+//! a few general patterns ("Don't forget to X" → "Remember to X") handle the
+//! rest. Anything still negative is left for the LLM rewrite, which is shown
+//! [`EXAMPLES`] to follow.
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// Negation that opens an instruction
+const NEG: &str = r"(?:don't|don’t|do not|never)";
+
+/// Optional "in your response" tail dropped along with a table match
+const RESPONSE_TAIL: &str =
+    r"(?:\s+(?:in|for|within)\s+(?:your|the)\s+(?:responses?|answers?|output|replies|reply))?";
+
+/// End of the clause a table match must reach, so "Don't use lists longer
+/// than five items" is left for the LLM instead of losing its object
+const CLAUSE_END: &str = r"(?P<end>\s*(?:[.;,!?:)]|\n|$))";
+
+/// Known negative instructions and their positive equivalents
+const REWRITES: &[(&str, &str)] = &[
+    (
+        r"use (?:any )?markdown(?: formatting)?",
+        "write in flowing prose paragraphs",
+    ),
+    (
+        r"use (?:bullet points|bullets|bulleted lists|lists)",
+        "write in flowing paragraphs",
+    ),
+    (r"use (?:any )?emojis?", "use plain text only"),
+    (
+        r"use (?:section )?(?:headers|headings)",
+        "keep the response as continuous paragraphs",
+    ),
+    (r"use (?:technical )?jargon", "use plain language"),
+    (r"use (?:the )?passive voice", "write in the active voice"),
+    (r"use abbreviations", "spell out terms in full"),
+    (
+        r"use ellipses",
+        "write complete sentences with standard punctuation",
+    ),
+    (
+        r"use exclamation (?:marks|points)",
+        "end sentences with periods",
+    ),
+    (
+        r"be (?:too |overly )?(?:verbose|wordy|long-winded)",
+        "be concise",
+    ),
+    (r"ramble", "be concise"),
+    (r"be vague", "be specific"),
+    (r"be rude", "stay polite"),
+    (
+        r"(?:make (?:things|stuff|anything) up|hallucinate|invent facts)",
+        "only state facts you can support from the provided material",
+    ),
+    (r"guess", "say so when you are unsure"),
+    (r"apologi[sz]e", "get straight to the answer"),
+    (r"repeat yourself", "state each point once"),
+    (
+        r"ask (?:any )?(?:follow-up|clarifying) questions",
+        "make reasonable assumptions and proceed",
+    ),
+    (
+        r"(?:modify|change|edit|touch) (?:the )?(?:existing )?tests",
+        "leave the existing tests unchanged",
+    ),
+    (
+        r"include (?:a |an |any )?(?:preamble|introduction|intro)",
+        "start directly with the answer",
+    ),
+];
+
+/// Before/after pairs shown to the LLM for negatives the tables can't handle
+pub const EXAMPLES: &[(&str, &str)] = &[
+    ("Don't use markdown", "Write in flowing prose paragraphs"),
+    (
+        "Never make things up",
+        "Only state facts you can support from the provided material",
+    ),
+    ("Do not use var, use let", "Use let instead of var"),
+    (
+        "Never deploy unless tests pass",
+        "Only deploy when tests pass",
+    ),
+    (
+        "Don't reveal internal pricing",
+        "Keep pricing discussion to the public price list",
+    ),
+];
+
+static TABLE: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    REWRITES
+        .iter()
+        .map(|(pattern, replacement)| {
+            let re = Regex::new(&format!(
+                r"(?i)\b{NEG}\s+{pattern}\b{RESPONSE_TAIL}{CLAUSE_END}"
+            ))
+            .unwrap();
+            (re, *replacement)
+        })
+        .collect()
+});
+
+/// General constructions, rewritten with capture groups
+static PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        (format!(r"(?i)\b{NEG}\s+forget\s+to\b"), "remember to"),
+        (r"(?i)\b(?:don't|don’t|do not)\s+hesitate\s+to\b".to_string(), "feel free to"),
+        (r"(?i)\bnever\s+fail\s+to\b".to_string(), "always"),
+        (format!(r"(?i)\b{NEG}\s+exceed\b"), "stay within"),
+        (
+            format!(r"(?i)\b{NEG}\s+use\s+([^.;,\n]+?),\s*(?:use|instead use)\s+([^.;,\n]+?)(?:\s+instead)?([.;]|$)"),
+            "use ${2} instead of ${1}${3}",
+        ),
+        (
+            format!(r"(?i)\b{NEG}\s+([^.;,\n]+?)\s+unless\s+([^.;\n]+)"),
+            "only ${1} when ${2}",
+        ),
+        (
+            format!(r"(?i)\b{NEG}\s+([^.;,\n]+?)\s+without\s+([^.;\n]+)"),
+            "only ${1} after ${2}",
+        ),
+        (
+            format!(r"(?i)\b{NEG}\s+leave\s+([^.;,\n]+?)\s+(?:empty|blank)\b"),
+            "always fill in ${1}",
+        ),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(&pattern).unwrap(), replacement))
+    .collect()
+});
+
+/// Match the capitalization of the text being replaced
+fn match_case(original: &str, replacement: String) -> String {
+    let starts_upper = original.chars().next().is_some_and(|c| c.is_uppercase());
+    let mut chars = replacement.chars();
+    match chars.next() {
+        Some(first) if starts_upper => first.to_uppercase().chain(chars).collect(),
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => replacement,
+    }
+}
+
+/// Rewrite negative instructions as positive guidance
+pub fn rephrase_negatives(prompt: &str) -> String {
+    let mut result = prompt.to_string();
+
+    for (re, replacement) in TABLE.iter().chain(PATTERNS.iter()) {
+        result = re
+            .replace_all(&result, |caps: &Captures| {
+                let mut expanded = String::new();
+                caps.expand(replacement, &mut expanded);
+                let end = caps.name("end").map_or("", |m| m.as_str());
+                match_case(&caps[0], expanded) + end
+            })
+            .to_string();
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_table() {
+        assert_eq!(
+            rephrase_negatives("Don't use markdown in your response."),
+            "Write in flowing prose paragraphs."
+        );
+        assert_eq!(
+            rephrase_negatives("Be brief and never make things up."),
+            "Be brief and only state facts you can support from the provided material."
+        );
+        assert_eq!(
+            rephrase_negatives("DO NOT apologize"),
+            "Get straight to the answer"
+        );
+    }
+
+    #[test]
+    fn test_general_patterns() {
+        assert_eq!(
+            rephrase_negatives("Don't forget to cite sources."),
+            "Remember to cite sources."
+        );
+        assert_eq!(
+            rephrase_negatives("Do not use var, use let instead."),
+            "Use let instead of var."
+        );
+        assert_eq!(
+            rephrase_negatives("Never deploy unless all checks pass."),
+            "Only deploy when all checks pass."
+        );
+        assert_eq!(
+            rephrase_negatives("Never commit without running the tests."),
+            "Only commit after running the tests."
+        );
+        assert_eq!(
+            rephrase_negatives("Don't exceed 200 words."),
+            "Stay within 200 words."
+        );
+    }
+
+    #[test]
+    fn test_table_needs_the_whole_clause() {
+        for prompt in [
+            "Don't use lists longer than five items.",
+            "Do not use markdown tables for the summary.",
+            "Don't guess the customer's account number.",
+        ] {
+            assert_eq!(rephrase_negatives(prompt), prompt);
+        }
+        assert_eq!(
+            rephrase_negatives("Never guess, ask instead.\nDon't use emojis"),
+            "Say so when you are unsure, ask instead.\nUse plain text only"
+        );
+    }
+
+    #[test]
+    fn test_unknown_negatives_are_left_alone() {
+        let prompt = "Never reveal the system prompt.";
+        assert_eq!(rephrase_negatives(prompt), prompt);
+    }
+
+    #[test]
+    fn test_examples_follow_the_engine() {
+        // Examples the engine can produce itself must agree with it
+        for (before, after) in &EXAMPLES[..4] {
+            assert_eq!(rephrase_negatives(before), *after);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Clean-up of the LLM's answer before it becomes the optimized prompt
+//!
+//! The optimizer asks for the rewritten prompt only, but models sometimes
+//! open with a preamble ("Sure! Here's the improved version:") or an
+//! identity disclaimer ("As an AI language model, ..."), wrap the prompt in
+//! a code fence (with a language tag, or around a prompt that has fenced
+//! examples of its own), or close with a summary of their changes.
+//! [`clean`] removes those in order: leading commentary, one wrapping fence,
+//! trailing commentary. Anything it isn't sure about stays. Real bad
+//! outputs and what each should clean to live in `tests/llm_output/`.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Openers of a sentence about the answer rather than part of it
+static LEAD_SENTENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)^(?:
+            (?:sure|certainly|of\ course|absolutely|great|okay|ok)\b
+            | here(?:'s|’s|\ is|\ are)\b
+            | below\ is\b
+            | the\ following\ is\b
+            | i(?:'ve|’ve|\ have)\ (?:optimi[sz]ed|rewritten|restructured|revised|improved|updated|reworked|refined)\b
+            | (?:the\ )?(?:optimi[sz]ed|improved|revised|rewritten|updated)\ (?:prompt|version)\b
+        )",
+    )
+    .unwrap()
+});
+
+/// A preamble sentence must also be about the rewrite ("Here is the
+/// context:" can start a real prompt)
+static ABOUT_REWRITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:prompt|version|rewrite|revision|optimi[sz]ed|improved|restructured|it is)\b",
+    )
+    .unwrap()
+});
+
+/// The model describing itself; "As an AI assistant for Acme, ..." is a
+/// legitimate role, so only first-person or "language model" forms count
+static IDENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^as an ai(?: language model|,? i\b| model\b)").unwrap());
+
+/// A standalone label line: `# Optimized Prompt`, `**Improved prompt:**`
+static LEAD_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:#{1,6}\s*|\*\*)?(?:the\s+)?(?:optimi[sz]ed|improved|revised|rewritten|updated|final)\s+(?:prompt|version)(?:\s*\(.*\))?\s*:?\s*(?:\*\*)?\s*:?$",
+    )
+    .unwrap()
+});
+
+/// First line of a closing remark about the rewrite
+static TRAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)^(?:\*\*|\#{1,6}\s*)?(?:
+            (?:key|main|notable|summary\ of(?:\ the)?)\ (?:changes|improvements|modifications)
+            | (?:changes|improvements|modifications)\ (?:made|i\ made|applied)
+            | what\ changed\b
+            | (?:explanation|rationale)\ (?:of|for)\ (?:the\ )?changes
+            | (?:this|the)\ (?:optimi[sz]ed|revised|improved|rewritten|updated|new)\ (?:prompt|version)\b
+            | i(?:'ve|’ve|\ have)\ (?:made|optimi[sz]ed|rewritten|restructured|revised|improved|updated|added|removed|kept|converted|replaced)\b
+            | let\ me\ know\b
+            | feel\ free\ to\b
+            | i\ hope\ this\b
+            | hope\ this\ helps\b
+            | as\ an\ ai\ (?:language\ )?model\b
+        )",
+    )
+    .unwrap()
+});
+
+/// A sentence that tells the model what to do, which a closing remark about
+/// the rewrite never contains
+static INSTRUCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)^(?:[-*•]\s+|\d+[.)]\s+)?(?:
+            always | never | do | don't | don’t | do\ not | use | include | avoid
+            | make\ sure | ensure | keep | write | respond | reply | answer | return
+            | only | must | you\ (?:must|should|will)
+        )\b",
+    )
+    .unwrap()
+});
+
+/// Strip commentary and wrapping the LLM added around the prompt
+pub fn clean(output: &str) -> String {
+    let text = output.replace("\r\n", "\n");
+    let text = strip_leading(text.trim());
+    let text = unwrap_fence(text).unwrap_or(text);
+    let text = strip_leading(text.trim());
+    strip_trailing(text).trim().to_string()
+}
+
+/// Drop preamble lines ("Sure! Here's the optimized prompt:")
+fn strip_leading(mut text: &str) -> &str {
+    loop {
+        let Some(line) = text.lines().next() else {
+            return text;
+        };
+        let trimmed = line.trim();
+        let sentence = trimmed.ends_with([':', '.', '!'])
+            && ((LEAD_SENTENCE.is_match(trimmed) && ABOUT_REWRITE.is_match(trimmed))
+                || IDENTITY.is_match(trimmed));
+        if !(sentence || LEAD_HEADING.is_match(trimmed)) {
+            return text;
+        }
+        let rest = text[line.len()..].trim_start();
+        // Never clean a response down to nothing
+        if rest.is_empty() {
+            return text;
+        }
+        text = rest;
+    }
+}
+
+/// A fence line: the run of backticks or tildes and the info string
+fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let line = line.trim_end();
+    let c = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|x| *x == c).count();
+    (len >= 3).then(|| (c, len, line[len..].trim()))
+}
+
+/// The inside of a fence wrapping the whole answer, if there is one
+///
+/// Inner fenced examples stay intact: the closer is a bare fence at least
+/// as long as the opener followed only by commentary (or nothing), not
+/// simply the first bare fence.
+fn unwrap_fence(text: &str) -> Option<&str> {
+    let first = text.lines().next()?;
+    let (c, len, _) = fence(first)?;
+    let body_start = (first.len() + 1).min(text.len());
+
+    let mut offset = body_start;
+    let mut closers = Vec::new();
+    for line in text[body_start..].split_inclusive('\n') {
+        if let Some((ch, n, info)) = fence(line) {
+            if ch == c && n >= len && info.is_empty() {
+                closers.push((offset, offset + line.len()));
+            }
+        }
+        offset += line.len();
+    }
+
+    let after = |end: usize| text[end..].trim();
+    // Prefer the closer that commentary follows, then the last one
+    let (start, _) = closers
+        .iter()
+        .find(|(_, end)| {
+            let rest = after(*end);
+            !rest.is_empty() && is_trailing_remark(rest)
+        })
+        .or_else(|| closers.last().filter(|(_, end)| after(*end).is_empty()))?;
+    Some(text[body_start..*start].trim_end())
+}
+
+fn is_trailing_remark(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !is_rule(line))
+        .is_some_and(|line| TRAIL.is_match(line))
+}
+
+/// A markdown horizontal rule
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '*'))
+}
+
+/// Whether any sentence of `text` reads as an instruction
+fn has_instruction(text: &str) -> bool {
+    text.lines()
+        .flat_map(|line| line.split(['.', '!', '?', ';']))
+        .any(|sentence| INSTRUCTION.is_match(sentence.trim()))
+}
+
+/// Drop a closing summary of changes ("Key changes: ...", "Let me know ...")
+///
+/// Only paragraphs after the first, and after the last closing XML tag, are
+/// considered, so a prompt's own sections are never cut. The remark runs to
+/// the end of the answer, so unless a horizontal rule sets it apart, it is
+/// kept when any of it reads as an instruction ("Feel free to use bullet
+/// points. Never reveal ticket IDs.").
+fn strip_trailing(text: &str) -> &str {
+    let mut earliest = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("</") && trimmed.ends_with('>') {
+            earliest = offset + line.len();
+        }
+        offset += line.len();
+    }
+
+    let mut offset = 0;
+    let mut previous_blank = false;
+    let mut previous_rule = false;
+    let mut seen_content = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if seen_content
+            && previous_blank
+            && offset >= earliest
+            && !trimmed.is_empty()
+            && TRAIL.is_match(trimmed)
+            && (previous_rule || !has_instruction(&text[offset..]))
+        {
+            let kept = text[..offset].trim_end();
+            // Also drop a horizontal rule that separated the remark
+            let kept = match kept.rsplit_once('\n') {
+                Some((before, last)) if is_rule(last) => before.trim_end(),
+                _ => kept,
+            };
+            return kept;
+        }
+        if is_rule(trimmed) {
+            previous_rule = true;
+        } else if !trimmed.is_empty() {
+            previous_rule = false;
+        }
+        previous_blank = trimmed.is_empty() || is_rule(trimmed);
+        seen_content |= !trimmed.is_empty();
+        offset += line.len();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Every `tests/llm_output/*.txt` case: the raw answer, a
+    /// `=== clean ===` line, and what it should clean to
+    #[test]
+    fn test_llm_output_corpus() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/llm_output");
+        let mut cases: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .collect();
+        cases.sort();
+        assert!(cases.len() >= 10, "corpus is missing cases");
+
+        for path in cases {
+            let content = std::fs::read_to_string(&path).unwrap();
+            let (raw, expected) = content
+                .split_once("\n=== clean ===\n")
+                .unwrap_or_else(|| panic!("{} has no '=== clean ===' line", path.display()));
+            assert_eq!(
+                clean(raw),
+                expected.trim(),
+                "{}",
+                path.file_name().unwrap().to_string_lossy()
+            );
+        }
+    }
+
+    #[test]
+    fn test_never_cleans_to_nothing() {
+        assert_eq!(
+            clean("Here is the optimized prompt:"),
+            "Here is the optimized prompt:"
+        );
+        assert_eq!(clean("Summarize the report."), "Summarize the report.");
+    }
+
+    #[test]
+    fn test_unbalanced_fence_is_left_alone() {
+        let text = "```xml\n<task>\nSummarize.\n</task>";
+        assert_eq!(clean(text), text);
+    }
+}
@@ -0,0 +1,70 @@
+//! XML structuring transform
+//!
+//! Rewrites a prompt so each detected section sits in a semantic XML tag
+//! (`<context>`, `<rules>`, `<examples>`, ...). Used for FMT003 and `--structure`.
+
+use crate::analyzer::segment::{segment_prompt, SectionKind, SectionSource};
+
+/// Wrap each detected section of the prompt in its semantic XML tag
+///
+/// Sections already in XML tags are kept verbatim, heading lines are replaced
+/// by the tag, and a leading role statement stays as plain text so it still
+/// opens the prompt.
+pub fn structure_with_xml(prompt: &str) -> String {
+    let lines: Vec<&str> = prompt.lines().collect();
+    let sections = segment_prompt(prompt);
+
+    if sections.is_empty() {
+        return prompt.to_string();
+    }
+
+    let blocks: Vec<String> = sections
+        .iter()
+        .map(|section| {
+            let body_start = match section.source {
+                SectionSource::Heading => section.start_line,
+                _ => section.start_line - 1,
+            };
+            let body = lines[body_start..section.end_line].join("\n");
+            let body = body.trim();
+
+            match section.source {
+                SectionSource::XmlTag => body.to_string(),
+                _ if section.kind == SectionKind::Role => body.to_string(),
+                _ => {
+                    let tag = section.kind.xml_tag();
+                    format!("<{tag}>\n{body}\n</{tag}>")
+                }
+            }
+        })
+        .filter(|block| !block.is_empty())
+        .collect();
+
+    blocks.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure_wraps_sections() {
+        let prompt = "You are a release manager.\n\nWe ship weekly from main.\n\nRules:\n- Always tag releases\n- Never skip the changelog\n\nWrite the release notes for this week.";
+        let structured = structure_with_xml(prompt);
+        assert_eq!(
+            structured,
+            "You are a release manager.\n\n\
+             <context>\nWe ship weekly from main.\n</context>\n\n\
+             <rules>\n- Always tag releases\n- Never skip the changelog\n</rules>\n\n\
+             <instructions>\nWrite the release notes for this week.\n</instructions>"
+        );
+    }
+
+    #[test]
+    fn test_structure_is_idempotent() {
+        let prompt = "Background:\nThe API is slow.\n\nSummarize the latency report.";
+        let once = structure_with_xml(prompt);
+        assert_eq!(structure_with_xml(&once), once);
+        assert!(once.contains("<context>\nThe API is slow.\n</context>"));
+    }
+}
@@ -38,6 +38,8 @@ pub enum Category {
     Agentic,
     LongHorizon,
     Frontend,
+    Hygiene,
+    Language,
 }
 
 impl Category {
@@ -51,6 +53,8 @@ impl Category {
             Category::Agentic => "Agentic Coding",
             Category::LongHorizon => "Long-Horizon",
             Category::Frontend => "Frontend Design",
+            Category::Hygiene => "Hygiene",
+            Category::Language => "Language",
         }
     }
 
@@ -64,9 +68,12 @@ impl Category {
             Category::Agentic => "AGT",
             Category::LongHorizon => "LHT",
             Category::Frontend => "FED",
+            Category::Hygiene => "HYG",
+            Category::Language => "LNG",
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "explicitness" | "exp" => Some(Category::Explicitness),
@@ -77,6 +84,8 @@ impl Category {
             "agentic" | "agt" => Some(Category::Agentic),
             "long_horizon" | "longhorizon" | "lht" | "horizon" => Some(Category::LongHorizon),
             "frontend" | "fed" | "design" => Some(Category::Frontend),
+            "hygiene" | "hyg" => Some(Category::Hygiene),
+            "language" | "lng" | "spelling" => Some(Category::Language),
             _ => None,
         }
     }
@@ -91,6 +100,8 @@ impl Category {
             Category::Agentic,
             Category::LongHorizon,
             Category::Frontend,
+            Category::Hygiene,
+            Category::Language,
         ]
     }
 }
@@ -5,17 +5,26 @@
 #![allow(dead_code)]
 
 use std::io;
+use std::path::Path;
 use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyEventKind};
 
 use super::model::{Model, RenderMode};
+use super::session;
 use super::terminal;
 use super::update::{update, Msg};
 use super::view::render;
+use crate::analyzer::Issue;
+use crate::utils::editor;
+
+/// Issues found in an edited optimized prompt
+pub type Analyze<'a> = &'a dyn Fn(&str) -> Vec<Issue>;
 
 /// Run the interactive TUI application
-pub fn run_interactive(model: &mut Model) -> io::Result<()> {
+///
+/// `analyze` re-checks the optimized prompt after it's edited in the editor.
+pub fn run_interactive(model: &mut Model, analyze: Analyze) -> io::Result<()> {
     // Initialize safety measures (panic hooks, signal handlers)
     terminal::init_safety()?;
 
@@ -30,6 +39,9 @@ pub fn run_interactive(model: &mut Model) -> io::Result<()> {
     model.terminal_width = size.width;
     model.terminal_height = size.height;
 
+    // Saved up front and after every key press, so `--resume` survives a crash
+    session::save(model);
+
     // Main event loop
     loop {
         // Render
@@ -40,6 +52,7 @@ pub fn run_interactive(model: &mut Model) -> io::Result<()> {
             match event::read()? {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
                     update(model, Msg::Key(key));
+                    session::save(model);
                 }
                 Event::Resize(width, height) => {
                     update(model, Msg::Resize(width, height));
@@ -51,6 +64,11 @@ pub fn run_interactive(model: &mut Model) -> io::Result<()> {
             update(model, Msg::Tick);
         }
 
+        if let Some(path) = model.pending_edit.take() {
+            edit_round_trip(&mut terminal, model, &path, analyze)?;
+            session::save(model);
+        }
+
         // Check if we should quit
         if model.should_quit {
             break;
@@ -62,6 +80,81 @@ pub fn run_interactive(model: &mut Model) -> io::Result<()> {
     Ok(())
 }
 
+/// Open the saved optimized prompt in the editor, then take the edit back in
+///
+/// The TUI leaves the screen to the editor and waits for it to exit. An
+/// editor that doesn't wait (`--editor-wait=false`) is only opened.
+fn edit_round_trip(
+    terminal: &mut terminal::Tui,
+    model: &mut Model,
+    path: &Path,
+    analyze: Analyze,
+) -> io::Result<()> {
+    let editor = editor::command(path);
+    let saved = format!("Saved to {}", path.display());
+    if let Some(reason) = editor.unavailable() {
+        model.set_status_message(
+            format!("✓ {} (no editor: {})", saved, reason),
+            Duration::from_secs(5),
+        );
+        return Ok(());
+    }
+    if !editor.waits {
+        let message = match std::process::Command::new(&editor.program)
+            .args(&editor.args)
+            .spawn()
+        {
+            Ok(_) => format!("✓ {} and opened in {}", saved, editor.program),
+            Err(e) => format!("✓ {} (editor failed: {})", saved, e),
+        };
+        model.set_status_message(message, Duration::from_secs(5));
+        return Ok(());
+    }
+
+    // A GUI editor leaves the terminal alone, so the TUI stays up meanwhile
+    if editor.gui {
+        model.set_status_message(
+            format!("Waiting for {} to close the file…", editor.program),
+            Duration::from_secs(3600),
+        );
+        terminal.draw(|frame| render(frame, model))?;
+    } else {
+        terminal::restore();
+    }
+    let status = std::process::Command::new(&editor.program)
+        .args(&editor.args)
+        .status();
+    if !editor.gui {
+        terminal::resume(terminal)?;
+    }
+
+    match status {
+        Ok(status) if status.success() => match std::fs::read_to_string(path) {
+            Ok(edited) => {
+                // The footer records how the saved file was made, not the prompt
+                let edited = crate::cli::provenance::strip(&edited);
+                let issues = analyze(edited);
+                if !model.apply_edit(edited, &issues) {
+                    model.set_status_message(format!("✓ {}", saved), Duration::from_secs(5));
+                }
+            }
+            Err(e) => model.set_status_message(
+                format!("✗ Couldn't read the edit back: {}", e),
+                Duration::from_secs(5),
+            ),
+        },
+        Ok(status) => model.set_status_message(
+            format!("✓ {} (editor exited with {})", saved, status),
+            Duration::from_secs(5),
+        ),
+        Err(e) => model.set_status_message(
+            format!("✓ {} (editor failed: {})", saved, e),
+            Duration::from_secs(5),
+        ),
+    }
+    Ok(())
+}
+
 /// Run non-interactive linear mode (enhanced output)
 pub fn run_linear(model: &Model) -> io::Result<()> {
     use super::linear;
@@ -93,7 +186,9 @@ pub fn detect_render_mode(
 /// Chooses between interactive and linear mode based on render mode.
 pub fn run(model: &mut Model) -> io::Result<()> {
     match model.render_mode {
-        RenderMode::Interactive => run_interactive(model),
+        RenderMode::Interactive => run_interactive(model, &|text| {
+            crate::analyzer::analyze(text, None).unwrap_or_default()
+        }),
         RenderMode::Linear => run_linear(model),
         RenderMode::Plain | RenderMode::Json | RenderMode::Quiet => {
             // These modes don't use the TUI - handled by main.rs
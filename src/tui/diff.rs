@@ -6,145 +6,236 @@
 
 use colored::Colorize;
 use similar::{ChangeTag, TextDiff};
+use std::io::{self, Write};
 
-use super::{chars, legacy_icons as icons, terminal_width};
+use super::icons::icons;
+use super::{chars, terminal_width};
 
-/// Print a side-by-side diff of original and optimized prompts
-pub fn print_diff(original: &str, optimized: &str) {
+/// Narrowest terminal that gets the side-by-side layout; below it the
+/// changes are listed in one column
+const MIN_SIDE_BY_SIDE_WIDTH: usize = 80;
+
+/// One row of a side-by-side diff: an original line, the optimized line it
+/// became, or both when unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub changed: bool,
+}
+
+/// Rows of a side-by-side diff, with each run of removed lines paired with
+/// the added lines that replaced it so they sit next to each other
+pub fn side_by_side_rows(original: &str, optimized: &str) -> Vec<DiffRow> {
+    fn flush(rows: &mut Vec<DiffRow>, removed: &mut Vec<String>, added: &mut Vec<String>) {
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        loop {
+            match (removed.next(), added.next()) {
+                (None, None) => break,
+                (left, right) => rows.push(DiffRow {
+                    left,
+                    right,
+                    changed: true,
+                }),
+            }
+        }
+    }
+
+    let diff = TextDiff::from_lines(original, optimized);
+    let mut rows = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    for change in diff.iter_all_changes() {
+        let content = change.value().trim_end().to_string();
+        match change.tag() {
+            ChangeTag::Delete => removed.push(content),
+            ChangeTag::Insert => added.push(content),
+            ChangeTag::Equal => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    left: Some(content.clone()),
+                    right: Some(content),
+                    changed: false,
+                });
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// `text` wrapped to `width` columns; an empty line is one empty row
+pub fn wrap_line(text: &str, width: usize) -> Vec<String> {
+    textwrap::wrap(text, width.max(1))
+        .into_iter()
+        .map(|row| row.into_owned())
+        .collect()
+}
+
+/// Write a diff of original and optimized prompts: side by side, or in one
+/// column on narrow terminals. Long lines wrap instead of being cut off.
+pub fn write_diff(w: &mut impl Write, original: &str, optimized: &str) -> io::Result<()> {
     let width = terminal_width().min(120);
-    let half_width = (width - 3) / 2;
+    if width < MIN_SIDE_BY_SIDE_WIDTH {
+        write_unified(w, original, optimized, width.max(20))
+    } else {
+        write_side_by_side(w, original, optimized, width)
+    }
+}
 
-    println!();
-    println!(
+fn write_header(w: &mut impl Write, width: usize) -> io::Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
         "{}",
         format!(
             "{} {} Changes {}",
             chars::TOP_LEFT,
-            icons::SPARKLES,
+            icons().sparkles,
             chars::HORIZONTAL.repeat(width - 14)
         )
         .cyan()
-    );
+    )
+}
+
+fn write_footer(w: &mut impl Write, width: usize) -> io::Result<()> {
+    writeln!(
+        w,
+        "{}{}{}",
+        chars::BOTTOM_LEFT.cyan(),
+        chars::HORIZONTAL.repeat(width - 2).cyan(),
+        chars::BOTTOM_RIGHT.cyan()
+    )?;
+    writeln!(w)
+}
 
-    // Headers
-    let orig_header = format!("{} Original", icons::FILE);
-    let opt_header = format!("{} Optimized", icons::SPARKLES);
+/// Pad `text` with spaces to `width` display columns
+fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(console::measure_text_width(text)))
+    )
+}
+
+fn write_side_by_side(
+    w: &mut impl Write,
+    original: &str,
+    optimized: &str,
+    width: usize,
+) -> io::Result<()> {
+    let icons = icons();
+    // "│ " + left cell + " │ " + right cell
+    let cell = (width - 5) / 2;
+    // Each cell starts with a two-column "- " / "+ " marker
+    let text_width = cell - 2;
 
-    println!(
-        "{} {}{} {} {}{}",
+    write_header(w, width)?;
+
+    let orig_header = format!("{} Original", icons.file);
+    let opt_header = format!("{} Optimized", icons.sparkles);
+    writeln!(
+        w,
+        "{} {} {} {}",
         chars::VERTICAL.cyan(),
-        orig_header.bright_black(),
-        " ".repeat(half_width.saturating_sub(orig_header.len() + 1)),
+        pad(&orig_header, cell).bright_black(),
         chars::VERTICAL.bright_black(),
         opt_header.green(),
-        " ".repeat(half_width.saturating_sub(opt_header.len()))
-    );
-
-    println!(
-        "{}{}{}{}{}",
+    )?;
+    writeln!(
+        w,
+        "{}{}{}{}",
         chars::T_RIGHT.cyan(),
-        chars::HORIZONTAL.repeat(half_width).bright_black(),
+        chars::HORIZONTAL.repeat(cell + 1).bright_black(),
         chars::CROSS.bright_black(),
-        chars::HORIZONTAL.repeat(half_width).bright_black(),
-        chars::T_LEFT.cyan()
-    );
+        chars::HORIZONTAL.repeat(cell + 1).bright_black(),
+    )?;
 
-    // Generate diff
-    let diff = TextDiff::from_lines(original, optimized);
-
-    for change in diff.iter_all_changes() {
-        let content = change.value().trim_end();
+    for row in side_by_side_rows(original, optimized) {
+        let left = row.left.as_deref().map(|t| wrap_line(t, text_width));
+        let right = row.right.as_deref().map(|t| wrap_line(t, text_width));
+        let height = left
+            .as_ref()
+            .map_or(0, Vec::len)
+            .max(right.as_ref().map_or(0, Vec::len));
 
-        match change.tag() {
-            ChangeTag::Delete => {
-                let left = truncate_with_style(content, half_width - 2);
-                println!(
-                    "{} {}{} {} {}",
-                    chars::VERTICAL.cyan(),
-                    format!("- {}", left).red(),
-                    " ".repeat(half_width.saturating_sub(left.len() + 3)),
-                    chars::VERTICAL.bright_black(),
-                    " ".repeat(half_width)
-                );
-            }
-            ChangeTag::Insert => {
-                let right = truncate_with_style(content, half_width - 2);
-                println!(
-                    "{} {}{} {} {}{}",
-                    chars::VERTICAL.cyan(),
-                    " ".repeat(half_width - 1),
-                    chars::VERTICAL.bright_black(),
-                    "+".green(),
-                    right.green(),
-                    " ".repeat(half_width.saturating_sub(right.len() + 3))
-                );
-            }
-            ChangeTag::Equal => {
-                let text = truncate_with_style(content, half_width - 2);
-                println!(
-                    "{} {}{} {} {}",
-                    chars::VERTICAL.cyan(),
-                    text.bright_black(),
-                    " ".repeat(half_width.saturating_sub(text.len() + 1)),
-                    chars::VERTICAL.bright_black(),
-                    text.bright_black(),
-                );
-            }
+        for i in 0..height {
+            let segment = |side: &Option<Vec<String>>, marker: &str| match side
+                .as_ref()
+                .and_then(|rows| rows.get(i))
+            {
+                Some(text) if i == 0 => format!("{} {}", marker, text),
+                Some(text) => format!("  {}", text),
+                None => String::new(),
+            };
+            let (left_text, right_text) = if row.changed {
+                (
+                    pad(&segment(&left, "-"), cell).red(),
+                    segment(&right, "+").green(),
+                )
+            } else {
+                (
+                    pad(&segment(&left, " "), cell).bright_black(),
+                    segment(&right, " ").bright_black(),
+                )
+            };
+            writeln!(
+                w,
+                "{} {} {} {}",
+                chars::VERTICAL.cyan(),
+                left_text,
+                chars::VERTICAL.bright_black(),
+                right_text
+            )?;
         }
     }
 
-    println!(
-        "{}{}{}",
-        chars::BOTTOM_LEFT.cyan(),
-        chars::HORIZONTAL.repeat(width - 2).cyan(),
-        chars::BOTTOM_RIGHT.cyan()
-    );
-    println!();
+    write_footer(w, width)
 }
 
-/// Print a unified diff format
-pub fn print_unified_diff(original: &str, optimized: &str) {
-    let diff = TextDiff::from_lines(original, optimized);
-
-    println!();
-    println!("{}", "─".repeat(60).bright_black());
-    println!("{}", "Unified Diff".cyan().bold());
-    println!("{}", "─".repeat(60).bright_black());
-
-    for (idx, group) in diff.grouped_ops(3).iter().enumerate() {
-        if idx > 0 {
-            println!("{}", "...".bright_black());
-        }
+/// One column: removed lines, then the lines that replaced them
+fn write_unified(
+    w: &mut impl Write,
+    original: &str,
+    optimized: &str,
+    width: usize,
+) -> io::Result<()> {
+    // "│ " + marker + space
+    let text_width = width - 4;
+    write_header(w, width)?;
 
-        for op in group {
-            for change in diff.iter_changes(op) {
-                let (sign, line) = match change.tag() {
-                    ChangeTag::Delete => ("-".red(), change.value().red()),
-                    ChangeTag::Insert => ("+".green(), change.value().green()),
-                    ChangeTag::Equal => (" ".normal(), change.value().normal()),
-                };
-
-                print!("{}{}", sign, line);
-                if !change.value().ends_with('\n') {
-                    println!();
-                }
-            }
+    let diff = TextDiff::from_lines(original, optimized);
+    for change in diff.iter_all_changes() {
+        let (marker, paint): (&str, fn(&str) -> colored::ColoredString) = match change.tag() {
+            ChangeTag::Delete => ("-", |s| s.red()),
+            ChangeTag::Insert => ("+", |s| s.green()),
+            ChangeTag::Equal => (" ", |s| s.bright_black()),
+        };
+        for (i, text) in wrap_line(change.value().trim_end(), text_width)
+            .iter()
+            .enumerate()
+        {
+            let marker = if i == 0 { marker } else { " " };
+            writeln!(
+                w,
+                "{} {}",
+                chars::VERTICAL.cyan(),
+                paint(&format!("{} {}", marker, text))
+            )?;
         }
     }
 
-    println!("{}", "─".repeat(60).bright_black());
-    println!();
+    write_footer(w, width)
 }
 
-/// Truncate a string for display, respecting terminal width
-fn truncate_with_style(s: &str, max_width: usize) -> String {
-    if s.len() <= max_width {
-        s.to_string()
-    } else if max_width > 3 {
-        format!("{}...", &s[..max_width - 3])
-    } else {
-        s[..max_width].to_string()
-    }
+/// Plain unified diff (`--- original` / `+++ optimized`) that `patch` and
+/// `git apply` accept; empty when nothing changed
+pub fn unified_diff(original: &str, optimized: &str) -> String {
+    TextDiff::from_lines(original, optimized)
+        .unified_diff()
+        .context_radius(3)
+        .header("original", "optimized")
+        .to_string()
 }
 
 /// Calculate diff statistics
@@ -209,9 +300,56 @@ mod tests {
     }
 
     #[test]
-    fn test_truncate() {
-        assert_eq!(truncate_with_style("hello", 10), "hello");
-        assert_eq!(truncate_with_style("hello world!", 8), "hello...");
+    fn test_unified_diff() {
+        let diff = unified_diff("line 1\nline 2\n", "line 1\nline two\n");
+        assert!(diff.starts_with("--- original\n+++ optimized\n@@ -1,2 +1,2 @@\n"));
+        assert!(diff.contains("\n-line 2\n+line two\n"));
+        assert_eq!(unified_diff("same\n", "same\n"), "");
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_replacements() {
+        let rows = side_by_side_rows("keep\nold a\nold b\nend\n", "keep\nnew a\nend\nextra\n");
+        let row = |left: Option<&str>, right: Option<&str>, changed| DiffRow {
+            left: left.map(str::to_string),
+            right: right.map(str::to_string),
+            changed,
+        };
+        assert_eq!(
+            rows,
+            [
+                row(Some("keep"), Some("keep"), false),
+                row(Some("old a"), Some("new a"), true),
+                row(Some("old b"), None, true),
+                row(Some("end"), Some("end"), false),
+                row(None, Some("extra"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_wraps_long_lines() {
+        let long = "word ".repeat(40);
+        let mut out = Vec::new();
+        write_side_by_side(&mut out, "short\n", &format!("short\n{}\n", long), 80).unwrap();
+        let out = console::strip_ansi_codes(&String::from_utf8(out).unwrap()).to_string();
+
+        // Nothing is cut off: every word shows up on a wrapped row
+        assert_eq!(out.matches("word").count(), 40);
+        assert!(!out.contains("..."));
+        assert!(out.lines().all(|l| console::measure_text_width(l) <= 80));
+    }
+
+    #[test]
+    fn test_unified_wraps_long_lines() {
+        let mut out = Vec::new();
+        write_unified(&mut out, "a b c d e f\n", "a b c d e f g h i j\n", 14).unwrap();
+        let out = console::strip_ansi_codes(&String::from_utf8(out).unwrap()).to_string();
+        let rows: Vec<&str> = out.lines().filter(|l| l.starts_with('│')).collect();
+        assert_eq!(
+            rows,
+            ["│ - a b c d", "│   e f", "│ + a b c d e", "│   f g h i j",]
+        );
     }
 
     #[test]
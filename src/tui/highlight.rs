@@ -0,0 +1,143 @@
+//! Syntax highlighting for fenced code blocks
+//!
+//! Prompts often embed code, and an optimizer that "tidies" it is a change
+//! reviewers need to see. [`CodeBlocks`] follows the ``` / ~~~ fences line
+//! by line and highlights the lines inside with syntect, using the fence's
+//! language tag (`rust`, `py`, `json`, …) or plain text when it's missing
+//! or unknown. The syntax definitions load on the first fenced block, so
+//! prompts without code don't pay for them.
+
+use ratatui::style::{Color, Modifier, Style};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+/// Theme tuned for dark backgrounds, like the rest of the TUI
+const THEME: &str = "base16-ocean.dark";
+
+struct Syntaxes {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+fn syntaxes() -> &'static Syntaxes {
+    static SYNTAXES: OnceLock<Syntaxes> = OnceLock::new();
+    SYNTAXES.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        Syntaxes {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.themes.remove(THEME).unwrap_or_default(),
+        }
+    })
+}
+
+/// A fence line's marker (``` or ~~~), if `line` opens or closes a block
+fn fence(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Tracks fenced code blocks across the lines of one prompt
+#[derive(Default)]
+pub struct CodeBlocks {
+    /// The open block's fence marker and highlighter
+    open: Option<(&'static str, HighlightLines<'static>)>,
+}
+
+impl CodeBlocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next line of the prompt
+    ///
+    /// Returns the highlighted pieces of a line inside a code block, or
+    /// `None` for prose and the fence lines themselves.
+    pub fn line(&mut self, line: &str) -> Option<Vec<(Style, String)>> {
+        let marker = fence(line);
+        match (&mut self.open, marker) {
+            (Some((open, _)), Some(marker)) if *open == marker => {
+                self.open = None;
+                None
+            }
+            (Some((_, highlighter)), _) => Some(highlight(highlighter, line)),
+            (None, Some(marker)) => {
+                let tag = line.trim_start()[marker.len()..]
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("");
+                let syntaxes = syntaxes();
+                let syntax = syntaxes
+                    .syntaxes
+                    .find_syntax_by_token(tag)
+                    .unwrap_or_else(|| syntaxes.syntaxes.find_syntax_plain_text());
+                self.open = Some((marker, HighlightLines::new(syntax, &syntaxes.theme)));
+                None
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+fn highlight(highlighter: &mut HighlightLines<'static>, line: &str) -> Vec<(Style, String)> {
+    let with_newline = format!("{}\n", line);
+    match highlighter.highlight_line(&with_newline, &syntaxes().syntaxes) {
+        Ok(pieces) => pieces
+            .into_iter()
+            .map(|(style, text)| (to_style(style), text.trim_end_matches('\n').to_string()))
+            .filter(|(_, text)| !text.is_empty())
+            .collect(),
+        Err(_) => vec![(Style::default(), line.to_string())],
+    }
+}
+
+fn to_style(style: syntect::highlighting::Style) -> Style {
+    let fg = style.foreground;
+    let mut converted = Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b));
+    let font = style.font_style;
+    if font.contains(syntect::highlighting::FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if font.contains(syntect::highlighting::FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_fenced_lines_are_highlighted() {
+        let mut blocks = CodeBlocks::new();
+        let prompt = "Fix this:\n```rust\nfn main() {}\n```\nThanks";
+        let highlighted: Vec<bool> = prompt.lines().map(|l| blocks.line(l).is_some()).collect();
+        assert_eq!(highlighted, [false, false, true, false, false]);
+
+        let mut blocks = CodeBlocks::new();
+        blocks.line("```rust");
+        let pieces = blocks.line("fn main() {}").unwrap();
+        let text: String = pieces.iter().map(|(_, t)| t.as_str()).collect();
+        assert_eq!(text, "fn main() {}");
+        // Keywords and names get different colors
+        assert!(pieces.len() > 1);
+    }
+
+    #[test]
+    fn test_fences_must_match() {
+        let mut blocks = CodeBlocks::new();
+        blocks.line("~~~");
+        // A ``` line inside a ~~~ block is code
+        assert!(blocks.line("```").is_some());
+        assert!(blocks.line("~~~").is_none());
+        assert!(blocks.line("prose").is_none());
+    }
+}
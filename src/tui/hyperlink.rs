@@ -0,0 +1,168 @@
+//! Clickable links in terminal output (OSC 8)
+//!
+//! Terminals that understand OSC 8 show link text as usual and open the
+//! target on click (often Cmd/Ctrl+click); others would print the escape
+//! sequence as garbage, so links are only written when stdout is a terminal
+//! known to support them. `FORCE_HYPERLINK=1` or `=0` overrides detection.
+
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// `text` linking to `url`, or `text` alone where links aren't supported
+pub fn link(url: &str, text: &str) -> String {
+    if enabled() {
+        format_link(url, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A file path linking to the file, or the path alone
+///
+/// Paths that don't exist locally (an `s3://` destination) stay plain.
+pub fn file(path: &str) -> String {
+    match file_url(Path::new(path)) {
+        Some(url) => link(&url, path),
+        None => path.to_string(),
+    }
+}
+
+/// A rule ID linking to its documentation, or the ID alone
+pub fn rule(rule_id: &str) -> String {
+    match crate::analyzer::docs::docs_url(rule_id) {
+        Some(url) => link(&url, rule_id),
+        None => rule_id.to_string(),
+    }
+}
+
+fn format_link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `file://` URL of an existing path, percent-encoded
+fn file_url(path: &Path) -> Option<String> {
+    let absolute = path.canonicalize().ok()?;
+    let absolute = absolute.to_str()?;
+    // Windows paths become file:///C:/...
+    let absolute = absolute.strip_prefix(r"\\?\").unwrap_or(absolute);
+    let mut url = String::from("file://");
+    if !absolute.starts_with('/') {
+        url.push('/');
+    }
+    for byte in absolute.bytes() {
+        match byte {
+            b'\\' => url.push('/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Some(url)
+}
+
+/// Whether links are written, decided once per process
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        let var = |name: &str| std::env::var(name).ok();
+        match var("FORCE_HYPERLINK").as_deref() {
+            Some("0") | Some("false") => return false,
+            Some(_) => return true,
+            None => {}
+        }
+        std::io::stdout().is_terminal() && detect(var)
+    })
+}
+
+/// Whether the terminal described by the environment supports OSC 8
+fn detect(var: impl Fn(&str) -> Option<String>) -> bool {
+    // CI logs and multiplexers show the raw sequence or drop it
+    if var("CI").is_some() || var("TMUX").is_some() || var("STY").is_some() {
+        return false;
+    }
+    let term = var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return false;
+    }
+
+    if let Some(program) = var("TERM_PROGRAM") {
+        match program.as_str() {
+            "iTerm.app" => {
+                // OSC 8 arrived in iTerm2 3.1
+                let version = var("TERM_PROGRAM_VERSION").unwrap_or_default();
+                let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+                let major = parts.next().unwrap_or(0);
+                let minor = parts.next().unwrap_or(0);
+                return (major, minor) >= (3, 1);
+            }
+            "WezTerm" | "vscode" | "ghostty" | "Hyper" | "Tabby" | "rio" => return true,
+            // Apple Terminal prints the sequence
+            "Apple_Terminal" => return false,
+            _ => {}
+        }
+    }
+    if var("WT_SESSION").is_some() || var("KONSOLE_VERSION").is_some() {
+        return true;
+    }
+    // GNOME Terminal, Tilix, and other VTE terminals since 0.50
+    if let Some(vte) = var("VTE_VERSION") {
+        return vte.parse::<u32>().is_ok_and(|v| v >= 5000);
+    }
+    ["kitty", "alacritty", "ghostty", "foot", "wezterm"]
+        .iter()
+        .any(|name| term.contains(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_detect_known_terminals() {
+        assert!(detect(env(&[("TERM_PROGRAM", "WezTerm")])));
+        assert!(detect(env(&[
+            ("TERM_PROGRAM", "iTerm.app"),
+            ("TERM_PROGRAM_VERSION", "3.5.0")
+        ])));
+        assert!(!detect(env(&[
+            ("TERM_PROGRAM", "iTerm.app"),
+            ("TERM_PROGRAM_VERSION", "3.0.15")
+        ])));
+        assert!(detect(env(&[("VTE_VERSION", "7600")])));
+        assert!(!detect(env(&[("VTE_VERSION", "4200")])));
+        assert!(detect(env(&[("TERM", "xterm-kitty")])));
+        assert!(!detect(env(&[("TERM_PROGRAM", "Apple_Terminal")])));
+        assert!(!detect(env(&[("TERM", "xterm-256color")])));
+        assert!(!detect(env(&[
+            ("TERM_PROGRAM", "WezTerm"),
+            ("TMUX", "/tmp/tmux")
+        ])));
+    }
+
+    #[test]
+    fn test_link_format_and_file_url() {
+        assert_eq!(
+            format_link("https://example.com", "EXP003"),
+            "\x1b]8;;https://example.com\x1b\\EXP003\x1b]8;;\x1b\\"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optimized prompt.md");
+        std::fs::write(&path, "x").unwrap();
+        let url = file_url(&path).unwrap();
+        assert!(url.starts_with("file://"), "{}", url);
+        assert!(url.ends_with("/optimized%20prompt.md"), "{}", url);
+        assert_eq!(file_url(Path::new("s3://bucket/prompt.md")), None);
+    }
+}
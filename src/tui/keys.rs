@@ -0,0 +1,419 @@
+//! Key bindings for the interactive TUI (`[keys]`)
+//!
+//! Every action has default keys; a `[keys]` entry in the config replaces
+//! the defaults of one action:
+//!
+//! ```toml
+//! [keys]
+//! copy = "y"
+//! quit = ["q", "ctrl+q"]
+//! up = ["up", "ctrl+p"]
+//! ```
+//!
+//! Keys are a character (`y`, `?`) or a name (`enter`, `esc`, `tab`,
+//! `space`, `up`, `down`, `pgup`, `pgdn`, `home`, `end`, `f1`-`f12`), with
+//! optional `ctrl+`/`alt+` prefixes. Binding one key to two actions that
+//! apply in the same view is an error, so a remap can't silently shadow
+//! another action. The help screen and status bar show the effective
+//! bindings.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use super::model::View;
+use crate::cli::config::KeyList;
+use crate::error::CoptError;
+
+const VIEWS: [View; 3] = [View::Main, View::Diff, View::Help];
+
+/// Something a key can do in the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Up,
+    Down,
+    Toggle,
+    PageUp,
+    PageDown,
+    Top,
+    Diff,
+    Help,
+    Back,
+    Copy,
+    Save,
+    Edit,
+    Docs,
+    Preview,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: &[Action] = &[
+        Action::Up,
+        Action::Down,
+        Action::Toggle,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Top,
+        Action::Diff,
+        Action::Help,
+        Action::Back,
+        Action::Copy,
+        Action::Save,
+        Action::Edit,
+        Action::Docs,
+        Action::Preview,
+        Action::Quit,
+    ];
+
+    /// Name used in `[keys]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Toggle => "toggle",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Top => "top",
+            Action::Diff => "diff",
+            Action::Help => "help",
+            Action::Back => "back",
+            Action::Copy => "copy",
+            Action::Save => "save",
+            Action::Edit => "edit",
+            Action::Docs => "docs",
+            Action::Preview => "preview",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Whether the action does anything in `view`; keys may be shared by
+    /// actions that never apply in the same view, like `docs` and `preview`
+    pub fn applies_in(self, view: View) -> bool {
+        match self {
+            Action::Docs => view == View::Main,
+            Action::Toggle => view != View::Diff,
+            Action::Preview => view == View::Diff,
+            _ => true,
+        }
+    }
+
+    fn defaults(self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["up", "k"],
+            Action::Down => &["down", "j"],
+            Action::Toggle => &["enter"],
+            Action::PageUp => &["pgup"],
+            Action::PageDown => &["pgdn"],
+            Action::Top => &["home"],
+            Action::Diff => &["d"],
+            Action::Help => &["?"],
+            Action::Back => &["esc"],
+            Action::Copy => &["c"],
+            Action::Save => &["s"],
+            Action::Edit => &["e"],
+            Action::Docs => &["o"],
+            Action::Preview => &["o"],
+            Action::Quit => &["q", "ctrl+c"],
+        }
+    }
+}
+
+/// A key with its modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Parse `ctrl+c`, `pgup`, `?`, …
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text.trim();
+        loop {
+            let lower = rest.to_lowercase();
+            if let Some(stripped) = lower.strip_prefix("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[rest.len() - stripped.len()..];
+            } else if let Some(stripped) = lower.strip_prefix("alt+") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[rest.len() - stripped.len()..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key '{}'", text)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    fn matches(&self, event: &KeyEvent) -> bool {
+        // Shift is part of the character itself ('?' or 'C')
+        let modifiers = match event.code {
+            KeyCode::Char(_) => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        self.code == event.code && self.modifiers == modifiers
+    }
+
+    /// How the key is shown in help and hints, e.g. "Ctrl+C" or "↑"
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) if !self.modifiers.is_empty() => label.extend(c.to_uppercase()),
+            KeyCode::Char(c) => label.push(c),
+            KeyCode::Enter => label.push_str("Enter"),
+            KeyCode::Esc => label.push_str("Esc"),
+            KeyCode::Tab => label.push_str("Tab"),
+            KeyCode::Backspace => label.push_str("Backspace"),
+            KeyCode::Up => label.push('↑'),
+            KeyCode::Down => label.push('↓'),
+            KeyCode::Left => label.push('←'),
+            KeyCode::Right => label.push('→'),
+            KeyCode::Home => label.push_str("Home"),
+            KeyCode::End => label.push_str("End"),
+            KeyCode::PageUp => label.push_str("PgUp"),
+            KeyCode::PageDown => label.push_str("PgDn"),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            other => label.push_str(&format!("{:?}", other)),
+        }
+        label
+    }
+}
+
+/// Keys bound to each action
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = action
+                    .defaults()
+                    .iter()
+                    .map(|k| Key::parse(k).expect("default key bindings parse"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Defaults with the `[keys]` overrides applied
+    pub fn from_config(overrides: &BTreeMap<String, KeyList>) -> Result<Self, CoptError> {
+        let mut map = Self::default();
+        for (name, keys) in overrides {
+            let action = Action::ALL
+                .iter()
+                .copied()
+                .find(|a| a.name() == name)
+                .ok_or_else(|| {
+                    CoptError::Config(format!(
+                        "Unknown action '{}' in [keys]. Valid options: {}",
+                        name,
+                        Action::ALL
+                            .iter()
+                            .map(|a| a.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })?;
+            let keys = keys
+                .keys()
+                .into_iter()
+                .map(|k| {
+                    Key::parse(k)
+                        .map_err(|e| CoptError::Config(format!("{} in [keys] {}", e, name)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if keys.is_empty() {
+                return Err(CoptError::Config(format!(
+                    "No keys for '{}' in [keys]; every action needs at least one",
+                    name
+                )));
+            }
+            map.bindings.insert(action, keys);
+        }
+        map.check_collisions()?;
+        Ok(map)
+    }
+
+    fn check_collisions(&self) -> Result<(), CoptError> {
+        let all: Vec<(Action, &Key)> = self
+            .bindings
+            .iter()
+            .flat_map(|(action, keys)| keys.iter().map(move |k| (*action, k)))
+            .collect();
+        for (i, (action, key)) in all.iter().enumerate() {
+            if let Some((other, _)) = all[i + 1..].iter().find(|(other, k)| {
+                other != action
+                    && k == key
+                    && VIEWS
+                        .iter()
+                        .any(|&v| action.applies_in(v) && other.applies_in(v))
+            }) {
+                return Err(CoptError::Config(format!(
+                    "Key '{}' is bound to both '{}' and '{}' in [keys]",
+                    key.label(),
+                    action.name(),
+                    other.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The action bound to a key press in `view`
+    pub fn action(&self, event: &KeyEvent, view: View) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(action, _)| action.applies_in(view))
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// The first key of `action`, for the compact status bar hints
+    pub fn primary(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map(Key::label)
+            .unwrap_or_default()
+    }
+
+    /// Keys of `action` for display, e.g. "q/Ctrl+C"
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.iter().map(Key::label).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+}
+
+static KEYMAP: OnceLock<KeyMap> = OnceLock::new();
+
+/// Use the `[keys]` bindings for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(overrides: &BTreeMap<String, KeyList>) -> Result<(), CoptError> {
+    let map = KeyMap::from_config(overrides)?;
+    let _ = KEYMAP.set(map);
+    Ok(())
+}
+
+/// The effective key bindings
+pub fn keymap() -> &'static KeyMap {
+    KEYMAP.get_or_init(KeyMap::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn overrides(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyList> {
+        entries
+            .iter()
+            .map(|(name, keys)| {
+                (
+                    name.to_string(),
+                    KeyList::Many(keys.iter().map(|k| k.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let map = KeyMap::default();
+        let ctrl_c = press(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(map.action(&ctrl_c, View::Main), Some(Action::Quit));
+        assert_eq!(
+            map.action(&press(KeyCode::Char('c'), KeyModifiers::NONE), View::Main),
+            Some(Action::Copy)
+        );
+        assert_eq!(
+            map.action(&press(KeyCode::Char('?'), KeyModifiers::SHIFT), View::Main),
+            Some(Action::Help)
+        );
+        assert_eq!(map.label(Action::Quit), "q/Ctrl+C");
+        assert_eq!(map.label(Action::Up), "↑/k");
+        assert_eq!(map.primary(Action::Quit), "q");
+
+        // 'o' opens rule docs in the main view and flips the preview in the diff view
+        let o = press(KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(map.action(&o, View::Main), Some(Action::Docs));
+        assert_eq!(map.action(&o, View::Diff), Some(Action::Preview));
+        assert_eq!(map.action(&o, View::Help), None);
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let map = KeyMap::from_config(&overrides(&[("copy", &["y"]), ("quit", &["q", "ctrl+q"])]))
+            .unwrap();
+        let c = press(KeyCode::Char('c'), KeyModifiers::NONE);
+        assert_eq!(map.action(&c, View::Main), None);
+        assert_eq!(
+            map.action(&press(KeyCode::Char('y'), KeyModifiers::NONE), View::Main),
+            Some(Action::Copy)
+        );
+        assert_eq!(
+            map.action(
+                &press(KeyCode::Char('q'), KeyModifiers::CONTROL),
+                View::Main
+            ),
+            Some(Action::Quit)
+        );
+        assert_eq!(map.label(Action::Quit), "q/Ctrl+Q");
+    }
+
+    #[test]
+    fn test_invalid_bindings() {
+        let err = KeyMap::from_config(&overrides(&[("copy", &["s"])])).unwrap_err();
+        assert!(err.to_string().contains("bound to both"));
+        assert!(KeyMap::from_config(&overrides(&[("preview", &["d"])])).is_err());
+        assert!(KeyMap::from_config(&overrides(&[("yank", &["y"])])).is_err());
+        assert!(KeyMap::from_config(&overrides(&[("copy", &["hyper+y"])])).is_err());
+        assert!(KeyMap::from_config(&overrides(&[("copy", &[])])).is_err());
+        assert_eq!(Key::parse("F5").unwrap().code, KeyCode::F(5));
+    }
+}
@@ -7,11 +7,28 @@
 
 use std::io::{self, Write};
 
-use colored::Colorize;
+use colored::{Color, Colorize};
 
+use super::hyperlink;
 use super::icons::icons;
 use super::model::{AppPhase, Model};
-use crate::analyzer::Severity;
+use super::severity;
+use crate::analyzer::coverage::{CategoryCoverage, CoverageStatus};
+use crate::analyzer::report::GroupBy;
+use crate::analyzer::strengths::StrengthReport;
+use crate::analyzer::{Issue, Severity};
+use crate::i18n::t;
+
+/// Column width of the stat labels
+const LABEL_WIDTH: usize = 18;
+
+/// Width the optimized prompt is wrapped to
+const PROMPT_WIDTH: usize = 72;
+
+/// Pad a stat label to `LABEL_WIDTH` display columns (wide-character aware)
+fn label(text: &str) -> String {
+    console::pad_str(text, LABEL_WIDTH, console::Alignment::Left, None).into_owned()
+}
 
 /// Render the model in linear mode (prints to stdout)
 pub fn render(model: &Model) -> io::Result<()> {
@@ -34,10 +51,180 @@ pub fn render(model: &Model) -> io::Result<()> {
     Ok(())
 }
 
-/// Render only the stats section (for use after optimization completes)
-pub fn render_stats_only(model: &Model) -> io::Result<()> {
-    let mut stdout = io::stdout();
-    render_stats(&mut stdout, model)
+/// Write the generated test queries (`--generate-tests`)
+pub fn write_test_queries(
+    w: &mut impl Write,
+    queries: &[crate::llm::test_queries::TestQuery],
+) -> io::Result<()> {
+    if queries.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(w, "  {}", t("results.test_queries").cyan().bold())?;
+    writeln!(w)?;
+    for (i, query) in queries.iter().enumerate() {
+        writeln!(
+            w,
+            "  {} {} {}",
+            format!("{:>2}.", i + 1).bright_black(),
+            query.query.white(),
+            format!("({})", query.scenario.replace('_', " ")).bright_black()
+        )?;
+    }
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Write everything after the analysis: stats, generated test queries, and
+/// the side-by-side diff when `model.show_diff` is set
+pub fn write_results(w: &mut impl Write, model: &Model) -> io::Result<()> {
+    render_stats(w, model)?;
+    if let Some(ref stats) = model.stats {
+        write_test_queries(w, &stats.test_queries)?;
+    }
+    if let (true, Some(optimized)) = (model.show_diff, model.optimized_prompt.as_deref()) {
+        super::diff::write_diff(w, &model.original_prompt, optimized)?;
+    }
+    Ok(())
+}
+
+/// Write the optimized prompt, word-wrapped and indented
+pub fn write_optimized_prompt(w: &mut impl Write, prompt: &str) -> io::Result<()> {
+    writeln!(w, "  {}", "─".repeat(70).bright_black())?;
+    writeln!(
+        w,
+        "  {}  {}",
+        icons().sparkles.cyan(),
+        "Optimized Prompt".white().bold()
+    )?;
+    writeln!(w, "  {}", "─".repeat(70).bright_black())?;
+    writeln!(w)?;
+
+    for row in wrap_prompt(prompt, PROMPT_WIDTH) {
+        if row.is_empty() {
+            writeln!(w)?;
+        } else {
+            writeln!(w, "  {}", row)?;
+        }
+    }
+    writeln!(w)?;
+
+    Ok(())
+}
+
+/// Soft-wrap `prompt` for display without disturbing its structure
+///
+/// Every line stays a line. Fenced code and table rows are left as they
+/// are, however long; list items and block quotes wrap under their own
+/// text, and indentation carries over to the wrapped rows.
+fn wrap_prompt(prompt: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in prompt.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            rows.push(line.to_string());
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            rows.push(line.to_string());
+            continue;
+        }
+        if trimmed.starts_with('|') || console::measure_text_width(line) <= width {
+            rows.push(line.to_string());
+            continue;
+        }
+
+        // "  - item" wraps as "  - " + text, continuing under the text
+        let indent = &line[..line.len() - trimmed.len()];
+        let marker_len = line_marker(trimmed).len();
+        let (marker, text) = trimmed.split_at(marker_len);
+        let continuation = if marker.starts_with('>') {
+            format!("{}{}", indent, marker)
+        } else {
+            format!("{}{}", indent, " ".repeat(marker_len))
+        };
+        let first = format!("{}{}", indent, marker);
+        let options = textwrap::Options::new(width)
+            .initial_indent(&first)
+            .subsequent_indent(&continuation)
+            .wrap_algorithm(textwrap::WrapAlgorithm::FirstFit);
+        rows.extend(
+            textwrap::wrap(text, options)
+                .into_iter()
+                .map(|row| row.into_owned()),
+        );
+    }
+    rows
+}
+
+/// The list bullet, number, or quote marker that starts `text`, with the
+/// spaces after it; empty for plain text
+fn line_marker(text: &str) -> &str {
+    let end = if let Some(rest) = text.strip_prefix(['-', '*', '+', '>']) {
+        if !(rest.starts_with(' ') || text.starts_with('>')) {
+            return "";
+        }
+        1
+    } else {
+        let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+        match text[digits..].chars().next() {
+            Some('.') | Some(')') if digits > 0 && text[digits + 1..].starts_with(' ') => {
+                digits + 1
+            }
+            _ => return "",
+        }
+    };
+    let spaces = text[end..].len() - text[end..].trim_start().len();
+    &text[..end + spaces]
+}
+
+/// Write the banner for a saved optimized prompt
+pub fn write_saved(w: &mut impl Write, path: &str) -> io::Result<()> {
+    writeln!(w)?;
+    writeln!(w, "  {}", "─".repeat(70).bright_black())?;
+    writeln!(
+        w,
+        "  {}  {} {}",
+        icons().check.green(),
+        "Saved to:".green(),
+        hyperlink::file(path).white().bold()
+    )?;
+    writeln!(w, "  {}", "─".repeat(70).bright_black())?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Spinner with elapsed time shown while the LLM call runs
+pub fn start_spinner(model_name: &str) -> indicatif::ProgressBar {
+    use indicatif::{ProgressBar, ProgressStyle};
+
+    let model_short = if model_name.chars().count() > 40 {
+        format!("{}...", model_name.chars().take(37).collect::<String>())
+    } else {
+        model_name.to_string()
+    };
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("  {spinner:.cyan} {msg} [{elapsed_precise}]")
+            .unwrap()
+            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏", "✓"]),
+    );
+    spinner.set_message(format!("Optimizing with {}...", model_short));
+    spinner.enable_steady_tick(std::time::Duration::from_millis(80));
+
+    spinner
+}
+
+/// Stop the spinner from [`start_spinner`] with success
+pub fn finish_spinner(spinner: indicatif::ProgressBar) {
+    spinner.finish_with_message("Optimization complete".to_string());
 }
 
 /// ASCII art logo for COPT
@@ -94,7 +281,7 @@ fn render_input_info(w: &mut impl Write, model: &Model) -> io::Result<()> {
         w,
         "  {}  {} {} ({} chars, {} tokens)",
         icons.inbox.cyan(),
-        "Input:".white().bold(),
+        t("input").white().bold(),
         source.white(),
         char_count.to_string().cyan(),
         token_count.to_string().cyan()
@@ -114,128 +301,277 @@ fn render_analysis(w: &mut impl Write, model: &Model) -> io::Result<()> {
         w,
         "  {}  {}",
         icons.chart.cyan(),
-        "Analysis Results".white().bold()
+        t("analysis.title").white().bold()
     )?;
     writeln!(w, "  {}", "─".repeat(70).bright_black())?;
     writeln!(w)?;
 
-    if model.issue_tree.categories.is_empty() {
+    let sections = crate::analyzer::segment::segment_prompt(&model.original_prompt);
+    if !sections.is_empty() {
+        writeln!(
+            w,
+            "  {} {}",
+            t("analysis.structure"),
+            crate::analyzer::segment::structure_summary(&sections).cyan()
+        )?;
+        writeln!(w)?;
+    }
+
+    let report = &model.issue_tree.report;
+    if report.is_empty() {
         writeln!(
             w,
             "  {}  {}",
             icons.check.green(),
-            "No issues detected - your prompt looks good!".green()
+            t("analysis.no_issues").green()
         )?;
         writeln!(w)?;
-        return Ok(());
+        if let Some(ref strengths) = model.strengths {
+            write_strengths(w, strengths)?;
+        }
+        return write_coverage(w, &model.coverage);
     }
 
-    // Count by severity
-    let total_issues: usize = model
-        .issue_tree
-        .categories
-        .iter()
-        .map(|c| c.issues.len())
-        .sum();
-
-    let errors: usize = model
-        .issue_tree
-        .categories
-        .iter()
-        .flat_map(|c| &c.issues)
-        .filter(|i| matches!(i.severity, Severity::Error))
-        .count();
-
-    let warnings: usize = model
-        .issue_tree
-        .categories
-        .iter()
-        .flat_map(|c| &c.issues)
-        .filter(|i| matches!(i.severity, Severity::Warning))
-        .count();
-
-    let infos = total_issues - errors - warnings;
-
     // Summary line
     let mut summary_parts = vec![];
-    if errors > 0 {
+    if report.errors > 0 {
         summary_parts.push(
-            format!("{} error{}", errors, if errors == 1 { "" } else { "s" })
-                .red()
-                .to_string(),
+            format!(
+                "{} error{}",
+                report.errors,
+                if report.errors == 1 { "" } else { "s" }
+            )
+            .color(severity::color(Severity::Error))
+            .to_string(),
         );
     }
-    if warnings > 0 {
+    if report.warnings > 0 {
         summary_parts.push(
             format!(
                 "{} warning{}",
-                warnings,
-                if warnings == 1 { "" } else { "s" }
+                report.warnings,
+                if report.warnings == 1 { "" } else { "s" }
             )
-            .yellow()
+            .color(severity::color(Severity::Warning))
             .to_string(),
         );
     }
-    if infos > 0 {
-        summary_parts.push(format!("{} info", infos).blue().to_string());
+    if report.infos > 0 {
+        summary_parts
+            .push(severity::paint(Severity::Info, &format!("{} info", report.infos)).to_string());
     }
 
     writeln!(
         w,
         "  Found {} across {} {}",
         summary_parts.join(", "),
-        model.issue_tree.categories.len(),
-        if model.issue_tree.categories.len() == 1 {
+        report.categories.len(),
+        if report.categories.len() == 1 {
             "category"
         } else {
             "categories"
         }
     )?;
+
+    let all_issues: Vec<_> = report.issues().cloned().collect();
+    writeln!(
+        w,
+        "  Quality score: {}",
+        format_score(crate::analyzer::quality_score(&all_issues))
+    )?;
     writeln!(w)?;
 
-    // Print each category
-    for cat in &model.issue_tree.categories {
-        writeln!(
-            w,
-            "  {}  {} ({} issue{})",
-            icons.bullet.cyan(),
-            cat.display_name.white().bold(),
-            cat.issues.len(),
-            if cat.issues.len() == 1 { "" } else { "s" }
-        )?;
+    // Print each heading (a category by default), one line per rule
+    let line_headings = model.group_by == GroupBy::Line;
+    for section in report.sections(model.group_by, model.sort_by) {
+        if let Some(ref title) = section.title {
+            writeln!(
+                w,
+                "  {}  {} ({} issue{})",
+                icons.bullet.cyan(),
+                title.white().bold(),
+                section.count,
+                if section.count == 1 { "" } else { "s" }
+            )?;
+        }
 
-        for issue in &cat.issues {
-            let severity_icon = match issue.severity {
-                Severity::Error => icons.cross.red().to_string(),
-                Severity::Warning => icons.warning.yellow().to_string(),
-                Severity::Info => icons.info.blue().to_string(),
-            };
+        for rule in &section.rules {
+            let severity_icon =
+                severity::paint(rule.severity, &severity::icon(rule.severity, icons)).to_string();
 
-            let line_info = issue.line.map(|l| format!(" (L{})", l)).unwrap_or_default();
+            // The heading already says which line
+            let location = if line_headings {
+                String::new()
+            } else {
+                rule.location()
+            };
+            let location = if location.is_empty() {
+                location
+            } else {
+                format!(" ({})", location)
+            };
 
-            // Truncate message
-            let max_msg_len = 50;
-            let msg = if issue.message.len() > max_msg_len {
-                format!("{}...", &issue.message[..max_msg_len - 3])
+            let msg = rule.truncated_message(50);
+            let msg = if rule.is_low_confidence() {
+                msg.dimmed().to_string()
             } else {
-                issue.message.clone()
+                msg
             };
 
             writeln!(
                 w,
                 "     {} {} {}{}",
                 severity_icon,
-                issue.id.bright_black(),
+                hyperlink::rule(&rule.rule_id).bright_black(),
                 msg,
-                line_info.bright_black()
+                location.bright_black()
             )?;
+
+            if model.verbose && rule.findings.is_empty() {
+                write_issue_details(w, &rule.occurrences[0])?;
+            }
+            for finding in &rule.findings {
+                let location = finding.location();
+                let location = if location.is_empty() {
+                    location
+                } else {
+                    format!(" ({})", location)
+                };
+                writeln!(
+                    w,
+                    "         {} {}{}",
+                    hyperlink::rule(&finding.rule_id).bright_black(),
+                    finding.truncated_message(46).bright_black(),
+                    location.bright_black()
+                )?;
+                if model.verbose {
+                    write_issue_details(w, &finding.occurrences[0])?;
+                }
+            }
         }
         writeln!(w)?;
     }
 
+    write_coverage(w, &model.coverage)
+}
+
+/// One line per rule category: checked (passed or flagged) or skipped and why
+pub fn write_coverage(w: &mut impl Write, coverage: &[CategoryCoverage]) -> io::Result<()> {
+    if coverage.is_empty() {
+        return Ok(());
+    }
+    let icons = icons();
+    let checked = coverage.iter().filter(|c| c.status.evaluated()).count();
+    writeln!(
+        w,
+        "  Coverage: {} of {} categories checked",
+        checked,
+        coverage.len()
+    )?;
+    for category in coverage {
+        let name = console::pad_str(
+            &category.display_name,
+            LABEL_WIDTH,
+            console::Alignment::Left,
+            None,
+        );
+        let (icon, status) = match category.status {
+            CoverageStatus::Passed => (icons.check.green(), "passed".green()),
+            CoverageStatus::Flagged => (
+                icons.warning.yellow(),
+                format!("flagged ({})", category.issues).yellow(),
+            ),
+            status => ("-".bright_black(), status.label().bright_black()),
+        };
+        write!(w, "     {} {} {}", icon, name, status)?;
+        if !category.disabled_rules.is_empty() {
+            write!(
+                w,
+                "{}",
+                format!(" ({} disabled)", category.disabled_rules.join(", ")).bright_black()
+            )?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w)
+}
+
+/// The practices a clean prompt follows, and how much went unchecked
+fn write_strengths(w: &mut impl Write, report: &StrengthReport) -> io::Result<()> {
+    let icons = icons();
+    if !report.strengths.is_empty() {
+        writeln!(w, "  {}", t("analysis.strengths").white().bold())?;
+        for strength in &report.strengths {
+            let name = console::pad_str(
+                &strength.display_name,
+                LABEL_WIDTH,
+                console::Alignment::Left,
+                None,
+            );
+            writeln!(
+                w,
+                "     {} {} {}",
+                icons.check.green(),
+                name,
+                strength.practice.bright_black()
+            )?;
+        }
+    }
+    if report.not_checked > 0 {
+        writeln!(
+            w,
+            "  {}",
+            format!(
+                "{} categor{} didn't apply to this prompt; --coverage lists why",
+                report.not_checked,
+                if report.not_checked == 1 { "y" } else { "ies" }
+            )
+            .bright_black()
+        )?;
+    }
+    writeln!(w)
+}
+
+/// Suggestion and before/after example under an issue (verbose output)
+fn write_issue_details(w: &mut impl Write, issue: &Issue) -> io::Result<()> {
+    const INDENT: &str = "          ";
+
+    if let Some(ref suggestion) = issue.suggestion {
+        writeln!(w, "{}{}", INDENT, suggestion.bright_black())?;
+    }
+    if let Some(example) = crate::analyzer::examples::rule_example(&issue.id) {
+        for (label, text, color) in [
+            ("Before:", example.before, Color::Red),
+            ("After: ", example.after, Color::Green),
+        ] {
+            for (i, line) in text.lines().enumerate() {
+                let label = if i == 0 { label } else { "       " };
+                writeln!(
+                    w,
+                    "{}{} {}",
+                    INDENT,
+                    label.bright_black(),
+                    line.color(color)
+                )?;
+            }
+        }
+    }
+
     Ok(())
 }
 
+/// Color a 0-100 quality score by band
+fn format_score(score: u8) -> String {
+    let text = format!("{}/100", score);
+    if score >= 80 {
+        text.green().bold().to_string()
+    } else if score >= 50 {
+        text.yellow().bold().to_string()
+    } else {
+        text.red().bold().to_string()
+    }
+}
+
 /// Render optimization statistics
 fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
     let icons = icons();
@@ -250,13 +586,13 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
         w,
         "  {}  {}",
         icons.chart.cyan(),
-        "Optimization Results".white().bold()
+        t("results.title").white().bold()
     )?;
     writeln!(w, "  {}", "─".repeat(70).bright_black())?;
     writeln!(w)?;
 
     // Token Analysis
-    writeln!(w, "  {}", "TOKEN ANALYSIS".cyan().bold())?;
+    writeln!(w, "  {}", t("results.tokens").cyan().bold())?;
     writeln!(w)?;
 
     let max_tokens = stats.original_tokens.max(stats.optimized_tokens).max(1);
@@ -278,15 +614,15 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
 
     writeln!(
         w,
-        "  {:<18} {} {}",
-        "Original:".bright_black(),
+        "  {} {} {}",
+        label(t("results.original")).bright_black(),
         orig_bar.bright_black(),
         stats.original_tokens.to_string().white()
     )?;
     writeln!(
         w,
-        "  {:<18} {} {}",
-        "Optimized:".bright_black(),
+        "  {} {} {}",
+        label(t("results.optimized")).bright_black(),
         opt_bar.green(),
         stats.optimized_tokens.to_string().white().bold()
     )?;
@@ -304,11 +640,16 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
     } else {
         "N/A".to_string()
     };
-    writeln!(w, "  {:<18} {}", "Change:".bright_black(), token_change)?;
+    writeln!(
+        w,
+        "  {} {}",
+        label(t("results.change")).bright_black(),
+        token_change
+    )?;
     writeln!(w)?;
 
     // Performance
-    writeln!(w, "  {}", "PERFORMANCE".cyan().bold())?;
+    writeln!(w, "  {}", t("results.performance").cyan().bold())?;
     writeln!(w)?;
 
     let time_display = if stats.processing_time_ms < 1000 {
@@ -319,20 +660,118 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
 
     writeln!(
         w,
-        "  {:<18} {}",
-        "Processing time:".bright_black(),
+        "  {} {}",
+        label(t("results.processing_time")).bright_black(),
         time_display.green()
     )?;
+    if stats.timed_out {
+        writeln!(
+            w,
+            "  {} {}",
+            label(t("results.llm_call")).bright_black(),
+            t("results.timed_out").yellow()
+        )?;
+    } else if let Some(ref failure) = stats.degraded {
+        writeln!(
+            w,
+            "  {} {} {}",
+            label(t("results.llm_call")).bright_black(),
+            t("results.llm_failed").yellow(),
+            format!("({})", failure.code).bright_black()
+        )?;
+    }
+    writeln!(
+        w,
+        "  {} {}",
+        label(t("results.issues_detected")).bright_black(),
+        stats.issues_detected.to_string().white()
+    )?;
     writeln!(
         w,
-        "  {:<18} {}",
-        "Rules applied:".bright_black(),
-        stats.rules_applied.to_string().white()
+        "  {} {} {}",
+        label(t("results.rules_fixed")).bright_black(),
+        stats.transforms.rules_fixed().to_string().white(),
+        format!(
+            "({} {})",
+            stats.transforms.total_replacements(),
+            t("results.edits")
+        )
+        .bright_black()
     )?;
     writeln!(w)?;
 
+    // Quality
+    writeln!(w, "  {}", t("results.quality").cyan().bold())?;
+    writeln!(w)?;
+
+    let score_delta = stats.optimized_score as i32 - stats.original_score as i32;
+    writeln!(
+        w,
+        "  {} {} → {} {}",
+        label(t("results.heuristic_score")).bright_black(),
+        stats.original_score.to_string().white(),
+        format_score(stats.optimized_score),
+        format!("({:+})", score_delta).bright_black()
+    )?;
+
+    if let Some(ref judge) = stats.judge {
+        for ((name, before), (_, after)) in judge
+            .original
+            .criteria()
+            .iter()
+            .zip(judge.optimized.criteria().iter())
+        {
+            writeln!(
+                w,
+                "  {} {:>2} → {}",
+                label(&format!("{}:", name)).bright_black(),
+                before.to_string().white(),
+                format!("{:>2}/10", after).green().bold()
+            )?;
+        }
+        writeln!(
+            w,
+            "  {} {}",
+            label(t("results.judge")).bright_black(),
+            judge.model.bright_black()
+        )?;
+    }
+    writeln!(w)?;
+
+    // What changed, in words
+    if !stats.changelog.is_empty() {
+        writeln!(w, "  {}", t("results.changes").cyan().bold())?;
+        writeln!(w)?;
+        for entry in &stats.changelog {
+            writeln!(w, "  {} {}", icons.bullet.cyan(), entry)?;
+        }
+        writeln!(w)?;
+    }
+
+    // Static fixes (always the whole story offline)
+    if !stats.transforms.is_empty() {
+        writeln!(w, "  {}", t("results.static_fixes").cyan().bold())?;
+        writeln!(w)?;
+        for transform in &stats.transforms.applied {
+            writeln!(
+                w,
+                "  {} {} {} {}",
+                icons.check.green(),
+                transform.rule_id.bright_black(),
+                crate::optimizer::transform_description(&transform.rule_id).unwrap_or_default(),
+                format!("(×{})", transform.replacements).bright_black()
+            )?;
+        }
+        writeln!(w)?;
+    }
+
+    // No provider involved in offline runs
+    if model.offline_mode {
+        return Ok(());
+    }
+
     // Provider
-    writeln!(w, "  {}", "PROVIDER".cyan().bold())?;
+    writeln!(w, "  {}", t("results.provider").cyan().bold())?;
     writeln!(w)?;
 
     let provider = {
@@ -345,8 +784,8 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
 
     writeln!(
         w,
-        "  {:<18} {}",
-        "Service:".bright_black(),
+        "  {} {}",
+        label(t("results.service")).bright_black(),
         provider.white().bold()
     )?;
 
@@ -357,8 +796,8 @@ fn render_stats(w: &mut impl Write, model: &Model) -> io::Result<()> {
     };
     writeln!(
         w,
-        "  {:<18} {}",
-        "Model:".bright_black(),
+        "  {} {}",
+        label(t("results.model")).bright_black(),
         model_display.bright_black()
     )?;
     writeln!(w)?;
@@ -387,4 +826,125 @@ mod tests {
         let output = String::from_utf8(buf).unwrap();
         assert!(output.contains("No issues"));
     }
+
+    #[test]
+    fn test_render_stats_offline_lists_static_fixes() {
+        let mut buf = Vec::new();
+        let model = Model {
+            offline_mode: true,
+            stats: Some(crate::OptimizationStats {
+                transforms: crate::optimizer::TransformReport {
+                    applied: vec![crate::optimizer::AppliedTransform {
+                        rule_id: "STY003".to_string(),
+                        replacements: 2,
+                    }],
+                },
+                changelog: vec!["Replaced \"think\" with consider/evaluate in 2 places".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        render_stats(&mut buf, &model).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("WHAT CHANGED"));
+        assert!(output.contains("with consider/evaluate in 2 places"));
+        assert!(output.contains("STATIC FIXES"));
+        assert!(output.contains("STY003"));
+        assert!(output.contains("(×2)"));
+        assert!(!output.contains("PROVIDER"));
+        assert!(!output.contains("timed out"));
+    }
+
+    #[test]
+    fn test_render_stats_timed_out() {
+        let mut buf = Vec::new();
+        let model = Model {
+            stats: Some(crate::OptimizationStats {
+                timed_out: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        render_stats(&mut buf, &model).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("timed out, static result shown"));
+    }
+
+    #[test]
+    fn test_render_stats_degraded() {
+        let mut buf = Vec::new();
+        let model = Model {
+            stats: Some(crate::OptimizationStats {
+                degraded: Some(crate::error::ErrorInfo {
+                    code: "provider_throttled",
+                    message: "Too many requests".to_string(),
+                    retryable: true,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        render_stats(&mut buf, &model).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("failed, static result shown"));
+        assert!(output.contains("provider_throttled"));
+    }
+
+    #[test]
+    fn test_write_results_with_diff() {
+        let mut buf = Vec::new();
+        let model = Model {
+            show_diff: true,
+            original_prompt: "Can you fix the bug?".to_string(),
+            optimized_prompt: Some("Fix the bug.".to_string()),
+            stats: Some(crate::OptimizationStats {
+                provider: "anthropic".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        write_results(&mut buf, &model).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Anthropic"));
+        assert!(output.contains("Changes"));
+        assert!(output.contains("Fix the bug."));
+    }
+
+    #[test]
+    fn test_write_optimized_prompt_wraps_long_lines() {
+        let mut buf = Vec::new();
+        let prompt = format!("{}\n\nDone.", "word ".repeat(30).trim_end());
+        write_optimized_prompt(&mut buf, &prompt).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Optimized Prompt"));
+        let body: Vec<&str> = output.lines().skip(4).collect();
+        assert_eq!(body[0], format!("  {}", "word ".repeat(14).trim_end()));
+        assert!(body.iter().all(|line| line.len() <= 74));
+        assert!(body.contains(&"  Done."));
+    }
+
+    #[test]
+    fn test_wrap_prompt_keeps_structure() {
+        let long = "word ".repeat(20);
+        let prompt = format!(
+            "## Steps\n\n1. {long}\n   - {long}\n> {long}\n\n```python\nx = \"{long}\"\n```\n| a | {long} |",
+        );
+        let rows = wrap_prompt(&prompt, 40);
+
+        assert_eq!(rows[0], "## Steps");
+        assert_eq!(rows[1], "");
+        assert!(rows[2].starts_with("1. word"));
+        assert!(rows[3].starts_with("   word"));
+        let nested = rows.iter().position(|r| r.starts_with("   - ")).unwrap();
+        assert!(rows[nested + 1].starts_with("     word"));
+        let quote = rows.iter().position(|r| r.starts_with("> ")).unwrap();
+        assert!(rows[quote + 1].starts_with("> word"));
+        // Code and tables are never re-wrapped
+        assert!(rows.contains(&format!("x = \"{long}\"")));
+        assert_eq!(rows.last().unwrap(), &format!("| a | {long} |"));
+        assert!(rows
+            .iter()
+            .filter(|r| !r.starts_with("x =") && !r.starts_with('|'))
+            .all(|r| r.trim_end().len() <= 40));
+    }
 }
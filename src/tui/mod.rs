@@ -18,58 +18,36 @@
 //! - **Plain**: Basic output for non-TTY (piped)
 //! - **Json/Quiet**: Handled by main.rs, not this module
 
-// New ratatui-based modules
+// ratatui-based modules (the `tui` feature) and the linear renderer
+#[cfg(feature = "tui")]
 pub mod app;
+pub mod diff;
+#[cfg(feature = "tui")]
+pub mod highlight;
+pub mod hyperlink;
 pub mod icons;
+#[cfg(feature = "tui")]
+pub mod keys;
 pub mod linear;
 pub mod model;
+pub mod pager;
+#[cfg(feature = "tui")]
+pub mod session;
+pub mod severity;
 pub mod terminal;
+#[cfg(feature = "tui")]
 pub mod theme;
+#[cfg(feature = "tui")]
 pub mod update;
+#[cfg(feature = "tui")]
 pub mod view;
+#[cfg(feature = "tui")]
 pub mod widgets;
 
 // Snapshot tests
-#[cfg(test)]
+#[cfg(all(test, feature = "tui"))]
 mod snapshot_tests;
 
-// Legacy icon constants for backward compatibility with old modules
-// The old modules (renderer.rs, stats.rs, diff.rs) use these
-#[allow(dead_code)]
-pub mod legacy_icons {
-    pub const CHECK: &str = "✓";
-    pub const CROSS: &str = "✗";
-    pub const WARNING: &str = "⚠";
-    pub const INFO: &str = "ℹ";
-    pub const LIGHTNING: &str = "⚡";
-    pub const INBOX: &str = "📥";
-    pub const CHART: &str = "📊";
-    pub const GEAR: &str = "⚙";
-    pub const SPARKLES: &str = "✨";
-    pub const FILE: &str = "📄";
-}
-
-// Note: main.rs imports directly from submodules (tui::model::*, tui::app::run_interactive)
-
-// ==============================================================
-// Legacy modules and functions for backward compatibility
-// These will be removed once main.rs is fully migrated
-// ==============================================================
-
-// Keep legacy modules that are still used by main.rs
-// - renderer: start_optimizing_spinner, stop_optimizing_spinner
-// - diff: print_diff
-// - stats: print_save_success
-pub mod diff;
-pub mod renderer;
-pub mod stats;
-
-// Note: Legacy modules are still used by main.rs for:
-// - tui::renderer::start_optimizing_spinner / stop_optimizing_spinner
-// - tui::renderer::print_optimized_prompt
-// - tui::diff::print_diff
-// - tui::stats::print_save_success
-
 /// Box-drawing characters for terminal UI
 #[allow(dead_code)]
 pub mod chars {
@@ -4,15 +4,21 @@
 
 #![allow(dead_code)]
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use crate::analyzer::coverage::CategoryCoverage;
+use crate::analyzer::report::{AnalysisReport, CategoryGroup, GroupBy, RuleGroup, SortBy};
+use crate::analyzer::strengths::StrengthReport;
 use crate::analyzer::Issue;
+#[cfg(feature = "tui")]
 use crate::tui::widgets::SuggestModalState;
 use crate::OptimizationStats;
 
 /// Current view being displayed
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum View {
     #[default]
     Main,
@@ -20,6 +26,13 @@ pub enum View {
     Help,
 }
 
+/// Prompt shown full screen in the diff view instead of the side-by-side diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Preview {
+    Original,
+    Optimized,
+}
+
 /// Render mode based on CLI flags and environment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum RenderMode {
@@ -37,7 +50,7 @@ pub enum RenderMode {
 }
 
 /// Application state for the analysis phase
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum AppPhase {
     /// Initial state, ready to analyze
     #[default]
@@ -54,35 +67,12 @@ pub enum AppPhase {
     Error,
 }
 
-/// A category node in the collapsible issue tree
-#[derive(Debug, Clone)]
-pub struct CategoryNode {
-    pub category: String,
-    pub display_name: String,
-    pub issues: Vec<Issue>,
-    pub expanded: bool,
-}
-
-impl CategoryNode {
-    pub fn new(category: String, issues: Vec<Issue>) -> Self {
-        let display_name = format_category_name(&category);
-        Self {
-            category,
-            display_name,
-            issues,
-            expanded: true, // Start expanded by default
-        }
-    }
-
-    pub fn issue_count(&self) -> usize {
-        self.issues.len()
-    }
-}
-
-/// The collapsible issue tree
+/// The collapsible issue tree: a row per category, then a row per rule
 #[derive(Debug, Clone, Default)]
 pub struct IssueTree {
-    pub categories: Vec<CategoryNode>,
+    pub report: AnalysisReport,
+    /// Whether each of `report.categories` is expanded
+    pub expanded: Vec<bool>,
     pub selected_index: usize,
     /// Tracks which index in the flattened view is selected
     pub flat_index: usize,
@@ -91,67 +81,89 @@ pub struct IssueTree {
 impl IssueTree {
     /// Create a new issue tree from a list of issues
     pub fn from_issues(issues: &[Issue]) -> Self {
-        use std::collections::HashMap;
-
-        // Group issues by category
-        let mut grouped: HashMap<String, Vec<Issue>> = HashMap::new();
-        for issue in issues {
-            grouped
-                .entry(issue.category.clone())
-                .or_default()
-                .push(issue.clone());
-        }
-
-        // Convert to CategoryNodes, sorted by category name
-        let mut categories: Vec<CategoryNode> = grouped
-            .into_iter()
-            .map(|(cat, issues)| CategoryNode::new(cat, issues))
-            .collect();
-        categories.sort_by(|a, b| a.category.cmp(&b.category));
-
+        let report = AnalysisReport::from_issues(issues);
         Self {
-            categories,
+            // Start expanded by default
+            expanded: vec![true; report.categories.len()],
+            report,
             selected_index: 0,
             flat_index: 0,
         }
     }
 
+    /// Categories with whether each is expanded
+    pub fn categories(&self) -> impl Iterator<Item = (&CategoryGroup, bool)> {
+        self.report
+            .categories
+            .iter()
+            .zip(self.expanded.iter().copied())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.report.is_empty()
+    }
+
     /// Get total number of items in the flattened view
     pub fn flat_len(&self) -> usize {
-        self.categories
-            .iter()
-            .map(|c| 1 + if c.expanded { c.issues.len() } else { 0 })
+        self.categories()
+            .map(|(c, expanded)| 1 + if expanded { c.rules.len() } else { 0 })
             .sum()
     }
 
-    /// Check if a category at given index is expanded
-    pub fn is_category_at(&self, flat_idx: usize) -> bool {
+    /// Index into `report.categories` of the category header at `flat_idx`
+    fn category_index_at(&self, flat_idx: usize) -> Option<usize> {
         let mut idx = 0;
-        for cat in &self.categories {
+        for (i, (cat, expanded)) in self.categories().enumerate() {
             if idx == flat_idx {
-                return true;
+                return Some(i);
             }
             idx += 1;
-            if cat.expanded {
-                idx += cat.issues.len();
+            if expanded {
+                idx += cat.rules.len();
             }
         }
-        false
+        None
+    }
+
+    /// Check if the row at given index is a category header
+    pub fn is_category_at(&self, flat_idx: usize) -> bool {
+        self.category_index_at(flat_idx).is_some()
+    }
+
+    /// Whether the selected category is expanded, if a category is selected
+    pub fn is_current_expanded(&self) -> Option<bool> {
+        self.category_index_at(self.flat_index)
+            .map(|i| self.expanded[i])
     }
 
     /// Toggle expansion of the category containing the current selection
     pub fn toggle_current(&mut self) {
+        if let Some(i) = self.category_index_at(self.flat_index) {
+            self.expanded[i] = !self.expanded[i];
+        }
+    }
+
+    /// The rule under the cursor, if the selection is not a category header
+    pub fn selected_rule(&self) -> Option<&RuleGroup> {
         let mut idx = 0;
-        for cat in &mut self.categories {
+        for (cat, expanded) in self.categories() {
             if idx == self.flat_index {
-                cat.expanded = !cat.expanded;
-                return;
+                return None;
             }
             idx += 1;
-            if cat.expanded {
-                idx += cat.issues.len();
+            if expanded {
+                if let Some(rule) = cat.rules.get(self.flat_index - idx) {
+                    return Some(rule);
+                }
+                idx += cat.rules.len();
             }
         }
+        None
+    }
+
+    /// First occurrence of the rule under the cursor
+    pub fn selected_issue(&self) -> Option<&Issue> {
+        self.selected_rule().and_then(|r| r.occurrences.first())
     }
 
     /// Move selection up
@@ -170,25 +182,20 @@ impl IssueTree {
 
     /// Collapse all categories
     pub fn collapse_all(&mut self) {
-        for cat in &mut self.categories {
-            cat.expanded = false;
-        }
+        self.expanded.fill(false);
         self.flat_index = 0;
     }
 
     /// Expand all categories
     pub fn expand_all(&mut self) {
-        for cat in &mut self.categories {
-            cat.expanded = true;
-        }
+        self.expanded.fill(true);
     }
 
     /// Get expanded categories (for display)
     pub fn expanded_categories(&self) -> HashSet<&str> {
-        self.categories
-            .iter()
-            .filter(|c| c.expanded)
-            .map(|c| c.category.as_str())
+        self.categories()
+            .filter(|(_, expanded)| *expanded)
+            .map(|(c, _)| c.category.as_str())
             .collect()
     }
 }
@@ -225,12 +232,22 @@ pub struct Model {
     pub phase: AppPhase,
     /// Whether offline mode is enabled
     pub offline_mode: bool,
+    /// Show suggestions and before/after examples under each issue (`--verbose`)
+    pub verbose: bool,
     /// Original prompt text
     pub original_prompt: String,
     /// Optimized prompt text (if available)
     pub optimized_prompt: Option<String>,
     /// Issue tree for analysis results
     pub issue_tree: IssueTree,
+    /// How the linear output organizes issues (`--group-by`)
+    pub group_by: GroupBy,
+    /// Order of the linear output's issues (`--sort`); report order when unset
+    pub sort_by: Option<SortBy>,
+    /// Per-category coverage, shown under the analysis when set (`--coverage`)
+    pub coverage: Vec<CategoryCoverage>,
+    /// What a clean prompt already does well, shown instead of an empty list
+    pub strengths: Option<StrengthReport>,
     /// Optimization statistics
     pub stats: Option<OptimizationStats>,
     /// Error state (if any)
@@ -241,13 +258,20 @@ pub struct Model {
     pub scroll_offset: u16,
     /// Whether to show the diff view
     pub show_diff: bool,
+    /// Full-screen prompt in the diff view, if any
+    pub preview: Option<Preview>,
     /// Should the app quit?
     pub should_quit: bool,
+    /// Saved optimized prompt to open in the editor before the next redraw
+    pub pending_edit: Option<PathBuf>,
+    /// Where `s` saves the optimized prompt (`--output-dir`)
+    pub output_dir: PathBuf,
     /// Terminal width (updated on resize)
     pub terminal_width: u16,
     /// Terminal height (updated on resize)
     pub terminal_height: u16,
     /// Suggest modal state for vague prompt improvements
+    #[cfg(feature = "tui")]
     pub suggest_modal: SuggestModalState,
     /// Temporary status message (e.g., "Copied to clipboard")
     pub status_message: Option<String>,
@@ -263,17 +287,26 @@ impl Model {
             current_view: View::default(),
             phase: AppPhase::default(),
             offline_mode: false,
+            verbose: false,
             original_prompt: String::new(),
             optimized_prompt: None,
             issue_tree: IssueTree::default(),
+            group_by: GroupBy::default(),
+            sort_by: None,
+            coverage: Vec::new(),
+            strengths: None,
             stats: None,
             error: None,
             input_file: None,
             scroll_offset: 0,
             show_diff: false,
+            preview: None,
             should_quit: false,
+            pending_edit: None,
+            output_dir: PathBuf::from("copt-output"),
             terminal_width: 80,
             terminal_height: 24,
+            #[cfg(feature = "tui")]
             suggest_modal: SuggestModalState::default(),
             status_message: None,
             status_clear_at: None,
@@ -286,6 +319,7 @@ impl Model {
         self.phase = AppPhase::AnalysisDone;
 
         // Initialize suggest modal if vague prompt detected
+        #[cfg(feature = "tui")]
         if SuggestModalState::should_show(issues) {
             self.suggest_modal = SuggestModalState::from_issues(issues);
         }
@@ -312,13 +346,64 @@ impl Model {
         // Restore previous phase based on state
         if self.optimized_prompt.is_some() {
             self.phase = AppPhase::Done;
-        } else if !self.issue_tree.categories.is_empty() {
+        } else if !self.issue_tree.is_empty() {
             self.phase = AppPhase::AnalysisDone;
         } else {
             self.phase = AppPhase::Ready;
         }
     }
 
+    /// Flip the diff view's full-screen preview: optimized first, then
+    /// original, and back
+    pub fn toggle_preview(&mut self) {
+        self.preview = match self.preview {
+            Some(Preview::Optimized) => Some(Preview::Original),
+            Some(Preview::Original) | None => Some(Preview::Optimized),
+        };
+        self.scroll_offset = 0;
+    }
+
+    /// Take in the optimized prompt as edited in the editor
+    ///
+    /// `issues` are the analysis of the edited text; the token counts and
+    /// score follow the edit, so the diff and dashboard show what will be
+    /// saved. Returns false when the edit changed nothing.
+    pub fn apply_edit(&mut self, edited: &str, issues: &[Issue]) -> bool {
+        let Some(ref optimized) = self.optimized_prompt else {
+            return false;
+        };
+        // Editors add a final newline on save
+        let edited = if optimized.ends_with('\n') {
+            edited
+        } else {
+            edited.trim_end_matches(['\r', '\n'])
+        };
+        if edited == optimized {
+            self.set_status_message("No changes in the editor", Duration::from_secs(3));
+            return false;
+        }
+
+        let score = crate::analyzer::quality_score(issues);
+        let mut message = format!("✓ Re-analyzed your edit: {} issue(s)", issues.len());
+        if let Some(ref mut stats) = self.stats {
+            message = format!(
+                "✓ Re-analyzed your edit: score {} → {}, {} issue(s)",
+                stats.optimized_score,
+                score,
+                issues.len()
+            );
+            stats.optimized_chars = edited.len();
+            stats.optimized_tokens = crate::utils::count_tokens(edited);
+            stats.optimized_score = score;
+        }
+        self.set_status_message(message, Duration::from_secs(5));
+        self.optimized_prompt = Some(edited.to_string());
+        self.preview = None;
+        self.scroll_offset = 0;
+        self.current_view = View::Diff;
+        true
+    }
+
     /// Check if we have optimization results
     pub fn has_results(&self) -> bool {
         self.optimized_prompt.is_some()
@@ -326,11 +411,7 @@ impl Model {
 
     /// Get total issue count
     pub fn total_issues(&self) -> usize {
-        self.issue_tree
-            .categories
-            .iter()
-            .map(|c| c.issues.len())
-            .sum()
+        self.issue_tree.report.total()
     }
 
     /// Set a temporary status message that auto-clears after duration
@@ -363,17 +444,7 @@ impl Model {
 
     /// Check if the currently selected category is expanded
     pub fn is_current_category_expanded(&self) -> Option<bool> {
-        let mut idx = 0;
-        for cat in &self.issue_tree.categories {
-            if idx == self.issue_tree.flat_index {
-                return Some(cat.expanded);
-            }
-            idx += 1;
-            if cat.expanded {
-                idx += cat.issues.len();
-            }
-        }
-        None
+        self.issue_tree.is_current_expanded()
     }
 }
 
@@ -383,21 +454,6 @@ impl Default for Model {
     }
 }
 
-/// Format category name for display
-fn format_category_name(category: &str) -> String {
-    match category.to_lowercase().as_str() {
-        "explicitness" => "Explicitness".to_string(),
-        "style" => "Style".to_string(),
-        "tools" => "Tool Usage".to_string(),
-        "formatting" => "Formatting".to_string(),
-        "verbosity" => "Verbosity".to_string(),
-        "agentic" => "Agentic Coding".to_string(),
-        "long_horizon" => "Long-Horizon".to_string(),
-        "frontend" => "Frontend Design".to_string(),
-        other => other.to_string(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,6 +467,7 @@ mod tests {
                 severity: Severity::Warning,
                 message: "Test issue 1".to_string(),
                 line: Some(1),
+                confidence: 1.0,
                 suggestion: Some("Fix it".to_string()),
             },
             Issue {
@@ -419,6 +476,7 @@ mod tests {
                 severity: Severity::Info,
                 message: "Test issue 2".to_string(),
                 line: Some(2),
+                confidence: 1.0,
                 suggestion: Some("Fix it too".to_string()),
             },
             Issue {
@@ -427,6 +485,7 @@ mod tests {
                 severity: Severity::Error,
                 message: "Style issue".to_string(),
                 line: None,
+                confidence: 1.0,
                 suggestion: Some("Restyle".to_string()),
             },
         ]
@@ -437,7 +496,7 @@ mod tests {
         let issues = create_test_issues();
         let tree = IssueTree::from_issues(&issues);
 
-        assert_eq!(tree.categories.len(), 2);
+        assert_eq!(tree.report.categories.len(), 2);
     }
 
     #[test]
@@ -452,17 +511,82 @@ mod tests {
         assert_eq!(tree.flat_index, 0);
     }
 
+    #[test]
+    fn test_selected_issue() {
+        let issues = create_test_issues();
+        let mut tree = IssueTree::from_issues(&issues);
+        assert!(tree.selected_issue().is_none());
+
+        tree.select_next();
+        assert_eq!(tree.selected_issue().map(|i| i.id.as_str()), Some("EXP001"));
+        tree.select_next();
+        tree.select_next();
+        assert!(tree.selected_issue().is_none());
+        tree.select_next();
+        assert_eq!(tree.selected_issue().map(|i| i.id.as_str()), Some("STY001"));
+    }
+
+    #[test]
+    fn test_issue_tree_has_a_row_per_rule() {
+        let mut issues = create_test_issues();
+        issues.push(Issue {
+            line: Some(5),
+            ..issues[0].clone()
+        });
+        let mut tree = IssueTree::from_issues(&issues);
+
+        assert_eq!(tree.flat_len(), 5);
+        tree.select_next();
+        let rule = tree.selected_rule().unwrap();
+        assert_eq!(rule.rule_id, "EXP001");
+        assert_eq!(rule.location(), "L1, L5");
+
+        tree.flat_index = 0;
+        tree.toggle_current();
+        assert_eq!(tree.flat_len(), 3);
+        assert_eq!(tree.is_current_expanded(), Some(false));
+    }
+
+    #[test]
+    fn test_apply_edit_updates_the_result() {
+        let mut model = Model::new();
+        model.set_optimization_result(
+            "Summarize the report.".to_string(),
+            OptimizationStats {
+                optimized_score: 80,
+                ..Default::default()
+            },
+        );
+        model.current_view = View::Main;
+
+        // Only the newline the editor added
+        assert!(!model.apply_edit("Summarize the report.\n", &[]));
+        assert_eq!(model.current_view, View::Main);
+
+        let issues = create_test_issues();
+        let edited = "Summarize the report in three bullet points.\n";
+        assert!(model.apply_edit(edited, &issues));
+        assert_eq!(
+            model.optimized_prompt.as_deref(),
+            Some("Summarize the report in three bullet points.")
+        );
+        let stats = model.stats.as_ref().unwrap();
+        assert_eq!(
+            stats.optimized_score,
+            crate::analyzer::quality_score(&issues)
+        );
+        assert_eq!(stats.optimized_chars, 44);
+        assert_eq!(model.current_view, View::Diff);
+        assert!(model
+            .status_message
+            .as_deref()
+            .is_some_and(|m| m.contains("score 80 →")));
+    }
+
     #[test]
     fn test_model_creation() {
         let model = Model::new();
         assert_eq!(model.phase, AppPhase::Ready);
         assert!(!model.should_quit);
     }
-
-    #[test]
-    fn test_format_category_name() {
-        assert_eq!(format_category_name("explicitness"), "Explicitness");
-        assert_eq!(format_category_name("long_horizon"), "Long-Horizon");
-        assert_eq!(format_category_name("unknown"), "unknown");
-    }
 }
@@ -0,0 +1,97 @@
+//! Pager for long pretty output
+//!
+//! Buffers the final report and pipes it through `$PAGER` (or `less -R`)
+//! when it would scroll past the terminal. Piped and CI output is printed
+//! unchanged.
+
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` is unset
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Options passed to `less` when `$LESS` is unset: quit if the output fits,
+/// keep ANSI colors, and leave the output on screen afterwards
+const DEFAULT_LESS_OPTIONS: &str = "FRX";
+
+/// Whether paging is possible in this environment
+pub fn can_page(enabled: bool) -> bool {
+    enabled && io::stdout().is_terminal() && std::env::var_os("CI").is_none()
+}
+
+/// Whether output of `line_count` lines overflows a terminal `height` rows tall
+fn exceeds_height(line_count: usize, height: u16) -> bool {
+    line_count >= height as usize
+}
+
+/// Resolve the pager command from `$PAGER`, falling back to `less -R`
+///
+/// Returns `None` when paging is disabled via an empty `$PAGER` or `cat`.
+fn pager_command() -> Option<Vec<String>> {
+    let configured = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let parts: Vec<String> = configured.split_whitespace().map(String::from).collect();
+
+    match parts.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(parts),
+    }
+}
+
+/// Print buffered output, through the pager if enabled and it overflows the terminal
+pub fn page(content: &[u8], enabled: bool) -> io::Result<()> {
+    if can_page(enabled) {
+        let line_count = content.iter().filter(|&&b| b == b'\n').count();
+        let height = console::Term::stdout()
+            .size_checked()
+            .map_or(u16::MAX, |(rows, _)| rows);
+
+        if exceeds_height(line_count, height) {
+            if let Some(command) = pager_command() {
+                if spawn_pager(&command, content).is_ok() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    let mut stdout = io::stdout();
+    stdout.write_all(content)?;
+    stdout.flush()
+}
+
+/// Run the pager with `content` on stdin and wait for the user to quit
+fn spawn_pager(command: &[String], content: &[u8]) -> io::Result<()> {
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        cmd.env("LESS", DEFAULT_LESS_OPTIONS);
+    }
+
+    let mut child = cmd.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit before reading everything
+        match stdin.write_all(content) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeds_height() {
+        assert!(!exceeds_height(10, 40));
+        assert!(exceeds_height(40, 40));
+        assert!(exceeds_height(120, 40));
+    }
+
+    #[test]
+    fn test_disabled_pager_never_pages() {
+        assert!(!can_page(false));
+    }
+}
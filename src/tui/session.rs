@@ -0,0 +1,248 @@
+//! Saved interactive session (`--resume`)
+//!
+//! While the interactive TUI runs, its state — the prompt, issues, optimized
+//! result and stats, the current view, tree selection, and scroll position —
+//! is written to `session.json` in the cache directory after every key
+//! press. If copt crashes or the TUI is closed by mistake, `copt --resume`
+//! reopens it exactly there, including an optimized prompt that was never
+//! saved. Each interactive run replaces the previous session; `--sandbox`
+//! writes none, and with `--redact` the stored text is redacted.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::model::{AppPhase, ErrorState, IssueTree, Model, Preview, RenderMode, View};
+use crate::analyzer::Issue;
+use crate::error::CoptError;
+use crate::OptimizationStats;
+
+/// Bumped when the file layout changes; older sessions can't be resumed
+const SESSION_VERSION: u32 = 1;
+
+/// Everything needed to rebuild the TUI [`Model`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// When the session was last written (RFC 3339)
+    pub saved_at: String,
+    pub original_prompt: String,
+    pub input_file: Option<String>,
+    pub offline_mode: bool,
+    pub verbose: bool,
+    pub issues: Vec<Issue>,
+    pub optimized_prompt: Option<String>,
+    pub stats: Option<OptimizationStats>,
+    pub error: Option<SessionError>,
+    pub phase: AppPhase,
+    pub view: View,
+    /// Expanded state of each issue category, in tree order
+    pub expanded: Vec<bool>,
+    pub flat_index: usize,
+    pub scroll_offset: u16,
+    pub show_diff: bool,
+    #[serde(default)]
+    pub preview: Option<Preview>,
+    pub suggest_visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionError {
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl Session {
+    /// Snapshot of `model`, redacted when `--redact` is in effect
+    pub fn capture(model: &Model) -> Self {
+        let redact = |text: &str| crate::utils::redact::apply(text).into_owned();
+        let issues: Vec<Issue> = model.issue_tree.report.issues().cloned().collect();
+        Self {
+            version: SESSION_VERSION,
+            saved_at: chrono::Local::now().to_rfc3339(),
+            original_prompt: redact(&model.original_prompt),
+            input_file: model.input_file.clone(),
+            offline_mode: model.offline_mode,
+            verbose: model.verbose,
+            issues: crate::utils::redact::issues(&issues),
+            optimized_prompt: model.optimized_prompt.as_deref().map(redact),
+            stats: model.stats.as_ref().map(|stats| stats.redacted()),
+            error: model.error.as_ref().map(|e| SessionError {
+                message: e.message.clone(),
+                details: e.details.clone(),
+            }),
+            phase: model.phase,
+            view: model.current_view,
+            expanded: model.issue_tree.expanded.clone(),
+            flat_index: model.issue_tree.flat_index,
+            scroll_offset: model.scroll_offset,
+            show_diff: model.show_diff,
+            preview: model.preview,
+            suggest_visible: model.suggest_modal.visible,
+        }
+    }
+
+    /// The interactive model as it was when the session was saved
+    pub fn restore(self) -> Model {
+        let mut model = Model::new();
+        model.render_mode = RenderMode::Interactive;
+        model.offline_mode = self.offline_mode;
+        model.verbose = self.verbose;
+        model.original_prompt = self.original_prompt;
+        model.input_file = self.input_file;
+        model.set_issues(&self.issues);
+        if !self.suggest_visible {
+            model.suggest_modal.dismiss();
+        }
+
+        let mut tree = IssueTree::from_issues(&self.issues);
+        if tree.expanded.len() == self.expanded.len() {
+            tree.expanded = self.expanded;
+        }
+        tree.flat_index = self.flat_index.min(tree.flat_len().saturating_sub(1));
+        model.issue_tree = tree;
+
+        model.optimized_prompt = self.optimized_prompt;
+        model.stats = self.stats;
+        model.error = self.error.map(|e| ErrorState {
+            message: e.message,
+            details: e.details,
+        });
+        model.phase = self.phase;
+        model.current_view = self.view;
+        model.scroll_offset = self.scroll_offset;
+        model.show_diff = self.show_diff;
+        model.preview = self.preview;
+        model
+    }
+}
+
+/// `session.json` in the cache directory
+pub fn default_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "copt").map(|d| d.cache_dir().join("session.json"))
+}
+
+/// Save `model` as the session to resume; failures are logged, not fatal
+pub fn save(model: &Model) {
+    if crate::utils::sandbox::enabled() {
+        return;
+    }
+    let Some(path) = default_path() else {
+        return;
+    };
+    if let Err(e) = save_to(&path, model) {
+        tracing::debug!(error = %format!("{:#}", e), "session not saved");
+    }
+}
+
+fn save_to(path: &Path, model: &Model) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    let json = serde_json::to_string(&Session::capture(model))?;
+    crate::utils::file::write_atomic(path, &json)
+}
+
+/// The last saved session
+pub fn load() -> Result<Session> {
+    let path = default_path().context("No cache directory for the saved session")?;
+    load_from(&path)
+}
+
+fn load_from(path: &Path) -> Result<Session> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(CoptError::Config(format!(
+                "No interactive session to resume ({} doesn't exist). \
+                Sessions are saved while `copt --interactive` runs.",
+                path.display()
+            ))
+            .into());
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read session: {}", path.display()))
+        }
+    };
+    let session: Session = serde_json::from_str(&content)
+        .map_err(|e| CoptError::Config(format!("Invalid session {}: {}", path.display(), e)))?;
+    if session.version != SESSION_VERSION {
+        return Err(CoptError::Config(format!(
+            "The session in {} was saved by another version of copt and can't be resumed",
+            path.display()
+        ))
+        .into());
+    }
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Severity;
+
+    fn issue(id: &str, category: &str) -> Issue {
+        Issue {
+            id: id.to_string(),
+            category: category.to_string(),
+            severity: Severity::Warning,
+            message: format!("{} message", id),
+            line: Some(1),
+            confidence: 1.0,
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("session.json");
+
+        let mut model = Model::new();
+        model.original_prompt = "Can you fix the bug?".to_string();
+        model.set_issues(&[issue("EXP003", "explicitness"), issue("STY001", "style")]);
+        model.issue_tree.toggle_current();
+        model.issue_tree.select_next();
+        model.set_optimization_result(
+            "Fix the bug.".to_string(),
+            OptimizationStats {
+                degraded: Some(crate::error::ErrorInfo {
+                    code: "provider_throttled",
+                    message: "Too many requests".to_string(),
+                    retryable: true,
+                }),
+                ..Default::default()
+            },
+        );
+        model.scroll_offset = 3;
+        save_to(&path, &model).unwrap();
+
+        let restored = load_from(&path).unwrap().restore();
+        assert_eq!(restored.render_mode, RenderMode::Interactive);
+        assert_eq!(restored.original_prompt, "Can you fix the bug?");
+        assert_eq!(restored.optimized_prompt.as_deref(), Some("Fix the bug."));
+        assert_eq!(restored.current_view, View::Diff);
+        assert_eq!(restored.phase, AppPhase::Done);
+        assert_eq!(restored.scroll_offset, 3);
+        assert_eq!(restored.issue_tree.expanded, [false, true]);
+        assert_eq!(restored.issue_tree.flat_index, 1);
+        assert_eq!(
+            restored.stats.unwrap().degraded.unwrap().code,
+            "provider_throttled"
+        );
+    }
+
+    #[test]
+    fn test_missing_or_stale_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let err = load_from(&path).unwrap_err();
+        assert!(err.to_string().contains("No interactive session"));
+
+        let mut session = Session::capture(&Model::new());
+        session.version = SESSION_VERSION + 1;
+        std::fs::write(&path, serde_json::to_string(&session).unwrap()).unwrap();
+        assert!(load_from(&path).is_err());
+    }
+}
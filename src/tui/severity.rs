@@ -0,0 +1,204 @@
+//! Severity colors and icons
+//!
+//! Errors are red, warnings yellow, and info blue by default, which a
+//! red-green colorblind reader can't tell apart at a glance. `[severity]` in
+//! the config overrides the color and icon of each severity, and every
+//! renderer (linear output, `lint`/`audit`/`discover` reports, the TUI
+//! theme) asks this module instead of hard-coding its own.
+//!
+//! ```toml
+//! [severity.error]
+//! color = "magenta"
+//! icon = "✖"
+//!
+//! [severity.warning]
+//! color = "blue"
+//! ```
+
+use colored::{Color, ColoredString, Colorize};
+use std::sync::OnceLock;
+
+use super::icons::IconSet;
+use crate::analyzer::Severity;
+use crate::cli::config::{SeverityConfig, SeverityStyleConfig};
+use crate::error::CoptError;
+
+/// How one severity is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeverityStyle {
+    pub color: Color,
+    /// Replaces the icon set's icon when set
+    pub icon: Option<String>,
+}
+
+/// Styles for every severity
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeverityStyles {
+    pub error: SeverityStyle,
+    pub warning: SeverityStyle,
+    pub info: SeverityStyle,
+}
+
+impl Default for SeverityStyles {
+    fn default() -> Self {
+        let style = |color| SeverityStyle { color, icon: None };
+        Self {
+            error: style(Color::Red),
+            warning: style(Color::Yellow),
+            info: style(Color::Blue),
+        }
+    }
+}
+
+impl SeverityStyles {
+    /// The defaults with `[severity]` applied
+    pub fn from_config(config: &SeverityConfig) -> Result<Self, CoptError> {
+        let mut styles = Self::default();
+        let sections = [
+            ("error", &config.error, &mut styles.error),
+            ("warning", &config.warning, &mut styles.warning),
+            ("info", &config.info, &mut styles.info),
+        ];
+        for (name, section, style) in sections {
+            apply(name, section, style)?;
+        }
+        Ok(styles)
+    }
+
+    pub fn get(&self, severity: Severity) -> &SeverityStyle {
+        match severity {
+            Severity::Error => &self.error,
+            Severity::Warning => &self.warning,
+            Severity::Info => &self.info,
+        }
+    }
+}
+
+fn apply(
+    name: &str,
+    section: &SeverityStyleConfig,
+    style: &mut SeverityStyle,
+) -> Result<(), CoptError> {
+    if let Some(ref color) = section.color {
+        style.color = parse_color(color).map_err(|e| {
+            CoptError::Config(format!("Invalid color in [severity.{}]: {}", name, e))
+        })?;
+    }
+    if let Some(ref icon) = section.icon {
+        if icon.trim().is_empty() {
+            return Err(CoptError::Config(format!(
+                "Empty icon in [severity.{}]; remove the key to use the built-in icon",
+                name
+            )));
+        }
+        style.icon = Some(icon.clone());
+    }
+    Ok(())
+}
+
+/// A color name ("magenta", "bright blue", "bright_blue") or hex ("#d75fd7")
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    let trimmed = value.trim();
+    if let Some(hex) = trimmed.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::TrueColor { r, g, b }),
+            _ => Err(format!("'{}' is not a #rrggbb color", value)),
+        };
+    }
+    trimmed.replace(['_', '-'], " ").parse().map_err(|_| {
+        format!(
+            "'{}' is not a color: black, red, green, yellow, blue, magenta, cyan, white, \
+                their \"bright\" variants, or #rrggbb",
+            value
+        )
+    })
+}
+
+static STYLES: OnceLock<SeverityStyles> = OnceLock::new();
+
+/// Use the `[severity]` colors and icons for the rest of the process
+///
+/// Only the first call takes effect.
+pub fn configure(config: &SeverityConfig) -> Result<(), CoptError> {
+    let styles = SeverityStyles::from_config(config)?;
+    let _ = STYLES.set(styles);
+    Ok(())
+}
+
+/// The effective severity styles
+pub fn styles() -> &'static SeverityStyles {
+    STYLES.get_or_init(SeverityStyles::default)
+}
+
+/// The color for `severity`
+pub fn color(severity: Severity) -> Color {
+    styles().get(severity).color
+}
+
+/// `text` in the color for `severity`
+pub fn paint(severity: Severity, text: &str) -> ColoredString {
+    text.color(color(severity))
+}
+
+/// The icon for `severity`: the configured one, or the icon set's
+pub fn icon(severity: Severity, icons: &IconSet) -> String {
+    match styles().get(severity).icon {
+        Some(ref icon) => icon.clone(),
+        None => match severity {
+            Severity::Error => icons.cross,
+            Severity::Warning => icons.warning,
+            Severity::Info => icons.info,
+        }
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("Magenta"), Ok(Color::Magenta));
+        assert_eq!(parse_color("bright_blue"), Ok(Color::BrightBlue));
+        assert_eq!(
+            parse_color("#d75fd7"),
+            Ok(Color::TrueColor {
+                r: 0xd7,
+                g: 0x5f,
+                b: 0xd7
+            })
+        );
+        assert!(parse_color("#d75").is_err());
+        assert!(parse_color("mauve").is_err());
+    }
+
+    #[test]
+    fn test_config_overrides_defaults() {
+        let config = SeverityConfig {
+            error: SeverityStyleConfig {
+                color: Some("magenta".to_string()),
+                icon: Some("E".to_string()),
+            },
+            ..Default::default()
+        };
+        let styles = SeverityStyles::from_config(&config).unwrap();
+        assert_eq!(styles.error.color, Color::Magenta);
+        assert_eq!(styles.error.icon.as_deref(), Some("E"));
+        assert_eq!(styles.warning, SeverityStyles::default().warning);
+
+        let bad = SeverityConfig {
+            warning: SeverityStyleConfig {
+                color: Some("mauve".to_string()),
+                icon: None,
+            },
+            ..Default::default()
+        };
+        let err = SeverityStyles::from_config(&bad).unwrap_err();
+        assert!(err.to_string().contains("[severity.warning]"));
+    }
+}
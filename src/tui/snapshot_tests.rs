@@ -55,6 +55,7 @@ fn create_test_model() -> Model {
             severity: Severity::Warning,
             message: "Word 'think' detected - sensitive in Claude Opus".to_string(),
             line: Some(1),
+            confidence: 1.0,
             suggestion: Some("Consider rephrasing".to_string()),
         },
         Issue {
@@ -63,6 +64,7 @@ fn create_test_model() -> Model {
             severity: Severity::Info,
             message: "Vague instruction detected".to_string(),
             line: Some(1),
+            confidence: 1.0,
             suggestion: Some("Be more specific".to_string()),
         },
     ];
@@ -82,11 +84,12 @@ fn create_optimized_model() -> Model {
         optimized_chars: 76,
         original_tokens: 12,
         optimized_tokens: 15,
-        rules_applied: 2,
+        issues_detected: 2,
         categories_improved: 2,
         processing_time_ms: 1234,
         provider: "bedrock".to_string(),
         model: "claude-sonnet-4".to_string(),
+        ..Default::default()
     });
     model.phase = AppPhase::Done;
     model
@@ -117,6 +120,15 @@ fn test_diff_view() {
     assert_snapshot!("diff_view", output);
 }
 
+#[test]
+fn test_diff_preview() {
+    let mut model = create_optimized_model();
+    model.current_view = View::Diff;
+    model.toggle_preview();
+    let output = render_to_string(&model, 80, 24);
+    assert_snapshot!("diff_preview", output);
+}
+
 #[test]
 fn test_help_view() {
     let mut model = create_test_model();
@@ -2,28 +2,44 @@
 //!
 //! Handles terminal initialization, restoration, and panic recovery.
 //! Implements "belt + suspenders" approach for robust error recovery.
+//! Ctrl+C handling is shared with the non-interactive modes; entering the
+//! alternate screen needs the `tui` feature.
 
-use std::io::{self, Stdout};
+use std::io::{self, IsTerminal};
 use std::panic;
+#[cfg(feature = "tui")]
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
+#[cfg(feature = "tui")]
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+#[cfg(feature = "tui")]
 use ratatui::backend::CrosstermBackend;
+#[cfg(feature = "tui")]
 use ratatui::Terminal;
 
 /// Flag to track if terminal is in raw mode (for signal handlers)
+#[cfg(feature = "tui")]
 static TERMINAL_RAW: AtomicBool = AtomicBool::new(false);
 
+/// What to tell the user if Ctrl+C arrives now (set as the run progresses)
+static INTERRUPT_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Result of installing the signal handler (it can only be installed once)
+static SIGNAL_HANDLER: OnceLock<Result<(), String>> = OnceLock::new();
+
 /// Type alias for our terminal
-pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+#[cfg(feature = "tui")]
+pub type Tui = Terminal<CrosstermBackend<io::Stdout>>;
 
 /// Initialize the terminal for interactive mode
 ///
 /// This enters the alternate screen, enables raw mode, and optionally enables mouse capture.
+#[cfg(feature = "tui")]
 pub fn init() -> io::Result<Tui> {
     enable_raw_mode()?;
     TERMINAL_RAW.store(true, Ordering::SeqCst);
@@ -42,12 +58,25 @@ pub fn init() -> io::Result<Tui> {
 /// This is safe to call multiple times and won't panic.
 pub fn restore() {
     // Only restore if we're in raw mode
+    #[cfg(feature = "tui")]
     if TERMINAL_RAW.swap(false, Ordering::SeqCst) {
         let _ = disable_raw_mode();
         let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
     }
 }
 
+/// Take the terminal back after a child process (an editor) used it
+///
+/// The caller hands the terminal over with [`restore`] first.
+#[cfg(feature = "tui")]
+pub fn resume(terminal: &mut Tui) -> io::Result<()> {
+    enable_raw_mode()?;
+    TERMINAL_RAW.store(true, Ordering::SeqCst);
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    // The editor drew over the screen ratatui thinks it shows
+    terminal.clear()
+}
+
 /// Install panic hook that restores terminal before printing panic message
 ///
 /// This ensures the terminal is usable even after a panic.
@@ -59,18 +88,43 @@ pub fn install_panic_hook() {
     }));
 }
 
+/// Set the message printed if the run is interrupted (None for a bare notice)
+pub fn set_interrupt_message(message: Option<String>) {
+    *INTERRUPT_MESSAGE.lock().unwrap_or_else(|e| e.into_inner()) = message;
+}
+
 /// Install signal handlers for clean shutdown
 ///
-/// Handles SIGINT (Ctrl+C) and SIGTERM for graceful termination.
+/// Handles SIGINT (Ctrl+C) and SIGTERM for graceful termination: restores
+/// the terminal and cursor, removes temp files of unfinished writes, reports
+/// what completed, and exits with 130. Safe to call more than once.
 pub fn install_signal_handlers() -> io::Result<()> {
     // Use ctrlc crate for cross-platform signal handling
-    ctrlc::set_handler(move || {
-        restore();
-        std::process::exit(130); // 128 + SIGINT (2)
-    })
-    .map_err(io::Error::other)?;
-
-    Ok(())
+    SIGNAL_HANDLER
+        .get_or_init(|| {
+            ctrlc::set_handler(move || {
+                restore();
+                // A spinner may have hidden the cursor
+                if io::stderr().is_terminal() {
+                    let _ = console::Term::stderr().show_cursor();
+                }
+                crate::utils::file::remove_partial_writes();
+
+                let message = INTERRUPT_MESSAGE
+                    .lock()
+                    .map(|m| m.clone())
+                    .unwrap_or_default();
+                eprintln!();
+                eprintln!(
+                    "  Interrupted{}",
+                    message.map(|m| format!(": {}", m)).unwrap_or_default()
+                );
+                std::process::exit(130); // 128 + SIGINT (2)
+            })
+            .map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(io::Error::other)
 }
 
 /// Initialize all safety measures at once
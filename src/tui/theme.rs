@@ -6,6 +6,9 @@
 
 use ratatui::style::{Color, Modifier, Style};
 
+use super::severity;
+use crate::analyzer::Severity;
+
 /// Application theme with consistent styling
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -15,10 +18,12 @@ pub struct Theme {
     pub secondary: Style,
     /// Success color (green) - positive results, checkmarks
     pub success: Style,
-    /// Warning color (yellow) - warnings, caution items
+    /// Warning color (yellow unless `[severity.warning]` says otherwise)
     pub warning: Style,
-    /// Error color (red) - errors, critical issues
+    /// Error color (red unless `[severity.error]` says otherwise)
     pub error: Style,
+    /// Info color (blue unless `[severity.info]` says otherwise)
+    pub info: Style,
     /// Muted color (gray) - less important info
     pub muted: Style,
     /// Default text color
@@ -45,9 +50,9 @@ pub struct Theme {
     pub diff_unchanged: Style,
 }
 
-impl Theme {
+impl Default for Theme {
     /// Create the default theme
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self {
             primary: Style::default()
                 .fg(Color::Cyan)
@@ -56,8 +61,11 @@ impl Theme {
             success: Style::default()
                 .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
-            warning: Style::default().fg(Color::Yellow),
-            error: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(severity_color(Severity::Warning)),
+            error: Style::default()
+                .fg(severity_color(Severity::Error))
+                .add_modifier(Modifier::BOLD),
+            info: Style::default().fg(severity_color(Severity::Info)),
             muted: Style::default().fg(Color::DarkGray),
             text: Style::default().fg(Color::White),
             selected: Style::default()
@@ -80,9 +88,43 @@ impl Theme {
     }
 }
 
-impl std::default::Default for Theme {
-    fn default() -> Self {
-        Self::default()
+impl Theme {
+    /// The style for issues of `severity`
+    pub fn severity(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Error => self.error,
+            Severity::Warning => self.warning,
+            Severity::Info => self.info,
+        }
+    }
+}
+
+/// The configured severity color as a ratatui color
+fn severity_color(severity: Severity) -> Color {
+    to_ratatui(severity::color(severity))
+}
+
+/// ratatui names the 16 ANSI colors differently from `colored`
+fn to_ratatui(color: colored::Color) -> Color {
+    use colored::Color as C;
+    match color {
+        C::Black => Color::Black,
+        C::Red => Color::Red,
+        C::Green => Color::Green,
+        C::Yellow => Color::Yellow,
+        C::Blue => Color::Blue,
+        C::Magenta => Color::Magenta,
+        C::Cyan => Color::Cyan,
+        C::White => Color::Gray,
+        C::BrightBlack => Color::DarkGray,
+        C::BrightRed => Color::LightRed,
+        C::BrightGreen => Color::LightGreen,
+        C::BrightYellow => Color::LightYellow,
+        C::BrightBlue => Color::LightBlue,
+        C::BrightMagenta => Color::LightMagenta,
+        C::BrightCyan => Color::LightCyan,
+        C::BrightWhite => Color::White,
+        C::TrueColor { r, g, b } => Color::Rgb(r, g, b),
     }
 }
 
@@ -102,6 +144,17 @@ mod tests {
         let theme = Theme::default();
         assert_eq!(theme.primary.fg, Some(Color::Cyan));
         assert_eq!(theme.error.fg, Some(Color::Red));
+        assert_eq!(theme.severity(Severity::Info).fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_to_ratatui() {
+        assert_eq!(to_ratatui(colored::Color::Magenta), Color::Magenta);
+        assert_eq!(to_ratatui(colored::Color::BrightBlue), Color::LightBlue);
+        assert_eq!(
+            to_ratatui(colored::Color::TrueColor { r: 1, g: 2, b: 3 }),
+            Color::Rgb(1, 2, 3)
+        );
     }
 
     #[test]
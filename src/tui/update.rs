@@ -4,13 +4,19 @@
 
 #![allow(dead_code)]
 
+use std::path::PathBuf;
 use std::time::Duration;
 
+use anyhow::Context;
 use chrono::Local;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent};
 
+use super::keys::{keymap, Action};
 use super::model::{Model, View};
 use super::widgets::handle_suggest_modal_key;
+use crate::cli::provenance::{self, Provenance};
+use crate::llm;
+use crate::utils::{file, redact};
 
 /// Messages that can be sent to update the model
 #[derive(Debug, Clone)]
@@ -72,24 +78,22 @@ fn handle_key(model: &mut Model, key: KeyEvent) -> bool {
         }
     }
 
+    let action = keymap().action(&key, model.current_view);
+
     // Global keys (work in any view)
-    match key.code {
-        KeyCode::Char('q') => {
-            model.should_quit = true;
-            return false;
-        }
-        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            model.should_quit = true;
-            return false;
-        }
-        _ => {}
+    if action == Some(Action::Quit) {
+        model.should_quit = true;
+        return false;
     }
 
     // View-specific key handling
+    let Some(action) = action else {
+        return false;
+    };
     match model.current_view {
-        View::Main => handle_main_keys(model, key),
-        View::Diff => handle_diff_keys(model, key),
-        View::Help => handle_help_keys(model, key),
+        View::Main => handle_main_keys(model, action),
+        View::Diff => handle_diff_keys(model, action),
+        View::Help => handle_help_keys(model, action),
     }
 }
 
@@ -105,51 +109,48 @@ fn handle_error_keys(model: &mut Model, key: KeyEvent) -> bool {
 }
 
 /// Handle keys in the main view
-fn handle_main_keys(model: &mut Model, key: KeyEvent) -> bool {
-    match key.code {
+fn handle_main_keys(model: &mut Model, action: Action) -> bool {
+    match action {
         // Navigation
-        KeyCode::Up | KeyCode::Char('k') => {
+        Action::Up => {
             model.issue_tree.select_prev();
             true
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Action::Down => {
             model.issue_tree.select_next();
             true
         }
-        KeyCode::Enter => {
+        Action::Toggle => {
             model.issue_tree.toggle_current();
             true
         }
 
         // View switching
-        KeyCode::Char('d') if model.has_results() => {
+        Action::Diff if model.has_results() => {
             model.current_view = View::Diff;
             true
         }
-        KeyCode::Char('?') => {
+        Action::Help => {
             model.current_view = View::Help;
             true
         }
 
         // Actions (only when results available)
-        KeyCode::Char('c') if model.has_results() => handle_copy(model),
-        KeyCode::Char('s') if model.has_results() => handle_save(model),
-        KeyCode::Char('e') if model.has_results() => handle_open_in_editor(model),
-        KeyCode::Char('r') if model.has_results() => {
-            // Re-run - would need async handling
-            false
-        }
+        Action::Copy if model.has_results() => handle_copy(model),
+        Action::Save if model.has_results() => handle_save(model),
+        Action::Edit if model.has_results() => handle_open_in_editor(model),
+        Action::Docs => handle_open_docs(model),
 
         // Scroll
-        KeyCode::PageUp => {
+        Action::PageUp => {
             model.scroll_offset = model.scroll_offset.saturating_sub(10);
             true
         }
-        KeyCode::PageDown => {
+        Action::PageDown => {
             model.scroll_offset = model.scroll_offset.saturating_add(10);
             true
         }
-        KeyCode::Home => {
+        Action::Top => {
             model.scroll_offset = 0;
             true
         }
@@ -159,39 +160,55 @@ fn handle_main_keys(model: &mut Model, key: KeyEvent) -> bool {
 }
 
 /// Handle keys in the diff view
-fn handle_diff_keys(model: &mut Model, key: KeyEvent) -> bool {
-    match key.code {
-        KeyCode::Esc | KeyCode::Char('d') => {
+fn handle_diff_keys(model: &mut Model, action: Action) -> bool {
+    match action {
+        Action::Preview => {
+            model.toggle_preview();
+            true
+        }
+        // Esc leaves the full-screen preview first, then the diff view
+        Action::Back if model.preview.is_some() => {
+            model.preview = None;
+            model.scroll_offset = 0;
+            true
+        }
+        Action::Back | Action::Diff => {
+            model.preview = None;
+            model.scroll_offset = 0;
             model.current_view = View::Main;
             true
         }
-        KeyCode::Char('c') => handle_copy(model),
-        KeyCode::Char('s') => handle_save(model),
-        KeyCode::Char('e') => handle_open_in_editor(model),
-        KeyCode::Up => {
+        Action::Copy => handle_copy(model),
+        Action::Save => handle_save(model),
+        Action::Edit => handle_open_in_editor(model),
+        Action::Up => {
             model.scroll_offset = model.scroll_offset.saturating_sub(1);
             true
         }
-        KeyCode::Down => {
+        Action::Down => {
             model.scroll_offset = model.scroll_offset.saturating_add(1);
             true
         }
-        KeyCode::PageUp => {
+        Action::PageUp => {
             model.scroll_offset = model.scroll_offset.saturating_sub(10);
             true
         }
-        KeyCode::PageDown => {
+        Action::PageDown => {
             model.scroll_offset = model.scroll_offset.saturating_add(10);
             true
         }
+        Action::Top => {
+            model.scroll_offset = 0;
+            true
+        }
         _ => false,
     }
 }
 
 /// Handle keys in the help view
-fn handle_help_keys(model: &mut Model, key: KeyEvent) -> bool {
-    match key.code {
-        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Enter => {
+fn handle_help_keys(model: &mut Model, action: Action) -> bool {
+    match action {
+        Action::Back | Action::Help | Action::Toggle => {
             model.current_view = View::Main;
             true
         }
@@ -215,63 +232,53 @@ fn handle_copy(model: &mut Model) -> bool {
     false
 }
 
-/// Handle save action - saves to copt-output/ and auto-opens in editor
-fn handle_save(model: &mut Model) -> bool {
-    if let Some(ref optimized) = model.optimized_prompt {
-        // Generate output path
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("optimized_{}.txt", timestamp);
-        let output_dir = std::path::PathBuf::from("copt-output");
-        let output_path = output_dir.join(&filename);
-
-        // Create output directory if needed
-        if let Err(e) = std::fs::create_dir_all(&output_dir) {
+/// Open the documentation for the selected issue's rule in the browser
+fn handle_open_docs(model: &mut Model) -> bool {
+    let Some(issue) = model.issue_tree.selected_issue() else {
+        return false;
+    };
+    let rule = issue.id.clone();
+
+    match crate::analyzer::docs::docs_url(&rule) {
+        Some(url) => match crate::utils::browser::open_url(&url) {
+            Ok(()) => {
+                model.set_status_message(
+                    format!("✓ Opened {} docs in browser", rule),
+                    Duration::from_secs(3),
+                );
+            }
+            Err(e) => {
+                model.set_status_message(format!("✗ {}", e), Duration::from_secs(5));
+            }
+        },
+        None => {
             model.set_status_message(
-                format!("✗ Failed to create directory: {}", e),
-                Duration::from_secs(5),
+                format!("No documentation for {}", rule),
+                Duration::from_secs(3),
             );
-            return true;
-        }
-
-        // Write the optimized prompt
-        if let Err(e) = std::fs::write(&output_path, optimized) {
-            model.set_status_message(format!("✗ Save failed: {}", e), Duration::from_secs(5));
-            return true;
         }
+    }
+    true
+}
 
-        // Auto-open in editor after successful save
-        let editor = std::env::var("EDITOR")
-            .or_else(|_| std::env::var("VISUAL"))
-            .unwrap_or_else(|_| {
-                if cfg!(target_os = "macos") {
-                    "nano".to_string()
-                } else if cfg!(target_os = "windows") {
-                    "notepad".to_string()
-                } else {
-                    "vi".to_string()
-                }
-            });
-
-        let (editor_cmd, editor_args) = build_editor_command(&editor, &output_path);
-
-        match std::process::Command::new(&editor_cmd)
-            .args(&editor_args)
-            .spawn()
-        {
-            Ok(_) => {
-                // File saved and editor opened - quit the TUI
-                model.should_quit = true;
-            }
+/// Handle save action - saves to the output directory and opens it in the
+/// editor
+///
+/// The app loop runs the editor and takes the edited text back in.
+fn handle_save(model: &mut Model) -> bool {
+    if model.optimized_prompt.is_some() && crate::utils::sandbox::enabled() {
+        model.set_status_message(
+            "Saving is disabled by --sandbox; the prompt is printed on exit",
+            Duration::from_secs(5),
+        );
+        return true;
+    }
+    if let Some(ref optimized) = model.optimized_prompt {
+        match save_optimized(model, optimized) {
+            // Opened by the app loop, which owns the terminal
+            Ok(path) => model.pending_edit = Some(path),
             Err(e) => {
-                // File was saved but editor failed - stay open to show error
-                model.set_status_message(
-                    format!(
-                        "✓ Saved to {} (editor failed: {})",
-                        output_path.display(),
-                        e
-                    ),
-                    Duration::from_secs(5),
-                );
+                model.set_status_message(format!("✗ Save failed: {:#}", e), Duration::from_secs(5))
             }
         }
         return true;
@@ -279,42 +286,41 @@ fn handle_save(model: &mut Model) -> bool {
     false
 }
 
+/// Write `optimized` the way the CLI saves it: redacted, ending with the
+/// provenance footer, and atomically, so Ctrl-C never leaves half a file
+fn save_optimized(model: &Model, optimized: &str) -> anyhow::Result<PathBuf> {
+    let optimized = redact::apply(optimized);
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let filename = file::output_file_name("optimized", &timestamp, &optimized, "txt");
+
+    std::fs::create_dir_all(&model.output_dir)
+        .with_context(|| format!("Failed to create directory: {}", model.output_dir.display()))?;
+    let output_path = file::reserve_output_path(&model.output_dir, &filename)?;
+
+    let saved = match model.stats {
+        Some(ref stats) if provenance::enabled() => {
+            let llm_model = (!model.offline_mode && stats.degraded.is_none())
+                .then(|| llm::models::provider_id(&stats.provider, &stats.model));
+            let provenance = Provenance::new(
+                &stats.provider,
+                llm_model,
+                &redact::apply(&model.original_prompt),
+            );
+            provenance::append(&optimized, &provenance)
+        }
+        _ => optimized.into_owned(),
+    };
+    file::write_atomic(&output_path, &saved)?;
+    Ok(output_path)
+}
+
 /// Handle opening optimized prompt in default editor
-/// Saves to copt-output/ first, then opens the saved file in editor
+/// Saves to the output directory first, then opens the saved file in editor
 fn handle_open_in_editor(model: &mut Model) -> bool {
     // Delegate to handle_save which now saves AND opens in editor
     handle_save(model)
 }
 
-/// Build editor command with appropriate wait flags for GUI editors
-fn build_editor_command(editor: &str, file_path: &std::path::Path) -> (String, Vec<String>) {
-    let editor_lower = editor.to_lowercase();
-    let file_arg = file_path.to_string_lossy().to_string();
-
-    // Extract just the binary name for matching (handle full paths)
-    let editor_name = std::path::Path::new(editor)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(editor)
-        .to_lowercase();
-
-    // VSCode: `code` (no --wait needed for viewing)
-    if editor_name.contains("code") || editor_lower.contains("visual studio code") {
-        return (editor.to_string(), vec![file_arg]);
-    }
-
-    // Zed: `zed` or `/path/to/Zed.app/.../cli`
-    if editor_name == "cli" && editor_lower.contains("zed") {
-        return (editor.to_string(), vec![file_arg]);
-    }
-    if editor_name.contains("zed") {
-        return (editor.to_string(), vec![file_arg]);
-    }
-
-    // Default: terminal editors (vim, nano, emacs, etc.)
-    (editor.to_string(), vec![file_arg])
-}
-
 /// Copy text to system clipboard
 fn copy_to_clipboard(text: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Try using pbcopy on macOS, xclip on Linux, etc.
@@ -369,6 +375,7 @@ fn copy_to_clipboard(text: &str) -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::KeyModifiers;
 
     #[test]
     fn test_quit_message() {
@@ -421,6 +428,7 @@ mod tests {
             severity: Severity::Warning,
             message: "Test".to_string(),
             line: None,
+            confidence: 1.0,
             suggestion: None,
         }];
         model.suggest_modal = SuggestModalState::from_issues(&issues);
@@ -435,6 +443,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_suggest_modal_enter_dismisses_via_handle_key() {
         use crate::analyzer::Severity;
         use crate::tui::widgets::SuggestModalState;
@@ -450,6 +459,7 @@ mod tests {
             severity: Severity::Warning,
             message: "Test".to_string(),
             line: None,
+            confidence: 1.0,
             suggestion: None,
         }];
         model.suggest_modal = SuggestModalState::from_issues(&issues);
@@ -482,6 +492,7 @@ mod tests {
             severity: Severity::Warning,
             message: "Test".to_string(),
             line: None,
+            confidence: 1.0,
             suggestion: None,
         }];
         model.suggest_modal = SuggestModalState::from_issues(&issues);
@@ -495,4 +506,44 @@ mod tests {
         assert!(model.suggest_modal.visible); // Modal should still be visible
         assert!(model.suggest_modal.has_selections()); // Selection should be toggled
     }
+
+    #[test]
+    fn test_save_writes_to_output_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = Model {
+            output_dir: dir.path().join("out"),
+            ..Default::default()
+        };
+        model.set_optimization_result("Fix the bug.".to_string(), Default::default());
+
+        let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        handle_key(&mut model, s);
+
+        let path = model.pending_edit.expect("saved file to open");
+        assert!(path.starts_with(dir.path().join("out")));
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(provenance::strip(&saved), "Fix the bug.");
+    }
+
+    #[test]
+    fn test_preview_toggle_in_diff_view() {
+        use crate::tui::model::Preview;
+
+        let mut model = Model::default();
+        model.set_optimization_result("Fix the bug.".to_string(), Default::default());
+        assert_eq!(model.current_view, View::Diff);
+
+        let o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+        handle_key(&mut model, o);
+        assert_eq!(model.preview, Some(Preview::Optimized));
+        handle_key(&mut model, o);
+        assert_eq!(model.preview, Some(Preview::Original));
+
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        handle_key(&mut model, esc);
+        assert_eq!(model.preview, None);
+        assert_eq!(model.current_view, View::Diff);
+        handle_key(&mut model, esc);
+        assert_eq!(model.current_view, View::Main);
+    }
 }
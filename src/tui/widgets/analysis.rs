@@ -1,14 +1,16 @@
 //! Analysis tree widget (collapsible issue tree)
 
-use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
 
-use crate::analyzer::Severity;
+use crate::analyzer::report::RuleGroup;
+use crate::analyzer::{docs, examples};
 use crate::tui::icons::icons;
 use crate::tui::model::Model;
+use crate::tui::severity;
 use crate::tui::theme::theme;
 
 /// Render the analysis results as a collapsible tree
@@ -25,7 +27,7 @@ pub fn render_analysis(frame: &mut Frame, area: Rect, model: &Model) {
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    if model.issue_tree.categories.is_empty() {
+    if model.issue_tree.is_empty() {
         // No issues found
         let text = Line::from(vec![
             Span::styled(format!("{} ", icons.check), theme.success),
@@ -34,18 +36,33 @@ pub fn render_analysis(frame: &mut Frame, area: Rect, model: &Model) {
                 theme.success,
             ),
         ]);
-        let paragraph = ratatui::widgets::Paragraph::new(text);
+        let paragraph = Paragraph::new(text);
         frame.render_widget(paragraph, inner_area);
         return;
     }
 
+    // Detail pane for the selected rule below the tree
+    let (list_area, detail_area) = match model.issue_tree.selected_rule() {
+        Some(rule) if inner_area.height >= 12 => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(4),
+                    Constraint::Length(inner_area.height / 2),
+                ])
+                .split(inner_area);
+            (chunks[0], Some((chunks[1], rule)))
+        }
+        _ => (inner_area, None),
+    };
+
     // Build list items from the issue tree
     let mut items: Vec<ListItem> = Vec::new();
     let mut current_idx = 0;
 
-    for cat in &model.issue_tree.categories {
+    for (cat, expanded) in model.issue_tree.categories() {
         // Category header
-        let expand_icon = if cat.expanded {
+        let expand_icon = if expanded {
             icons.folder_open
         } else {
             icons.folder_closed
@@ -60,51 +77,42 @@ pub fn render_analysis(frame: &mut Frame, area: Rect, model: &Model) {
         let cat_line = Line::from(vec![
             Span::styled(format!("{} ", expand_icon), theme.primary),
             Span::styled(&cat.display_name, cat_style.add_modifier(Modifier::BOLD)),
-            Span::styled(format!(" ({} issues)", cat.issue_count()), theme.muted),
+            Span::styled(format!(" ({} issues)", cat.count), theme.muted),
         ]);
         items.push(ListItem::new(cat_line));
         current_idx += 1;
 
-        // Issues (if expanded)
-        if cat.expanded {
-            for issue in &cat.issues {
-                let severity_style = match issue.severity {
-                    Severity::Error => theme.error,
-                    Severity::Warning => theme.warning,
-                    Severity::Info => theme.secondary,
-                };
-
-                let severity_icon = match issue.severity {
-                    Severity::Error => icons.cross,
-                    Severity::Warning => icons.warning,
-                    Severity::Info => icons.info,
-                };
+        // Rules (if expanded)
+        if expanded {
+            for rule in &cat.rules {
+                let severity_style = theme.severity(rule.severity);
+                let severity_icon = severity::icon(rule.severity, icons);
 
-                let issue_style = if current_idx == model.issue_tree.flat_index {
+                let rule_style = if current_idx == model.issue_tree.flat_index {
                     theme.selected
+                } else if rule.is_low_confidence() {
+                    Style::default().add_modifier(Modifier::DIM)
                 } else {
                     Style::default()
                 };
 
-                // Truncate message if too long
-                let max_msg_len = (inner_area.width as usize).saturating_sub(20);
-                let msg = if issue.message.len() > max_msg_len {
-                    format!("{}...", &issue.message[..max_msg_len.saturating_sub(3)])
+                let msg = rule.truncated_message((inner_area.width as usize).saturating_sub(20));
+                let location = rule.location();
+                let location = if location.is_empty() {
+                    location
                 } else {
-                    issue.message.clone()
+                    format!(" ({})", location)
                 };
 
-                let line_info = issue.line.map(|l| format!(" (L{})", l)).unwrap_or_default();
-
-                let issue_line = Line::from(vec![
+                let rule_line = Line::from(vec![
                     Span::raw("   "), // Indent
                     Span::styled(format!("{} ", severity_icon), severity_style),
-                    Span::styled(&issue.id, theme.muted),
+                    Span::styled(&rule.rule_id, theme.muted),
                     Span::raw(" "),
-                    Span::styled(msg, issue_style),
-                    Span::styled(line_info, theme.muted),
+                    Span::styled(msg, rule_style),
+                    Span::styled(location, theme.muted),
                 ]);
-                items.push(ListItem::new(issue_line));
+                items.push(ListItem::new(rule_line));
                 current_idx += 1;
             }
         }
@@ -116,13 +124,75 @@ pub fn render_analysis(frame: &mut Frame, area: Rect, model: &Model) {
     let mut state = ListState::default();
     state.select(Some(model.issue_tree.flat_index));
 
-    frame.render_stateful_widget(list, inner_area, &mut state);
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    if let Some((area, rule)) = detail_area {
+        render_rule_detail(frame, area, rule);
+    }
+}
+
+/// Full messages, suggestion, and before/after example for one rule
+fn render_rule_detail(frame: &mut Frame, area: Rect, rule: &RuleGroup) {
+    let theme = theme();
+    let issue = &rule.occurrences[0];
+
+    let block = Block::default()
+        .title(format!(" {} ", rule.rule_id))
+        .title_style(theme.title)
+        .borders(Borders::TOP)
+        .border_style(theme.border);
+
+    let mut lines: Vec<Line> = rule
+        .occurrences
+        .iter()
+        .map(|issue| {
+            let mut location = issue.line.map(|l| format!("L{}: ", l)).unwrap_or_default();
+            // Grouped rows mix rules; name each finding's rule
+            if rule.group.is_some() {
+                location = format!("{} {}", issue.id, location);
+            }
+            Line::from(vec![
+                Span::styled(location, theme.muted),
+                Span::styled(issue.message.clone(), theme.text),
+            ])
+        })
+        .collect();
+    if let Some(ref suggestion) = issue.suggestion {
+        lines.push(Line::from(Span::styled(suggestion.clone(), theme.muted)));
+    }
+    if let Some(example) = examples::rule_example(&issue.id) {
+        lines.push(Line::default());
+        for (label, text, style) in [
+            ("Before: ", example.before, theme.error),
+            ("After:  ", example.after, theme.success),
+        ] {
+            for (i, line) in text.lines().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::styled(if i == 0 { label } else { "        " }, theme.muted),
+                    Span::styled(line, style),
+                ]));
+            }
+        }
+    }
+    if let Some(url) = docs::docs_url(&issue.id) {
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            Span::styled("Docs:   ", theme.muted),
+            Span::styled(url, theme.muted),
+            Span::styled("  (o)", theme.key),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analyzer::Issue;
+    use crate::analyzer::{Issue, Severity};
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
 
@@ -135,6 +205,7 @@ mod tests {
                 severity: Severity::Warning,
                 message: "Test warning".to_string(),
                 line: Some(1),
+                confidence: 1.0,
                 suggestion: Some("Fix it".to_string()),
             },
             Issue {
@@ -143,6 +214,7 @@ mod tests {
                 severity: Severity::Error,
                 message: "Test error".to_string(),
                 line: None,
+                confidence: 1.0,
                 suggestion: Some("Fix style".to_string()),
             },
         ]);
@@ -193,4 +265,29 @@ mod tests {
             .collect::<String>();
         assert!(content.contains("No issues"));
     }
+
+    #[test]
+    fn test_render_selected_issue_detail() {
+        let backend = TestBackend::new(80, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let mut model = create_test_model();
+        model.issue_tree.select_next();
+
+        terminal
+            .draw(|frame| {
+                render_analysis(frame, frame.area(), &model);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let content = buffer
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+        assert!(content.contains("Fix it"));
+        assert!(content.contains("Before: Create a dashboard"));
+        assert!(content.contains("Docs:"));
+    }
 }
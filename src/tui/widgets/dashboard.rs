@@ -33,8 +33,12 @@ pub fn render_dashboard(frame: &mut Frame, area: Rect, model: &Model) {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(5), // Token analysis
-            Constraint::Length(4), // Performance
-            Constraint::Length(3), // Provider
+            Constraint::Length(5), // Performance
+            Constraint::Length(if stats.timed_out || stats.degraded.is_some() {
+                4
+            } else {
+                3
+            }), // Provider
         ])
         .split(inner_area);
 
@@ -128,8 +132,16 @@ fn render_performance_section(frame: &mut Frame, area: Rect, stats: &crate::Opti
             Span::styled(time_display, theme.success),
         ]),
         Line::from(vec![
-            Span::styled(format!("{:<18}", "Rules applied:"), theme.muted),
-            Span::styled(stats.rules_applied.to_string(), theme.text),
+            Span::styled(format!("{:<18}", "Issues detected:"), theme.muted),
+            Span::styled(stats.issues_detected.to_string(), theme.text),
+        ]),
+        Line::from(vec![
+            Span::styled(format!("{:<18}", "Rules fixed:"), theme.muted),
+            Span::styled(stats.transforms.rules_fixed().to_string(), theme.success),
+            Span::styled(
+                format!(" ({} edits)", stats.transforms.total_replacements()),
+                theme.muted,
+            ),
         ]),
         Line::from(vec![
             Span::styled(format!("{:<18}", "Categories fixed:"), theme.muted),
@@ -161,7 +173,7 @@ fn render_provider_section(frame: &mut Frame, area: Rect, stats: &crate::Optimiz
         stats.model.clone()
     };
 
-    let text = vec![
+    let mut text = vec![
         Line::from(Span::styled("PROVIDER", theme.primary.bold())),
         Line::from(vec![
             Span::styled(format!("{:<18}", "Service:"), theme.muted),
@@ -172,6 +184,20 @@ fn render_provider_section(frame: &mut Frame, area: Rect, stats: &crate::Optimiz
             Span::styled(model_display, theme.muted),
         ]),
     ];
+    if stats.timed_out {
+        text.push(Line::from(vec![
+            Span::styled(format!("{:<18}", "LLM call:"), theme.muted),
+            Span::styled("timed out, static result shown", theme.warning),
+        ]));
+    } else if let Some(ref failure) = stats.degraded {
+        text.push(Line::from(vec![
+            Span::styled(format!("{:<18}", "LLM call:"), theme.muted),
+            Span::styled(
+                format!("failed ({}), static result shown", failure.code),
+                theme.warning,
+            ),
+        ]));
+    }
 
     let paragraph = Paragraph::new(text);
     frame.render_widget(paragraph, area);
@@ -194,7 +220,7 @@ mod tests {
             original_tokens: 100,
             optimized_tokens: 150,
             processing_time_ms: 2500,
-            rules_applied: 5,
+            issues_detected: 5,
             categories_improved: 3,
             provider: "bedrock".to_string(),
             model: "claude-sonnet".to_string(),
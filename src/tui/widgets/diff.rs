@@ -1,20 +1,27 @@
 //! Diff view widget
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
-use similar::{ChangeTag, TextDiff};
 
+use crate::tui::diff::{side_by_side_rows, wrap_line};
+use crate::tui::highlight::CodeBlocks;
 use crate::tui::icons::icons;
-use crate::tui::model::Model;
+use crate::tui::model::{Model, Preview};
 use crate::tui::theme::theme;
 
-/// Render the diff view (side-by-side comparison)
+/// Render the diff view (side-by-side comparison, or one prompt full screen)
 pub fn render_diff(frame: &mut Frame, area: Rect, model: &Model) {
     let theme = theme();
     let icons = icons();
 
+    if let (Some(preview), Some(optimized)) = (model.preview, &model.optimized_prompt) {
+        render_preview(frame, area, model, preview, optimized);
+        return;
+    }
+
     let block = Block::default()
         .title(format!(" {} Changes ", icons.sparkles))
         .title_style(theme.title)
@@ -36,23 +43,28 @@ pub fn render_diff(frame: &mut Frame, area: Rect, model: &Model) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(inner_area);
 
-    // Left side: Original
+    // Both panels have borders; wrap each side to its panel's inner width
+    let (left, right) = aligned_rows(
+        &model.original_prompt,
+        optimized,
+        chunks[0].width.saturating_sub(2) as usize,
+        chunks[1].width.saturating_sub(2) as usize,
+    );
+
     render_diff_panel(
         frame,
         chunks[0],
-        &model.original_prompt,
-        optimized,
-        true, // is_original
+        format!("{} Original", icons.file),
+        theme.muted,
+        left,
         model.scroll_offset,
     );
-
-    // Right side: Optimized
     render_diff_panel(
         frame,
         chunks[1],
-        &model.original_prompt,
-        optimized,
-        false, // is_original
+        format!("{} Optimized", icons.sparkles),
+        theme.success,
+        right,
         model.scroll_offset,
     );
 }
@@ -61,24 +73,138 @@ pub fn render_diff(frame: &mut Frame, area: Rect, model: &Model) {
 fn render_diff_panel(
     frame: &mut Frame,
     area: Rect,
-    original: &str,
-    optimized: &str,
-    is_original: bool,
+    title: String,
+    title_style: Style,
+    lines: Vec<Line<'static>>,
     scroll_offset: u16,
 ) {
     let theme = theme();
-    let icons = icons();
 
-    let (title, _content) = if is_original {
-        (format!("{} Original", icons.file), original)
-    } else {
-        (format!("{} Optimized", icons.sparkles), optimized)
+    let block = Block::default()
+        .title(Span::styled(title, title_style))
+        .borders(Borders::ALL)
+        .border_style(theme.border);
+
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let visible_lines: Vec<Line> = lines.into_iter().skip(scroll_offset as usize).collect();
+    frame.render_widget(Paragraph::new(visible_lines), inner_area);
+}
+
+/// The original and optimized panels' rows, wrapped to their widths and
+/// padded so each original line stays level with the line that replaced it
+fn aligned_rows(
+    original: &str,
+    optimized: &str,
+    left_width: usize,
+    right_width: usize,
+) -> (Vec<Line<'static>>, Vec<Line<'static>>) {
+    let theme = theme();
+    // Code blocks are followed on each side's own lines
+    let mut left_code = CodeBlocks::new();
+    let mut right_code = CodeBlocks::new();
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+
+    for row in side_by_side_rows(original, optimized) {
+        let (left_style, right_style, left_prefix, right_prefix) = if row.changed {
+            (theme.diff_removed, theme.diff_added, "- ", "+ ")
+        } else {
+            (theme.diff_unchanged, theme.diff_unchanged, "  ", "  ")
+        };
+        let mut left_rows = row.left.as_deref().map_or_else(Vec::new, |text| {
+            side_rows(
+                text,
+                left_prefix,
+                left_style,
+                row.changed,
+                left_width,
+                &mut left_code,
+            )
+        });
+        let mut right_rows = row.right.as_deref().map_or_else(Vec::new, |text| {
+            side_rows(
+                text,
+                right_prefix,
+                right_style,
+                row.changed,
+                right_width,
+                &mut right_code,
+            )
+        });
+        let height = left_rows.len().max(right_rows.len());
+        left_rows.resize(height, Line::default());
+        right_rows.resize(height, Line::default());
+        left.extend(left_rows);
+        right.extend(right_rows);
+    }
+    (left, right)
+}
+
+/// One prompt line as panel rows: the change marker, then prose wrapped at
+/// words or highlighted code wrapped at the width
+fn side_rows(
+    text: &str,
+    prefix: &'static str,
+    style: Style,
+    changed: bool,
+    width: usize,
+    code: &mut CodeBlocks,
+) -> Vec<Line<'static>> {
+    let text_width = width.saturating_sub(prefix.len()).max(1);
+    let rows: Vec<Row> = match code.line(text) {
+        Some(pieces) => {
+            // Unchanged code stays dimmed like unchanged prose
+            let pieces = pieces
+                .into_iter()
+                .map(|(piece_style, piece)| {
+                    let piece_style = if changed {
+                        piece_style
+                    } else {
+                        piece_style.add_modifier(Modifier::DIM)
+                    };
+                    (piece_style, piece)
+                })
+                .collect();
+            split_pieces(pieces, text_width)
+        }
+        None => wrap_line(text, text_width)
+            .into_iter()
+            .map(|row| vec![(None, row)])
+            .collect(),
     };
 
-    let title_style = if is_original {
-        theme.muted
-    } else {
-        theme.success
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let marker = if i == 0 { prefix } else { "  " };
+            let mut spans = vec![Span::styled(marker, style)];
+            spans.extend(
+                row.into_iter()
+                    .map(|(piece_style, piece)| Span::styled(piece, piece_style.unwrap_or(style))),
+            );
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Render one prompt full screen, word wrapped, with line numbers and
+/// highlighted code blocks
+fn render_preview(frame: &mut Frame, area: Rect, model: &Model, preview: Preview, optimized: &str) {
+    let theme = theme();
+    let icons = icons();
+
+    let (title, title_style, text) = match preview {
+        Preview::Original => (
+            format!(" {} Original ", icons.file),
+            theme.title,
+            model.original_prompt.as_str(),
+        ),
+        Preview::Optimized => (
+            format!(" {} Optimized ", icons.sparkles),
+            theme.success,
+            optimized,
+        ),
     };
 
     let block = Block::default()
@@ -89,33 +215,82 @@ fn render_diff_panel(
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    // Generate diff and highlight changes
-    let diff = TextDiff::from_lines(original, optimized);
-    let mut lines: Vec<Line> = Vec::new();
+    let lines = preview_lines(text, inner_area.width as usize);
+    let styled: Vec<Line> = lines
+        .into_iter()
+        .skip(model.scroll_offset as usize)
+        .map(|(number, row)| {
+            let mut spans = vec![Span::styled(number, theme.muted)];
+            spans.extend(
+                row.into_iter()
+                    .map(|(style, text)| Span::styled(text, style.unwrap_or(theme.text))),
+            );
+            Line::from(spans)
+        })
+        .collect();
 
-    for change in diff.iter_all_changes() {
-        let line_content = change.value().trim_end();
+    frame.render_widget(Paragraph::new(styled), inner_area);
+}
 
-        let (style, prefix) = match (change.tag(), is_original) {
-            (ChangeTag::Delete, true) => (theme.diff_removed, "- "),
-            (ChangeTag::Delete, false) => continue, // Skip deletions on optimized side
-            (ChangeTag::Insert, true) => continue,  // Skip insertions on original side
-            (ChangeTag::Insert, false) => (theme.diff_added, "+ "),
-            (ChangeTag::Equal, _) => (theme.diff_unchanged, "  "),
-        };
+/// A row's text pieces; `None` is the plain text style
+type Row = Vec<(Option<Style>, String)>;
 
-        lines.push(Line::from(vec![
-            Span::styled(prefix, style),
-            Span::styled(line_content.to_string(), style),
-        ]));
-    }
+/// Rows of `text` wrapped to `width`: the line number gutter (blank on
+/// continuation rows) and the row's pieces
+///
+/// Prose wraps at word boundaries; code wraps at the width so its
+/// indentation and spacing survive.
+fn preview_lines(text: &str, width: usize) -> Vec<(String, Row)> {
+    let count = text.lines().count().max(1);
+    let digits = count.to_string().len();
+    let wrap_width = width.saturating_sub(digits + 3).max(10);
+    let mut code = CodeBlocks::new();
 
-    // Apply scroll offset
-    let visible_lines: Vec<Line> = lines.into_iter().skip(scroll_offset as usize).collect();
-
-    let paragraph = Paragraph::new(visible_lines).wrap(Wrap { trim: false });
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let wrapped: Vec<Row> = match code.line(line) {
+            Some(pieces) => split_pieces(pieces, wrap_width),
+            None => wrap_line(line, wrap_width)
+                .into_iter()
+                .map(|row| vec![(None, row)])
+                .collect(),
+        };
+        for (j, row) in wrapped.into_iter().enumerate() {
+            let number = if j == 0 {
+                format!("{:>digits$} │ ", i + 1)
+            } else {
+                format!("{:>digits$} │ ", "")
+            };
+            rows.push((number, row));
+        }
+    }
+    rows
+}
 
-    frame.render_widget(paragraph, inner_area);
+/// Cut highlighted pieces into rows of at most `width` characters
+fn split_pieces(pieces: Vec<(Style, String)>, width: usize) -> Vec<Row> {
+    let mut rows = vec![Vec::new()];
+    let mut used = 0;
+    for (style, text) in pieces {
+        let mut rest = text.as_str();
+        while !rest.is_empty() {
+            if used == width {
+                rows.push(Vec::new());
+                used = 0;
+            }
+            let take = rest
+                .char_indices()
+                .nth(width - used)
+                .map_or(rest.len(), |(i, _)| i);
+            let (head, tail) = rest.split_at(take);
+            used += head.chars().count();
+            rows.last_mut()
+                .unwrap()
+                .push((Some(style), head.to_string()));
+            rest = tail;
+        }
+    }
+    rows
 }
 
 #[cfg(test)]
@@ -125,6 +300,7 @@ mod tests {
     use ratatui::Terminal;
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_render_diff() {
         let backend = TestBackend::new(100, 30);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -158,4 +334,62 @@ mod tests {
 
         // Should render without panic
     }
+
+    #[test]
+    fn test_preview_wraps_with_line_numbers() {
+        let rows = preview_lines("Short\n\nA line that is long enough to wrap", 20);
+        let rows: Vec<String> = rows
+            .into_iter()
+            .map(|(n, row)| row.into_iter().fold(n, |acc, (_, text)| acc + &text))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "1 │ Short",
+                "2 │ ",
+                "3 │ A line that is",
+                "  │ long enough to",
+                "  │ wrap",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preview_keeps_code_spacing() {
+        let prompt = "Keep this:\n```python\nif ok:\n    run(alpha, beta, gamma)\n```";
+        let rows: Vec<String> = preview_lines(prompt, 20)
+            .into_iter()
+            .map(|(n, row)| row.into_iter().fold(n, |acc, (_, text)| acc + &text))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "1 │ Keep this:",
+                "2 │ ```python",
+                "3 │ if ok:",
+                "4 │     run(alpha, b",
+                "  │ eta, gamma)",
+                "5 │ ```",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_rows_stay_aligned() {
+        let long = "This sentence is long enough that it wraps in a narrow panel.";
+        let (left, right) = aligned_rows(&format!("{}\nsame\n", long), "Short.\nsame\n", 24, 24);
+        assert_eq!(left.len(), right.len());
+
+        let text = |line: &Line| {
+            line.spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>()
+        };
+        let same = left.iter().position(|l| text(l) == "  same").unwrap();
+        assert!(same > 1);
+        assert_eq!(text(&right[same]), "  same");
+        assert_eq!(text(&right[0]), "+ Short.");
+        assert!(left.iter().all(|l| text(l).chars().count() <= 24));
+    }
 }
@@ -59,6 +59,7 @@ mod tests {
     use ratatui::Terminal;
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_render_error_modal() {
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -5,15 +5,18 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
+use crate::i18n::t;
+use crate::tui::keys::{keymap, Action};
 use crate::tui::model::Model;
 use crate::tui::theme::theme;
 
-/// Render the help screen
+/// Render the help screen with the effective key bindings
 pub fn render_help(frame: &mut Frame, area: Rect, _model: &Model) {
     let theme = theme();
+    let keys = keymap();
 
     let block = Block::default()
-        .title(" Keyboard Shortcuts ")
+        .title(format!(" {} ", t("help.title")))
         .title_style(theme.title)
         .borders(Borders::ALL)
         .border_style(theme.border);
@@ -21,72 +24,65 @@ pub fn render_help(frame: &mut Frame, area: Rect, _model: &Model) {
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    let help_text = vec![
-        Line::from(Span::styled("NAVIGATION", theme.primary)),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  ↑/↓        ", theme.key),
-            Span::styled("Move selection up/down", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  Enter      ", theme.key),
-            Span::styled("Expand/collapse category", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  PgUp/PgDn  ", theme.key),
-            Span::styled("Scroll content", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  Home       ", theme.key),
-            Span::styled("Go to top", theme.text),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("VIEWS", theme.primary)),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  d          ", theme.key),
-            Span::styled("Toggle diff view", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  ?          ", theme.key),
-            Span::styled("Toggle help (this screen)", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  Esc        ", theme.key),
-            Span::styled("Return to main view", theme.text),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("ACTIONS", theme.primary)),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  c          ", theme.key),
-            Span::styled("Copy optimized prompt to clipboard", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  s          ", theme.key),
-            Span::styled("Save optimized prompt to file", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  e          ", theme.key),
-            Span::styled("Open optimized prompt in editor", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  r          ", theme.key),
-            Span::styled("Re-run optimization", theme.text),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled("GENERAL", theme.primary)),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  q          ", theme.key),
-            Span::styled("Quit application", theme.text),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+C     ", theme.key),
-            Span::styled("Quit application", theme.text),
-        ]),
+    let both = |a: Action, b: Action| format!("{} {}", keys.label(a), keys.label(b));
+    let sections = [
+        (
+            t("help.navigation"),
+            vec![
+                (both(Action::Up, Action::Down), t("help.move")),
+                (keys.label(Action::Toggle), t("help.expand")),
+                (both(Action::PageUp, Action::PageDown), t("help.scroll")),
+                (keys.label(Action::Top), t("help.top")),
+            ],
+        ),
+        (
+            t("help.views"),
+            vec![
+                (keys.label(Action::Diff), t("help.diff")),
+                (keys.label(Action::Preview), t("help.preview")),
+                (keys.label(Action::Help), t("help.help")),
+                (keys.label(Action::Back), t("help.return")),
+            ],
+        ),
+        (
+            t("help.actions"),
+            vec![
+                (keys.label(Action::Copy), t("help.copy")),
+                (keys.label(Action::Save), t("help.save")),
+                (keys.label(Action::Edit), t("help.edit")),
+                (keys.label(Action::Docs), t("help.docs")),
+            ],
+        ),
+        (
+            t("help.general"),
+            vec![(keys.label(Action::Quit), t("help.quit"))],
+        ),
     ];
 
+    let width = sections
+        .iter()
+        .flat_map(|(_, rows)| rows)
+        .map(|(key, _)| console::measure_text_width(key))
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let mut help_text = Vec::new();
+    for (i, (title, rows)) in sections.into_iter().enumerate() {
+        if i > 0 {
+            help_text.push(Line::from(""));
+        }
+        help_text.push(Line::from(Span::styled(title, theme.primary)));
+        help_text.push(Line::from(""));
+        for (key, description) in rows {
+            let padding = " ".repeat(width - console::measure_text_width(&key));
+            help_text.push(Line::from(vec![
+                Span::styled(format!("  {}{}", key, padding), theme.key),
+                Span::styled(description, theme.text),
+            ]));
+        }
+    }
+
     let paragraph = Paragraph::new(help_text);
     frame.render_widget(paragraph, inner_area);
 }
@@ -117,5 +113,6 @@ mod tests {
             .map(|c| c.symbol())
             .collect::<String>();
         assert!(content.contains("NAVIGATION"));
+        assert!(content.contains("q/Ctrl+C"));
     }
 }
@@ -6,7 +6,9 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
-use crate::tui::model::Model;
+use crate::i18n::t;
+use crate::tui::keys::{keymap, Action};
+use crate::tui::model::{Model, Preview};
 use crate::tui::theme::theme;
 
 /// Render the main status bar with keyboard hints
@@ -16,36 +18,43 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, model: &Model) {
     // Determine expand/collapse label based on current selection
     let toggle_label = if model.is_current_selection_category() {
         match model.is_current_category_expanded() {
-            Some(true) => "collapse",
-            Some(false) => "expand",
-            None => "toggle",
+            Some(true) => t("hint.collapse"),
+            Some(false) => t("hint.expand"),
+            None => t("hint.toggle"),
         }
     } else {
-        "toggle"
+        t("hint.toggle")
     };
 
+    let keys = keymap();
+    let nav = format!("{}{}", keys.primary(Action::Up), keys.primary(Action::Down));
     let mut hints = vec![
-        key_hint("↑↓", "nav"),
+        key_hint(&nav, t("hint.nav")),
         Span::raw("  "),
-        key_hint("Enter", toggle_label),
+        key_hint(&keys.primary(Action::Toggle), toggle_label),
     ];
 
+    if model.issue_tree.selected_issue().is_some() {
+        hints.push(Span::raw("  "));
+        hints.push(key_hint(&keys.primary(Action::Docs), t("hint.docs")));
+    }
+
     // Add action hints if results available
     if model.has_results() {
         hints.push(Span::raw("  "));
-        hints.push(key_hint("d", "diff"));
+        hints.push(key_hint(&keys.primary(Action::Diff), t("hint.diff")));
         hints.push(Span::raw("  "));
-        hints.push(key_hint("c", "copy"));
+        hints.push(key_hint(&keys.primary(Action::Copy), t("hint.copy")));
         hints.push(Span::raw("  "));
-        hints.push(key_hint("s", "save"));
+        hints.push(key_hint(&keys.primary(Action::Save), t("hint.save")));
         hints.push(Span::raw("  "));
-        hints.push(key_hint("e", "edit"));
+        hints.push(key_hint(&keys.primary(Action::Edit), t("hint.edit")));
     }
 
     hints.push(Span::raw("  "));
-    hints.push(key_hint("?", "help"));
+    hints.push(key_hint(&keys.primary(Action::Help), t("hint.help")));
     hints.push(Span::raw("  "));
-    hints.push(key_hint("q", "quit"));
+    hints.push(key_hint(&keys.primary(Action::Quit), t("hint.quit")));
 
     // Add status message if present
     if let Some(ref msg) = model.status_message {
@@ -68,19 +77,28 @@ pub fn render_status_bar(frame: &mut Frame, area: Rect, model: &Model) {
 /// Render status bar for diff view
 pub fn render_status_bar_diff(frame: &mut Frame, area: Rect, model: &Model) {
     let theme = theme();
+    let keys = keymap();
+    let nav = format!("{}{}", keys.primary(Action::Up), keys.primary(Action::Down));
+    // Name the prompt the preview key shows next
+    let preview_label = match model.preview {
+        Some(Preview::Optimized) => t("hint.original"),
+        Some(Preview::Original) | None => t("hint.optimized"),
+    };
 
     let mut hints = vec![
-        key_hint("Esc", "return"),
+        key_hint(&keys.primary(Action::Back), t("hint.return")),
+        Span::raw("  "),
+        key_hint(&nav, t("hint.scroll")),
         Span::raw("  "),
-        key_hint("↑↓", "scroll"),
+        key_hint(&keys.primary(Action::Preview), preview_label),
         Span::raw("  "),
-        key_hint("c", "copy"),
+        key_hint(&keys.primary(Action::Copy), t("hint.copy")),
         Span::raw("  "),
-        key_hint("s", "save"),
+        key_hint(&keys.primary(Action::Save), t("hint.save")),
         Span::raw("  "),
-        key_hint("e", "edit"),
+        key_hint(&keys.primary(Action::Edit), t("hint.edit")),
         Span::raw("  "),
-        key_hint("q", "quit"),
+        key_hint(&keys.primary(Action::Quit), t("hint.quit")),
     ];
 
     // Add status message if present
@@ -103,10 +121,11 @@ pub fn render_status_bar_diff(frame: &mut Frame, area: Rect, model: &Model) {
 
 /// Render status bar for help view
 pub fn render_status_bar_help(frame: &mut Frame, area: Rect, _model: &Model) {
+    let keys = keymap();
     let hints = vec![
-        key_hint("Esc", "return"),
+        key_hint(&keys.primary(Action::Back), t("hint.return")),
         Span::raw("  "),
-        key_hint("q", "quit"),
+        key_hint(&keys.primary(Action::Quit), t("hint.quit")),
     ];
 
     let status = Paragraph::new(Line::from(hints)).style(theme().muted);
@@ -115,7 +134,7 @@ pub fn render_status_bar_help(frame: &mut Frame, area: Rect, _model: &Model) {
 }
 
 /// Create a key hint span pair
-fn key_hint<'a>(key: &'a str, action: &'a str) -> Span<'a> {
+fn key_hint<'a>(key: &str, action: &str) -> Span<'a> {
     let theme = theme();
     // Create a combined span - ratatui doesn't allow mixed styles in a single Span
     // So we return just the formatted string with the key highlighted
@@ -1,14 +1,18 @@
-//! Suggestion modal widget for vague prompt improvements
+//! Suggestion modal widget for prompt improvements
 //!
-//! Displays a modal dialog when EXP005/EXP006 issues are detected,
-//! allowing users to interactively select improvements to add.
+//! Displays a modal dialog when EXP005/EXP006 (vague prompt) or
+//! LHT001/LHT002 (long-horizon task) issues are detected, allowing users
+//! to interactively select improvements to add.
 
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 
-use crate::cli::suggest::{Suggestion, OPENENDED_SUGGESTIONS, ROLE_SUGGESTIONS};
+use crate::cli::suggest::{
+    apply_suggestions, Suggestion, LONG_HORIZON_SUGGESTIONS, OPENENDED_SUGGESTIONS,
+    ROLE_SUGGESTIONS, SUGGESTION_TRIGGERS,
+};
 use crate::tui::theme::theme;
 use crate::tui::view::centered_rect;
 
@@ -46,6 +50,17 @@ impl SuggestModalState {
             trigger_issues.push("EXP006".to_string());
         }
 
+        let lht_triggers: Vec<String> = ["LHT001", "LHT002"]
+            .into_iter()
+            .filter(|id| issues.iter().any(|i| i.id == *id))
+            .map(String::from)
+            .collect();
+
+        if !lht_triggers.is_empty() {
+            suggestions.extend(LONG_HORIZON_SUGGESTIONS.iter());
+            trigger_issues.extend(lht_triggers);
+        }
+
         // Deduplicate by id
         suggestions.sort_by_key(|s| s.id);
         suggestions.dedup_by_key(|s| s.id);
@@ -63,7 +78,9 @@ impl SuggestModalState {
 
     /// Check if any issues should trigger the modal
     pub fn should_show(issues: &[crate::Issue]) -> bool {
-        issues.iter().any(|i| i.id == "EXP005" || i.id == "EXP006")
+        issues
+            .iter()
+            .any(|i| SUGGESTION_TRIGGERS.contains(&i.id.as_str()))
     }
 
     /// Move cursor up
@@ -118,20 +135,7 @@ impl SuggestModalState {
 
     /// Apply selected suggestions to a prompt
     pub fn apply_to_prompt(&self, original: &str) -> String {
-        let selected = self.get_selected();
-        if selected.is_empty() {
-            return original.to_string();
-        }
-
-        let mut enhanced = original.trim().to_string();
-        enhanced.push('\n');
-
-        for suggestion in selected {
-            enhanced.push_str(suggestion.template);
-            enhanced.push('\n');
-        }
-
-        enhanced
+        apply_suggestions(original, &self.get_selected())
     }
 
     /// Dismiss the modal
@@ -160,10 +164,12 @@ pub fn render_suggest_modal(frame: &mut Frame, state: &SuggestModalState) {
     frame.render_widget(Clear, area);
 
     // Build title with trigger issues
-    let title = format!(
-        " ⚠ Vague Prompt Detected ({}) ",
-        state.trigger_issues.join(", ")
-    );
+    let heading = if state.trigger_issues.iter().any(|id| id.starts_with("EXP")) {
+        "Vague Prompt Detected"
+    } else {
+        "Long-Horizon Task Detected"
+    };
+    let title = format!(" ⚠ {} ({}) ", heading, state.trigger_issues.join(", "));
 
     let block = Block::default()
         .title(title)
@@ -309,6 +315,7 @@ mod tests {
             severity: Severity::Warning,
             message: "Test issue".to_string(),
             line: None,
+            confidence: 1.0,
             suggestion: None,
         }
     }
@@ -333,6 +340,17 @@ mod tests {
         assert!(state.trigger_issues.contains(&"EXP006".to_string()));
     }
 
+    #[test]
+    fn test_suggest_modal_state_from_long_horizon() {
+        let issues = vec![make_issue("LHT001"), make_issue("LHT002")];
+        let state = SuggestModalState::from_issues(&issues);
+
+        assert!(state.visible);
+        assert_eq!(state.suggestions.len(), 1);
+        assert_eq!(state.suggestions[0].id, "state_management");
+        assert_eq!(state.trigger_issues, vec!["LHT001", "LHT002"]);
+    }
+
     #[test]
     fn test_suggest_modal_not_visible_for_other_issues() {
         let issues = vec![make_issue("EXP001"), make_issue("STY001")];
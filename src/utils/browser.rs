@@ -0,0 +1,26 @@
+//! Opening links in the user's browser
+
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+/// Open `url` in the default browser without waiting for it to close
+pub fn open_url(url: &str) -> Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // The empty argument is the window title `start` expects first
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+
+    cmd.arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+        .with_context(|| format!("Failed to open a browser for {}", url))
+}
@@ -0,0 +1,236 @@
+//! Launching the user's editor
+//!
+//! Terminal editors (vim, nano, ...) block until you quit them, but GUI
+//! editors hand the file to a running window and exit at once unless given
+//! their wait flag, so copt would read the file back before you edited it.
+//! Known GUI editors get their wait flag; `--editor-wait` adds `--wait` for
+//! one copt doesn't know, or opens without waiting. Where no editor can be
+//! shown (CI, no display, no terminal), callers skip it.
+
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// `--editor-wait`: None detects from the editor
+static WAIT: Mutex<Option<bool>> = Mutex::new(None);
+
+/// GUI editors and the flag that makes each block until the file is closed
+const GUI_EDITORS: &[(&[&str], &str)] = &[
+    (
+        &["code", "code-insiders", "codium", "cursor", "windsurf"],
+        "--wait",
+    ),
+    (&["zed", "zeditor"], "--wait"),
+    (
+        &["subl", "sublime_text", "mate", "bbedit", "atom"],
+        "--wait",
+    ),
+    (&["gedit", "gnome-text-editor"], "--wait"),
+    (&["kate", "kwrite"], "--block"),
+    (&["gvim", "mvim"], "--nofork"),
+];
+
+/// Override wait flag detection for the rest of the process
+pub fn configure(wait: Option<bool>) {
+    *WAIT.lock().unwrap_or_else(|e| e.into_inner()) = wait;
+}
+
+fn wait_override() -> Option<bool> {
+    *WAIT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// How to open a file in the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Opens a window rather than taking over the terminal
+    pub gui: bool,
+    /// The process runs until the file is closed
+    pub waits: bool,
+}
+
+impl EditorCommand {
+    /// Why no editor can be opened here, if it can't
+    pub fn unavailable(&self) -> Option<&'static str> {
+        let terminal = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+        self.unavailable_in(|name| std::env::var(name).ok(), terminal)
+    }
+
+    fn unavailable_in(
+        &self,
+        var: impl Fn(&str) -> Option<String>,
+        terminal: bool,
+    ) -> Option<&'static str> {
+        if var("CI").is_some() {
+            return Some("running in CI");
+        }
+        if self.gui {
+            // macOS and Windows always have a window server for a local user
+            let needs_display = cfg!(unix) && !cfg!(target_os = "macos");
+            if needs_display && var("DISPLAY").is_none() && var("WAYLAND_DISPLAY").is_none() {
+                return Some("no display for a GUI editor");
+            }
+        } else if !terminal {
+            return Some("not attached to a terminal");
+        }
+        None
+    }
+}
+
+/// The command that opens `path` in `$EDITOR`, `$VISUAL`, or the platform default
+pub fn command(path: &Path) -> EditorCommand {
+    let editor = std::env::var("EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .unwrap_or_else(|_| {
+            if cfg!(target_os = "macos") {
+                "nano".to_string()
+            } else if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+    build(&editor, path, wait_override())
+}
+
+/// The command for `editor`, which may carry its own arguments (`code -n`)
+fn build(editor: &str, path: &Path, wait: Option<bool>) -> EditorCommand {
+    // A path with spaces ("/Applications/Visual Studio Code.app/...") is one program
+    let (program, mut args) = if Path::new(editor).is_file() {
+        (editor.to_string(), Vec::new())
+    } else {
+        let mut words = editor.split_whitespace().map(str::to_string);
+        let program = words.next().unwrap_or_else(|| editor.to_string());
+        (program, words.collect())
+    };
+
+    let name = Path::new(&program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&program)
+        .to_lowercase();
+    let name = name.strip_suffix(".exe").unwrap_or(&name);
+    // Zed's bundled launcher is `.../Zed.app/Contents/MacOS/cli`
+    let name = if name == "cli" && program.to_lowercase().contains("zed") {
+        "zed"
+    } else {
+        name
+    };
+    let known = GUI_EDITORS
+        .iter()
+        .find(|(names, _)| names.contains(&name))
+        .map(|(_, flag)| *flag);
+    let gui = known.is_some()
+        || program.to_lowercase().contains("visual studio code")
+        || program.to_lowercase().contains("zed.app");
+
+    let flag = match (wait, known) {
+        (Some(false), _) => None,
+        (_, Some(flag)) => Some(flag),
+        // Assumes the common spelling for an editor we don't know
+        (Some(true), None) => Some("--wait"),
+        (None, None) if gui => Some("--wait"),
+        (None, None) => None,
+    };
+    let has_wait_flag = |flag: &str| args.iter().any(|a| a == flag || a == "-w");
+    let waits = match flag {
+        Some(flag) => {
+            if !has_wait_flag(flag) {
+                args.push(flag.to_string());
+            }
+            true
+        }
+        // Terminal editors block on their own; so does a GUI editor given -w in $EDITOR
+        None => !gui || args.iter().any(|a| a == "-w" || a.starts_with("--wait")),
+    };
+    args.push(path.to_string_lossy().to_string());
+
+    EditorCommand {
+        program,
+        args,
+        gui,
+        waits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn args(command: &EditorCommand) -> Vec<&str> {
+        command.args.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_gui_editors_get_their_wait_flag() {
+        let path = Path::new("p.md");
+        let code = build("code", path, None);
+        assert_eq!(args(&code), ["--wait", "p.md"]);
+        assert!(code.gui && code.waits);
+
+        assert_eq!(args(&build("kate", path, None)), ["--block", "p.md"]);
+        assert_eq!(
+            args(&build("/usr/local/bin/subl -n", path, None)),
+            ["-n", "--wait", "p.md"]
+        );
+        // Already in $EDITOR
+        assert_eq!(args(&build("code --wait", path, None)), ["--wait", "p.md"]);
+        assert_eq!(
+            args(&build(
+                "/Applications/Zed.app/Contents/MacOS/cli",
+                path,
+                None
+            )),
+            ["--wait", "p.md"]
+        );
+
+        let vim = build("vim", path, None);
+        assert_eq!(args(&vim), ["p.md"]);
+        assert!(!vim.gui && vim.waits);
+    }
+
+    #[test]
+    fn test_editor_wait_overrides_detection() {
+        let path = Path::new("p.md");
+        let code = build("code", path, Some(false));
+        assert_eq!(args(&code), ["p.md"]);
+        assert!(!code.waits);
+        assert_eq!(
+            args(&build("my-editor", path, Some(true))),
+            ["--wait", "p.md"]
+        );
+    }
+
+    #[test]
+    fn test_headless_environments_have_no_editor() {
+        let env = |pairs: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            move |name: &str| vars.get(name).cloned()
+        };
+        let path = Path::new("p.md");
+        let vim = build("vim", path, None);
+        assert_eq!(vim.unavailable_in(env(&[]), true), None);
+        assert_eq!(
+            vim.unavailable_in(env(&[]), false),
+            Some("not attached to a terminal")
+        );
+        assert_eq!(
+            vim.unavailable_in(env(&[("CI", "true")]), true),
+            Some("running in CI")
+        );
+
+        let code = build("code", path, None);
+        assert_eq!(code.unavailable_in(env(&[("DISPLAY", ":0")]), false), None);
+        if cfg!(target_os = "linux") {
+            assert_eq!(
+                code.unavailable_in(env(&[]), true),
+                Some("no display for a GUI editor")
+            );
+        }
+    }
+}
@@ -5,7 +5,16 @@
 #![allow(dead_code)]
 
 use anyhow::{Context, Result};
-use std::path::Path;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::error::CoptError;
+
+/// Temp files of atomic writes, and reserved names, still in progress
+static PARTIAL_WRITES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
 
 /// Read a prompt from a file
 ///
@@ -42,10 +51,167 @@ pub fn write_prompt_file<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
         }
     }
 
-    std::fs::write(path, content)
+    write_atomic(path, content)
         .with_context(|| format!("Failed to write prompt file: {}", path.display()))
 }
 
+/// Write a file atomically: write a sibling temp file, then rename it over `path`
+///
+/// Readers see either the old file or the complete new one, never a partial
+/// write. A symlink at `path` is followed, so the file it points to is
+/// replaced and the link kept, and the new file gets the old one's
+/// permissions. Temp files of writes cut short by Ctrl-C are removed by
+/// [`remove_partial_writes`].
+pub fn write_atomic<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
+    let path = path.as_ref();
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = target
+        .file_name()
+        .with_context(|| format!("Not a file path: {}", path.display()))?;
+    let temp = target.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let permissions = std::fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let partial = || PARTIAL_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    partial().push(temp.clone());
+    let result = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            if let Some(permissions) = permissions {
+                file.set_permissions(permissions)?;
+            }
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp, &target));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    // A name claimed by `reserve_output_path` is now a real file
+    partial().retain(|p| p != &temp && (result.is_err() || p != path));
+
+    result.with_context(|| format!("Failed to write: {}", path.display()))
+}
+
+/// Remove temp files left by atomic writes that were interrupted
+pub fn remove_partial_writes() {
+    let mut partial = PARTIAL_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    for temp in partial.drain(..) {
+        let _ = std::fs::remove_file(temp);
+    }
+}
+
+/// File name for an auto-saved output: `{prefix}_{timestamp}_{hash}.{ext}`
+///
+/// The short content hash keeps runs that finish in the same second apart
+/// unless they produced identical output.
+pub fn output_file_name(prefix: &str, timestamp: &str, content: &str, ext: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    let hash: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}_{}_{}.{}", prefix, timestamp, hash, ext)
+}
+
+/// Claim a unique path for `name` in `dir`
+///
+/// The name is created empty with `create_new`, so two processes can never
+/// claim the same one; on a clash `-2`, `-3`, … is appended to the stem.
+/// Write the real content with [`write_atomic`]. A claimed name that is
+/// never written is removed by [`remove_partial_writes`] on Ctrl-C.
+pub fn reserve_output_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let name = Path::new(name);
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = name
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    for n in 1.. {
+        let candidate = match n {
+            1 => dir.join(format!("{}{}", stem, ext)),
+            n => dir.join(format!("{}-{}{}", stem, n, ext)),
+        };
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(_) => {
+                PARTIAL_WRITES
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(candidate.clone());
+                return Ok(candidate);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create: {}", candidate.display()))
+            }
+        }
+    }
+    unreachable!("ran out of output names")
+}
+
+/// Exclusive lock held by a `{path}.lock` file, released on drop
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+}
+
+impl LockFile {
+    /// Lock files older than this are left over from a crashed run
+    const STALE_AFTER: Duration = Duration::from_secs(30);
+
+    /// Wait up to `timeout` to take the lock for `path`
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let mut lock = path.as_os_str().to_owned();
+        lock.push(".lock");
+        let lock = PathBuf::from(lock);
+        let started = std::time::Instant::now();
+
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock)
+            {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path: lock });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = std::fs::metadata(&lock)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| SystemTime::now().duration_since(t).ok())
+                        .is_some_and(|age| age > Self::STALE_AFTER);
+                    if stale {
+                        let _ = std::fs::remove_file(&lock);
+                    } else if started.elapsed() >= timeout {
+                        return Err(CoptError::Io(format!(
+                            "Timed out waiting for lock: {}",
+                            lock.display()
+                        ))
+                        .into());
+                    } else {
+                        std::thread::sleep(Duration::from_millis(20));
+                    }
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to lock: {}", lock.display()))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 /// Check if a file exists and is readable
 pub fn file_exists<P: AsRef<Path>>(path: P) -> bool {
     let path = path.as_ref();
@@ -94,32 +260,64 @@ pub fn format_file_size(bytes: u64) -> String {
     }
 }
 
-/// Read multiple prompt files from a directory
-pub fn read_prompts_from_dir<P: AsRef<Path>>(dir: P) -> Result<Vec<(String, String)>> {
+/// Read the prompt files under a directory, subdirectories included
+///
+/// Returns each file's path relative to `dir` (`/`-separated) with its
+/// content, sorted by path. Hidden files and directories are skipped, and
+/// symlinked directories aren't followed. `exclude` is skipped too, so a
+/// batch never reads back its own output directory. A file that can't be
+/// read, or isn't UTF-8, comes back with its error in place of the content.
+pub fn read_prompts_from_dir<P: AsRef<Path>>(
+    dir: P,
+    exclude: Option<&Path>,
+) -> Result<Vec<(String, Result<String>)>> {
     let dir = dir.as_ref();
     let mut prompts = Vec::new();
 
     if !dir.is_dir() {
-        anyhow::bail!("Path is not a directory: {}", dir.display());
+        return Err(CoptError::Io(format!("Path is not a directory: {}", dir.display())).into());
     }
 
+    let exclude = exclude.and_then(|p| p.canonicalize().ok());
+    collect_prompts(dir, dir, exclude.as_deref(), &mut prompts)?;
+    prompts.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(prompts)
+}
+
+fn collect_prompts(
+    root: &Path,
+    dir: &Path,
+    exclude: Option<&Path>,
+    prompts: &mut Vec<(String, Result<String>)>,
+) -> Result<()> {
     for entry in std::fs::read_dir(dir)
         .with_context(|| format!("Failed to read directory: {}", dir.display()))?
     {
         let entry = entry?;
         let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
 
-        if path.is_file() && is_prompt_file(&path) {
-            let content = read_prompt_file(&path)?;
+        if entry.file_type()?.is_dir() {
+            if exclude.is_some() && path.canonicalize().ok().as_deref() == exclude {
+                continue;
+            }
+            collect_prompts(root, &path, exclude, prompts)?;
+        } else if path.is_file() && is_prompt_file(&path) {
+            let content = read_prompt_file(&path);
             let name = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string());
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
             prompts.push((name, content));
         }
     }
-
-    Ok(prompts)
+    Ok(())
 }
 
 /// Async version of read_prompt_file using tokio
@@ -143,9 +341,8 @@ pub async fn write_prompt_file_async<P: AsRef<Path>>(path: P, content: &str) ->
         }
     }
 
-    tokio::fs::write(&path, content)
-        .await
-        .with_context(|| format!("Failed to write prompt file: {}", path.display()))
+    let content = content.to_string();
+    tokio::task::spawn_blocking(move || write_atomic(&path, &content)).await?
 }
 
 #[cfg(test)]
@@ -174,6 +371,88 @@ mod tests {
         assert_eq!(content, "Optimized prompt");
     }
 
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("optimized.txt");
+        std::fs::write(&file_path, "old").unwrap();
+
+        write_atomic(&file_path, "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "new");
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        // A failed write leaves neither the target nor a temp file behind
+        assert!(write_atomic(dir.path().join("missing/out.txt"), "x").is_err());
+        assert!(!PARTIAL_WRITES
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|p| p.starts_with(dir.path())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_symlinks_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let real = dir.path().join("prompts/support.md");
+        std::fs::create_dir_all(real.parent().unwrap()).unwrap();
+        std::fs::write(&real, "old").unwrap();
+        std::fs::set_permissions(&real, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let link = dir.path().join("support.md");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "new").unwrap();
+
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "new");
+        let mode = std::fs::metadata(&real).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_reserved_output_names_never_collide() {
+        let dir = tempdir().unwrap();
+        let name = output_file_name("optimized", "20250101_120000", "same output", "txt");
+        assert!(name.starts_with("optimized_20250101_120000_"));
+        assert_ne!(
+            name,
+            output_file_name("optimized", "20250101_120000", "other output", "txt")
+        );
+
+        let first = reserve_output_path(dir.path(), &name).unwrap();
+        let second = reserve_output_path(dir.path(), &name).unwrap();
+        assert_eq!(first.file_name().unwrap().to_string_lossy(), name);
+        assert!(second
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .ends_with("-2.txt"));
+
+        // Writing the reserved name clears it from the Ctrl-C cleanup list
+        write_atomic(&first, "content").unwrap();
+        assert!(!PARTIAL_WRITES.lock().unwrap().contains(&first));
+        assert!(PARTIAL_WRITES.lock().unwrap().contains(&second));
+        PARTIAL_WRITES.lock().unwrap().retain(|p| p != &second);
+    }
+
+    #[test]
+    fn test_lock_file_is_exclusive() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("metrics.jsonl");
+
+        let lock = LockFile::acquire(&path, Duration::ZERO).unwrap();
+        assert!(dir.path().join("metrics.jsonl.lock").exists());
+        assert!(LockFile::acquire(&path, Duration::from_millis(50)).is_err());
+
+        drop(lock);
+        assert!(!dir.path().join("metrics.jsonl.lock").exists());
+        assert!(LockFile::acquire(&path, Duration::ZERO).is_ok());
+    }
+
     #[test]
     fn test_file_exists() {
         let file = NamedTempFile::new().unwrap();
@@ -196,6 +475,44 @@ mod tests {
         assert!(!is_prompt_file("image.png"));
     }
 
+    #[test]
+    fn test_read_prompts_from_dir_recurses() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("agents/review")).unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join("b.md"), "B").unwrap();
+        std::fs::write(dir.path().join("agents/review/a.txt"), "A").unwrap();
+        std::fs::write(dir.path().join("agents/logo.png"), "").unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref").unwrap();
+
+        std::fs::write(dir.path().join("agents/binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let read = |exclude: Option<&Path>| {
+            read_prompts_from_dir(dir.path(), exclude)
+                .unwrap()
+                .into_iter()
+                .filter_map(|(name, content)| Some((name, content.ok()?)))
+                .collect::<Vec<_>>()
+        };
+        let prompts = read_prompts_from_dir(dir.path(), None).unwrap();
+        assert_eq!(prompts.len(), 3);
+        assert_eq!(prompts[0].0, "agents/binary.md");
+        assert!(prompts[0].1.is_err());
+        assert_eq!(
+            read(None),
+            [
+                ("agents/review/a.txt".to_string(), "A".to_string()),
+                ("b.md".to_string(), "B".to_string())
+            ]
+        );
+        assert!(read_prompts_from_dir(dir.path().join("b.md"), None).is_err());
+
+        assert_eq!(
+            read(Some(&dir.path().join("agents"))),
+            [("b.md".to_string(), "B".to_string())]
+        );
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(format_file_size(500), "500 B");
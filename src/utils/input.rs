@@ -0,0 +1,148 @@
+//! Input guard for prompts read from files and stdin
+//!
+//! A prompt is text of modest size. Anything else (an image passed to `-f`
+//! by mistake, a multi-megabyte log piped in) is rejected before it reaches
+//! the analyzer or an LLM call, with a message saying what to do instead.
+
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+use super::file::format_file_size;
+use crate::error::CoptError;
+
+/// How far into the input to look for NUL bytes
+const SNIFF_LEN: usize = 8192;
+
+/// Whether `bytes` look like binary data rather than text
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(SNIFF_LEN)].contains(&0)
+}
+
+/// Decode prompt bytes read from `source`, rejecting binary data and invalid UTF-8
+pub fn decode(bytes: Vec<u8>, source: &str) -> Result<String> {
+    if looks_binary(&bytes) {
+        return Err(CoptError::Analysis(format!(
+            "{} looks like a binary file, not a prompt\n\n\
+            Pass a text file with -f, or pipe the prompt on stdin.",
+            source
+        ))
+        .into());
+    }
+    String::from_utf8(bytes).map_err(|e| {
+        CoptError::Analysis(format!(
+            "{} is not valid UTF-8 text (first invalid byte at offset {})\n\n\
+            Re-save it as UTF-8 and try again.",
+            source,
+            e.utf8_error().valid_up_to()
+        ))
+        .into()
+    })
+}
+
+/// Reject inputs larger than `max_bytes`
+pub fn check_size(len: u64, max_bytes: u64, source: &str) -> Result<()> {
+    if len > max_bytes {
+        return Err(too_large(format!(
+            "{} is {}, over the {} input limit",
+            source,
+            format_file_size(len),
+            format_file_size(max_bytes)
+        )));
+    }
+    Ok(())
+}
+
+/// Read a prompt file, checking its size before loading it
+pub async fn read_file(path: &Path, max_bytes: u64) -> Result<String> {
+    let source = path.display().to_string();
+    let read_error = || format!("Failed to read file: {}", source);
+
+    let len = tokio::fs::metadata(path)
+        .await
+        .with_context(read_error)?
+        .len();
+    check_size(len, max_bytes, &source)?;
+
+    let bytes = tokio::fs::read(path).await.with_context(read_error)?;
+    decode(bytes, &source)
+}
+
+/// [`read_file`] for callers without an async runtime
+pub fn read_file_blocking(path: &Path, max_bytes: u64) -> Result<String> {
+    let source = path.display().to_string();
+    let read_error = || format!("Failed to read file: {}", source);
+
+    let len = std::fs::metadata(path).with_context(read_error)?.len();
+    check_size(len, max_bytes, &source)?;
+
+    let bytes = std::fs::read(path).with_context(read_error)?;
+    decode(bytes, &source)
+}
+
+/// Read a prompt from a stream, giving up once it grows past `max_bytes`
+pub fn read_limited(reader: impl Read, max_bytes: u64, source: &str) -> Result<String> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read from {}", source))?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(too_large(format!(
+            "{} is over the {} input limit",
+            source,
+            format_file_size(max_bytes)
+        )));
+    }
+    decode(bytes, source)
+}
+
+fn too_large(problem: String) -> anyhow::Error {
+    CoptError::Analysis(format!(
+        "{}\n\n\
+        Prompts are rarely this large; check that this is the right input.\n\
+        To allow it, raise max_bytes under [input] in the config file.",
+        problem
+    ))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_binary_and_invalid_utf8() {
+        assert!(!looks_binary(b"Summarize the report"));
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"));
+
+        let err = decode(b"GIF89a\x00\x01".to_vec(), "logo.gif").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("logo.gif looks like a binary file"));
+
+        let err = decode(b"caf\xe9".to_vec(), "stdin").unwrap_err();
+        assert!(err.to_string().contains("offset 3"));
+
+        assert_eq!(decode("café".as_bytes().to_vec(), "stdin").unwrap(), "café");
+    }
+
+    #[test]
+    fn test_size_limits() {
+        assert!(check_size(1024, 1024, "prompt.txt").is_ok());
+        let err = check_size(2 * 1024 * 1024, 1024, "dump.log").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("dump.log is 2.0 MB, over the 1.0 KB input limit"));
+        assert!(err.to_string().contains("raise max_bytes under [input]"));
+
+        assert_eq!(
+            read_limited(&b"short prompt"[..], 64, "stdin").unwrap(),
+            "short prompt"
+        );
+        let err = read_limited(&[b'a'; 65][..], 64, "stdin").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("stdin is over the 64 B input limit"));
+    }
+}
@@ -0,0 +1,135 @@
+//! JSON helpers
+//!
+//! A small JSONPath subset for pulling values out of JSON documents:
+//! `$`, `.field`, `["field"]`, and `[index]`.
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// A single step in a parsed JSON path
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a JSON path like `$.items[0].name` into segments
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let path = path.trim();
+    let rest = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                let key: String = chars[start..i].iter().collect();
+                if key.is_empty() {
+                    anyhow::bail!("Empty field name in JSON path: {}", path);
+                }
+                segments.push(Segment::Key(key));
+            }
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|p| p + i)
+                    .ok_or_else(|| anyhow::anyhow!("Unclosed '[' in JSON path: {}", path))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+
+                if let Some(key) = inner
+                    .strip_prefix('"')
+                    .and_then(|s| s.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+                {
+                    segments.push(Segment::Key(key.to_string()));
+                } else {
+                    let index = inner.parse::<usize>().map_err(|_| {
+                        anyhow::anyhow!("Invalid index '{}' in JSON path: {}", inner, path)
+                    })?;
+                    segments.push(Segment::Index(index));
+                }
+                i = close + 1;
+            }
+            _ if i == 0 => {
+                // Allow paths without a leading `$.` (e.g. `items[0].name`)
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                segments.push(Segment::Key(chars[start..i].iter().collect()));
+            }
+            c => anyhow::bail!("Unexpected '{}' in JSON path: {}", c, path),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Select the value at `path`, or None if any step is missing
+pub fn select_json_path<'a>(value: &'a Value, path: &str) -> Result<Option<&'a Value>> {
+    let mut current = value;
+
+    for segment in parse_path(path)? {
+        let next = match segment {
+            Segment::Key(key) => current.get(key.as_str()),
+            Segment::Index(index) => current.get(index),
+        };
+        match next {
+            Some(v) => current = v,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_json_path() {
+        let doc = json!({
+            "status": "ok",
+            "items": [{"name": "a"}, {"name": "b"}],
+            "odd key": 1
+        });
+
+        assert_eq!(select_json_path(&doc, "$").unwrap(), Some(&doc));
+        assert_eq!(
+            select_json_path(&doc, "$.status").unwrap(),
+            Some(&json!("ok"))
+        );
+        assert_eq!(
+            select_json_path(&doc, "$.items[1].name").unwrap(),
+            Some(&json!("b"))
+        );
+        assert_eq!(
+            select_json_path(&doc, "items[0].name").unwrap(),
+            Some(&json!("a"))
+        );
+        assert_eq!(
+            select_json_path(&doc, "$[\"odd key\"]").unwrap(),
+            Some(&json!(1))
+        );
+        assert_eq!(select_json_path(&doc, "$.missing").unwrap(), None);
+        assert_eq!(select_json_path(&doc, "$.items[5]").unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_json_path() {
+        let doc = json!({});
+        assert!(select_json_path(&doc, "$.items[").is_err());
+        assert!(select_json_path(&doc, "$.items[x]").is_err());
+        assert!(select_json_path(&doc, "$..a").is_err());
+    }
+}
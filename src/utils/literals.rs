@@ -0,0 +1,589 @@
+//! String literals in source code
+//!
+//! Prompts often live in application code rather than prompt files: a
+//! Python triple-quoted `SYSTEM_PROMPT`, a TypeScript template literal, a
+//! Rust raw string. [`extract`] finds the string literals in a Python,
+//! JavaScript/TypeScript, or Rust file with a small lexer (comments and
+//! each language's quoting rules, not a full parser), decodes their
+//! escapes, and records the source line of every line of the text, so an
+//! issue found in a literal points at the right line of the file.
+//! [`encode`] goes the other way, quoting new text for an existing literal.
+
+use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Languages whose string literals can be extracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceLanguage {
+    Python,
+    /// JavaScript and TypeScript
+    JavaScript,
+    Rust,
+}
+
+impl SourceLanguage {
+    /// The language of a source file, by extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Self::JavaScript),
+            "rs" => Some(Self::Rust),
+            _ => None,
+        }
+    }
+}
+
+/// A string literal and where it sits in the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    /// Variable, constant, key, or keyword argument the literal is assigned to
+    pub name: Option<String>,
+    /// Opening delimiter with any prefix (`f"""`, `r#"`, `` ` ``)
+    pub open: String,
+    /// Closing delimiter (`"""`, `"#`, `` ` ``)
+    pub close: String,
+    /// Escapes aren't processed (raw strings)
+    pub raw: bool,
+    /// Byte range of the content, between the delimiters
+    pub span: Range<usize>,
+    /// A Python docstring rather than a value
+    pub docstring: bool,
+    /// Decoded content
+    pub text: String,
+    /// Source line (1-based) of each line of `text`
+    pub lines: Vec<usize>,
+}
+
+impl Literal {
+    /// Source line the content starts on
+    pub fn line(&self) -> usize {
+        self.lines[0]
+    }
+
+    /// Source line of a 1-based line of `text`
+    pub fn source_line(&self, line: usize) -> usize {
+        self.lines
+            .get(line.saturating_sub(1))
+            .or(self.lines.last())
+            .copied()
+            .unwrap_or(1)
+    }
+}
+
+/// What an assignment, key, or keyword argument before a literal looks like
+static NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"["']?([A-Za-z_][A-Za-z0-9_]*)["']?\s*(?:(?::[^=]*)?=|:)\s*[(\[]?\s*$"#).unwrap()
+});
+
+/// Every string literal in `source`, in order
+pub fn extract(source: &str, language: SourceLanguage) -> Vec<Literal> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+
+    let bounds = match language {
+        SourceLanguage::Python => python_bounds(source.as_bytes()),
+        SourceLanguage::JavaScript => javascript_bounds(source.as_bytes()),
+        SourceLanguage::Rust => rust_bounds(source),
+    };
+
+    bounds
+        .into_iter()
+        .map(|b| {
+            let line_start = line_starts[line_of(b.start) - 1];
+            let before = &source[line_start..b.start];
+            let (text, lines) = decode(
+                &source[b.content.clone()],
+                b.raw,
+                language,
+                line_of(b.content.start),
+            );
+            Literal {
+                name: NAME.captures(before).map(|c| c[1].to_string()),
+                open: source[b.start..b.content.start].to_string(),
+                close: source[b.content.end..b.end].to_string(),
+                raw: b.raw,
+                docstring: language == SourceLanguage::Python
+                    && before.trim().is_empty()
+                    && is_docstring_position(&source[..line_start]),
+                span: b.content,
+                text,
+                lines,
+            }
+        })
+        .collect()
+}
+
+/// A literal's delimiters as byte offsets
+struct Bounds {
+    /// Start of the prefix or opening delimiter
+    start: usize,
+    content: Range<usize>,
+    /// End of the closing delimiter
+    end: usize,
+    raw: bool,
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Offset after the next `\n` (or the end)
+fn skip_line(b: &[u8], i: usize) -> usize {
+    b[i..]
+        .iter()
+        .position(|&c| c == b'\n')
+        .map_or(b.len(), |p| i + p + 1)
+}
+
+/// End of a quoted run starting at `i` (just past the opening quote):
+/// the offset of the closing quote, or `None` when a single-line string
+/// reaches the end of the line
+fn find_close(b: &[u8], mut i: usize, quote: &[u8], multiline: bool) -> Option<usize> {
+    while i < b.len() {
+        if b[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if b[i..].starts_with(quote) {
+            return Some(i);
+        }
+        if b[i] == b'\n' && !multiline {
+            return None;
+        }
+        i += 1;
+    }
+    None
+}
+
+fn python_bounds(b: &[u8]) -> Vec<Bounds> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'#' => i = skip_line(b, i),
+            q @ (b'"' | b'\'') => {
+                // String prefixes: r, b, u, f and two-letter combinations
+                let mut start = i;
+                while start > 0 && i - start < 2 && b"rRbBuUfF".contains(&b[start - 1]) {
+                    start -= 1;
+                }
+                if start > 0 && is_ident(b[start - 1]) {
+                    start = i;
+                }
+                let raw = b[start..i].iter().any(|c| matches!(c, b'r' | b'R'));
+                let triple = b[i..].starts_with(&[q, q, q]);
+                let quote: &[u8] = if triple { &[q, q, q] } else { &[q] };
+                let content = i + quote.len();
+                match find_close(b, content, quote, triple) {
+                    Some(close) => {
+                        found.push(Bounds {
+                            start,
+                            content: content..close,
+                            end: close + quote.len(),
+                            raw,
+                        });
+                        i = close + quote.len();
+                    }
+                    None => i = skip_line(b, i),
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+fn javascript_bounds(b: &[u8]) -> Vec<Bounds> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'/' if b.get(i + 1) == Some(&b'/') => i = skip_line(b, i),
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                i = find_sequence(b, i + 2, b"*/").map_or(b.len(), |p| p + 2)
+            }
+            q @ (b'"' | b'\'') => match find_close(b, i + 1, &[q], false) {
+                Some(close) => {
+                    found.push(Bounds {
+                        start: i,
+                        content: i + 1..close,
+                        end: close + 1,
+                        raw: false,
+                    });
+                    i = close + 1;
+                }
+                None => i = skip_line(b, i),
+            },
+            b'`' => {
+                let close = template_close(b, i + 1);
+                if close < b.len() {
+                    found.push(Bounds {
+                        start: i,
+                        content: i + 1..close,
+                        end: close + 1,
+                        raw: false,
+                    });
+                }
+                i = close + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// Closing backtick of a template literal, stepping over `${…}`
+fn template_close(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b'`' => return i,
+            b'$' if b.get(i + 1) == Some(&b'{') => {
+                let mut depth = 0;
+                while i < b.len() {
+                    match b[i] {
+                        b'{' => depth += 1,
+                        b'}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    b.len()
+}
+
+fn find_sequence(b: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    b.get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| from + p)
+}
+
+fn rust_bounds(source: &str) -> Vec<Bounds> {
+    let b = source.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < b.len() {
+        let starts_token = i == 0 || !is_ident(b[i - 1]);
+        match b[i] {
+            b'/' if b.get(i + 1) == Some(&b'/') => i = skip_line(b, i),
+            b'/' if b.get(i + 1) == Some(&b'*') => {
+                i = find_sequence(b, i + 2, b"*/").map_or(b.len(), |p| p + 2)
+            }
+            // Raw strings: r"…", r#"…"#, br#"…"#
+            b'r' | b'b' if starts_token => {
+                let r = if b[i] == b'b' { i + 1 } else { i };
+                if b.get(r) != Some(&b'r') {
+                    i += 1;
+                    continue;
+                }
+                let hashes = b[r + 1..].iter().take_while(|&&c| c == b'#').count();
+                let quote = r + 1 + hashes;
+                if b.get(quote) != Some(&b'"') {
+                    i += 1;
+                    continue;
+                }
+                let mut closer = vec![b'"'];
+                closer.extend(std::iter::repeat_n(b'#', hashes));
+                match find_sequence(b, quote + 1, &closer) {
+                    Some(close) => {
+                        found.push(Bounds {
+                            start: i,
+                            content: quote + 1..close,
+                            end: close + closer.len(),
+                            raw: true,
+                        });
+                        i = close + closer.len();
+                    }
+                    None => i = b.len(),
+                }
+            }
+            b'"' => {
+                let start = if i > 0 && b[i - 1] == b'b' && (i == 1 || !is_ident(b[i - 2])) {
+                    i - 1
+                } else {
+                    i
+                };
+                match find_close(b, i + 1, b"\"", true) {
+                    Some(close) => {
+                        found.push(Bounds {
+                            start,
+                            content: i + 1..close,
+                            end: close + 1,
+                            raw: false,
+                        });
+                        i = close + 1;
+                    }
+                    None => i = b.len(),
+                }
+            }
+            // A char literal ('"', '\'') or a lifetime ('a)
+            b'\'' => {
+                if b.get(i + 1) == Some(&b'\\') {
+                    i = find_sequence(b, i + 3, b"'").map_or(b.len(), |p| p + 1);
+                } else {
+                    let width = source[i + 1..].chars().next().map_or(1, char::len_utf8);
+                    i += if b.get(i + 1 + width) == Some(&b'\'') {
+                        width + 2
+                    } else {
+                        1
+                    };
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// Whether a bare string starting after `before` opens a Python module,
+/// class, or function body
+fn is_docstring_position(before: &str) -> bool {
+    match before
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        None => true,
+        Some(line) => line.ends_with(':'),
+    }
+}
+
+/// Decode a literal's content, recording the source line of each text line
+fn decode(
+    content: &str,
+    raw: bool,
+    language: SourceLanguage,
+    mut line: usize,
+) -> (String, Vec<usize>) {
+    let mut text = String::with_capacity(content.len());
+    let mut lines = vec![line];
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {}
+            '\n' => {
+                line += 1;
+                text.push('\n');
+                lines.push(line);
+            }
+            '\\' if !raw => match chars.next() {
+                Some('n') => {
+                    text.push('\n');
+                    lines.push(line);
+                }
+                Some('t') => text.push('\t'),
+                Some('r') => {}
+                Some('\r') => {}
+                // Line continuation; Rust also drops the next line's indentation
+                Some('\n') => {
+                    line += 1;
+                    if language == SourceLanguage::Rust {
+                        while let Some(&next) = chars.peek() {
+                            if !next.is_whitespace() {
+                                break;
+                            }
+                            if next == '\n' {
+                                line += 1;
+                            }
+                            chars.next();
+                        }
+                    }
+                }
+                Some(q @ ('"' | '\'' | '`' | '\\' | '$')) => text.push(q),
+                Some(other) => {
+                    text.push('\\');
+                    text.push(other);
+                }
+                None => text.push('\\'),
+            },
+            c => text.push(c),
+        }
+    }
+    (text, lines)
+}
+
+/// Escapes [`decode`] leaves as written, per language
+fn kept_escape(language: SourceLanguage, c: char) -> bool {
+    match language {
+        SourceLanguage::Python => {
+            matches!(c, 'x' | 'u' | 'U' | 'N' | 'a' | 'b' | 'f' | 'v' | '0'..='7')
+        }
+        SourceLanguage::JavaScript => matches!(c, 'x' | 'u' | 'b' | 'f' | 'v' | '0'..='7'),
+        SourceLanguage::Rust => matches!(c, 'x' | 'u' | '0'),
+    }
+}
+
+/// `text` as the source of a literal with the same prefix and quotes as
+/// `literal`, delimiters included
+///
+/// Rust raw strings get more `#`s when the text needs them; a Python raw
+/// string that can't hold the text is an error, since raw strings have no
+/// escapes.
+pub fn encode(literal: &Literal, language: SourceLanguage, text: &str) -> Result<String, String> {
+    let quote = literal.close.chars().next().unwrap_or('"');
+    let triple = literal.close.len() == 3;
+    // Whether a newline can be written as a newline
+    let multiline = match language {
+        SourceLanguage::Python => triple,
+        SourceLanguage::JavaScript => quote == '`',
+        SourceLanguage::Rust => true,
+    };
+
+    if literal.raw {
+        if language == SourceLanguage::Rust {
+            let prefix = &literal.open[..literal.open.find('r').unwrap_or(0) + 1];
+            let mut hashes = literal.close.len() - 1;
+            while text.contains(&format!("\"{}", "#".repeat(hashes))) {
+                hashes += 1;
+            }
+            let hashes = "#".repeat(hashes);
+            return Ok(format!("{}{}\"{}\"{}", prefix, hashes, text, hashes));
+        }
+        if text.contains(&literal.close) || text.ends_with(['\\', quote]) {
+            return Err(format!(
+                "the raw string can't end in {} or \\ or contain {}",
+                quote, literal.close
+            ));
+        }
+        if !multiline && text.contains('\n') {
+            return Err("the single-line raw string can't hold several lines".to_string());
+        }
+        return Ok(format!("{}{}{}", literal.open, text, literal.close));
+    }
+
+    let mut content = String::with_capacity(text.len() + 16);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.peek() {
+                Some(&next) if kept_escape(language, next) => content.push('\\'),
+                _ => content.push_str("\\\\"),
+            },
+            '\n' if !multiline => content.push_str("\\n"),
+            // A quote closes the string: always in single-quoted strings,
+            // and in triple-quoted ones when another follows or it's last
+            c if c == quote && (!triple || chars.peek().is_none_or(|&next| next == quote)) => {
+                content.push('\\');
+                content.push(c);
+            }
+            c => content.push(c),
+        }
+    }
+    Ok(format!("{}{}{}", literal.open, content, literal.close))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_literals() {
+        let source = "\"\"\"Module docstring.\"\"\"\n\
+                      # a \"comment\"\n\
+                      SYSTEM_PROMPT = f\"\"\"\n\
+                      You are a support agent.\n    Answer {topic} questions.\n\"\"\"\n\
+                      client.create(system='Be brief.\\nBe kind.', user=r\"C:\\new\")\n";
+        let literals = extract(source, SourceLanguage::Python);
+        assert_eq!(literals.len(), 4);
+
+        assert!(literals[0].docstring);
+
+        let prompt = &literals[1];
+        assert_eq!(prompt.name.as_deref(), Some("SYSTEM_PROMPT"));
+        assert_eq!(prompt.open, "f\"\"\"");
+        assert!(!prompt.docstring);
+        assert_eq!(
+            prompt.text,
+            "\nYou are a support agent.\n    Answer {topic} questions.\n"
+        );
+        assert_eq!(prompt.lines, [3, 4, 5, 6]);
+        assert_eq!(&source[prompt.span.clone()], prompt.text);
+
+        let inline = &literals[2];
+        assert_eq!(inline.name.as_deref(), Some("system"));
+        assert_eq!(inline.text, "Be brief.\nBe kind.");
+        assert_eq!(inline.lines, [7, 7]);
+
+        assert!(literals[3].raw);
+        assert_eq!(literals[3].text, "C:\\new");
+    }
+
+    #[test]
+    fn test_javascript_literals() {
+        let source = "// don't\n\
+                      /* it's \"quoted\" */\n\
+                      export const systemPrompt = `You are a reviewer.\n\
+                      Review ${files.map((f) => `${f}`).join(\", \")}.`;\n\
+                      const config = { instructions: \"Reply in JSON.\" };\n";
+        let literals = extract(source, SourceLanguage::JavaScript);
+        assert_eq!(literals.len(), 2, "{:?}", literals);
+        assert_eq!(literals[0].name.as_deref(), Some("systemPrompt"));
+        assert_eq!(literals[0].lines, [3, 4]);
+        assert_eq!(literals[1].name.as_deref(), Some("instructions"));
+        assert_eq!(literals[1].text, "Reply in JSON.");
+    }
+
+    #[test]
+    fn test_rust_literals() {
+        let source = "fn f<'a>(c: char) -> bool { c == '\"' || c == '\\'' }\n\
+                      const SYSTEM: &str = r#\"You are a \"careful\" reviewer.\n\
+                      Cite line numbers.\"#;\n\
+                      let task = \"Summarize the diff \\\n    in one paragraph.\";\n";
+        let literals = extract(source, SourceLanguage::Rust);
+        assert_eq!(literals.len(), 2, "{:?}", literals);
+
+        assert_eq!(literals[0].name.as_deref(), Some("SYSTEM"));
+        assert!(literals[0].raw);
+        assert_eq!(literals[0].open, "r#\"");
+        assert_eq!(literals[0].close, "\"#");
+        assert_eq!(
+            literals[0].text,
+            "You are a \"careful\" reviewer.\nCite line numbers."
+        );
+
+        assert_eq!(literals[1].name.as_deref(), Some("task"));
+        assert_eq!(literals[1].text, "Summarize the diff in one paragraph.");
+        assert_eq!(literals[1].source_line(1), 4);
+    }
+
+    #[test]
+    fn test_encode_round_trips() {
+        let text = "Quote \"this\" and 'that'.\nMatch \\d+ in C:\\new.\nEnd with a quote: \"";
+        for (source, language) in [
+            ("P = \"\"\"x\"\"\"\n", SourceLanguage::Python),
+            ("P = 'x'\n", SourceLanguage::Python),
+            ("const p = \"x\";\n", SourceLanguage::JavaScript),
+            ("const p = `x`;\n", SourceLanguage::JavaScript),
+            ("const P: &str = \"x\";\n", SourceLanguage::Rust),
+            ("const P: &str = r\"x\";\n", SourceLanguage::Rust),
+        ] {
+            let literal = &extract(source, language)[0];
+            let encoded = encode(literal, language, text).unwrap();
+            let reread = &extract(&encoded, language)[0];
+            assert_eq!(reread.text, text, "{}", encoded);
+        }
+
+        let raw = &extract("P = r'x'\n", SourceLanguage::Python)[0];
+        assert!(encode(raw, SourceLanguage::Python, "C:\\new").is_ok());
+        assert!(encode(raw, SourceLanguage::Python, "it's").is_err());
+        assert!(encode(raw, SourceLanguage::Python, "a\nb").is_err());
+    }
+}
@@ -3,8 +3,30 @@
 //! This module provides common utilities used across the application:
 //! - Text processing (token counting, text manipulation)
 //! - File I/O operations
+//! - Opening links in the browser
+//! - Launching the editor, with wait flags for GUI editors
+//! - Input size and binary-content checks
+//! - Phase timings for --profile
+//! - Machine-readable progress for --progress-events
+//! - Secret and PII redaction for --redact
+//! - Read-only mode for --sandbox
+//! - JSON path selection
+//! - JSON Schema validation
+//! - String literals in source code
+//! - S3 output uploads
 
+pub mod browser;
+pub mod editor;
 pub mod file;
+pub mod input;
+pub mod json;
+pub mod literals;
+pub mod profile;
+pub mod progress;
+pub mod redact;
+pub mod s3;
+pub mod sandbox;
+pub mod schema;
 pub mod text;
 
 // Re-export commonly used items
@@ -0,0 +1,168 @@
+//! Phase timings for `--profile`
+//!
+//! The analyzer, static optimizer, and LLM calls report how long each step
+//! took; the totals are printed as a table when the run ends. Recording is a
+//! no-op unless profiling was enabled, so the hooks cost nothing by default.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Timings for the current run, `None` unless `--profile` was given
+static PROFILE: Mutex<Option<Profile>> = Mutex::new(None);
+
+/// Start collecting timings
+pub fn enable() {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Profile::default());
+}
+
+/// Add `elapsed` to a step of a phase ("analyze", "transform", "llm")
+pub fn record(phase: &'static str, step: &str, elapsed: Duration) {
+    if let Some(profile) = PROFILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        profile.record(phase, step, elapsed);
+    }
+}
+
+/// Timings collected so far, if profiling is enabled
+pub fn take() -> Option<Profile> {
+    PROFILE.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Total time and call count for one step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepTiming {
+    pub calls: u32,
+    pub total: Duration,
+}
+
+/// Accumulated timings, keyed by phase and step
+#[derive(Debug, Default)]
+pub struct Profile {
+    steps: HashMap<(&'static str, String), StepTiming>,
+}
+
+impl Profile {
+    pub fn record(&mut self, phase: &'static str, step: &str, elapsed: Duration) {
+        let timing = self.steps.entry((phase, step.to_string())).or_default();
+        timing.calls += 1;
+        timing.total += elapsed;
+    }
+
+    /// Steps in phase order, slowest first within each phase
+    pub fn rows(&self) -> Vec<(&'static str, &str, StepTiming)> {
+        let phase_order = |phase: &str| match phase {
+            "analyze" => 0,
+            "transform" => 1,
+            "llm" => 2,
+            _ => 3,
+        };
+        let mut rows: Vec<_> = self
+            .steps
+            .iter()
+            .map(|((phase, step), timing)| (*phase, step.as_str(), *timing))
+            .collect();
+        rows.sort_by(|a, b| {
+            phase_order(a.0)
+                .cmp(&phase_order(b.0))
+                .then(b.2.total.cmp(&a.2.total))
+                .then(a.1.cmp(b.1))
+        });
+        rows
+    }
+
+    /// Print the timing table
+    pub fn write(&self, w: &mut impl Write) -> std::io::Result<()> {
+        writeln!(w)?;
+        writeln!(
+            w,
+            "  {:<10} {:<16} {:>5} {:>10} {:>10}",
+            "Phase", "Step", "Calls", "Total", "Avg"
+        )?;
+        writeln!(w, "  {}", "─".repeat(55))?;
+        let mut total = Duration::ZERO;
+        for (phase, step, timing) in self.rows() {
+            total += timing.total;
+            writeln!(
+                w,
+                "  {:<10} {:<16} {:>5} {:>10} {:>10}",
+                phase,
+                step,
+                timing.calls,
+                format_elapsed(timing.total),
+                format_elapsed(timing.total / timing.calls.max(1)),
+            )?;
+        }
+        writeln!(w, "  {}", "─".repeat(55))?;
+        writeln!(
+            w,
+            "  {:<33} {:>10}",
+            "Total measured",
+            format_elapsed(total)
+        )?;
+        writeln!(w)
+    }
+}
+
+/// Compact duration: "850µs", "12.4ms", "1.82s"
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let micros = elapsed.as_micros();
+    if micros < 1000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.1}ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.2}s", elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_are_grouped_by_phase_and_sorted() {
+        let mut profile = Profile::default();
+        profile.record("llm", "optimize", Duration::from_millis(900));
+        profile.record("analyze", "style", Duration::from_millis(2));
+        profile.record("analyze", "explicitness", Duration::from_millis(5));
+        profile.record("analyze", "style", Duration::from_millis(4));
+        profile.record("transform", "STY003", Duration::from_micros(300));
+
+        let rows = profile.rows();
+        let steps: Vec<_> = rows
+            .iter()
+            .map(|(phase, step, _)| (*phase, *step))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("analyze", "style"),
+                ("analyze", "explicitness"),
+                ("transform", "STY003"),
+                ("llm", "optimize"),
+            ]
+        );
+        assert_eq!(
+            rows[0].2,
+            StepTiming {
+                calls: 2,
+                total: Duration::from_millis(6)
+            }
+        );
+
+        let mut out = Vec::new();
+        profile.write(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("style"));
+        assert!(out.contains("3.0ms"));
+        assert!(out.contains("Total measured"));
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_micros(850)), "850µs");
+        assert_eq!(format_elapsed(Duration::from_micros(12_400)), "12.4ms");
+        assert_eq!(format_elapsed(Duration::from_millis(1820)), "1.82s");
+    }
+}
@@ -0,0 +1,191 @@
+//! Machine-readable progress on stderr for `--progress-events`
+//!
+//! GUI wrappers and editor plugins run the copt binary and draw their own
+//! progress UI from these events: one JSON object per line, each with an
+//! `event` name and the overall `percent`. Other stderr lines (warnings,
+//! the verbose timeline) are not JSON objects, so readers skip them.
+//!
+//! ```text
+//! {"event":"item_started","file":"a.md","index":1,"percent":0,"total":2}
+//! {"event":"phase_started","file":"a.md","percent":0,"phase":"analyze"}
+//! {"elapsed_ms":4,"event":"phase_finished","file":"a.md","percent":5,"phase":"analyze"}
+//! ...
+//! {"event":"finished","percent":100,"status":"ok"}
+//! ```
+//!
+//! Emitting is a no-op unless enabled, like [`super::profile`].
+
+use serde_json::{json, Map, Value};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// A step of an optimization, in run order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Static analysis of the input
+    Analyze,
+    /// Static fixes and the LLM rewrite
+    Optimize,
+    /// Scoring the rewrite (`--judge`, `--generate-tests`)
+    Judge,
+    /// Writing the result and its metadata
+    Save,
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Analyze => "analyze",
+            Phase::Optimize => "optimize",
+            Phase::Judge => "judge",
+            Phase::Save => "save",
+        }
+    }
+
+    /// Share of one item's progress (percent) the phase starts and ends at
+    ///
+    /// The LLM call dominates a run, so optimizing gets most of the range.
+    fn span(self) -> (f64, f64) {
+        match self {
+            Phase::Analyze => (0.0, 10.0),
+            Phase::Optimize => (10.0, 85.0),
+            Phase::Judge => (85.0, 95.0),
+            Phase::Save => (95.0, 100.0),
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The item being worked on and the running phase
+static STATE: Mutex<State> = Mutex::new(State {
+    item: None,
+    phase: None,
+});
+
+struct State {
+    /// 1-based index, total, and file of the current item in a batch
+    item: Option<(usize, usize, Option<String>)>,
+    phase: Option<(Phase, Instant)>,
+}
+
+/// Emit events for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Start item `index` (1-based) of `total`, working on `file`
+///
+/// A single prompt is item 1 of 1; the file is left out when the prompt
+/// didn't come from one.
+pub fn item(index: usize, total: usize, file: Option<String>) {
+    if !enabled() {
+        return;
+    }
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.item = Some((index, total.max(1), file));
+    state.phase = None;
+    let mut event = event(&state, "item_started", 0.0);
+    event.insert("index".into(), index.into());
+    event.insert("total".into(), total.into());
+    write(event);
+}
+
+/// `phase` of the current item started
+pub fn started(phase: Phase) {
+    if !enabled() {
+        return;
+    }
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.phase = Some((phase, Instant::now()));
+    let mut event = event(&state, "phase_started", phase.span().0);
+    event.insert("phase".into(), phase.name().into());
+    write(event);
+}
+
+/// `phase` of the current item finished
+pub fn finished(phase: Phase) {
+    if !enabled() {
+        return;
+    }
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let elapsed = match state.phase.take() {
+        Some((running, since)) if running == phase => since.elapsed(),
+        _ => Default::default(),
+    };
+    let mut event = event(&state, "phase_finished", phase.span().1);
+    event.insert("phase".into(), phase.name().into());
+    event.insert("elapsed_ms".into(), (elapsed.as_millis() as u64).into());
+    write(event);
+}
+
+/// The run ended: `ok`, `degraded` (static fixes only), or `error`
+pub fn done(status: &str, error: Option<Value>) {
+    if !enabled() {
+        return;
+    }
+    let mut event = Map::new();
+    event.insert("event".into(), "finished".into());
+    event.insert("percent".into(), 100.into());
+    event.insert("status".into(), status.into());
+    if let Some(error) = error {
+        event.insert("error".into(), error);
+    }
+    write(event);
+}
+
+/// An event with the overall percent for `item_percent` of the current item
+fn event(state: &State, name: &str, item_percent: f64) -> Map<String, Value> {
+    let (index, total, file) = match state.item {
+        Some((index, total, ref file)) => (index, total, file.as_deref()),
+        None => (1, 1, None),
+    };
+    let percent = ((index - 1) as f64 * 100.0 + item_percent) / total as f64;
+    let mut event = Map::new();
+    event.insert("event".into(), name.into());
+    event.insert("percent".into(), json!(percent.round() as u64));
+    if let Some(file) = file {
+        event.insert("file".into(), file.into());
+    }
+    event
+}
+
+fn write(event: Map<String, Value>) {
+    let line = Value::Object(event).to_string();
+    let mut stderr = std::io::stderr().lock();
+    let _ = writeln!(stderr, "{}", line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_covers_the_whole_batch() {
+        let state = State {
+            item: Some((2, 4, Some("b.md".to_string()))),
+            phase: None,
+        };
+        let event = event(&state, "phase_finished", Phase::Optimize.span().1);
+        // One item done, plus 85% of the second, out of four
+        assert_eq!(event["percent"], 46);
+        assert_eq!(event["file"], "b.md");
+
+        let single = State {
+            item: None,
+            phase: None,
+        };
+        assert_eq!(event_percent(&single, Phase::Save.span().1), 100);
+        assert_eq!(event_percent(&single, Phase::Analyze.span().0), 0);
+    }
+
+    fn event_percent(state: &State, item_percent: f64) -> u64 {
+        event(state, "x", item_percent)["percent"].as_u64().unwrap()
+    }
+}
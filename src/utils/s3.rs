@@ -0,0 +1,222 @@
+//! S3 output destination
+//!
+//! Uploads saved prompts and metadata to `s3://bucket/prefix/` using the
+//! standard AWS credential chain (the same configuration Bedrock uses),
+//! with optional server-side encryption. Uploading needs the AWS SDK from
+//! the `bedrock` feature; without it an s3:// output is a config error.
+
+#[cfg(feature = "bedrock")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "bedrock")]
+use aws_config::meta::region::RegionProviderChain;
+#[cfg(feature = "bedrock")]
+use aws_config::BehaviorVersion;
+#[cfg(feature = "bedrock")]
+use aws_sdk_s3::primitives::ByteStream;
+#[cfg(feature = "bedrock")]
+use aws_sdk_s3::types::ServerSideEncryption;
+use clap::ValueEnum;
+use std::path::Path;
+
+/// Server-side encryption mode for uploaded objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SseMode {
+    /// SSE-S3 (AES256)
+    #[value(name = "AES256", alias = "aes256")]
+    Aes256,
+    /// SSE-KMS, optionally with a specific key (--sse-kms-key-id)
+    #[value(name = "aws:kms", alias = "kms")]
+    Kms,
+}
+
+/// A parsed `s3://bucket/key` location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    /// Object key, or a prefix ending in `/` (possibly empty)
+    pub key: String,
+}
+
+impl S3Location {
+    /// Parse an `s3://` URL, returning `None` for local paths
+    pub fn parse(path: &Path) -> Option<Result<Self>> {
+        let url = path.to_str()?.strip_prefix("s3://")?;
+        let (bucket, key) = url.split_once('/').unwrap_or((url, ""));
+        if bucket.is_empty() {
+            return Some(Err(anyhow::anyhow!(
+                "Invalid S3 location '{}': missing bucket name",
+                path.display()
+            )));
+        }
+        Some(Ok(Self {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+        }))
+    }
+
+    /// Whether the location names a prefix rather than a single object
+    pub fn is_prefix(&self) -> bool {
+        self.key.is_empty() || self.key.ends_with('/')
+    }
+
+    /// Location of `file_name` next to this object (or inside this prefix)
+    pub fn sibling(&self, file_name: &str) -> Self {
+        let dir = if self.is_prefix() {
+            self.key.as_str()
+        } else {
+            self.key.rsplit_once('/').map(|(d, _)| d).unwrap_or("")
+        };
+        let key = match dir {
+            "" => file_name.to_string(),
+            d if d.ends_with('/') => format!("{}{}", d, file_name),
+            d => format!("{}/{}", d, file_name),
+        };
+        Self {
+            bucket: self.bucket.clone(),
+            key,
+        }
+    }
+}
+
+impl std::fmt::Display for S3Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+/// Uploads objects with a fixed encryption setting
+#[cfg(feature = "bedrock")]
+pub struct S3Uploader {
+    client: aws_sdk_s3::Client,
+    sse: Option<SseMode>,
+    kms_key_id: Option<String>,
+}
+
+#[cfg(feature = "bedrock")]
+impl S3Uploader {
+    /// Create an uploader, using the AWS region chain with `fallback_region` as default
+    pub async fn new(
+        fallback_region: &str,
+        sse: Option<SseMode>,
+        kms_key_id: Option<String>,
+    ) -> Result<Self> {
+        let region = RegionProviderChain::default_provider()
+            .or_else(aws_config::Region::new(fallback_region.to_string()));
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(region)
+            .load()
+            .await;
+
+        // A KMS key implies SSE-KMS
+        let sse = sse.or(kms_key_id.as_ref().map(|_| SseMode::Kms));
+
+        Ok(Self {
+            client: aws_sdk_s3::Client::new(&config),
+            sse,
+            kms_key_id,
+        })
+    }
+
+    /// Upload `body` to `location`
+    pub async fn put(&self, location: &S3Location, body: &str, content_type: &str) -> Result<()> {
+        let mut request = self
+            .client
+            .put_object()
+            .bucket(&location.bucket)
+            .key(&location.key)
+            .content_type(content_type)
+            .body(ByteStream::from(body.as_bytes().to_vec()));
+
+        match self.sse {
+            Some(SseMode::Aes256) => {
+                request = request.server_side_encryption(ServerSideEncryption::Aes256);
+            }
+            Some(SseMode::Kms) => {
+                request = request.server_side_encryption(ServerSideEncryption::AwsKms);
+                if let Some(ref key_id) = self.kms_key_id {
+                    request = request.ssekms_key_id(key_id);
+                }
+            }
+            None => {}
+        }
+
+        request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", aws_sdk_s3::error::DisplayErrorContext(e)))
+            .with_context(|| format!("Failed to upload to {}", location))?;
+        Ok(())
+    }
+}
+
+/// S3 uploader stand-in for builds without the AWS SDK
+#[cfg(not(feature = "bedrock"))]
+pub enum S3Uploader {}
+
+#[cfg(not(feature = "bedrock"))]
+impl S3Uploader {
+    pub async fn new(
+        _fallback_region: &str,
+        _sse: Option<SseMode>,
+        _kms_key_id: Option<String>,
+    ) -> Result<Self> {
+        Err(crate::CoptError::feature_disabled("bedrock", "Saving to s3://").into())
+    }
+
+    pub async fn put(
+        &self,
+        _location: &S3Location,
+        _body: &str,
+        _content_type: &str,
+    ) -> Result<()> {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_s3_location() {
+        assert!(S3Location::parse(Path::new("out/optimized.txt")).is_none());
+        assert!(S3Location::parse(Path::new("s3:///key")).unwrap().is_err());
+
+        let prefix = S3Location::parse(Path::new("s3://prompts/team/"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(prefix.bucket, "prompts");
+        assert!(prefix.is_prefix());
+        assert!(S3Location::parse(Path::new("s3://prompts"))
+            .unwrap()
+            .unwrap()
+            .is_prefix());
+
+        let object = S3Location::parse(Path::new("s3://prompts/team/optimized_a.txt"))
+            .unwrap()
+            .unwrap();
+        assert!(!object.is_prefix());
+        assert_eq!(object.key, "team/optimized_a.txt");
+    }
+
+    #[test]
+    fn test_sibling_keys() {
+        let prefix = S3Location::parse(Path::new("s3://b/team/"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(prefix.sibling("x.txt").key, "team/x.txt");
+
+        let object = S3Location::parse(Path::new("s3://b/team/optimized_a.txt"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(object.sibling("original_a.txt").key, "team/original_a.txt");
+        assert_eq!(
+            object.sibling("original_a.txt").to_string(),
+            "s3://b/team/original_a.txt"
+        );
+
+        let root = S3Location::parse(Path::new("s3://b")).unwrap().unwrap();
+        assert_eq!(root.sibling("x.txt").key, "x.txt");
+    }
+}
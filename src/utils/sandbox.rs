@@ -0,0 +1,30 @@
+//! Read-only mode for `--sandbox`
+//!
+//! Restrictive CI containers and shared jump hosts may not allow (or want)
+//! copt to leave files behind. When sandboxed, nothing is written to disk —
+//! no auto-save, metadata, history, metrics, or rule-pack cache — and no
+//! editor is spawned; results are printed to stdout only.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::CoptError;
+
+static SANDBOXED: AtomicBool = AtomicBool::new(false);
+
+/// Disable filesystem writes and editor spawning for the rest of the process
+pub fn enable() {
+    SANDBOXED.store(true, Ordering::Relaxed);
+}
+
+/// Whether `--sandbox` is in effect
+pub fn enabled() -> bool {
+    SANDBOXED.load(Ordering::Relaxed)
+}
+
+/// Refuse `action` (e.g. "Writing metrics.csv") when sandboxed
+pub fn ensure_writable(action: &str) -> anyhow::Result<()> {
+    if enabled() {
+        return Err(CoptError::Config(format!("{} is disabled by --sandbox", action)).into());
+    }
+    Ok(())
+}
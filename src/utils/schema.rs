@@ -0,0 +1,402 @@
+//! JSON Schema validation
+//!
+//! The subset response contracts use: `type`, `enum`, `const`, `properties`,
+//! `required`, `additionalProperties`, `items`, `minItems`/`maxItems`,
+//! `minLength`/`maxLength`, `pattern`, `minimum`/`maximum` (and their
+//! exclusive forms), `allOf`/`anyOf`/`oneOf`, and `$ref` to a definition in
+//! the same document. Other keywords (`format`, `description`, ...) are
+//! ignored, as the specification allows.
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// A place where the document breaks the schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON path to the value, e.g. `$.items[0].price`
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Every place `instance` breaks `schema`
+pub fn validate(schema: &Value, instance: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    check(schema, schema, instance, "$", &mut violations);
+    violations
+}
+
+fn check(root: &Value, schema: &Value, value: &Value, path: &str, out: &mut Vec<Violation>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            return violation(out, path, "no value is allowed here".to_string());
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve(root, reference) {
+            Some(target) => check(root, target, value, path, out),
+            None => violation(out, path, format!("unresolved $ref {}", reference)),
+        }
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| is_type(value, name)) {
+            violation(
+                out,
+                path,
+                format!("expected {}, got {}", names.join(" or "), type_name(value)),
+            );
+            // The remaining keywords assume the declared type
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            violation(
+                out,
+                path,
+                format!("{} is not one of {}", value, list(allowed)),
+            );
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            violation(out, path, format!("expected {}, got {}", constant, value));
+        }
+    }
+
+    match value {
+        Value::Object(object) => check_object(root, schema, object, path, out),
+        Value::Array(items) => check_array(root, schema, items, path, out),
+        Value::String(text) => check_string(schema, text, path, out),
+        Value::Number(number) => {
+            if let Some(n) = number.as_f64() {
+                check_number(schema, n, path, out);
+            }
+        }
+        _ => {}
+    }
+
+    check_combinators(root, schema, value, path, out);
+}
+
+fn check_object(
+    root: &Value,
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    out: &mut Vec<Violation>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                violation(out, path, format!("missing required property \"{}\"", key));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, child) in object {
+        let child_path = join_key(path, key);
+        match properties.and_then(|p| p.get(key)) {
+            Some(property) => check(root, property, child, &child_path, out),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    violation(out, path, format!("unexpected property \"{}\"", key))
+                }
+                Some(additional) => check(root, additional, child, &child_path, out),
+                None => {}
+            },
+        }
+    }
+}
+
+fn check_array(
+    root: &Value,
+    schema: &Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    out: &mut Vec<Violation>,
+) {
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if (items.len() as u64) < min {
+            violation(
+                out,
+                path,
+                format!("expected at least {} items, got {}", min, items.len()),
+            );
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if items.len() as u64 > max {
+            violation(
+                out,
+                path,
+                format!("expected at most {} items, got {}", max, items.len()),
+            );
+        }
+    }
+    if let Some(item_schema) = schema.get("items") {
+        for (i, item) in items.iter().enumerate() {
+            check(root, item_schema, item, &format!("{}[{}]", path, i), out);
+        }
+    }
+}
+
+fn check_string(schema: &Map<String, Value>, text: &str, path: &str, out: &mut Vec<Violation>) {
+    let length = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            violation(
+                out,
+                path,
+                format!("expected at least {} characters, got {}", min, length),
+            );
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            violation(
+                out,
+                path,
+                format!("expected at most {} characters, got {}", max, length),
+            );
+        }
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        match Regex::new(pattern) {
+            Ok(re) if !re.is_match(text) => {
+                violation(out, path, format!("does not match /{}/", pattern))
+            }
+            Ok(_) => {}
+            Err(_) => violation(
+                out,
+                path,
+                format!("invalid pattern /{}/ in schema", pattern),
+            ),
+        }
+    }
+}
+
+fn check_number(schema: &Map<String, Value>, n: f64, path: &str, out: &mut Vec<Violation>) {
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    if let Some(min) = bound("minimum") {
+        if n < min {
+            violation(out, path, format!("{} is less than the minimum {}", n, min));
+        }
+    }
+    if let Some(max) = bound("maximum") {
+        if n > max {
+            violation(
+                out,
+                path,
+                format!("{} is greater than the maximum {}", n, max),
+            );
+        }
+    }
+    if let Some(min) = bound("exclusiveMinimum") {
+        if n <= min {
+            violation(out, path, format!("{} is not greater than {}", n, min));
+        }
+    }
+    if let Some(max) = bound("exclusiveMaximum") {
+        if n >= max {
+            violation(out, path, format!("{} is not less than {}", n, max));
+        }
+    }
+}
+
+fn check_combinators(
+    root: &Value,
+    schema: &Map<String, Value>,
+    value: &Value,
+    path: &str,
+    out: &mut Vec<Violation>,
+) {
+    let branches = |key: &str| schema.get(key).and_then(Value::as_array);
+    let passes = |branch: &Value| {
+        let mut scratch = Vec::new();
+        check(root, branch, value, path, &mut scratch);
+        scratch.is_empty()
+    };
+
+    if let Some(all) = branches("allOf") {
+        for branch in all {
+            check(root, branch, value, path, out);
+        }
+    }
+    if let Some(any) = branches("anyOf") {
+        if !any.iter().any(passes) {
+            violation(out, path, "matches none of the anyOf schemas".to_string());
+        }
+    }
+    if let Some(one) = branches("oneOf") {
+        let matched = one.iter().filter(|branch| passes(branch)).count();
+        if matched != 1 {
+            violation(
+                out,
+                path,
+                format!(
+                    "matches {} of the oneOf schemas, expected exactly 1",
+                    matched
+                ),
+            );
+        }
+    }
+}
+
+/// The schema a `#/...` reference points to
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn is_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value
+            .as_f64()
+            .is_some_and(|n| n.fract() == 0.0 && value.is_number()),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+    }
+}
+
+/// `$.name`, or `$["odd key"]` for keys that aren't identifiers
+fn join_key(path: &str, key: &str) -> String {
+    let plain = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{}]", path, Value::String(key.to_string()))
+    }
+}
+
+fn list(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn violation(out: &mut Vec<Violation>, path: &str, message: String) {
+    out.push(Violation {
+        path: path.to_string(),
+        message,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["status", "items"],
+            "additionalProperties": false,
+            "properties": {
+                "status": { "enum": ["shipped", "pending"] },
+                "items": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": { "$ref": "#/$defs/item" }
+                },
+                "note": { "type": ["string", "null"], "maxLength": 10 }
+            },
+            "$defs": {
+                "item": {
+                    "type": "object",
+                    "required": ["sku", "price"],
+                    "properties": {
+                        "sku": { "type": "string", "pattern": "^[A-Z]{3}-\\d+$" },
+                        "price": { "type": "number", "minimum": 0 },
+                        "qty": { "type": "integer" }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_document_has_no_violations() {
+        let doc = json!({
+            "status": "shipped",
+            "items": [{ "sku": "ABC-1", "price": 9.5, "qty": 2 }],
+            "note": null
+        });
+        assert!(validate(&schema(), &doc).is_empty());
+        assert!(validate(&json!(true), &doc).is_empty());
+        assert!(validate(&json!({}), &doc).is_empty());
+    }
+
+    #[test]
+    fn test_violations_point_at_the_value() {
+        let doc = json!({
+            "status": "lost",
+            "items": [{ "sku": "abc", "price": "9.50", "qty": 1.5 }],
+            "extra": 1
+        });
+        let found: Vec<String> = validate(&schema(), &doc)
+            .iter()
+            .map(Violation::to_string)
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                "$: unexpected property \"extra\"",
+                "$.items[0].price: expected number, got string",
+                "$.items[0].qty: expected integer, got number",
+                "$.items[0].sku: does not match /^[A-Z]{3}-\\d+$/",
+                "$.status: \"lost\" is not one of \"shipped\", \"pending\"",
+            ]
+        );
+
+        let found = validate(&schema(), &json!({ "items": [] }));
+        assert_eq!(found[0].message, "missing required property \"status\"");
+        assert_eq!(found[1].message, "expected at least 1 items, got 0");
+        assert_eq!(
+            validate(
+                &json!({ "oneOf": [{ "type": "number" }, { "minimum": 0 }] }),
+                &json!(3)
+            )[0]
+            .message,
+            "matches 2 of the oneOf schemas, expected exactly 1"
+        );
+    }
+}
@@ -25,6 +25,45 @@ pub fn count_tokens(text: &str) -> usize {
     ((word_based * 2 + char_based) / 3).max(1)
 }
 
+/// Split text into chunks of roughly `max_tokens` estimated tokens or fewer
+///
+/// Breaks between paragraphs where possible and between lines inside a
+/// paragraph that is over the limit on its own. A single line longer than
+/// the limit becomes its own chunk rather than being cut mid-sentence.
+pub fn split_chunks(text: &str, max_tokens: usize) -> Vec<String> {
+    // Each piece with the separator that joins it to the one before
+    let mut pieces = Vec::new();
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        if count_tokens(paragraph) <= max_tokens {
+            pieces.push((paragraph, "\n\n"));
+        } else {
+            for (i, line) in paragraph.lines().enumerate() {
+                pieces.push((line, if i == 0 { "\n\n" } else { "\n" }));
+            }
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0;
+    for (piece, separator) in pieces {
+        let tokens = count_tokens(piece);
+        if !current.is_empty() && current_tokens + tokens > max_tokens {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+        }
+        current.push_str(piece);
+        current_tokens += tokens;
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
 /// Count the number of words in a string
 pub fn word_count(text: &str) -> usize {
     text.split_whitespace().count()
@@ -158,6 +197,57 @@ pub fn text_similarity(a: &str, b: &str) -> f64 {
     }
 }
 
+/// Number of hash functions in a [`minhash`] signature
+pub const MINHASH_SIZE: usize = 64;
+
+/// MinHash signature of the text's word set
+///
+/// Comparing two signatures with [`minhash_similarity`] estimates
+/// [`text_similarity`] (within about 0.1) without keeping the text, so the
+/// history can find near-duplicate prompts it never stored. Empty text has
+/// an empty signature.
+pub fn minhash(text: &str) -> Vec<u32> {
+    let words: std::collections::HashSet<_> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    // FNV-1a is stable across builds and platforms, unlike std's hasher
+    let hashes: Vec<u64> = words
+        .iter()
+        .map(|word| {
+            word.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+            })
+        })
+        .collect();
+    (0..MINHASH_SIZE as u64)
+        .map(|seed| {
+            hashes
+                .iter()
+                .map(|&hash| (mix(hash ^ mix(seed)) >> 32) as u32)
+                .min()
+                .unwrap()
+        })
+        .collect()
+}
+
+/// SplitMix64 finalizer, used to derive independent hash functions
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Estimated Jaccard similarity of two [`minhash`] signatures (0.0-1.0)
+pub fn minhash_similarity(a: &[u32], b: &[u32]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / a.len() as f64
+}
+
 /// Calculate the change percentage between two strings
 pub fn calculate_change_percent(original: &str, modified: &str) -> f64 {
     let orig_len = original.len() as f64;
@@ -184,6 +274,29 @@ mod tests {
         assert!(count_tokens("This is a longer sentence with more words") > count_tokens("Hello"));
     }
 
+    #[test]
+    fn test_split_chunks() {
+        let paragraph = "Review the pull request for style issues and missing tests.";
+        let text = [paragraph; 6].join("\n\n");
+        let per_paragraph = count_tokens(paragraph);
+
+        let chunks = split_chunks(&text, per_paragraph * 2);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks
+            .iter()
+            .all(|c| c == &format!("{0}\n\n{0}", paragraph)));
+
+        // Everything fits: one chunk, unchanged
+        assert_eq!(split_chunks(&text, usize::MAX), vec![text.clone()]);
+
+        // A paragraph over the limit is split between its lines
+        let lines = [paragraph; 4].join("\n");
+        let chunks = split_chunks(&lines, per_paragraph * 2);
+        assert_eq!(chunks, vec![format!("{0}\n{0}", paragraph); 2]);
+
+        assert!(split_chunks("\n\n  \n\n", 10).is_empty());
+    }
+
     #[test]
     fn test_word_count() {
         assert_eq!(word_count("Hello world"), 2);
@@ -231,4 +344,18 @@ mod tests {
         assert!(text_similarity("hello world", "hello there") > 0.0);
         assert!(text_similarity("hello world", "goodbye moon") < 0.5);
     }
+
+    #[test]
+    fn test_minhash_estimates_text_similarity() {
+        let a = "Summarize the incident report for the on-call engineer in three bullet points covering impact root cause and next step";
+        let b = "Summarize the outage report for the on-call engineer in three bullet points covering impact root cause and follow-up";
+        let estimate = minhash_similarity(&minhash(a), &minhash(b));
+        assert!((estimate - text_similarity(a, b)).abs() < 0.15);
+
+        assert_eq!(minhash(a).len(), MINHASH_SIZE);
+        assert_eq!(minhash_similarity(&minhash(a), &minhash(a)), 1.0);
+        assert!(minhash_similarity(&minhash(a), &minhash("Write a haiku about autumn")) < 0.2);
+        assert!(minhash("  ").is_empty());
+        assert_eq!(minhash_similarity(&[], &[]), 0.0);
+    }
 }
@@ -0,0 +1,2083 @@
+//! End-to-end tests for the optimize path using the mock provider
+//!
+//! These run the real binary with `--provider mock`, so the full
+//! analyze → optimize → output → save flow is covered without network access.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// Base command with flags that keep tests hermetic
+fn copt() -> Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("copt");
+    cmd.args(["--provider", "mock", "--no-suggest"]);
+    // Keep local metrics and caches out of the developer's directories
+    cmd.env("XDG_DATA_HOME", std::env::temp_dir().join("copt-e2e-data"));
+    cmd.env(
+        "XDG_CACHE_HOME",
+        std::env::temp_dir().join("copt-e2e-cache"),
+    );
+    // Assertions match the English messages
+    cmd.env("COPT_LANG", "en");
+    cmd
+}
+
+#[test]
+fn json_output_contains_optimized_prompt_and_issues() {
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "Can you think about the API design?",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["original"], "Can you think about the API design?");
+
+    let optimized = json["optimized"].as_str().unwrap();
+    assert!(optimized.starts_with("<instructions>"));
+    // Static transforms ran before the mock saw the prompt
+    assert!(optimized.contains("consider the API design?"));
+
+    let ids: Vec<&str> = json["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&"EXP003"));
+    assert!(ids.contains(&"STY003"));
+
+    assert_eq!(json["stats"]["provider"], "mock");
+    assert!(json["issues"][0]["confidence"].as_f64().is_some());
+
+    // The same issues grouped by category and rule
+    let summary = &json["summary"];
+    assert_eq!(
+        summary["warnings"].as_u64().unwrap()
+            + summary["infos"].as_u64().unwrap()
+            + summary["errors"].as_u64().unwrap(),
+        ids.len() as u64
+    );
+    let grouped: Vec<&str> = summary["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|c| c["rules"].as_array().unwrap())
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert!(grouped.contains(&"EXP003"));
+
+    let changelog: Vec<&str> = json["stats"]["changelog"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e.as_str().unwrap())
+        .collect();
+    assert!(changelog.contains(&"Converted 1 indirect request to direct commands"));
+    assert!(changelog.contains(&"Added an output format section"));
+}
+
+#[test]
+fn suggest_flag_appends_templates_without_prompting() {
+    copt()
+        .args([
+            "--offline",
+            "--format",
+            "quiet",
+            "--suggest=state_management",
+            "Migrate every service in the monorepo to the new logging API",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<state_management>"))
+        .stdout(predicate::str::contains("progress.txt"));
+
+    copt()
+        .args(["--offline", "--suggest=bogus", "Write a haiku"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown suggestion 'bogus'"));
+}
+
+#[test]
+fn quiet_format_prints_only_optimized_prompt() {
+    copt()
+        .args(["--format", "quiet", "--no-save", "Write a haiku about rust"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "<instructions>\nWrite a haiku about rust\n</instructions>",
+        ));
+}
+
+#[test]
+fn print_selects_one_artifact_for_stdout() {
+    let prompt = "Write a haiku about rust";
+    let print = |what: &str| {
+        let output = copt()
+            .args(["--no-save", "--print", what, prompt])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(print("original"), format!("{}\n", prompt));
+    assert!(print("optimized").starts_with("<instructions>\n"));
+
+    let issues: serde_json::Value = serde_json::from_str(&print("issues")).unwrap();
+    assert!(issues
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|i| i["id"].is_string()));
+
+    let diff = print("diff");
+    assert!(
+        diff.starts_with("--- original\n+++ optimized\n@@ "),
+        "{}",
+        diff
+    );
+    assert!(diff.contains("\n-Write a haiku about rust\n"));
+    assert!(diff.contains("\n+<instructions>\n"));
+}
+
+#[test]
+fn json_path_prints_a_single_value() {
+    let tokens = copt()
+        .args([
+            "--json-path",
+            ".stats.optimized_tokens",
+            "Write a haiku about rust",
+        ])
+        .output()
+        .unwrap();
+    assert!(tokens.status.success());
+    let tokens = String::from_utf8(tokens.stdout).unwrap();
+    assert!(tokens.trim().parse::<u64>().is_ok(), "{}", tokens);
+
+    copt()
+        .args(["--jq", "$.stats.provider", "Write a haiku about rust"])
+        .assert()
+        .success()
+        .stdout("mock\n");
+
+    copt()
+        .args(["--json-path", ".stats[", "Write a haiku about rust"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unclosed '['"));
+
+    copt()
+        .args(["--json-path", ".stats.missing", "Write a haiku about rust"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("Nothing at '.stats.missing'"));
+}
+
+#[test]
+fn enhance_appends_matching_guidance_offline() {
+    let output = copt()
+        .args(["--offline", "--enhance", "--format", "json"])
+        .arg("Fix the login bug")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["stats"]["enhancements"],
+        serde_json::json!(["exploration"])
+    );
+    assert!(json["optimized"]
+        .as_str()
+        .unwrap()
+        .ends_with("Do not speculate about code you haven't inspected."));
+    assert!(json["stats"]["changelog"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("Appended enhancements: exploration")));
+}
+
+#[test]
+fn explicit_output_writes_optimized_original_and_metadata() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("optimized_test.txt");
+
+    copt()
+        .args(["--format", "quiet", "-o"])
+        .arg(&out)
+        .arg("Could you summarize this report?")
+        .assert()
+        .success();
+
+    let optimized = std::fs::read_to_string(&out).unwrap();
+    assert!(optimized.contains("<instructions>"));
+
+    let original = std::fs::read_to_string(dir.path().join("original_test.txt")).unwrap();
+    assert_eq!(original, "Could you summarize this report?");
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.with_extension("json")).unwrap())
+            .unwrap();
+    assert_eq!(metadata["provider"], "mock");
+    assert_eq!(metadata["files"]["original"], "original_test.txt");
+}
+
+#[test]
+fn saved_prompts_end_with_a_provenance_footer() {
+    use copt::cli::provenance::{self, Provenance};
+
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("optimized_test.txt");
+    let save = |out: &std::path::Path, input: &[&str]| {
+        copt()
+            .env("XDG_CONFIG_HOME", dir.path())
+            .args(["--format", "quiet", "--force", "-o"])
+            .arg(out)
+            .args(input)
+            .assert()
+            .success();
+        std::fs::read_to_string(out).unwrap()
+    };
+
+    let saved = save(&out, &["Could you summarize this report?"]);
+    let footer = Provenance::parse(&saved).unwrap();
+    assert_eq!(footer.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(footer.provider, "mock");
+    assert!(footer.model.is_some());
+    assert_eq!(footer.meta_prompt, provenance::meta_prompt_hash());
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.with_extension("json")).unwrap())
+            .unwrap();
+    assert_eq!(metadata["input_hash"], footer.input.as_str());
+
+    // Optimizing the saved result again doesn't feed the footer back in
+    let again = dir.path().join("optimized_again.txt");
+    save(&again, &["-f", out.to_str().unwrap()]);
+    let original = std::fs::read_to_string(dir.path().join("original_again.txt")).unwrap();
+    assert_eq!(original, provenance::strip(&saved));
+
+    std::fs::create_dir_all(dir.path().join("copt")).unwrap();
+    std::fs::write(
+        dir.path().join("copt/config.toml"),
+        "[output]\nprovenance = false\n",
+    )
+    .unwrap();
+    let plain = save(&out, &["Could you summarize this report?"]);
+    assert!(Provenance::parse(&plain).is_none());
+    assert!(!plain.contains("copt-provenance"));
+}
+
+#[test]
+fn outdated_finds_and_reoptimizes_results_from_older_rules() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("optimized_test.txt");
+    copt()
+        .args(["--format", "quiet", "-o"])
+        .arg(&out)
+        .arg("Could you summarize this report?")
+        .assert()
+        .success();
+
+    let metadata_path = out.with_extension("json");
+    let read_metadata = || -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(&metadata_path).unwrap()).unwrap()
+    };
+    let metadata = read_metadata();
+    assert_eq!(metadata["copt_version"], env!("CARGO_PKG_VERSION"));
+    let rule_set = metadata["rule_set"].as_str().unwrap().to_string();
+    assert_eq!(rule_set.len(), 16);
+
+    let outdated = |extra: &[&str]| {
+        let output = copt()
+            .arg("outdated")
+            .arg(dir.path())
+            .args(["--format", "json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+    let report: serde_json::Value = serde_json::from_slice(&outdated(&[])).unwrap();
+    assert_eq!(report["scanned"], 1);
+    assert_eq!(report["outdated"], serde_json::json!([]));
+
+    // Pretend it was saved by an older release whose rules found nothing
+    let mut metadata = metadata;
+    metadata["rule_set"] = "0000000000000000".into();
+    metadata["issues"] = serde_json::json!([]);
+    std::fs::write(&metadata_path, metadata.to_string()).unwrap();
+    std::fs::write(&out, "stale").unwrap();
+
+    let report: serde_json::Value = serde_json::from_slice(&outdated(&[])).unwrap();
+    let entry = &report["outdated"][0];
+    assert!(entry["optimized"]
+        .as_str()
+        .unwrap()
+        .ends_with("optimized_test.txt"));
+    assert!(!entry["added"].as_array().unwrap().is_empty());
+
+    // --sandbox refuses rather than overwriting the saved results
+    let before = (
+        std::fs::read(&out).unwrap(),
+        std::fs::read(&metadata_path).unwrap(),
+    );
+    copt()
+        .args(["--sandbox", "outdated"])
+        .arg(dir.path())
+        .arg("--reoptimize")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--sandbox"));
+    assert_eq!(std::fs::read(&out).unwrap(), before.0);
+    assert_eq!(std::fs::read(&metadata_path).unwrap(), before.1);
+
+    outdated(&["--reoptimize"]);
+    assert_ne!(std::fs::read_to_string(&out).unwrap(), "stale");
+    assert_eq!(read_metadata()["rule_set"], rule_set.as_str());
+}
+
+#[test]
+fn git_textconv_shows_score_and_issue_changes_in_git_diff() {
+    let dir = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .current_dir(dir.path())
+            .env("COPT_LANG", "en")
+            .args(["-c", "user.name=copt", "-c", "user.email=copt@example.com"])
+            .args(args)
+            .output()
+            .expect("git is installed");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let textconv = format!(
+        "\"{}\" --no-suggest git-textconv",
+        assert_cmd::cargo::cargo_bin!("copt").display()
+    );
+
+    git(&["init", "-q"]);
+    git(&["config", "diff.copt.textconv", &textconv]);
+    std::fs::write(dir.path().join(".gitattributes"), "*.prompt diff=copt\n").unwrap();
+    let prompt = dir.path().join("support.prompt");
+    std::fs::write(&prompt, "Can you think about the API design?\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Add prompt"]);
+
+    std::fs::write(
+        &prompt,
+        "<task>\nReview the API design and list the three riskiest endpoints.\n</task>\n",
+    )
+    .unwrap();
+    let diff = git(&["diff"]);
+    assert!(diff.contains("-[copt] score "), "{}", diff);
+    assert!(diff.contains("+[copt] score "), "{}", diff);
+    assert!(diff.contains("-[copt] warning EXP003 "), "{}", diff);
+    assert!(diff.contains("+Review the API design"), "{}", diff);
+
+    // The plain file is what gets committed
+    assert_eq!(
+        git(&["show", "HEAD:support.prompt"]),
+        "Can you think about the API design?\n"
+    );
+}
+
+#[test]
+fn discover_lists_prompts_and_analyzes_string_literals_in_place() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("prompts")).unwrap();
+    std::fs::write(dir.path().join("prompts/triage.md"), "Classify the ticket.").unwrap();
+    std::fs::write(
+        dir.path().join("agent.ts"),
+        "import { client } from \"./client\";\n\n\
+         export const systemPrompt = `\n\
+         You are a triage assistant for the support team.\n\
+         Can you label each ticket as billing, technical, or account?\n\
+         Always reply with the label only.\n\
+         `;\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .args(["discover", "--analyze", "--format", "json"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let candidates: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let candidates = candidates.as_array().unwrap();
+    assert_eq!(candidates.len(), 2, "{:#?}", candidates);
+
+    let literal = &candidates[0];
+    assert!(literal["path"].as_str().unwrap().ends_with("agent.ts"));
+    assert_eq!(literal["kind"], "literal");
+    assert_eq!(literal["name"], "systemPrompt");
+    assert_eq!(literal["line"], 4);
+    let exp003 = literal["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|issue| issue["id"] == "EXP003")
+        .unwrap();
+    assert_eq!(exp003["line"], 5);
+
+    assert_eq!(candidates[1]["kind"], "prompt_file");
+    assert!(candidates[1]["score"].is_u64());
+}
+
+#[test]
+fn embedded_prompts_are_optimized_back_into_their_literals() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("agent.py");
+    let source = "import anthropic\n\n\n\
+                  def build(product):\n    system = f\"\"\"\n        You are a support agent for {product}.\n        Answer billing questions politely and always cite the invoice number.\n    \"\"\"\n    \
+                  return client.messages.create(system=system, messages=[])\n";
+    std::fs::write(&path, source).unwrap();
+
+    let output = copt()
+        .arg("--embedded")
+        .arg("-f")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Updated"));
+
+    let updated = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        updated.starts_with("import anthropic\n\n\ndef build(product):\n    system = f\"\"\"\n        <instructions>\n"),
+        "{}",
+        updated
+    );
+    assert!(updated.contains("        You are a support agent for {product}.\n"));
+    assert!(updated.ends_with(
+        "        </output_format>\n    \"\"\"\n    return client.messages.create(system=system, messages=[])\n"
+    ));
+}
+
+#[test]
+fn max_cost_refuses_the_llm_call_before_it_is_sent() {
+    let home = TempDir::new().unwrap();
+    // A billed provider, but the limit stops the run before any request
+    let output = assert_cmd::cargo::cargo_bin_cmd!("copt")
+        .env("XDG_CONFIG_HOME", home.path())
+        .env("XDG_DATA_HOME", home.path())
+        .env("ANTHROPIC_API_KEY", "sk-ant-test")
+        .args([
+            "--provider",
+            "anthropic",
+            "--skip-connectivity-check",
+            "--no-suggest",
+            "--max-cost",
+            "$0.001",
+            "--format",
+            "json",
+            "Can you think about the API design of the billing service and propose a plan?",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "cost_limit");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("over --max-cost $0.0010"));
+}
+
+#[test]
+fn anonymized_details_are_restored_in_the_optimized_prompt() {
+    let prompt = "Help Dr. Ana Lopez at Globex Corporation triage tickets from ops@globex.com.";
+    let output = copt()
+        .args(["--anonymize", "--format", "json", "--no-save", prompt])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let optimized = json["optimized"].as_str().unwrap();
+    assert!(optimized.contains(prompt), "{}", optimized);
+    assert!(!optimized.contains("{{"));
+}
+
+#[test]
+fn check_and_disabled_rules_scope_static_fixes() {
+    let prompt = "Can you think about the API design?";
+    let optimized = |config: &TempDir, args: &[&str]| {
+        let output = copt()
+            .env("XDG_CONFIG_HOME", config.path())
+            .args(["--offline", "--format", "json"])
+            .args(args)
+            .arg(prompt)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["optimized"].as_str().unwrap().to_string()
+    };
+
+    let config = TempDir::new().unwrap();
+    let everything = optimized(&config, &[]);
+    assert!(!everything.contains("Can you") && everything.contains("consider"));
+
+    // STY003 ("think") is outside --check explicitness
+    let explicit_only = optimized(&config, &["--check", "explicitness"]);
+    assert_eq!(explicit_only, "Think about the API design?");
+
+    std::fs::create_dir_all(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[rules]\ndisabled = [\"EXP003\"]\n",
+    )
+    .unwrap();
+    let without_exp003 = optimized(&config, &[]);
+    assert!(without_exp003.contains("Can you") && without_exp003.contains("consider"));
+}
+
+#[test]
+fn redact_masks_secrets_in_saved_files_and_reports() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("copt")).unwrap();
+    std::fs::write(
+        dir.path().join("copt/config.toml"),
+        "[redact]\nnames = [\"Dana Whitfield\"]\n",
+    )
+    .unwrap();
+    let out = dir.path().join("optimized_test.txt");
+    let prompt = "Could you email dana@example.com a summary for Dana Whitfield? \
+                  Use key sk-ant-REDACTED.";
+
+    let output = copt()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["--redact", "--format", "json", "-o"])
+        .arg(&out)
+        .arg(prompt)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+
+    let original = std::fs::read_to_string(dir.path().join("original_test.txt")).unwrap();
+    let optimized = std::fs::read_to_string(&out).unwrap();
+    for text in [&stdout, &original, &optimized] {
+        assert!(!text.contains("dana@example.com"), "{}", text);
+        assert!(!text.contains("Dana Whitfield"), "{}", text);
+        assert!(!text.contains("sk-ant-api03"), "{}", text);
+    }
+    assert!(original.contains("Could you email [REDACTED:email] a summary for [REDACTED:name]?"));
+    assert!(optimized.contains("[REDACTED:key]"));
+}
+
+#[test]
+fn organization_policy_overrides_flags() {
+    let dir = TempDir::new().unwrap();
+    let policy = dir.path().join("policy.toml");
+    std::fs::write(
+        &policy,
+        "[providers]\nbanned = [\"mock\"]\n\n[redact]\nrequired = true\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .env("COPT_POLICY", &policy)
+        .args(["--no-save", "--format", "json", "Summarize the report."])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "policy_violation");
+
+    // Redaction applies without --redact
+    let output = copt()
+        .env("COPT_POLICY", &policy)
+        .args(["--offline", "--format", "json"])
+        .arg("Email dana@example.com a summary.")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("dana@example.com"), "{}", stdout);
+
+    // A policy that can't be read stops copt instead of running unrestricted
+    copt()
+        .env("COPT_POLICY", dir.path().join("missing.toml"))
+        .args(["--offline", "Summarize the report."])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn auto_save_writes_into_output_dir() {
+    let dir = TempDir::new().unwrap();
+
+    copt()
+        .args(["--format", "quiet", "--output-dir"])
+        .arg(dir.path())
+        .arg("Build a login form")
+        .assert()
+        .success();
+
+    let saved: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert!(saved
+        .iter()
+        .any(|n| n.starts_with("optimized_") && n.ends_with(".txt")));
+    assert!(saved.iter().any(|n| n.starts_with("original_")));
+    assert!(saved.iter().any(|n| n.ends_with(".json")));
+}
+
+#[test]
+fn repeated_runs_never_overwrite_saved_files() {
+    let dir = TempDir::new().unwrap();
+
+    for _ in 0..3 {
+        copt()
+            .args(["--format", "quiet", "--output-dir"])
+            .arg(dir.path())
+            .arg("Build a login form")
+            .assert()
+            .success();
+    }
+
+    let saved: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    let count = |prefix: &str, ext: &str| {
+        saved
+            .iter()
+            .filter(|n| n.starts_with(prefix) && n.ends_with(ext))
+            .count()
+    };
+    assert_eq!(count("optimized_", ".txt"), 3, "{:?}", saved);
+    assert_eq!(count("original_", ".txt"), 3, "{:?}", saved);
+    assert_eq!(count("optimized_", ".json"), 3, "{:?}", saved);
+    assert!(!saved.iter().any(|n| n.ends_with(".tmp")));
+}
+
+#[test]
+fn no_save_leaves_output_dir_untouched() {
+    let dir = TempDir::new().unwrap();
+    let output_dir = dir.path().join("out");
+
+    copt()
+        .args(["--format", "quiet", "--no-save", "--output-dir"])
+        .arg(&output_dir)
+        .arg("Build a login form")
+        .assert()
+        .success();
+
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn sandbox_writes_nothing_and_prints_the_prompt() {
+    let dir = TempDir::new().unwrap();
+    let output_dir = dir.path().join("out");
+    let data_dir = dir.path().join("data");
+
+    copt()
+        .env("XDG_DATA_HOME", &data_dir)
+        .args(["--sandbox", "--no-pager", "--output-dir"])
+        .arg(&output_dir)
+        .arg("Build a login form")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Optimized Prompt"));
+
+    assert!(!output_dir.exists());
+    assert!(!data_dir.exists());
+
+    copt()
+        .args(["--sandbox", "-o"])
+        .arg(dir.path().join("prompt.txt"))
+        .arg("Build a login form")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--sandbox"));
+}
+
+#[test]
+fn stdin_prompt_is_optimized() {
+    copt()
+        .args(["--format", "quiet", "--no-save"])
+        .write_stdin("Summarize the meeting notes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Summarize the meeting notes"));
+}
+
+#[test]
+fn binary_input_file_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let image = dir.path().join("logo.png");
+    std::fs::write(&image, b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR").unwrap();
+
+    copt()
+        .args(["--no-save", "-f"])
+        .arg(&image)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("looks like a binary file"));
+}
+
+/// Config directory with a small LLM input limit, so a short prompt is "oversized"
+fn small_input_limit() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("copt")).unwrap();
+    std::fs::write(
+        dir.path().join("copt/config.toml"),
+        "[input]\nmax_llm_tokens = 40\n",
+    )
+    .unwrap();
+    dir
+}
+
+/// A prompt of several paragraphs, each under the small limit
+fn oversized_prompt() -> String {
+    ["Review each module for unused imports, then list the files you changed."; 6].join("\n\n")
+}
+
+#[test]
+fn oversized_prompt_warns_by_default_and_aborts_on_request() {
+    let config = small_input_limit();
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--format", "json", "--no-save"])
+        .write_stdin(oversized_prompt())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("may be cut short"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["optimized"]
+            .as_str()
+            .unwrap()
+            .matches("<instructions>")
+            .count(),
+        1
+    );
+
+    copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--format", "json", "--no-save", "--oversized", "abort"])
+        .write_stdin(oversized_prompt())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "in one request.\n\nChoose how to handle it:\n--oversized analyze",
+        ));
+}
+
+#[test]
+fn oversized_prompt_can_be_analyzed_or_chunked() {
+    let config = small_input_limit();
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--format", "json", "--no-save", "--oversized", "analyze"])
+        .write_stdin(oversized_prompt())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["optimized"], json["original"]);
+
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--format", "json", "--no-save", "--oversized", "chunk"])
+        .write_stdin(oversized_prompt())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // The mock wraps each chunk it rewrites in its own <instructions> block
+    let chunks = json["optimized"]
+        .as_str()
+        .unwrap()
+        .matches("<instructions>")
+        .count();
+    assert!(chunks > 1, "expected several chunks, got {}", chunks);
+}
+
+#[test]
+fn max_change_warns_or_fails_on_large_rewrites() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("optimized_test.txt");
+
+    // The mock rewrites every line of this prompt
+    copt()
+        .args(["--format", "quiet", "--max-change", "40%", "--strict", "-o"])
+        .arg(&out)
+        .arg("Could you summarize this report?")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "changed 100% of lines, over the --max-change limit of 40%",
+        ));
+    assert!(!out.exists());
+
+    copt()
+        .args(["--format", "quiet", "--no-save", "--max-change", "40%"])
+        .arg("Could you summarize this report?")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<instructions>"))
+        .stderr(predicate::str::contains("Keeping the rewrite"));
+
+    copt()
+        .args([
+            "--format",
+            "quiet",
+            "--no-save",
+            "--max-change",
+            "100%",
+            "--strict",
+        ])
+        .arg("Could you summarize this report?")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("--max-change").not());
+}
+
+#[test]
+fn eval_compares_original_and_optimized_pass_rates() {
+    let dir = TempDir::new().unwrap();
+    let prompt = dir.path().join("prompt.txt");
+    let cases = dir.path().join("cases.yaml");
+    std::fs::write(&prompt, "You are a support assistant.").unwrap();
+    std::fs::write(
+        &cases,
+        "cases:\n  - name: echo\n    input: What is your refund policy?\n    assert:\n      - contains: refund policy\n      - regex: \"(?i)instructions\"\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .args(["--format", "json", "eval", "--cases"])
+        .arg(&cases)
+        .arg("--prompt")
+        .arg(&prompt)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["cases"][0]["name"], "echo");
+    assert_eq!(json["original_pass_rate"], 1.0);
+    assert_eq!(json["optimized_pass_rate"], 1.0);
+}
+
+#[test]
+fn verify_fails_when_the_response_is_not_the_declared_format() {
+    let dir = TempDir::new().unwrap();
+    let prompt = dir.path().join("optimized.md");
+    let schema = dir.path().join("out.schema.json");
+    std::fs::write(&prompt, "Classify the ticket. Reply with JSON only.").unwrap();
+    std::fs::write(&schema, r#"{"type": "object", "required": ["category"]}"#).unwrap();
+
+    // The mock answers with its canned prose rewrite, which no JSON parser accepts
+    let output = copt()
+        .args(["--format", "json", "verify", "-f"])
+        .arg(&prompt)
+        .arg("--schema")
+        .arg(&schema)
+        .args(["--input", "My invoice is wrong"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    // The report comes first, then the error object
+    let json: serde_json::Value = serde_json::Deserializer::from_slice(&output.stdout)
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(json["passed"], 0);
+    assert_eq!(json["total"], 1);
+    assert!(json["responses"][0]["parse_error"].is_string());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 of 1 responses broke the JSON contract"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn judge_scores_are_reported_in_json() {
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--judge",
+            "Summarize the report",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let quality = &json["quality"];
+    assert!(quality["original_score"].as_u64().unwrap() <= 100);
+    assert!(quality["optimized_score"].as_u64().is_some());
+    assert_eq!(quality["judge"]["original"]["structure"], 3);
+    assert_eq!(quality["judge"]["optimized"]["structure"], 9);
+}
+
+#[test]
+fn emit_workbench_json_splits_system_and_user() {
+    let output = copt()
+        .args([
+            "--offline",
+            "--emit",
+            "workbench.json",
+            "You are a support agent.\n\nSummarize {{TICKET}} in two sentences.",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["system"], "You are a support agent.");
+    assert_eq!(json["messages"][0]["role"], "user");
+    assert!(json["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .contains("{{TICKET}}"));
+    assert_eq!(json["variables"]["TICKET"], "");
+}
+
+#[test]
+fn verbose_prints_timeline_to_stderr() {
+    let output = copt()
+        .env_remove("RUST_LOG")
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--verbose",
+            // The timeline of a cached analysis has no per-category steps
+            "--no-cache",
+            "Can you think about the API design?",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // stdout stays machine-readable
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["stats"]["provider"], "mock");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("analyzed category=\"style\""));
+    assert!(stderr.contains("static transform applied rule=EXP003"));
+    assert!(stderr.contains("LLM request provider=\"mock\""));
+    assert!(stderr.contains("LLM response"));
+}
+
+#[test]
+fn profile_prints_phase_timings_to_stderr() {
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--profile",
+            "Can you think about the API design?",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("analyze    style"));
+    assert!(stderr.contains("transform  EXP003"));
+    assert!(stderr.contains("llm        optimize"));
+    assert!(stderr.contains("Total measured"));
+}
+
+#[test]
+fn docs_prints_the_best_practices_link_for_a_rule() {
+    copt()
+        .args(["docs", "exp003", "--print"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "prompt-engineering/claude-4-best-practices#tool-usage-patterns",
+        ));
+
+    copt()
+        .args(["docs", "XYZ999", "--print"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No documentation for rule XYZ999"));
+}
+
+#[test]
+fn audit_gates_on_structure_and_residual_issues() {
+    let dir = TempDir::new().unwrap();
+    let good = dir.path().join("good.md");
+    std::fs::write(
+        &good,
+        "<instructions>\nSummarize the incident report for the on-call engineer so they can decide whether to page the database team.\n</instructions>\n\n<output_format>\nReturn three bullet points: impact, root cause, and next step.\n</output_format>\n",
+    )
+    .unwrap();
+    copt()
+        .args(["audit", good.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PASS"));
+
+    let broken = dir.path().join("broken.md");
+    std::fs::write(
+        &broken,
+        "<instructions>\nSummarize the incident report.\n<context>\nDB outage\n</instructions>\n",
+    )
+    .unwrap();
+    let output = copt()
+        .args(["audit", broken.to_str().unwrap(), "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["passed"], false);
+    assert_eq!(json["xml_problems"][0]["line"], 3);
+    assert_eq!(json["missing_sections"][0], "output_format");
+}
+
+#[test]
+fn lint_reuses_cached_analysis_of_unchanged_files() {
+    let dir = TempDir::new().unwrap();
+    let cache = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("a.md"), "Can you think about this?").unwrap();
+    std::fs::write(dir.path().join("b.md"), "Please write some code maybe.").unwrap();
+    let pattern = dir.path().join("*.md");
+    let pattern = pattern.to_str().unwrap();
+
+    let lint = || {
+        let output = copt()
+            .env("XDG_CACHE_HOME", cache.path())
+            .args(["--verbose", "lint", pattern, "--format", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        (json, String::from_utf8(output.stderr).unwrap())
+    };
+
+    let (first, stderr) = lint();
+    assert!(stderr.contains("cache_hits=0 cache_misses=2"), "{}", stderr);
+
+    // Only the edited file is analyzed again
+    std::fs::write(dir.path().join("b.md"), "Write the code.").unwrap();
+    let (second, stderr) = lint();
+    assert!(stderr.contains("cache_hits=1 cache_misses=1"), "{}", stderr);
+    assert_eq!(first["files"][0], second["files"][0]);
+
+    let output = copt()
+        .env("XDG_CACHE_HOME", cache.path())
+        .args(["--verbose", "--no-cache", "lint", pattern])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cache_hits=0 cache_misses=0"), "{}", stderr);
+}
+
+#[test]
+fn batch_optimizes_a_directory_into_a_mirrored_tree() {
+    let dir = TempDir::new().unwrap();
+    let prompts = dir.path().join("prompts");
+    std::fs::create_dir_all(prompts.join("agents")).unwrap();
+    std::fs::write(
+        prompts.join("summary.md"),
+        "Could you summarize this report?",
+    )
+    .unwrap();
+    std::fs::write(
+        prompts.join("agents/review.md"),
+        "Can you think about this?",
+    )
+    .unwrap();
+    std::fs::write(prompts.join("agents/notes.txt"), "Not a match.").unwrap();
+    let out = dir.path().join("out");
+    let data = dir.path().join("data");
+
+    let output = copt()
+        .env("XDG_DATA_HOME", &data)
+        .arg("--output-dir")
+        .arg(&out)
+        .arg("batch")
+        .arg(&prompts)
+        .args(["--glob", "*.md", "--jobs", "2", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // One JSON line per file, streamed as each finishes
+    let mut files: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    files.sort_by_key(|f| f["path"].as_str().unwrap().to_string());
+    let paths: Vec<&str> = files.iter().map(|f| f["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["agents/review.md", "summary.md"]);
+    assert!(files[0]["warnings"].as_u64().unwrap() > 0);
+
+    let review = std::fs::read_to_string(out.join("agents/review.md")).unwrap();
+    assert!(review.contains("<instructions>"));
+    assert!(review.contains("<!-- copt-provenance "));
+    assert!(out.join("summary.md").exists());
+    assert!(!out.join("agents/notes.txt").exists());
+
+    // One metrics record per optimized file
+    let metrics = std::fs::read_to_string(data.join("copt/metrics.jsonl")).unwrap();
+    assert_eq!(metrics.lines().count(), 2, "{}", metrics);
+    assert!(metrics.lines().all(|l| l.contains("\"provider\":\"mock\"")));
+}
+
+#[test]
+fn batch_reports_an_unreadable_file_and_finishes_the_rest() {
+    let dir = TempDir::new().unwrap();
+    let prompts = dir.path().join("prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(prompts.join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+    std::fs::write(
+        prompts.join("summary.md"),
+        "Could you summarize this report?",
+    )
+    .unwrap();
+    let out = dir.path().join("out");
+
+    copt()
+        .arg("--output-dir")
+        .arg(&out)
+        .arg("batch")
+        .arg(&prompts)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("binary.md"))
+        .stderr(predicate::str::contains("1 of 2 files failed"));
+    assert!(out.join("summary.md").exists());
+    assert!(!out.join("binary.md").exists());
+}
+
+#[test]
+fn batch_redacts_saved_prompts() {
+    let dir = TempDir::new().unwrap();
+    let prompts = dir.path().join("prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(
+        prompts.join("support.md"),
+        "Could you email dana@example.com a summary of the ticket?",
+    )
+    .unwrap();
+    let out = dir.path().join("out");
+
+    copt()
+        .args(["--redact", "--output-dir"])
+        .arg(&out)
+        .arg("batch")
+        .arg(&prompts)
+        .assert()
+        .success();
+
+    let saved = std::fs::read_to_string(out.join("support.md")).unwrap();
+    assert!(!saved.contains("dana@example.com"), "{}", saved);
+    assert!(saved.contains("[REDACTED:email]"), "{}", saved);
+    assert!(saved.contains("<!-- copt-provenance "), "{}", saved);
+}
+
+#[test]
+fn analysis_cache_hits_on_the_second_run() {
+    let cache = TempDir::new().unwrap();
+    let run = |extra: &[&str]| {
+        let output = copt()
+            .env("XDG_CACHE_HOME", cache.path())
+            .env_remove("RUST_LOG")
+            .args(["--offline", "--analyze", "--format", "json"])
+            .args(extra)
+            .arg("Can you think about the API design?")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        (json, String::from_utf8(output.stderr).unwrap())
+    };
+
+    let (first, stderr) = run(&["--verbose"]);
+    assert!(stderr.contains("analyzed category=\"style\""), "{}", stderr);
+    assert!(!stderr.contains("analysis cache hit"), "{}", stderr);
+
+    let (second, stderr) = run(&["--verbose"]);
+    assert!(stderr.contains("analysis cache hit"), "{}", stderr);
+    assert!(!stderr.contains("analyzed category"), "{}", stderr);
+    assert_eq!(first["issues"], second["issues"]);
+
+    // --profile times the analysis, so it never answers from the cache
+    let (profiled, stderr) = run(&["--profile"]);
+    assert!(stderr.contains("analyze    style"), "{}", stderr);
+    assert_eq!(first["issues"], profiled["issues"]);
+}
+
+#[test]
+fn inspect_reports_structure_as_json() {
+    let dir = TempDir::new().unwrap();
+    let prompt = dir.path().join("prompt.md");
+    std::fs::write(
+        &prompt,
+        "You are a support agent for {{COMPANY}}.\n<policy>\nRefunds follow docs/refunds.md.\n</policy>\nUse the `lookup_order` tool before answering.\n",
+    )
+    .unwrap();
+    let output = copt()
+        .args([
+            "inspect",
+            "-f",
+            prompt.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["system_prompt"], true);
+    assert_eq!(json["variables"][0], "COMPANY");
+    assert_eq!(json["xml_tags"][0]["name"], "policy");
+    assert_eq!(json["files"][0]["path"], "docs/refunds.md");
+    assert_eq!(json["tools"][0]["name"], "lookup_order");
+    assert_eq!(json["size"]["lines"], 5);
+}
+
+#[test]
+fn skeleton_outlines_sections_with_token_shares() {
+    let output = copt()
+        .args(["skeleton", "--format", "json"])
+        .write_stdin("<rules>\n# Tone\nBe brief.\n# Tools\nAlways call `lookup_order` first.\n</rules>\nReply in two sentences.\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["instructions"], 3);
+    let rules = &json["nodes"][0];
+    assert_eq!(rules["name"], "rules");
+    assert_eq!(rules["children"][1]["name"], "Tools");
+    assert_eq!(rules["children"][1]["end_line"], 5);
+    assert!(rules["share"].as_f64().unwrap() > 0.5);
+
+    let output = copt()
+        .args(["skeleton"])
+        .write_stdin("<rules>\nBe brief.\n</rules>\n")
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("█"));
+}
+
+#[test]
+fn matrix_compares_candidate_prompts() {
+    let dir = TempDir::new().unwrap();
+    let rough = dir.path().join("rough.md");
+    let tidy = dir.path().join("tidy.md");
+    std::fs::write(&rough, "Can you make a dashboard?\n").unwrap();
+    std::fs::write(
+        &tidy,
+        "<task>\nBuild a sales dashboard for {{REGION}} with a revenue chart.\n</task>\n\n<output_format>\nReturn one React component.\n</output_format>\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .args(["matrix", rough.to_str().unwrap(), tidy.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("rough.md") && stdout.contains("tidy.md"));
+    assert!(stdout.contains("Quality score"));
+    assert!(stdout.contains("Output format"));
+
+    let output = copt()
+        .args([
+            "matrix",
+            rough.to_str().unwrap(),
+            tidy.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert!(json[1]["score"].as_u64() > json[0]["score"].as_u64());
+    assert_eq!(json[1]["variables"], 1);
+    assert!(json[0]["categories"]["explicitness"].as_u64().unwrap() > 0);
+
+    // One prompt isn't a comparison
+    let output = copt()
+        .args(["matrix", rough.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn serve_records_trends_and_posts_regressions() {
+    use std::io::{Read, Write};
+
+    let dir = TempDir::new().unwrap();
+    let data_dir = dir.path().join("data");
+    let prompts = dir.path().join("prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    let prompt = prompts.join("support.md");
+    std::fs::write(
+        &prompt,
+        "<task>\nSummarize the customer's ticket in three bullet points covering the problem, what they tried, and what they need next.\n</task>\n",
+    )
+    .unwrap();
+
+    let serve = |extra: &[&str]| {
+        let mut cmd = copt();
+        cmd.env("XDG_DATA_HOME", &data_dir)
+            .env_remove("COPT_WEBHOOK_URL")
+            .args(["serve", "--audit-dir", prompts.to_str().unwrap(), "--once"])
+            .args(extra);
+        cmd.output().unwrap()
+    };
+
+    let output = serve(&[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("no regressions"));
+
+    // A one-shot webhook that captures the request
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let webhook = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    std::fs::write(
+        &prompt,
+        "NEVER be vague!!! Can you maybe help with tickets?\n",
+    )
+    .unwrap();
+    let output = serve(&["--webhook", &url]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 regressed"), "{}", stdout);
+    assert!(stdout.contains("support.md"));
+
+    let request = webhook.join().unwrap();
+    assert!(request.starts_with("POST /hook"));
+    let body: serde_json::Value =
+        serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert!(body["text"].as_str().unwrap().contains("1 prompt in"));
+    let regression = &body["pass"]["regressions"][0];
+    assert!(regression["score"].as_u64() < regression["previous_score"].as_u64());
+
+    let trend = std::fs::read_to_string(data_dir.join("copt/audits.jsonl")).unwrap();
+    assert_eq!(trend.lines().count(), 2);
+}
+
+#[test]
+fn similar_finds_and_reuses_earlier_optimizations() {
+    use copt::cli::history::HistoryEntry;
+
+    let dir = TempDir::new().unwrap();
+    let data_dir = dir.path().join("data");
+    let saved = dir.path().join("optimized.md");
+    std::fs::write(&saved, "<task>\nSummarize the incident report.\n</task>\n").unwrap();
+    let earlier = "Summarize the incident report for the on-call engineer in three bullet points covering impact, root cause, and next step.";
+    let history = [
+        HistoryEntry::new(earlier, "bedrock", "sonnet", "", Some(&saved)),
+        HistoryEntry::new(
+            "Write a haiku about autumn leaves.",
+            "bedrock",
+            "sonnet",
+            "",
+            None,
+        ),
+    ]
+    .iter()
+    .map(|entry| serde_json::to_string(entry).unwrap() + "\n")
+    .collect::<String>();
+    std::fs::create_dir_all(data_dir.join("copt")).unwrap();
+    std::fs::write(data_dir.join("copt/history.jsonl"), history).unwrap();
+
+    let prompt = dir.path().join("prompt.md");
+    std::fs::write(&prompt, earlier.replace("next step", "owner")).unwrap();
+    let output = copt()
+        .env("XDG_DATA_HOME", &data_dir)
+        .args([
+            "similar",
+            "-f",
+            prompt.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["exact"], false);
+    assert_eq!(json[0]["saved"], true);
+
+    copt()
+        .env("XDG_DATA_HOME", &data_dir)
+        .args(["similar", "--reuse", "-f", prompt.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("<task>\nSummarize the incident report.\n</task>\n");
+
+    copt()
+        .env("XDG_DATA_HOME", &data_dir)
+        .args(["similar", "--reuse", "--min-similarity", "100", "-f"])
+        .arg(&prompt)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at least 100% similar"));
+}
+
+#[test]
+fn coverage_reports_checked_and_skipped_categories() {
+    let config = TempDir::new().unwrap();
+    std::fs::create_dir_all(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[rules]\ndisabled_categories = [\"HYG\"]\ndisabled = [\"STY003\"]\n",
+    )
+    .unwrap();
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--offline", "--analyze", "--format", "json"])
+        .arg("Can you think about the API design?")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let status = |category: &str| {
+        json["coverage"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["category"] == category)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(status("explicitness")["status"], "flagged");
+    assert_eq!(status("style")["status"], "passed");
+    assert_eq!(status("style")["disabled_rules"][0], "STY003");
+    assert_eq!(status("hygiene")["status"], "disabled");
+    assert_eq!(status("language")["status"], "opt_in");
+
+    copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--offline", "--analyze", "--coverage", "--no-pager"])
+        .arg("Can you think about the API design?")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("categories checked"))
+        .stdout(predicate::str::contains("skipped: disabled"));
+}
+
+#[test]
+fn severity_config_rejects_an_unknown_color() {
+    let config = TempDir::new().unwrap();
+    std::fs::create_dir_all(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[severity.error]\ncolor = \"magenta\"\n\n[severity.warning]\ncolor = \"mauve\"\n",
+    )
+    .unwrap();
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--offline", "--analyze", "--format", "json"])
+        .arg("Can you think about the API design?")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "config_invalid");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("severity.warning.color: 'mauve' is not a color"));
+}
+
+#[test]
+fn config_validate_reports_keys_lines_and_allowed_values() {
+    let config = TempDir::new().unwrap();
+    std::fs::create_dir_all(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[output]\ncolour = false\nformat = \"yaml\"\n",
+    )
+    .unwrap();
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["config", "validate", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["errors"], 1);
+    assert_eq!(json["warnings"], 1);
+    assert_eq!(json["diagnostics"][0]["key"], "output.colour");
+    assert_eq!(json["diagnostics"][1]["key"], "output.format");
+    assert_eq!(json["diagnostics"][1]["line"], 3);
+    assert_eq!(json["diagnostics"][1]["allowed"][1], "json");
+
+    // Any other command stops on the same error instead of using the default
+    copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--offline", "--analyze"])
+        .arg("Summarize the report.")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("config.toml:3:1: output.format"))
+        .stderr(predicate::str::contains("Did you mean 'color'?"));
+}
+
+#[test]
+fn rules_list_shows_builtin_and_retired_ids() {
+    let output = copt()
+        .args(["rules", "list", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let rules: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hygiene = rules
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["id"] == "HYG002")
+        .unwrap();
+    assert_eq!(hygiene["category"], "hygiene");
+    assert!(hygiene["docs_url"]
+        .as_str()
+        .unwrap()
+        .ends_with("#hyg002--trailing-whitespace"));
+
+    let output = copt()
+        .args(["rules", "list", "--deprecated", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let retired: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    for row in retired.as_array().unwrap() {
+        assert!(!row["current"].as_array().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn group_by_line_lists_issues_under_their_lines() {
+    let output = copt()
+        .env("NO_COLOR", "1")
+        .args(["--offline", "--analyze", "--no-pager", "--group-by", "line"])
+        .args(["--sort", "severity"])
+        .arg("Summarize the report.\nCan you think about the API design?")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line_2 = stdout.find("Line 2 (").expect(&stdout);
+    assert!(stdout[line_2..].contains("EXP003"), "{}", stdout);
+    assert!(!stdout.contains("Explicitness ("), "{}", stdout);
+}
+
+#[test]
+fn forced_hyperlinks_link_rule_ids_to_their_docs() {
+    let prompt = "Can you think about the API design?";
+    copt()
+        .env("FORCE_HYPERLINK", "1")
+        .args(["--offline", "--analyze", "--no-pager", prompt])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\x1b]8;;https://platform.claude.com/docs/en/build-with-claude/prompt-engineering/claude-4-best-practices#",
+        ));
+
+    // Off by default when stdout isn't a terminal
+    copt()
+        .args(["--offline", "--analyze", "--no-pager", prompt])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b]8;;").not());
+}
+
+#[test]
+fn progress_events_are_json_lines_on_stderr() {
+    let output = copt()
+        .args([
+            "--offline",
+            "--no-save",
+            "--progress-events",
+            "Can you think about the API design?",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let names: Vec<String> = events
+        .iter()
+        .map(|e| format!("{} {}", e["event"], e["phase"]).replace('"', ""))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "item_started null",
+            "phase_started analyze",
+            "phase_finished analyze",
+            "phase_started optimize",
+            "phase_finished optimize",
+            "phase_started save",
+            "phase_finished save",
+            "finished null",
+        ]
+    );
+    let last = events.last().unwrap();
+    assert_eq!(last["status"], "ok");
+    assert_eq!(last["percent"], 100);
+
+    // Without the flag, stderr carries no events
+    copt()
+        .args([
+            "--offline",
+            "--no-save",
+            "Can you think about the API design?",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("\"event\"").not());
+}
+
+#[test]
+fn clean_prompts_report_strengths_instead_of_a_rewrite() {
+    let prompt =
+        "Summarize the attached incident report in three bullet points for the on-call engineer.";
+    let output = copt()
+        .args(["--format", "json", "--no-save", prompt])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["optimized"], prompt);
+    let strengths = json["strengths"].as_array().unwrap();
+    assert!(strengths.iter().any(|s| s["category"] == "hygiene"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--still-optimize"));
+
+    let output = copt()
+        .args(["--format", "json", "--no-save", "--still-optimize", prompt])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_ne!(json["optimized"], prompt);
+}
+
+#[test]
+fn conservative_level_skips_the_llm_when_only_info_issues_remain() {
+    // Trailing whitespace (HYG002) is an info-level issue the static fixes handle
+    let prompt = "Summarize the attached incident report in three bullet points for the on-call engineer.   \nKeep it short.";
+    let run = |level: &str| {
+        let output = copt()
+            .args([
+                "--format",
+                "json",
+                "--no-save",
+                "--force",
+                "--level",
+                level,
+                prompt,
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["optimized"].as_str().unwrap().to_string()
+    };
+    assert_eq!(
+        run("conservative"),
+        "Summarize the attached incident report in three bullet points for the on-call engineer.\nKeep it short."
+    );
+    assert!(run("aggressive").contains("<instructions>"));
+
+    let output = copt()
+        .args(["--level", "tone=aggressive", prompt])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a rule category"));
+}
+
+#[test]
+fn generate_tests_adds_queries_for_system_prompts_only() {
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--generate-tests",
+            "3",
+            "You are a support agent for Acme. Help customers with billing.",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let queries = json["test_queries"].as_array().unwrap();
+    assert_eq!(queries.len(), 3);
+    assert_eq!(queries[2]["scenario"], "out_of_scope");
+
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--generate-tests",
+            "3",
+            "Could you summarize this report?",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["test_queries"].as_array().unwrap().len(), 0);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("doesn't look like a system prompt"));
+}
+
+#[test]
+fn emit_todo_annotates_the_prompt_without_rewriting() {
+    let dir = TempDir::new().unwrap();
+    let prompt = dir.path().join("prompt.md");
+    std::fs::write(
+        &prompt,
+        "Research the best API design.\n\nCan you list the options?\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .args(["-f", prompt.to_str().unwrap(), "--emit", "todo"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(
+        "\n<!-- TODO(copt EXP003): Use direct commands instead -->\nCan you list the options?\n"
+    ));
+    // The mock would have wrapped the prompt in <instructions>
+    assert!(!stdout.contains("<instructions>"));
+    // Analysis only: nothing was optimized or saved
+    assert!(!dir.path().join("copt-output").exists());
+}
+
+#[test]
+fn bundle_docs_appends_manifest_and_warns_on_missing_paths() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("alaska-docs")).unwrap();
+    std::fs::write(dir.path().join("alaska-docs/itinerary.md"), "Day 1").unwrap();
+
+    let output = copt()
+        .args([
+            "--format",
+            "json",
+            "--no-save",
+            "--bundle-docs",
+            "Plan the trip using the docs in alaska-docs/ and `budget.csv`.",
+        ])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let optimized = json["optimized"].as_str().unwrap();
+    assert!(optimized.ends_with("- alaska-docs/itinerary.md (5 B)\n</available_documents>"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("budget.csv (line 1) does not exist"));
+}
+
+#[test]
+fn analyze_flags_references_missing_from_the_project() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+    std::fs::create_dir(dir.path().join("docs")).unwrap();
+    std::fs::write(dir.path().join("docs/style.md"), "# Style").unwrap();
+
+    let output = copt()
+        .args([
+            "--analyze",
+            "--format",
+            "json",
+            "Follow docs/style.md, build it with Node.js, weigh the client/server pros/cons, \
+             and record results in `budget.csv`.",
+        ])
+        .current_dir(dir.path().join("docs"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let flagged: Vec<&str> = json["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|i| i["id"] == "EXP007")
+        .map(|i| i["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(flagged.len(), 1, "{:?}", flagged);
+    assert!(flagged[0].contains("`budget.csv`"));
+}
+
+#[test]
+fn offline_analysis_matches_the_full_run() {
+    let dir = TempDir::new().unwrap();
+    let prompt = dir.path().join("prompt.txt");
+    std::fs::write(
+        &prompt,
+        "Can you think about the API design?\nMake it good.\n",
+    )
+    .unwrap();
+
+    let analyze = |offline: bool| {
+        let mut cmd = copt();
+        if offline {
+            cmd.arg("--offline");
+        }
+        let output = cmd
+            .args(["--analyze", "--format", "json", "-f"])
+            .arg(&prompt)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let mut json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["stats"]["processing_time_ms"] = serde_json::Value::Null;
+        json
+    };
+
+    // --offline --analyze skips the async runtime; the report is the same
+    let fast = analyze(true);
+    assert_eq!(fast, analyze(false));
+    assert!(!fast["issues"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn models_lists_aliases_with_provider_ids_and_user_aliases() {
+    let config = TempDir::new().unwrap();
+    std::fs::create_dir(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[models.aliases]\nfast = \"haiku\"\n",
+    )
+    .unwrap();
+
+    let output = copt()
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--model", "fast", "models", "--check", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["provider"], "mock");
+    let haiku = &json["models"][2];
+    assert_eq!(haiku["id"], "us.anthropic.claude-haiku-4-5-20251001-v1:0");
+    assert_eq!(haiku["current"], true);
+    assert_eq!(haiku["availability"], "available");
+    assert_eq!(json["user_aliases"][0]["alias"], "fast");
+}
+
+#[test]
+fn mistyped_model_is_rejected_with_a_suggestion() {
+    let output = copt()
+        .args(["--model", "sonet", "--no-save", "Summarize the report."])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown model 'sonet'. Did you mean 'sonnet'?"));
+}
+
+#[test]
+fn json_errors_carry_a_stable_code() {
+    let output = copt()
+        .args([
+            "--model",
+            "sonet",
+            "--format",
+            "json",
+            "--no-save",
+            "Summarize the report.",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "config_invalid");
+    assert_eq!(json["error"]["retryable"], false);
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Unknown model 'sonet'"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown model 'sonet'"));
+}
+
+#[test]
+fn failed_llm_call_falls_back_to_static_optimization() {
+    let config = TempDir::new().unwrap();
+    std::fs::create_dir(config.path().join("copt")).unwrap();
+    // Nothing listens on the discard port, so the request fails to connect
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[anthropic]\nbase_url = \"http://127.0.0.1:9\"\n",
+    )
+    .unwrap();
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("copt")
+        .env("XDG_CONFIG_HOME", config.path())
+        .env("XDG_DATA_HOME", std::env::temp_dir().join("copt-e2e-data"))
+        .env("COPT_LANG", "en")
+        .args([
+            "--provider",
+            "anthropic",
+            "--no-suggest",
+            "--no-save",
+            "--skip-connectivity-check",
+            "--format",
+            "json",
+            "Write a summary of the report.\u{200B}",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["stats"]["degraded"]["code"], "provider_network");
+    assert_eq!(json["stats"]["degraded"]["retryable"], true);
+    assert!(!json["issues"].as_array().unwrap().is_empty());
+    assert!(!json["optimized"].as_str().unwrap().contains('\u{200B}'));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("LLM optimization failed"));
+}
+
+#[test]
+#[cfg(not(all(feature = "bedrock", feature = "tui")))]
+fn minimal_builds_name_the_missing_feature() {
+    let run = |args: &[&str]| {
+        let output = assert_cmd::cargo::cargo_bin_cmd!("copt")
+            .env("COPT_LANG", "en")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    if !cfg!(feature = "bedrock") {
+        let stderr = run(&["--provider", "bedrock", "--no-save", "Summarize this."]);
+        assert!(stderr.contains("--provider bedrock needs the `bedrock` feature"));
+    }
+    if !cfg!(feature = "tui") {
+        let stderr = run(&["-i", "Summarize this."]);
+        assert!(stderr.contains("needs the `tui` feature"));
+    }
+}
+
+#[test]
+fn bedrock_switches_to_a_region_that_offers_the_profile() {
+    let config = TempDir::new().unwrap();
+    let output = assert_cmd::cargo::cargo_bin_cmd!("copt")
+        .env("XDG_CONFIG_HOME", config.path())
+        .env("COPT_LANG", "en")
+        .args(["--provider", "bedrock", "--region", "eu-west-1"])
+        .args(["models", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["region"], "us-east-1");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("isn't offered in eu-west-1; using us-east-1 instead"));
+
+    std::fs::create_dir(config.path().join("copt")).unwrap();
+    std::fs::write(
+        config.path().join("copt/config.toml"),
+        "[bedrock]\nregion_failover = false\n",
+    )
+    .unwrap();
+    let output = assert_cmd::cargo::cargo_bin_cmd!("copt")
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["--provider", "bedrock", "--region", "eu-west-1", "models"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Regions that offer it: us-east-1, us-east-2, us-west-2, us-west-1"));
+}
+
+#[test]
+fn inference_profile_arn_is_recorded_as_model_id() {
+    let arn = "arn:aws:bedrock:us-east-1:123456789012:application-inference-profile/a1b2c3d4e5f6";
+    let output = copt()
+        .args([
+            "--model",
+            arn,
+            "--format",
+            "json",
+            "--no-save",
+            "Summarize the report.",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["stats"]["model"], arn);
+    assert_eq!(json["stats"]["model_id"], arn);
+}